@@ -9,10 +9,14 @@ pub struct AccessToken {
     pub sub: String,
     pub iss: String,
     pub exp: u64,
+    /// The session the token was issued for, requests are refused once it is revoked. Tokens
+    /// issued before sessions were recorded have none and stay valid until they expire.
+    #[serde(default)]
+    pub sid: Option<Uuid>,
 }
 
 impl AccessToken {
-    pub fn new(user_id: &Uuid, issuer: String, expiry_in_minutes: &u64) -> Self {
+    pub fn new(user_id: &Uuid, session_id: &Uuid, issuer: String, expiry_in_minutes: &u64) -> Self {
         let mut timer = SystemTime::now();
         timer += Duration::from_secs(expiry_in_minutes * 60);
         let exp = timer.duration_since(UNIX_EPOCH).unwrap().as_secs();
@@ -21,6 +25,7 @@ impl AccessToken {
             iss: issuer,
             sub: user_id.hyphenated().to_string(),
             exp,
+            sid: Some(*session_id),
         }
     }
 
@@ -0,0 +1,30 @@
+use errors::BigNeonError;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+
+/// Signed into the link emailed to confirm an address. It only verifies the address it was
+/// sent to, so a link is useless once the user changes their email.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EmailVerificationToken {
+    pub sub: String,
+    pub email: String,
+    pub exp: u64,
+}
+
+impl EmailVerificationToken {
+    pub fn new(user_id: &Uuid, email: String, expiry_in_hours: u64) -> Self {
+        let mut timer = SystemTime::now();
+        timer += Duration::from_secs(expiry_in_hours * 60 * 60);
+        let exp = timer.duration_since(UNIX_EPOCH).unwrap().as_secs();
+
+        EmailVerificationToken {
+            sub: user_id.hyphenated().to_string(),
+            email,
+            exp,
+        }
+    }
+
+    pub fn get_id(&self) -> Result<Uuid, BigNeonError> {
+        Ok(Uuid::parse_str(&self.sub)?)
+    }
+}
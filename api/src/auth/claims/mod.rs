@@ -1,5 +1,7 @@
 pub use self::access_token::AccessToken;
+pub use self::email_verification_token::EmailVerificationToken;
 pub use self::refresh_token::RefreshToken;
 
 pub mod access_token;
+pub mod email_verification_token;
 pub mod refresh_token;
@@ -7,10 +7,16 @@ pub struct RefreshToken {
     pub sub: String,
     pub iss: String,
     pub issued: u64,
+    /// The session the token belongs to, tokens issued before sessions were recorded have none
+    #[serde(default)]
+    pub sid: Option<Uuid>,
+    /// Identifies the token within its session, only the session's latest token is accepted
+    #[serde(default)]
+    pub jti: Option<Uuid>,
 }
 
 impl RefreshToken {
-    pub fn new(user_id: &Uuid, issuer: String) -> Self {
+    pub fn new(user_id: &Uuid, session_id: &Uuid, token_id: &Uuid, issuer: String) -> Self {
         let issued = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap()
@@ -20,6 +26,8 @@ impl RefreshToken {
             iss: issuer,
             sub: user_id.hyphenated().to_string(),
             issued,
+            sid: Some(*session_id),
+            jti: Some(*token_id),
         }
     }
 
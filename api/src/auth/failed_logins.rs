@@ -0,0 +1,84 @@
+use bigneon_db::models::User;
+use chrono::{Duration, Utc};
+use communications::mailers;
+use config::Environment;
+use db::Connection;
+use diesel::connection::TransactionManager;
+use diesel::Connection as DieselConnection;
+use errors::*;
+use log::Level::Warn;
+use server::AppState;
+
+/// Locks the user out for a while after too many failed attempts, letting them know by email.
+/// Wrong passwords and wrong two factor codes count towards the same limit.
+pub fn record_failed_login(
+    state: &AppState,
+    conn: &Connection,
+    user: &User,
+) -> Result<(), BigNeonError> {
+    let connection = conn.get();
+    let locked = user.record_failed_login(
+        state.config.max_failed_login_attempts,
+        Duration::minutes(state.config.login_lockout_minutes),
+        connection,
+    )?;
+    if locked {
+        jlog!(Warn, "User locked after repeated failed logins", {"id": user.id, "email": user.email.clone()});
+        mailers::user::account_locked_email(
+            &state.config,
+            user,
+            state.config.login_lockout_minutes,
+            connection,
+        )?;
+    }
+
+    // The failure response rolls back the request's transaction, the attempt must be kept
+    commit_before_error_response(state, conn)
+}
+
+/// Commits the request's transaction so far and begins a new one, so changes survive the
+/// rollback of an error response. Test connections are already inside a test transaction which
+/// is never committed, only a request transaction opened within it is.
+pub fn commit_before_error_response(
+    state: &AppState,
+    conn: &Connection,
+) -> Result<(), BigNeonError> {
+    let test_transaction_depth = match state.config.environment {
+        Environment::Test => 1,
+        _ => 0,
+    };
+    if conn.get().transaction_manager().get_transaction_depth() > test_transaction_depth {
+        conn.commit_transaction()?;
+        conn.begin_transaction()?;
+    }
+    Ok(())
+}
+
+/// Refuses sign in while the user is locked out after too many failed attempts
+pub fn requires_unlocked(user: &User) -> Result<(), BigNeonError> {
+    if user.is_locked() {
+        let retry_after = user
+            .locked_until
+            .map(|locked_until| (locked_until - Utc::now().naive_utc()).num_seconds())
+            .unwrap_or(0);
+        return Err(RateLimitError::new(
+            "Too many failed sign in attempts, please try again later".to_string(),
+            retry_after.max(1),
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Sign in without a password, e.g. through an identity provider or an emailed link, is refused
+/// for deactivated users as well as locked ones
+pub fn requires_sign_in_allowed(user: &User) -> Result<(), BigNeonError> {
+    if !user.active {
+        return Err(AuthError::new(
+            AuthErrorType::Unauthorized,
+            "User account is disabled".to_string(),
+        )
+        .into());
+    }
+    requires_unlocked(user)
+}
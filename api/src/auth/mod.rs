@@ -1,5 +1,6 @@
 pub use self::token_response::TokenResponse;
 
 pub mod claims;
+pub mod failed_logins;
 pub mod token_response;
 pub mod user;
@@ -1,13 +1,15 @@
+use actix_web::http::header;
 use actix_web::Error;
 use actix_web::HttpRequest;
 use actix_web::HttpResponse;
 use actix_web::Responder;
 use auth::{claims::AccessToken, claims::RefreshToken};
-use bigneon_db::models::User;
+use bigneon_db::models::{User, UserSession};
+use diesel::PgConnection;
 use errors::BigNeonError;
 use jwt::{encode, Header};
 use serde_json;
-use uuid::Uuid;
+use server::AppState;
 
 #[derive(Serialize, Deserialize)]
 pub struct TokenResponse {
@@ -35,20 +37,55 @@ impl TokenResponse {
         }
     }
 
+    /// Signs the user in on the requesting device, starting a new session
     pub fn create_from_user(
+        request: &HttpRequest<AppState>,
+        user: &User,
+        conn: &PgConnection,
+    ) -> Result<Self, BigNeonError> {
+        let user_agent = request
+            .headers()
+            .get(header::USER_AGENT)
+            .and_then(|h| h.to_str().ok())
+            .map(|h| h.to_string());
+        let ip_address = request.connection_info().remote().map(|i| i.to_string());
+        let session = UserSession::create(user.id, user_agent, ip_address, conn)?;
+
+        let config = &request.state().config;
+        TokenResponse::create_for_session(
+            &config.token_secret,
+            &config.token_issuer,
+            &config.jwt_expiry_time,
+            &session,
+        )
+    }
+
+    /// Tokens for the session, the refresh token is only valid until the session is next
+    /// refreshed
+    pub fn create_for_session(
         token_secret: &str,
         token_issuer: &str,
-        expiry: &u64,
-        user: &User,
+        expiry_time_in_minutes: &u64,
+        session: &UserSession,
     ) -> Result<Self, BigNeonError> {
-        let access_token_claims = AccessToken::new(&user.id, token_issuer.to_string(), expiry);
+        let access_token_claims = AccessToken::new(
+            &session.user_id,
+            &session.id,
+            token_issuer.to_string(),
+            expiry_time_in_minutes,
+        );
         let access_token = encode(
             &Header::default(),
             &access_token_claims,
             token_secret.as_bytes(),
         )?;
 
-        let refresh_token_claims = RefreshToken::new(&user.id, token_issuer.to_string());
+        let refresh_token_claims = RefreshToken::new(
+            &session.user_id,
+            &session.id,
+            &session.refresh_token_id,
+            token_issuer.to_string(),
+        );
         let refresh_token = encode(
             &Header::default(),
             &refresh_token_claims,
@@ -60,25 +97,4 @@ impl TokenResponse {
             refresh_token,
         })
     }
-
-    pub fn create_from_refresh_token(
-        token_secret: &str,
-        token_issuer: &str,
-        expiry_time_in_minutes: &u64,
-        user_id: &Uuid,
-        signed_refresh_token: &str,
-    ) -> Result<Self, BigNeonError> {
-        let access_token_claims =
-            AccessToken::new(&user_id, token_issuer.to_string(), expiry_time_in_minutes);
-        let access_token = encode(
-            &Header::default(),
-            &access_token_claims,
-            token_secret.as_bytes(),
-        )?;
-
-        Ok(TokenResponse {
-            access_token,
-            refresh_token: String::from(signed_refresh_token),
-        })
-    }
 }
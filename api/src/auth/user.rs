@@ -1,7 +1,12 @@
 use actix_web::{HttpRequest, Result};
+use auth::failed_logins::{record_failed_login, requires_unlocked};
 use bigneon_db::models::User as DbUser;
-use bigneon_db::models::{Organization, Scopes};
+use bigneon_db::models::{
+    Event, EventUser, Organization, OrganizationApiKey, OrganizationRole, Scopes,
+    TwoFactorAuthentication,
+};
 use bigneon_db::prelude::errors::EnumParseError;
+use db::Connection;
 use diesel::PgConnection;
 use errors::*;
 use log::Level::Warn;
@@ -11,6 +16,9 @@ use server::AppState;
 use std::collections::HashMap;
 use uuid::Uuid;
 
+/// Header carrying the authenticator app code for actions that require step-up verification
+pub const TWO_FACTOR_CODE_HEADER: &str = "X-Two-Factor-Code";
+
 #[derive(Clone, Debug)]
 pub struct User {
     pub user: DbUser,
@@ -18,6 +26,11 @@ pub struct User {
     pub ip_address: Option<String>,
     pub uri: String,
     pub method: String,
+    /// Set when the request was authenticated with an organization API key rather than a
+    /// token, `user` is then the key's service account
+    pub api_key: Option<OrganizationApiKey>,
+    /// Sent in the `X-Two-Factor-Code` header to confirm a sensitive action
+    pub two_factor_code: Option<String>,
 }
 
 impl User {
@@ -33,9 +46,32 @@ impl User {
             ip_address: request.connection_info().remote().map(|i| i.to_string()),
             uri: request.uri().to_string(),
             method: request.method().to_string(),
+            api_key: None,
+            two_factor_code: request
+                .headers()
+                .get(TWO_FACTOR_CODE_HEADER)
+                .and_then(|h| h.to_str().ok())
+                .map(|h| h.to_string()),
         })
     }
 
+    /// A service account acting through an API key only has the scopes granted to the key
+    pub fn for_api_key(
+        user: DbUser,
+        api_key: OrganizationApiKey,
+        request: &HttpRequest<AppState>,
+    ) -> User {
+        User {
+            user,
+            global_scopes: Vec::new(),
+            ip_address: request.connection_info().remote().map(|i| i.to_string()),
+            uri: request.uri().to_string(),
+            method: request.method().to_string(),
+            api_key: Some(api_key),
+            two_factor_code: None,
+        }
+    }
+
     pub fn id(&self) -> Uuid {
         self.user.id
     }
@@ -48,6 +84,7 @@ impl User {
         &self,
         scope: Scopes,
         organization: Option<&Organization>,
+        event: Option<&Event>,
         connection: Option<&PgConnection>,
         log_on_failure: bool,
     ) -> Result<bool, BigNeonError> {
@@ -56,12 +93,34 @@ impl User {
         }
 
         let mut logging_data = HashMap::new();
-        if let (Some(organization), Some(connection)) = (organization, connection) {
-            let organization_scopes = organization.get_scopes_for_user(&self.user, connection)?;
+        if let Some(ref api_key) = self.api_key {
+            logging_data.insert("api_key_id", json!(api_key.id));
+            logging_data.insert("api_key_scopes", json!(api_key.scopes));
+            if let Some(organization) = organization {
+                if api_key.allows(scope, organization.id, event.map(|e| e.id)) {
+                    return Ok(true);
+                }
+            }
+        } else if let (Some(organization), Some(connection)) = (organization, connection) {
+            let mut organization_scopes =
+                organization.get_scopes_for_user(&self.user, connection)?;
             logging_data.insert("organization_scopes", json!(organization_scopes));
             logging_data.insert("organization_id", json!(organization.id));
+            // Roles given on the event itself, e.g. to an outside promoter
+            if let Some(event) = event.filter(|e| e.organization_id == organization.id) {
+                let event_scopes = EventUser::get_scopes_for_user(event.id, self.id(), connection)?;
+                logging_data.insert("event_scopes", json!(event_scopes));
+                logging_data.insert("event_id", json!(event.id));
+                organization_scopes.extend(event_scopes);
+            }
             if organization_scopes.contains(&scope) {
-                return Ok(true);
+                if !organization.require_two_factor
+                    || TwoFactorAuthentication::find_enabled_for_user(self.id(), connection)?
+                        .is_some()
+                {
+                    return Ok(true);
+                }
+                logging_data.insert("two_factor_required", json!(true));
             }
         }
 
@@ -75,7 +134,7 @@ impl User {
     }
 
     pub fn has_scope(&self, scope: Scopes) -> Result<bool, BigNeonError> {
-        self.check_scope_access(scope, None, None, false)
+        self.check_scope_access(scope, None, None, None, false)
     }
 
     pub fn has_scope_for_organization(
@@ -84,7 +143,7 @@ impl User {
         organization: &Organization,
         conn: &PgConnection,
     ) -> Result<bool, BigNeonError> {
-        self.check_scope_access(scope, Some(organization), Some(conn), false)
+        self.check_scope_access(scope, Some(organization), None, Some(conn), false)
     }
 
     pub fn log_unauthorized_access_attempt(&self, mut logging_data: HashMap<&'static str, Value>) {
@@ -97,7 +156,7 @@ impl User {
     }
 
     pub fn requires_scope(&self, scope: Scopes) -> Result<(), BigNeonError> {
-        if self.check_scope_access(scope, None, None, true)? {
+        if self.check_scope_access(scope, None, None, None, true)? {
             return Ok(());
         }
         Err(AuthError::new(
@@ -113,7 +172,25 @@ impl User {
         organization: &Organization,
         conn: &PgConnection,
     ) -> Result<(), BigNeonError> {
-        if self.check_scope_access(scope, Some(organization), Some(conn), true)? {
+        if self.check_scope_access(scope, Some(organization), None, Some(conn), true)? {
+            return Ok(());
+        }
+        Err(AuthError::new(
+            AuthErrorType::Unauthorized,
+            "User does not have the required permissions".to_string(),
+        )
+        .into())
+    }
+
+    /// As `requires_scope_for_organization`, but also allows API keys limited to `event`
+    pub fn requires_scope_for_organization_event(
+        &self,
+        scope: Scopes,
+        organization: &Organization,
+        event: &Event,
+        conn: &PgConnection,
+    ) -> Result<(), BigNeonError> {
+        if self.check_scope_access(scope, Some(organization), Some(event), Some(conn), true)? {
             return Ok(());
         }
         Err(AuthError::new(
@@ -122,4 +199,76 @@ impl User {
         )
         .into())
     }
+
+    /// Giving someone an organization's custom roles needs `OrgUsers` and every scope the roles
+    /// grant, so no one can hand out more access than they have
+    pub fn requires_scopes_for_organization_roles(
+        &self,
+        organization_role_ids: &[Uuid],
+        organization: &Organization,
+        conn: &PgConnection,
+    ) -> Result<(), BigNeonError> {
+        if organization_role_ids.is_empty() {
+            return Ok(());
+        }
+        OrganizationRole::validate_ids_for_organization(
+            organization_role_ids,
+            organization.id,
+            conn,
+        )?;
+        self.requires_scope_for_organization(Scopes::OrgUsers, organization, conn)?;
+        let mut scopes: Vec<Scopes> = OrganizationRole::find_by_ids_for_organization(
+            organization_role_ids,
+            organization.id,
+            conn,
+        )?
+        .iter()
+        .flat_map(|r| r.get_scopes())
+        .collect();
+        scopes.sort();
+        scopes.dedup();
+        for scope in scopes {
+            self.requires_scope_for_organization(scope, organization, conn)?;
+        }
+        Ok(())
+    }
+
+    /// Step-up verification before a sensitive action. Users who have enabled two factor
+    /// authentication must send a current code (or a recovery code) with the request. Wrong codes
+    /// count as failed sign ins, so repeated guesses lock the user out.
+    ///
+    /// Requests made with an organization API key are exempt, the key belongs to a service
+    /// account with no second factor and is already limited to the scopes it was issued with.
+    pub fn requires_two_factor_verification(
+        &self,
+        state: &AppState,
+        conn: &Connection,
+    ) -> Result<(), BigNeonError> {
+        if self.api_key.is_some() {
+            return Ok(());
+        }
+        let connection = conn.get();
+        let two_factor_authentication =
+            match TwoFactorAuthentication::find_enabled_for_user(self.id(), connection)? {
+                Some(two_factor_authentication) => two_factor_authentication,
+                None => return Ok(()),
+            };
+        requires_unlocked(&self.user)?;
+        let message = match self.two_factor_code {
+            Some(ref code) => {
+                if two_factor_authentication.verify(
+                    code,
+                    &state.config.api_keys_encryption_key,
+                    connection,
+                )? {
+                    self.user.clear_failed_logins(connection)?;
+                    return Ok(());
+                }
+                record_failed_login(state, conn, &self.user)?;
+                "Two factor code incorrect"
+            }
+            None => "Two factor code required",
+        };
+        Err(AuthError::new(AuthErrorType::Unauthorized, message.to_string()).into())
+    }
 }
@@ -0,0 +1,36 @@
+use bigneon_db::models::{Campaign, CommunicationChannelType, Organization};
+use communications::{mailers, smsers};
+use config::Config;
+use errors::*;
+use utils::communication::Communication;
+use uuid::Uuid;
+
+/// Builds the campaign's message for its channel, test messages have no unsubscribe token
+pub fn campaign_communication(
+    config: &Config,
+    campaign: &Campaign,
+    organization: &Organization,
+    address: String,
+    unsubscribe_token: Option<Uuid>,
+) -> Result<Communication, BigNeonError> {
+    match campaign.channel {
+        CommunicationChannelType::Email => Ok(mailers::campaigns::campaign_email(
+            config,
+            campaign,
+            organization,
+            address,
+            unsubscribe_token,
+        )),
+        CommunicationChannelType::Sms => Ok(smsers::campaigns::campaign_sms(
+            config,
+            campaign,
+            organization,
+            address,
+            unsubscribe_token,
+        )),
+        CommunicationChannelType::Push => Err(ApplicationError::new(
+            "Campaigns cannot be sent by push notification".to_string(),
+        )
+        .into()),
+    }
+}
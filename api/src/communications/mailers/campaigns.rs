@@ -0,0 +1,45 @@
+use bigneon_db::models::{Campaign, Organization};
+use config::Config;
+use utils::communication::*;
+use uuid::Uuid;
+
+/// Builds a campaign email for one recipient. Test emails have no unsubscribe token and are
+/// marked as a test in the subject.
+pub fn campaign_email(
+    config: &Config,
+    campaign: &Campaign,
+    organization: &Organization,
+    email: String,
+    unsubscribe_token: Option<Uuid>,
+) -> Communication {
+    let source = CommAddress::from(config.communication_default_source_email.clone());
+    let destinations = CommAddress::from(email);
+    let subject = campaign.subject.clone().unwrap_or(campaign.name.clone());
+    let (title, unsubscribe_link) = match unsubscribe_token {
+        Some(token) => (
+            subject,
+            format!(
+                "{}/campaigns/unsubscribe?token={}",
+                config.front_end_url, token
+            ),
+        ),
+        None => (
+            format!("[Test] {}", subject),
+            format!("{}/campaigns/unsubscribe", config.front_end_url),
+        ),
+    };
+    let body = format!(
+        "{}\n\nYou are receiving this email from {}. To stop receiving these emails, unsubscribe here: {}",
+        campaign.body, organization.name, unsubscribe_link
+    );
+
+    Communication::new(
+        CommunicationType::Email,
+        title,
+        Some(body),
+        Some(source),
+        destinations,
+        None,
+        None,
+    )
+}
@@ -1,13 +1,15 @@
 use bigneon_db::models::enums::OrderItemTypes;
-use bigneon_db::models::DisplayOrder;
+use bigneon_db::models::{DisplayOrder, Event, Venue};
 use config::Config;
 use errors::*;
 use utils::communication::*;
+use utils::ical;
 
 pub fn purchase_completed(
     user_first_name: &String,
     user_email: String,
     display_order: DisplayOrder,
+    events: &[(Event, Option<Venue>)],
     config: &Config,
 ) -> Result<Communication, BigNeonError> {
     let source = CommAddress::from(config.communication_default_source_email.clone());
@@ -68,13 +70,23 @@ pub fn purchase_completed(
     );
 
     // TODO: Perhaps move this to an event subscription
-    Ok(Communication::new(
+    let mut communication = Communication::new(
         CommunicationType::EmailTemplate,
-        title,
+        title.clone(),
         None,
         Some(source),
         destinations,
         Some(template_id),
         Some(vec![template_data]),
-    ))
+    );
+
+    if events.iter().any(|(e, _)| e.event_start.is_some()) {
+        communication.add_attachment(CommAttachment::new(
+            "event.ics",
+            ical::ICAL_CONTENT_TYPE,
+            &ical::calendar(&title, events, config),
+        ));
+    }
+
+    Ok(communication)
 }
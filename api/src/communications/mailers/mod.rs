@@ -1,5 +1,7 @@
+pub mod campaigns;
 pub mod cart;
 pub mod orders;
 pub mod organization_invites;
+pub mod reports;
 pub mod tickets;
 pub mod user;
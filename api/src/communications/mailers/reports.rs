@@ -0,0 +1,50 @@
+use bigneon_db::models::{Organization, ReportSubscription};
+use config::Config;
+use diesel::pg::PgConnection;
+use errors::*;
+use utils::communication::*;
+use utils::report_export::RenderedReport;
+
+pub fn scheduled_report_email(
+    config: &Config,
+    user_email: String,
+    subscription: &ReportSubscription,
+    organization: &Organization,
+    report: &RenderedReport,
+    conn: &PgConnection,
+) -> Result<(), BigNeonError> {
+    let unsubscribe_link = format!(
+        "{}/reports/unsubscribe?token={}",
+        config.front_end_url, subscription.unsubscribe_token
+    );
+    let (period_start, period_end) = subscription.reporting_period();
+
+    let source = CommAddress::from(config.communication_default_source_email.clone());
+    let destinations = CommAddress::from(user_email);
+    let title = format!("BigNeon {} report", organization.name);
+    let body = format!(
+        "Your {} {} report for {} is attached, covering {} to {} UTC.\n\nTo stop receiving this report, unsubscribe here: {}",
+        subscription.frequency.to_string().to_lowercase(),
+        subscription.report_type,
+        organization.name,
+        period_start.format("%Y-%m-%d %H:%M"),
+        period_end.format("%Y-%m-%d %H:%M"),
+        unsubscribe_link
+    );
+
+    let mut communication = Communication::new(
+        CommunicationType::Email,
+        title,
+        Some(body),
+        Some(source),
+        destinations,
+        None,
+        None,
+    );
+    communication.add_attachment(CommAttachment::from_bytes(
+        &report.filename,
+        report.content_type,
+        &report.content,
+    ));
+    communication.queue(conn)
+}
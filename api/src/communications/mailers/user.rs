@@ -89,3 +89,99 @@ pub fn invite_user_email(config: &Config, user: &User) -> Communication {
         Some(vec![template_data]),
     )
 }
+
+pub fn account_locked_email(
+    config: &Config,
+    user: &User,
+    lockout_minutes: i64,
+    conn: &PgConnection,
+) -> Result<(), BigNeonError> {
+    let email = match user.email {
+        Some(ref email) => email.clone(),
+        None => return Ok(()),
+    };
+    let password_reset_link = format!("{}/password-reset", config.front_end_url);
+
+    let source = CommAddress::from(config.communication_default_source_email.clone());
+    let destinations = CommAddress::from(email);
+    let title = "Big Neon account locked".to_string();
+    let body = format!(
+        "There have been several unsuccessful attempts to sign in to your account, so signing in has been disabled for {} minutes.\n\nIf this was not you, we recommend resetting your password: {}",
+        lockout_minutes, password_reset_link
+    );
+    Communication::new(
+        CommunicationType::Email,
+        title,
+        Some(body),
+        Some(source),
+        destinations,
+        None,
+        None,
+    )
+    .queue(conn)
+}
+
+pub fn email_verification_email(
+    config: &Config,
+    user: &User,
+    token: &str,
+    conn: &PgConnection,
+) -> Result<(), BigNeonError> {
+    let email = match user.email {
+        Some(ref email) => email.clone(),
+        None => return Ok(()),
+    };
+    let email_verification_link = format!("{}/verify-email?token={}", config.front_end_url, token);
+
+    let source = CommAddress::from(config.communication_default_source_email.clone());
+    let destinations = CommAddress::from(email);
+    let title = "Big Neon email verification".to_string();
+    let body = format!(
+        "Please confirm this is your email address by following this link: {}",
+        email_verification_link
+    );
+    Communication::new(
+        CommunicationType::Email,
+        title,
+        Some(body),
+        Some(source),
+        destinations,
+        None,
+        None,
+    )
+    .queue(conn)
+}
+
+pub fn passwordless_login_email(
+    config: &Config,
+    user: &User,
+    conn: &PgConnection,
+) -> Result<(), BigNeonError> {
+    let email = match user.email {
+        Some(ref email) => email.clone(),
+        None => return Ok(()),
+    };
+    let login_link = format!(
+        "{}/login?login_token={}",
+        config.front_end_url,
+        user.login_token.expect("Login token is not set")
+    );
+
+    let source = CommAddress::from(config.communication_default_source_email.clone());
+    let destinations = CommAddress::from(email);
+    let title = "Sign in to Big Neon".to_string();
+    let body = format!(
+        "Follow this link to sign in, it can only be used once and expires in 15 minutes: {}\n\nIf you did not ask to sign in you can ignore this email.",
+        login_link
+    );
+    Communication::new(
+        CommunicationType::Email,
+        title,
+        Some(body),
+        Some(source),
+        destinations,
+        None,
+        None,
+    )
+    .queue(conn)
+}
@@ -1,2 +1,3 @@
+pub mod campaigns;
 pub mod mailers;
 pub mod smsers;
@@ -0,0 +1,33 @@
+use bigneon_db::models::{Campaign, Organization};
+use config::Config;
+use utils::communication::*;
+use uuid::Uuid;
+
+/// Builds a campaign text message for one recipient, test messages have no unsubscribe token
+pub fn campaign_sms(
+    config: &Config,
+    campaign: &Campaign,
+    organization: &Organization,
+    phone: String,
+    unsubscribe_token: Option<Uuid>,
+) -> Communication {
+    let source = CommAddress::from(config.communication_default_source_phone.clone());
+    let destinations = CommAddress::from(phone);
+    let body = match unsubscribe_token {
+        Some(token) => format!(
+            "{}: {} Unsubscribe: {}/campaigns/unsubscribe?token={}",
+            organization.name, campaign.body, config.front_end_url, token
+        ),
+        None => format!("[Test] {}: {}", organization.name, campaign.body),
+    };
+
+    Communication::new(
+        CommunicationType::Sms,
+        body,
+        None,
+        Some(source),
+        destinations,
+        None,
+        None,
+    )
+}
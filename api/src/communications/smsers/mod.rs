@@ -1 +1,3 @@
+pub mod campaigns;
 pub mod tickets;
+pub mod user;
@@ -0,0 +1,48 @@
+use config::Config;
+use diesel::pg::PgConnection;
+use errors::*;
+use utils::communication::CommAddress;
+use utils::communication::Communication;
+use utils::communication::CommunicationType;
+
+pub fn phone_verification_code(
+    config: &Config,
+    phone: String,
+    code: &str,
+    conn: &PgConnection,
+) -> Result<(), BigNeonError> {
+    let source = CommAddress::from(config.communication_default_source_phone.clone());
+    let destinations = CommAddress::from(phone);
+    let body = format!("Your {} verification code is {}", config.app_name, code);
+    Communication::new(
+        CommunicationType::Sms,
+        body,
+        None,
+        Some(source),
+        destinations,
+        None,
+        None,
+    )
+    .queue(conn)
+}
+
+pub fn passwordless_login_code(
+    config: &Config,
+    phone: String,
+    code: &str,
+    conn: &PgConnection,
+) -> Result<(), BigNeonError> {
+    let source = CommAddress::from(config.communication_default_source_phone.clone());
+    let destinations = CommAddress::from(phone);
+    let body = format!("Your {} sign in code is {}", config.app_name, code);
+    Communication::new(
+        CommunicationType::Sms,
+        body,
+        None,
+        Some(source),
+        destinations,
+        None,
+        None,
+    )
+    .queue(conn)
+}
@@ -1,6 +1,10 @@
+use bigneon_db::models::{APPLE_SITE, GOOGLE_SITE};
 use dotenv::dotenv;
+use serde_json;
 use std::env;
+use std::net::IpAddr;
 use tari_client::{HttpTariClient, TariClient, TariTestClient};
+use utils::identity_providers::JsonWebKeySet;
 
 #[derive(Clone, PartialEq)]
 pub enum Environment {
@@ -9,6 +13,150 @@ pub enum Environment {
     Production,
 }
 
+/// An OpenID Connect provider users can sign in with
+#[derive(Clone, Debug, Deserialize)]
+pub struct IdentityProviderConfig {
+    /// Name used in the sign in urls, e.g. `google`
+    pub name: String,
+    /// Recorded as the `site` of the external logins created through the provider
+    pub site: String,
+    pub issuer: String,
+    pub client_id: String,
+    pub jwks_url: Option<String>,
+    /// Keys to verify tokens with instead of fetching them from `jwks_url`
+    pub jwks: Option<JsonWebKeySet>,
+}
+
+/// What the requests counted towards a rate limit have in common
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitKey {
+    Ip,
+    User,
+    /// The account the request is aimed at, e.g. the email signed in with. Counted by the
+    /// controller as it is only known once the body has been read.
+    Target,
+}
+
+/// Where the requests counted towards rate limits are kept
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RateLimitStoreType {
+    /// Shared by every instance of the API
+    Database,
+    /// Kept by each instance of the API, limits are per instance
+    Memory,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct RateLimitConfig {
+    /// Identifies the limit in its counters, e.g. `auth_token`
+    pub name: String,
+    /// Route as it appears in `routing.rs`, e.g. `/redemption_codes/{code}`
+    pub path: String,
+    /// All methods are limited if not set
+    pub method: Option<String>,
+    pub key: RateLimitKey,
+    pub limit: i32,
+    pub period_in_seconds: i64,
+}
+
+impl RateLimitConfig {
+    fn new(
+        name: &str,
+        path: &str,
+        method: &str,
+        key: RateLimitKey,
+        limit: i32,
+        period_in_seconds: i64,
+    ) -> RateLimitConfig {
+        RateLimitConfig {
+            name: name.to_string(),
+            path: path.to_string(),
+            method: Some(method.to_string()),
+            key,
+            limit,
+            period_in_seconds,
+        }
+    }
+}
+
+fn default_rate_limits() -> Vec<RateLimitConfig> {
+    use self::RateLimitKey::*;
+    vec![
+        RateLimitConfig::new("auth_token", "/auth/token", "POST", Ip, 20, 60),
+        RateLimitConfig::new("auth_token", "/auth/token", "POST", Target, 10, 300),
+        RateLimitConfig::new("password_reset", "/password_reset", "POST", Ip, 10, 600),
+        RateLimitConfig::new("password_reset", "/password_reset", "POST", Target, 3, 3600),
+        RateLimitConfig::new("password_reset_use", "/password_reset", "PUT", Ip, 10, 600),
+        RateLimitConfig::new(
+            "passwordless_login",
+            "/passwordless_login",
+            "POST",
+            Ip,
+            10,
+            600,
+        ),
+        RateLimitConfig::new(
+            "passwordless_login",
+            "/passwordless_login",
+            "POST",
+            Target,
+            3,
+            600,
+        ),
+        RateLimitConfig::new(
+            "passwordless_login_use",
+            "/passwordless_login",
+            "PUT",
+            Ip,
+            20,
+            600,
+        ),
+        RateLimitConfig::new(
+            "redemption_code",
+            "/redemption_codes/{code}",
+            "GET",
+            Ip,
+            30,
+            60,
+        ),
+        RateLimitConfig::new(
+            "redemption_code",
+            "/redemption_codes/{code}",
+            "GET",
+            User,
+            20,
+            60,
+        ),
+        RateLimitConfig::new("cart_checkout", "/cart/checkout", "POST", Ip, 30, 60),
+        RateLimitConfig::new("cart_checkout", "/cart/checkout", "POST", User, 10, 60),
+        RateLimitConfig::new(
+            "email_verification",
+            "/email_verification",
+            "POST",
+            User,
+            5,
+            3600,
+        ),
+        RateLimitConfig::new(
+            "phone_verification",
+            "/phone_verification",
+            "POST",
+            User,
+            5,
+            3600,
+        ),
+        RateLimitConfig::new(
+            "phone_verification_use",
+            "/phone_verification",
+            "PUT",
+            Ip,
+            20,
+            600,
+        ),
+    ]
+}
+
 #[derive(Clone)]
 pub struct Config {
     pub allowed_origins: String,
@@ -27,6 +175,7 @@ pub struct Config {
     pub validate_ipns: bool,
     pub api_base_url: String,
     pub google_recaptcha_secret_key: Option<String>,
+    pub identity_providers: Vec<IdentityProviderConfig>,
     pub http_keep_alive: usize,
     pub block_external_comms: bool,
     pub primary_currency: String,
@@ -49,6 +198,12 @@ pub struct Config {
     pub twilio_api_key: String,
     pub api_keys_encryption_key: String,
     pub jwt_expiry_time: u64,
+    pub rate_limits: Vec<RateLimitConfig>,
+    pub rate_limit_store: RateLimitStoreType,
+    pub max_failed_login_attempts: i32,
+    pub login_lockout_minutes: i64,
+    /// Proxies whose `X-Forwarded-For` header is believed when finding a client's address
+    pub trusted_proxies: Vec<IpAddr>,
 }
 
 const ALLOWED_ORIGINS: &str = "ALLOWED_ORIGINS";
@@ -65,6 +220,10 @@ const GLOBEE_BASE_URL: &str = "GLOBEE_BASE_URL";
 const VALIDATE_IPNS: &str = "VALIDATE_IPNS";
 const API_BASE_URL: &str = "API_BASE_URL";
 const GOOGLE_RECAPTCHA_SECRET_KEY: &str = "GOOGLE_RECAPTCHA_SECRET_KEY";
+const GOOGLE_CLIENT_ID: &str = "GOOGLE_CLIENT_ID";
+const APPLE_CLIENT_ID: &str = "APPLE_CLIENT_ID";
+// JSON list of further OpenID Connect providers, in the form of `IdentityProviderConfig`
+const OIDC_PROVIDERS: &str = "OIDC_PROVIDERS";
 const PRIMARY_CURRENCY: &str = "PRIMARY_CURRENCY";
 const STRIPE_SECRET_KEY: &str = "STRIPE_SECRET_KEY";
 const TARI_URL: &str = "TARI_URL";
@@ -100,6 +259,14 @@ const API_KEYS_ENCRYPTION_KEY: &str = "API_KEYS_ENCRYPTION_KEY";
 
 const JWT_EXPIRY_TIME: &str = "JWT_EXPIRY_TIME";
 
+// JSON list of `RateLimitConfig`, replaces the default limits
+const RATE_LIMITS: &str = "RATE_LIMITS";
+// `database` (default) or `memory`
+const RATE_LIMIT_STORE: &str = "RATE_LIMIT_STORE";
+const MAX_FAILED_LOGIN_ATTEMPTS: &str = "MAX_FAILED_LOGIN_ATTEMPTS";
+const LOGIN_LOCKOUT_MINUTES: &str = "LOGIN_LOCKOUT_MINUTES";
+const TRUSTED_PROXIES: &str = "TRUSTED_PROXIES";
+
 impl Config {
     pub fn new(environment: Environment) -> Self {
         dotenv().ok();
@@ -172,6 +339,35 @@ impl Config {
             .expect(&format!("{} is not a valid boolean value", VALIDATE_IPNS));
         let google_recaptcha_secret_key = env::var(&GOOGLE_RECAPTCHA_SECRET_KEY).ok();
 
+        let mut identity_providers: Vec<IdentityProviderConfig> = env::var(&OIDC_PROVIDERS)
+            .map(|s| {
+                serde_json::from_str(&s).expect(&format!(
+                    "{} is not a valid list of providers",
+                    OIDC_PROVIDERS
+                ))
+            })
+            .unwrap_or_else(|_| Vec::new());
+        if let Ok(client_id) = env::var(&GOOGLE_CLIENT_ID) {
+            identity_providers.push(IdentityProviderConfig {
+                name: "google".to_string(),
+                site: GOOGLE_SITE.to_string(),
+                issuer: "https://accounts.google.com".to_string(),
+                client_id,
+                jwks_url: Some("https://www.googleapis.com/oauth2/v3/certs".to_string()),
+                jwks: None,
+            });
+        }
+        if let Ok(client_id) = env::var(&APPLE_CLIENT_ID) {
+            identity_providers.push(IdentityProviderConfig {
+                name: "apple".to_string(),
+                site: APPLE_SITE.to_string(),
+                issuer: "https://appleid.apple.com".to_string(),
+                client_id,
+                jwks_url: Some("https://appleid.apple.com/auth/keys".to_string()),
+                jwks: None,
+            });
+        }
+
         let communication_default_source_email = env::var(&COMMUNICATION_DEFAULT_SOURCE_EMAIL)
             .unwrap_or_else(|_| panic!("{} must be defined.", COMMUNICATION_DEFAULT_SOURCE_EMAIL));
         let communication_default_source_phone = env::var(&COMMUNICATION_DEFAULT_SOURCE_PHONE)
@@ -234,6 +430,53 @@ impl Config {
             .parse()
             .unwrap();
 
+        let rate_limits = env::var(&RATE_LIMITS)
+            .map(|s| {
+                serde_json::from_str(&s).expect(&format!(
+                    "{} is not a valid list of rate limits",
+                    RATE_LIMITS
+                ))
+            })
+            .unwrap_or_else(|_| default_rate_limits());
+
+        let rate_limit_store = match env::var(&RATE_LIMIT_STORE)
+            .unwrap_or_else(|_| "database".to_string())
+            .to_lowercase()
+            .as_str()
+        {
+            "database" => RateLimitStoreType::Database,
+            "memory" => RateLimitStoreType::Memory,
+            store => panic!(
+                "{} is not a valid rate limit store: {}",
+                RATE_LIMIT_STORE, store
+            ),
+        };
+
+        let max_failed_login_attempts = env::var(&MAX_FAILED_LOGIN_ATTEMPTS)
+            .unwrap_or("5".to_string())
+            .parse()
+            .unwrap();
+
+        let login_lockout_minutes = env::var(&LOGIN_LOCKOUT_MINUTES)
+            .unwrap_or("15".to_string())
+            .parse()
+            .unwrap();
+
+        let trusted_proxies = env::var(&TRUSTED_PROXIES)
+            .map(|s| {
+                s.split(',')
+                    .map(|proxy| proxy.trim())
+                    .filter(|proxy| !proxy.is_empty())
+                    .map(|proxy| {
+                        proxy.parse().expect(&format!(
+                            "{} contains an invalid IP address: {}",
+                            TRUSTED_PROXIES, proxy
+                        ))
+                    })
+                    .collect()
+            })
+            .unwrap_or_else(|_| Vec::new());
+
         Config {
             allowed_origins,
             app_name,
@@ -250,6 +493,7 @@ impl Config {
             validate_ipns,
             api_base_url,
             google_recaptcha_secret_key,
+            identity_providers,
             http_keep_alive,
             block_external_comms,
             primary_currency,
@@ -273,6 +517,11 @@ impl Config {
             twilio_account_id,
             api_keys_encryption_key,
             jwt_expiry_time,
+            rate_limits,
+            rate_limit_store,
+            max_failed_login_attempts,
+            login_lockout_minutes,
+            trusted_proxies,
         }
     }
 }
@@ -0,0 +1,206 @@
+use actix_web::{http::StatusCode, HttpResponse, Path, Query};
+use auth::user::User;
+use bigneon_db::models::*;
+use chrono::NaiveDateTime;
+use db::Connection;
+use diesel::PgConnection;
+use errors::*;
+use extractors::*;
+use helpers::application;
+use models::{AffiliateCommissionRulePathParameters, PathParameters, WebPayload};
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct CreateAffiliateRequest {
+    pub name: String,
+    pub code: Option<String>,
+    pub user_id: Option<Uuid>,
+    pub attribution_window_in_days: Option<i64>,
+}
+
+#[derive(Deserialize)]
+pub struct CreateCommissionRuleRequest {
+    pub ticket_type_id: Option<Uuid>,
+    pub commission_type: CommissionTypes,
+    #[serde(default)]
+    pub flat_in_cents: i64,
+    #[serde(default)]
+    pub rate_in_basis_points: i64,
+}
+
+#[derive(Deserialize)]
+pub struct RecordClickRequest {
+    pub code: String,
+}
+
+#[derive(Deserialize)]
+pub struct SalesQueryParameters {
+    pub start_utc: Option<NaiveDateTime>,
+    pub end_utc: Option<NaiveDateTime>,
+}
+
+#[derive(Serialize)]
+pub struct DisplayAffiliate {
+    #[serde(flatten)]
+    pub affiliate: Affiliate,
+    pub commission_rules: Vec<AffiliateCommissionRule>,
+}
+
+pub fn index(
+    (connection, path, query_parameters, user): (
+        Connection,
+        Path<PathParameters>,
+        Query<PagingParameters>,
+        User,
+    ),
+) -> Result<WebPayload<Affiliate>, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgRead, &organization, connection)?;
+    //TODO refactor query using paging parameters
+    let affiliates = Affiliate::find_for_organization(organization.id, connection)?;
+
+    Ok(WebPayload::new(
+        StatusCode::OK,
+        Payload::from_data(
+            affiliates,
+            query_parameters.page(),
+            query_parameters.limit(),
+        ),
+    ))
+}
+
+pub fn create(
+    (connection, path, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<CreateAffiliateRequest>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgWrite, &organization, connection)?;
+
+    let json = json.into_inner();
+    let mut affiliate = Affiliate::create(organization.id, &json.name, json.code, json.user_id);
+    if let Some(attribution_window_in_days) = json.attribution_window_in_days {
+        affiliate.attribution_window_in_days = attribution_window_in_days;
+    }
+    let affiliate = affiliate.commit(connection)?;
+    Ok(HttpResponse::Created().json(&affiliate))
+}
+
+pub fn show(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let affiliate = find_with_scope(path.id, Scopes::OrgRead, &user, connection)?;
+    let commission_rules = affiliate.commission_rules(connection)?;
+    Ok(HttpResponse::Ok().json(&DisplayAffiliate {
+        affiliate,
+        commission_rules,
+    }))
+}
+
+pub fn update(
+    (connection, path, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<AffiliateEditableAttributes>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let affiliate = find_with_scope(path.id, Scopes::OrgWrite, &user, connection)?;
+    let affiliate = affiliate.update(json.into_inner(), connection)?;
+    Ok(HttpResponse::Ok().json(&affiliate))
+}
+
+pub fn add_commission_rule(
+    (connection, path, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<CreateCommissionRuleRequest>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let affiliate = find_with_scope(path.id, Scopes::OrgWrite, &user, connection)?;
+    let commission_rule = AffiliateCommissionRule::create(
+        affiliate.id,
+        json.ticket_type_id,
+        json.commission_type,
+        json.flat_in_cents,
+        json.rate_in_basis_points,
+    )
+    .commit(connection)?;
+    Ok(HttpResponse::Created().json(&commission_rule))
+}
+
+pub fn destroy_commission_rule(
+    (connection, path, user): (
+        Connection,
+        Path<AffiliateCommissionRulePathParameters>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let affiliate = find_with_scope(path.id, Scopes::OrgWrite, &user, connection)?;
+    let commission_rule = AffiliateCommissionRule::find(path.commission_rule_id, connection)?;
+    if commission_rule.affiliate_id != affiliate.id {
+        return application::not_found();
+    }
+    commission_rule.destroy(connection)?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Records a visit through an affiliate's tracking link, no login is required. The returned
+/// click id is sent as part of the cart's attribution.
+pub fn record_click(
+    (connection, json): (Connection, Json<RecordClickRequest>),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let affiliate = Affiliate::find_by_code(&json.code, connection)?;
+    let click = affiliate.record_click(connection)?;
+    Ok(HttpResponse::Created().json(&click))
+}
+
+/// Sales attributed to the affiliate, visible to the affiliate's own user as well as to
+/// organization users who can view reports
+pub fn sales(
+    (connection, path, query, user): (
+        Connection,
+        Path<PathParameters>,
+        Query<SalesQueryParameters>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let affiliate = Affiliate::find(path.id, connection)?;
+    if affiliate.user_id != Some(user.id()) {
+        let organization = affiliate.organization(connection)?;
+        user.requires_scope_for_organization(Scopes::OrgReports, &organization, connection)?;
+    }
+
+    let result = Report::affiliate_commission_report(
+        affiliate.organization_id,
+        Some(affiliate.id),
+        query.start_utc,
+        query.end_utc,
+        connection,
+    )?;
+    Ok(HttpResponse::Ok().json(result))
+}
+
+fn find_with_scope(
+    id: Uuid,
+    scope: Scopes,
+    user: &User,
+    connection: &PgConnection,
+) -> Result<Affiliate, BigNeonError> {
+    let affiliate = Affiliate::find(id, connection)?;
+    let organization = affiliate.organization(connection)?;
+    user.requires_scope_for_organization(scope, &organization, connection)?;
+    Ok(affiliate)
+}
@@ -1,12 +1,15 @@
 use actix_web::{HttpRequest, HttpResponse, State};
+use auth::failed_logins::{commit_before_error_response, record_failed_login, requires_unlocked};
 use auth::{claims::RefreshToken, TokenResponse};
-use bigneon_db::models::{deserialize_unless_blank, User};
+use bigneon_db::models::{deserialize_unless_blank, TwoFactorAuthentication, User, UserSession};
+use bigneon_db::utils::errors::Optional;
 use db::Connection;
 use errors::*;
 use extractors::*;
 use helpers::application;
 use jwt::{decode, Validation};
-use log::Level::Info;
+use log::Level::{Info, Warn};
+use middleware::RateLimiter;
 use server::AppState;
 use std::collections::HashMap;
 use utils::google_recaptcha;
@@ -18,6 +21,9 @@ pub struct LoginRequest {
     #[serde(rename = "g-recaptcha-response")]
     #[serde(default, deserialize_with = "deserialize_unless_blank")]
     captcha_response: Option<String>,
+    /// Required once the user has enabled two factor authentication
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    two_factor_code: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -31,8 +37,14 @@ impl LoginRequest {
             email: String::from(email),
             password: String::from(password),
             captcha_response: None,
+            two_factor_code: None,
         }
     }
+
+    pub fn with_two_factor_code(mut self, two_factor_code: &str) -> Self {
+        self.two_factor_code = Some(two_factor_code.to_string());
+        self
+    }
 }
 
 impl RefreshRequest {
@@ -82,10 +94,14 @@ pub fn token(
         }
     }
 
+    RateLimiter::check_target(&http_request, &login_request.email)?;
+
     // Generic messaging to prevent exposing user is member of system
     let login_failure_messaging = "Email or password incorrect";
 
-    let user = match User::find_by_email(&login_request.email, connection.get()) {
+    let conn = connection;
+    let connection = conn.get();
+    let user = match User::find_by_email(&login_request.email, connection) {
         Ok(u) => u,
         Err(_e) => {
             return application::unauthorized_with_message(
@@ -96,7 +112,10 @@ pub fn token(
         }
     };
 
+    requires_unlocked(&user)?;
+
     if !user.check_password(&login_request.password) {
+        record_failed_login(state, &conn, &user)?;
         return application::unauthorized_with_message(
             login_failure_messaging,
             None,
@@ -104,18 +123,41 @@ pub fn token(
         );
     }
 
+    if let Some(two_factor_authentication) =
+        TwoFactorAuthentication::find_enabled_for_user(user.id, connection)?
+    {
+        let verified = match login_request.two_factor_code {
+            Some(ref code) => two_factor_authentication.verify(
+                code,
+                &state.config.api_keys_encryption_key,
+                connection,
+            )?,
+            None => {
+                return application::unauthorized_with_message(
+                    "Two factor code required",
+                    None,
+                    Some(login_log_data),
+                );
+            }
+        };
+        if !verified {
+            record_failed_login(state, &conn, &user)?;
+            return application::unauthorized_with_message(
+                "Two factor code incorrect",
+                None,
+                Some(login_log_data),
+            );
+        }
+    }
+
+    user.clear_failed_logins(connection)?;
     jlog!(Info, "User logged in via email and password", {"id": user.id, "email": user.email.clone()});
-    let response = TokenResponse::create_from_user(
-        &state.config.token_secret,
-        &state.config.token_issuer,
-        &state.config.jwt_expiry_time,
-        &user,
-    )?;
+    let response = TokenResponse::create_from_user(&http_request, &user, connection)?;
     Ok(response)
 }
 
 pub fn token_refresh(
-    (state, connection, refresh_request): (State<AppState>, Connection, Json<RefreshRequest>),
+    (state, conn, refresh_request): (State<AppState>, Connection, Json<RefreshRequest>),
 ) -> Result<HttpResponse, BigNeonError> {
     let mut validation = Validation::default();
     validation.validate_exp = false;
@@ -124,7 +166,11 @@ pub fn token_refresh(
         state.config.token_secret.as_bytes(),
         &validation,
     )?;
-    let user = User::find(token.claims.get_id()?, connection.get())?;
+    let connection = conn.get();
+    let user = User::find(token.claims.get_id()?, connection)?;
+    if !user.active {
+        return application::unauthorized_with_message("Invalid token", None, None);
+    }
 
     // If the user changes their password invalidate all refresh tokens
     let password_modified_timestamp = user.password_modified_at.timestamp() as u64;
@@ -132,12 +178,57 @@ pub fn token_refresh(
         return application::unauthorized_with_message("Invalid token", None, None);
     }
 
-    let response = TokenResponse::create_from_refresh_token(
+    let (session_id, refresh_token_id) = match (token.claims.sid, token.claims.jti) {
+        (Some(session_id), Some(refresh_token_id)) => (session_id, refresh_token_id),
+        (None, None) => {
+            // Issued before sessions were recorded, exchanged once for a session of its own
+            let session = match UserSession::create_from_legacy_token(
+                user.id,
+                &refresh_request.refresh_token,
+                None,
+                None,
+                connection,
+            )? {
+                Some(session) => session,
+                None => {
+                    jlog!(Warn, "Legacy refresh token reused, session revoked", {"id": user.id});
+                    commit_before_error_response(&state, &conn)?;
+                    return application::unauthorized_with_message("Invalid token", None, None);
+                }
+            };
+            return token_refresh_response(&state, &user, &session);
+        }
+        _ => return application::unauthorized_with_message("Invalid token", None, None),
+    };
+    let session = match UserSession::find(session_id, connection).optional()? {
+        Some(ref session) if session.user_id == user.id => session.clone(),
+        _ => return application::unauthorized_with_message("Invalid token", None, None),
+    };
+    let session = match session.rotate(refresh_token_id, connection)? {
+        Some(session) => session,
+        None => {
+            if !session.is_revoked() {
+                jlog!(Warn, "Refresh token reused, session revoked", {"id": user.id, "session_id": session.id});
+                // The revocation must outlast the rollback of the failure response
+                commit_before_error_response(&state, &conn)?;
+            }
+            return application::unauthorized_with_message("Invalid token", None, None);
+        }
+    };
+
+    token_refresh_response(&state, &user, &session)
+}
+
+fn token_refresh_response(
+    state: &AppState,
+    user: &User,
+    session: &UserSession,
+) -> Result<HttpResponse, BigNeonError> {
+    let response = TokenResponse::create_for_session(
         &state.config.token_secret,
         &state.config.token_issuer,
         &state.config.jwt_expiry_time,
-        &user.id,
-        &refresh_request.refresh_token,
+        session,
     )?;
     jlog!(Info, "User refreshed token", {"id": user.id, "email": user.email.clone()});
 
@@ -0,0 +1,265 @@
+use actix_web::{http::StatusCode, HttpRequest, HttpResponse, Path, Query, State};
+use auth::user::User;
+use bigneon_db::models::*;
+use communications::campaigns::campaign_communication;
+use db::Connection;
+use diesel::PgConnection;
+use errors::*;
+use extractors::*;
+use helpers::application;
+use models::{PathParameters, WebPayload};
+use server::AppState;
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct CreateCampaignRequest {
+    pub name: String,
+    pub channel: CommunicationChannelType,
+    pub audience: CampaignAudiences,
+    pub event_id: Option<Uuid>,
+    pub ticket_type_id: Option<Uuid>,
+    pub fan_segment_id: Option<Uuid>,
+    pub subject: Option<String>,
+    pub body: String,
+    pub batch_size: Option<i32>,
+    pub batch_interval_in_seconds: Option<i32>,
+}
+
+#[derive(Deserialize)]
+pub struct RecipientsQueryParameters {
+    pub status: Option<CampaignRecipientStatus>,
+    pub page: Option<u32>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Deserialize)]
+pub struct UnsubscribeRequest {
+    pub token: Uuid,
+}
+
+#[derive(Serialize)]
+pub struct DisplayCampaign {
+    #[serde(flatten)]
+    pub campaign: Campaign,
+    pub recipient_counts: CampaignRecipientCounts,
+}
+
+/// The message as recipients will see it and who it will reach, nothing is sent
+#[derive(Deserialize, Serialize)]
+pub struct CampaignPreview {
+    pub subject: Option<String>,
+    pub body: String,
+    pub audience: CampaignAudienceSummary,
+}
+
+pub fn index(
+    (connection, path, query_parameters, user): (
+        Connection,
+        Path<PathParameters>,
+        Query<PagingParameters>,
+        User,
+    ),
+) -> Result<WebPayload<Campaign>, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgFans, &organization, connection)?;
+    //TODO refactor query using paging parameters
+    let campaigns = Campaign::find_for_organization(organization.id, connection)?;
+
+    Ok(WebPayload::new(
+        StatusCode::OK,
+        Payload::from_data(campaigns, query_parameters.page(), query_parameters.limit()),
+    ))
+}
+
+pub fn create(
+    (connection, path, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<CreateCampaignRequest>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgFans, &organization, connection)?;
+    let json = json.into_inner();
+
+    let mut new_campaign = Campaign::create(
+        organization.id,
+        &json.name,
+        json.channel,
+        json.audience,
+        json.subject,
+        &json.body,
+        user.id(),
+    );
+    new_campaign.event_id = json.event_id;
+    new_campaign.ticket_type_id = json.ticket_type_id;
+    new_campaign.fan_segment_id = json.fan_segment_id;
+    if let Some(batch_size) = json.batch_size {
+        new_campaign.batch_size = batch_size;
+    }
+    if let Some(batch_interval_in_seconds) = json.batch_interval_in_seconds {
+        new_campaign.batch_interval_in_seconds = batch_interval_in_seconds;
+    }
+    let campaign = new_campaign.commit(connection)?;
+    Ok(HttpResponse::Created().json(&for_display(campaign, connection)?))
+}
+
+pub fn show(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let campaign = find_with_scope(path.id, Scopes::OrgFans, &user, connection)?;
+    let campaign = campaign.update_recipient_statuses(connection)?;
+    Ok(HttpResponse::Ok().json(&for_display(campaign, connection)?))
+}
+
+pub fn update(
+    (connection, path, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<CampaignEditableAttributes>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let campaign = find_with_scope(path.id, Scopes::OrgFans, &user, connection)?;
+    let campaign = campaign.update(json.into_inner(), connection)?;
+    Ok(HttpResponse::Ok().json(&for_display(campaign, connection)?))
+}
+
+pub fn preview(
+    (connection, path, user, state): (Connection, Path<PathParameters>, User, State<AppState>),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let campaign = find_with_scope(path.id, Scopes::OrgFans, &user, connection)?;
+    let organization = campaign.organization(connection)?;
+
+    // A placeholder token shows the unsubscribe link recipients will receive
+    let communication = campaign_communication(
+        &state.config,
+        &campaign,
+        &organization,
+        String::new(),
+        Some(Uuid::nil()),
+    )?;
+    let (subject, body) = match communication.body {
+        Some(body) => (Some(communication.title), body),
+        None => (None, communication.title),
+    };
+    Ok(HttpResponse::Ok().json(&CampaignPreview {
+        subject,
+        body,
+        audience: campaign.audience_summary(connection)?,
+    }))
+}
+
+/// Sends the campaign to the current user only, so it can be checked before it is sent
+pub fn send_test(
+    (connection, path, user, state): (Connection, Path<PathParameters>, User, State<AppState>),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let campaign = find_with_scope(path.id, Scopes::OrgFans, &user, connection)?;
+    let organization = campaign.organization(connection)?;
+
+    let address = match campaign.address_for(&CampaignAudienceMember {
+        user_id: user.id(),
+        first_name: user.user.first_name.clone(),
+        last_name: user.user.last_name.clone(),
+        email: user.user.email.clone(),
+        phone: user.user.phone.clone(),
+    }) {
+        Some(address) => address,
+        None => {
+            return application::unprocessable(
+                "You do not have an address for this campaign's channel",
+            );
+        }
+    };
+    campaign_communication(&state.config, &campaign, &organization, address, None)?
+        .queue(connection)?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Starts sending the campaign. Its messages are queued in the background in batches of
+/// `batch_size`, `batch_interval_in_seconds` apart so large audiences are sent gradually.
+pub fn send(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let campaign = find_with_scope(path.id, Scopes::OrgWrite, &user, connection)?;
+    // Checked up front as the messages are only built once the campaign is sending
+    if campaign.channel == CommunicationChannelType::Push {
+        return application::unprocessable("Campaigns cannot be sent by push notification");
+    }
+    let campaign = campaign.start_sending(connection)?;
+    Ok(HttpResponse::Ok().json(&for_display(campaign, connection)?))
+}
+
+pub fn cancel(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let campaign = find_with_scope(path.id, Scopes::OrgWrite, &user, connection)?;
+    let campaign = campaign.cancel(connection)?;
+    Ok(HttpResponse::Ok().json(&for_display(campaign, connection)?))
+}
+
+pub fn recipients(
+    (connection, path, query, user): (
+        Connection,
+        Path<PathParameters>,
+        Query<RecipientsQueryParameters>,
+        User,
+    ),
+) -> Result<WebPayload<CampaignRecipient>, BigNeonError> {
+    let connection = connection.get();
+    let campaign = find_with_scope(path.id, Scopes::OrgFans, &user, connection)?;
+    let campaign = campaign.update_recipient_statuses(connection)?;
+    let payload = campaign.recipients(
+        query.status,
+        query.page.unwrap_or(0),
+        query.limit.unwrap_or(100),
+        connection,
+    )?;
+    Ok(WebPayload::new(StatusCode::OK, payload))
+}
+
+/// Turns off the organization's marketing on the campaign's channel using the token from the
+/// link in a campaign message, no login is required
+pub fn unsubscribe(
+    (connection, json, request): (Connection, Json<UnsubscribeRequest>, HttpRequest<AppState>),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let recipient = CampaignRecipient::find_by_unsubscribe_token(json.token, connection)?;
+    recipient.unsubscribe(
+        request.connection_info().remote().map(|i| i.to_string()),
+        connection,
+    )?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+fn for_display(
+    campaign: Campaign,
+    connection: &PgConnection,
+) -> Result<DisplayCampaign, BigNeonError> {
+    let recipient_counts = campaign.recipient_counts(connection)?;
+    Ok(DisplayCampaign {
+        campaign,
+        recipient_counts,
+    })
+}
+
+fn find_with_scope(
+    id: Uuid,
+    scope: Scopes,
+    user: &User,
+    connection: &PgConnection,
+) -> Result<Campaign, BigNeonError> {
+    let campaign = Campaign::find(id, connection)?;
+    let organization = campaign.organization(connection)?;
+    user.requires_scope_for_organization(scope, &organization, connection)?;
+    Ok(campaign)
+}
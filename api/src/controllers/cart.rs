@@ -36,6 +36,8 @@ pub struct CartItem {
 pub struct UpdateCartRequest {
     pub items: Vec<CartItem>,
     pub box_office_pricing: Option<bool>,
+    #[serde(default)]
+    pub attribution: Option<OrderAttribution>,
 }
 
 pub fn update_cart(
@@ -66,6 +68,9 @@ pub fn update_cart(
 
     // Find the current cart of the user, if it exists.
     let mut cart = Order::find_or_create_cart(&user.user, connection)?;
+    if let Some(attribution) = json.attribution {
+        cart = cart.set_attribution(attribution, connection)?;
+    }
 
     let order_items: Vec<UpdateOrderItem> = json
         .items
@@ -133,6 +138,9 @@ pub fn replace_cart(
 
     // Find the current cart of the user, if it exists.
     let mut cart = Order::find_or_create_cart(&user.user, connection)?;
+    if let Some(attribution) = json.attribution {
+        cart = cart.set_attribution(attribution, connection)?;
+    }
 
     let order_items: Vec<UpdateOrderItem> = json
         .items
@@ -241,6 +249,17 @@ pub fn checkout(
     };
     order.lock_version(connection.get())?;
 
+    if !user.user.has_verified_contact_details()
+        && order
+            .organizations(connection.get())?
+            .iter()
+            .any(|o| o.require_verified_contact_details)
+    {
+        return application::unprocessable(
+            "Please verify your email and phone number before checking out",
+        );
+    }
+
     if !order.items_valid_for_purchase(connection.get())? {
         return application::unprocessable(
             "Could not complete this checkout because it contains invalid order items",
@@ -275,6 +294,10 @@ pub fn checkout(
         }
     }
 
+    let currency = order
+        .payment_currency(&state.config.primary_currency)
+        .to_lowercase();
+
     let payment_response = match &req.method {
         PaymentRequest::Free => {
             info!("CART: Received checkout for free cart");
@@ -284,7 +307,7 @@ pub fn checkout(
                     "Could not use free payment method this cart because it has a total greater than zero",
                 );
             }
-            checkout_free(&connection, order, &user)?
+            checkout_free(&connection, order, &user, &currency)?
         }
         PaymentRequest::External {
             reference,
@@ -305,6 +328,7 @@ pub fn checkout(
                 phone.clone(),
                 note.clone(),
                 &user,
+                &currency,
             )?
         }
         PaymentRequest::PaymentMethod { provider } => {
@@ -330,7 +354,7 @@ pub fn checkout(
                 &mut order,
                 None,
                 &user,
-                &state.config.primary_currency,
+                &currency,
                 &provider,
                 true,
                 false,
@@ -344,7 +368,7 @@ pub fn checkout(
             &mut order,
             None,
             &user,
-            &state.config.primary_currency,
+            &currency,
             provider,
             false,
             false,
@@ -362,7 +386,7 @@ pub fn checkout(
             &mut order,
             Some(&token),
             &user,
-            &state.config.primary_currency,
+            &currency,
             provider,
             false,
             *save_payment_method,
@@ -378,6 +402,7 @@ fn checkout_free(
     conn: &Connection,
     order: Order,
     user: &User,
+    currency: &str,
 ) -> Result<HttpResponse, BigNeonError> {
     let conn = conn.get();
     if order.status != OrderStatus::Draft {
@@ -386,7 +411,13 @@ fn checkout_free(
         );
     }
     let mut order = order;
-    order.add_external_payment(Some("Free Checkout".to_string()), user.id(), 0, conn)?;
+    order.add_external_payment(
+        Some("Free Checkout".to_string()),
+        user.id(),
+        0,
+        currency,
+        conn,
+    )?;
 
     let order = Order::find(order.id, conn)?;
     let response = HttpResponse::Ok().json(json!(order.for_display(None, conn)?));
@@ -405,6 +436,7 @@ fn checkout_external(
     phone: Option<String>,
     note: Option<String>,
     user: &User,
+    currency: &str,
 ) -> Result<HttpResponse, BigNeonError> {
     let conn = conn.get();
 
@@ -446,7 +478,7 @@ fn checkout_external(
     order.set_behalf_of_user(guest.unwrap(), user.id(), conn)?;
     let total = order.calculate_total(conn)?;
 
-    order.add_external_payment(reference, user.id(), total, conn)?;
+    order.add_external_payment(reference, user.id(), total, currency, conn)?;
 
     let order = Order::find(order.id, conn)?;
     Ok(HttpResponse::Ok().json(json!(order.for_display(None, conn)?)))
@@ -598,6 +630,7 @@ fn auth_then_complete(
         auth_result.id.clone(),
         PaymentStatus::Authorized,
         auth_result.to_json()?,
+        currency,
         connection,
     ) {
         Ok(p) => p,
@@ -678,6 +711,7 @@ fn redirect_to_payment_page(
         PaymentStatus::Requested,
         Some(nonce),
         json!(response.clone()),
+        &config.primary_currency,
         conn,
     )?;
     let order = Order::find(order.id, conn)?;
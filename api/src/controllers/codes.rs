@@ -21,6 +21,8 @@ pub struct CreateCodeRequest {
     pub end_date: NaiveDateTime,
     pub max_tickets_per_user: Option<u32>,
     pub ticket_type_ids: Vec<Uuid>,
+    #[serde(default)]
+    pub fan_segment_id: Option<Uuid>,
 }
 
 #[derive(Clone, Default, Deserialize, Serialize)]
@@ -35,6 +37,8 @@ pub struct UpdateCodeRequest {
     #[serde(default, deserialize_with = "double_option::deserialize")]
     pub max_tickets_per_user: Option<Option<u32>>,
     pub ticket_type_ids: Option<Vec<Uuid>>,
+    #[serde(default, deserialize_with = "double_option::deserialize")]
+    pub fan_segment_id: Option<Option<Uuid>>,
 }
 
 impl From<UpdateCodeRequest> for UpdateCodeAttributes {
@@ -49,6 +53,7 @@ impl From<UpdateCodeRequest> for UpdateCodeAttributes {
             max_tickets_per_user: attributes
                 .max_tickets_per_user
                 .map(|m| m.map(|m2| m2 as i64)),
+            fan_segment_id: attributes.fan_segment_id,
         }
     }
 }
@@ -58,7 +63,13 @@ pub fn show(
 ) -> Result<HttpResponse, BigNeonError> {
     let conn = conn.get();
     let code = Code::find(path.id, conn)?;
-    user.requires_scope_for_organization(Scopes::CodeRead, &code.organization(conn)?, conn)?;
+    let event = Event::find(code.event_id, conn)?;
+    user.requires_scope_for_organization_event(
+        Scopes::CodeRead,
+        &event.organization(conn)?,
+        &event,
+        conn,
+    )?;
 
     Ok(HttpResponse::Ok().json(code.for_display(conn)?))
 }
@@ -73,9 +84,10 @@ pub fn create(
 ) -> Result<HttpResponse, BigNeonError> {
     let conn = conn.get();
     let event = Event::find(path.id, conn)?;
-    user.requires_scope_for_organization(Scopes::CodeWrite, &event.organization(conn)?, conn)?;
+    let organization = event.organization(conn)?;
+    user.requires_scope_for_organization_event(Scopes::CodeWrite, &organization, &event, conn)?;
 
-    let code = Code::create(
+    let mut new_code = Code::create(
         req.name.clone(),
         path.id,
         req.code_type,
@@ -85,11 +97,16 @@ pub fn create(
         req.start_date,
         req.end_date,
         req.max_tickets_per_user,
-    )
-    .commit(conn)?;
+    );
+    new_code.fan_segment_id = req.fan_segment_id;
+    let code = new_code.commit(conn)?;
 
     code.update_ticket_types(req.ticket_type_ids.clone(), conn)?;
-    application::created(json!(code.for_display(conn)?))
+    let display_code = code.for_display(conn)?;
+    audit_log(&user, &organization, &code)
+        .created(&display_code)?
+        .commit(conn)?;
+    application::created(json!(display_code))
 }
 
 pub fn update(
@@ -103,7 +120,10 @@ pub fn update(
     let conn = conn.get();
 
     let code = Code::find(path.id, conn)?;
-    user.requires_scope_for_organization(Scopes::CodeWrite, &code.organization(conn)?, conn)?;
+    let event = Event::find(code.event_id, conn)?;
+    let organization = event.organization(conn)?;
+    user.requires_scope_for_organization_event(Scopes::CodeWrite, &organization, &event, conn)?;
+    let previous_code = code.for_display(conn)?;
 
     let code = code.update(req.clone().into(), conn)?;
 
@@ -111,7 +131,11 @@ pub fn update(
         code.update_ticket_types(ticket_type_ids.clone(), conn)?;
     }
 
-    Ok(HttpResponse::Ok().json(code.for_display(conn)?))
+    let display_code = code.for_display(conn)?;
+    audit_log(&user, &organization, &code)
+        .updated(&previous_code, &display_code)?
+        .commit(conn)?;
+    Ok(HttpResponse::Ok().json(display_code))
 }
 
 pub fn destroy(
@@ -119,8 +143,24 @@ pub fn destroy(
 ) -> Result<HttpResponse, BigNeonError> {
     let conn = conn.get();
     let code = Code::find(path.id, conn)?;
-    user.requires_scope_for_organization(Scopes::CodeWrite, &code.organization(conn)?, conn)?;
+    let event = Event::find(code.event_id, conn)?;
+    let organization = event.organization(conn)?;
+    user.requires_scope_for_organization_event(Scopes::CodeWrite, &organization, &event, conn)?;
 
+    audit_log(&user, &organization, &code)
+        .deleted(&code.for_display(conn)?)?
+        .commit(conn)?;
     code.destroy(&*conn)?;
     Ok(HttpResponse::Ok().json(json!({})))
 }
+
+fn audit_log(user: &User, organization: &Organization, code: &Code) -> NewAuditLog {
+    AuditLog::create(
+        Some(user.id()),
+        Some(Scopes::CodeWrite),
+        Tables::Codes,
+        code.id,
+        Some(organization.id),
+        Some(code.event_id),
+    )
+}
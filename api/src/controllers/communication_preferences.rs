@@ -0,0 +1,58 @@
+use actix_web::HttpResponse;
+use auth::user::User as AuthUser;
+use bigneon_db::models::*;
+use db::Connection;
+use errors::*;
+use extractors::*;
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct UpdateCommunicationPreferenceRequest {
+    /// Leave empty to apply the preference to every organization
+    pub organization_id: Option<Uuid>,
+    pub category: CommunicationCategory,
+    pub channel: CommunicationChannelType,
+    pub subscribed: bool,
+}
+
+pub fn index(
+    (connection, auth_user): (Connection, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let preferences = CommunicationPreference::find_for_user(auth_user.id(), connection)?;
+    Ok(HttpResponse::Ok().json(&preferences))
+}
+
+pub fn update(
+    (connection, json, auth_user): (
+        Connection,
+        Json<UpdateCommunicationPreferenceRequest>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    if let Some(organization_id) = json.organization_id {
+        Organization::find(organization_id, connection)?;
+    }
+
+    let preference = CommunicationPreference::set(
+        auth_user.id(),
+        json.organization_id,
+        json.category,
+        json.channel,
+        json.subscribed,
+        "Preferences",
+        auth_user.ip_address.clone(),
+        connection,
+    )?;
+    Ok(HttpResponse::Ok().json(&preference))
+}
+
+/// The user's history of agreeing to and declining communications
+pub fn consents(
+    (connection, auth_user): (Connection, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let consents = CommunicationConsent::find_for_user(auth_user.id(), connection)?;
+    Ok(HttpResponse::Ok().json(&consents))
+}
@@ -2,7 +2,7 @@ use actix_web::{http::StatusCode, HttpResponse, Path, Query};
 use auth::user::User;
 use bigneon_db::models::*;
 use chrono::prelude::*;
-use controllers::holds::UpdateHoldRequest;
+use controllers::holds::{AuditedHold, UpdateHoldRequest};
 use db::Connection;
 use errors::BigNeonError;
 use extractors::*;
@@ -18,7 +18,13 @@ pub fn index(
 ) -> Result<WebPayload<DisplayHold>, BigNeonError> {
     let conn = conn.get();
     let hold = Hold::find(path.id, conn)?;
-    user.requires_scope_for_organization(Scopes::CompRead, &hold.organization(conn)?, conn)?;
+    let event = Event::find(hold.event_id, conn)?;
+    user.requires_scope_for_organization_event(
+        Scopes::CompRead,
+        &event.organization(conn)?,
+        &event,
+        conn,
+    )?;
 
     let comps = Hold::find_by_parent_id(
         path.id,
@@ -45,7 +51,13 @@ pub fn show(
 ) -> Result<HttpResponse, BigNeonError> {
     let conn = conn.get();
     let hold = Hold::find(path.id, conn)?;
-    user.requires_scope_for_organization(Scopes::CompRead, &hold.organization(conn)?, conn)?;
+    let event = Event::find(hold.event_id, conn)?;
+    user.requires_scope_for_organization_event(
+        Scopes::CompRead,
+        &event.organization(conn)?,
+        &event,
+        conn,
+    )?;
     let comp = hold.into_display(conn)?;
     Ok(HttpResponse::Ok().json(&comp))
 }
@@ -68,7 +80,9 @@ pub fn create(
 ) -> Result<WebResult<DisplayHold>, BigNeonError> {
     let conn = conn.get();
     let hold = Hold::find(path.id, conn)?;
-    user.requires_scope_for_organization(Scopes::CompWrite, &hold.organization(conn)?, conn)?;
+    let event = Event::find(hold.event_id, conn)?;
+    let organization = event.organization(conn)?;
+    user.requires_scope_for_organization_event(Scopes::CompWrite, &organization, &event, conn)?;
     let new_comp = new_comp.into_inner();
     let comp = Hold::create_comp_for_person(
         new_comp.name,
@@ -81,6 +95,9 @@ pub fn create(
         new_comp.quantity,
         conn,
     )?;
+    audit_log(&user, &organization, &comp)
+        .created(&AuditedHold::new(&comp, conn)?)?
+        .commit(conn)?;
 
     Ok(WebResult::new(
         StatusCode::CREATED,
@@ -99,13 +116,19 @@ pub fn update(
     let conn = conn.get();
 
     let comp = Hold::find(path.id, conn)?;
-    user.requires_scope_for_organization(Scopes::CompWrite, &comp.organization(conn)?, conn)?;
+    let event = Event::find(comp.event_id, conn)?;
+    let organization = event.organization(conn)?;
+    user.requires_scope_for_organization_event(Scopes::CompWrite, &organization, &event, conn)?;
+    let audited_comp = AuditedHold::new(&comp, conn)?;
     let req = req.into_inner();
     let quantity = req.quantity;
     let hold = comp.update(req.into(), conn)?;
     if quantity.is_some() {
         hold.set_quantity(quantity.unwrap(), conn)?;
     }
+    audit_log(&user, &organization, &hold)
+        .updated(&audited_comp, &AuditedHold::new(&hold, conn)?)?
+        .commit(conn)?;
 
     let comp = hold.into_display(conn)?;
     Ok(HttpResponse::Ok().json(comp))
@@ -116,9 +139,25 @@ pub fn destroy(
 ) -> Result<HttpResponse, BigNeonError> {
     let conn = conn.get();
     let hold = Hold::find(path.id, conn)?;
-    user.requires_scope_for_organization(Scopes::CompWrite, &hold.organization(conn)?, conn)?;
+    let event = Event::find(hold.event_id, conn)?;
+    let organization = event.organization(conn)?;
+    user.requires_scope_for_organization_event(Scopes::CompWrite, &organization, &event, conn)?;
 
     let comp = Hold::find(path.id, conn)?;
+    audit_log(&user, &organization, &comp)
+        .deleted(&AuditedHold::new(&comp, conn)?)?
+        .commit(conn)?;
     comp.destroy(&*conn)?;
     Ok(HttpResponse::Ok().json(json!({})))
 }
+
+fn audit_log(user: &User, organization: &Organization, comp: &Hold) -> NewAuditLog {
+    AuditLog::create(
+        Some(user.id()),
+        Some(Scopes::CompWrite),
+        Tables::Holds,
+        comp.id,
+        Some(organization.id),
+        Some(comp.event_id),
+    )
+}
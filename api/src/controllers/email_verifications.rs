@@ -0,0 +1,72 @@
+use actix_web::{HttpResponse, State};
+use auth::claims::EmailVerificationToken;
+use auth::user::User as AuthUser;
+use bigneon_db::models::User;
+use communications::mailers;
+use db::Connection;
+use diesel::PgConnection;
+use errors::*;
+use extractors::*;
+use helpers::application;
+use jwt::{decode, encode, Header, Validation};
+use server::AppState;
+
+const EMAIL_VERIFICATION_EXPIRY_HOURS: u64 = 48;
+
+#[derive(Deserialize)]
+pub struct UpdateEmailVerificationParameters {
+    pub token: String,
+}
+
+/// Emails the user a link to verify their address
+pub fn create(
+    (state, connection, user): (State<AppState>, Connection, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    if user.user.email.is_none() {
+        return application::unprocessable("User does not have an email");
+    }
+    if user.user.is_email_verified() {
+        return application::unprocessable("Email is already verified");
+    }
+    send_email_verification(&state, &user.user, connection.get())?;
+    application::no_content()
+}
+
+/// Called with the token from the emailed link, which is enough to identify the user
+pub fn update(
+    (state, connection, parameters): (
+        State<AppState>,
+        Connection,
+        Json<UpdateEmailVerificationParameters>,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let token = match decode::<EmailVerificationToken>(
+        &parameters.token,
+        state.config.token_secret.as_bytes(),
+        &Validation::default(),
+    ) {
+        Ok(token) => token,
+        Err(_) => return application::unprocessable("Email verification link is invalid"),
+    };
+    let connection = connection.get();
+    let user = User::find(token.claims.get_id()?, connection)?;
+    user.verify_email(&token.claims.email, connection)?;
+    application::no_content()
+}
+
+pub fn send_email_verification(
+    state: &AppState,
+    user: &User,
+    conn: &PgConnection,
+) -> Result<(), BigNeonError> {
+    let email = match user.email {
+        Some(ref email) => email.clone(),
+        None => return Ok(()),
+    };
+    let token = encode(
+        &Header::default(),
+        &EmailVerificationToken::new(&user.id, email, EMAIL_VERIFICATION_EXPIRY_HOURS),
+        state.config.token_secret.as_bytes(),
+    )?;
+    mailers::user::email_verification_email(&state.config, user, &token, conn)
+}
@@ -0,0 +1,115 @@
+use actix_web::{http::StatusCode, HttpResponse, Path, Query};
+use auth::user::User as AuthUser;
+use bigneon_db::models::*;
+use db::Connection;
+use errors::*;
+use extractors::*;
+use helpers::application;
+use models::{EventSplitPartnerPathParameters, PathParameters, WebPayload};
+use uuid::Uuid;
+
+#[derive(Deserialize, Serialize)]
+pub struct NewEventSplitPartnerRequest {
+    pub organization_id: Uuid,
+    pub split_type: SplitTypes,
+    #[serde(default)]
+    pub ticket_sales_flat_in_cents: i64,
+    #[serde(default)]
+    pub ticket_sales_rate_in_basis_points: i64,
+    #[serde(default)]
+    pub fees_flat_in_cents: i64,
+    #[serde(default)]
+    pub fees_rate_in_basis_points: i64,
+}
+
+pub fn index(
+    (connection, path, query_parameters, user): (
+        Connection,
+        Path<PathParameters>,
+        Query<PagingParameters>,
+        AuthUser,
+    ),
+) -> Result<WebPayload<EventSplitPartner>, BigNeonError> {
+    let connection = connection.get();
+    let event = Event::find(path.id, connection)?;
+    let organization = event.organization(connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::EventFinancialReports,
+        &organization,
+        &event,
+        connection,
+    )?;
+
+    let split_partners = EventSplitPartner::find_for_event(event.id, connection)?;
+    let payload = Payload::from_data(
+        split_partners,
+        query_parameters.page(),
+        query_parameters.limit(),
+    );
+    Ok(WebPayload::new(StatusCode::OK, payload))
+}
+
+/// Adds a co-promoting organization to the event with a share of its ticket sales and fees
+pub fn create(
+    (connection, path, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<NewEventSplitPartnerRequest>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let event = Event::find(path.id, connection)?;
+    let organization = event.organization(connection)?;
+    user.requires_scope_for_organization(Scopes::OrgWrite, &organization, connection)?;
+    let partner_organization = Organization::find(json.organization_id, connection)?;
+
+    let split_partner = NewEventSplitPartner {
+        event_id: event.id,
+        organization_id: partner_organization.id,
+        split_type: json.split_type,
+        ticket_sales_flat_in_cents: json.ticket_sales_flat_in_cents,
+        ticket_sales_rate_in_basis_points: json.ticket_sales_rate_in_basis_points,
+        fees_flat_in_cents: json.fees_flat_in_cents,
+        fees_rate_in_basis_points: json.fees_rate_in_basis_points,
+    }
+    .commit(connection)?;
+    audit_log(&user, &organization, &split_partner)
+        .created(&split_partner)?
+        .commit(connection)?;
+    Ok(HttpResponse::Created().json(&split_partner))
+}
+
+pub fn destroy(
+    (connection, path, user): (Connection, Path<EventSplitPartnerPathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let event = Event::find(path.id, connection)?;
+    let organization = event.organization(connection)?;
+    user.requires_scope_for_organization(Scopes::OrgWrite, &organization, connection)?;
+    let split_partner = EventSplitPartner::find(path.split_partner_id, connection)?;
+    if split_partner.event_id != event.id {
+        return application::not_found();
+    }
+
+    audit_log(&user, &organization, &split_partner)
+        .deleted(&split_partner)?
+        .commit(connection)?;
+    split_partner.destroy(connection)?;
+    Ok(HttpResponse::Ok().json(json!({})))
+}
+
+fn audit_log(
+    user: &AuthUser,
+    organization: &Organization,
+    split_partner: &EventSplitPartner,
+) -> NewAuditLog {
+    AuditLog::create(
+        Some(user.id()),
+        Some(Scopes::OrgWrite),
+        Tables::EventSplitPartners,
+        split_partner.id,
+        Some(organization.id),
+        Some(split_partner.event_id),
+    )
+}
@@ -0,0 +1,144 @@
+use actix_web::{http::StatusCode, HttpResponse, Path, Query};
+use auth::user::User as AuthUser;
+use bigneon_db::models::*;
+use bigneon_db::utils::errors::Optional;
+use db::Connection;
+use diesel::PgConnection;
+use errors::*;
+use extractors::*;
+use helpers::application;
+use models::{EventUserPathParameters, PathParameters, WebPayload};
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct NewEventUserRequest {
+    pub user_email: String,
+    pub role: Roles,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Debug)]
+pub struct DisplayEventUser {
+    pub user_id: Uuid,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub email: Option<String>,
+    pub roles: Vec<Roles>,
+    pub organization_or_event: String,
+}
+
+/// The event's team: the members of its organization, followed by those given a role on the
+/// event only
+pub fn index(
+    (connection, path, query_parameters, user): (
+        Connection,
+        Path<PathParameters>,
+        Query<PagingParameters>,
+        AuthUser,
+    ),
+) -> Result<WebPayload<DisplayEventUser>, BigNeonError> {
+    let connection = connection.get();
+    let event = Event::find(path.id, connection)?;
+    let organization = event.organization(connection)?;
+    user.requires_scope_for_organization_event(Scopes::OrgRead, &organization, &event, connection)?;
+
+    let mut members: Vec<DisplayEventUser> = organization
+        .users(connection)?
+        .into_iter()
+        .map(|u| DisplayEventUser {
+            user_id: u.1.id,
+            first_name: u.1.first_name,
+            last_name: u.1.last_name,
+            email: u.1.email,
+            roles: u.0.role,
+            organization_or_event: "organization".to_string(),
+        })
+        .collect();
+
+    for (event_user, u) in EventUser::find_for_event(event.id, connection)? {
+        members.push(DisplayEventUser {
+            user_id: u.id,
+            first_name: u.first_name,
+            last_name: u.last_name,
+            email: u.email,
+            roles: vec![event_user.role],
+            organization_or_event: "event".to_string(),
+        });
+    }
+
+    let payload = Payload::from_data(members, query_parameters.page(), query_parameters.limit());
+    Ok(WebPayload::new(StatusCode::OK, payload))
+}
+
+/// Gives an existing user a role on the event alone, e.g. a promoter co-promoting the show
+pub fn create(
+    (connection, path, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<NewEventUserRequest>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let event = Event::find(path.id, connection)?;
+    let organization = event.organization(connection)?;
+    requires_scopes_for_role(&user, json.role, &organization, &event, connection)?;
+
+    let event_user_to_add = match User::find_by_email(&json.user_email, connection).optional()? {
+        Some(u) => u,
+        None => return application::unprocessable("No user exists with that email"),
+    };
+    let existing_event_user =
+        EventUser::find_by_event_id_user_id(event.id, event_user_to_add.id, connection)
+            .optional()?;
+    let event_user = EventUser::create(event.id, event_user_to_add.id, json.role, connection)?;
+    let new_audit_log = audit_log(&user, &organization, &event_user);
+    let new_audit_log = match existing_event_user {
+        Some(existing_event_user) => new_audit_log.updated(&existing_event_user, &event_user)?,
+        None => new_audit_log.created(&event_user)?,
+    };
+    new_audit_log.commit(connection)?;
+    Ok(HttpResponse::Created().json(&event_user))
+}
+
+pub fn destroy(
+    (connection, path, user): (Connection, Path<EventUserPathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let event = Event::find(path.id, connection)?;
+    let organization = event.organization(connection)?;
+    let event_user = EventUser::find_by_event_id_user_id(event.id, path.user_id, connection)?;
+    requires_scopes_for_role(&user, event_user.role, &organization, &event, connection)?;
+
+    event_user.destroy(connection)?;
+    audit_log(&user, &organization, &event_user)
+        .deleted(&event_user)?
+        .commit(connection)?;
+    Ok(HttpResponse::Ok().json(json!({})))
+}
+
+/// Managing a role on the event needs `OrgUsers` and every scope the role grants, so no one can
+/// hand out more access than they have
+fn requires_scopes_for_role(
+    user: &AuthUser,
+    role: Roles,
+    organization: &Organization,
+    event: &Event,
+    connection: &PgConnection,
+) -> Result<(), BigNeonError> {
+    user.requires_scope_for_organization(Scopes::OrgUsers, organization, connection)?;
+    for scope in scopes::get_scopes(vec![role]) {
+        user.requires_scope_for_organization_event(scope, organization, event, connection)?;
+    }
+    Ok(())
+}
+
+fn audit_log(user: &AuthUser, organization: &Organization, event_user: &EventUser) -> NewAuditLog {
+    AuditLog::create(
+        Some(user.id()),
+        Some(Scopes::OrgUsers),
+        Tables::EventUsers,
+        event_user.id,
+        Some(organization.id),
+        Some(event_user.event_id),
+    )
+}
@@ -8,7 +8,10 @@ use db::Connection;
 use errors::*;
 use extractors::*;
 use helpers::application;
-use models::{PathParameters, RedeemTicketPathParameters, UserDisplayTicketType, WebPayload};
+use models::{
+    AuditLogQueryParameters, PathParameters, RedeemTicketPathParameters, UserDisplayTicketType,
+    WebPayload,
+};
 use serde_json::Value;
 use serde_with::{self, CommaSeparator};
 use server::AppState;
@@ -292,9 +295,10 @@ pub fn show(
     let box_office_pricing = query.box_office_pricing.unwrap_or(false);
     if box_office_pricing {
         match user {
-            Some(ref user) => user.requires_scope_for_organization(
+            Some(ref user) => user.requires_scope_for_organization_event(
                 Scopes::BoxOfficeTicketRead,
                 &organization,
+                &event,
                 connection,
             )?,
             None => {
@@ -450,8 +454,16 @@ pub fn publish(
 ) -> Result<HttpResponse, BigNeonError> {
     let conn = connection.get();
     let event = Event::find(path.id, conn)?;
-    user.requires_scope_for_organization(Scopes::EventWrite, &event.organization(conn)?, conn)?;
-    event.publish(conn)?;
+    user.requires_scope_for_organization_event(
+        Scopes::EventWrite,
+        &event.organization(conn)?,
+        &event,
+        conn,
+    )?;
+    let published_event = event.publish(conn)?;
+    audit_log(&user, &event)
+        .updated(&event, &published_event)?
+        .commit(conn)?;
 
     // TODO: Remove domain action and replace with domain event EventPublished
     //       once domain events are ready #DomainEvents
@@ -465,8 +477,16 @@ pub fn unpublish(
 ) -> Result<HttpResponse, BigNeonError> {
     let conn = connection.get();
     let event = Event::find(path.id, conn)?;
-    user.requires_scope_for_organization(Scopes::EventWrite, &event.organization(conn)?, conn)?;
-    event.unpublish(conn)?;
+    user.requires_scope_for_organization_event(
+        Scopes::EventWrite,
+        &event.organization(conn)?,
+        &event,
+        conn,
+    )?;
+    let unpublished_event = event.unpublish(conn)?;
+    audit_log(&user, &event)
+        .updated(&event, &unpublished_event)?
+        .commit(conn)?;
     Ok(HttpResponse::Ok().finish())
 }
 
@@ -492,7 +512,12 @@ pub fn redeem_ticket(
     )?;
     let db_event = Event::find(ticket.event_id, connection)?;
     let organization = db_event.organization(connection)?;
-    auth_user.requires_scope_for_organization(Scopes::RedeemTicket, &organization, connection)?;
+    auth_user.requires_scope_for_organization_event(
+        Scopes::RedeemTicket,
+        &organization,
+        &db_event,
+        connection,
+    )?;
 
     let redeemable =
         TicketInstance::show_redeemable_ticket(parameters.ticket_instance_id, connection)?;
@@ -577,7 +602,12 @@ pub fn dashboard(
 ) -> Result<HttpResponse, BigNeonError> {
     let conn = connection.get();
     let event = Event::find(path.id, conn)?;
-    user.requires_scope_for_organization(Scopes::DashboardRead, &event.organization(conn)?, conn)?;
+    user.requires_scope_for_organization_event(
+        Scopes::DashboardRead,
+        &event.organization(conn)?,
+        &event,
+        conn,
+    )?;
     let summary = event.summary(conn)?;
     let start_utc = query
         .start_utc
@@ -621,6 +651,9 @@ pub fn create(
     user.requires_scope_for_organization(Scopes::EventWrite, &organization, connection)?;
 
     let event = new_event.commit(connection)?;
+    audit_log(&user, &event)
+        .created(&event)?
+        .commit(connection)?;
     Ok(HttpResponse::Created().json(&event))
 }
 
@@ -648,9 +681,17 @@ pub fn update(
     let connection = connection.get();
     let event = Event::find(parameters.id, connection)?;
     let organization = event.organization(connection)?;
-    user.requires_scope_for_organization(Scopes::EventWrite, &organization, connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::EventWrite,
+        &organization,
+        &event,
+        connection,
+    )?;
 
     let updated_event = event.update(event_parameters.into_inner(), connection)?;
+    audit_log(&user, &event)
+        .updated(&event, &updated_event)?
+        .commit(connection)?;
     Ok(HttpResponse::Ok().json(&updated_event))
 }
 
@@ -660,10 +701,18 @@ pub fn cancel(
     let connection = connection.get();
     let event = Event::find(parameters.id, connection)?;
     let organization = event.organization(connection)?;
-    user.requires_scope_for_organization(Scopes::EventWrite, &organization, connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::EventWrite,
+        &organization,
+        &event,
+        connection,
+    )?;
 
     //Doing this in the DB layer so it can use the DB time as now.
-    let updated_event = event.cancel(connection)?;
+    let updated_event = event.clone().cancel(connection)?;
+    audit_log(&user, &event)
+        .updated(&event, &updated_event)?
+        .commit(connection)?;
 
     Ok(HttpResponse::Ok().json(&updated_event))
 }
@@ -722,7 +771,12 @@ pub fn add_artist(
     let connection = connection.get();
     let event = Event::find(parameters.id, connection)?;
     let organization = event.organization(connection)?;
-    user.requires_scope_for_organization(Scopes::EventWrite, &organization, connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::EventWrite,
+        &organization,
+        &event,
+        connection,
+    )?;
 
     let event_artist = EventArtist::create(
         parameters.id,
@@ -747,7 +801,12 @@ pub fn update_artists(
     let connection = connection.get();
     let event = Event::find(parameters.id, connection)?;
     let organization = event.organization(connection)?;
-    user.requires_scope_for_organization(Scopes::EventWrite, &organization, connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::EventWrite,
+        &organization,
+        &event,
+        connection,
+    )?;
 
     EventArtist::clear_all_from_event(parameters.id, connection)?;
 
@@ -804,9 +863,10 @@ pub fn guest_list(
     //TODO refactor GuestListQueryParameters to PagingParameters
     let conn = connection.get();
     let event = Event::find(path.id, conn)?;
-    user.requires_scope_for_organization(
+    user.requires_scope_for_organization_event(
         Scopes::EventViewGuests,
         &event.organization(conn)?,
+        &event,
         conn,
     )?;
     let tickets = event.guest_list(&query.query, conn)?;
@@ -823,9 +883,11 @@ pub fn codes(
     ),
 ) -> Result<HttpResponse, BigNeonError> {
     let conn = conn.get();
-    user.requires_scope_for_organization(
+    let event = Event::find(path.id, conn)?;
+    user.requires_scope_for_organization_event(
         Scopes::CodeRead,
-        &Organization::find_for_event(path.id, conn)?,
+        &event.organization(conn)?,
+        &event,
         conn,
     )?;
 
@@ -851,9 +913,11 @@ pub fn holds(
     ),
 ) -> Result<HttpResponse, BigNeonError> {
     let conn = conn.get();
-    user.requires_scope_for_organization(
+    let event = Event::find(path.id, conn)?;
+    user.requires_scope_for_organization_event(
         Scopes::HoldRead,
-        &Organization::find_for_event(path.id, conn)?,
+        &event.organization(conn)?,
+        &event,
         conn,
     )?;
     let holds = Hold::find_for_event(path.id, conn)?;
@@ -937,3 +1001,38 @@ pub fn fans_index(
 
     Ok(WebPayload::new(StatusCode::OK, payload))
 }
+
+/// Changes made to the event and to its ticket types, holds, comps and codes
+pub fn audit(
+    (connection, path, query, user): (
+        Connection,
+        Path<PathParameters>,
+        Query<AuditLogQueryParameters>,
+        User,
+    ),
+) -> Result<WebPayload<AuditLog>, BigNeonError> {
+    let connection = connection.get();
+    let event = Event::find(path.id, connection)?;
+    let organization = event.organization(connection)?;
+    user.requires_scope_for_organization(Scopes::OrgWrite, &organization, connection)?;
+
+    let payload = AuditLog::find_for_event(
+        event.id,
+        query.filters(),
+        query.page.unwrap_or(0),
+        query.limit.unwrap_or(100),
+        connection,
+    )?;
+    Ok(WebPayload::new(StatusCode::OK, payload))
+}
+
+fn audit_log(user: &User, event: &Event) -> NewAuditLog {
+    AuditLog::create(
+        Some(user.id()),
+        Some(Scopes::EventWrite),
+        Tables::Events,
+        event.id,
+        Some(event.organization_id),
+        Some(event.id),
+    )
+}
@@ -0,0 +1,44 @@
+use actix_web::{http::StatusCode, HttpResponse, Path, Query};
+use auth::user::User;
+use bigneon_db::models::*;
+use db::Connection;
+use errors::*;
+use extractors::*;
+use models::PathParameters;
+use models::WebPayload;
+
+pub fn index(
+    (connection, query_parameters, user): (Connection, Query<PagingParameters>, User),
+) -> Result<WebPayload<ExchangeRate>, BigNeonError> {
+    user.requires_scope(Scopes::ExchangeRateWrite)?;
+    //TODO refactor query using paging parameters
+    let exchange_rates = ExchangeRate::all(connection.get())?;
+
+    Ok(WebPayload::new(
+        StatusCode::OK,
+        Payload::from_data(
+            exchange_rates,
+            query_parameters.page(),
+            query_parameters.limit(),
+        ),
+    ))
+}
+
+pub fn create(
+    (connection, new_exchange_rate, user): (Connection, Json<NewExchangeRate>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    user.requires_scope(Scopes::ExchangeRateWrite)?;
+    let connection = connection.get();
+    let exchange_rate = new_exchange_rate.into_inner().commit(connection)?;
+    Ok(HttpResponse::Created().json(&exchange_rate))
+}
+
+pub fn destroy(
+    (connection, parameters, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    user.requires_scope(Scopes::ExchangeRateWrite)?;
+    let connection = connection.get();
+    let exchange_rate = ExchangeRate::find(parameters.id, connection)?;
+    exchange_rate.destroy(connection)?;
+    Ok(HttpResponse::Ok().finish())
+}
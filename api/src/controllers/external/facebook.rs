@@ -1,95 +1,24 @@
-use actix_web::{HttpResponse, State};
-use auth::TokenResponse;
-use bigneon_db::models::{ExternalLogin, User, FACEBOOK_SITE};
+use actix_web::{HttpRequest, HttpResponse};
+use controllers::external::identity_providers;
 use db::Connection;
 use errors::*;
 use extractors::*;
 use models::FacebookWebLoginToken;
-use reqwest;
-use serde_json;
 use server::AppState;
-
-const FACEBOOK_GRAPH_URL: &str = "https://graph.facebook.com";
-
-#[derive(Deserialize)]
-struct FacebookGraphResponse {
-    id: String,
-    first_name: String,
-    last_name: String,
-    email: String,
-}
+use utils::identity_providers::FacebookIdentityProvider;
 
 // TODO: Not covered by tests
 pub fn web_login(
-    (state, connection, auth_token): (State<AppState>, Connection, Json<FacebookWebLoginToken>),
+    (request, connection, auth_token): (
+        HttpRequest<AppState>,
+        Connection,
+        Json<FacebookWebLoginToken>,
+    ),
 ) -> Result<HttpResponse, BigNeonError> {
-    info!("Finding user");
-    let url = format!(
-        "{}/me?fields=id,email,first_name,last_name",
-        FACEBOOK_GRAPH_URL
-    );
-    let connection = connection.get();
-    let client = reqwest::Client::new();
-    let response = client
-        .get(&url)
-        .header(
-            "Authorization",
-            format!("Bearer {}", auth_token.access_token),
-        )
-        .send()?
-        .text()?;
-
-    let facebook_graph_response: FacebookGraphResponse = serde_json::from_str(&response)?;
-
-    let existing_user =
-        ExternalLogin::find_user(&facebook_graph_response.id, "facebook.com", connection)?;
-    let user = match existing_user {
-        Some(u) => {
-            info!("Found existing user with id: {}", &u.user_id);
-            User::find(u.user_id, connection)?
-        }
-        None => {
-            info!("User not found for external id");
-
-            // Link account if email exists
-            match User::find_by_email(&facebook_graph_response.email.clone(), connection) {
-                Ok(user) => {
-                    info!("User has existing account, linking external service");
-                    user.add_external_login(
-                        facebook_graph_response.id.clone(),
-                        FACEBOOK_SITE.to_string(),
-                        auth_token.access_token.clone(),
-                        connection,
-                    )?;
-                    user
-                }
-                Err(e) => {
-                    match e.code {
-                        // Not found
-                        2000 => {
-                            info!("Creating new user");
-                            User::create_from_external_login(
-                                facebook_graph_response.id.clone(),
-                                facebook_graph_response.first_name.clone(),
-                                facebook_graph_response.last_name.clone(),
-                                facebook_graph_response.email.clone(),
-                                FACEBOOK_SITE.to_string(),
-                                auth_token.access_token.clone(),
-                                connection,
-                            )?
-                        }
-                        _ => return Err(e.into()),
-                    }
-                }
-            }
-        }
-    };
-    info!("Saving access token");
-    let response = TokenResponse::create_from_user(
-        &state.config.token_secret,
-        &state.config.token_issuer,
-        &state.config.jwt_expiry_time,
-        &user,
-    )?;
-    Ok(HttpResponse::Ok().json(response))
+    identity_providers::sign_in(
+        &request,
+        connection.get(),
+        &FacebookIdentityProvider::new(),
+        &auth_token.access_token,
+    )
 }
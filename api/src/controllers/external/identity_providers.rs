@@ -0,0 +1,162 @@
+use actix_web::{HttpRequest, HttpResponse, Path, State};
+use auth::failed_logins::requires_sign_in_allowed;
+use auth::user::User as AuthUser;
+use auth::TokenResponse;
+use bigneon_db::models::{ExternalLogin, TwoFactorAuthentication, User};
+use bigneon_db::utils::errors::Optional;
+use db::Connection;
+use diesel::PgConnection;
+use errors::*;
+use extractors::*;
+use helpers::application;
+use models::{ExternalLoginRequest, IdentityProviderPathParameters};
+use server::AppState;
+use utils::identity_providers::{find_identity_provider, IdentityProvider};
+
+pub fn login(
+    (request, connection, path, json): (
+        HttpRequest<AppState>,
+        Connection,
+        Path<IdentityProviderPathParameters>,
+        Json<ExternalLoginRequest>,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let provider = find_identity_provider(&path.provider, &request.state().config)?;
+    sign_in(&request, connection.get(), provider.as_ref(), &json.token)
+}
+
+/// Signs in the user the provider identifies. Unknown identities are linked to the account
+/// with the same email address if both the provider and the account have verified it, or to a
+/// new account if there is none.
+pub fn sign_in(
+    request: &HttpRequest<AppState>,
+    connection: &PgConnection,
+    provider: &IdentityProvider,
+    token: &str,
+) -> Result<HttpResponse, BigNeonError> {
+    let identity = provider.verify(token)?;
+
+    let user =
+        match ExternalLogin::find_user(&identity.external_user_id, provider.site(), connection)? {
+            Some(external_login) => {
+                info!("Found existing user with id: {}", &external_login.user_id);
+                User::find(external_login.user_id, connection)?
+            }
+            None => {
+                info!("User not found for external id");
+                let email = match identity.verified_email() {
+                    Some(email) => email.to_string(),
+                    None => {
+                        return application::unprocessable(
+                            "A verified email address is required to sign in",
+                        );
+                    }
+                };
+                match User::find_by_email(&email, connection).optional()? {
+                    // Anyone could have registered the email without verifying it, so the
+                    // owner must sign in with their password to link it instead
+                    Some(ref user) if !user.is_email_verified() => {
+                        return application::unprocessable(
+                            "Please sign in with your password to link this account",
+                        );
+                    }
+                    Some(user) => {
+                        info!("User has existing account, linking external service");
+                        user.add_external_login(
+                            identity.external_user_id.clone(),
+                            provider.site().to_string(),
+                            token.to_string(),
+                            connection,
+                        )?;
+                        user
+                    }
+                    None => {
+                        info!("Creating new user");
+                        User::create_from_external_login(
+                            identity.external_user_id.clone(),
+                            identity.first_name.clone().unwrap_or_default(),
+                            identity.last_name.clone().unwrap_or_default(),
+                            email,
+                            provider.site().to_string(),
+                            token.to_string(),
+                            connection,
+                        )?
+                    }
+                }
+            }
+        };
+
+    requires_sign_in_allowed(&user)?;
+    // The provider stands in for the password only, not for the second factor
+    if TwoFactorAuthentication::find_enabled_for_user(user.id, connection)?.is_some() {
+        return application::unprocessable(
+            "Two-factor authentication is enabled, please sign in with your password",
+        );
+    }
+
+    let response = TokenResponse::create_from_user(request, &user, connection)?;
+    Ok(HttpResponse::Ok().json(response))
+}
+
+pub fn index((connection, user): (Connection, AuthUser)) -> Result<HttpResponse, BigNeonError> {
+    let external_logins = ExternalLogin::find_for_user(user.id(), connection.get())?;
+    Ok(HttpResponse::Ok().json(&external_logins))
+}
+
+/// Links the provider's account to the signed in user so it can be used to sign in
+pub fn link(
+    (state, connection, path, json, user): (
+        State<AppState>,
+        Connection,
+        Path<IdentityProviderPathParameters>,
+        Json<ExternalLoginRequest>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let provider = find_identity_provider(&path.provider, &state.config)?;
+    let identity = provider.verify(&json.token)?;
+
+    if let Some(external_login) =
+        ExternalLogin::find_user(&identity.external_user_id, provider.site(), connection)?
+    {
+        if external_login.user_id == user.id() {
+            return Ok(HttpResponse::Ok().json(&external_login));
+        }
+        return application::unprocessable("This account is already linked to another user");
+    }
+    if user
+        .user
+        .find_external_login(provider.site(), connection)?
+        .is_some()
+    {
+        return application::unprocessable("An account from this provider is already linked");
+    }
+
+    let external_login = user.user.add_external_login(
+        identity.external_user_id,
+        provider.site().to_string(),
+        json.into_inner().token,
+        connection,
+    )?;
+    Ok(HttpResponse::Created().json(&external_login))
+}
+
+pub fn unlink(
+    (state, connection, path, user): (
+        State<AppState>,
+        Connection,
+        Path<IdentityProviderPathParameters>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let provider = find_identity_provider(&path.provider, &state.config)?;
+    match user.user.find_external_login(provider.site(), connection)? {
+        Some(external_login) => {
+            external_login.destroy(connection)?;
+            application::no_content()
+        }
+        None => application::not_found(),
+    }
+}
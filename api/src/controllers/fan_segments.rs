@@ -0,0 +1,216 @@
+use actix_web::{http::StatusCode, HttpResponse, Path, Query};
+use auth::user::User;
+use bigneon_db::models::*;
+use chrono::NaiveDateTime;
+use db::Connection;
+use diesel::PgConnection;
+use errors::*;
+use extractors::*;
+use helpers::application;
+use models::{PathParameters, WebPayload};
+use utils::export::{self, ExportCell, Pages};
+use uuid::Uuid;
+
+const EXPORT_DATE_FORMAT: &'static str = "%Y-%m-%d %H:%M";
+const EXPORT_PAGE_SIZE: u32 = 1000;
+const FAN_COLUMNS: &'static [&'static str] = &[
+    "First name",
+    "Last name",
+    "Email",
+    "Phone",
+    "Orders",
+    "First order",
+    "Last order",
+    "Revenue",
+];
+
+#[derive(Deserialize)]
+pub struct CreateFanSegmentRequest {
+    pub name: String,
+    pub criteria: FanSegmentCriteria,
+}
+
+#[derive(Default, Deserialize)]
+pub struct UpdateFanSegmentRequest {
+    pub name: Option<String>,
+    pub criteria: Option<FanSegmentCriteria>,
+}
+
+#[derive(Deserialize)]
+pub struct ExportParameters {
+    pub format: ExportFormats,
+}
+
+#[derive(Serialize)]
+pub struct DisplayFanSegment {
+    #[serde(flatten)]
+    pub fan_segment: FanSegment,
+    pub member_count: i64,
+}
+
+impl From<UpdateFanSegmentRequest> for FanSegmentEditableAttributes {
+    fn from(attributes: UpdateFanSegmentRequest) -> Self {
+        FanSegmentEditableAttributes {
+            name: attributes.name,
+            criteria: attributes.criteria.map(|c| json!(c)),
+        }
+    }
+}
+
+pub fn index(
+    (connection, path, query_parameters, user): (
+        Connection,
+        Path<PathParameters>,
+        Query<PagingParameters>,
+        User,
+    ),
+) -> Result<WebPayload<FanSegment>, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgFans, &organization, connection)?;
+    //TODO refactor query using paging parameters
+    let fan_segments = FanSegment::find_for_organization(organization.id, connection)?;
+
+    Ok(WebPayload::new(
+        StatusCode::OK,
+        Payload::from_data(
+            fan_segments,
+            query_parameters.page(),
+            query_parameters.limit(),
+        ),
+    ))
+}
+
+pub fn create(
+    (connection, path, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<CreateFanSegmentRequest>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgFans, &organization, connection)?;
+
+    let fan_segment = FanSegment::create(organization.id, &json.name, &json.criteria, user.id())
+        .commit(connection)?;
+    Ok(HttpResponse::Created().json(&for_display(fan_segment, connection)?))
+}
+
+pub fn show(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let fan_segment = find_with_scope(path.id, &user, connection)?;
+    Ok(HttpResponse::Ok().json(&for_display(fan_segment, connection)?))
+}
+
+pub fn update(
+    (connection, path, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<UpdateFanSegmentRequest>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let fan_segment = find_with_scope(path.id, &user, connection)?;
+    let fan_segment = fan_segment.update(json.into_inner().into(), connection)?;
+    Ok(HttpResponse::Ok().json(&for_display(fan_segment, connection)?))
+}
+
+pub fn destroy(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let fan_segment = find_with_scope(path.id, &user, connection)?;
+    fan_segment.destroy(connection)?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+pub fn members(
+    (connection, path, query_parameters, user): (
+        Connection,
+        Path<PathParameters>,
+        Query<PagingParameters>,
+        User,
+    ),
+) -> Result<WebPayload<DisplayFan>, BigNeonError> {
+    let connection = connection.get();
+    let fan_segment = find_with_scope(path.id, &user, connection)?;
+    let payload = fan_segment.members(
+        query_parameters.page(),
+        query_parameters.limit(),
+        connection,
+    )?;
+    Ok(WebPayload::new(StatusCode::OK, payload))
+}
+
+/// Streams the segment's members as a CSV or XLSX file
+pub fn export(
+    (connection, path, query, user): (
+        Connection,
+        Path<PathParameters>,
+        Query<ExportParameters>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let fan_segment = find_with_scope(path.id, &user, connection)?;
+    let filename = format!("fan_segment-{}", fan_segment.id);
+
+    // Members are read a page at a time now, the request's connection is released before the
+    // response is streamed
+    let pages = Pages::new(|page| {
+        Ok(fan_segment
+            .members(page, EXPORT_PAGE_SIZE, connection)?
+            .data
+            .iter()
+            .map(fan_export_row)
+            .collect())
+    })
+    .collect::<Result<Vec<Vec<Vec<ExportCell>>>, BigNeonError>>()?;
+    let pages = pages.into_iter().map(Ok);
+    match export::stream_response(query.format, &filename, FAN_COLUMNS.to_vec(), pages) {
+        Some(response) => Ok(response),
+        None => application::unprocessable("Fan segments can only be exported as CSV or XLSX"),
+    }
+}
+
+fn fan_export_row(fan: &DisplayFan) -> Vec<ExportCell> {
+    let format_time =
+        |time: Option<NaiveDateTime>| time.map(|t| t.format(EXPORT_DATE_FORMAT).to_string());
+    vec![
+        fan.first_name.clone().into(),
+        fan.last_name.clone().into(),
+        fan.email.clone().into(),
+        fan.phone.clone().into(),
+        ExportCell::Integer(fan.order_count.unwrap_or(0) as i64),
+        format_time(fan.first_order_time).into(),
+        format_time(fan.last_order_time).into(),
+        ExportCell::Money(fan.revenue_in_cents.unwrap_or(0)),
+    ]
+}
+
+fn for_display(
+    fan_segment: FanSegment,
+    connection: &PgConnection,
+) -> Result<DisplayFanSegment, BigNeonError> {
+    let member_count = fan_segment.member_count(connection)?;
+    Ok(DisplayFanSegment {
+        fan_segment,
+        member_count,
+    })
+}
+
+fn find_with_scope(
+    id: Uuid,
+    user: &User,
+    connection: &PgConnection,
+) -> Result<FanSegment, BigNeonError> {
+    let fan_segment = FanSegment::find(id, connection)?;
+    let organization = fan_segment.organization(connection)?;
+    user.requires_scope_for_organization(Scopes::OrgFans, &organization, connection)?;
+    Ok(fan_segment)
+}
@@ -0,0 +1,141 @@
+use actix_web::{HttpResponse, Path};
+use auth::user::User as AuthUser;
+use bigneon_db::models::*;
+use db::Connection;
+use diesel::PgConnection;
+use errors::*;
+use extractors::*;
+use helpers::application;
+use models::{
+    OrganizationFanNotePathParameters, OrganizationFanPathParameters,
+    OrganizationFanTagPathParameters, PathParameters,
+};
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct AddFanTagRequest {
+    pub tag: String,
+}
+
+#[derive(Deserialize)]
+pub struct CreateFanNoteRequest {
+    pub note: String,
+}
+
+/// Every tag used by the organization, for suggestions when tagging fans
+pub fn organization_tags(
+    (connection, path, auth_user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    auth_user.requires_scope_for_organization(Scopes::OrgFans, &organization, connection)?;
+
+    let tags = FanTag::find_tags_for_organization(organization.id, connection)?;
+    Ok(HttpResponse::Ok().json(&tags))
+}
+
+pub fn tags(
+    (connection, path, auth_user): (Connection, Path<OrganizationFanPathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = find_organization(path.id, &auth_user, connection)?;
+
+    let tags = FanTag::find_for_fan(organization.id, path.user_id, connection)?;
+    Ok(HttpResponse::Ok().json(&tags))
+}
+
+pub fn add_tag(
+    (connection, path, json, auth_user): (
+        Connection,
+        Path<OrganizationFanPathParameters>,
+        Json<AddFanTagRequest>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = find_organization(path.id, &auth_user, connection)?;
+    if !is_fan(&organization, path.user_id, connection)? {
+        return application::forbidden("Fan does not belong to this organization");
+    }
+
+    let tag = FanTag::create(organization.id, path.user_id, &json.tag).commit(connection)?;
+    Ok(HttpResponse::Created().json(&tag))
+}
+
+pub fn remove_tag(
+    (connection, path, auth_user): (Connection, Path<OrganizationFanTagPathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = find_organization(path.id, &auth_user, connection)?;
+
+    if FanTag::remove(organization.id, path.user_id, &path.tag, connection)? == 0 {
+        return application::not_found();
+    }
+    Ok(HttpResponse::Ok().finish())
+}
+
+pub fn notes(
+    (connection, path, auth_user): (Connection, Path<OrganizationFanPathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = find_organization(path.id, &auth_user, connection)?;
+
+    let notes = FanNote::find_for_fan(organization.id, path.user_id, connection)?;
+    Ok(HttpResponse::Ok().json(&notes))
+}
+
+pub fn create_note(
+    (connection, path, json, auth_user): (
+        Connection,
+        Path<OrganizationFanPathParameters>,
+        Json<CreateFanNoteRequest>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = find_organization(path.id, &auth_user, connection)?;
+    if !is_fan(&organization, path.user_id, connection)? {
+        return application::forbidden("Fan does not belong to this organization");
+    }
+
+    let note = FanNote::create(organization.id, path.user_id, auth_user.id(), &json.note)
+        .commit(connection)?;
+    Ok(HttpResponse::Created().json(&note))
+}
+
+pub fn destroy_note(
+    (connection, path, auth_user): (
+        Connection,
+        Path<OrganizationFanNotePathParameters>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = find_organization(path.id, &auth_user, connection)?;
+
+    let note = FanNote::find(path.note_id, connection)?;
+    if note.organization_id != organization.id || note.user_id != path.user_id {
+        return application::not_found();
+    }
+    note.destroy(connection)?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+fn find_organization(
+    id: Uuid,
+    auth_user: &AuthUser,
+    connection: &PgConnection,
+) -> Result<Organization, BigNeonError> {
+    let organization = Organization::find(id, connection)?;
+    auth_user.requires_scope_for_organization(Scopes::OrgFans, &organization, connection)?;
+    Ok(organization)
+}
+
+fn is_fan(
+    organization: &Organization,
+    user_id: Uuid,
+    connection: &PgConnection,
+) -> Result<bool, BigNeonError> {
+    let user = User::find(user_id, connection)?;
+    Ok(organization.has_fan(&user, connection)?)
+}
@@ -3,6 +3,7 @@ use auth::user::User;
 use bigneon_db::models::*;
 use chrono::prelude::*;
 use db::Connection;
+use diesel::PgConnection;
 use errors::BigNeonError;
 use extractors::*;
 use helpers::application;
@@ -57,6 +58,23 @@ impl From<UpdateHoldRequest> for UpdateHoldAttributes {
 
 // add update fields in here as well
 
+/// The hold as it is recorded in the audit log, its quantity is kept on its tickets
+#[derive(Serialize)]
+pub struct AuditedHold {
+    #[serde(flatten)]
+    pub hold: Hold,
+    pub quantity: u32,
+}
+
+impl AuditedHold {
+    pub fn new(hold: &Hold, conn: &PgConnection) -> Result<AuditedHold, BigNeonError> {
+        Ok(AuditedHold {
+            hold: hold.clone(),
+            quantity: hold.quantity(conn)?.0,
+        })
+    }
+}
+
 pub fn create(
     (conn, req, path, user): (
         Connection,
@@ -67,7 +85,8 @@ pub fn create(
 ) -> Result<HttpResponse, BigNeonError> {
     let conn = conn.get();
     let event = Event::find(path.id, conn)?;
-    user.requires_scope_for_organization(Scopes::HoldWrite, &event.organization(conn)?, conn)?;
+    let organization = event.organization(conn)?;
+    user.requires_scope_for_organization_event(Scopes::HoldWrite, &organization, &event, conn)?;
 
     let hold = Hold::create_hold(
         req.name.clone(),
@@ -82,6 +101,9 @@ pub fn create(
     .commit(conn)?;
 
     hold.set_quantity(req.quantity, conn)?;
+    audit_log(&user, &organization, &hold)
+        .created(&AuditedHold::new(&hold, conn)?)?
+        .commit(conn)?;
 
     #[derive(Serialize)]
     struct R {
@@ -128,12 +150,18 @@ pub fn update(
     let conn = conn.get();
 
     let hold = Hold::find(path.id, conn)?;
-    user.requires_scope_for_organization(Scopes::HoldWrite, &hold.organization(conn)?, conn)?;
+    let event = Event::find(hold.event_id, conn)?;
+    let organization = event.organization(conn)?;
+    user.requires_scope_for_organization_event(Scopes::HoldWrite, &organization, &event, conn)?;
+    let audited_hold = AuditedHold::new(&hold, conn)?;
     let quantity = req.quantity;
     let hold = hold.update(req.into_inner().into(), conn)?;
     if let Some(quantity) = quantity {
         hold.set_quantity(quantity, conn)?;
     }
+    audit_log(&user, &organization, &hold)
+        .updated(&audited_hold, &AuditedHold::new(&hold, conn)?)?
+        .commit(conn)?;
 
     Ok(HttpResponse::Ok().json(hold))
 }
@@ -143,7 +171,21 @@ pub fn show(
 ) -> Result<HttpResponse, BigNeonError> {
     let conn = conn.get();
     let hold = Hold::find(path.id, conn)?;
-    user.requires_scope_for_organization(Scopes::OrgRead, &hold.organization(conn)?, conn)?;
+    let event = Event::find(hold.event_id, conn)?;
+    let organization = event.organization(conn)?;
+    // Organization members need `OrgRead`, roles given on the event itself need `HoldRead`
+    if !user.has_scope_for_organization(Scopes::OrgRead, &organization, conn)? {
+        if EventUser::get_scopes_for_user(event.id, user.id(), conn)?.contains(&Scopes::HoldRead) {
+            user.requires_scope_for_organization_event(
+                Scopes::HoldRead,
+                &organization,
+                &event,
+                conn,
+            )?;
+        } else {
+            user.requires_scope_for_organization(Scopes::OrgRead, &organization, conn)?;
+        }
+    }
 
     #[derive(Serialize)]
     struct R {
@@ -206,7 +248,10 @@ pub fn split(
 ) -> Result<HttpResponse, BigNeonError> {
     let conn = conn.get();
     let hold = Hold::find(path.id, conn)?;
-    user.requires_scope_for_organization(Scopes::HoldWrite, &hold.organization(conn)?, conn)?;
+    let event = Event::find(hold.event_id, conn)?;
+    let organization = event.organization(conn)?;
+    user.requires_scope_for_organization_event(Scopes::HoldWrite, &organization, &event, conn)?;
+    let audited_hold = AuditedHold::new(&hold, conn)?;
     let new_hold = hold.split(
         req.name.clone(),
         req.redemption_code.clone(),
@@ -217,6 +262,12 @@ pub fn split(
         req.max_per_order,
         conn,
     )?;
+    audit_log(&user, &organization, &hold)
+        .updated(&audited_hold, &AuditedHold::new(&hold, conn)?)?
+        .commit(conn)?;
+    audit_log(&user, &organization, &new_hold)
+        .created(&AuditedHold::new(&new_hold, conn)?)?
+        .commit(conn)?;
     Ok(HttpResponse::Created().json(new_hold))
 }
 
@@ -225,7 +276,23 @@ pub fn destroy(
 ) -> Result<HttpResponse, BigNeonError> {
     let conn = conn.get();
     let hold = Hold::find(path.id, conn)?;
-    user.requires_scope_for_organization(Scopes::HoldWrite, &hold.organization(conn)?, conn)?;
+    let event = Event::find(hold.event_id, conn)?;
+    let organization = event.organization(conn)?;
+    user.requires_scope_for_organization_event(Scopes::HoldWrite, &organization, &event, conn)?;
+    audit_log(&user, &organization, &hold)
+        .deleted(&AuditedHold::new(&hold, conn)?)?
+        .commit(conn)?;
     hold.destroy(conn)?;
     Ok(HttpResponse::Ok().finish())
 }
+
+fn audit_log(user: &User, organization: &Organization, hold: &Hold) -> NewAuditLog {
+    AuditLog::create(
+        Some(user.id()),
+        Some(Scopes::HoldWrite),
+        Tables::Holds,
+        hold.id,
+        Some(organization.id),
+        Some(hold.event_id),
+    )
+}
@@ -1,25 +1,43 @@
+pub mod affiliates;
 pub mod artists;
 pub mod auth;
+pub mod campaigns;
 pub mod cart;
 pub mod codes;
+pub mod communication_preferences;
 pub mod comps;
+pub mod email_verifications;
+pub mod event_split_partners;
+pub mod event_users;
 pub mod events;
+pub mod exchange_rates;
 pub mod external;
+pub mod fan_segments;
+pub mod fans;
 pub mod holds;
 pub mod ipns;
 pub mod orders;
+pub mod organization_api_keys;
 pub mod organization_invites;
+pub mod organization_roles;
 pub mod organizations;
 pub mod password_resets;
+pub mod passwordless_logins;
 pub mod payment_methods;
 pub mod payments;
+pub mod phone_verifications;
 pub mod redemption_codes;
 pub mod regions;
+pub mod report_subscriptions;
 pub mod reports;
+pub mod settlements;
 pub mod stages;
 pub mod status;
+pub mod tax_rules;
 pub mod ticket_types;
 pub mod tickets;
+pub mod two_factor_authentications;
 pub mod user_invites;
+pub mod user_sessions;
 pub mod users;
 pub mod venues;
@@ -143,6 +143,7 @@ pub fn refund(
         details_data.insert("items", json!(items));
         return application::unauthorized(Some(user), Some(details_data));
     }
+    user.requires_two_factor_verification(&state, &conn)?;
 
     let ticket_instance_ids = items
         .iter()
@@ -0,0 +1,101 @@
+use actix_web::{http::StatusCode, HttpResponse, Path, Query};
+use auth::user::User;
+use bigneon_db::models::*;
+use chrono::NaiveDateTime;
+use db::Connection;
+use errors::*;
+use extractors::*;
+use helpers::application;
+use models::{OrganizationApiKeyPathParameters, PathParameters, WebPayload};
+use std::str::FromStr;
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct CreateOrganizationApiKeyRequest {
+    pub name: String,
+    pub scopes: Vec<String>,
+    #[serde(default)]
+    pub event_ids: Vec<Uuid>,
+    pub expires_at: Option<NaiveDateTime>,
+}
+
+/// The key is only shown in the response to its creation
+#[derive(Serialize)]
+pub struct CreatedOrganizationApiKey {
+    #[serde(flatten)]
+    pub api_key: OrganizationApiKey,
+    pub key: String,
+}
+
+pub fn index(
+    (connection, path, query_parameters, user): (
+        Connection,
+        Path<PathParameters>,
+        Query<PagingParameters>,
+        User,
+    ),
+) -> Result<WebPayload<OrganizationApiKey>, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgWrite, &organization, connection)?;
+
+    let api_keys = OrganizationApiKey::find_for_organization(organization.id, connection)?;
+    Ok(WebPayload::new(
+        StatusCode::OK,
+        Payload::from_data(api_keys, query_parameters.page(), query_parameters.limit()),
+    ))
+}
+
+pub fn create(
+    (connection, path, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<CreateOrganizationApiKeyRequest>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgWrite, &organization, connection)?;
+
+    // A key can not be given more access than the user creating it has
+    let mut scopes = Vec::new();
+    for scope in &json.scopes {
+        let scope = match Scopes::from_str(scope) {
+            Ok(scope) => scope,
+            Err(_) => return application::unprocessable(&format!("Unknown scope {}", scope)),
+        };
+        user.requires_scope_for_organization(scope, &organization, connection)?;
+        scopes.push(scope);
+    }
+
+    let json = json.into_inner();
+    let (api_key, key) = OrganizationApiKey::create(
+        organization.id,
+        &json.name,
+        scopes,
+        json.event_ids,
+        json.expires_at,
+        user.id(),
+        connection,
+    )?;
+    Ok(HttpResponse::Created().json(&CreatedOrganizationApiKey { api_key, key }))
+}
+
+pub fn revoke(
+    (connection, path, user): (Connection, Path<OrganizationApiKeyPathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let api_key = OrganizationApiKey::find(path.api_key_id, connection)?;
+    if api_key.organization_id != path.id {
+        return application::not_found();
+    }
+    user.requires_scope_for_organization(
+        Scopes::OrgWrite,
+        &api_key.organization(connection)?,
+        connection,
+    )?;
+
+    let api_key = api_key.revoke(connection)?;
+    Ok(HttpResponse::Ok().json(&api_key))
+}
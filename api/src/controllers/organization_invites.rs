@@ -27,6 +27,8 @@ pub struct InviteResponseQuery {
 pub struct NewOrgInviteRequest {
     pub user_email: String,
     pub roles: Vec<Roles>,
+    #[serde(default)]
+    pub organization_role_ids: Vec<Uuid>,
 }
 
 pub fn create(
@@ -73,6 +75,11 @@ pub fn create(
             )?,
         }
     }
+    auth_user.requires_scopes_for_organization_roles(
+        &new_org_invite.organization_role_ids,
+        &organization,
+        connection,
+    )?;
 
     let mut invite: NewOrganizationInvite;
     let recipient: String;
@@ -105,6 +112,7 @@ pub fn create(
         new_org_invite.user_email.as_str(),
         user_id,
         new_org_invite.roles.clone(),
+        new_org_invite.organization_role_ids.clone(),
     );
 
     let invite = invite.commit(connection)?;
@@ -185,6 +193,9 @@ pub fn destroy(
             )?,
         }
     }
+    if !invite.organization_role_ids.is_empty() {
+        auth_user.requires_scope_for_organization(Scopes::OrgUsers, &organization, connection)?;
+    }
 
     invite.destroy(connection)?;
     Ok(HttpResponse::Ok().json(json!({})))
@@ -226,7 +237,30 @@ pub fn accept_request(
             if valid_for_acceptance {
                 invite_details.change_invite_status(1, connection)?;
                 let org = Organization::find(invite_details.organization_id, connection)?;
-                org.add_user(u.id(), invite_details.roles, connection)?;
+                let organization_user =
+                    OrganizationUser::find_by_user_id(u.id(), org.id, connection).optional()?;
+                let updated_organization_user = org.add_user(
+                    u.id(),
+                    invite_details.roles,
+                    invite_details.organization_role_ids,
+                    connection,
+                )?;
+                // Accepting an invite needs no scope, the invite itself grants the roles
+                let new_audit_log = AuditLog::create(
+                    Some(u.id()),
+                    None,
+                    Tables::OrganizationUsers,
+                    updated_organization_user.id,
+                    Some(org.id),
+                    None,
+                );
+                let new_audit_log = match organization_user {
+                    Some(organization_user) => {
+                        new_audit_log.updated(&organization_user, &updated_organization_user)?
+                    }
+                    None => new_audit_log.created(&updated_organization_user)?,
+                };
+                new_audit_log.commit(connection)?;
             } else {
                 return application::unauthorized(Some(u), None);
             }
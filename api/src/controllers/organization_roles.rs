@@ -0,0 +1,138 @@
+use actix_web::{http::StatusCode, HttpResponse, Path, Query};
+use auth::user::User;
+use bigneon_db::models::*;
+use bigneon_db::utils::errors::DatabaseError;
+use db::Connection;
+use diesel::PgConnection;
+use errors::*;
+use extractors::*;
+use helpers::application;
+use models::{OrganizationRolePathParameters, PathParameters, WebPayload};
+use std::str::FromStr;
+
+#[derive(Deserialize)]
+pub struct OrganizationRoleRequest {
+    pub name: String,
+    pub scopes: Vec<String>,
+}
+
+pub fn index(
+    (connection, path, query_parameters, user): (
+        Connection,
+        Path<PathParameters>,
+        Query<PagingParameters>,
+        User,
+    ),
+) -> Result<WebPayload<OrganizationRole>, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgUsers, &organization, connection)?;
+
+    let organization_roles = OrganizationRole::find_for_organization(organization.id, connection)?;
+    Ok(WebPayload::new(
+        StatusCode::OK,
+        Payload::from_data(
+            organization_roles,
+            query_parameters.page(),
+            query_parameters.limit(),
+        ),
+    ))
+}
+
+pub fn create(
+    (connection, path, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<OrganizationRoleRequest>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgAdminUsers, &organization, connection)?;
+    let scopes = parse_scopes(&json.scopes, &user, &organization, connection)?;
+
+    let organization_role =
+        OrganizationRole::create(organization.id, &json.name, scopes, connection)?;
+    audit_log(&user, &organization, &organization_role)
+        .created(&organization_role)?
+        .commit(connection)?;
+    Ok(HttpResponse::Created().json(&organization_role))
+}
+
+pub fn update(
+    (connection, path, json, user): (
+        Connection,
+        Path<OrganizationRolePathParameters>,
+        Json<OrganizationRoleRequest>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization_role = OrganizationRole::find(path.organization_role_id, connection)?;
+    if organization_role.organization_id != path.id {
+        return application::not_found();
+    }
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgAdminUsers, &organization, connection)?;
+    let scopes = parse_scopes(&json.scopes, &user, &organization, connection)?;
+
+    let updated_organization_role = organization_role.update(&json.name, scopes, connection)?;
+    audit_log(&user, &organization, &organization_role)
+        .updated(&organization_role, &updated_organization_role)?
+        .commit(connection)?;
+    Ok(HttpResponse::Ok().json(&updated_organization_role))
+}
+
+/// Removing a role takes it away from the members and invitees who had it
+pub fn destroy(
+    (connection, path, user): (Connection, Path<OrganizationRolePathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization_role = OrganizationRole::find(path.organization_role_id, connection)?;
+    if organization_role.organization_id != path.id {
+        return application::not_found();
+    }
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgAdminUsers, &organization, connection)?;
+
+    organization_role.destroy(connection)?;
+    audit_log(&user, &organization, &organization_role)
+        .deleted(&organization_role)?
+        .commit(connection)?;
+    Ok(HttpResponse::Ok().json(json!({})))
+}
+
+/// A role can not be given more access than the user defining it has
+fn parse_scopes(
+    scopes: &[String],
+    user: &User,
+    organization: &Organization,
+    connection: &PgConnection,
+) -> Result<Vec<Scopes>, BigNeonError> {
+    let mut parsed_scopes = Vec::new();
+    for scope in scopes {
+        let scope = match Scopes::from_str(scope) {
+            Ok(scope) => scope,
+            Err(_) => DatabaseError::validation_error("scopes", "Scope is not recognized")?,
+        };
+        user.requires_scope_for_organization(scope, organization, connection)?;
+        parsed_scopes.push(scope);
+    }
+    Ok(parsed_scopes)
+}
+
+fn audit_log(
+    user: &User,
+    organization: &Organization,
+    organization_role: &OrganizationRole,
+) -> NewAuditLog {
+    AuditLog::create(
+        Some(user.id()),
+        Some(Scopes::OrgAdminUsers),
+        Tables::OrganizationRoles,
+        organization_role.id,
+        Some(organization.id),
+        None,
+    )
+}
@@ -1,14 +1,17 @@
 use actix_web::{http::StatusCode, HttpResponse, Path, Query, State};
 use auth::user::User;
 use bigneon_db::models::*;
+use bigneon_db::utils::errors::Optional;
 use chrono::NaiveDateTime;
 use db::Connection;
 use errors::*;
 use extractors::*;
 use helpers::application;
 use models::WebPayload;
-use models::{OrganizationUserPathParameters, PathParameters};
+use models::{AuditLogQueryParameters, OrganizationUserPathParameters, PathParameters};
 use server::AppState;
+use std::collections::HashMap;
+use utils::ical;
 use utils::marketing_contacts;
 use uuid::Uuid;
 
@@ -18,6 +21,8 @@ const LOG_TARGET: &'static str = "bigneon::controllers::organizations";
 pub struct AddUserRequest {
     pub user_id: Uuid,
     pub roles: Vec<Roles>,
+    #[serde(default)]
+    pub organization_role_ids: Option<Vec<Uuid>>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -52,6 +57,8 @@ pub struct NewOrganizationRequest {
     pub google_ga_key: Option<String>,
     #[serde(default, deserialize_with = "deserialize_unless_blank")]
     pub facebook_pixel_key: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub currency: Option<String>,
 }
 
 pub fn index(
@@ -119,6 +126,7 @@ pub fn create(
             min_price_in_cents: 0,
             company_fee_in_cents: 0,
             client_fee_in_cents: 0,
+            ..Default::default()
         }],
     )
     .commit(user.id(), connection)?;
@@ -137,6 +145,7 @@ pub fn create(
         sendgrid_api_key: new_organization.sendgrid_api_key.clone(),
         google_ga_key: new_organization.google_ga_key.clone(),
         facebook_pixel_key: new_organization.facebook_pixel_key.clone(),
+        currency: new_organization.currency.clone(),
     };
 
     let mut organization = new_organization_with_fee_schedule.commit(
@@ -165,6 +174,10 @@ pub fn update(
     let mut organization = Organization::find(parameters.id, conn)?;
     user.requires_scope_for_organization(Scopes::OrgWrite, &organization, conn)?;
     let organization_update = organization_parameters.into_inner();
+    // Owners decide whether their staff must use two factor authentication
+    if organization_update.require_two_factor.is_some() {
+        user.requires_scope_for_organization(Scopes::OrgAdminUsers, &organization, conn)?;
+    }
     let mut updated_organization = organization.update(
         organization_update,
         &state.config.api_keys_encryption_key,
@@ -173,6 +186,15 @@ pub fn update(
 
     organization.decrypt(&state.config.api_keys_encryption_key)?;
     updated_organization.decrypt(&state.config.api_keys_encryption_key)?;
+    audit_log(
+        &user,
+        Scopes::OrgWrite,
+        &organization,
+        Tables::Organizations,
+        organization.id,
+    )
+    .updated(&organization, &updated_organization)?
+    .commit(conn)?;
 
     // If we have a new/changed sendgrid api key,
     // we create a domain action for each event
@@ -281,8 +303,48 @@ pub fn add_or_replace_user(
             _ => return application::forbidden("Role is not allowed for this user"),
         };
     }
+    if let Some(ref organization_role_ids) = req.organization_role_ids {
+        user.requires_scopes_for_organization_roles(
+            organization_role_ids,
+            &organization,
+            connection,
+        )?;
+    }
 
-    organization.add_user(req.user_id, req.roles, connection)?;
+    // Adding an owner needs the most privileged scope, so that is the one recorded
+    let scope = if req.roles.contains(&Roles::OrgOwner) {
+        Scopes::OrgAdmin
+    } else if req.roles.contains(&Roles::OrgAdmin) {
+        Scopes::OrgAdminUsers
+    } else {
+        Scopes::OrgUsers
+    };
+    let organization_user =
+        OrganizationUser::find_by_user_id(req.user_id, organization.id, connection).optional()?;
+    // Custom roles are only replaced when the request includes them
+    let organization_role_ids = match req.organization_role_ids {
+        Some(organization_role_ids) => organization_role_ids,
+        None => organization_user
+            .as_ref()
+            .map(|ou| ou.organization_role_ids.clone())
+            .unwrap_or_default(),
+    };
+    let updated_organization_user =
+        organization.add_user(req.user_id, req.roles, organization_role_ids, connection)?;
+    let new_audit_log = audit_log(
+        &user,
+        scope,
+        &organization,
+        Tables::OrganizationUsers,
+        updated_organization_user.id,
+    );
+    let new_audit_log = match organization_user {
+        Some(organization_user) => {
+            new_audit_log.updated(&organization_user, &updated_organization_user)?
+        }
+        None => new_audit_log.created(&updated_organization_user)?,
+    };
+    new_audit_log.commit(connection)?;
     Ok(HttpResponse::Created().finish())
 }
 
@@ -293,6 +355,20 @@ pub fn remove_user(
     let organization = Organization::find(parameters.id, connection)?;
     user.requires_scope_for_organization(Scopes::OrgUsers, &organization, connection)?;
 
+    if let Some(organization_user) =
+        OrganizationUser::find_by_user_id(parameters.user_id, organization.id, connection)
+            .optional()?
+    {
+        audit_log(
+            &user,
+            Scopes::OrgUsers,
+            &organization,
+            Tables::OrganizationUsers,
+            organization_user.id,
+        )
+        .deleted(&organization_user)?
+        .commit(connection)?;
+    }
     let organization = organization.remove_user(parameters.user_id, connection)?;
     Ok(HttpResponse::Ok().json(&organization))
 }
@@ -319,6 +395,7 @@ pub fn list_organization_members(
             last_name: u.1.last_name,
             email: u.1.email,
             roles: u.0.role,
+            organization_role_ids: u.0.organization_role_ids,
             invite_or_member: "member".to_string(),
             invite_id: None,
         })
@@ -331,6 +408,7 @@ pub fn list_organization_members(
             last_name: None,
             email: Some(inv.user_email),
             roles: inv.roles,
+            organization_role_ids: inv.organization_role_ids,
             invite_or_member: "invite".to_string(),
             invite_id: Some(inv.id),
         });
@@ -347,10 +425,37 @@ pub struct DisplayOrganizationUser {
     pub last_name: Option<String>,
     pub email: Option<String>,
     pub roles: Vec<Roles>,
+    pub organization_role_ids: Vec<Uuid>,
     pub invite_or_member: String,
     pub invite_id: Option<Uuid>,
 }
 
+pub fn calendar(
+    (state, connection, parameters): (State<AppState>, Connection, Path<PathParameters>),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(parameters.id, connection)?;
+
+    let mut venues: HashMap<Uuid, Option<Venue>> = HashMap::new();
+    let mut events = Vec::new();
+    for event in Event::find_all_active_events_for_organization(&organization.id, connection)? {
+        let venue = match event.venue_id {
+            Some(venue_id) => {
+                if !venues.contains_key(&venue_id) {
+                    venues.insert(venue_id, event.venue(connection)?);
+                }
+                venues[&venue_id].clone()
+            }
+            None => None,
+        };
+        events.push((event, venue));
+    }
+
+    Ok(HttpResponse::Ok()
+        .content_type(ical::ICAL_CONTENT_TYPE)
+        .body(ical::calendar(&organization.name, &events, &state.config)))
+}
+
 pub fn show_fee_schedule(
     (connection, parameters, user): (Connection, Path<PathParameters>, User),
 ) -> Result<HttpResponse, BigNeonError> {
@@ -372,7 +477,8 @@ pub fn show_fee_schedule(
 }
 
 pub fn add_fee_schedule(
-    (connection, parameters, json, user): (
+    (state, conn, parameters, json, user): (
+        State<AppState>,
         Connection,
         Path<PathParameters>,
         Json<NewFeeSchedule>,
@@ -380,12 +486,38 @@ pub fn add_fee_schedule(
     ),
 ) -> Result<HttpResponse, BigNeonError> {
     user.requires_scope(Scopes::OrgAdmin)?;
-    let connection = connection.get();
+    let connection = conn.get();
+    user.requires_two_factor_verification(&state, &conn)?;
 
     let fee_schedule = json.into_inner().commit(user.id(), connection)?;
     let fee_schedule_ranges = fee_schedule.ranges(connection)?;
 
-    Organization::find(parameters.id, connection)?.add_fee_schedule(&fee_schedule, connection)?;
+    let organization = Organization::find(parameters.id, connection)?;
+    let updated_organization = organization.add_fee_schedule(&fee_schedule, connection)?;
+    audit_log(
+        &user,
+        Scopes::OrgAdmin,
+        &organization,
+        Tables::FeeSchedules,
+        fee_schedule.id,
+    )
+    .created(&FeeScheduleWithRanges {
+        id: fee_schedule.id,
+        name: fee_schedule.name.clone(),
+        version: fee_schedule.version,
+        created_at: fee_schedule.created_at,
+        ranges: fee_schedule_ranges.clone(),
+    })?
+    .commit(connection)?;
+    audit_log(
+        &user,
+        Scopes::OrgAdmin,
+        &organization,
+        Tables::Organizations,
+        organization.id,
+    )
+    .updated(&organization, &updated_organization)?
+    .commit(connection)?;
 
     Ok(HttpResponse::Created().json(FeeScheduleWithRanges {
         id: fee_schedule.id,
@@ -421,3 +553,43 @@ pub fn search_fans(
     )?;
     Ok(WebPayload::new(StatusCode::OK, payload))
 }
+
+/// Changes made to the organization, its members and everything belonging to its events
+pub fn audit(
+    (connection, path, query, user): (
+        Connection,
+        Path<PathParameters>,
+        Query<AuditLogQueryParameters>,
+        User,
+    ),
+) -> Result<WebPayload<AuditLog>, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgWrite, &organization, connection)?;
+
+    let payload = AuditLog::find_for_organization(
+        organization.id,
+        query.filters(),
+        query.page.unwrap_or(0),
+        query.limit.unwrap_or(100),
+        connection,
+    )?;
+    Ok(WebPayload::new(StatusCode::OK, payload))
+}
+
+fn audit_log(
+    user: &User,
+    scope: Scopes,
+    organization: &Organization,
+    main_table: Tables,
+    main_id: Uuid,
+) -> NewAuditLog {
+    AuditLog::create(
+        Some(user.id()),
+        Some(scope),
+        main_table,
+        main_id,
+        Some(organization.id),
+        None,
+    )
+}
@@ -1,4 +1,4 @@
-use actix_web::{HttpResponse, State};
+use actix_web::{HttpRequest, HttpResponse};
 use auth::TokenResponse;
 use bigneon_db::models::concerns::users::password_resetable::*;
 use bigneon_db::models::User;
@@ -6,6 +6,7 @@ use communications::mailers;
 use db::Connection;
 use errors::*;
 use extractors::*;
+use middleware::RateLimiter;
 use server::AppState;
 use uuid::Uuid;
 
@@ -21,12 +22,13 @@ pub struct UpdatePasswordResetParameters {
 }
 
 pub fn create(
-    (state, connection, parameters): (
-        State<AppState>,
+    (request, connection, parameters): (
+        HttpRequest<AppState>,
         Connection,
         Json<CreatePasswordResetParameters>,
     ),
 ) -> Result<HttpResponse, BigNeonError> {
+    RateLimiter::check_target(&request, &parameters.email)?;
     let request_pending_response = Ok(HttpResponse::Created().json(json!({
         "message": format!("Your request has been received; {} will receive an email shortly with a link to reset your password if it is an account on file.", parameters.email)
     })));
@@ -38,28 +40,25 @@ pub fn create(
     };
 
     let user = user.create_password_reset_token(connection)?;
-    mailers::user::password_reset_email(&state.config, &user).queue(connection)?;
+    mailers::user::password_reset_email(&request.state().config, &user).queue(connection)?;
 
     request_pending_response
 }
 
 pub fn update(
-    (state, connection, parameters): (
-        State<AppState>,
+    (request, connection, parameters): (
+        HttpRequest<AppState>,
         Connection,
         Json<UpdatePasswordResetParameters>,
     ),
 ) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
     let user = User::consume_password_reset_token(
         &parameters.password_reset_token,
         &parameters.password,
-        connection.get(),
+        connection,
     )?;
 
-    Ok(HttpResponse::Ok().json(&TokenResponse::create_from_user(
-        &state.config.token_secret,
-        &state.config.token_issuer,
-        &state.config.jwt_expiry_time,
-        &user,
-    )?))
+    let response = TokenResponse::create_from_user(&request, &user, connection)?;
+    Ok(HttpResponse::Ok().json(&response))
 }
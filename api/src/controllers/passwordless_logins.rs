@@ -0,0 +1,114 @@
+use actix_web::{HttpRequest, HttpResponse};
+use auth::failed_logins::{commit_before_error_response, requires_sign_in_allowed};
+use auth::TokenResponse;
+use bigneon_db::models::concerns::users::passwordless_loginable::*;
+use bigneon_db::models::{TwoFactorAuthentication, User};
+use communications::{mailers, smsers};
+use db::Connection;
+use errors::*;
+use extractors::*;
+use helpers::application;
+use middleware::RateLimiter;
+use server::AppState;
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct CreatePasswordlessLoginParameters {
+    pub email: Option<String>,
+    pub phone: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct UpdatePasswordlessLoginParameters {
+    pub login_token: Option<Uuid>,
+    pub phone: Option<String>,
+    pub code: Option<String>,
+}
+
+/// Emails a sign in link, or texts a sign in code when a phone number is given. The response
+/// does not reveal whether there is an account for the email or phone.
+pub fn create(
+    (request, connection, parameters): (
+        HttpRequest<AppState>,
+        Connection,
+        Json<CreatePasswordlessLoginParameters>,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let config = &request.state().config;
+    match (&parameters.email, &parameters.phone) {
+        (Some(email), _) => {
+            RateLimiter::check_target(&request, email)?;
+            let request_pending_response = Ok(HttpResponse::Created().json(json!({
+                "message": format!("Your request has been received; {} will receive an email shortly with a link to sign in if it is an account on file.", email)
+            })));
+            let user = match User::find_by_email(email, connection) {
+                Ok(user) => user,
+                Err(_) => return request_pending_response,
+            };
+            let user = user.create_login_token(connection)?;
+            mailers::user::passwordless_login_email(config, &user, connection)?;
+            request_pending_response
+        }
+        (None, Some(phone)) => {
+            RateLimiter::check_target(&request, phone)?;
+            let request_pending_response = Ok(HttpResponse::Created().json(json!({
+                "message": format!("Your request has been received; {} will receive a text message shortly with a code to sign in if it is an account on file.", phone)
+            })));
+            let user = match User::find_by_login_phone(phone, connection)? {
+                Some(user) => user,
+                None => return request_pending_response,
+            };
+            let (_, code) = user.create_login_code(connection)?;
+            smsers::user::passwordless_login_code(config, phone.clone(), &code, connection)?;
+            request_pending_response
+        }
+        (None, None) => application::unprocessable("Email or phone is required"),
+    }
+}
+
+/// Signs in with the emailed `login_token` or the texted `code`, each of which can be used once.
+/// The link or code is only used up once the user is allowed to sign in with it.
+pub fn update(
+    (request, connection, parameters): (
+        HttpRequest<AppState>,
+        Connection,
+        Json<UpdatePasswordlessLoginParameters>,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let conn = connection;
+    let connection = conn.get();
+    let user = match (&parameters.login_token, &parameters.phone, &parameters.code) {
+        (Some(login_token), _, _) => User::find_by_login_token(login_token, connection)?,
+        (None, Some(phone), Some(_)) => match User::find_by_login_phone(phone, connection)? {
+            Some(user) => user,
+            None => return application::unprocessable("Code is incorrect or has expired"),
+        },
+        _ => return application::unprocessable("Login token, or phone and code are required"),
+    };
+
+    requires_sign_in_allowed(&user)?;
+    // The link or code stands in for the password only, not for the second factor
+    if TwoFactorAuthentication::find_enabled_for_user(user.id, connection)?.is_some() {
+        return application::unprocessable(
+            "Two-factor authentication is enabled, please sign in with your password",
+        );
+    }
+
+    let user = match (&parameters.login_token, &parameters.phone, &parameters.code) {
+        (Some(login_token), _, _) => User::consume_login_token(login_token, connection)?,
+        (_, Some(phone), Some(code)) => match User::consume_login_code(phone, code, connection)? {
+            Some(user) => user,
+            None => {
+                // The failure response rolls back the request's transaction, the attempt must
+                // be kept
+                commit_before_error_response(request.state(), &conn)?;
+                return application::unprocessable("Code is incorrect or has expired");
+            }
+        },
+        _ => return application::unprocessable("Login token, or phone and code are required"),
+    };
+
+    let response = TokenResponse::create_from_user(&request, &user, connection)?;
+    Ok(HttpResponse::Ok().json(&response))
+}
@@ -0,0 +1,58 @@
+use actix_web::{HttpResponse, State};
+use auth::user::User as AuthUser;
+use bigneon_db::models::PhoneVerification;
+use communications::smsers;
+use config::Environment;
+use db::Connection;
+use errors::*;
+use extractors::*;
+use helpers::application;
+use server::AppState;
+
+#[derive(Deserialize)]
+pub struct UpdatePhoneVerificationParameters {
+    pub code: String,
+}
+
+/// Texts the user a code to verify their phone
+pub fn create(
+    (state, connection, user): (State<AppState>, Connection, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    if user.user.is_phone_verified() {
+        return application::unprocessable("Phone is already verified");
+    }
+    let connection = connection.get();
+    let (phone_verification, code) = PhoneVerification::create(&user.user, connection)?;
+    smsers::user::phone_verification_code(
+        &state.config,
+        phone_verification.phone.clone(),
+        &code,
+        connection,
+    )?;
+    Ok(HttpResponse::Created().json(&phone_verification))
+}
+
+pub fn update(
+    (state, connection, parameters, user): (
+        State<AppState>,
+        Connection,
+        Json<UpdatePhoneVerificationParameters>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let conn = connection;
+    let connection = conn.get();
+    let phone_verification = match PhoneVerification::find_for_user(user.id(), connection)? {
+        Some(phone_verification) => phone_verification,
+        None => return application::not_found(),
+    };
+    if !phone_verification.verify(&parameters.code, connection)? {
+        // The failure response rolls back the request's transaction, the attempt must be kept
+        if state.config.environment != Environment::Test {
+            conn.commit_transaction()?;
+            conn.begin_transaction()?;
+        }
+        return application::unprocessable("Code is incorrect or has expired");
+    }
+    application::no_content()
+}
@@ -0,0 +1,142 @@
+use actix_web::{http::StatusCode, HttpResponse, Path, Query};
+use auth::user::User;
+use bigneon_db::models::*;
+use db::Connection;
+use diesel::PgConnection;
+use errors::*;
+use extractors::*;
+use helpers::application;
+use models::{PathParameters, WebPayload};
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct CreateReportSubscriptionRequest {
+    pub report_type: ReportTypes,
+    pub event_id: Option<Uuid>,
+    pub frequency: ReportFrequencies,
+    pub day_of_week: Option<i32>,
+    pub hour_of_day: i32,
+    pub timezone: String,
+    #[serde(default)]
+    pub format: ExportFormats,
+}
+
+#[derive(Deserialize)]
+pub struct UnsubscribeRequest {
+    pub token: Uuid,
+}
+
+pub fn index(
+    (connection, path, query_parameters, user): (
+        Connection,
+        Path<PathParameters>,
+        Query<PagingParameters>,
+        User,
+    ),
+) -> Result<WebPayload<ReportSubscription>, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgRead, &organization, connection)?;
+    //TODO refactor query using paging parameters
+    let subscriptions = ReportSubscription::find_for_user(organization.id, user.id(), connection)?;
+
+    Ok(WebPayload::new(
+        StatusCode::OK,
+        Payload::from_data(
+            subscriptions,
+            query_parameters.page(),
+            query_parameters.limit(),
+        ),
+    ))
+}
+
+pub fn create(
+    (connection, path, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<CreateReportSubscriptionRequest>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    let json = json.into_inner();
+    user.requires_scope_for_organization(
+        json.report_type.required_scope(json.event_id),
+        &organization,
+        connection,
+    )?;
+
+    let subscription = NewReportSubscription {
+        organization_id: organization.id,
+        user_id: user.id(),
+        report_type: json.report_type,
+        event_id: json.event_id,
+        frequency: json.frequency,
+        day_of_week: json.day_of_week,
+        hour_of_day: json.hour_of_day,
+        timezone: json.timezone,
+        format: json.format,
+    }
+    .commit(connection)?;
+    Ok(HttpResponse::Created().json(&subscription))
+}
+
+pub fn destroy(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let subscription = match find_for_user(path.id, &user, connection)? {
+        Some(subscription) => subscription,
+        None => return application::not_found(),
+    };
+    subscription.unsubscribe(connection)?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+pub fn deliveries(
+    (connection, path, query_parameters, user): (
+        Connection,
+        Path<PathParameters>,
+        Query<PagingParameters>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let subscription = match find_for_user(path.id, &user, connection)? {
+        Some(subscription) => subscription,
+        None => return application::not_found(),
+    };
+    //TODO refactor query using paging parameters
+    let deliveries = subscription.deliveries(connection)?;
+    Ok(HttpResponse::Ok().json(&Payload::from_data(
+        deliveries,
+        query_parameters.page(),
+        query_parameters.limit(),
+    )))
+}
+
+/// Unsubscribes using the token from the link in a report email, no login is required
+pub fn unsubscribe(
+    (connection, json): (Connection, Json<UnsubscribeRequest>),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let subscription = ReportSubscription::find_by_unsubscribe_token(json.token, connection)?;
+    if subscription.unsubscribed_at.is_none() {
+        subscription.unsubscribe(connection)?;
+    }
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Subscriptions are only visible to the user who created them
+fn find_for_user(
+    id: Uuid,
+    user: &User,
+    connection: &PgConnection,
+) -> Result<Option<ReportSubscription>, BigNeonError> {
+    let subscription = ReportSubscription::find(id, connection)?;
+    if subscription.user_id != user.id() {
+        return Ok(None);
+    }
+    Ok(Some(subscription))
+}
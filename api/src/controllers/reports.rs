@@ -1,20 +1,31 @@
 use actix_web::{HttpResponse, Path, Query};
 use auth::user::User as AuthUser;
-use bigneon_db::models::{Organization, Report, Scopes};
+use bigneon_db::models::{Event, EventSplitPartner, ExportFormats, Organization, Report, Scopes};
+use bigneon_db::utils::errors::Optional;
 use chrono::prelude::*;
 use db::Connection;
+use diesel::PgConnection;
 use errors::*;
 use helpers::application;
 use models::PathParameters;
+use std::collections::HashMap;
+use std::iter;
 use std::str;
+use utils::export::{self, ExportCell, Pages};
+use utils::report_export::*;
 use uuid::Uuid;
 
+const EXPORT_PAGE_SIZE: u32 = 1000;
+
 #[derive(Deserialize)]
 pub struct ReportQueryParameters {
     pub report: String,
     pub start_utc: Option<NaiveDateTime>,
     pub end_utc: Option<NaiveDateTime>,
     pub event_id: Option<Uuid>,
+    pub affiliate_id: Option<Uuid>,
+    pub currency: Option<String>,
+    pub format: Option<ExportFormats>,
 }
 
 pub fn get_report(
@@ -31,6 +42,13 @@ pub fn get_report(
         "weekly_settlement" => weekly_settlement_report((connection, query, path, user)),
         "ticket_count" => ticket_counts((connection, query, path, user)),
         "audit_report" => audit_report((connection, query, path, user)),
+        "sales_by_source" => sales_by_source_report((connection, query, path, user)),
+        "affiliate_commissions" => affiliate_commission_report((connection, query, path, user)),
+        "currency_summary" | "tax_liability" if query.format.is_some() => {
+            application::unprocessable("This report cannot be exported")
+        }
+        "currency_summary" => currency_summary_report((connection, query, path, user)),
+        "tax_liability" => tax_liability_report((connection, query, path, user)),
         _ => application::not_found(),
     }
 }
@@ -46,19 +64,46 @@ pub fn transaction_detail_report(
     let connection = connection.get();
     //Check if they have org admin permissions
     let organization = Organization::find(path.id, connection)?;
-    if query.event_id.is_some() {
-        user.requires_scope_for_organization(
-            Scopes::EventFinancialReports,
-            &organization,
-            connection,
-        )?;
-    } else {
-        user.requires_scope_for_organization(Scopes::OrgReports, &organization, connection)?;
+    let organization_id = match query.event_id {
+        Some(event_id) => {
+            requires_event_report_access(&user, event_id, &organization, false, connection)?
+        }
+        None => {
+            user.requires_scope_for_organization(Scopes::OrgReports, &organization, connection)?;
+            organization.id
+        }
+    };
+
+    if let Some(format) = query.format {
+        // Rows are read a page at a time now, the request's connection is released before the
+        // response is streamed
+        let mut timezones = HashMap::new();
+        let pages = Pages::new(|page| {
+            Report::transaction_detail_report_page(
+                query.event_id,
+                Some(organization_id),
+                query.start_utc,
+                query.end_utc,
+                page,
+                EXPORT_PAGE_SIZE,
+                connection,
+            )?
+            .iter()
+            .map(|row| transaction_export_row(row, &mut timezones, connection))
+            .collect()
+        })
+        .collect::<Result<Vec<Vec<Vec<ExportCell>>>, BigNeonError>>()?;
+        return export_response(
+            format,
+            "transaction_details",
+            TRANSACTION_COLUMNS.to_vec(),
+            pages.into_iter().map(Ok),
+        );
     }
 
     let result = Report::transaction_detail_report(
         query.event_id,
-        Some(path.id),
+        Some(organization_id),
         query.start_utc,
         query.end_utc,
         connection,
@@ -77,16 +122,15 @@ pub fn event_summary_report(
     let connection = connection.get();
     //Check if they have org admin permissions
     let organization = Organization::find(path.id, connection)?;
-    if query.event_id.is_some() {
-        user.requires_scope_for_organization(
-            Scopes::EventFinancialReports,
-            &organization,
-            connection,
-        )?;
-    } else {
-        // TODO: Switch this out for bad request
-        return application::unprocessable("event_id parameter is required");
-    }
+    let event_organization_id = match query.event_id {
+        Some(event_id) => {
+            requires_event_report_access(&user, event_id, &organization, true, connection)?
+        }
+        None => {
+            // TODO: Switch this out for bad request
+            return application::unprocessable("event_id parameter is required");
+        }
+    };
 
     let result = Report::summary_event_report(
         //We catch the is_none() above so I'll use unwrap here
@@ -95,6 +139,30 @@ pub fn event_summary_report(
         query.end_utc,
         connection,
     )?;
+    if event_organization_id != organization.id {
+        // Split partners only see their own share
+        let summary = match result.for_split_partner(organization.id) {
+            Some(summary) => summary,
+            None => return application::forbidden("Report is not available to split partners"),
+        };
+        if let Some(format) = query.format {
+            return export_response(
+                format,
+                "event_summary",
+                SPLIT_PARTNER_SUMMARY_COLUMNS.to_vec(),
+                iter::once(Ok(split_partner_summary_export_rows(&summary))),
+            );
+        }
+        return Ok(HttpResponse::Ok().json(summary));
+    }
+    if let Some(format) = query.format {
+        return export_response(
+            format,
+            "event_summary",
+            SUMMARY_COLUMNS.to_vec(),
+            iter::once(Ok(summary_export_rows(&result))),
+        );
+    }
     Ok(HttpResponse::Ok().json(result))
 }
 
@@ -109,16 +177,15 @@ pub fn audit_report(
     let connection = connection.get();
     //Check if they have org admin permissions
     let organization = Organization::find(path.id, connection)?;
-    if query.event_id.is_some() {
-        user.requires_scope_for_organization(
-            Scopes::EventFinancialReports,
-            &organization,
-            connection,
-        )?;
-    } else {
-        // TODO: Switch this out for bad request
-        return application::unprocessable("event_id parameter is required");
-    }
+    let organization_id = match query.event_id {
+        Some(event_id) => {
+            requires_event_report_access(&user, event_id, &organization, false, connection)?
+        }
+        None => {
+            // TODO: Switch this out for bad request
+            return application::unprocessable("event_id parameter is required");
+        }
+    };
 
     let all_sales_result = Report::summary_event_report(
         //We catch the is_none() above so I'll use unwrap here
@@ -139,7 +206,18 @@ pub fn audit_report(
 
     // ticket counts
     // TODO: update this query to do the inventory at end_date
-    let ticket_counts = Report::ticket_count_report(query.event_id, Some(path.id), connection)?;
+    let ticket_counts =
+        Report::ticket_count_report(query.event_id, Some(organization_id), connection)?;
+
+    if let Some(format) = query.format {
+        let rows = audit_export_rows(&end_date_sales_result, &all_sales_result);
+        return export_response(
+            format,
+            "audit_report",
+            prefixed_columns("Section"),
+            iter::once(Ok(rows)),
+        );
+    }
 
     Ok(HttpResponse::Ok().json(json!({
     "end_date_sales":end_date_sales_result,
@@ -164,6 +242,15 @@ pub fn weekly_settlement_report(
 
     let result =
         Report::organization_summary_report(path.id, query.start_utc, query.end_utc, connection)?;
+    if let Some(format) = query.format {
+        let rows = weekly_settlement_export_rows(&result, connection)?;
+        return export_response(
+            format,
+            "weekly_settlement",
+            weekly_settlement_columns(),
+            iter::once(Ok(rows)),
+        );
+    }
     Ok(HttpResponse::Ok().json(result))
 }
 
@@ -178,16 +265,198 @@ pub fn ticket_counts(
     let connection = connection.get();
     //Check if they have org admin permissions
     let organization = Organization::find(path.id, connection)?;
-    if query.event_id.is_some() {
+    let organization_id = match query.event_id {
+        Some(event_id) => {
+            requires_event_report_access(&user, event_id, &organization, false, connection)?
+        }
+        None => {
+            user.requires_scope_for_organization(Scopes::OrgReports, &organization, connection)?;
+            organization.id
+        }
+    };
+
+    let result = Report::ticket_count_report(query.event_id, Some(organization_id), connection)?;
+    if let Some(format) = query.format {
+        return export_response(
+            format,
+            "ticket_count",
+            TICKET_COUNT_COLUMNS.to_vec(),
+            iter::once(Ok(ticket_count_export_rows(&result))),
+        );
+    }
+    Ok(HttpResponse::Ok().json(result))
+}
+
+pub fn sales_by_source_report(
+    (connection, query, path, user): (
+        Connection,
+        Query<ReportQueryParameters>,
+        Path<PathParameters>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    //Check if they have org admin permissions
+    let organization = Organization::find(path.id, connection)?;
+    let organization_id = match query.event_id {
+        Some(event_id) => {
+            requires_event_report_access(&user, event_id, &organization, false, connection)?
+        }
+        None => {
+            user.requires_scope_for_organization(Scopes::OrgReports, &organization, connection)?;
+            organization.id
+        }
+    };
+
+    let result = Report::sales_by_source_report(
+        organization_id,
+        query.event_id,
+        query.start_utc,
+        query.end_utc,
+        connection,
+    )?;
+    if let Some(format) = query.format {
+        return export_response(
+            format,
+            "sales_by_source",
+            SALES_BY_SOURCE_COLUMNS.to_vec(),
+            iter::once(Ok(sales_by_source_export_rows(&result))),
+        );
+    }
+    Ok(HttpResponse::Ok().json(result))
+}
+
+pub fn affiliate_commission_report(
+    (connection, query, path, user): (
+        Connection,
+        Query<ReportQueryParameters>,
+        Path<PathParameters>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::OrgReports, &organization, connection)?;
+
+    let result = Report::affiliate_commission_report(
+        path.id,
+        query.affiliate_id,
+        query.start_utc,
+        query.end_utc,
+        connection,
+    )?;
+    if let Some(format) = query.format {
+        return export_response(
+            format,
+            "affiliate_commissions",
+            AFFILIATE_COMMISSION_COLUMNS.to_vec(),
+            iter::once(Ok(affiliate_commission_export_rows(&result))),
+        );
+    }
+    Ok(HttpResponse::Ok().json(result))
+}
+
+pub fn currency_summary_report(
+    (connection, query, path, user): (
+        Connection,
+        Query<ReportQueryParameters>,
+        Path<PathParameters>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    //Check if they have org admin permissions
+    let organization = Organization::find(path.id, connection)?;
+
+    user.requires_scope_for_organization(Scopes::OrgFinancialReports, &organization, connection)?;
+
+    let result = Report::currency_summary_report(
+        path.id,
+        query.start_utc,
+        query.end_utc,
+        query.currency.as_ref().map(|c| c.to_uppercase()),
+        connection,
+    )?;
+    Ok(HttpResponse::Ok().json(result))
+}
+
+pub fn tax_liability_report(
+    (connection, query, path, user): (
+        Connection,
+        Query<ReportQueryParameters>,
+        Path<PathParameters>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    //Check if they have org admin permissions
+    let organization = Organization::find(path.id, connection)?;
+
+    user.requires_scope_for_organization(Scopes::OrgFinancialReports, &organization, connection)?;
+
+    let result = Report::tax_liability_report(path.id, query.start_utc, query.end_utc, connection)?;
+    Ok(HttpResponse::Ok().json(result))
+}
+
+/// Event reports are also available to those given a role on the event. The event's split
+/// partners may only run reports allowing them, which do not expose the event's buyers.
+/// Returns the id of the event's organization, which the report is run for.
+fn requires_event_report_access(
+    user: &AuthUser,
+    event_id: Uuid,
+    organization: &Organization,
+    allow_split_partners: bool,
+    connection: &PgConnection,
+) -> Result<Uuid, BigNeonError> {
+    let event = Event::find(event_id, connection)?;
+    if event.organization_id != organization.id {
+        let split_partner = EventSplitPartner::find_by_event_id_organization_id(
+            event.id,
+            organization.id,
+            connection,
+        )
+        .optional()?;
+        if split_partner.is_none() {
+            return Err(ApplicationError::new_with_type(
+                ApplicationErrorType::Unprocessable,
+                "Event does not belong to the organization".to_string(),
+            )
+            .into());
+        }
         user.requires_scope_for_organization(
             Scopes::EventFinancialReports,
-            &organization,
+            organization,
             connection,
         )?;
-    } else {
-        user.requires_scope_for_organization(Scopes::OrgReports, &organization, connection)?;
+        if !allow_split_partners {
+            return Err(AuthError::new(
+                AuthErrorType::Forbidden,
+                "Report is not available to split partners".to_string(),
+            )
+            .into());
+        }
+        return Ok(event.organization_id);
     }
+    user.requires_scope_for_organization_event(
+        Scopes::EventFinancialReports,
+        organization,
+        &event,
+        connection,
+    )?;
+    Ok(event.organization_id)
+}
 
-    let result = Report::ticket_count_report(query.event_id, Some(path.id), connection)?;
-    Ok(HttpResponse::Ok().json(result))
+fn export_response<I>(
+    format: ExportFormats,
+    filename: &str,
+    columns: Vec<&'static str>,
+    pages: I,
+) -> Result<HttpResponse, BigNeonError>
+where
+    I: Iterator<Item = Result<Vec<Vec<ExportCell>>, BigNeonError>> + 'static,
+{
+    match export::stream_response(format, filename, columns, pages) {
+        Some(response) => Ok(response),
+        None => application::unprocessable("Reports can only be exported as CSV or XLSX"),
+    }
 }
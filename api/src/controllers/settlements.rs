@@ -0,0 +1,260 @@
+use actix_web::{http::StatusCode, HttpResponse, Path, Query};
+use auth::user::User;
+use bigneon_db::models::*;
+use chrono::NaiveDateTime;
+use db::Connection;
+use diesel::PgConnection;
+use errors::*;
+use extractors::*;
+use helpers::application;
+use models::{PathParameters, SettlementAdjustmentPathParameters, WebPayload};
+use utils::csv;
+use utils::money::format_cents;
+use utils::pdf;
+use uuid::Uuid;
+
+const EXPORT_DATE_FORMAT: &'static str = "%Y-%m-%d %H:%M";
+
+#[derive(Deserialize)]
+pub struct CreateSettlementRequest {
+    pub event_id: Option<Uuid>,
+    pub start_time: Option<NaiveDateTime>,
+    pub end_time: Option<NaiveDateTime>,
+}
+
+#[derive(Deserialize)]
+pub struct CreateSettlementAdjustmentRequest {
+    pub adjustment_type: SettlementAdjustmentTypes,
+    pub amount_in_cents: i64,
+    pub note: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ExportParameters {
+    pub format: ExportFormats,
+}
+
+pub fn index(
+    (connection, path, query_parameters, user): (
+        Connection,
+        Path<PathParameters>,
+        Query<PagingParameters>,
+        User,
+    ),
+) -> Result<WebPayload<Settlement>, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::SettlementRead, &organization, connection)?;
+    //TODO refactor query using paging parameters
+    let settlements = Settlement::find_for_organization(organization.id, connection)?;
+
+    Ok(WebPayload::new(
+        StatusCode::OK,
+        Payload::from_data(
+            settlements,
+            query_parameters.page(),
+            query_parameters.limit(),
+        ),
+    ))
+}
+
+pub fn create(
+    (connection, path, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<CreateSettlementRequest>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let organization = Organization::find(path.id, connection)?;
+    user.requires_scope_for_organization(Scopes::SettlementWrite, &organization, connection)?;
+
+    let settlement = Settlement::create(
+        organization.id,
+        json.event_id,
+        json.start_time,
+        json.end_time,
+        user.id(),
+    )
+    .commit(connection)?;
+    Ok(HttpResponse::Created().json(&settlement.for_display(connection)?))
+}
+
+pub fn show(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let settlement = find_with_scope(path.id, Scopes::SettlementRead, &user, connection)?;
+    Ok(HttpResponse::Ok().json(&settlement.for_display(connection)?))
+}
+
+pub fn add_adjustment(
+    (connection, path, json, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<CreateSettlementAdjustmentRequest>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let settlement = find_with_scope(path.id, Scopes::SettlementWrite, &user, connection)?;
+    let json = json.into_inner();
+    let adjustment = SettlementAdjustment::create(
+        settlement.id,
+        json.adjustment_type,
+        json.amount_in_cents,
+        json.note,
+        user.id(),
+    )
+    .commit(connection)?;
+    Ok(HttpResponse::Created().json(&adjustment))
+}
+
+pub fn destroy_adjustment(
+    (connection, path, user): (Connection, Path<SettlementAdjustmentPathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let settlement = find_with_scope(path.id, Scopes::SettlementWrite, &user, connection)?;
+    let adjustment = SettlementAdjustment::find(path.adjustment_id, connection)?;
+    if adjustment.settlement_id != settlement.id {
+        return application::not_found();
+    }
+    adjustment.destroy(connection)?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+pub fn approve(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let settlement = find_with_scope(path.id, Scopes::SettlementApprove, &user, connection)?;
+    let settlement = settlement.approve(user.id(), connection)?;
+    Ok(HttpResponse::Ok().json(&settlement.for_display(connection)?))
+}
+
+pub fn mark_paid(
+    (connection, path, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let settlement = find_with_scope(path.id, Scopes::SettlementWrite, &user, connection)?;
+    let settlement = settlement.mark_paid(user.id(), connection)?;
+    Ok(HttpResponse::Ok().json(&settlement.for_display(connection)?))
+}
+
+pub fn export(
+    (connection, path, query, user): (
+        Connection,
+        Path<PathParameters>,
+        Query<ExportParameters>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let settlement = find_with_scope(path.id, Scopes::SettlementRead, &user, connection)?;
+    let organization = settlement.organization(connection)?;
+    let display_settlement = settlement.for_display(connection)?;
+    let rows = statement_rows(&organization, &display_settlement, connection)?;
+
+    let filename = format!("settlement-{}.{}", settlement.id, query.format.extension());
+    let mut response = HttpResponse::Ok();
+    response.header(
+        "Content-Disposition",
+        format!("attachment; filename=\"{}\"", filename),
+    );
+    match query.format {
+        ExportFormats::Csv => Ok(response
+            .content_type(csv::CSV_CONTENT_TYPE)
+            .body(csv::to_csv(&rows))),
+        ExportFormats::Pdf => Ok(response
+            .content_type(pdf::PDF_CONTENT_TYPE)
+            .body(pdf::document(
+                &format!("Settlement statement - {}", organization.name),
+                &[0, 350],
+                &rows,
+            ))),
+        ExportFormats::Xlsx => {
+            application::unprocessable("Settlements can only be exported as CSV or PDF")
+        }
+    }
+}
+
+fn find_with_scope(
+    id: Uuid,
+    scope: Scopes,
+    user: &User,
+    connection: &PgConnection,
+) -> Result<Settlement, BigNeonError> {
+    let settlement = Settlement::find(id, connection)?;
+    let organization = settlement.organization(connection)?;
+    user.requires_scope_for_organization(scope, &organization, connection)?;
+    Ok(settlement)
+}
+
+fn statement_rows(
+    organization: &Organization,
+    display_settlement: &DisplaySettlement,
+    connection: &PgConnection,
+) -> Result<Vec<Vec<String>>, BigNeonError> {
+    let settlement = &display_settlement.settlement;
+    let format_time = |time: Option<NaiveDateTime>| {
+        time.map(|t| t.format(EXPORT_DATE_FORMAT).to_string())
+            .unwrap_or_default()
+    };
+
+    let mut rows = vec![
+        vec!["Settlement".to_string(), settlement.id.to_string()],
+        vec!["Organization".to_string(), organization.name.clone()],
+    ];
+    if let Some(event_id) = settlement.event_id {
+        rows.push(vec![
+            "Event".to_string(),
+            Event::find(event_id, connection)?.name,
+        ]);
+    }
+    rows.push(vec![
+        "Period".to_string(),
+        format!(
+            "{} - {}",
+            format_time(settlement.start_time),
+            format_time(settlement.end_time)
+        ),
+    ]);
+    rows.push(vec!["Status".to_string(), settlement.status.to_string()]);
+    rows.push(vec!["Currency".to_string(), settlement.currency.clone()]);
+    rows.push(vec![]);
+    rows.push(vec!["Description".to_string(), "Amount".to_string()]);
+    rows.push(vec![
+        "Ticket sales".to_string(),
+        format_cents(settlement.ticket_sales_in_cents),
+    ]);
+    rows.push(vec![
+        "Fees collected".to_string(),
+        format_cents(settlement.client_fees_in_cents),
+    ]);
+    rows.push(vec![
+        "Fees absorbed".to_string(),
+        format_cents(-settlement.absorbed_fees_in_cents),
+    ]);
+    for adjustment in &display_settlement.adjustments {
+        let description = match adjustment.note {
+            Some(ref note) => format!("{}: {}", adjustment.adjustment_type, note),
+            None => adjustment.adjustment_type.to_string(),
+        };
+        rows.push(vec![description, format_cents(adjustment.amount_in_cents)]);
+    }
+    for partner_share in &display_settlement.partner_shares {
+        rows.push(vec![
+            format!(
+                "Split partner share: {}",
+                Organization::find(partner_share.organization_id, connection)?.name
+            ),
+            format_cents(-partner_share.amount_in_cents()),
+        ]);
+    }
+    rows.push(vec![
+        "Total".to_string(),
+        format_cents(display_settlement.total_in_cents),
+    ]);
+    Ok(rows)
+}
@@ -0,0 +1,63 @@
+use actix_web::{http::StatusCode, HttpResponse, Path, Query};
+use auth::user::User;
+use bigneon_db::models::*;
+use db::Connection;
+use errors::*;
+use extractors::*;
+use models::PathParameters;
+use models::WebPayload;
+
+pub fn index(
+    (connection, query_parameters, user): (Connection, Query<PagingParameters>, User),
+) -> Result<WebPayload<TaxRule>, BigNeonError> {
+    user.requires_scope(Scopes::TaxRuleWrite)?;
+    //TODO refactor query using paging parameters
+    let tax_rules = TaxRule::all(connection.get())?;
+
+    Ok(WebPayload::new(
+        StatusCode::OK,
+        Payload::from_data(tax_rules, query_parameters.page(), query_parameters.limit()),
+    ))
+}
+
+pub fn show(
+    (connection, parameters, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    user.requires_scope(Scopes::TaxRuleWrite)?;
+    let tax_rule = TaxRule::find(parameters.id, connection.get())?;
+    Ok(HttpResponse::Ok().json(&tax_rule))
+}
+
+pub fn create(
+    (connection, new_tax_rule, user): (Connection, Json<NewTaxRule>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    user.requires_scope(Scopes::TaxRuleWrite)?;
+    let connection = connection.get();
+    let tax_rule = new_tax_rule.into_inner().commit(connection)?;
+    Ok(HttpResponse::Created().json(&tax_rule))
+}
+
+pub fn update(
+    (connection, parameters, tax_rule_parameters, user): (
+        Connection,
+        Path<PathParameters>,
+        Json<TaxRuleEditableAttributes>,
+        User,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    user.requires_scope(Scopes::TaxRuleWrite)?;
+    let connection = connection.get();
+    let tax_rule = TaxRule::find(parameters.id, connection)?;
+    let updated_tax_rule = tax_rule.update(tax_rule_parameters.into_inner(), connection)?;
+    Ok(HttpResponse::Ok().json(updated_tax_rule))
+}
+
+pub fn destroy(
+    (connection, parameters, user): (Connection, Path<PathParameters>, User),
+) -> Result<HttpResponse, BigNeonError> {
+    user.requires_scope(Scopes::TaxRuleWrite)?;
+    let connection = connection.get();
+    let tax_rule = TaxRule::find(parameters.id, connection)?;
+    tax_rule.destroy(connection)?;
+    Ok(HttpResponse::Ok().finish())
+}
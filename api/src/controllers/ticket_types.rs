@@ -34,6 +34,8 @@ pub struct CreateTicketTypeRequest {
     pub increment: Option<i32>,
     pub limit_per_person: i32,
     pub price_in_cents: i64,
+    #[serde(default)]
+    pub fee_mode: Option<FeeModes>,
 }
 
 #[derive(Deserialize, Serialize)]
@@ -58,6 +60,28 @@ pub struct UpdateTicketTypeRequest {
     pub increment: Option<i32>,
     pub limit_per_person: Option<i32>,
     pub price_in_cents: Option<i64>,
+    #[serde(default, deserialize_with = "double_option_deserialize_unless_blank")]
+    pub fee_mode: Option<Option<FeeModes>>,
+}
+
+/// The ticket type as it is recorded in the audit log, its capacity is kept on its tickets
+#[derive(Serialize)]
+struct AuditedTicketType {
+    #[serde(flatten)]
+    ticket_type: TicketType,
+    capacity: u32,
+}
+
+impl AuditedTicketType {
+    fn new(
+        ticket_type: &TicketType,
+        connection: &PgConnection,
+    ) -> Result<AuditedTicketType, BigNeonError> {
+        Ok(AuditedTicketType {
+            ticket_type: ticket_type.clone(),
+            capacity: ticket_type.valid_ticket_count(connection)?,
+        })
+    }
 }
 
 #[derive(Serialize, Deserialize)]
@@ -77,7 +101,12 @@ pub fn create(
     let connection = connection.get();
     let event = Event::find(path.id, connection)?;
     let organization = event.organization(connection)?;
-    user.requires_scope_for_organization(Scopes::EventWrite, &organization, connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::EventWrite,
+        &organization,
+        &event,
+        connection,
+    )?;
     //Retrieve default wallet
     let org_wallet = Wallet::find_default_for_organization(event.organization_id, connection)?;
 
@@ -94,9 +123,14 @@ pub fn create(
         data.price_in_cents,
         connection,
     )?;
+    let ticket_type = match data.fee_mode {
+        Some(fee_mode) => ticket_type.update_fee_mode(Some(fee_mode), connection)?,
+        None => ticket_type,
+    };
     //Add each ticket pricing entry for newly created ticket type
+    let mut ticket_pricing = Vec::new();
     for current_pricing_entry in &data.ticket_pricing {
-        let _pricing_result = ticket_type.add_ticket_pricing(
+        let pricing_result = ticket_type.add_ticket_pricing(
             current_pricing_entry.name.clone(),
             current_pricing_entry.start_date,
             current_pricing_entry.end_date,
@@ -105,10 +139,20 @@ pub fn create(
             None,
             connection,
         )?;
+        ticket_pricing.push(pricing_result);
     }
 
     ticket_type.validate_ticket_pricing(connection)?;
 
+    audit_log(&user, &event, Tables::TicketTypes, ticket_type.id)
+        .created(&AuditedTicketType::new(&ticket_type, connection)?)?
+        .commit(connection)?;
+    for pricing in &ticket_pricing {
+        audit_log(&user, &event, Tables::TicketPricing, pricing.id)
+            .created(pricing)?
+            .commit(connection)?;
+    }
+
     // TODO: move this to an async processor...
 
     let tari_asset_id = state.config.tari_client.create_asset(
@@ -144,7 +188,12 @@ pub fn index(
     let connection = connection.get();
     let event = Event::find(path.id, connection)?;
     let organization = event.organization(connection)?;
-    user.requires_scope_for_organization(Scopes::EventWrite, &organization, connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::EventWrite,
+        &organization,
+        &event,
+        connection,
+    )?;
 
     let fee_schedule = FeeSchedule::find(organization.fee_schedule_id, connection)?;
     //TODO refactor using paging params
@@ -175,9 +224,15 @@ pub fn cancel(
     let connection = connection.get();
     let event = Event::find(path.event_id, connection)?;
     let organization = event.organization(connection)?;
-    user.requires_scope_for_organization(Scopes::EventWrite, &organization, connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::EventWrite,
+        &organization,
+        &event,
+        connection,
+    )?;
 
     let ticket_type = TicketType::find(path.ticket_type_id, connection)?;
+    let audited_ticket_type = AuditedTicketType::new(&ticket_type, connection)?;
     ticket_type.cancel(connection)?;
 
     // Reduce holds to quantity sold
@@ -187,6 +242,9 @@ pub fn cancel(
     {
         hold.remove_available_quantity(connection)?;
         if hold.quantity(connection)?.0 == 0 {
+            audit_log(&user, &event, Tables::Holds, hold.id)
+                .deleted(&hold)?
+                .commit(connection)?;
             hold.destroy(connection)?;
         }
     }
@@ -201,6 +259,14 @@ pub fn cancel(
         connection,
     )?;
 
+    let cancelled_ticket_type = TicketType::find(path.ticket_type_id, connection)?;
+    audit_log(&user, &event, Tables::TicketTypes, path.ticket_type_id)
+        .updated(
+            &audited_ticket_type,
+            &AuditedTicketType::new(&cancelled_ticket_type, connection)?,
+        )?
+        .commit(connection)?;
+
     Ok(HttpResponse::Ok().finish())
 }
 
@@ -217,11 +283,17 @@ pub fn update(
     let event = Event::find(path.event_id, connection)?;
     let organization = event.organization(connection)?;
     let fee_schedule_id = organization.fee_schedule_id;
-    user.requires_scope_for_organization(Scopes::EventWrite, &organization, connection)?;
+    user.requires_scope_for_organization_event(
+        Scopes::EventWrite,
+        &organization,
+        &event,
+        connection,
+    )?;
 
     let data = data.into_inner();
     jlog!(Debug, "Updating ticket type", {"ticket_type_id": path.ticket_type_id, "event_id":event.id, "request": &data});
     let ticket_type = TicketType::find(path.ticket_type_id, connection)?;
+    let audited_ticket_type = AuditedTicketType::new(&ticket_type, connection)?;
     if let Some(requested_capacity) = data.capacity {
         let valid_ticket_count = ticket_type.valid_ticket_count(connection)?;
         jlog!(Debug, "Update ticket type: Capacity changed", {"ticket_type_id": path.ticket_type_id, "new_capacity": requested_capacity, "old_capacity": valid_ticket_count});
@@ -274,6 +346,7 @@ pub fn update(
         increment: data.increment,
         limit_per_person: data.limit_per_person,
         price_in_cents: data.price_in_cents,
+        fee_mode: data.fee_mode,
     };
     let updated_ticket_type = ticket_type.update(update_parameters, connection)?;
 
@@ -292,6 +365,14 @@ pub fn update(
             }
             if !found_flag {
                 current_ticket_pricing.destroy(connection)?;
+                audit_log(
+                    &user,
+                    &event,
+                    Tables::TicketPricing,
+                    current_ticket_pricing.id,
+                )
+                .deleted(current_ticket_pricing)?
+                .commit(connection)?;
             }
         }
 
@@ -310,7 +391,18 @@ pub fn update(
                     .iter()
                     .position(|ref r| r.id == current_ticket_pricing_id);
                 match found_index {
-                    Some(index) => ticket_pricing[index].update(update_parameters, connection)?,
+                    Some(index) => {
+                        let updated_ticket_pricing =
+                            ticket_pricing[index].update(update_parameters, connection)?;
+                        audit_log(
+                            &user,
+                            &event,
+                            Tables::TicketPricing,
+                            current_ticket_pricing_id,
+                        )
+                        .updated(&ticket_pricing[index], &updated_ticket_pricing)?
+                        .commit(connection)?;
+                    }
                     None => {
                         return application::internal_server_error(&format!(
                             "Unable to find specified ticket pricing with id {}",
@@ -326,7 +418,7 @@ pub fn update(
             ) {
                 //Only create a new pricing entry if all of its required data was provided
                 //Add new ticket pricing
-                let _pricing_result = updated_ticket_type.add_ticket_pricing(
+                let pricing_result = updated_ticket_type.add_ticket_pricing(
                     name,
                     start_date,
                     end_date,
@@ -335,16 +427,26 @@ pub fn update(
                     None,
                     connection,
                 )?;
+                audit_log(&user, &event, Tables::TicketPricing, pricing_result.id)
+                    .created(&pricing_result)?
+                    .commit(connection)?;
             } else {
                 //TODO send error when all data was not specified
-
             }
         }
         updated_ticket_type.validate_ticket_pricing(connection)?;
     }
 
+    let updated_ticket_type = TicketType::find(path.ticket_type_id, connection)?;
+    audit_log(&user, &event, Tables::TicketTypes, updated_ticket_type.id)
+        .updated(
+            &audited_ticket_type,
+            &AuditedTicketType::new(&updated_ticket_type, connection)?,
+        )?
+        .commit(connection)?;
+
     let result = AdminDisplayTicketType::from_ticket_type(
-        &(TicketType::find(path.ticket_type_id, connection)?),
+        &updated_ticket_type,
         &FeeSchedule::find(fee_schedule_id, connection)?,
         connection,
     )?;
@@ -393,3 +495,14 @@ fn nullify_tickets(
 
     Ok(())
 }
+
+fn audit_log(user: &User, event: &Event, main_table: Tables, main_id: Uuid) -> NewAuditLog {
+    AuditLog::create(
+        Some(user.id()),
+        Some(Scopes::EventWrite),
+        main_table,
+        main_id,
+        Some(event.organization_id),
+        Some(event.id),
+    )
+}
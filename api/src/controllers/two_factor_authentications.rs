@@ -0,0 +1,104 @@
+use actix_web::{HttpResponse, State};
+use auth::user::User as AuthUser;
+use bigneon_db::models::TwoFactorAuthentication;
+use db::Connection;
+use errors::*;
+use extractors::*;
+use helpers::application;
+use server::AppState;
+use url::percent_encoding::{utf8_percent_encode, USERINFO_ENCODE_SET};
+
+#[derive(Deserialize)]
+pub struct EnableTwoFactorAuthenticationRequest {
+    pub code: String,
+}
+
+/// The secret is only shown in the response to enrolment, `provisioning_uri` is what the
+/// authenticator app reads from the QR code
+#[derive(Serialize)]
+pub struct TwoFactorAuthenticationEnrolment {
+    #[serde(flatten)]
+    pub two_factor_authentication: TwoFactorAuthentication,
+    pub secret: String,
+    pub provisioning_uri: String,
+}
+
+#[derive(Serialize)]
+pub struct TwoFactorAuthenticationRecoveryCodes {
+    pub recovery_codes: Vec<String>,
+}
+
+pub fn show((connection, user): (Connection, AuthUser)) -> Result<HttpResponse, BigNeonError> {
+    match TwoFactorAuthentication::find_for_user(user.id(), connection.get())? {
+        Some(two_factor_authentication) => Ok(HttpResponse::Ok().json(&two_factor_authentication)),
+        None => application::not_found(),
+    }
+}
+
+/// Starts enrolment, two factor authentication is only enabled once a code from the
+/// authenticator app has been confirmed
+pub fn create(
+    (state, connection, user): (State<AppState>, Connection, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let (two_factor_authentication, secret) = TwoFactorAuthentication::create(
+        user.id(),
+        &state.config.api_keys_encryption_key,
+        connection.get(),
+    )?;
+
+    let issuer = utf8_percent_encode(&state.config.app_name, USERINFO_ENCODE_SET).to_string();
+    let account = utf8_percent_encode(
+        &user.email().unwrap_or_else(|| user.id().to_string()),
+        USERINFO_ENCODE_SET,
+    )
+    .to_string();
+    let provisioning_uri = format!(
+        "otpauth://totp/{}:{}?secret={}&issuer={}",
+        issuer, account, secret, issuer
+    );
+    Ok(
+        HttpResponse::Created().json(&TwoFactorAuthenticationEnrolment {
+            two_factor_authentication,
+            secret,
+            provisioning_uri,
+        }),
+    )
+}
+
+pub fn enable(
+    (state, connection, json, user): (
+        State<AppState>,
+        Connection,
+        Json<EnableTwoFactorAuthenticationRequest>,
+        AuthUser,
+    ),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let two_factor_authentication =
+        match TwoFactorAuthentication::find_for_user(user.id(), connection)? {
+            Some(two_factor_authentication) => two_factor_authentication,
+            None => return application::not_found(),
+        };
+    let recovery_codes = two_factor_authentication.enable(
+        &json.code,
+        &state.config.api_keys_encryption_key,
+        connection,
+    )?;
+    Ok(HttpResponse::Ok().json(&TwoFactorAuthenticationRecoveryCodes { recovery_codes }))
+}
+
+/// Turning two factor authentication off needs a current code, so a stolen session alone
+/// can not remove it
+pub fn destroy(
+    (state, conn, user): (State<AppState>, Connection, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = conn.get();
+    let two_factor_authentication =
+        match TwoFactorAuthentication::find_for_user(user.id(), connection)? {
+            Some(two_factor_authentication) => two_factor_authentication,
+            None => return application::not_found(),
+        };
+    user.requires_two_factor_verification(&state, &conn)?;
+    two_factor_authentication.destroy(connection)?;
+    application::no_content()
+}
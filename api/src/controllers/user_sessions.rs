@@ -0,0 +1,34 @@
+use actix_web::{HttpResponse, Path};
+use auth::user::User as AuthUser;
+use bigneon_db::models::UserSession;
+use db::Connection;
+use errors::*;
+use helpers::application;
+use models::PathParameters;
+
+/// Devices the user is signed in on
+pub fn index((connection, user): (Connection, AuthUser)) -> Result<HttpResponse, BigNeonError> {
+    let sessions = UserSession::find_active_for_user(user.id(), connection.get())?;
+    Ok(HttpResponse::Ok().json(&sessions))
+}
+
+/// Signs a device out, its refresh token stops working and its access token is not renewed
+pub fn destroy(
+    (connection, path, user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let session = UserSession::find(path.id, connection)?;
+    if session.user_id != user.id() || session.is_revoked() {
+        return application::not_found();
+    }
+    session.revoke(connection)?;
+    application::no_content()
+}
+
+/// Signs the user out on every device
+pub fn destroy_all(
+    (connection, user): (Connection, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    UserSession::revoke_all_for_user(user.id(), connection.get())?;
+    application::no_content()
+}
@@ -5,6 +5,7 @@ use auth::user::User as AuthUser;
 use bigneon_db::models::*;
 use communications::mailers;
 use controllers::auth;
+use controllers::email_verifications;
 use controllers::auth::LoginRequest;
 use db::Connection;
 use diesel::PgConnection;
@@ -26,10 +27,14 @@ pub struct SearchUserByEmail {
 #[derive(Serialize)]
 pub struct CurrentUser {
     pub user: DisplayUser,
+    pub email_verified: bool,
+    pub phone_verified: bool,
     pub roles: Vec<Roles>,
     pub scopes: Vec<Scopes>,
     pub organization_roles: HashMap<Uuid, Vec<Roles>>,
     pub organization_scopes: HashMap<Uuid, Vec<Scopes>>,
+    pub event_roles: HashMap<Uuid, Vec<Roles>>,
+    pub event_scopes: HashMap<Uuid, Vec<Scopes>>,
 }
 
 impl Responder for CurrentUser {
@@ -129,6 +134,37 @@ pub fn show(
     Ok(HttpResponse::Ok().json(&user.for_display()?))
 }
 
+/// Everything held about the user as a downloadable JSON archive
+pub fn export_data(
+    (connection, parameters, auth_user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let user = User::find(parameters.id, connection)?;
+    if !(auth_user.user == user || auth_user.user.is_admin()) {
+        return application::unauthorized(Some(auth_user), None);
+    }
+
+    let data = user.export_data(connection)?;
+    Ok(HttpResponse::Ok()
+        .header(
+            "Content-Disposition",
+            format!("attachment; filename=\"user-data-{}.json\"", user.id),
+        )
+        .json(&data))
+}
+
+/// Anonymizes the user in response to an erasure request, financial records are kept
+pub fn erase(
+    (connection, parameters, auth_user): (Connection, Path<PathParameters>, AuthUser),
+) -> Result<HttpResponse, BigNeonError> {
+    if !auth_user.user.is_admin() {
+        return application::unauthorized(Some(auth_user), None);
+    }
+    let connection = connection.get();
+    let user = User::find(parameters.id, connection)?.erase(Some(auth_user.id()), connection)?;
+    Ok(HttpResponse::Ok().json(&user.for_display()?))
+}
+
 pub fn list_organizations(
     (connection, parameters, query_parameters, auth_user): (
         Connection,
@@ -238,11 +274,12 @@ pub fn register(
     }
 
     let new_user: NewUser = parameters.into_inner().into();
-    new_user.commit(connection.get())?;
+    let user = new_user.commit(connection.get())?;
 
     if let (Some(first_name), Some(email)) = (new_user.first_name, new_user.email) {
         mailers::user::user_registered(first_name, email, &state.config, connection.get())?;
     }
+    email_verifications::send_email_verification(state, &user, connection.get())?;
 
     Ok(HttpResponse::Created().finish())
 }
@@ -277,13 +314,14 @@ pub fn register_and_login(
     let email = parameters.email.clone();
     let password = parameters.password.clone();
     let new_user: NewUser = parameters.into_inner().into();
-    new_user.commit(connection.get())?;
+    let user = new_user.commit(connection.get())?;
     let json = Json(LoginRequest::new(&email, &password));
     let token_response = auth::token((http_request.clone(), connection.clone(), json))?;
 
     if let (Some(first_name), Some(email)) = (new_user.first_name, new_user.email) {
         mailers::user::user_registered(first_name, email, &state.config, connection.get())?;
     }
+    email_verifications::send_email_verification(state, &user, connection.get())?;
 
     Ok(HttpResponse::Created().json(token_response))
 }
@@ -297,13 +335,27 @@ fn current_user_from_user(
     for (organization_id, roles) in &roles_by_organization {
         scopes_by_organization.insert(organization_id.clone(), scopes::get_scopes(roles.clone()));
     }
+    // Events the user was given a role on without being a member of their organization
+    let mut roles_by_event = HashMap::new();
+    let mut scopes_by_event = HashMap::new();
+    for event_user in EventUser::find_for_user(user.id, connection)? {
+        roles_by_event.insert(event_user.event_id, vec![event_user.role]);
+        scopes_by_event.insert(
+            event_user.event_id,
+            scopes::get_scopes(vec![event_user.role]),
+        );
+    }
 
     Ok(CurrentUser {
         user: user.clone().for_display()?,
+        email_verified: user.is_email_verified(),
+        phone_verified: user.is_phone_verified(),
         roles: user.role.clone(),
         scopes: user.get_global_scopes(),
         organization_roles: roles_by_organization,
         organization_scopes: scopes_by_organization,
+        event_roles: roles_by_event,
+        event_scopes: scopes_by_event,
     })
 }
 
@@ -1,10 +1,12 @@
-use actix_web::{HttpResponse, Path, Query};
+use actix_web::{HttpResponse, Path, Query, State};
 use auth::user::User;
 use bigneon_db::models::*;
 use db::Connection;
 use errors::*;
 use extractors::*;
 use models::{AddVenueToOrganizationRequest, PathParameters};
+use server::AppState;
+use utils::ical;
 
 pub fn index(
     (connection, query_parameters, user): (Connection, Query<PagingParameters>, OptionalUser),
@@ -31,6 +33,22 @@ pub fn show(
     Ok(HttpResponse::Ok().json(&venue))
 }
 
+pub fn calendar(
+    (state, connection, parameters): (State<AppState>, Connection, Path<PathParameters>),
+) -> Result<HttpResponse, BigNeonError> {
+    let connection = connection.get();
+    let venue = Venue::find(parameters.id, connection)?;
+    let events: Vec<(Event, Option<Venue>)> =
+        Event::find_all_active_events_for_venue(&venue.id, connection)?
+            .into_iter()
+            .map(|e| (e, Some(venue.clone())))
+            .collect();
+
+    Ok(HttpResponse::Ok()
+        .content_type(ical::ICAL_CONTENT_TYPE)
+        .body(ical::calendar(&venue.name, &events, &state.config)))
+}
+
 pub fn show_from_organizations(
     (connection, organization_id, query_parameters, user): (
         Connection,
@@ -4,13 +4,14 @@ use std::sync::mpsc::Receiver;
 use std::sync::mpsc::Sender;
 use std::thread;
 use std::thread::JoinHandle;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use log::Level::*;
 
 use bigneon_db::prelude::*;
 use config::Config;
 use db::*;
+use diesel::PgConnection;
 use domain_events::errors::DomainActionError;
 use domain_events::executor_future::ExecutorFuture;
 use domain_events::routing::DomainActionRouter;
@@ -25,6 +26,9 @@ use tokio::timer::Timeout;
 //    None
 //}
 
+/// How often the housekeeping in `run_periodic_tasks` is done
+const PERIODIC_TASKS_INTERVAL_IN_SECS: u64 = 60;
+
 pub struct DomainActionMonitor {
     config: Config,
     database: Database,
@@ -51,6 +55,7 @@ impl DomainActionMonitor {
     pub fn run_til_empty(&self) -> Result<(), DomainActionError> {
         //let publisher = DomainActionMonitor::get_publisher();
         let router = DomainActionMonitor::create_router(&self.config);
+        DomainActionMonitor::run_periodic_tasks_logging_errors(&self.database);
 
         loop {
             let mut num_processed = 0;
@@ -130,6 +135,46 @@ impl DomainActionMonitor {
     //        Ok(())
     //    }
 
+    /// Housekeeping that is not triggered by a domain action
+    pub fn run_periodic_tasks(connection: &PgConnection) -> Result<(), DomainActionError> {
+        let deleted_rate_limit_counters = RateLimitCounter::delete_expired(connection)?;
+        if deleted_rate_limit_counters > 0 {
+            jlog!(
+                Debug,
+                "bigneon::domain_actions",
+                "Removed expired rate limit counters",
+                { "count": deleted_rate_limit_counters }
+            );
+        }
+
+        // Subscriptions whose delivery action expired or failed would otherwise never be
+        // delivered again
+        for subscription in ReportSubscription::schedule_overdue_deliveries(connection)? {
+            jlog!(
+                Info,
+                "bigneon::domain_actions",
+                "Scheduled overdue report delivery",
+                { "report_subscription_id": subscription.id }
+            );
+        }
+        Ok(())
+    }
+
+    fn run_periodic_tasks_logging_errors(database: &Database) {
+        let result = database
+            .get_connection()
+            .map_err(DomainActionError::from)
+            .and_then(|connection| DomainActionMonitor::run_periodic_tasks(connection.get()));
+        if let Err(e) = result {
+            jlog!(
+                Error,
+                "bigneon::domain_actions",
+                "Periodic tasks failed",
+                { "error": e.description() }
+            );
+        }
+    }
+
     fn create_router(conf: &Config) -> DomainActionRouter {
         let mut router = DomainActionRouter::new();
 
@@ -232,6 +277,7 @@ impl DomainActionMonitor {
         let mut runtime = Runtime::new()?;
 
         //let connection = database.get_connection();
+        let mut periodic_tasks_run_at: Option<Instant> = None;
 
         loop {
             if rx.try_recv().is_ok() {
@@ -243,6 +289,14 @@ impl DomainActionMonitor {
                 );
                 break;
             }
+            if periodic_tasks_run_at
+                .map(|run_at| run_at.elapsed().as_secs() >= PERIODIC_TASKS_INTERVAL_IN_SECS)
+                .unwrap_or(true)
+            {
+                DomainActionMonitor::run_periodic_tasks_logging_errors(&database);
+                periodic_tasks_run_at = Some(Instant::now());
+            }
+
             //Check for actions that are due to be processed
 
             let futures = DomainActionMonitor::find_actions(
@@ -1,4 +1,6 @@
 pub mod marketing_contacts;
 pub mod process_payment_ipn;
+pub mod send_campaign;
 pub mod send_communication;
 pub mod send_order_complete;
+pub mod send_scheduled_report;
@@ -15,6 +15,7 @@ pub struct ProcessPaymentIPNExecutor {
     globee_api_key: String,
     globee_base_url: String,
     validate_ipn: bool,
+    primary_currency: String,
 }
 
 impl DomainActionExecutor for ProcessPaymentIPNExecutor {
@@ -35,6 +36,7 @@ impl ProcessPaymentIPNExecutor {
             globee_api_key: config.globee_api_key.clone(),
             globee_base_url: config.globee_base_url.clone(),
             validate_ipn: config.validate_ipns,
+            primary_currency: config.primary_currency.clone(),
         }
     }
 
@@ -107,6 +109,7 @@ impl ProcessPaymentIPNExecutor {
                     status,
                     None,
                     action.payload.clone(),
+                    &self.primary_currency,
                     connection,
                 )?
             }
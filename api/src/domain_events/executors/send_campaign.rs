@@ -0,0 +1,89 @@
+use bigneon_db::prelude::*;
+use chrono::prelude::*;
+use chrono::Duration;
+use communications::campaigns::campaign_communication;
+use config::Config;
+use db::Connection;
+use domain_events::executor_future::ExecutorFuture;
+use domain_events::routing::DomainActionExecutor;
+use errors::*;
+use futures::future;
+use log::Level::Error;
+
+pub struct SendCampaignExecutor {
+    config: Config,
+}
+
+impl DomainActionExecutor for SendCampaignExecutor {
+    fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
+        match self.perform_job(&action, &conn) {
+            Ok(_) => ExecutorFuture::new(action, conn, Box::new(future::ok(()))),
+            Err(e) => {
+                jlog!(Error, "Send campaign action failed", {"action_id": action.id, "main_table_id":action.main_table_id,  "error": e.to_string()});
+                ExecutorFuture::new(action, conn, Box::new(future::err(e)))
+            }
+        }
+    }
+}
+
+impl SendCampaignExecutor {
+    pub fn new(config: Config) -> SendCampaignExecutor {
+        SendCampaignExecutor { config }
+    }
+
+    /// Queues the messages for the next `batch_size` recipients, the following batch is
+    /// scheduled `batch_interval_in_seconds` later until every recipient has been queued
+    pub fn perform_job(
+        &self,
+        action: &DomainAction,
+        conn: &Connection,
+    ) -> Result<(), BigNeonError> {
+        let conn = conn.get();
+        let campaign = Campaign::find(
+            action.main_table_id.ok_or(ApplicationError::new(
+                "No campaign id supplied in the action".to_string(),
+            ))?,
+            conn,
+        )?;
+        // Cancelled campaigns are not sent any further
+        if campaign.status != CampaignStatus::Sending {
+            return Ok(());
+        }
+
+        campaign.record_recipients(conn)?;
+        let organization = campaign.organization(conn)?;
+        let now = Utc::now().naive_utc();
+        for recipient in campaign.pending_recipients(campaign.batch_size as i64, conn)? {
+            let address = recipient.address.clone().ok_or(ApplicationError::new(
+                "Campaign recipient has no address".to_string(),
+            ))?;
+            let mut communication = campaign_communication(
+                &self.config,
+                &campaign,
+                &organization,
+                address,
+                Some(recipient.unsubscribe_token),
+            )?;
+            communication.subject_to_preferences(
+                recipient.user_id,
+                Some(organization.id),
+                CommunicationCategory::Marketing,
+            );
+            let communication_action = communication.queue_at(
+                now,
+                Some(Tables::CampaignRecipients),
+                Some(recipient.id),
+                conn,
+            )?;
+            recipient.mark_queued(communication_action.id, conn)?;
+        }
+
+        if campaign.recipient_counts(conn)?.pending > 0 {
+            campaign.schedule_batch(
+                now + Duration::seconds(campaign.batch_interval_in_seconds as i64),
+                conn,
+            )?;
+        }
+        Ok(())
+    }
+}
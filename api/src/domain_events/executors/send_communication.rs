@@ -3,6 +3,10 @@ use config::Config;
 use db::Connection;
 use domain_events::executor_future::ExecutorFuture;
 use domain_events::routing::DomainActionExecutor;
+use errors::*;
+use futures::future;
+use log::Level::Info;
+use serde_json;
 use utils::communication::Communication;
 
 pub struct SendCommunicationExecutor {
@@ -13,11 +17,27 @@ impl SendCommunicationExecutor {
     pub fn new(config: Config) -> SendCommunicationExecutor {
         SendCommunicationExecutor { config }
     }
+
+    /// Preferences are checked when sending rather than when queueing, as the recipient may
+    /// unsubscribe while the communication is waiting to be sent
+    fn is_allowed(&self, action: &DomainAction, conn: &Connection) -> Result<bool, BigNeonError> {
+        let communication: Communication = serde_json::from_value(action.payload.clone())?;
+        communication.is_allowed(conn.get())
+    }
 }
 
 impl DomainActionExecutor for SendCommunicationExecutor {
     fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
-        let future = Communication::send_async(&action, &self.config);
-        ExecutorFuture::new(action, conn, Box::new(future))
+        match self.is_allowed(&action, &conn) {
+            Ok(true) => {
+                let future = Communication::send_async(&action, &self.config);
+                ExecutorFuture::new(action, conn, Box::new(future))
+            }
+            Ok(false) => {
+                jlog!(Info, "Communication not sent, the recipient has unsubscribed", {"action_id": action.id});
+                ExecutorFuture::new(action, conn, Box::new(future::ok(())))
+            }
+            Err(e) => ExecutorFuture::new(action, conn, Box::new(future::err(e))),
+        }
     }
 }
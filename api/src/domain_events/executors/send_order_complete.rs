@@ -90,13 +90,24 @@ impl SendOrderCompleteExecutor {
         }
 
         let display_order = order.for_display(None, conn)?;
+        let mut events = Vec::new();
+        for event in order.events(conn)? {
+            let venue = event.venue(conn)?;
+            events.push((event, venue));
+        }
 
         let user = User::find(order.on_behalf_of_user_id.unwrap_or(order.user_id), conn)?;
 
         //Communicate purchase completed to user
         if let (Some(first_name), Some(email)) = (user.first_name, user.email) {
-            mailers::cart::purchase_completed(&first_name, email, display_order, &self.config)?
-                .queue(conn)?;
+            mailers::cart::purchase_completed(
+                &first_name,
+                email,
+                display_order,
+                &events,
+                &self.config,
+            )?
+            .queue(conn)?;
         }
         Ok(())
     }
@@ -0,0 +1,100 @@
+use bigneon_db::prelude::*;
+use chrono::prelude::*;
+use communications::mailers;
+use config::Config;
+use db::Connection;
+use diesel::PgConnection;
+use domain_events::executor_future::ExecutorFuture;
+use domain_events::routing::DomainActionExecutor;
+use errors::*;
+use futures::future;
+use log::Level::Error;
+use utils::report_export;
+
+pub struct SendScheduledReportExecutor {
+    config: Config,
+}
+
+impl DomainActionExecutor for SendScheduledReportExecutor {
+    fn execute(&self, action: DomainAction, conn: Connection) -> ExecutorFuture {
+        match self.perform_job(&action, &conn) {
+            Ok(_) => ExecutorFuture::new(action, conn, Box::new(future::ok(()))),
+            Err(e) => {
+                jlog!(Error, "Send scheduled report action failed", {"action_id": action.id, "main_table_id":action.main_table_id,  "error": e.to_string()});
+                ExecutorFuture::new(action, conn, Box::new(future::err(e)))
+            }
+        }
+    }
+}
+
+impl SendScheduledReportExecutor {
+    pub fn new(config: Config) -> SendScheduledReportExecutor {
+        SendScheduledReportExecutor { config }
+    }
+
+    /// Failures to build or send the report are recorded against the subscription rather
+    /// than retried, the next delivery is scheduled either way
+    pub fn perform_job(
+        &self,
+        action: &DomainAction,
+        conn: &Connection,
+    ) -> Result<(), BigNeonError> {
+        let conn = conn.get();
+        let subscription = ReportSubscription::find(
+            action.main_table_id.ok_or(ApplicationError::new(
+                "No report subscription id supplied in the action".to_string(),
+            ))?,
+            conn,
+        )?;
+        // Unsubscribed or already delivered for this period
+        if subscription.unsubscribed_at.is_some()
+            || subscription.next_delivery_at > Utc::now().naive_utc()
+        {
+            return Ok(());
+        }
+
+        let (status, error) = match self.send_report(&subscription, conn) {
+            Ok(_) => (ReportDeliveryStatus::Sent, None),
+            Err(e) => {
+                jlog!(Error, "Could not send scheduled report", {"report_subscription_id": subscription.id, "error": e.to_string()});
+                (ReportDeliveryStatus::Failed, Some(e.to_string()))
+            }
+        };
+        subscription.complete_delivery(status, error, conn)?;
+        Ok(())
+    }
+
+    fn send_report(
+        &self,
+        subscription: &ReportSubscription,
+        conn: &PgConnection,
+    ) -> Result<(), BigNeonError> {
+        let user = User::find(subscription.user_id, conn)?;
+        let organization = Organization::find(subscription.organization_id, conn)?;
+        let scope = subscription
+            .report_type
+            .required_scope(subscription.event_id);
+        if !organization
+            .get_scopes_for_user(&user, conn)?
+            .contains(&scope)
+        {
+            return Err(ApplicationError::new(
+                "User no longer has access to this report".to_string(),
+            )
+            .into());
+        }
+        let email = user.email.ok_or(ApplicationError::new(
+            "User does not have an email address".to_string(),
+        ))?;
+
+        let report = report_export::render_subscription(subscription, conn)?;
+        mailers::reports::scheduled_report_email(
+            &self.config,
+            email,
+            subscription,
+            &organization,
+            &report,
+            conn,
+        )
+    }
+}
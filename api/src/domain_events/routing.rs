@@ -8,8 +8,10 @@ use domain_events::executors::marketing_contacts::{
     BulkEventFanListImportExecutor, CreateEventListExecutor,
 };
 use domain_events::executors::process_payment_ipn::ProcessPaymentIPNExecutor;
+use domain_events::executors::send_campaign::SendCampaignExecutor;
 use domain_events::executors::send_communication::SendCommunicationExecutor;
 use domain_events::executors::send_order_complete::SendOrderCompleteExecutor;
+use domain_events::executors::send_scheduled_report::SendScheduledReportExecutor;
 use std::borrow::Borrow;
 use std::collections::HashMap;
 
@@ -64,9 +66,11 @@ impl DomainActionRouter {
                 PaymentProviderIPN => Box::new(ProcessPaymentIPNExecutor::new(&conf)),
                 SendPurchaseCompletedCommunication => {
                     Box::new(SendOrderCompleteExecutor::new(conf))
-                } //
-                  // DO NOT add
-                  // _ =>
+                }
+                SendCampaign => Box::new(SendCampaignExecutor::new(conf)),
+                SendScheduledReport => Box::new(SendScheduledReportExecutor::new(conf)),
+                // DO NOT add
+                // _ =>
             }
         };
 
@@ -93,5 +97,11 @@ impl DomainActionRouter {
             find_executor(SendPurchaseCompletedCommunication),
         )
         .expect("Configuration error");
+
+        self.add_executor(SendCampaign, find_executor(SendCampaign))
+            .expect("Configuration error");
+
+        self.add_executor(SendScheduledReport, find_executor(SendScheduledReport))
+            .expect("Configuration error");
     }
 }
@@ -17,6 +17,7 @@ use std::error::Error;
 use std::fmt;
 use tari_client::TariError;
 use uuid::ParseError as UuidParseError;
+use zip::result::ZipError;
 
 #[derive(Debug)]
 pub struct BigNeonError(Box<ConvertToWebError + Send + Sync>);
@@ -40,6 +41,7 @@ error_conversion!(EmailBuilderError);
 error_conversion!(EnumParseError);
 error_conversion!(JwtError);
 error_conversion!(PaymentProcessorError);
+error_conversion!(RateLimitError);
 error_conversion!(ReqwestError);
 error_conversion!(ReqwestToStrError);
 error_conversion!(SerdeError);
@@ -47,6 +49,7 @@ error_conversion!(SmtpError);
 error_conversion!(TariError);
 error_conversion!(UuidParseError);
 error_conversion!(GlobeeError);
+error_conversion!(ZipError);
 
 impl fmt::Display for BigNeonError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
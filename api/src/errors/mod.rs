@@ -1,9 +1,11 @@
 pub use self::application_error::*;
 pub use self::auth_error::*;
 pub use self::big_neon_error::*;
+pub use self::rate_limit_error::*;
 pub use self::web_error::*;
 
 mod application_error;
 mod auth_error;
 mod big_neon_error;
+mod rate_limit_error;
 mod web_error;
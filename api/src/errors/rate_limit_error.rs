@@ -0,0 +1,25 @@
+use std::error::Error;
+use std::fmt;
+
+/// A request refused because too many have been made, `retry_after` is in seconds
+#[derive(Debug)]
+pub struct RateLimitError {
+    pub reason: String,
+    pub retry_after: i64,
+}
+
+impl RateLimitError {
+    pub fn new(reason: String, retry_after: i64) -> RateLimitError {
+        RateLimitError {
+            reason,
+            retry_after,
+        }
+    }
+}
+
+impl fmt::Display for RateLimitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{}", self.reason)
+    }
+}
+impl Error for RateLimitError {}
@@ -18,6 +18,7 @@ use std::string::ToString;
 use stripe::StripeError;
 use tari_client::TariError;
 use uuid::ParseError as UuidParseError;
+use zip::result::ZipError;
 
 pub trait ConvertToWebError: Debug + Error + ToString {
     fn to_response(&self) -> HttpResponse;
@@ -89,6 +90,13 @@ impl ConvertToWebError for UuidParseError {
     }
 }
 
+impl ConvertToWebError for ZipError {
+    fn to_response(&self) -> HttpResponse {
+        error!("Zip error: {}", self);
+        internal_error("Internal error")
+    }
+}
+
 impl ConvertToWebError for EmailBuilderError {
     fn to_response(&self) -> HttpResponse {
         error!("Email Builder error: {}", self);
@@ -175,6 +183,16 @@ impl ConvertToWebError for AuthError {
     }
 }
 
+impl ConvertToWebError for RateLimitError {
+    fn to_response(&self) -> HttpResponse {
+        warn!("Rate limit error: {}", self.reason);
+        HttpResponse::new(StatusCode::TOO_MANY_REQUESTS)
+            .into_builder()
+            .header("Retry-After", self.retry_after.to_string())
+            .json(json!({"error": self.reason.to_string()}))
+    }
+}
+
 impl ConvertToWebError for DatabaseError {
     fn to_response(&self) -> HttpResponse {
         match self.code {
@@ -2,7 +2,10 @@ use actix_web::error::*;
 use actix_web::{FromRequest, HttpRequest};
 use auth::claims;
 use auth::user::User;
+use bigneon_db::models::OrganizationApiKey;
 use bigneon_db::models::User as DbUser;
+use bigneon_db::models::UserSession;
+use bigneon_db::utils::errors::Optional;
 use errors::*;
 use jwt::{decode, Validation};
 use middleware::RequestConnection;
@@ -19,8 +22,26 @@ impl FromRequest<AppState> for User {
                     .to_str()
                     .map_err(|e| BigNeonError::from(e))?
                     .split_whitespace();
-                if str::ne(parts.next().unwrap_or("None"), "Bearer") {
-                    return Err(ErrorUnauthorized("Authorization scheme not supported"));
+                match parts.next().unwrap_or("None") {
+                    "Bearer" => (),
+                    "ApiKey" => {
+                        let key = match parts.next() {
+                            Some(key) => key,
+                            None => return Err(ErrorUnauthorized("No API key provided")),
+                        };
+                        let connection = req.connection()?;
+                        let connection = connection.get();
+                        let api_key = OrganizationApiKey::find_active_by_key(key, connection)
+                            .map_err(|_| ErrorUnauthorized("Invalid API key"))?;
+                        api_key
+                            .mark_used(connection)
+                            .map_err(|e| ErrorInternalServerError(e))?;
+                        return match DbUser::find(api_key.user_id, connection) {
+                            Ok(user) => Ok(User::for_api_key(user, api_key, req)),
+                            Err(e) => Err(ErrorInternalServerError(e)),
+                        };
+                    }
+                    _ => return Err(ErrorUnauthorized("Authorization scheme not supported")),
                 }
 
                 match parts.next() {
@@ -31,8 +52,17 @@ impl FromRequest<AppState> for User {
                             &Validation::default(),
                         )
                         .map_err(|e| BigNeonError::from(e))?;
+                        let user_id = token.claims.get_id()?;
                         let connection = req.connection()?;
-                        match DbUser::find(token.claims.get_id()?, connection.get()) {
+                        if let Some(session_id) = token.claims.sid {
+                            match UserSession::find(session_id, connection.get()).optional() {
+                                Ok(Some(ref session))
+                                    if session.user_id == user_id && !session.is_revoked() => {}
+                                Ok(_) => return Err(ErrorUnauthorized("Invalid token")),
+                                Err(e) => return Err(ErrorInternalServerError(e)),
+                            }
+                        }
+                        match DbUser::find(user_id, connection.get()) {
                             Ok(user) => Ok(User::new(user, req)
                                 .map_err(|_| ErrorUnauthorized("User has invalid role data"))?),
                             Err(e) => Err(ErrorInternalServerError(e)),
@@ -31,6 +31,10 @@ pub fn forbidden<T: Responder>(message: &str) -> Result<T, BigNeonError> {
     Err(AuthError::new(AuthErrorType::Forbidden, message.into()).into())
 }
 
+pub fn too_many_requests<T: Responder>(message: &str, retry_after: i64) -> Result<T, BigNeonError> {
+    Err(RateLimitError::new(message.to_string(), retry_after).into())
+}
+
 pub fn unprocessable<T: Responder>(message: &str) -> Result<T, BigNeonError> {
     Err(
         ApplicationError::new_with_type(ApplicationErrorType::Unprocessable, message.to_string())
@@ -7,9 +7,12 @@
 #![deny(unused_must_use)]
 #![cfg_attr(not(debug_assertions), deny(unused_extern_crates))]
 extern crate actix_web;
+extern crate base64;
 extern crate bigneon_db;
+extern crate bytes;
 //#[macro_use]
 extern crate chrono;
+extern crate chrono_tz;
 extern crate diesel;
 extern crate dotenv;
 extern crate futures;
@@ -42,6 +45,7 @@ extern crate uuid;
 extern crate validator;
 #[macro_use]
 extern crate validator_derive;
+extern crate zip;
 
 pub mod auth;
 pub mod communications;
@@ -1,7 +1,11 @@
 pub use self::app_version_header::*;
 pub use self::big_neon_logger::*;
 pub use self::database_transaction::*;
+pub use self::rate_limit_store::*;
+pub use self::rate_limiter::*;
 
 mod app_version_header;
 mod big_neon_logger;
 mod database_transaction;
+mod rate_limit_store;
+mod rate_limiter;
@@ -0,0 +1,113 @@
+use bigneon_db::models::RateLimitCounter;
+use config::{Config, RateLimitStoreType};
+use db::Database;
+use errors::*;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+// Expired counters are only removed once there are this many
+const MEMORY_PRUNE_THRESHOLD: usize = 10_000;
+
+lazy_static! {
+    // Shared by all workers of this instance
+    static ref MEMORY_COUNTERS: Arc<Mutex<HashMap<String, MemoryCounter>>> =
+        Arc::new(Mutex::new(HashMap::new()));
+}
+
+/// Keeps the requests counted against rate limit keys, e.g. `auth_token:ip:127.0.0.1`
+pub trait RateLimitStore {
+    /// Counts a hit against `key`, starting a new window of `period_in_seconds` if the last
+    /// one has ended. Returns the seconds until another request is allowed once `limit` has
+    /// been exceeded.
+    fn hit(
+        &self,
+        key: &str,
+        limit: i32,
+        period_in_seconds: i64,
+    ) -> Result<Option<i64>, BigNeonError>;
+}
+
+pub fn create_rate_limit_store(config: &Config, database: &Database) -> Box<RateLimitStore> {
+    match config.rate_limit_store {
+        RateLimitStoreType::Database => Box::new(DatabaseRateLimitStore::new(database.clone())),
+        RateLimitStoreType::Memory => Box::new(MemoryRateLimitStore::new()),
+    }
+}
+
+/// Counters in the `rate_limit_counters` table, shared by every instance of the API
+pub struct DatabaseRateLimitStore {
+    database: Database,
+}
+
+impl DatabaseRateLimitStore {
+    pub fn new(database: Database) -> DatabaseRateLimitStore {
+        DatabaseRateLimitStore { database }
+    }
+}
+
+impl RateLimitStore for DatabaseRateLimitStore {
+    fn hit(
+        &self,
+        key: &str,
+        limit: i32,
+        period_in_seconds: i64,
+    ) -> Result<Option<i64>, BigNeonError> {
+        // Counted outside of the request's transaction, which is rolled back for failed requests
+        let connection = self.database.get_connection()?;
+        let counter = RateLimitCounter::hit(key, period_in_seconds, connection.get())?;
+        Ok(counter.retry_after(limit))
+    }
+}
+
+struct MemoryCounter {
+    hits: i32,
+    window_ends_at: Instant,
+}
+
+/// Counters kept in this instance of the API, each instance enforces the limits on its own
+pub struct MemoryRateLimitStore {
+    counters: Arc<Mutex<HashMap<String, MemoryCounter>>>,
+}
+
+impl MemoryRateLimitStore {
+    pub fn new() -> MemoryRateLimitStore {
+        MemoryRateLimitStore {
+            counters: MEMORY_COUNTERS.clone(),
+        }
+    }
+}
+
+impl RateLimitStore for MemoryRateLimitStore {
+    fn hit(
+        &self,
+        key: &str,
+        limit: i32,
+        period_in_seconds: i64,
+    ) -> Result<Option<i64>, BigNeonError> {
+        let now = Instant::now();
+        let mut counters = self
+            .counters
+            .lock()
+            .map_err(|_| ApplicationError::new("Could not update rate limit".to_string()))?;
+        if counters.len() >= MEMORY_PRUNE_THRESHOLD {
+            counters.retain(|_, counter| counter.window_ends_at > now);
+        }
+
+        let counter = counters.entry(key.to_string()).or_insert(MemoryCounter {
+            hits: 0,
+            window_ends_at: now,
+        });
+        if counter.window_ends_at <= now {
+            counter.hits = 0;
+            counter.window_ends_at = now + Duration::from_secs(period_in_seconds.max(0) as u64);
+        }
+        counter.hits += 1;
+
+        if counter.hits <= limit {
+            return Ok(None);
+        }
+        let remaining = counter.window_ends_at.duration_since(now).as_secs() as i64;
+        Ok(Some(remaining.max(1)))
+    }
+}
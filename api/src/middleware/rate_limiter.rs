@@ -0,0 +1,150 @@
+use actix_web::middleware::{Middleware, Started};
+use actix_web::{HttpRequest, ResponseError, Result};
+use auth::claims::AccessToken;
+use config::{RateLimitConfig, RateLimitKey};
+use errors::*;
+use helpers::application;
+use jwt::{decode, Validation};
+use log::Level::Warn;
+use server::AppState;
+use std::net::{IpAddr, SocketAddr};
+
+/// Refuses requests with a 429 once one of the configured limits keyed by IP address or user
+/// is reached. Limits keyed by target are counted by the controllers, see `check_target`.
+pub struct RateLimiter {}
+
+impl RateLimiter {
+    pub fn new() -> RateLimiter {
+        RateLimiter {}
+    }
+
+    /// Counts the request against the limits for its route keyed by `target`, e.g. the email
+    /// being signed in with
+    pub fn check_target(request: &HttpRequest<AppState>, target: &str) -> Result<(), BigNeonError> {
+        RateLimiter::check(request, RateLimitKey::Target, &target.to_lowercase())
+    }
+
+    /// Counts the request against the limits for its route keyed by `key`, refusing it once one
+    /// is reached
+    pub fn check(
+        request: &HttpRequest<AppState>,
+        key: RateLimitKey,
+        value: &str,
+    ) -> Result<(), BigNeonError> {
+        let state = request.state();
+        let rate_limits: Vec<&RateLimitConfig> = state
+            .config
+            .rate_limits
+            .iter()
+            .filter(|r| r.key == key && route_matches(r, request.method().as_str(), request.path()))
+            .collect();
+        if rate_limits.is_empty() {
+            return Ok(());
+        }
+
+        for rate_limit in rate_limits {
+            let counter_key = format!("{}:{:?}:{}", rate_limit.name, key, value).to_lowercase();
+            if let Some(retry_after) = state.rate_limit_store.hit(
+                &counter_key,
+                rate_limit.limit,
+                rate_limit.period_in_seconds,
+            )? {
+                jlog!(Warn, "Rate limit exceeded", {"key": counter_key, "retry_after": retry_after});
+                return application::too_many_requests(
+                    "Too many requests, please try again later",
+                    retry_after,
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Middleware<AppState> for RateLimiter {
+    fn start(&self, request: &HttpRequest<AppState>) -> Result<Started> {
+        for key in &[RateLimitKey::Ip, RateLimitKey::User] {
+            let value = match *key {
+                RateLimitKey::Ip => client_ip(
+                    request.peer_addr(),
+                    request
+                        .headers()
+                        .get("X-Forwarded-For")
+                        .and_then(|header| header.to_str().ok()),
+                    &request.state().config.trusted_proxies,
+                )
+                .map(|ip| ip.to_string()),
+                _ => user_id(request),
+            };
+            if let Some(value) = value {
+                if let Err(error) = RateLimiter::check(request, *key, &value) {
+                    return Ok(Started::Response(error.error_response()));
+                }
+            }
+        }
+        Ok(Started::Done)
+    }
+}
+
+/// Matches a route pattern like `/redemption_codes/{code}` against the request
+fn route_matches(rate_limit: &RateLimitConfig, method: &str, path: &str) -> bool {
+    if let Some(ref limited_method) = rate_limit.method {
+        if !limited_method.eq_ignore_ascii_case(method) {
+            return false;
+        }
+    }
+
+    let pattern: Vec<&str> = rate_limit.path.trim_end_matches('/').split('/').collect();
+    let path: Vec<&str> = path.trim_end_matches('/').split('/').collect();
+    pattern.len() == path.len()
+        && pattern
+            .iter()
+            .zip(path.iter())
+            .all(|(p, s)| p == s || (p.starts_with('{') && !s.is_empty()))
+}
+
+/// The client's address. `X-Forwarded-For` is set by the client unless a proxy replaces it, so
+/// it is only believed when the request comes from one of the `trusted_proxies`. The client is
+/// then the last address in it that is not one of those proxies.
+pub fn client_ip(
+    peer_addr: Option<SocketAddr>,
+    forwarded_for: Option<&str>,
+    trusted_proxies: &[IpAddr],
+) -> Option<IpAddr> {
+    let peer_ip = peer_addr?.ip();
+    if !trusted_proxies.contains(&peer_ip) {
+        return Some(peer_ip);
+    }
+
+    // Entries are appended by each proxy, anything left of the first untrusted one is the
+    // client's own
+    let mut client_ip = peer_ip;
+    for forwarded_ip in forwarded_for.unwrap_or("").split(',').rev() {
+        match forwarded_ip.trim().parse::<IpAddr>() {
+            Ok(forwarded_ip) => {
+                client_ip = forwarded_ip;
+                if !trusted_proxies.contains(&forwarded_ip) {
+                    break;
+                }
+            }
+            Err(_) => break,
+        }
+    }
+    Some(client_ip)
+}
+
+/// The user is taken from the access token without loading them, an invalid token is left
+/// for the controller to reject
+fn user_id(request: &HttpRequest<AppState>) -> Option<String> {
+    let header = request.headers().get("Authorization")?.to_str().ok()?;
+    let mut parts = header.split_whitespace();
+    if parts.next() != Some("Bearer") {
+        return None;
+    }
+    decode::<AccessToken>(
+        parts.next()?,
+        request.state().config.token_secret.as_bytes(),
+        &Validation::default(),
+    )
+    .ok()
+    .map(|token| token.claims.sub)
+}
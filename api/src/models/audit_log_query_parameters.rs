@@ -0,0 +1,28 @@
+use bigneon_db::models::{AuditActions, AuditLogFilters, Tables};
+use chrono::NaiveDateTime;
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct AuditLogQueryParameters {
+    pub main_table: Option<Tables>,
+    pub main_id: Option<Uuid>,
+    pub user_id: Option<Uuid>,
+    pub action: Option<AuditActions>,
+    pub start_date: Option<NaiveDateTime>,
+    pub end_date: Option<NaiveDateTime>,
+    pub page: Option<u32>,
+    pub limit: Option<u32>,
+}
+
+impl AuditLogQueryParameters {
+    pub fn filters(&self) -> AuditLogFilters {
+        AuditLogFilters {
+            main_table: self.main_table,
+            main_id: self.main_id,
+            user_id: self.user_id,
+            action: self.action,
+            start_date: self.start_date,
+            end_date: self.end_date,
+        }
+    }
+}
@@ -59,10 +59,11 @@ impl DisplayTicketPricing {
         // Determine fees using discounted price, comps and box office purchases have no fees
         let mut fee_in_cents = 0;
         if !is_comp && !box_office_pricing {
+            let price_in_cents = ticket_pricing.price_in_cents - discount_in_cents;
             fee_in_cents = fee_schedule
-                .get_range(ticket_pricing.price_in_cents - discount_in_cents, conn)
+                .get_range(price_in_cents, conn)
                 .optional()?
-                .map(|f| f.fee_in_cents)
+                .map(|f| f.calculate_fees(price_in_cents).fee_in_cents)
                 .unwrap_or(0);
         }
 
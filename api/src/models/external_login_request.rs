@@ -0,0 +1,5 @@
+#[derive(Deserialize, Serialize)]
+pub struct ExternalLoginRequest {
+    /// Access token for Facebook, ID token for OpenID Connect providers
+    pub token: String,
+}
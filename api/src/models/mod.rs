@@ -1,7 +1,9 @@
 pub use self::add_venue_to_organization_request::*;
 pub use self::admin_display_ticket_type::*;
+pub use self::audit_log_query_parameters::*;
 pub use self::create_artist_request::*;
 pub use self::display_ticket_pricing::*;
+pub use self::external_login_request::*;
 pub use self::facebook_web_login_token::*;
 pub use self::path_parameters::*;
 pub use self::payload::*;
@@ -11,8 +13,10 @@ pub use self::user_profile_attributes::*;
 
 mod add_venue_to_organization_request;
 mod admin_display_ticket_type;
+mod audit_log_query_parameters;
 mod create_artist_request;
 mod display_ticket_pricing;
+mod external_login_request;
 mod facebook_web_login_token;
 mod path_parameters;
 mod payload;
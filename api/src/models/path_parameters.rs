@@ -15,12 +15,29 @@ pub struct ExternalPathParameters {
     pub id: String,
 }
 
+#[derive(Deserialize)]
+pub struct IdentityProviderPathParameters {
+    pub provider: String,
+}
+
 #[derive(Deserialize)]
 pub struct EventTicketPathParameters {
     pub event_id: Uuid,
     pub ticket_type_id: Uuid,
 }
 
+#[derive(Deserialize)]
+pub struct EventSplitPartnerPathParameters {
+    pub id: Uuid, // Event Id
+    pub split_partner_id: Uuid,
+}
+
+#[derive(Deserialize)]
+pub struct EventUserPathParameters {
+    pub id: Uuid, // Event Id
+    pub user_id: Uuid,
+}
+
 #[derive(Deserialize)]
 pub struct RedeemTicketPathParameters {
     pub id: Uuid, // Event Id
@@ -33,18 +50,56 @@ pub struct OrganizationFanPathParameters {
     pub user_id: Uuid,
 }
 
+#[derive(Deserialize)]
+pub struct OrganizationFanNotePathParameters {
+    pub id: Uuid, // Organization Id
+    pub user_id: Uuid,
+    pub note_id: Uuid,
+}
+
+#[derive(Deserialize)]
+pub struct OrganizationFanTagPathParameters {
+    pub id: Uuid, // Organization Id
+    pub user_id: Uuid,
+    pub tag: String,
+}
+
 #[derive(Deserialize)]
 pub struct OrganizationUserPathParameters {
     pub id: Uuid, // Organization Id
     pub user_id: Uuid,
 }
 
+#[derive(Deserialize)]
+pub struct AffiliateCommissionRulePathParameters {
+    pub id: Uuid, // Affiliate Id
+    pub commission_rule_id: Uuid,
+}
+
+#[derive(Deserialize)]
+pub struct OrganizationApiKeyPathParameters {
+    pub id: Uuid, // Organization Id
+    pub api_key_id: Uuid,
+}
+
 #[derive(Deserialize)]
 pub struct OrganizationInvitePathParameters {
     pub id: Uuid, // Organization Id
     pub invite_id: Uuid,
 }
 
+#[derive(Deserialize)]
+pub struct OrganizationRolePathParameters {
+    pub id: Uuid, // Organization Id
+    pub organization_role_id: Uuid,
+}
+
+#[derive(Deserialize)]
+pub struct SettlementAdjustmentPathParameters {
+    pub id: Uuid, // Settlement Id
+    pub adjustment_id: Uuid,
+}
+
 #[derive(Deserialize)]
 pub struct CompPathParameters {
     pub hold_id: Uuid,
@@ -17,6 +17,7 @@ pub struct UserDisplayTicketType {
     pub limit_per_person: u32,
     pub ticket_pricing: Option<DisplayTicketPricing>,
     pub redemption_code: Option<String>,
+    pub fee_mode: FeeModes,
 }
 
 impl UserDisplayTicketType {
@@ -29,8 +30,9 @@ impl UserDisplayTicketType {
     ) -> Result<UserDisplayTicketType, DatabaseError> {
         let mut status = ticket_type.status;
         let available = ticket_type.valid_available_ticket_count(conn)?;
+        let fee_mode = ticket_type.effective_fee_mode(conn)?;
 
-        let ticket_pricing = match ticket_type
+        let mut ticket_pricing = match ticket_type
             .current_ticket_pricing(box_office_pricing, conn)
             .optional()?
         {
@@ -44,6 +46,18 @@ impl UserDisplayTicketType {
             None => None,
         };
 
+        // Buyers only see the fees they are charged separately
+        if let Some(ref mut ticket_pricing) = ticket_pricing {
+            match fee_mode {
+                FeeModes::AllIn => {
+                    ticket_pricing.price_in_cents += ticket_pricing.fee_in_cents;
+                    ticket_pricing.fee_in_cents = 0;
+                }
+                FeeModes::Absorbed => ticket_pricing.fee_in_cents = 0,
+                FeeModes::Standard => {}
+            }
+        }
+
         if ticket_type.status == TicketTypeStatus::Published {
             if available == 0 {
                 status = TicketTypeStatus::SoldOut;
@@ -64,6 +78,7 @@ impl UserDisplayTicketType {
             redemption_code: None,
             increment: ticket_type.increment,
             limit_per_person: ticket_type.limit_per_person as u32,
+            fee_mode,
         };
 
         if let Some(ref redemption_code) = redemption_code {
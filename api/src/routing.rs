@@ -5,7 +5,27 @@ use server::AppState;
 
 pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
     // Please try to keep in alphabetical order
-    app.resource("/artists/search", |r| {
+    app.resource("/affiliates/clicks", |r| {
+        r.method(Method::POST).with(affiliates::record_click);
+    })
+    .resource(
+        "/affiliates/{id}/commission_rules/{commission_rule_id}",
+        |r| {
+            r.method(Method::DELETE)
+                .with(affiliates::destroy_commission_rule);
+        },
+    )
+    .resource("/affiliates/{id}/commission_rules", |r| {
+        r.method(Method::POST).with(affiliates::add_commission_rule);
+    })
+    .resource("/affiliates/{id}/sales", |r| {
+        r.method(Method::GET).with(affiliates::sales);
+    })
+    .resource("/affiliates/{id}", |r| {
+        r.method(Method::GET).with(affiliates::show);
+        r.method(Method::PUT).with(affiliates::update);
+    })
+    .resource("/artists/search", |r| {
         r.method(Method::GET).with(artists::search);
     })
     .resource("/artists/{id}/toggle_privacy", |r| {
@@ -23,6 +43,28 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
     .resource("/auth/token/refresh", |r| {
         r.method(Method::POST).with(auth::token_refresh)
     })
+    .resource("/campaigns/unsubscribe", |r| {
+        r.method(Method::POST).with(campaigns::unsubscribe);
+    })
+    .resource("/campaigns/{id}/cancel", |r| {
+        r.method(Method::POST).with(campaigns::cancel);
+    })
+    .resource("/campaigns/{id}/preview", |r| {
+        r.method(Method::GET).with(campaigns::preview);
+    })
+    .resource("/campaigns/{id}/recipients", |r| {
+        r.method(Method::GET).with(campaigns::recipients);
+    })
+    .resource("/campaigns/{id}/send", |r| {
+        r.method(Method::POST).with(campaigns::send);
+    })
+    .resource("/campaigns/{id}/test", |r| {
+        r.method(Method::POST).with(campaigns::send_test);
+    })
+    .resource("/campaigns/{id}", |r| {
+        r.method(Method::GET).with(campaigns::show);
+        r.method(Method::PUT).with(campaigns::update);
+    })
     .resource("/cart", |r| {
         r.method(Method::DELETE).with(cart::destroy);
         r.method(Method::POST).with(cart::update_cart);
@@ -45,6 +87,10 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
         r.method(Method::PATCH).with(comps::update);
         r.method(Method::DELETE).with(comps::destroy);
     })
+    .resource("/email_verification", |r| {
+        r.method(Method::POST).with(email_verifications::create);
+        r.method(Method::PUT).with(email_verifications::update);
+    })
     .resource("/events", |r| {
         r.method(Method::GET).with(events::index);
         r.method(Method::POST).with(events::create);
@@ -61,6 +107,9 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
         r.method(Method::POST).with(events::add_artist);
         r.method(Method::PUT).with(events::update_artists);
     })
+    .resource("/events/{id}/audit", |r| {
+        r.method(Method::GET).with(events::audit);
+    })
     .resource("/events/{id}/codes", |r| {
         r.method(Method::GET).with(events::codes);
         r.method(Method::POST).with(codes::create);
@@ -92,6 +141,13 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
     .resource("/events/{id}/redeem/{ticket_instance_id}", |r| {
         r.method(Method::POST).with(events::redeem_ticket);
     })
+    .resource("/events/{id}/split_partners/{split_partner_id}", |r| {
+        r.method(Method::DELETE).with(event_split_partners::destroy);
+    })
+    .resource("/events/{id}/split_partners", |r| {
+        r.method(Method::GET).with(event_split_partners::index);
+        r.method(Method::POST).with(event_split_partners::create);
+    })
     .resource("/events/{id}/tickets", |r| {
         r.method(Method::GET).with(tickets::index);
     })
@@ -103,9 +159,44 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
         r.method(Method::PATCH).with(ticket_types::update);
         r.method(Method::DELETE).with(ticket_types::cancel);
     })
+    .resource("/events/{id}/users/{user_id}", |r| {
+        r.method(Method::DELETE).with(event_users::destroy);
+    })
+    .resource("/events/{id}/users", |r| {
+        r.method(Method::GET).with(event_users::index);
+        r.method(Method::POST).with(event_users::create);
+    })
+    .resource("/exchange_rates/{id}", |r| {
+        r.method(Method::DELETE).with(exchange_rates::destroy);
+    })
+    .resource("/exchange_rates", |r| {
+        r.method(Method::GET).with(exchange_rates::index);
+        r.method(Method::POST).with(exchange_rates::create);
+    })
     .resource("/external/facebook/web_login", |r| {
         r.method(Method::POST).with(external::facebook::web_login)
     })
+    .resource("/external/logins", |r| {
+        r.method(Method::GET).with(external::identity_providers::index);
+    })
+    .resource("/external/{provider}/link", |r| {
+        r.method(Method::POST).with(external::identity_providers::link);
+        r.method(Method::DELETE).with(external::identity_providers::unlink);
+    })
+    .resource("/external/{provider}/login", |r| {
+        r.method(Method::POST).with(external::identity_providers::login);
+    })
+    .resource("/fan_segments/{id}/export", |r| {
+        r.method(Method::GET).with(fan_segments::export);
+    })
+    .resource("/fan_segments/{id}/members", |r| {
+        r.method(Method::GET).with(fan_segments::members);
+    })
+    .resource("/fan_segments/{id}", |r| {
+        r.method(Method::GET).with(fan_segments::show);
+        r.method(Method::PUT).with(fan_segments::update);
+        r.method(Method::DELETE).with(fan_segments::destroy);
+    })
     .resource("/invitations/{id}", |r| {
         r.method(Method::GET).with(organization_invites::view);
     })
@@ -144,16 +235,58 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
         r.method(Method::GET).with(orders::show);
         r.method(Method::PATCH).with(orders::update);
     })
+    .resource("/organizations/{id}/affiliates", |r| {
+        r.method(Method::GET).with(affiliates::index);
+        r.method(Method::POST).with(affiliates::create);
+    })
+    .resource("/organizations/{id}/api_keys/{api_key_id}", |r| {
+        r.method(Method::DELETE).with(organization_api_keys::revoke);
+    })
+    .resource("/organizations/{id}/api_keys", |r| {
+        r.method(Method::GET).with(organization_api_keys::index);
+        r.method(Method::POST).with(organization_api_keys::create);
+    })
     .resource("/organizations/{id}/artists", |r| {
         r.method(Method::GET).with(artists::show_from_organizations);
         r.method(Method::POST).with(organizations::add_artist);
     })
+    .resource("/organizations/{id}/audit", |r| {
+        r.method(Method::GET).with(organizations::audit);
+    })
+    .resource("/organizations/{id}/campaigns", |r| {
+        r.method(Method::GET).with(campaigns::index);
+        r.method(Method::POST).with(campaigns::create);
+    })
+    .resource("/organizations/{id}/calendar", |r| {
+        r.method(Method::GET).with(organizations::calendar);
+    })
     .resource("/organizations/{id}/events", |r| {
         r.method(Method::GET).with(events::show_from_organizations);
     })
+    .resource("/organizations/{id}/fan_segments", |r| {
+        r.method(Method::GET).with(fan_segments::index);
+        r.method(Method::POST).with(fan_segments::create);
+    })
+    .resource("/organizations/{id}/fan_tags", |r| {
+        r.method(Method::GET).with(fans::organization_tags);
+    })
     .resource("/organizations/{id}/fans/{user_id}/history", |r| {
         r.method(Method::GET).with(users::history);
     })
+    .resource("/organizations/{id}/fans/{user_id}/notes/{note_id}", |r| {
+        r.method(Method::DELETE).with(fans::destroy_note);
+    })
+    .resource("/organizations/{id}/fans/{user_id}/notes", |r| {
+        r.method(Method::GET).with(fans::notes);
+        r.method(Method::POST).with(fans::create_note);
+    })
+    .resource("/organizations/{id}/fans/{user_id}/tags/{tag}", |r| {
+        r.method(Method::DELETE).with(fans::remove_tag);
+    })
+    .resource("/organizations/{id}/fans/{user_id}/tags", |r| {
+        r.method(Method::GET).with(fans::tags);
+        r.method(Method::POST).with(fans::add_tag);
+    })
     .resource("/organizations/{id}/fans/{user_id}", |r| {
         r.method(Method::GET).with(users::profile);
     })
@@ -171,6 +304,22 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
         r.method(Method::GET).with(organization_invites::index);
         r.method(Method::POST).with(organization_invites::create);
     })
+    .resource("/organizations/{id}/report_subscriptions", |r| {
+        r.method(Method::GET).with(report_subscriptions::index);
+        r.method(Method::POST).with(report_subscriptions::create);
+    })
+    .resource("/organizations/{id}/roles/{organization_role_id}", |r| {
+        r.method(Method::PUT).with(organization_roles::update);
+        r.method(Method::DELETE).with(organization_roles::destroy);
+    })
+    .resource("/organizations/{id}/roles", |r| {
+        r.method(Method::GET).with(organization_roles::index);
+        r.method(Method::POST).with(organization_roles::create);
+    })
+    .resource("/organizations/{id}/settlements", |r| {
+        r.method(Method::GET).with(settlements::index);
+        r.method(Method::POST).with(settlements::create);
+    })
     .resource("/organizations/{id}/users", |r| {
         r.method(Method::POST)
             .with(organizations::add_or_replace_user);
@@ -198,12 +347,20 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
         r.method(Method::POST).with(password_resets::create);
         r.method(Method::PUT).with(password_resets::update);
     })
+    .resource("/passwordless_login", |r| {
+        r.method(Method::POST).with(passwordless_logins::create);
+        r.method(Method::PUT).with(passwordless_logins::update);
+    })
     .resource("/payments/callback/{nonce}/{id}", |r| {
         r.method(Method::GET).with(payments::callback);
     })
     .resource("/payment_methods", |r| {
         r.method(Method::GET).with(payment_methods::index);
     })
+    .resource("/phone_verification", |r| {
+        r.method(Method::POST).with(phone_verifications::create);
+        r.method(Method::PUT).with(phone_verifications::update);
+    })
     .resource("/redemption_codes/{code}", |r| {
         r.method(Method::GET).with(redemption_codes::show)
     })
@@ -215,15 +372,51 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
         r.method(Method::GET).with(regions::index);
         r.method(Method::POST).with(regions::create)
     })
+    .resource("/report_subscriptions/unsubscribe", |r| {
+        r.method(Method::POST).with(report_subscriptions::unsubscribe);
+    })
+    .resource("/report_subscriptions/{id}/deliveries", |r| {
+        r.method(Method::GET).with(report_subscriptions::deliveries);
+    })
+    .resource("/report_subscriptions/{id}", |r| {
+        r.method(Method::DELETE).with(report_subscriptions::destroy);
+    })
     .resource("/reports/{id}", |r| {
         r.method(Method::GET).with(reports::get_report);
     })
+    .resource("/settlements/{id}/adjustments/{adjustment_id}", |r| {
+        r.method(Method::DELETE).with(settlements::destroy_adjustment);
+    })
+    .resource("/settlements/{id}/adjustments", |r| {
+        r.method(Method::POST).with(settlements::add_adjustment);
+    })
+    .resource("/settlements/{id}/approve", |r| {
+        r.method(Method::POST).with(settlements::approve);
+    })
+    .resource("/settlements/{id}/export", |r| {
+        r.method(Method::GET).with(settlements::export);
+    })
+    .resource("/settlements/{id}/mark_paid", |r| {
+        r.method(Method::POST).with(settlements::mark_paid);
+    })
+    .resource("/settlements/{id}", |r| {
+        r.method(Method::GET).with(settlements::show);
+    })
     .resource("/status", |r| r.method(Method::GET).with(status::check))
     .resource("/stages/{id}", |r| {
         r.method(Method::GET).with(stages::show);
         r.method(Method::PUT).with(stages::update);
         r.method(Method::DELETE).with(stages::delete);
     })
+    .resource("/tax_rules/{id}", |r| {
+        r.method(Method::GET).with(tax_rules::show);
+        r.method(Method::PUT).with(tax_rules::update);
+        r.method(Method::DELETE).with(tax_rules::destroy);
+    })
+    .resource("/tax_rules", |r| {
+        r.method(Method::GET).with(tax_rules::index);
+        r.method(Method::POST).with(tax_rules::create);
+    })
     .resource("/tickets/transfer", |r| {
         r.method(Method::POST).with(tickets::transfer_authorization);
     })
@@ -247,6 +440,28 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
         r.method(Method::GET).with(users::current_user);
         r.method(Method::PUT).with(users::update_current_user);
     })
+    .resource("/users/me/communication_consents", |r| {
+        r.method(Method::GET).with(communication_preferences::consents);
+    })
+    .resource("/users/me/communication_preferences", |r| {
+        r.method(Method::GET).with(communication_preferences::index);
+        r.method(Method::PUT).with(communication_preferences::update);
+    })
+    .resource("/users/me/sessions", |r| {
+        r.method(Method::GET).with(user_sessions::index);
+        r.method(Method::DELETE).with(user_sessions::destroy_all);
+    })
+    .resource("/users/me/sessions/{id}", |r| {
+        r.method(Method::DELETE).with(user_sessions::destroy);
+    })
+    .resource("/users/me/two_factor", |r| {
+        r.method(Method::GET).with(two_factor_authentications::show);
+        r.method(Method::POST).with(two_factor_authentications::create);
+        r.method(Method::DELETE).with(two_factor_authentications::destroy);
+    })
+    .resource("/users/me/two_factor/enable", |r| {
+        r.method(Method::POST).with(two_factor_authentications::enable);
+    })
     .resource("/users/register", |r| {
         r.method(Method::POST).with(users::register)
     })
@@ -267,6 +482,12 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
     .resource("/users", |r| {
         r.method(Method::POST).with(users::register_and_login);
     })
+    .resource("/users/{id}/data_export", |r| {
+        r.method(Method::GET).with(users::export_data);
+    })
+    .resource("/users/{id}/erase", |r| {
+        r.method(Method::POST).with(users::erase);
+    })
     .resource("/users/{id}", |r| {
         r.method(Method::GET).with(users::show);
     })
@@ -276,6 +497,9 @@ pub fn routes(app: &mut CorsBuilder<AppState>) -> App<AppState> {
     .resource("/users/{id}/organizations", |r| {
         r.method(Method::GET).with(users::list_organizations);
     })
+    .resource("/venues/{id}/calendar", |r| {
+        r.method(Method::GET).with(venues::calendar);
+    })
     .resource("/venues/{id}/events", |r| {
         r.method(Method::GET).with(events::show_from_venues);
     })
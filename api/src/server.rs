@@ -2,10 +2,14 @@ use actix_web::http;
 use actix_web::middleware::cors::Cors;
 use actix_web::{server, App};
 
+use auth::user::TWO_FACTOR_CODE_HEADER;
 use config::Config;
 use db::*;
 use domain_events::DomainActionMonitor;
-use middleware::{AppVersionHeader, BigNeonLogger, DatabaseTransaction};
+use middleware::{
+    create_rate_limit_store, AppVersionHeader, BigNeonLogger, DatabaseTransaction, RateLimitStore,
+    RateLimiter,
+};
 use routing;
 use std::io;
 use utils::spotify;
@@ -18,13 +22,15 @@ pub struct AppState {
     pub config: Config,
     pub database: Database,
     pub service_locator: ServiceLocator,
+    pub rate_limit_store: Box<RateLimitStore>,
 }
 
 impl AppState {
     pub fn new(config: Config, database: Database) -> AppState {
         AppState {
-            database,
             service_locator: ServiceLocator::new(&config),
+            rate_limit_store: create_rate_limit_store(&config, &database),
+            database,
             config,
         }
     }
@@ -69,6 +75,7 @@ impl Server {
                         .middleware(BigNeonLogger::new(LOGGER_FORMAT))
                         .middleware(DatabaseTransaction::new())
                         .middleware(AppVersionHeader::new())
+                        .middleware(RateLimiter::new())
                         .configure(|a| {
                             let mut cors_config = Cors::for_app(a);
                             match config.allowed_origins.as_ref() {
@@ -83,6 +90,9 @@ impl Server {
                                     "X-API-Client-Version"
                                         .parse::<http::header::HeaderName>()
                                         .unwrap(),
+                                    TWO_FACTOR_CODE_HEADER
+                                        .parse::<http::header::HeaderName>()
+                                        .unwrap(),
                                 ])
                                 .allowed_header(http::header::CONTENT_TYPE)
                                 .max_age(3600);
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use chrono::{Duration, Utc};
+use chrono::{Duration, NaiveDateTime, Utc};
 use diesel::PgConnection;
 use futures::Future;
 use tokio::prelude::*;
@@ -12,6 +12,7 @@ use errors::*;
 use futures::future::Either;
 use utils::sendgrid::mail as sendgrid;
 use utils::twilio;
+use uuid::Uuid;
 
 pub type TemplateData = HashMap<String, String>;
 
@@ -63,6 +64,40 @@ impl CommAddress {
         self.addresses.push(address.clone());
     }
 }
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct CommAttachment {
+    pub filename: String,
+    pub content_type: String,
+    pub content: String,
+}
+
+impl CommAttachment {
+    pub fn new(filename: &str, content_type: &str, content: &str) -> CommAttachment {
+        CommAttachment {
+            filename: filename.to_string(),
+            content_type: content_type.to_string(),
+            content: base64::encode(content),
+        }
+    }
+
+    pub fn from_bytes(filename: &str, content_type: &str, content: &[u8]) -> CommAttachment {
+        CommAttachment {
+            filename: filename.to_string(),
+            content_type: content_type.to_string(),
+            content: base64::encode(content),
+        }
+    }
+}
+
+/// The user a communication is for and what kind of communication it is, so it is only sent
+/// if the user's preferences allow it
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct CommPreference {
+    pub user_id: Uuid,
+    pub organization_id: Option<Uuid>,
+    pub category: CommunicationCategory,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct Communication {
     pub comm_type: CommunicationType,
@@ -72,6 +107,10 @@ pub struct Communication {
     pub destinations: CommAddress,
     pub template_id: Option<String>,
     pub template_data: Option<Vec<TemplateData>>,
+    #[serde(default)]
+    pub attachments: Option<Vec<CommAttachment>>,
+    #[serde(default)]
+    pub preference: Option<CommPreference>,
 }
 
 impl Communication {
@@ -92,29 +131,77 @@ impl Communication {
             destinations,
             template_id,
             template_data,
+            attachments: None,
+            preference: None,
+        }
+    }
+
+    pub fn add_attachment(&mut self, attachment: CommAttachment) {
+        self.attachments
+            .get_or_insert_with(|| Vec::new())
+            .push(attachment);
+    }
+
+    /// Communications without a preference, e.g. password resets, are always sent
+    pub fn subject_to_preferences(
+        &mut self,
+        user_id: Uuid,
+        organization_id: Option<Uuid>,
+        category: CommunicationCategory,
+    ) {
+        self.preference = Some(CommPreference {
+            user_id,
+            organization_id,
+            category,
+        });
+    }
+
+    pub fn is_allowed(&self, connection: &PgConnection) -> Result<bool, BigNeonError> {
+        match self.preference {
+            Some(ref preference) => Ok(CommunicationPreference::allows(
+                preference.user_id,
+                preference.organization_id,
+                preference.category,
+                self.channel(),
+                connection,
+            )?),
+            None => Ok(true),
+        }
+    }
+
+    pub fn channel(&self) -> CommunicationChannelType {
+        match self.comm_type {
+            CommunicationType::Email => CommunicationChannelType::Email,
+            CommunicationType::EmailTemplate => CommunicationChannelType::Email,
+            CommunicationType::Sms => CommunicationChannelType::Sms,
         }
     }
 
     pub fn queue(&self, connection: &PgConnection) -> Result<(), BigNeonError> {
-        DomainAction::create(
+        self.queue_at(Utc::now().naive_utc(), None, None, connection)?;
+        Ok(())
+    }
+
+    /// Queues the communication to be sent at `scheduled_at`, linked to the record it is for
+    pub fn queue_at(
+        &self,
+        scheduled_at: NaiveDateTime,
+        main_table: Option<Tables>,
+        main_table_id: Option<Uuid>,
+        connection: &PgConnection,
+    ) -> Result<DomainAction, BigNeonError> {
+        Ok(DomainAction::create(
             None,
             DomainActionTypes::Communication,
-            match self.comm_type {
-                CommunicationType::Email => Some(CommunicationChannelType::Email),
-                CommunicationType::EmailTemplate => Some(CommunicationChannelType::Email),
-                CommunicationType::Sms => Some(CommunicationChannelType::Sms),
-            },
+            Some(self.channel()),
             json!(self),
-            None,
-            None,
-            Utc::now().naive_utc(),
-            (Utc::now().naive_utc())
-                .checked_add_signed(Duration::days(1))
-                .unwrap(),
+            main_table.map(|t| t.to_string()),
+            main_table_id,
+            scheduled_at,
+            scheduled_at.checked_add_signed(Duration::days(1)).unwrap(),
             3,
         )
-        .commit(connection)?;
-        Ok(())
+        .commit(connection)?)
     }
 
     pub fn send_async(
@@ -144,6 +231,7 @@ impl Communication {
                                 destination_addresses,
                                 communication.title.clone(),
                                 communication.body.clone(),
+                                communication.attachments.as_ref(),
                             ),
                             CommunicationType::EmailTemplate => {
                                 sendgrid::send_email_template_async(
@@ -152,6 +240,7 @@ impl Communication {
                                     &destination_addresses,
                                     communication.template_id.clone().unwrap(),
                                     communication.template_data.as_ref().unwrap(),
+                                    communication.attachments.as_ref(),
                                 )
                             }
                             CommunicationType::Sms => twilio::send_sms_async(
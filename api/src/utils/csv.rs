@@ -0,0 +1,47 @@
+use errors::BigNeonError;
+use utils::export::{escape_formula, ExportCell, ExportWriter};
+
+pub const CSV_CONTENT_TYPE: &'static str = "text/csv; charset=utf-8";
+
+/// Builds an RFC 4180 document from the given rows. Fields are quoted only when they
+/// contain a delimiter, quote or line break, and are guarded against formula injection.
+pub fn to_csv(rows: &[Vec<String>]) -> String {
+    let mut result = String::new();
+    for row in rows {
+        let fields: Vec<String> = row.iter().map(|field| escape_field(field)).collect();
+        result.push_str(&fields.join(","));
+        result.push_str("\r\n");
+    }
+    result
+}
+
+fn escape_field(field: &str) -> String {
+    let field = escape_formula(field);
+    if field.contains(|c| c == ',' || c == '"' || c == '\r' || c == '\n') {
+        format!("\"{}\"", field.replace("\"", "\"\""))
+    } else {
+        field
+    }
+}
+
+/// Streams report rows as CSV, see `utils::export`
+pub struct CsvWriter;
+
+impl ExportWriter for CsvWriter {
+    fn header(&mut self, columns: &[&str]) -> Result<Vec<u8>, BigNeonError> {
+        let row: Vec<String> = columns.iter().map(|c| c.to_string()).collect();
+        Ok(to_csv(&[row]).into_bytes())
+    }
+
+    fn rows(&mut self, rows: &[Vec<ExportCell>]) -> Result<Vec<u8>, BigNeonError> {
+        let rows: Vec<Vec<String>> = rows
+            .iter()
+            .map(|row| row.iter().map(|cell| cell.to_string()).collect())
+            .collect();
+        Ok(to_csv(&rows).into_bytes())
+    }
+
+    fn finish(&mut self) -> Result<Vec<u8>, BigNeonError> {
+        Ok(Vec::new())
+    }
+}
@@ -0,0 +1,191 @@
+use actix_web::{Error, HttpResponse};
+use bigneon_db::models::ExportFormats;
+use bytes::Bytes;
+use errors::BigNeonError;
+use futures::{Async, Poll, Stream};
+use std::fmt;
+use utils::csv::{CsvWriter, CSV_CONTENT_TYPE};
+use utils::money::format_cents;
+use utils::xlsx::{XlsxWriter, XLSX_CONTENT_TYPE};
+
+pub enum ExportCell {
+    Text(String),
+    Integer(i64),
+    Money(i64),
+}
+
+impl fmt::Display for ExportCell {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            ExportCell::Text(value) => write!(f, "{}", value),
+            ExportCell::Integer(value) => write!(f, "{}", value),
+            ExportCell::Money(value) => write!(f, "{}", format_cents(*value)),
+        }
+    }
+}
+
+impl From<String> for ExportCell {
+    fn from(value: String) -> Self {
+        ExportCell::Text(value)
+    }
+}
+
+impl<'a> From<&'a str> for ExportCell {
+    fn from(value: &'a str) -> Self {
+        ExportCell::Text(value.to_string())
+    }
+}
+
+impl<T: Into<ExportCell>> From<Option<T>> for ExportCell {
+    fn from(value: Option<T>) -> Self {
+        value
+            .map(|v| v.into())
+            .unwrap_or(ExportCell::Text(String::new()))
+    }
+}
+
+/// Prefixes text which a spreadsheet would evaluate as a formula with a `'` so it is
+/// displayed as entered. Plain numbers such as negative amounts are left untouched.
+pub fn escape_formula(value: &str) -> String {
+    let is_formula = value.starts_with(|c| match c {
+        '=' | '+' | '-' | '@' | '\t' | '\r' => true,
+        _ => false,
+    });
+    if is_formula && value.parse::<f64>().is_err() {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    }
+}
+
+/// Tabular file format which can be written incrementally. Each method returns the bytes
+/// to append to the output, which may be empty if the format buffers its output.
+pub trait ExportWriter {
+    fn header(&mut self, columns: &[&str]) -> Result<Vec<u8>, BigNeonError>;
+    fn rows(&mut self, rows: &[Vec<ExportCell>]) -> Result<Vec<u8>, BigNeonError>;
+    fn finish(&mut self) -> Result<Vec<u8>, BigNeonError>;
+}
+
+/// Lazily fetches pages of rows until an empty page is returned
+pub struct Pages<F> {
+    fetch: F,
+    page: u32,
+    finished: bool,
+}
+
+impl<F> Pages<F>
+where
+    F: FnMut(u32) -> Result<Vec<Vec<ExportCell>>, BigNeonError>,
+{
+    pub fn new(fetch: F) -> Pages<F> {
+        Pages {
+            fetch,
+            page: 0,
+            finished: false,
+        }
+    }
+}
+
+impl<F> Iterator for Pages<F>
+where
+    F: FnMut(u32) -> Result<Vec<Vec<ExportCell>>, BigNeonError>,
+{
+    type Item = Result<Vec<Vec<ExportCell>>, BigNeonError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let result = (self.fetch)(self.page);
+        self.page += 1;
+        match result {
+            Ok(ref rows) if !rows.is_empty() => {}
+            Ok(_) => {
+                self.finished = true;
+                return None;
+            }
+            Err(_) => self.finished = true,
+        }
+        Some(result)
+    }
+}
+
+struct ExportStream<I> {
+    writer: Box<ExportWriter>,
+    columns: Option<Vec<&'static str>>,
+    pages: I,
+    finished: bool,
+}
+
+impl<I> Stream for ExportStream<I>
+where
+    I: Iterator<Item = Result<Vec<Vec<ExportCell>>, BigNeonError>>,
+{
+    type Item = Bytes;
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<Option<Bytes>, Error> {
+        loop {
+            let chunk = if let Some(columns) = self.columns.take() {
+                self.writer.header(&columns)?
+            } else if self.finished {
+                return Ok(Async::Ready(None));
+            } else {
+                match self.pages.next() {
+                    Some(rows) => self.writer.rows(&rows?)?,
+                    None => {
+                        self.finished = true;
+                        self.writer.finish()?
+                    }
+                }
+            };
+            // An empty chunk would end a chunked response early so it is skipped
+            if !chunk.is_empty() {
+                return Ok(Async::Ready(Some(Bytes::from(chunk))));
+            }
+        }
+    }
+}
+
+/// The writer and content type for a tabular format, `None` for other formats
+pub fn writer(format: ExportFormats) -> Option<(Box<ExportWriter>, &'static str)> {
+    match format {
+        ExportFormats::Csv => Some((Box::new(CsvWriter), CSV_CONTENT_TYPE)),
+        ExportFormats::Xlsx => Some((Box::new(XlsxWriter::new()), XLSX_CONTENT_TYPE)),
+        ExportFormats::Pdf => None,
+    }
+}
+
+/// Streams the pages of rows as a file download. Pages are only fetched as the response
+/// is written, after the request's transaction has ended, so rows read with the request's
+/// connection must be fetched beforehand. Returns `None` if the format is not a tabular format.
+pub fn stream_response<I>(
+    format: ExportFormats,
+    filename: &str,
+    columns: Vec<&'static str>,
+    pages: I,
+) -> Option<HttpResponse>
+where
+    I: Iterator<Item = Result<Vec<Vec<ExportCell>>, BigNeonError>> + 'static,
+{
+    let (writer, content_type) = writer(format)?;
+
+    Some(
+        HttpResponse::Ok()
+            .content_type(content_type)
+            .header(
+                "Content-Disposition",
+                format!(
+                    "attachment; filename=\"{}.{}\"",
+                    filename,
+                    format.extension()
+                ),
+            )
+            .streaming(ExportStream {
+                writer,
+                columns: Some(columns),
+                pages,
+                finished: false,
+            }),
+    )
+}
@@ -0,0 +1,320 @@
+use bigneon_db::models::{Event, Venue};
+use chrono::prelude::*;
+use chrono::Duration;
+use chrono_tz::Tz;
+use config::Config;
+use std::collections::BTreeMap;
+
+const ICAL_DATE_FORMAT: &'static str = "%Y%m%dT%H%M%S";
+const ICAL_LINE_LENGTH: usize = 75;
+pub const ICAL_CONTENT_TYPE: &'static str = "text/calendar; charset=utf-8";
+
+/// Builds an RFC 5545 calendar containing one VEVENT per event. Events without a start
+/// time are skipped as they cannot be placed on a calendar.
+pub fn calendar(name: &str, events: &[(Event, Option<Venue>)], config: &Config) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//Big Neon//Big Neon API//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        format!("X-WR-CALNAME:{}", escape_text(name)),
+    ];
+
+    lines.append(&mut timezone_lines(events));
+    for (event, venue) in events {
+        lines.append(&mut event_lines(event, venue, config));
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut result = String::new();
+    for line in lines {
+        result.push_str(&fold_line(&line));
+        result.push_str("\r\n");
+    }
+    result
+}
+
+fn event_lines(event: &Event, venue: &Option<Venue>, config: &Config) -> Vec<String> {
+    if event.event_start.is_none() {
+        return Vec::new();
+    }
+
+    let mut lines = vec![
+        "BEGIN:VEVENT".to_string(),
+        format!("UID:{}@bigneon.com", event.id),
+        format!("DTSTAMP:{}", utc_timestamp(&event.updated_at)),
+    ];
+
+    let localized_times = event.get_all_localized_times(venue);
+    match (venue, localized_times.event_start) {
+        (Some(venue), Some(event_start)) => {
+            lines.push(format!(
+                "DTSTART;TZID={}:{}",
+                venue.timezone,
+                event_start.format(ICAL_DATE_FORMAT)
+            ));
+            if let Some(event_end) = localized_times.event_end {
+                lines.push(format!(
+                    "DTEND;TZID={}:{}",
+                    venue.timezone,
+                    event_end.format(ICAL_DATE_FORMAT)
+                ));
+            }
+        }
+        _ => {
+            // No venue timezone is available so fall back to UTC
+            lines.push(format!(
+                "DTSTART:{}",
+                utc_timestamp(&event.event_start.unwrap())
+            ));
+            if let Some(event_end) = event.event_end {
+                lines.push(format!("DTEND:{}", utc_timestamp(&event_end)));
+            }
+        }
+    }
+
+    lines.push(format!("SUMMARY:{}", escape_text(&event.name)));
+    if let Some(ref venue) = venue {
+        lines.push(format!("LOCATION:{}", escape_text(&venue_location(venue))));
+    }
+    if let Some(ref top_line_info) = event.top_line_info {
+        lines.push(format!("DESCRIPTION:{}", escape_text(top_line_info)));
+    }
+    lines.push(format!("URL:{}/events/{}", config.front_end_url, event.id));
+    if event.cancelled_at.is_some() {
+        lines.push("STATUS:CANCELLED".to_string());
+    }
+    lines.push("END:VEVENT".to_string());
+    lines
+}
+
+/// A VTIMEZONE for each venue timezone the events' times are written in, as calendar clients
+/// can not be relied on to know the timezone from its TZID alone. It gives the UTC offsets in
+/// effect over the period the events cover.
+fn timezone_lines(events: &[(Event, Option<Venue>)]) -> Vec<String> {
+    let mut periods: BTreeMap<String, (Tz, NaiveDateTime, NaiveDateTime)> = BTreeMap::new();
+    for (event, venue) in events {
+        let (venue, event_start) = match (venue, event.event_start) {
+            (Some(venue), Some(event_start)) => (venue, event_start),
+            _ => continue,
+        };
+        let timezone: Tz = match venue.timezone.parse() {
+            Ok(timezone) => timezone,
+            Err(_) => continue,
+        };
+        let event_end = event.event_end.unwrap_or(event_start).max(event_start);
+        let period =
+            periods
+                .entry(venue.timezone.clone())
+                .or_insert((timezone, event_start, event_end));
+        period.1 = period.1.min(event_start);
+        period.2 = period.2.max(event_end);
+    }
+
+    let mut lines = Vec::new();
+    for (name, (timezone, start, end)) in periods {
+        lines.push("BEGIN:VTIMEZONE".to_string());
+        lines.push(format!("TZID:{}", name));
+        for observance in observances(timezone, start, end) {
+            let component = if observance.is_daylight(timezone) {
+                "DAYLIGHT"
+            } else {
+                "STANDARD"
+            };
+            lines.push(format!("BEGIN:{}", component));
+            // The onset is given in the local time before it
+            lines.push(format!(
+                "DTSTART:{}",
+                (observance.onset + Duration::seconds(observance.offset_from as i64))
+                    .format(ICAL_DATE_FORMAT)
+            ));
+            lines.push(format!(
+                "TZOFFSETFROM:{}",
+                format_utc_offset(observance.offset_from)
+            ));
+            lines.push(format!(
+                "TZOFFSETTO:{}",
+                format_utc_offset(observance.offset_to)
+            ));
+            lines.push(format!("END:{}", component));
+        }
+        lines.push("END:VTIMEZONE".to_string());
+    }
+    lines
+}
+
+/// A UTC offset, in seconds east of UTC, taking effect at `onset` (in UTC)
+#[derive(Debug, PartialEq)]
+struct Observance {
+    onset: NaiveDateTime,
+    offset_from: i32,
+    offset_to: i32,
+}
+
+impl Observance {
+    /// Daylight saving time is any offset ahead of the lowest one in effect during the year
+    fn is_daylight(&self, timezone: Tz) -> bool {
+        let year = self.onset.year();
+        let standard_offset =
+            utc_offset(timezone, NaiveDate::from_ymd(year, 1, 1).and_hms(0, 0, 0)).min(utc_offset(
+                timezone,
+                NaiveDate::from_ymd(year, 7, 1).and_hms(0, 0, 0),
+            ));
+        self.offset_to > standard_offset
+    }
+}
+
+/// The offset in effect at the start of the day before `from`, followed by each change of
+/// offset until the day after `to`. Changes are found a day at a time, then narrowed down to
+/// the minute.
+fn observances(timezone: Tz, from: NaiveDateTime, to: NaiveDateTime) -> Vec<Observance> {
+    let from = from.date().and_hms(0, 0, 0) - Duration::days(1);
+    let to = to + Duration::days(1);
+    let initial_offset = utc_offset(timezone, from);
+    let mut observances = vec![Observance {
+        onset: from,
+        offset_from: initial_offset,
+        offset_to: initial_offset,
+    }];
+
+    let mut day_start = from;
+    while day_start < to {
+        let day_end = day_start + Duration::days(1);
+        let offset_from = utc_offset(timezone, day_start);
+        let offset_to = utc_offset(timezone, day_end);
+        if offset_from != offset_to {
+            let (mut before, mut after) = (0, 24 * 60);
+            while after - before > 1 {
+                let middle = (before + after) / 2;
+                if utc_offset(timezone, day_start + Duration::minutes(middle)) == offset_from {
+                    before = middle;
+                } else {
+                    after = middle;
+                }
+            }
+            observances.push(Observance {
+                onset: day_start + Duration::minutes(after),
+                offset_from,
+                offset_to,
+            });
+        }
+        day_start = day_end;
+    }
+    observances
+}
+
+fn utc_offset(timezone: Tz, utc: NaiveDateTime) -> i32 {
+    timezone
+        .offset_from_utc_datetime(&utc)
+        .fix()
+        .local_minus_utc()
+}
+
+fn format_utc_offset(offset: i32) -> String {
+    let sign = if offset < 0 { '-' } else { '+' };
+    let offset = offset.abs();
+    let (hours, minutes, seconds) = (offset / 3600, offset % 3600 / 60, offset % 60);
+    if seconds == 0 {
+        format!("{}{:02}{:02}", sign, hours, minutes)
+    } else {
+        format!("{}{:02}{:02}{:02}", sign, hours, minutes, seconds)
+    }
+}
+
+fn venue_location(venue: &Venue) -> String {
+    vec![
+        &venue.name,
+        &venue.address,
+        &venue.city,
+        &venue.state,
+        &venue.postal_code,
+        &venue.country,
+    ]
+    .into_iter()
+    .filter(|v| !v.is_empty())
+    .map(|v| v.as_str())
+    .collect::<Vec<&str>>()
+    .join(", ")
+}
+
+fn utc_timestamp(date: &NaiveDateTime) -> String {
+    format!("{}Z", date.format(ICAL_DATE_FORMAT))
+}
+
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Content lines longer than 75 octets are folded onto continuation lines beginning with a space
+fn fold_line(line: &str) -> String {
+    let mut result = String::new();
+    let mut line_length = 0;
+    for c in line.chars() {
+        if line_length + c.len_utf8() > ICAL_LINE_LENGTH {
+            result.push_str("\r\n ");
+            line_length = 1;
+        }
+        result.push(c);
+        line_length += c.len_utf8();
+    }
+    result
+}
+
+#[test]
+fn fold_line_splits_long_lines() {
+    let line = "a".repeat(100);
+    let folded = fold_line(&line);
+    let parts: Vec<&str> = folded.split("\r\n").collect();
+    assert_eq!(parts.len(), 2);
+    assert_eq!(parts[0].len(), 75);
+    assert_eq!(parts[1], format!(" {}", "a".repeat(25)));
+    assert_eq!(fold_line("short"), "short");
+}
+
+#[test]
+fn escape_text_escapes_special_characters() {
+    assert_eq!(
+        escape_text("Doors; 8pm, Main\\Stage\nLate"),
+        "Doors\\; 8pm\\, Main\\\\Stage\\nLate"
+    );
+}
+
+#[test]
+fn observances_finds_offset_changes() {
+    let timezone: Tz = "America/New_York".parse().unwrap();
+    let observances = observances(
+        timezone,
+        NaiveDate::from_ymd(2019, 3, 1).and_hms(1, 0, 0),
+        NaiveDate::from_ymd(2019, 3, 20).and_hms(1, 0, 0),
+    );
+    assert_eq!(
+        observances,
+        vec![
+            Observance {
+                onset: NaiveDate::from_ymd(2019, 2, 28).and_hms(0, 0, 0),
+                offset_from: -5 * 3600,
+                offset_to: -5 * 3600,
+            },
+            Observance {
+                onset: NaiveDate::from_ymd(2019, 3, 10).and_hms(7, 0, 0),
+                offset_from: -5 * 3600,
+                offset_to: -4 * 3600,
+            },
+        ]
+    );
+    assert!(!observances[0].is_daylight(timezone));
+    assert!(observances[1].is_daylight(timezone));
+}
+
+#[test]
+fn format_utc_offset_includes_sign() {
+    assert_eq!(format_utc_offset(-5 * 3600), "-0500");
+    assert_eq!(format_utc_offset(5 * 3600 + 30 * 60), "+0530");
+    assert_eq!(format_utc_offset(0), "+0000");
+}
@@ -0,0 +1,54 @@
+use bigneon_db::models::FACEBOOK_SITE;
+use errors::*;
+use reqwest;
+use serde_json;
+use utils::identity_providers::{ExternalIdentity, IdentityProvider};
+
+const FACEBOOK_GRAPH_URL: &str = "https://graph.facebook.com";
+
+#[derive(Deserialize)]
+struct FacebookGraphResponse {
+    id: String,
+    first_name: String,
+    last_name: String,
+    email: Option<String>,
+}
+
+/// Identifies the user with a Facebook access token through the Graph API
+pub struct FacebookIdentityProvider;
+
+impl FacebookIdentityProvider {
+    pub fn new() -> FacebookIdentityProvider {
+        FacebookIdentityProvider
+    }
+}
+
+impl IdentityProvider for FacebookIdentityProvider {
+    fn site(&self) -> &str {
+        FACEBOOK_SITE
+    }
+
+    // TODO: Not covered by tests
+    fn verify(&self, token: &str) -> Result<ExternalIdentity, BigNeonError> {
+        let url = format!(
+            "{}/me?fields=id,email,first_name,last_name",
+            FACEBOOK_GRAPH_URL
+        );
+        let client = reqwest::Client::new();
+        let response = client
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", token))
+            .send()?
+            .text()?;
+        let facebook_graph_response: FacebookGraphResponse = serde_json::from_str(&response)?;
+
+        // The Graph API only returns addresses the user has confirmed with Facebook
+        Ok(ExternalIdentity {
+            external_user_id: facebook_graph_response.id,
+            email_verified: facebook_graph_response.email.is_some(),
+            email: facebook_graph_response.email,
+            first_name: Some(facebook_graph_response.first_name),
+            last_name: Some(facebook_graph_response.last_name),
+        })
+    }
+}
@@ -0,0 +1,167 @@
+use base64;
+use errors::*;
+use reqwest;
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+const KEY_SET_TTL_IN_SECONDS: u64 = 60 * 60;
+const KEY_SET_MIN_REFETCH_INTERVAL_IN_SECONDS: u64 = 60;
+
+lazy_static! {
+    static ref KEY_SET_CACHE: KeySetCache = KeySetCache::new(
+        Duration::from_secs(KEY_SET_TTL_IN_SECONDS),
+        Duration::from_secs(KEY_SET_MIN_REFETCH_INTERVAL_IN_SECONDS),
+    );
+}
+
+/// A public key published by an identity provider to verify the tokens it signs
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct JsonWebKey {
+    pub kid: Option<String>,
+    pub kty: String,
+    pub alg: Option<String>,
+    pub n: Option<String>,
+    pub e: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct JsonWebKeySet {
+    pub keys: Vec<JsonWebKey>,
+}
+
+impl JsonWebKeySet {
+    pub fn fetch(url: &str) -> Result<JsonWebKeySet, BigNeonError> {
+        Ok(reqwest::get(url)?.error_for_status()?.json()?)
+    }
+
+    /// Like `find`, on the key set published at `url`, which is only fetched when the cached
+    /// copy has expired or does not have the key
+    pub fn find_cached(url: &str, kid: Option<&str>) -> Result<Option<JsonWebKey>, BigNeonError> {
+        KEY_SET_CACHE.find(url, kid, JsonWebKeySet::fetch)
+    }
+
+    /// The RSA key with the id from the token header, or the only RSA key if the token does
+    /// not name one
+    pub fn find(&self, kid: Option<&str>) -> Option<&JsonWebKey> {
+        let mut rsa_keys = self.keys.iter().filter(|k| k.kty == "RSA");
+        match kid {
+            Some(kid) => rsa_keys.find(|k| k.kid.as_ref().map(|k| k.as_str()) == Some(kid)),
+            None => match (rsa_keys.next(), rsa_keys.next()) {
+                (Some(key), None) => Some(key),
+                _ => None,
+            },
+        }
+    }
+}
+
+struct CachedKeySet {
+    key_set: JsonWebKeySet,
+    fetched_at: Instant,
+}
+
+/// Key sets fetched from identity providers, kept so signing in does not wait on the provider.
+/// A set is fetched again once it is older than `ttl`, or sooner when a token names a key it
+/// does not have, as the provider may have rotated its keys.
+pub struct KeySetCache {
+    ttl: Duration,
+    min_refetch_interval: Duration,
+    key_sets: RwLock<HashMap<String, CachedKeySet>>,
+}
+
+impl KeySetCache {
+    pub fn new(ttl: Duration, min_refetch_interval: Duration) -> KeySetCache {
+        KeySetCache {
+            ttl,
+            min_refetch_interval,
+            key_sets: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn find<F>(
+        &self,
+        url: &str,
+        kid: Option<&str>,
+        fetch: F,
+    ) -> Result<Option<JsonWebKey>, BigNeonError>
+    where
+        F: FnOnce(&str) -> Result<JsonWebKeySet, BigNeonError>,
+    {
+        if let Some(cached) = self.key_sets.read().unwrap().get(url) {
+            let age = cached.fetched_at.elapsed();
+            if age < self.ttl {
+                if let Some(key) = cached.key_set.find(kid) {
+                    return Ok(Some(key.clone()));
+                }
+                // Unknown keys are only looked for again after a while, so tokens naming made
+                // up keys can not make every sign in wait on the provider
+                if age < self.min_refetch_interval {
+                    return Ok(None);
+                }
+            }
+        }
+
+        // Fetched without holding the lock, concurrent misses may both fetch
+        let key_set = fetch(url)?;
+        let key = key_set.find(kid).cloned();
+        self.key_sets.write().unwrap().insert(
+            url.to_string(),
+            CachedKeySet {
+                key_set,
+                fetched_at: Instant::now(),
+            },
+        );
+        Ok(key)
+    }
+}
+
+impl JsonWebKey {
+    /// The key as a DER encoded `RSAPublicKey`, which is how `jsonwebtoken` expects RS256
+    /// verification keys
+    pub fn rsa_public_key_der(&self) -> Result<Vec<u8>, BigNeonError> {
+        let modulus = decode_component(self.n.as_ref())?;
+        let exponent = decode_component(self.e.as_ref())?;
+
+        let mut content = der_integer(&modulus);
+        content.extend(der_integer(&exponent));
+        Ok(der_element(0x30, content))
+    }
+}
+
+fn decode_component(value: Option<&String>) -> Result<Vec<u8>, BigNeonError> {
+    let value = value.ok_or_else(|| {
+        ApplicationError::new("Identity provider key is not an RSA key".to_string())
+    })?;
+    base64::decode_config(value, base64::URL_SAFE_NO_PAD)
+        .map_err(|e| ApplicationError::new(format!("Invalid identity provider key: {}", e)).into())
+}
+
+/// Big endian unsigned integer, with a leading zero byte when needed to keep it positive
+fn der_integer(value: &[u8]) -> Vec<u8> {
+    let first_significant = value.iter().position(|b| *b != 0).unwrap_or(value.len());
+    let value = &value[first_significant..];
+    let mut content = Vec::with_capacity(value.len() + 1);
+    if value.is_empty() || value[0] & 0x80 != 0 {
+        content.push(0);
+    }
+    content.extend_from_slice(value);
+    der_element(0x02, content)
+}
+
+fn der_element(tag: u8, content: Vec<u8>) -> Vec<u8> {
+    let mut element = vec![tag];
+    let length = content.len();
+    if length < 0x80 {
+        element.push(length as u8);
+    } else {
+        let length_bytes: Vec<u8> = (0..8)
+            .rev()
+            .map(|i| (length >> (i * 8)) as u8)
+            .skip_while(|b| *b == 0)
+            .collect();
+        element.push(0x80 | length_bytes.len() as u8);
+        element.extend(length_bytes);
+    }
+    element.extend(content);
+    element
+}
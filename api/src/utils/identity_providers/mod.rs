@@ -0,0 +1,59 @@
+use config::Config;
+use errors::*;
+
+pub use self::facebook::FacebookIdentityProvider;
+pub use self::jwks::*;
+pub use self::oidc::OidcIdentityProvider;
+
+mod facebook;
+mod jwks;
+mod oidc;
+
+pub const FACEBOOK_PROVIDER: &str = "facebook";
+
+/// A user as vouched for by an external identity provider
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExternalIdentity {
+    pub external_user_id: String,
+    pub email: Option<String>,
+    pub email_verified: bool,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+}
+
+impl ExternalIdentity {
+    /// Only an address the provider has verified can be used to find or create an account
+    pub fn verified_email(&self) -> Option<&str> {
+        match self.email {
+            Some(ref email) if self.email_verified => Some(email),
+            _ => None,
+        }
+    }
+}
+
+pub trait IdentityProvider {
+    /// Recorded as the `site` of the user's external logins
+    fn site(&self) -> &str;
+
+    /// Checks the token the client received from the provider and returns who it identifies
+    fn verify(&self, token: &str) -> Result<ExternalIdentity, BigNeonError>;
+}
+
+/// The provider named in the request path, Facebook is always available while the OpenID
+/// Connect providers (Google, Apple and others) are only available once configured
+pub fn find_identity_provider(
+    name: &str,
+    config: &Config,
+) -> Result<Box<IdentityProvider>, BigNeonError> {
+    if name == FACEBOOK_PROVIDER {
+        return Ok(Box::new(FacebookIdentityProvider::new()));
+    }
+    match config.identity_providers.iter().find(|p| p.name == name) {
+        Some(provider_config) => Ok(Box::new(OidcIdentityProvider::new(provider_config.clone()))),
+        None => Err(ApplicationError::new_with_type(
+            ApplicationErrorType::Unprocessable,
+            format!("Identity provider {} is not supported", name),
+        )
+        .into()),
+    }
+}
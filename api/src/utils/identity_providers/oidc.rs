@@ -0,0 +1,81 @@
+use config::IdentityProviderConfig;
+use errors::*;
+use jwt::{decode, decode_header, Algorithm, Validation};
+use serde_json::Value;
+use utils::identity_providers::{ExternalIdentity, IdentityProvider, JsonWebKey, JsonWebKeySet};
+
+#[derive(Deserialize)]
+struct IdTokenClaims {
+    sub: String,
+    email: Option<String>,
+    // Apple sends this as the string "true" rather than a boolean
+    email_verified: Option<Value>,
+    given_name: Option<String>,
+    family_name: Option<String>,
+}
+
+/// Identifies the user with an OpenID Connect ID token, checking its signature against the
+/// provider's published key set and that it was issued by the provider for this client
+pub struct OidcIdentityProvider {
+    config: IdentityProviderConfig,
+}
+
+impl OidcIdentityProvider {
+    pub fn new(config: IdentityProviderConfig) -> OidcIdentityProvider {
+        OidcIdentityProvider { config }
+    }
+
+    fn find_key(&self, kid: Option<&str>) -> Result<Option<JsonWebKey>, BigNeonError> {
+        if let Some(ref jwks) = self.config.jwks {
+            return Ok(jwks.find(kid).cloned());
+        }
+        match self.config.jwks_url {
+            Some(ref jwks_url) => JsonWebKeySet::find_cached(jwks_url, kid),
+            None => Err(ApplicationError::new_with_type(
+                ApplicationErrorType::ServerConfigError,
+                format!(
+                    "No keys configured for identity provider {}",
+                    self.config.name
+                ),
+            )
+            .into()),
+        }
+    }
+}
+
+impl IdentityProvider for OidcIdentityProvider {
+    fn site(&self) -> &str {
+        &self.config.site
+    }
+
+    fn verify(&self, token: &str) -> Result<ExternalIdentity, BigNeonError> {
+        let header = decode_header(token)?;
+        let key = self
+            .find_key(header.kid.as_ref().map(|k| k.as_str()))?
+            .ok_or_else(|| {
+                AuthError::new(
+                    AuthErrorType::Unauthorized,
+                    "Token was not signed with a known key".to_string(),
+                )
+            })?;
+
+        let mut validation = Validation::new(Algorithm::RS256);
+        validation.iss = Some(self.config.issuer.clone());
+        validation.set_audience(&self.config.client_id);
+        let claims =
+            decode::<IdTokenClaims>(token, &key.rsa_public_key_der()?, &validation)?.claims;
+
+        let email_verified = match claims.email_verified {
+            Some(Value::Bool(verified)) => verified,
+            Some(Value::String(ref verified)) => verified == "true",
+            _ => false,
+        };
+        Ok(ExternalIdentity {
+            external_user_id: claims.sub,
+            email: claims.email,
+            email_verified,
+            first_name: claims.given_name,
+            last_name: claims.family_name,
+        })
+    }
+}
@@ -1,9 +1,17 @@
 pub use self::service_locator::*;
 
 pub mod communication;
+pub mod csv;
+pub mod export;
 pub mod google_recaptcha;
+pub mod identity_providers;
+pub mod ical;
 pub mod marketing_contacts;
+pub mod money;
+pub mod pdf;
+pub mod report_export;
 pub mod sendgrid;
 mod service_locator;
 pub mod spotify;
 pub mod twilio;
+pub mod xlsx;
@@ -0,0 +1,6 @@
+/// Formats an amount in cents as a decimal string, e.g. -1050 becomes "-10.50"
+pub fn format_cents(amount_in_cents: i64) -> String {
+    let sign = if amount_in_cents < 0 { "-" } else { "" };
+    let amount = amount_in_cents.abs();
+    format!("{}{}.{:02}", sign, amount / 100, amount % 100)
+}
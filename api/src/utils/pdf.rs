@@ -0,0 +1,109 @@
+pub const PDF_CONTENT_TYPE: &'static str = "application/pdf";
+
+const PAGE_WIDTH: usize = 612;
+const PAGE_HEIGHT: usize = 792;
+const MARGIN: usize = 50;
+const FONT_SIZE: usize = 10;
+const LINE_HEIGHT: usize = 14;
+
+/// Renders a PDF document with a title followed by one line per row. Each row is laid
+/// out using the column offsets, in points, from the left margin. Rows that do not fit
+/// on a page continue on the next one.
+pub fn document(title: &str, column_offsets: &[usize], rows: &[Vec<String>]) -> Vec<u8> {
+    let mut pages = Vec::new();
+    let mut content = String::new();
+    let mut y = PAGE_HEIGHT - MARGIN;
+    content.push_str(&text(MARGIN, y, FONT_SIZE + 4, title));
+    y -= LINE_HEIGHT * 2;
+
+    for row in rows {
+        if y < MARGIN {
+            pages.push(content);
+            content = String::new();
+            y = PAGE_HEIGHT - MARGIN;
+        }
+        for (i, field) in row.iter().enumerate() {
+            let offset = column_offsets.get(i).cloned().unwrap_or(0);
+            content.push_str(&text(MARGIN + offset, y, FONT_SIZE, field));
+        }
+        y -= LINE_HEIGHT;
+    }
+    pages.push(content);
+
+    // Objects 1 to 3 are the catalog, page tree and font, followed by a page and
+    // contents object for each page
+    let kids: Vec<String> = (0..pages.len())
+        .map(|i| format!("{} 0 R", 4 + i * 2))
+        .collect();
+    let mut objects = vec![
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            kids.join(" "),
+            pages.len()
+        ),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+            .to_string(),
+    ];
+    for (i, content) in pages.iter().enumerate() {
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Contents {} 0 R /Resources << /Font << /F1 3 0 R >> >> >>",
+            PAGE_WIDTH,
+            PAGE_HEIGHT,
+            5 + i * 2
+        ));
+        objects.push(format!(
+            "<< /Length {} >>\nstream\n{}endstream",
+            content.len(),
+            content
+        ));
+    }
+
+    let mut result = "%PDF-1.4\n".to_string();
+    let mut offsets = Vec::new();
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(result.len());
+        result.push_str(&format!("{} 0 obj\n{}\nendobj\n", i + 1, object));
+    }
+
+    let xref_offset = result.len();
+    result.push_str(&format!(
+        "xref\n0 {}\n0000000000 65535 f \n",
+        objects.len() + 1
+    ));
+    for offset in offsets {
+        result.push_str(&format!("{:010} 00000 n \n", offset));
+    }
+    result.push_str(&format!(
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+        objects.len() + 1,
+        xref_offset
+    ));
+    result.into_bytes()
+}
+
+fn text(x: usize, y: usize, size: usize, value: &str) -> String {
+    format!(
+        "BT /F1 {} Tf {} {} Td ({}) Tj ET\n",
+        size,
+        x,
+        y,
+        escape_text(value)
+    )
+}
+
+/// Escapes string delimiters and replaces characters outside the standard font's range
+fn escape_text(value: &str) -> String {
+    let mut result = String::new();
+    for c in value.chars() {
+        match c {
+            '\\' | '(' | ')' => {
+                result.push('\\');
+                result.push(c);
+            }
+            ' '...'~' => result.push(c),
+            _ => result.push('?'),
+        }
+    }
+    result
+}
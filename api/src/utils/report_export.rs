@@ -0,0 +1,412 @@
+use bigneon_db::models::*;
+use diesel::PgConnection;
+use errors::*;
+use std::collections::HashMap;
+use utils::export::{self, ExportCell};
+use uuid::Uuid;
+
+pub const TRANSACTION_COLUMNS: &'static [&'static str] = &[
+    "Event",
+    "Ticket",
+    "Quantity",
+    "Refunded quantity",
+    "Unit price",
+    "Gross",
+    "Company fee",
+    "Client fee",
+    "Gross fee",
+    "Gross fee total",
+    "Event fee company",
+    "Event fee client",
+    "Event fee gross",
+    "Event fee gross total",
+    "Order type",
+    "Payment method",
+    "Transaction date",
+    "Redemption code",
+    "Order ID",
+    "Event ID",
+    "User ID",
+    "First name",
+    "Last name",
+    "Email",
+    "Currency",
+    "Fee mode",
+];
+
+pub const SUMMARY_COLUMNS: &'static [&'static str] = &[
+    "Ticket",
+    "Pricing",
+    "Price",
+    "Online",
+    "Box office",
+    "Comps",
+    "Total sold",
+    "Client fees",
+    "Company fees",
+    "Absorbed fees",
+    "Gross income",
+];
+
+pub const SPLIT_PARTNER_SUMMARY_COLUMNS: &'static [&'static str] =
+    &["Currency", "Split type", "Ticket sales share", "Fees share"];
+
+pub const TICKET_COUNT_COLUMNS: &'static [&'static str] = &[
+    "Event",
+    "Ticket",
+    "Status",
+    "Allocation",
+    "Available",
+    "Purchased",
+    "Reserved",
+    "Redeemed",
+    "Refunded",
+    "Nullified",
+    "Comps",
+    "Holds",
+    "Online sales",
+    "Box office sales",
+];
+
+pub const SALES_BY_SOURCE_COLUMNS: &'static [&'static str] = &[
+    "Source",
+    "Medium",
+    "Campaign",
+    "Source ID",
+    "Carts",
+    "Orders",
+    "Conversion rate",
+    "Tickets",
+    "Revenue",
+];
+
+pub const AFFILIATE_COMMISSION_COLUMNS: &'static [&'static str] = &[
+    "Affiliate",
+    "Code",
+    "Event",
+    "Ticket",
+    "Orders",
+    "Tickets",
+    "Revenue",
+    "Commission",
+];
+
+pub fn prefix_rows(prefix: &str, rows: Vec<Vec<ExportCell>>) -> Vec<Vec<ExportCell>> {
+    rows.into_iter()
+        .map(|mut row| {
+            row.insert(0, prefix.into());
+            row
+        })
+        .collect()
+}
+
+/// Formats the transaction date in the timezone of the event's venue, venue timezones are
+/// cached as the same events appear on many rows
+pub fn transaction_export_row(
+    row: &TransactionReportRow,
+    timezones: &mut HashMap<Uuid, Option<String>>,
+    connection: &PgConnection,
+) -> Result<Vec<ExportCell>, BigNeonError> {
+    if !timezones.contains_key(&row.event_id) {
+        let venue = Event::find(row.event_id, connection)?.venue(connection)?;
+        timezones.insert(row.event_id, venue.map(|v| v.timezone));
+    }
+    let transaction_date =
+        match Event::localized_time(&Some(row.transaction_date), &timezones[&row.event_id]) {
+            Some(local_date) => local_date.format("%Y-%m-%d %H:%M:%S %Z").to_string(),
+            None => row
+                .transaction_date
+                .format("%Y-%m-%d %H:%M:%S UTC")
+                .to_string(),
+        };
+
+    Ok(vec![
+        row.event_name.clone().into(),
+        row.ticket_name.clone().into(),
+        ExportCell::Integer(row.quantity),
+        ExportCell::Integer(row.refunded_quantity),
+        ExportCell::Money(row.unit_price_in_cents),
+        ExportCell::Money(row.gross),
+        ExportCell::Money(row.company_fee_in_cents),
+        ExportCell::Money(row.client_fee_in_cents),
+        ExportCell::Money(row.gross_fee_in_cents),
+        ExportCell::Money(row.gross_fee_in_cents_total),
+        ExportCell::Money(row.event_fee_company_in_cents),
+        ExportCell::Money(row.event_fee_client_in_cents),
+        ExportCell::Money(row.event_fee_gross_in_cents),
+        ExportCell::Money(row.event_fee_gross_in_cents_total),
+        row.order_type.to_string().into(),
+        row.payment_method.as_ref().map(|p| p.to_string()).into(),
+        transaction_date.into(),
+        row.redemption_code.clone().into(),
+        row.order_id.to_string().into(),
+        row.event_id.to_string().into(),
+        row.user_id.to_string().into(),
+        row.first_name.clone().into(),
+        row.last_name.clone().into(),
+        row.email.clone().into(),
+        row.currency.clone().into(),
+        row.fee_mode.to_string().into(),
+    ])
+}
+
+pub fn summary_export_rows(result: &EventSummarySalesResult) -> Vec<Vec<ExportCell>> {
+    let mut rows: Vec<Vec<ExportCell>> = result
+        .sales
+        .iter()
+        .map(|row| {
+            vec![
+                row.ticket_name.clone().into(),
+                row.pricing_name.clone().into(),
+                ExportCell::Money(row.price_in_cents),
+                ExportCell::Integer(row.online_count),
+                ExportCell::Integer(row.box_office_count),
+                ExportCell::Integer(row.comp_count),
+                ExportCell::Integer(row.total_sold),
+                ExportCell::Money(row.total_client_fee_in_cents),
+                ExportCell::Money(row.total_company_fee_in_cents),
+                ExportCell::Money(row.total_absorbed_fee_in_cents),
+                ExportCell::Money(row.total_gross_income_in_cents),
+            ]
+        })
+        .collect();
+
+    for row in &result.other_fees {
+        rows.push(vec![
+            "Event fees".into(),
+            "".into(),
+            ExportCell::Money(row.unit_price_in_cents),
+            "".into(),
+            "".into(),
+            "".into(),
+            "".into(),
+            ExportCell::Money(row.total_client_fee_in_cents),
+            ExportCell::Money(row.total_company_fee_in_cents),
+            ExportCell::Money(row.total_absorbed_fee_in_cents),
+            ExportCell::Money(
+                row.total_client_fee_in_cents + row.total_company_fee_in_cents
+                    - row.total_absorbed_fee_in_cents,
+            ),
+        ]);
+    }
+    rows
+}
+
+pub fn split_partner_summary_export_rows(
+    summary: &SplitPartnerEventSummary,
+) -> Vec<Vec<ExportCell>> {
+    vec![vec![
+        summary.currency.clone().into(),
+        summary.split_type.to_string().into(),
+        ExportCell::Money(summary.ticket_sales_in_cents),
+        ExportCell::Money(summary.fees_in_cents),
+    ]]
+}
+
+pub fn ticket_count_export_rows(result: &TicketSalesAndCounts) -> Vec<Vec<ExportCell>> {
+    result
+        .counts
+        .iter()
+        .map(|count| {
+            let sales = result.sales.iter().find(|sales| {
+                sales.event_id == count.event_id && sales.ticket_type_id == count.ticket_type_id
+            });
+            vec![
+                count.event_name.clone().into(),
+                count.ticket_name.clone().into(),
+                count.ticket_status.clone().into(),
+                ExportCell::Integer(count.allocation_count),
+                ExportCell::Integer(count.available_for_purchase_count),
+                ExportCell::Integer(count.purchased_count),
+                ExportCell::Integer(count.reserved_count),
+                ExportCell::Integer(count.redeemed_count),
+                ExportCell::Integer(count.total_refunded_count),
+                ExportCell::Integer(count.nullified_count),
+                ExportCell::Integer(count.comp_count),
+                ExportCell::Integer(count.hold_count),
+                ExportCell::Money(sales.map(|s| s.online_sales_in_cents).unwrap_or(0)),
+                ExportCell::Money(sales.map(|s| s.box_office_sales_in_cents).unwrap_or(0)),
+            ]
+        })
+        .collect()
+}
+
+pub fn sales_by_source_export_rows(result: &[SalesBySourceRow]) -> Vec<Vec<ExportCell>> {
+    result
+        .iter()
+        .map(|row| {
+            vec![
+                row.utm_source.clone().into(),
+                row.utm_medium.clone().into(),
+                row.utm_campaign.clone().into(),
+                row.source_id.clone().into(),
+                ExportCell::Integer(row.cart_count),
+                ExportCell::Integer(row.order_count),
+                format!(
+                    "{}.{:02}%",
+                    row.conversion_rate_in_basis_points / 100,
+                    row.conversion_rate_in_basis_points % 100
+                )
+                .into(),
+                ExportCell::Integer(row.ticket_count),
+                ExportCell::Money(row.revenue_in_cents),
+            ]
+        })
+        .collect()
+}
+
+pub fn affiliate_commission_export_rows(result: &[AffiliateCommissionRow]) -> Vec<Vec<ExportCell>> {
+    result
+        .iter()
+        .map(|row| {
+            vec![
+                row.affiliate_name.clone().into(),
+                row.affiliate_code.clone().into(),
+                row.event_name.clone().into(),
+                row.ticket_type_name.clone().into(),
+                ExportCell::Integer(row.order_count),
+                ExportCell::Integer(row.ticket_count),
+                ExportCell::Money(row.revenue_in_cents),
+                ExportCell::Money(row.commission_in_cents),
+            ]
+        })
+        .collect()
+}
+
+/// Rows for the organization summary, one section per event and currency
+pub fn weekly_settlement_export_rows(
+    result: &[EventSummarySalesResult],
+    connection: &PgConnection,
+) -> Result<Vec<Vec<ExportCell>>, BigNeonError> {
+    let mut rows = Vec::new();
+    for event_result in result {
+        let event = Event::find(event_result.event_id, connection)?;
+        rows.append(&mut prefix_rows(
+            &event.name,
+            prefix_rows(&event_result.currency, summary_export_rows(event_result)),
+        ));
+    }
+    Ok(rows)
+}
+
+pub fn audit_export_rows(
+    end_date_sales: &EventSummarySalesResult,
+    all_sales: &EventSummarySalesResult,
+) -> Vec<Vec<ExportCell>> {
+    let mut rows = prefix_rows("End date", summary_export_rows(end_date_sales));
+    rows.append(&mut prefix_rows(
+        "All sales",
+        summary_export_rows(all_sales),
+    ));
+    rows
+}
+
+pub fn prefixed_columns(prefix: &'static str) -> Vec<&'static str> {
+    let mut columns = vec![prefix];
+    columns.extend_from_slice(SUMMARY_COLUMNS);
+    columns
+}
+
+pub fn weekly_settlement_columns() -> Vec<&'static str> {
+    let mut columns = prefixed_columns("Currency");
+    columns.insert(0, "Event");
+    columns
+}
+
+pub struct RenderedReport {
+    pub filename: String,
+    pub content_type: &'static str,
+    pub content: Vec<u8>,
+}
+
+/// Renders the report for the subscription's current reporting period
+pub fn render_subscription(
+    subscription: &ReportSubscription,
+    connection: &PgConnection,
+) -> Result<RenderedReport, BigNeonError> {
+    let (start, end) = subscription.reporting_period();
+    let (start_utc, end_utc) = (Some(start), Some(end));
+    let organization_id = subscription.organization_id;
+    let event_id = subscription.event_id;
+    let required_event_id =
+        || event_id.ok_or_else(|| ApplicationError::new("Report requires an event".to_string()));
+
+    let (name, columns, rows) = match subscription.report_type {
+        ReportTypes::TransactionDetails => {
+            let mut timezones = HashMap::new();
+            let rows = Report::transaction_detail_report(
+                event_id,
+                Some(organization_id),
+                start_utc,
+                end_utc,
+                connection,
+            )?
+            .iter()
+            .map(|row| transaction_export_row(row, &mut timezones, connection))
+            .collect::<Result<Vec<Vec<ExportCell>>, BigNeonError>>()?;
+            ("transaction_details", TRANSACTION_COLUMNS.to_vec(), rows)
+        }
+        ReportTypes::EventSummary => {
+            let result =
+                Report::summary_event_report(required_event_id()?, start_utc, end_utc, connection)?;
+            (
+                "event_summary",
+                SUMMARY_COLUMNS.to_vec(),
+                summary_export_rows(&result),
+            )
+        }
+        ReportTypes::WeeklySettlement => {
+            let result = Report::organization_summary_report(
+                organization_id,
+                start_utc,
+                end_utc,
+                connection,
+            )?;
+            (
+                "weekly_settlement",
+                weekly_settlement_columns(),
+                weekly_settlement_export_rows(&result, connection)?,
+            )
+        }
+        ReportTypes::TicketCount => {
+            let result = Report::ticket_count_report(event_id, Some(organization_id), connection)?;
+            (
+                "ticket_count",
+                TICKET_COUNT_COLUMNS.to_vec(),
+                ticket_count_export_rows(&result),
+            )
+        }
+        ReportTypes::AuditReport => {
+            let event_id = required_event_id()?;
+            let all_sales = Report::summary_event_report(event_id, None, end_utc, connection)?;
+            let end_date_sales =
+                Report::summary_event_report(event_id, start_utc, end_utc, connection)?;
+            (
+                "audit_report",
+                prefixed_columns("Section"),
+                audit_export_rows(&end_date_sales, &all_sales),
+            )
+        }
+    };
+
+    let (mut writer, content_type) = export::writer(subscription.format).ok_or_else(|| {
+        ApplicationError::new(format!(
+            "Scheduled reports can not be delivered as {}",
+            subscription.format
+        ))
+    })?;
+    let mut content = writer.header(&columns)?;
+    content.append(&mut writer.rows(&rows)?);
+    content.append(&mut writer.finish()?);
+
+    Ok(RenderedReport {
+        filename: format!(
+            "{}-{}.{}",
+            name,
+            end.format("%Y-%m-%d"),
+            subscription.format.extension()
+        ),
+        content_type,
+        content,
+    })
+}
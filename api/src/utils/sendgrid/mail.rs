@@ -44,6 +44,7 @@ pub fn send_email_async(
     dest_email_addresses: Vec<String>,
     title: String,
     body: Option<String>,
+    attachments: Option<&Vec<CommAttachment>>,
 ) -> Box<Future<Item = (), Error = BigNeonError>> {
     let mut sg_message = SGMailMessage::new();
     sg_message.subject = Some(title);
@@ -60,6 +61,7 @@ pub fn send_email_async(
         msg_content.value = body;
     }
     sg_message.content.push(msg_content);
+    sg_message.add_attachments(attachments);
 
     Box::new(sg_message.send_async(sg_api_key))
 }
@@ -105,6 +107,7 @@ pub fn send_email_template_async(
     dest_email_addresses: &[String],
     template_id: String,
     template_data: &[TemplateData],
+    attachments: Option<&Vec<CommAttachment>>,
 ) -> Box<Future<Item = (), Error = BigNeonError>> {
     Box::new(if dest_email_addresses.len() != template_data.len() {
         Either::A(future::err(
@@ -129,6 +132,7 @@ pub fn send_email_template_async(
 
         let msg_content = SGContent::new();
         sg_message.content.push(msg_content);
+        sg_message.add_attachments(attachments);
 
         Either::B(sg_message.send_async(&sg_api_key))
     })
@@ -177,6 +181,26 @@ impl SGContent {
     }
 }
 
+#[derive(Clone, Serialize)]
+pub struct SGAttachment {
+    pub content: String,
+    #[serde(rename = "type")]
+    pub content_type: String,
+    pub filename: String,
+    pub disposition: String,
+}
+
+impl SGAttachment {
+    pub fn from(attachment: &CommAttachment) -> SGAttachment {
+        SGAttachment {
+            content: attachment.content.clone(),
+            content_type: attachment.content_type.clone(),
+            filename: attachment.filename.clone(),
+            disposition: "attachment".to_string(),
+        }
+    }
+}
+
 #[derive(Serialize)]
 pub struct SGPersonalization {
     pub to: Vec<SGEmail>,
@@ -220,6 +244,8 @@ pub struct SGMailMessage {
     pub personalizations: Vec<SGPersonalization>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub template_id: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<SGAttachment>,
 }
 
 impl SGMailMessage {
@@ -230,6 +256,15 @@ impl SGMailMessage {
             content: Vec::new(),
             personalizations: Vec::new(),
             template_id: None,
+            attachments: Vec::new(),
+        }
+    }
+
+    pub fn add_attachments(&mut self, attachments: Option<&Vec<CommAttachment>>) {
+        if let Some(attachments) = attachments {
+            for attachment in attachments {
+                self.attachments.push(SGAttachment::from(attachment));
+            }
         }
     }
 
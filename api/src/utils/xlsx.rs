@@ -0,0 +1,126 @@
+use errors::BigNeonError;
+use std::io::{Cursor, Write};
+use utils::export::{escape_formula, ExportCell, ExportWriter};
+use utils::money::format_cents;
+use zip::result::ZipResult;
+use zip::write::{FileOptions, ZipWriter};
+
+pub const XLSX_CONTENT_TYPE: &'static str =
+    "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet";
+
+const SHEET_NAME: &'static str = "xl/worksheets/sheet1.xml";
+
+const CONTENT_TYPES: &'static str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/><Override PartName="/xl/worksheets/sheet1.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/><Override PartName="/xl/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml"/></Types>"#;
+
+const ROOT_RELS: &'static str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/></Relationships>"#;
+
+const WORKBOOK: &'static str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="Report" sheetId="1" r:id="rId1"/></sheets></workbook>"#;
+
+const WORKBOOK_RELS: &'static str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles" Target="styles.xml"/></Relationships>"#;
+
+// Style 1 is used for money cells and displays two decimal places
+const STYLES: &'static str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><fonts count="2"><font><sz val="11"/><name val="Calibri"/></font><font><b/><sz val="11"/><name val="Calibri"/></font></fonts><fills count="2"><fill><patternFill patternType="none"/></fill><fill><patternFill patternType="gray125"/></fill></fills><borders count="1"><border><left/><right/><top/><bottom/><diagonal/></border></borders><cellStyleXfs count="1"><xf numFmtId="0" fontId="0" fillId="0" borderId="0"/></cellStyleXfs><cellXfs count="3"><xf numFmtId="0" fontId="0" fillId="0" borderId="0" xfId="0"/><xf numFmtId="2" fontId="0" fillId="0" borderId="0" xfId="0" applyNumberFormat="1"/><xf numFmtId="0" fontId="1" fillId="0" borderId="0" xfId="0" applyFont="1"/></cellXfs></styleSheet>"#;
+
+const SHEET_START: &'static str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>"#;
+
+const SHEET_END: &'static str = "</sheetData></worksheet>";
+
+/// Writes a single sheet workbook. The archive is compressed in memory and returned by
+/// `finish` as the sheet entry's header can only be completed once every row is written.
+pub struct XlsxWriter {
+    zip: ZipWriter<Cursor<Vec<u8>>>,
+}
+
+impl XlsxWriter {
+    pub fn new() -> XlsxWriter {
+        XlsxWriter {
+            zip: ZipWriter::new(Cursor::new(Vec::new())),
+        }
+    }
+
+    fn write_entry(&mut self, name: &str, data: &[u8]) -> ZipResult<()> {
+        self.zip.start_file(name, FileOptions::default())?;
+        self.zip.write_all(data)?;
+        Ok(())
+    }
+
+    fn write_sheet(&mut self, data: &str) -> ZipResult<()> {
+        self.zip.write_all(data.as_bytes())?;
+        Ok(())
+    }
+}
+
+impl ExportWriter for XlsxWriter {
+    fn header(&mut self, columns: &[&str]) -> Result<Vec<u8>, BigNeonError> {
+        self.write_entry("[Content_Types].xml", CONTENT_TYPES.as_bytes())?;
+        self.write_entry("_rels/.rels", ROOT_RELS.as_bytes())?;
+        self.write_entry("xl/workbook.xml", WORKBOOK.as_bytes())?;
+        self.write_entry("xl/_rels/workbook.xml.rels", WORKBOOK_RELS.as_bytes())?;
+        self.write_entry("xl/styles.xml", STYLES.as_bytes())?;
+        // Large reports may exceed the 4GB limit of a standard zip entry
+        self.zip
+            .start_file(SHEET_NAME, FileOptions::default().large_file(true))?;
+
+        let mut sheet = SHEET_START.to_string();
+        sheet.push_str("<row>");
+        for column in columns {
+            sheet.push_str(&format!(
+                r#"<c t="inlineStr" s="2"><is><t>{}</t></is></c>"#,
+                escape_xml(column)
+            ));
+        }
+        sheet.push_str("</row>");
+        self.write_sheet(&sheet)?;
+        Ok(Vec::new())
+    }
+
+    fn rows(&mut self, rows: &[Vec<ExportCell>]) -> Result<Vec<u8>, BigNeonError> {
+        let mut sheet = String::new();
+        for row in rows {
+            sheet.push_str("<row>");
+            for cell in row {
+                sheet.push_str(&match cell {
+                    ExportCell::Text(value) => format!(
+                        r#"<c t="inlineStr"><is><t xml:space="preserve">{}</t></is></c>"#,
+                        escape_xml(&escape_formula(value))
+                    ),
+                    ExportCell::Integer(value) => format!("<c><v>{}</v></c>", value),
+                    ExportCell::Money(value) => {
+                        format!(r#"<c s="1"><v>{}</v></c>"#, format_cents(*value))
+                    }
+                });
+            }
+            sheet.push_str("</row>");
+        }
+        self.write_sheet(&sheet)?;
+        Ok(Vec::new())
+    }
+
+    fn finish(&mut self) -> Result<Vec<u8>, BigNeonError> {
+        self.write_sheet(SHEET_END)?;
+        Ok(self.zip.finish()?.into_inner())
+    }
+}
+
+/// Escapes markup characters and drops control characters which are not allowed in XML
+fn escape_xml(value: &str) -> String {
+    let mut result = String::new();
+    for c in value.chars() {
+        match c {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            '\t' | '\n' | '\r' => result.push(c),
+            c if c.is_control() => {}
+            _ => result.push(c),
+        }
+    }
+    result
+}
@@ -0,0 +1,148 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path, Query};
+use bigneon_api::auth::user::User as AuthUser;
+use bigneon_api::controllers::affiliates::{self, RecordClickRequest, SalesQueryParameters};
+use bigneon_api::extractors::*;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use functional::base;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+    #[test]
+    fn create_org_member() {
+        base::affiliates::create(Roles::OrgMember, false);
+    }
+    #[test]
+    fn create_admin() {
+        base::affiliates::create(Roles::Admin, true);
+    }
+    #[test]
+    fn create_user() {
+        base::affiliates::create(Roles::User, false);
+    }
+    #[test]
+    fn create_org_owner() {
+        base::affiliates::create(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn create_door_person() {
+        base::affiliates::create(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn create_org_admin() {
+        base::affiliates::create(Roles::OrgAdmin, true);
+    }
+    #[test]
+    fn create_box_office() {
+        base::affiliates::create(Roles::OrgBoxOffice, false);
+    }
+}
+
+#[test]
+fn record_click() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let affiliate = Affiliate::create(
+        organization.id,
+        "Promoter",
+        Some("SUMMER".to_string()),
+        None,
+    )
+    .commit(connection)
+    .unwrap();
+
+    let json = Json(RecordClickRequest {
+        code: "summer".to_string(),
+    });
+    let response: HttpResponse =
+        affiliates::record_click((database.connection.clone().into(), json)).into();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let click: AffiliateClick = serde_json::from_str(&body).unwrap();
+    assert_eq!(click.affiliate_id, affiliate.id);
+
+    let json = Json(RecordClickRequest {
+        code: "winter".to_string(),
+    });
+    let response: HttpResponse =
+        affiliates::record_click((database.connection.clone().into(), json)).into();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[test]
+fn sales() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let affiliate_user = database.create_user().finish();
+    let affiliate = Affiliate::create(organization.id, "Promoter", None, Some(affiliate_user.id))
+        .commit(connection)
+        .unwrap();
+    AffiliateCommissionRule::create(affiliate.id, None, CommissionTypes::Flat, 100, 0)
+        .commit(connection)
+        .unwrap();
+    let click = affiliate.record_click(connection).unwrap();
+    database
+        .create_order()
+        .for_event(&event)
+        .quantity(2)
+        .is_paid()
+        .finish()
+        .set_attribution(
+            OrderAttribution {
+                affiliate_click_id: Some(click.id),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+    let other_affiliate = Affiliate::create(organization.id, "Blogger", None, None)
+        .commit(connection)
+        .unwrap();
+
+    let sales = |affiliate: &Affiliate, user: AuthUser| -> HttpResponse {
+        let test_request = TestRequest::create_with_uri("/sales?");
+        let query_parameters =
+            Query::<SalesQueryParameters>::extract(&test_request.request).unwrap();
+        let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+        path.id = affiliate.id;
+        affiliates::sales((
+            database.connection.clone().into(),
+            path,
+            query_parameters,
+            user,
+        ))
+        .into()
+    };
+
+    // Affiliates can view their own sales
+    let auth_user =
+        support::create_auth_user_from_user(&affiliate_user, Roles::User, None, &database);
+    let response = sales(&affiliate, auth_user.clone());
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let rows: Vec<AffiliateCommissionRow> = serde_json::from_str(&body).unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].ticket_count, 2);
+    assert_eq!(rows[0].commission_in_cents, 200);
+
+    // But not the sales of other affiliates
+    let response = sales(&other_affiliate, auth_user);
+    support::expects_unauthorized(&response);
+
+    let org_admin = support::create_auth_user(Roles::OrgAdmin, Some(&organization), &database);
+    let response = sales(&other_affiliate, org_admin);
+    assert_eq!(response.status(), StatusCode::OK);
+}
@@ -121,6 +121,7 @@ fn index_with_org_linked_and_private_venues() {
     let _ = org1.add_user(
         user_id,
         vec![Roles::OrgMember],
+        Vec::new(),
         database.connection.clone().get(),
     );
     expected_artists.push(artist4);
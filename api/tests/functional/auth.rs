@@ -3,6 +3,7 @@ use bigneon_api::auth::{claims::AccessToken, claims::RefreshToken, TokenResponse
 use bigneon_api::controllers::auth;
 use bigneon_api::controllers::auth::{LoginRequest, RefreshRequest};
 use bigneon_api::extractors::*;
+use bigneon_db::models::{User, UserSession};
 use jwt::{decode, encode, Header, Validation};
 use serde_json;
 use support;
@@ -25,8 +26,12 @@ fn token() {
     let state = test_request.extract_state();
     let json = Json(LoginRequest::new("fake@localhost", "strong_password"));
 
-    let response: TokenResponse =
-        auth::token((test_request.request, database.connection.into(), json)).unwrap();
+    let response: TokenResponse = auth::token((
+        test_request.request,
+        database.connection.clone().into(),
+        json,
+    ))
+    .unwrap();
 
     let access_token = decode::<AccessToken>(
         &response.access_token,
@@ -46,6 +51,12 @@ fn token() {
 
     assert_eq!(access_token.claims.get_id().unwrap(), user.id);
     assert_eq!(refresh_token.claims.get_id().unwrap(), user.id);
+
+    // Each sign in starts a session
+    let sessions = UserSession::find_active_for_user(user.id, database.connection.get()).unwrap();
+    assert_eq!(sessions.len(), 1);
+    assert_eq!(refresh_token.claims.sid, Some(sessions[0].id));
+    assert_eq!(refresh_token.claims.jti, Some(sessions[0].refresh_token_id));
 }
 
 #[test]
@@ -85,15 +96,113 @@ fn token_incorrect_password() {
     );
 }
 
+#[test]
+fn token_with_two_factor_authentication() {
+    let database = TestDatabase::new();
+    let user = database
+        .create_user()
+        .with_email("fake@localhost".to_string())
+        .with_password("strong_password".to_string())
+        .finish();
+    let secret = support::enable_two_factor_authentication(&user, &database);
+
+    let test_request = TestRequest::create();
+    let json = Json(LoginRequest::new("fake@localhost", "strong_password"));
+    let response = auth::token((
+        test_request.request,
+        database.connection.clone().into(),
+        json,
+    ));
+    assert_eq!(
+        "Two factor code required",
+        response.err().unwrap().to_string()
+    );
+
+    let test_request = TestRequest::create();
+    let json =
+        Json(LoginRequest::new("fake@localhost", "strong_password").with_two_factor_code("12345x"));
+    let response = auth::token((
+        test_request.request,
+        database.connection.clone().into(),
+        json,
+    ));
+    assert_eq!(
+        "Two factor code incorrect",
+        response.err().unwrap().to_string()
+    );
+
+    let test_request = TestRequest::create();
+    let json = Json(
+        LoginRequest::new("fake@localhost", "strong_password")
+            .with_two_factor_code(&support::two_factor_code(&secret)),
+    );
+    let response: TokenResponse =
+        auth::token((test_request.request, database.connection.into(), json)).unwrap();
+    let access_token = decode::<AccessToken>(
+        &response.access_token,
+        TestRequest::test_config().token_secret.as_bytes(),
+        &Validation::default(),
+    )
+    .unwrap();
+    assert_eq!(access_token.claims.get_id().unwrap(), user.id);
+}
+
+#[test]
+fn token_locked_after_failed_attempts() {
+    let database = TestDatabase::new();
+    let user = database
+        .create_user()
+        .with_email("fake@localhost".to_string())
+        .with_password("strong_password".to_string())
+        .finish();
+    let mut config = TestRequest::test_config();
+    config.max_failed_login_attempts = 2;
+
+    for _ in 0..2 {
+        let test_request = TestRequest::create_with_config("/", vec![], config.clone());
+        let json = Json(LoginRequest::new("fake@localhost", "incorrect"));
+        let response = auth::token((
+            test_request.request,
+            database.connection.clone().into(),
+            json,
+        ));
+        assert_eq!(
+            "Email or password incorrect",
+            response.err().unwrap().to_string()
+        );
+    }
+    let user = User::find(user.id, database.connection.get()).unwrap();
+    assert!(user.is_locked());
+
+    // Even the correct password is refused until the lock has passed
+    let test_request = TestRequest::create_with_config("/", vec![], config.clone());
+    let json = Json(LoginRequest::new("fake@localhost", "strong_password"));
+    let response = auth::token((
+        test_request.request,
+        database.connection.clone().into(),
+        json,
+    ));
+    assert_eq!(
+        "Too many failed sign in attempts, please try again later",
+        response.err().unwrap().to_string()
+    );
+}
+
 #[test]
 fn token_refresh() {
     let database = TestDatabase::new();
     let user = database.create_user().finish();
+    let session = UserSession::create(user.id, None, None, database.connection.get()).unwrap();
 
     let test_request = TestRequest::create();
     let state = test_request.extract_state();
     let token_secret = &state.config.token_secret.clone();
-    let refresh_token_claims = RefreshToken::new(&user.id, state.config.token_issuer.clone());
+    let refresh_token_claims = RefreshToken::new(
+        &user.id,
+        &session.id,
+        &session.refresh_token_id,
+        state.config.token_issuer.clone(),
+    );
     let refresh_token = encode(
         &Header::default(),
         &refresh_token_claims,
@@ -104,7 +213,7 @@ fn token_refresh() {
     let json = Json(RefreshRequest::new(&refresh_token));
 
     let response: HttpResponse =
-        auth::token_refresh((state, database.connection.into(), json)).into();
+        auth::token_refresh((state, database.connection.clone().into(), json)).into();
     assert_eq!(response.status(), StatusCode::OK);
     let body = support::unwrap_body_to_string(&response).unwrap();
     let response: TokenResponse = serde_json::from_str(&body).unwrap();
@@ -115,8 +224,218 @@ fn token_refresh() {
         &Validation::default(),
     )
     .unwrap();
-    assert_eq!(response.refresh_token, refresh_token);
     assert_eq!(access_token.claims.get_id().unwrap(), user.id);
+    assert_eq!(access_token.claims.sid, Some(session.id));
+
+    // The refresh token is rotated
+    let mut validation = Validation::default();
+    validation.validate_exp = false;
+    let new_refresh_token = decode::<RefreshToken>(
+        &response.refresh_token,
+        token_secret.as_bytes(),
+        &validation,
+    )
+    .unwrap();
+    assert_ne!(response.refresh_token, refresh_token);
+    assert_eq!(new_refresh_token.claims.sid, Some(session.id));
+    assert_ne!(new_refresh_token.claims.jti, Some(session.refresh_token_id));
+    let session = UserSession::find(session.id, database.connection.get()).unwrap();
+    assert_eq!(new_refresh_token.claims.jti, Some(session.refresh_token_id));
+}
+
+#[test]
+fn token_refresh_reused_refresh_token() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let session = UserSession::create(user.id, None, None, database.connection.get()).unwrap();
+
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state();
+    let refresh_token_claims = RefreshToken::new(
+        &user.id,
+        &session.id,
+        &session.refresh_token_id,
+        state.config.token_issuer.clone(),
+    );
+    let refresh_token = encode(
+        &Header::default(),
+        &refresh_token_claims,
+        state.config.token_secret.as_bytes(),
+    )
+    .unwrap();
+
+    let response: HttpResponse = auth::token_refresh((
+        test_request.extract_state(),
+        database.connection.clone().into(),
+        Json(RefreshRequest::new(&refresh_token)),
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let rotated: TokenResponse = serde_json::from_str(&body).unwrap();
+
+    // Presenting the exchanged token again revokes the session, including the rotated token
+    let response: HttpResponse = auth::token_refresh((
+        test_request.extract_state(),
+        database.connection.clone().into(),
+        Json(RefreshRequest::new(&refresh_token)),
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert!(UserSession::find(session.id, database.connection.get())
+        .unwrap()
+        .is_revoked());
+
+    let response: HttpResponse = auth::token_refresh((
+        state,
+        database.connection.into(),
+        Json(RefreshRequest::new(&rotated.refresh_token)),
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[test]
+fn token_refresh_reused_refresh_token_revocation_survives_rollback() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let session = UserSession::create(user.id, None, None, database.connection.get()).unwrap();
+    // Rotate the session so the original refresh token has been exchanged
+    session
+        .rotate(session.refresh_token_id, database.connection.get())
+        .unwrap()
+        .unwrap();
+
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state();
+    let refresh_token_claims = RefreshToken::new(
+        &user.id,
+        &session.id,
+        &session.refresh_token_id,
+        state.config.token_issuer.clone(),
+    );
+    let refresh_token = encode(
+        &Header::default(),
+        &refresh_token_claims,
+        state.config.token_secret.as_bytes(),
+    )
+    .unwrap();
+
+    // As the DatabaseTransaction middleware does, run the request in its own transaction and
+    // roll it back on the error response
+    database.connection.begin_transaction().unwrap();
+    let response: HttpResponse = auth::token_refresh((
+        state,
+        database.connection.clone().into(),
+        Json(RefreshRequest::new(&refresh_token)),
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    database.connection.rollback_transaction().unwrap();
+
+    assert!(UserSession::find(session.id, database.connection.get())
+        .unwrap()
+        .is_revoked());
+}
+
+#[test]
+fn token_refresh_for_erased_user() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let session = UserSession::create(user.id, None, None, connection).unwrap();
+
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state();
+    let refresh_token_claims = RefreshToken::new(
+        &user.id,
+        &session.id,
+        &session.refresh_token_id,
+        state.config.token_issuer.clone(),
+    );
+    let refresh_token = encode(
+        &Header::default(),
+        &refresh_token_claims,
+        state.config.token_secret.as_bytes(),
+    )
+    .unwrap();
+    user.erase(None, connection).unwrap();
+
+    let response: HttpResponse = auth::token_refresh((
+        state,
+        database.connection.clone().into(),
+        Json(RefreshRequest::new(&refresh_token)),
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert!(UserSession::find(session.id, connection)
+        .unwrap()
+        .is_revoked());
+}
+
+#[test]
+fn token_refresh_without_session() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+
+    let test_request = TestRequest::create();
+    let state = test_request.extract_state();
+    let mut refresh_token_claims = RefreshToken::new(
+        &user.id,
+        &Uuid::new_v4(),
+        &Uuid::new_v4(),
+        state.config.token_issuer.clone(),
+    );
+    // Issued before sessions were recorded
+    refresh_token_claims.sid = None;
+    refresh_token_claims.jti = None;
+    let refresh_token = encode(
+        &Header::default(),
+        &refresh_token_claims,
+        state.config.token_secret.as_bytes(),
+    )
+    .unwrap();
+    let json = Json(RefreshRequest::new(&refresh_token));
+
+    let response: HttpResponse =
+        auth::token_refresh((state, database.connection.clone().into(), json)).into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let response: TokenResponse = serde_json::from_str(&body).unwrap();
+    let mut validation = Validation::default();
+    validation.validate_exp = false;
+    let refresh_token_claims = decode::<RefreshToken>(
+        &response.refresh_token,
+        test_request.config.token_secret.as_bytes(),
+        &validation,
+    )
+    .unwrap()
+    .claims;
+    let session = UserSession::find(refresh_token_claims.sid.unwrap(), connection).unwrap();
+    assert_eq!(session.user_id, user.id);
+    let access_token = decode::<AccessToken>(
+        &response.access_token,
+        test_request.config.token_secret.as_bytes(),
+        &Validation::default(),
+    )
+    .unwrap();
+    assert_eq!(access_token.claims.sid, Some(session.id));
+    assert!(!session.is_revoked());
+
+    // The legacy token can only be exchanged once
+    let state = test_request.extract_state();
+    let json = Json(RefreshRequest::new(&refresh_token));
+    let response: HttpResponse =
+        auth::token_refresh((state, database.connection.clone().into(), json)).into();
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    assert_eq!(body, json!({"error": "Invalid token"}).to_string());
+    assert!(UserSession::find(session.id, connection)
+        .unwrap()
+        .is_revoked());
 }
 
 #[test]
@@ -126,7 +445,12 @@ fn token_refresh_invalid_refresh_token_secret() {
 
     let test_request = TestRequest::create();
     let state = test_request.extract_state();
-    let refresh_token_claims = RefreshToken::new(&user.id, state.config.token_issuer.clone());
+    let refresh_token_claims = RefreshToken::new(
+        &user.id,
+        &Uuid::new_v4(),
+        &Uuid::new_v4(),
+        state.config.token_issuer.clone(),
+    );
     let refresh_token = encode(
         &Header::default(),
         &refresh_token_claims,
@@ -170,7 +494,12 @@ fn token_refresh_user_does_not_exist() {
     let test_request = TestRequest::create();
 
     let state = test_request.extract_state();
-    let mut refresh_token_claims = RefreshToken::new(&user.id, state.config.token_issuer.clone());
+    let mut refresh_token_claims = RefreshToken::new(
+        &user.id,
+        &Uuid::new_v4(),
+        &Uuid::new_v4(),
+        state.config.token_issuer.clone(),
+    );
     refresh_token_claims.sub = Uuid::new_v4().to_string();
 
     let refresh_token = encode(
@@ -197,7 +526,13 @@ fn token_refresh_password_reset_since_issued() {
     let test_request = TestRequest::create();
 
     let state = test_request.extract_state();
-    let mut refresh_token_claims = RefreshToken::new(&user.id, state.config.token_issuer.clone());
+    let session = UserSession::create(user.id, None, None, database.connection.get()).unwrap();
+    let mut refresh_token_claims = RefreshToken::new(
+        &user.id,
+        &session.id,
+        &session.refresh_token_id,
+        state.config.token_issuer.clone(),
+    );
 
     // Issued a second prior to the latest password
     refresh_token_claims.issued = password_modified_timestamp - 1;
@@ -228,7 +563,13 @@ fn token_refreshed_after_password_change() {
 
     let state = test_request.extract_state();
     let token_secret = &state.config.token_secret.clone();
-    let mut refresh_token_claims = RefreshToken::new(&user.id, state.config.token_issuer.clone());
+    let session = UserSession::create(user.id, None, None, database.connection.get()).unwrap();
+    let mut refresh_token_claims = RefreshToken::new(
+        &user.id,
+        &session.id,
+        &session.refresh_token_id,
+        state.config.token_issuer.clone(),
+    );
 
     // Issued a second after the latest password
     refresh_token_claims.issued = password_modified_timestamp + 1;
@@ -253,6 +594,6 @@ fn token_refreshed_after_password_change() {
         &Validation::default(),
     )
     .unwrap();
-    assert_eq!(response.refresh_token, refresh_token);
+    assert_ne!(response.refresh_token, refresh_token);
     assert_eq!(access_token.claims.get_id().unwrap(), user.id);
 }
@@ -0,0 +1,39 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::affiliates::{self, CreateAffiliateRequest};
+use bigneon_api::extractors::*;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+pub fn create(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let user = support::create_auth_user(role, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let json = Json(CreateAffiliateRequest {
+        name: "Promoter".to_string(),
+        code: Some("summer".to_string()),
+        user_id: None,
+        attribution_window_in_days: Some(14),
+    });
+
+    let response: HttpResponse =
+        affiliates::create((database.connection.clone().into(), path, json, user)).into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let affiliate: Affiliate = serde_json::from_str(&body).unwrap();
+    assert_eq!(affiliate.organization_id, organization.id);
+    assert_eq!(affiliate.code, "SUMMER".to_string());
+    assert_eq!(affiliate.attribution_window_in_days, 14);
+}
@@ -0,0 +1,51 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::campaigns::{self, CreateCampaignRequest};
+use bigneon_api::extractors::*;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+pub fn create(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .finish();
+    let user = support::create_auth_user(role, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let json = Json(CreateCampaignRequest {
+        name: "Doors update".to_string(),
+        channel: CommunicationChannelType::Email,
+        audience: CampaignAudiences::EventTicketHolders,
+        event_id: Some(event.id),
+        ticket_type_id: None,
+        fan_segment_id: None,
+        subject: Some("Doors open at 8pm".to_string()),
+        body: "See you there".to_string(),
+        batch_size: Some(50),
+        batch_interval_in_seconds: None,
+    });
+
+    let response: HttpResponse =
+        campaigns::create((database.connection.clone().into(), path, json, user)).into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let campaign: Campaign = serde_json::from_str(&body).unwrap();
+    assert_eq!(campaign.organization_id, organization.id);
+    assert_eq!(campaign.event_id, Some(event.id));
+    assert_eq!(campaign.status, CampaignStatus::Draft);
+    assert_eq!(campaign.batch_size, 50);
+    assert_eq!(campaign.batch_interval_in_seconds, 60);
+}
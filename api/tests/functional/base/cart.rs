@@ -36,6 +36,7 @@ pub fn update_box_office_pricing(role: Roles, should_test_succeed: bool) {
     let ticket_type_id = ticket_type.id;
 
     let input = Json(cart::UpdateCartRequest {
+        attribution: None,
         box_office_pricing: Some(true),
         items: vec![cart::CartItem {
             ticket_type_id,
@@ -132,6 +133,7 @@ pub fn replace_box_office_pricing(role: Roles, should_test_succeed: bool) {
         .is_none());
 
     let input = Json(cart::UpdateCartRequest {
+        attribution: None,
         box_office_pricing: Some(true),
         items: vec![cart::CartItem {
             ticket_type_id,
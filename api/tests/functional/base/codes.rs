@@ -88,6 +88,7 @@ pub fn create(role: Roles, should_test_succeed: bool) {
         end_date,
         max_tickets_per_user: None,
         ticket_type_ids: vec![ticket_type_id],
+        fan_segment_id: None,
     });
 
     let test_request = TestRequest::create();
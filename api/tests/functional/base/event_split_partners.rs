@@ -0,0 +1,50 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::event_split_partners::{self, NewEventSplitPartnerRequest};
+use bigneon_api::extractors::*;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+pub fn create(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .finish();
+    let partner = database.create_organization().finish();
+    let user = support::create_auth_user(role, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    let json = Json(NewEventSplitPartnerRequest {
+        organization_id: partner.id,
+        split_type: SplitTypes::Percentage,
+        ticket_sales_flat_in_cents: 0,
+        ticket_sales_rate_in_basis_points: 2_500,
+        fees_flat_in_cents: 0,
+        fees_rate_in_basis_points: 0,
+    });
+
+    let response: HttpResponse =
+        event_split_partners::create((database.connection.clone().into(), path, json, user)).into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let split_partner: EventSplitPartner = serde_json::from_str(&body).unwrap();
+    assert_eq!(split_partner.organization_id, partner.id);
+    assert_eq!(split_partner.ticket_sales_rate_in_basis_points, 2_500);
+    assert_eq!(
+        EventSplitPartner::find_for_event(event.id, connection).unwrap(),
+        vec![split_partner]
+    );
+}
@@ -0,0 +1,46 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::event_users::{self, NewEventUserRequest};
+use bigneon_api::extractors::*;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+pub fn create(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .finish();
+    let promoter = database.create_user().finish();
+    let user = support::create_auth_user(role, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    let json = Json(NewEventUserRequest {
+        user_email: promoter.email.clone().unwrap(),
+        role: Roles::Promoter,
+    });
+
+    let response: HttpResponse =
+        event_users::create((database.connection.clone().into(), path, json, user)).into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let event_user: EventUser = serde_json::from_str(&body).unwrap();
+    assert_eq!(event_user.user_id, promoter.id);
+    assert_eq!(event_user.role, Roles::Promoter);
+    assert_eq!(
+        EventUser::find_by_event_id_user_id(event.id, promoter.id, connection).unwrap(),
+        event_user
+    );
+}
@@ -2,7 +2,7 @@ use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path, Query};
 use bigneon_api::controllers::events;
 use bigneon_api::controllers::events::*;
 use bigneon_api::extractors::*;
-use bigneon_api::models::{PathParameters, UserDisplayTicketType};
+use bigneon_api::models::{AuditLogQueryParameters, PathParameters, UserDisplayTicketType};
 use bigneon_db::models::*;
 use chrono::prelude::*;
 use chrono::Duration;
@@ -134,13 +134,31 @@ pub fn update(role: Roles, should_test_succeed: bool) {
     let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
     path.id = event.id;
 
-    let response: HttpResponse =
-        events::update((database.connection.into(), path, json, auth_user.clone())).into();
+    let response: HttpResponse = events::update((
+        database.connection.clone().into(),
+        path,
+        json,
+        auth_user.clone(),
+    ))
+    .into();
     let body = support::unwrap_body_to_string(&response).unwrap();
     if should_test_succeed {
         assert_eq!(response.status(), StatusCode::OK);
         let updated_event: Event = serde_json::from_str(&body).unwrap();
         assert_eq!(updated_event.name, new_name);
+
+        let audit_logs = AuditLog::find_for_event(
+            event.id,
+            AuditLogFilters::default(),
+            0,
+            100,
+            database.connection.get(),
+        )
+        .unwrap();
+        assert_eq!(audit_logs.data.len(), 1);
+        assert_eq!(audit_logs.data[0].user_id, Some(user.id));
+        assert_eq!(audit_logs.data[0].action, AuditActions::Updated);
+        assert_eq!(audit_logs.data[0].changes["name"]["after"], json!(new_name));
     } else {
         support::expects_unauthorized(&response);
     }
@@ -420,7 +438,7 @@ pub fn dashboard(role: Roles, should_test_succeed: bool) {
     )
     .unwrap();
     assert_eq!(cart.calculate_total(connection).unwrap(), 1700);
-    cart.add_external_payment(Some("test".to_string()), user.id, 1700, connection)
+    cart.add_external_payment(Some("test".to_string()), user.id, 1700, "USD", connection)
         .unwrap();
     assert_eq!(cart.status, OrderStatus::Paid);
 
@@ -815,3 +833,45 @@ pub fn expected_show_json(
     })
     .unwrap()
 }
+
+pub fn audit(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let auth_user =
+        support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .finish();
+    let audit_log = AuditLog::create(
+        Some(user.id),
+        Some(Scopes::EventWrite),
+        Tables::Events,
+        event.id,
+        Some(organization.id),
+        Some(event.id),
+    )
+    .created(&event)
+    .unwrap()
+    .commit(database.connection.get())
+    .unwrap();
+
+    let test_request =
+        TestRequest::create_with_uri(&format!("/events/{}/audit?action=Created", event.id));
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    let query = Query::<AuditLogQueryParameters>::extract(&test_request.request).unwrap();
+
+    let response = events::audit((database.connection.clone().into(), path, query, auth_user));
+    if should_test_succeed {
+        let response = response.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.payload().data, vec![audit_log]);
+    } else {
+        assert_eq!(
+            response.err().unwrap().to_string(),
+            "User does not have the required permissions"
+        );
+    }
+}
@@ -0,0 +1,33 @@
+use actix_web::{http::StatusCode, HttpResponse};
+use bigneon_api::controllers::exchange_rates;
+use bigneon_api::extractors::*;
+use bigneon_db::models::{BigDecimal, ExchangeRate, NewExchangeRate, Roles};
+use serde_json;
+use support;
+use support::database::TestDatabase;
+
+pub fn create(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+
+    let user = support::create_auth_user(role, None, &database);
+    let json = Json(NewExchangeRate {
+        base_currency: "USD".to_string(),
+        target_currency: "ZAR".to_string(),
+        rate: "14.5".parse().unwrap(),
+        effective_at: None,
+    });
+
+    let response: HttpResponse =
+        exchange_rates::create((database.connection.into(), json, user)).into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let exchange_rate: ExchangeRate = serde_json::from_str(&body).unwrap();
+    assert_eq!(exchange_rate.base_currency, "USD");
+    assert_eq!(exchange_rate.target_currency, "ZAR");
+    assert_eq!(exchange_rate.rate, "14.5".parse::<BigDecimal>().unwrap());
+}
@@ -0,0 +1,41 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::fan_segments::{self, CreateFanSegmentRequest};
+use bigneon_api::extractors::*;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+pub fn create(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let user = support::create_auth_user(role, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let criteria = FanSegmentCriteria {
+        min_event_count: Some(2),
+        ..Default::default()
+    };
+    let json = Json(CreateFanSegmentRequest {
+        name: "Regulars".to_string(),
+        criteria: criteria.clone(),
+    });
+
+    let response: HttpResponse =
+        fan_segments::create((database.connection.clone().into(), path, json, user)).into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let fan_segment: FanSegment = serde_json::from_str(&body).unwrap();
+    assert_eq!(fan_segment.organization_id, organization.id);
+    assert_eq!(fan_segment.name, "Regulars".to_string());
+    assert_eq!(fan_segment.criteria().unwrap(), criteria);
+}
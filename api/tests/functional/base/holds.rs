@@ -59,6 +59,32 @@ pub fn create(role: Roles, should_test_succeed: bool) {
     }
 }
 
+pub fn show(role: Roles, should_test_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let hold = database.create_hold().finish();
+    let event = Event::find(hold.event_id, connection).unwrap();
+    let organization = event.organization(connection).unwrap();
+    let auth_user =
+        support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = hold.id;
+
+    let response: HttpResponse = holds::show((database.connection.clone(), path, auth_user)).into();
+
+    if should_test_succeed {
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = support::unwrap_body_to_string(&response).unwrap();
+        let fetched_hold: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(fetched_hold["id"], json!(hold.id));
+    } else {
+        support::expects_unauthorized(&response);
+    }
+}
+
 pub fn update(role: Roles, should_test_succeed: bool) {
     let database = TestDatabase::new();
     let connection = database.connection.get();
@@ -1,14 +1,25 @@
+pub mod affiliates;
 pub mod artists;
+pub mod campaigns;
 pub mod cart;
 pub mod codes;
 pub mod comps;
+pub mod event_split_partners;
+pub mod event_users;
 pub mod events;
+pub mod exchange_rates;
+pub mod fan_segments;
 pub mod holds;
 pub mod orders;
+pub mod organization_api_keys;
 pub mod organization_invites;
+pub mod organization_roles;
 pub mod organizations;
 pub mod regions;
+pub mod report_subscriptions;
+pub mod settlements;
 pub mod stages;
+pub mod tax_rules;
 pub mod ticket_types;
 pub mod tickets;
 pub mod users;
@@ -33,7 +33,7 @@ pub fn show_other_user_order(role: Roles, should_succeed: bool) {
     let conn = database.connection.get();
     let total = order.calculate_total(conn).unwrap();
     order
-        .add_external_payment(Some("test".to_string()), user.id, total, conn)
+        .add_external_payment(Some("test".to_string()), user.id, total, "USD", conn)
         .unwrap();
     assert_eq!(order.status, OrderStatus::Paid);
 
@@ -66,7 +66,7 @@ pub fn show_other_user_order_not_matching_users_organization(role: Roles, should
     let conn = database.connection.get();
     let total = order.calculate_total(conn).unwrap();
     order
-        .add_external_payment(Some("test".to_string()), user.id, total, conn)
+        .add_external_payment(Some("test".to_string()), user.id, total, "USD", conn)
         .unwrap();
     assert_eq!(order.status, OrderStatus::Paid);
 
@@ -122,7 +122,7 @@ pub fn details(role: Roles, should_succeed: bool) {
     .unwrap();
 
     let total = cart.calculate_total(connection).unwrap();
-    cart.add_external_payment(Some("Test".to_string()), user.id, total, connection)
+    cart.add_external_payment(Some("Test".to_string()), user.id, total, "USD", connection)
         .unwrap();
 
     let items = cart.items(connection).unwrap();
@@ -251,7 +251,7 @@ pub fn refund(role: Roles, should_succeed: bool) {
     .unwrap();
 
     let total = cart.calculate_total(connection).unwrap();
-    cart.add_external_payment(Some("Test".to_string()), user.id, total, connection)
+    cart.add_external_payment(Some("Test".to_string()), user.id, total, "USD", connection)
         .unwrap();
 
     let items = cart.items(&connection).unwrap();
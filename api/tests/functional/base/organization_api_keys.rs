@@ -0,0 +1,48 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::organization_api_keys::{self, CreateOrganizationApiKeyRequest};
+use bigneon_api::extractors::*;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use serde_json::{self, Value};
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+pub fn create(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .finish();
+    let user = support::create_auth_user(role, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let json = Json(CreateOrganizationApiKeyRequest {
+        name: "Box office integration".to_string(),
+        scopes: vec!["event:write".to_string()],
+        event_ids: vec![event.id],
+        expires_at: None,
+    });
+
+    let response: HttpResponse =
+        organization_api_keys::create((database.connection.clone().into(), path, json, user))
+            .into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let created: Value = serde_json::from_str(&body).unwrap();
+    assert!(created.get("key_hash").is_none());
+    let key = created["key"].as_str().unwrap();
+    let api_key = OrganizationApiKey::find_active_by_key(key, connection).unwrap();
+    assert_eq!(api_key.organization_id, organization.id);
+    assert_eq!(api_key.scopes, vec!["event:write".to_string()]);
+    assert_eq!(api_key.event_ids, vec![event.id]);
+}
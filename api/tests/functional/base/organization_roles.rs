@@ -0,0 +1,44 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::organization_roles::{self, OrganizationRoleRequest};
+use bigneon_api::extractors::*;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+pub fn create(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let user = support::create_auth_user(role, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let json = Json(OrganizationRoleRequest {
+        name: "Marketing".to_string(),
+        scopes: vec!["org:fans".to_string(), "org:reports".to_string()],
+    });
+
+    let response: HttpResponse =
+        organization_roles::create((database.connection.clone().into(), path, json, user)).into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let organization_role: OrganizationRole = serde_json::from_str(&body).unwrap();
+    assert_eq!(organization_role.name, "Marketing".to_string());
+    assert_eq!(
+        organization_role.get_scopes(),
+        vec![Scopes::OrgFans, Scopes::OrgReports]
+    );
+    assert_eq!(
+        OrganizationRole::find_for_organization(organization.id, connection).unwrap(),
+        vec![organization_role]
+    );
+}
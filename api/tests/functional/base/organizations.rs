@@ -161,6 +161,7 @@ pub fn create(role: Roles, should_test_succeed: bool) {
             min_price_in_cents: 0,
             client_fee_in_cents: 0,
             company_fee_in_cents: 0,
+            ..Default::default()
         }],
     )
     .commit(admin.id, database.connection.get())
@@ -179,6 +180,7 @@ pub fn create(role: Roles, should_test_succeed: bool) {
         facebook_pixel_key: None,
         client_event_fee_in_cents: None,
         company_event_fee_in_cents: None,
+        currency: None,
     });
 
     let test_request = TestRequest::create_with_uri("/organizations");
@@ -223,6 +225,7 @@ pub fn update(role: Roles, should_succeed: bool) {
         sendgrid_api_key: Some(Some("sendgrid_api_key".to_string())),
         google_ga_key: Some(Some("google_ga_key".to_string())),
         facebook_pixel_key: Some(Some("facebook_pixel_key".to_string())),
+        currency: None,
     });
 
     let response: HttpResponse = organizations::update((
@@ -286,6 +289,7 @@ pub fn add_user(role: Roles, should_test_succeed: bool) {
     let json = Json(organizations::AddUserRequest {
         user_id: user2.id,
         roles: vec![Roles::OrgMember],
+        organization_role_ids: None,
     });
     let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
     path.id = organization.id;
@@ -394,6 +398,7 @@ pub fn list_organization_members(role: Roles, should_succeed: bool) {
             last_name: user1.last_name,
             email: user1.email,
             roles: vec![role],
+            organization_role_ids: Vec::new(),
             invite_or_member: "member".to_string(),
             invite_id: None,
         });
@@ -404,6 +409,7 @@ pub fn list_organization_members(role: Roles, should_succeed: bool) {
         last_name: user2.last_name,
         email: user2.email,
         roles: vec![Roles::OrgMember],
+        organization_role_ids: Vec::new(),
         invite_or_member: "member".to_string(),
         invite_id: None,
     });
@@ -506,11 +512,13 @@ pub fn add_fee_schedule(role: Roles, should_succeed: bool) {
                 min_price_in_cents: 20,
                 company_fee_in_cents: 4,
                 client_fee_in_cents: 6,
+                ..Default::default()
             },
             NewFeeScheduleRange {
                 min_price_in_cents: 1000,
                 company_fee_in_cents: 40,
                 client_fee_in_cents: 60,
+                ..Default::default()
             },
         ],
     });
@@ -518,8 +526,14 @@ pub fn add_fee_schedule(role: Roles, should_succeed: bool) {
     let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
     path.id = organization.id;
 
-    let response: HttpResponse =
-        organizations::add_fee_schedule((database.connection.into(), path, json, auth_user)).into();
+    let response: HttpResponse = organizations::add_fee_schedule((
+        test_request.extract_state(),
+        database.connection.into(),
+        path,
+        json,
+        auth_user,
+    ))
+    .into();
 
     if !should_succeed {
         support::expects_unauthorized(&response);
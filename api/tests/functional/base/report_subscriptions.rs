@@ -0,0 +1,49 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::report_subscriptions::{self, CreateReportSubscriptionRequest};
+use bigneon_api::extractors::*;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+pub fn create(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let user = support::create_auth_user(role, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let json = Json(CreateReportSubscriptionRequest {
+        report_type: ReportTypes::TicketCount,
+        event_id: None,
+        frequency: ReportFrequencies::Daily,
+        day_of_week: None,
+        hour_of_day: 7,
+        timezone: "Africa/Johannesburg".to_string(),
+        format: ExportFormats::Xlsx,
+    });
+
+    let response: HttpResponse = report_subscriptions::create((
+        database.connection.clone().into(),
+        path,
+        json,
+        user.clone(),
+    ))
+    .into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let subscription: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(subscription["user_id"], json!(user.id()));
+    assert_eq!(subscription["report_type"], json!("TicketCount"));
+    assert_eq!(subscription["format"], json!("xlsx"));
+    // The token is only sent in report emails
+    assert!(subscription.get("unsubscribe_token").is_none());
+}
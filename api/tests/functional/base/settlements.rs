@@ -0,0 +1,106 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path, Query};
+use bigneon_api::controllers::settlements::{self, CreateSettlementRequest};
+use bigneon_api::extractors::*;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+pub fn index(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let user = support::create_auth_user(role, Some(&organization), &database);
+    let settlement = Settlement::create(organization.id, None, None, None, user.id())
+        .commit(connection)
+        .unwrap();
+
+    let test_request = TestRequest::create_with_uri(&format!("/limits?"));
+    let query_parameters = Query::<PagingParameters>::extract(&test_request.request).unwrap();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+
+    let response = settlements::index((
+        database.connection.clone().into(),
+        path,
+        query_parameters,
+        user,
+    ));
+
+    if should_succeed {
+        let response = response.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.payload().data, vec![settlement]);
+    } else {
+        assert_eq!(
+            response.err().unwrap().to_string(),
+            "User does not have the required permissions"
+        );
+    }
+}
+
+pub fn create(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .finish();
+    let user = support::create_auth_user(role, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let json = Json(CreateSettlementRequest {
+        event_id: Some(event.id),
+        start_time: None,
+        end_time: None,
+    });
+
+    let response: HttpResponse =
+        settlements::create((database.connection.clone().into(), path, json, user)).into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let display_settlement: DisplaySettlement = serde_json::from_str(&body).unwrap();
+    assert_eq!(display_settlement.settlement.event_id, Some(event.id));
+    assert_eq!(
+        display_settlement.settlement.status,
+        SettlementStatus::Draft
+    );
+}
+
+pub fn approve(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let user = support::create_auth_user(role, Some(&organization), &database);
+    let settlement = Settlement::create(organization.id, None, None, None, user.id())
+        .commit(connection)
+        .unwrap();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = settlement.id;
+
+    let response: HttpResponse =
+        settlements::approve((database.connection.clone().into(), path, user)).into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let display_settlement: DisplaySettlement = serde_json::from_str(&body).unwrap();
+    assert_eq!(
+        display_settlement.settlement.status,
+        SettlementStatus::Approved
+    );
+}
@@ -0,0 +1,57 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::tax_rules;
+use bigneon_api::extractors::*;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::{Roles, TaxRule};
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+pub fn create(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let region = database.create_region().finish();
+
+    let user = support::create_auth_user(role, None, &database);
+    let json = Json(TaxRule::create("Sales Tax", Some(region.id), None, 825, false));
+
+    let response: HttpResponse =
+        tax_rules::create((database.connection.into(), json, user)).into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let tax_rule: TaxRule = serde_json::from_str(&body).unwrap();
+    assert_eq!(tax_rule.name, "Sales Tax");
+    assert_eq!(tax_rule.region_id, Some(region.id));
+    assert_eq!(tax_rule.rate_in_basis_points, 825);
+}
+
+pub fn destroy(role: Roles, should_succeed: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let region = database.create_region().finish();
+    let tax_rule = TaxRule::create("Sales Tax", Some(region.id), None, 825, false)
+        .commit(connection)
+        .unwrap();
+
+    let user = support::create_auth_user(role, None, &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = tax_rule.id;
+
+    let response: HttpResponse =
+        tax_rules::destroy((database.connection.clone().into(), path, user)).into();
+
+    if !should_succeed {
+        support::expects_unauthorized(&response);
+        assert!(!TaxRule::find(tax_rule.id, connection).unwrap().is_deleted());
+        return;
+    }
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(TaxRule::find(tax_rule.id, connection).unwrap().is_deleted());
+    assert!(TaxRule::all(connection).unwrap().is_empty());
+}
@@ -53,6 +53,7 @@ pub fn create(role: Roles, should_test_succeed: bool) {
         increment: None,
         limit_per_person: 0,
         price_in_cents: 20000,
+        fee_mode: None,
     };
     let response: HttpResponse = ticket_types::create((
         database.connection.into(),
@@ -129,6 +130,7 @@ pub fn update(role: Roles, should_test_succeed: bool) {
         increment: None,
         limit_per_person: Some(0),
         price_in_cents: Some(15000),
+        fee_mode: None,
     };
     let request_json = serde_json::to_string(&request_data).unwrap();
 
@@ -175,6 +177,7 @@ pub fn update(role: Roles, should_test_succeed: bool) {
         increment: None,
         limit_per_person: Some(0),
         price_in_cents: Some(updated_ticket_type.price_in_cents),
+        fee_mode: None,
     };
     let updated_json = serde_json::to_string(&updated_data).unwrap();
 
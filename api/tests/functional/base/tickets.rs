@@ -161,7 +161,7 @@ pub fn show_redeemable_ticket(role: Roles, should_test_succeed: bool) {
     )
     .unwrap();
     let total = cart.calculate_total(conn).unwrap();
-    cart.add_external_payment(Some("test".to_string()), user2.id, total, conn)
+    cart.add_external_payment(Some("test".to_string()), user2.id, total, "USD", conn)
         .unwrap();
 
     let ticket = TicketInstance::find_for_user(user2.id, conn)
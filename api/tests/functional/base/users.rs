@@ -46,7 +46,7 @@ pub fn profile(role: Roles, should_test_true: bool) {
     )
     .unwrap();
     assert_eq!(cart.calculate_total(&*connection).unwrap(), 1700);
-    cart.add_external_payment(Some("test".to_string()), user.id, 1700, connection)
+    cart.add_external_payment(Some("test".to_string()), user.id, 1700, "USD", connection)
         .unwrap();
     assert_eq!(cart.status, OrderStatus::Paid);
 
@@ -116,7 +116,7 @@ pub fn history(role: Roles, should_test_true: bool) {
     )
     .unwrap();
     assert_eq!(cart.calculate_total(connection).unwrap(), 1700);
-    cart.add_external_payment(Some("test".to_string()), user.id, 1700, connection)
+    cart.add_external_payment(Some("test".to_string()), user.id, 1700, "USD", connection)
         .unwrap();
     assert_eq!(cart.status, OrderStatus::Paid);
 
@@ -408,3 +408,63 @@ pub fn show(role: Roles, should_test_true: bool) {
         support::expects_unauthorized(&response);
     }
 }
+
+pub fn export_data(role: Roles, should_test_true: bool) {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let user2 = database.create_user().finish();
+
+    let organization = database
+        .create_organization()
+        .with_member(&user2, Roles::OrgMember)
+        .finish();
+    let auth_user =
+        support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = user2.id;
+    let response: HttpResponse =
+        users::export_data((database.connection.into(), path, auth_user)).into();
+    if should_test_true {
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = support::unwrap_body_to_string(&response).unwrap();
+        let data: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(data["profile"]["id"], json!(user2.id));
+        assert_eq!(
+            data["organizations"][0]["organization_id"],
+            json!(organization.id)
+        );
+    } else {
+        support::expects_unauthorized(&response);
+    }
+}
+
+pub fn erase(role: Roles, should_test_true: bool) {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let user2 = database.create_user().finish();
+
+    let organization = database
+        .create_organization()
+        .with_member(&user2, Roles::OrgMember)
+        .finish();
+    let auth_user =
+        support::create_auth_user_from_user(&user, role, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = user2.id;
+    let response: HttpResponse =
+        users::erase((database.connection.clone().into(), path, auth_user)).into();
+    let user2 = User::find(user2.id, connection).unwrap();
+    if should_test_true {
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(user2.email, None);
+        assert!(!user2.active);
+    } else {
+        support::expects_unauthorized(&response);
+        assert!(user2.email.is_some());
+    }
+}
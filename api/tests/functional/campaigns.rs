@@ -0,0 +1,229 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::campaigns::{self, UnsubscribeRequest};
+use bigneon_api::domain_events::executors::send_campaign::SendCampaignExecutor;
+use bigneon_api::extractors::*;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use bigneon_db::schema::domain_actions;
+use chrono::prelude::*;
+use chrono::Duration;
+use diesel::prelude::*;
+use functional::base;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+    #[test]
+    fn create_org_member() {
+        base::campaigns::create(Roles::OrgMember, true);
+    }
+    #[test]
+    fn create_admin() {
+        base::campaigns::create(Roles::Admin, true);
+    }
+    #[test]
+    fn create_user() {
+        base::campaigns::create(Roles::User, false);
+    }
+    #[test]
+    fn create_org_owner() {
+        base::campaigns::create(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn create_door_person() {
+        base::campaigns::create(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn create_org_admin() {
+        base::campaigns::create(Roles::OrgAdmin, true);
+    }
+    #[test]
+    fn create_box_office() {
+        base::campaigns::create(Roles::OrgBoxOffice, false);
+    }
+}
+
+fn draft_campaign(database: &TestDatabase, organization: &Organization) -> Campaign {
+    let event = database
+        .create_event()
+        .with_organization(organization)
+        .with_ticket_pricing()
+        .finish();
+    let fan = database.create_user().finish();
+    database
+        .create_order()
+        .for_event(&event)
+        .for_user(&fan)
+        .is_paid()
+        .finish();
+    let mut new_campaign = Campaign::create(
+        organization.id,
+        "Doors update",
+        CommunicationChannelType::Email,
+        CampaignAudiences::EventTicketHolders,
+        Some("Doors open at 8pm".to_string()),
+        "See you there",
+        fan.id,
+    );
+    new_campaign.event_id = Some(event.id);
+    new_campaign.commit(database.connection.get()).unwrap()
+}
+
+#[test]
+fn send() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let campaign = draft_campaign(&database, &organization);
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = campaign.id;
+    let response: HttpResponse =
+        campaigns::send((database.connection.clone().into(), path, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Recipients are recorded and queued in the background
+    let campaign = Campaign::find(campaign.id, connection).unwrap();
+    assert_eq!(campaign.status, CampaignStatus::Sending);
+    assert_eq!(
+        campaign.recipient_counts(connection).unwrap(),
+        CampaignRecipientCounts::default()
+    );
+    let batches =
+        DomainAction::find_pending(Some(DomainActionTypes::SendCampaign), connection).unwrap();
+    assert_eq!(batches.len(), 1);
+    assert_eq!(batches[0].main_table_id, Some(campaign.id));
+
+    let executor = SendCampaignExecutor::new(test_request.config.clone());
+    executor
+        .perform_job(&batches[0], &database.connection.clone())
+        .unwrap();
+    assert_eq!(
+        campaign.recipient_counts(connection).unwrap(),
+        CampaignRecipientCounts {
+            queued: 1,
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn send_in_batches() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let campaign = draft_campaign(&database, &organization);
+    let event = Event::find(campaign.event_id.unwrap(), connection).unwrap();
+    let fan = database.create_user().finish();
+    database
+        .create_order()
+        .for_event(&event)
+        .for_user(&fan)
+        .is_paid()
+        .finish();
+    let campaign = campaign
+        .update(
+            CampaignEditableAttributes {
+                batch_size: Some(1),
+                batch_interval_in_seconds: Some(600),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap()
+        .start_sending(connection)
+        .unwrap();
+    let test_request = TestRequest::create();
+    let executor = SendCampaignExecutor::new(test_request.config.clone());
+
+    let batches =
+        DomainAction::find_pending(Some(DomainActionTypes::SendCampaign), connection).unwrap();
+    executor
+        .perform_job(&batches[0], &database.connection.clone())
+        .unwrap();
+    assert_eq!(
+        campaign.recipient_counts(connection).unwrap(),
+        CampaignRecipientCounts {
+            pending: 1,
+            queued: 1,
+            ..Default::default()
+        }
+    );
+
+    // The next batch is scheduled after the batch interval
+    let next_batch: DomainAction = domain_actions::table
+        .filter(domain_actions::domain_action_type.eq(DomainActionTypes::SendCampaign))
+        .filter(domain_actions::id.ne(batches[0].id))
+        .first(connection)
+        .unwrap();
+    assert!(next_batch.scheduled_at > Utc::now().naive_utc() + Duration::seconds(500));
+    executor
+        .perform_job(&next_batch, &database.connection.clone())
+        .unwrap();
+    assert_eq!(
+        campaign.recipient_counts(connection).unwrap(),
+        CampaignRecipientCounts {
+            queued: 2,
+            ..Default::default()
+        }
+    );
+    assert_eq!(
+        domain_actions::table
+            .filter(domain_actions::domain_action_type.eq(DomainActionTypes::SendCampaign))
+            .count()
+            .get_result::<i64>(connection)
+            .unwrap(),
+        2
+    );
+}
+
+#[test]
+fn send_org_member() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let campaign = draft_campaign(&database, &organization);
+    let auth_user = support::create_auth_user(Roles::OrgMember, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = campaign.id;
+    let response: HttpResponse =
+        campaigns::send((database.connection.clone().into(), path, auth_user)).into();
+    support::expects_unauthorized(&response);
+}
+
+#[test]
+fn unsubscribe() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let campaign = draft_campaign(&database, &organization);
+    let campaign = campaign.start_sending(connection).unwrap();
+    campaign.record_recipients(connection).unwrap();
+    let recipients = campaign.pending_recipients(100, connection).unwrap();
+
+    let json = Json(UnsubscribeRequest {
+        token: recipients[0].unsubscribe_token,
+    });
+    let test_request = TestRequest::create();
+    let response: HttpResponse = campaigns::unsubscribe((
+        database.connection.clone().into(),
+        json,
+        test_request.request,
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(!CommunicationPreference::allows(
+        recipients[0].user_id,
+        Some(organization.id),
+        CommunicationCategory::Marketing,
+        CommunicationChannelType::Email,
+        connection
+    )
+    .unwrap());
+}
@@ -183,6 +183,7 @@ fn update() {
     let ticket_type_id = event.ticket_types(true, None, connection).unwrap()[0].id;
 
     let input = Json(cart::UpdateCartRequest {
+        attribution: None,
         box_office_pricing: None,
         items: vec![cart::CartItem {
             ticket_type_id,
@@ -237,6 +238,7 @@ fn update_with_draft_event() {
     let ticket_type_id = event.ticket_types(true, None, connection).unwrap()[0].id;
 
     let input = Json(cart::UpdateCartRequest {
+        attribution: None,
         items: vec![cart::CartItem {
             ticket_type_id,
             quantity: 2,
@@ -267,6 +269,7 @@ fn update_multiple() {
     let ticket_type_id = ticket_types[0].id;
     let ticket_type_id2 = ticket_types[1].id;
     let input = Json(cart::UpdateCartRequest {
+        attribution: None,
         box_office_pricing: None,
         items: vec![
             cart::CartItem {
@@ -354,6 +357,7 @@ fn add_with_increment() {
     let ticket_type_id = ticket_type.id;
 
     let input = Json(cart::UpdateCartRequest {
+        attribution: None,
         box_office_pricing: None,
         items: vec![cart::CartItem {
             ticket_type_id,
@@ -412,6 +416,7 @@ fn update_with_increment_failure_invalid_quantity() {
     let ticket_type_id = ticket_type.id;
 
     let input = Json(cart::UpdateCartRequest {
+        attribution: None,
         box_office_pricing: None,
         items: vec![cart::CartItem {
             ticket_type_id,
@@ -449,6 +454,7 @@ fn update_with_existing_cart() {
     let cart = Order::find_or_create_cart(&user, connection).unwrap();
 
     let input = Json(cart::UpdateCartRequest {
+        attribution: None,
         box_office_pricing: None,
         items: vec![cart::CartItem {
             ticket_type_id,
@@ -530,6 +536,7 @@ fn reduce() {
     );
 
     let input = Json(cart::UpdateCartRequest {
+        attribution: None,
         box_office_pricing: None,
         items: vec![cart::CartItem {
             ticket_type_id,
@@ -613,6 +620,7 @@ fn remove() {
     );
 
     let input = Json(cart::UpdateCartRequest {
+        attribution: None,
         box_office_pricing: None,
         items: vec![cart::CartItem {
             ticket_type_id,
@@ -692,6 +700,7 @@ fn remove_with_increment() {
     );
 
     let input = Json(cart::UpdateCartRequest {
+        attribution: None,
         box_office_pricing: None,
         items: vec![cart::CartItem {
             ticket_type_id,
@@ -783,6 +792,7 @@ fn remove_with_increment_failure_invalid_quantity() {
     );
 
     let input = Json(cart::UpdateCartRequest {
+        attribution: None,
         box_office_pricing: None,
         items: vec![cart::CartItem {
             ticket_type_id,
@@ -886,6 +896,67 @@ fn checkout_free() {
     assert_eq!(order.status, OrderStatus::Paid);
 }
 
+#[test]
+fn checkout_requires_verified_contact_details() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database
+        .create_organization()
+        .finish()
+        .update(
+            OrganizationEditableAttributes {
+                require_verified_contact_details: Some(true),
+                ..Default::default()
+            },
+            &"encryption_key".to_string(),
+            connection,
+        )
+        .unwrap();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let user = database.create_user().finish();
+    database
+        .create_cart()
+        .with_free_items()
+        .for_user(&user)
+        .for_event(&event)
+        .finish();
+    let request = TestRequest::create();
+
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let response: HttpResponse = cart::checkout((
+        database.connection.clone().into(),
+        Json(cart::CheckoutCartRequest {
+            method: PaymentRequest::Free,
+        }),
+        auth_user,
+        request.extract_state(),
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let user = user
+        .verify_email(&user.email.clone().unwrap(), connection)
+        .unwrap()
+        .verify_phone(&user.phone.clone().unwrap(), connection)
+        .unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let response: HttpResponse = cart::checkout((
+        database.connection.clone().into(),
+        Json(cart::CheckoutCartRequest {
+            method: PaymentRequest::Free,
+        }),
+        auth_user,
+        request.extract_state(),
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
 #[test]
 fn checkout_free_for_paid_items() {
     let database = TestDatabase::new();
@@ -1193,3 +1264,47 @@ fn checkout_provider_globee() {
     let order = Order::find(order.id, conn).unwrap();
     assert_eq!(order.status, OrderStatus::Paid);
 }
+
+#[test]
+fn update_with_attribution() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let event = database
+        .create_event()
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let user = database.create_user().finish();
+    let ticket_type_id = event.ticket_types(true, None, connection).unwrap()[0].id;
+
+    // Blank parameters from the landing page url are ignored
+    let attribution: OrderAttribution = serde_json::from_value(json!({
+        "referrer": "https://www.google.com/",
+        "utm_source": "google",
+        "utm_medium": "cpc",
+        "utm_campaign": "",
+        "source_id": "spring-sale"
+    }))
+    .unwrap();
+    let input = Json(cart::UpdateCartRequest {
+        attribution: Some(attribution.clone()),
+        box_office_pricing: None,
+        items: vec![cart::CartItem {
+            ticket_type_id,
+            quantity: 2,
+            redemption_code: None,
+        }],
+    });
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let response =
+        cart::update_cart((database.connection.clone().into(), input, auth_user)).unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let cart = Order::find_cart_for_user(user.id, connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(cart.utm_source, Some("google".to_string()));
+    assert_eq!(cart.utm_campaign, None);
+    assert_eq!(cart.source_id, Some("spring-sale".to_string()));
+    assert_eq!(cart.attribution(), attribution);
+}
@@ -166,6 +166,7 @@ fn create_with_validation_errors() {
         end_date,
         max_tickets_per_user: None,
         ticket_type_ids: vec![ticket_type_id],
+        fan_segment_id: None,
     });
 
     let test_request = TestRequest::create();
@@ -216,6 +217,7 @@ fn create_fails_adding_ticket_type_id_from_other_event() {
         end_date,
         max_tickets_per_user: None,
         ticket_type_ids: vec![ticket_type_id],
+        fan_segment_id: None,
     });
 
     let test_request = TestRequest::create();
@@ -0,0 +1,75 @@
+use actix_web::{http::StatusCode, HttpResponse};
+use bigneon_api::controllers::communication_preferences::{
+    self, UpdateCommunicationPreferenceRequest,
+};
+use bigneon_api::extractors::*;
+use bigneon_db::models::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+
+#[test]
+fn update() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let auth_user = support::create_auth_user(Roles::User, None, &database);
+    let user_id = auth_user.id();
+
+    let json = Json(UpdateCommunicationPreferenceRequest {
+        organization_id: Some(organization.id),
+        category: CommunicationCategory::Marketing,
+        channel: CommunicationChannelType::Sms,
+        subscribed: false,
+    });
+    let response: HttpResponse = communication_preferences::update((
+        database.connection.clone().into(),
+        json,
+        auth_user.clone(),
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let preference: CommunicationPreference = serde_json::from_str(&body).unwrap();
+    assert_eq!(preference.user_id, user_id);
+    assert!(!preference.subscribed);
+    assert!(!CommunicationPreference::allows(
+        user_id,
+        Some(organization.id),
+        CommunicationCategory::Marketing,
+        CommunicationChannelType::Sms,
+        connection
+    )
+    .unwrap());
+
+    let response: HttpResponse =
+        communication_preferences::consents((database.connection.clone().into(), auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let consents: Vec<CommunicationConsent> = serde_json::from_str(&body).unwrap();
+    assert_eq!(consents.len(), 1);
+    assert_eq!(consents[0].source, "Preferences".to_string());
+}
+
+#[test]
+fn update_transactional_email() {
+    let database = TestDatabase::new();
+    let auth_user = support::create_auth_user(Roles::User, None, &database);
+
+    let json = Json(UpdateCommunicationPreferenceRequest {
+        organization_id: None,
+        category: CommunicationCategory::Transactional,
+        channel: CommunicationChannelType::Email,
+        subscribed: false,
+    });
+    let response: HttpResponse =
+        communication_preferences::update((database.connection.clone().into(), json, auth_user))
+            .into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let validation_response = support::validation_response_from_response(&response).unwrap();
+    let subscribed = validation_response.fields.get("subscribed").unwrap();
+    assert_eq!(
+        subscribed[0].code,
+        "Transactional emails cannot be turned off"
+    );
+}
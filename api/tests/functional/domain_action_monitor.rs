@@ -0,0 +1,68 @@
+use bigneon_api::domain_events::DomainActionMonitor;
+use bigneon_db::models::*;
+use bigneon_db::schema::{domain_actions, report_subscriptions};
+use chrono::prelude::*;
+use chrono::Duration;
+use diesel;
+use diesel::prelude::*;
+use support::database::TestDatabase;
+
+#[test]
+fn run_periodic_tasks_deletes_expired_rate_limit_counters() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    RateLimitCounter::hit("password_reset:ip:10.0.0.1", 0, connection).unwrap();
+    RateLimitCounter::hit("password_reset:ip:10.0.0.2", 60, connection).unwrap();
+
+    DomainActionMonitor::run_periodic_tasks(connection).unwrap();
+    assert!(
+        RateLimitCounter::find_by_key("password_reset:ip:10.0.0.1", connection)
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        RateLimitCounter::find_by_key("password_reset:ip:10.0.0.2", connection)
+            .unwrap()
+            .is_some()
+    );
+}
+
+#[test]
+fn run_periodic_tasks_schedules_overdue_report_deliveries() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let subscription = NewReportSubscription {
+        organization_id: organization.id,
+        user_id: user.id,
+        report_type: ReportTypes::TicketCount,
+        event_id: None,
+        frequency: ReportFrequencies::Daily,
+        day_of_week: None,
+        hour_of_day: 8,
+        timezone: "UTC".to_string(),
+        format: ExportFormats::Csv,
+    }
+    .commit(connection)
+    .unwrap();
+    // The delivery action expired before the report could be sent
+    let overdue_delivery_at = Utc::now().naive_utc() - Duration::days(2);
+    diesel::update(&subscription)
+        .set(report_subscriptions::next_delivery_at.eq(overdue_delivery_at))
+        .execute(connection)
+        .unwrap();
+    diesel::update(domain_actions::table.filter(domain_actions::main_table_id.eq(subscription.id)))
+        .set(domain_actions::expires_at.eq(overdue_delivery_at))
+        .execute(connection)
+        .unwrap();
+
+    DomainActionMonitor::run_periodic_tasks(connection).unwrap();
+    assert!(DomainAction::has_pending_action(
+        DomainActionTypes::SendScheduledReport,
+        Tables::ReportSubscriptions.to_string(),
+        subscription.id,
+        connection,
+    )
+    .unwrap());
+}
@@ -0,0 +1,96 @@
+use actix_web::{http::StatusCode, HttpResponse};
+use bigneon_api::auth::claims::EmailVerificationToken;
+use bigneon_api::controllers::email_verifications::{self, UpdateEmailVerificationParameters};
+use bigneon_api::extractors::*;
+use bigneon_db::models::*;
+use jwt::{encode, Header};
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+fn token(user: &User, email: &str, secret: &str) -> String {
+    encode(
+        &Header::default(),
+        &EmailVerificationToken::new(&user.id, email.to_string(), 1),
+        secret.as_bytes(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn create() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let test_request = TestRequest::create();
+    let response: HttpResponse = email_verifications::create((
+        test_request.extract_state(),
+        database.connection.clone().into(),
+        auth_user,
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let user = user
+        .verify_email(&user.email.clone().unwrap(), database.connection.get())
+        .unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let response: HttpResponse = email_verifications::create((
+        test_request.extract_state(),
+        database.connection.clone().into(),
+        auth_user,
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[test]
+fn update() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let test_request = TestRequest::create();
+    let secret = test_request.config.token_secret.clone();
+
+    let response: HttpResponse = email_verifications::update((
+        test_request.extract_state(),
+        database.connection.clone().into(),
+        Json(UpdateEmailVerificationParameters {
+            token: token(&user, &user.email.clone().unwrap(), "wrong_secret"),
+        }),
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let response: HttpResponse = email_verifications::update((
+        test_request.extract_state(),
+        database.connection.clone().into(),
+        Json(UpdateEmailVerificationParameters {
+            token: token(&user, &user.email.clone().unwrap(), &secret),
+        }),
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let user = User::find(user.id, database.connection.get()).unwrap();
+    assert!(user.is_email_verified());
+}
+
+#[test]
+fn update_after_email_changed() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let test_request = TestRequest::create();
+    let secret = test_request.config.token_secret.clone();
+
+    let response: HttpResponse = email_verifications::update((
+        test_request.extract_state(),
+        database.connection.clone().into(),
+        Json(UpdateEmailVerificationParameters {
+            token: token(&user, "old_email@tari.com", &secret),
+        }),
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let user = User::find(user.id, database.connection.get()).unwrap();
+    assert!(!user.is_email_verified());
+}
@@ -0,0 +1,258 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path, Query};
+use bigneon_api::controllers::event_split_partners;
+use bigneon_api::controllers::reports::{self, ReportQueryParameters};
+use bigneon_api::extractors::*;
+use bigneon_api::models::{EventSplitPartnerPathParameters, PathParameters};
+use bigneon_db::models::*;
+use functional::base;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+    #[test]
+    fn create_org_member() {
+        base::event_split_partners::create(Roles::OrgMember, false);
+    }
+    #[test]
+    fn create_admin() {
+        base::event_split_partners::create(Roles::Admin, true);
+    }
+    #[test]
+    fn create_user() {
+        base::event_split_partners::create(Roles::User, false);
+    }
+    #[test]
+    fn create_org_owner() {
+        base::event_split_partners::create(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn create_door_person() {
+        base::event_split_partners::create(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn create_org_admin() {
+        base::event_split_partners::create(Roles::OrgAdmin, true);
+    }
+    #[test]
+    fn create_box_office() {
+        base::event_split_partners::create(Roles::OrgBoxOffice, false);
+    }
+}
+
+#[test]
+fn index() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .finish();
+    let partner = database.create_organization().finish();
+    let split_partner = EventSplitPartner::create(event.id, partner.id, SplitTypes::Flat, 5_000, 0)
+        .commit(connection)
+        .unwrap();
+    let user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    let query_parameters = Query::<PagingParameters>::extract(&test_request.request).unwrap();
+
+    let response: HttpResponse = event_split_partners::index((
+        database.connection.clone().into(),
+        path,
+        query_parameters,
+        user,
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let split_partners: Payload<EventSplitPartner> = serde_json::from_str(&body).unwrap();
+    assert_eq!(split_partners.data, vec![split_partner]);
+}
+
+#[test]
+fn destroy() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .finish();
+    let partner = database.create_organization().finish();
+    let split_partner = EventSplitPartner::create(event.id, partner.id, SplitTypes::Flat, 5_000, 0)
+        .commit(connection)
+        .unwrap();
+    let user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request =
+        TestRequest::create_with_uri_custom_params("/", vec!["id", "split_partner_id"]);
+    let mut path = Path::<EventSplitPartnerPathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    path.split_partner_id = split_partner.id;
+
+    let response: HttpResponse =
+        event_split_partners::destroy((database.connection.clone().into(), path, user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(EventSplitPartner::find(split_partner.id, connection).is_err());
+}
+
+#[test]
+fn partner_organization_report_access() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .finish();
+    let partner = database.create_organization().finish();
+    let other_organization = database.create_organization().finish();
+    EventSplitPartner::create(event.id, partner.id, SplitTypes::Percentage, 2_500, 0)
+        .commit(connection)
+        .unwrap();
+
+    let response = report(
+        &database,
+        "event_summary",
+        Roles::OrgOwner,
+        &partner,
+        &event,
+    );
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = report(
+        &database,
+        "event_summary",
+        Roles::OrgOwner,
+        &other_organization,
+        &event,
+    );
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    // Partner members still need financial report access
+    let response = report(
+        &database,
+        "event_summary",
+        Roles::DoorPerson,
+        &partner,
+        &event,
+    );
+    support::expects_unauthorized(&response);
+
+    // Other event reports, such as the buyers in the transaction details, are kept from partners
+    for name in &[
+        "transaction_details",
+        "audit_report",
+        "ticket_count",
+        "sales_by_source",
+    ] {
+        let response = report(&database, name, Roles::OrgOwner, &partner, &event);
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let response = report(&database, name, Roles::OrgOwner, &organization, &event);
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
+
+#[test]
+fn partner_event_summary_report_only_includes_their_share() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    database
+        .create_order()
+        .for_event(&event)
+        .quantity(2)
+        .is_paid()
+        .finish();
+    let partner = database.create_organization().finish();
+    let other_partner = database.create_organization().finish();
+    EventSplitPartner::create(event.id, partner.id, SplitTypes::Percentage, 2_500, 0)
+        .commit(connection)
+        .unwrap();
+    EventSplitPartner::create(event.id, other_partner.id, SplitTypes::Flat, 1_000, 0)
+        .commit(connection)
+        .unwrap();
+    let result = Report::summary_event_report(event.id, None, None, connection).unwrap();
+    let expected_share = result
+        .split_partner_shares
+        .iter()
+        .find(|share| share.organization_id == partner.id)
+        .unwrap()
+        .clone();
+
+    let response = report(
+        &database,
+        "event_summary",
+        Roles::OrgOwner,
+        &partner,
+        &event,
+    );
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let summary: SplitPartnerEventSummary = serde_json::from_str(&body).unwrap();
+    assert_eq!(
+        summary,
+        SplitPartnerEventSummary {
+            event_id: event.id,
+            currency: event.currency.clone(),
+            organization_id: partner.id,
+            split_type: SplitTypes::Percentage,
+            ticket_sales_in_cents: expected_share.ticket_sales_in_cents,
+            fees_in_cents: expected_share.fees_in_cents,
+        }
+    );
+    assert!(summary.ticket_sales_in_cents > 0);
+    // Nothing of the sales or the other shares is included
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert!(body.get("sales").is_none());
+    assert!(body.get("split_partner_shares").is_none());
+    assert!(!body.to_string().contains(&other_partner.id.to_string()));
+
+    // The event's organization sees every share
+    let response = report(
+        &database,
+        "event_summary",
+        Roles::OrgOwner,
+        &organization,
+        &event,
+    );
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let result: EventSummarySalesResult = serde_json::from_str(&body).unwrap();
+    assert_eq!(result.split_partner_shares.len(), 2);
+}
+
+fn report(
+    database: &TestDatabase,
+    name: &str,
+    role: Roles,
+    organization: &Organization,
+    event: &Event,
+) -> HttpResponse {
+    let user = support::create_auth_user(role, Some(organization), database);
+    let test_request = TestRequest::create_with_uri(&format!(
+        "/reports/{}?report={}&event_id={}",
+        organization.id, name, event.id
+    ));
+    let query_parameters = Query::<ReportQueryParameters>::extract(&test_request.request).unwrap();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+
+    reports::get_report((
+        database.connection.clone().into(),
+        query_parameters,
+        path,
+        user,
+    ))
+    .into()
+}
@@ -0,0 +1,184 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path, Query};
+use bigneon_api::auth::user::User as AuthUser;
+use bigneon_api::controllers::event_users::{self, DisplayEventUser, NewEventUserRequest};
+use bigneon_api::controllers::events::{self, GuestListQueryParameters};
+use bigneon_api::controllers::holds;
+use bigneon_api::extractors::*;
+use bigneon_api::models::{EventUserPathParameters, PathParameters};
+use bigneon_db::models::*;
+use functional::base;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+    #[test]
+    fn create_org_member() {
+        base::event_users::create(Roles::OrgMember, false);
+    }
+    #[test]
+    fn create_admin() {
+        base::event_users::create(Roles::Admin, true);
+    }
+    #[test]
+    fn create_user() {
+        base::event_users::create(Roles::User, false);
+    }
+    #[test]
+    fn create_org_owner() {
+        base::event_users::create(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn create_door_person() {
+        base::event_users::create(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn create_org_admin() {
+        base::event_users::create(Roles::OrgAdmin, true);
+    }
+    #[test]
+    fn create_box_office() {
+        base::event_users::create(Roles::OrgBoxOffice, false);
+    }
+}
+
+#[test]
+fn create_with_unknown_email() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .finish();
+    let user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    let json = Json(NewEventUserRequest {
+        user_email: "unknown@tari.com".to_string(),
+        role: Roles::Promoter,
+    });
+
+    let response: HttpResponse =
+        event_users::create((database.connection.clone().into(), path, json, user)).into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[test]
+fn index() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .finish();
+    let owner = database.create_user().finish();
+    let user = support::create_auth_user_from_user(
+        &owner,
+        Roles::OrgOwner,
+        Some(&organization),
+        &database,
+    );
+    let promoter = database.create_user().finish();
+    EventUser::create(event.id, promoter.id, Roles::Promoter, connection).unwrap();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    let query_parameters = Query::<PagingParameters>::extract(&test_request.request).unwrap();
+
+    let response: HttpResponse = event_users::index((
+        database.connection.clone().into(),
+        path,
+        query_parameters,
+        user,
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let members: Payload<DisplayEventUser> = serde_json::from_str(&body).unwrap();
+    assert_eq!(
+        members
+            .data
+            .iter()
+            .map(|m| (m.user_id, m.roles.clone(), m.organization_or_event.clone()))
+            .collect::<Vec<(_, _, _)>>(),
+        vec![
+            (owner.id, vec![Roles::OrgOwner], "organization".to_string()),
+            (promoter.id, vec![Roles::Promoter], "event".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn destroy() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .finish();
+    let promoter = database.create_user().finish();
+    EventUser::create(event.id, promoter.id, Roles::Promoter, connection).unwrap();
+    let user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request = TestRequest::create_with_uri_custom_params("/", vec!["id", "user_id"]);
+    let mut path = Path::<EventUserPathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    path.user_id = promoter.id;
+
+    let response: HttpResponse =
+        event_users::destroy((database.connection.clone().into(), path, user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(EventUser::find_by_event_id_user_id(event.id, promoter.id, connection).is_err());
+}
+
+#[test]
+fn promoter_access_is_limited_to_their_event() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let hold = database.create_hold().finish();
+    let event = Event::find(hold.event_id, connection).unwrap();
+    let organization = event.organization(connection).unwrap();
+    let other_event = database
+        .create_event()
+        .with_organization(&organization)
+        .finish();
+    let promoter = database.create_user().finish();
+    EventUser::create(event.id, promoter.id, Roles::Promoter, connection).unwrap();
+    let test_request = TestRequest::create();
+    let auth_user = AuthUser::new(promoter, &test_request.request).unwrap();
+
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = hold.id;
+    let response: HttpResponse =
+        holds::show((database.connection.clone().into(), path, auth_user.clone())).into();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    for (event_id, should_succeed) in vec![(event.id, true), (other_event.id, false)] {
+        let test_request =
+            TestRequest::create_with_uri(&format!("/events/{}/guest?query=", event_id));
+        let query_parameters =
+            Query::<GuestListQueryParameters>::extract(&test_request.request).unwrap();
+        let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+        path.id = event_id;
+        let response: HttpResponse = events::guest_list((
+            database.connection.clone().into(),
+            query_parameters,
+            path,
+            auth_user.clone(),
+        ))
+        .into();
+        if should_succeed {
+            assert_eq!(response.status(), StatusCode::OK);
+        } else {
+            support::expects_unauthorized(&response);
+        }
+    }
+}
@@ -626,6 +626,46 @@ mod dashboard_tests {
     }
 }
 
+#[cfg(test)]
+mod audit_tests {
+    use super::*;
+
+    #[test]
+    fn audit_org_member() {
+        base::events::audit(Roles::OrgMember, false);
+    }
+
+    #[test]
+    fn audit_admin() {
+        base::events::audit(Roles::Admin, true);
+    }
+
+    #[test]
+    fn audit_user() {
+        base::events::audit(Roles::User, false);
+    }
+
+    #[test]
+    fn audit_org_owner() {
+        base::events::audit(Roles::OrgOwner, true);
+    }
+
+    #[test]
+    fn audit_door_person() {
+        base::events::audit(Roles::DoorPerson, false);
+    }
+
+    #[test]
+    fn audit_org_admin() {
+        base::events::audit(Roles::OrgAdmin, true);
+    }
+
+    #[test]
+    fn audit_box_office() {
+        base::events::audit(Roles::OrgBoxOffice, false);
+    }
+}
+
 #[cfg(test)]
 mod create_tests {
     use super::*;
@@ -942,8 +982,14 @@ fn dashboard_with_default_range() {
     )
     .unwrap();
     assert_eq!(cart.calculate_total(connection).unwrap(), 1700);
-    cart.add_external_payment(Some("test".to_string()), org_admin.id, 1700, connection)
-        .unwrap();
+    cart.add_external_payment(
+        Some("test".to_string()),
+        org_admin.id,
+        1700,
+        "USD",
+        connection,
+    )
+    .unwrap();
     assert_eq!(cart.status, OrderStatus::Paid);
 
     let test_request = TestRequest::create_with_uri(&format!("/events/{}/dashboard?", event.id));
@@ -0,0 +1,82 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path, Query};
+use bigneon_api::controllers::exchange_rates;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use functional::base;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+#[test]
+fn index() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let exchange_rate = ExchangeRate::create("USD", "ZAR", "14.5".parse().unwrap(), None)
+        .commit(connection)
+        .unwrap();
+    let exchange_rate2 = ExchangeRate::create("EUR", "USD", "1.13".parse().unwrap(), None)
+        .commit(connection)
+        .unwrap();
+
+    let user = support::create_auth_user(Roles::Admin, None, &database);
+    let test_request = TestRequest::create_with_uri(&format!("/limits?"));
+    let query_parameters = Query::<PagingParameters>::extract(&test_request.request).unwrap();
+    let response =
+        exchange_rates::index((database.connection.clone().into(), query_parameters, user))
+            .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.payload().data, vec![exchange_rate2, exchange_rate]);
+}
+
+#[test]
+fn destroy() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let exchange_rate = ExchangeRate::create("USD", "ZAR", "14.5".parse().unwrap(), None)
+        .commit(connection)
+        .unwrap();
+
+    let user = support::create_auth_user(Roles::Admin, None, &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = exchange_rate.id;
+
+    let response: HttpResponse =
+        exchange_rates::destroy((database.connection.clone().into(), path, user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(ExchangeRate::find(exchange_rate.id, connection).is_err());
+}
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+    #[test]
+    fn create_org_member() {
+        base::exchange_rates::create(Roles::OrgMember, false);
+    }
+    #[test]
+    fn create_admin() {
+        base::exchange_rates::create(Roles::Admin, true);
+    }
+    #[test]
+    fn create_user() {
+        base::exchange_rates::create(Roles::User, false);
+    }
+    #[test]
+    fn create_org_owner() {
+        base::exchange_rates::create(Roles::OrgOwner, false);
+    }
+    #[test]
+    fn create_door_person() {
+        base::exchange_rates::create(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn create_org_admin() {
+        base::exchange_rates::create(Roles::OrgAdmin, false);
+    }
+    #[test]
+    fn create_box_office() {
+        base::exchange_rates::create(Roles::OrgBoxOffice, false);
+    }
+}
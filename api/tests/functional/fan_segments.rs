@@ -0,0 +1,158 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path, Query};
+use bigneon_api::auth::user::User as AuthUser;
+use bigneon_api::controllers::fan_segments::{self, ExportParameters};
+use bigneon_api::extractors::*;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use functional::base;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+    #[test]
+    fn create_org_member() {
+        base::fan_segments::create(Roles::OrgMember, true);
+    }
+    #[test]
+    fn create_admin() {
+        base::fan_segments::create(Roles::Admin, true);
+    }
+    #[test]
+    fn create_user() {
+        base::fan_segments::create(Roles::User, false);
+    }
+    #[test]
+    fn create_org_owner() {
+        base::fan_segments::create(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn create_door_person() {
+        base::fan_segments::create(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn create_org_admin() {
+        base::fan_segments::create(Roles::OrgAdmin, true);
+    }
+    #[test]
+    fn create_box_office() {
+        base::fan_segments::create(Roles::OrgBoxOffice, false);
+    }
+}
+
+#[test]
+fn members() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let fan = database.create_user().finish();
+    database
+        .create_order()
+        .for_event(&event)
+        .for_user(&fan)
+        .is_paid()
+        .finish();
+    let fan_segment = FanSegment::create(
+        organization.id,
+        "Everyone",
+        &FanSegmentCriteria::default(),
+        user.id,
+    )
+    .commit(connection)
+    .unwrap();
+    let auth_user = support::create_auth_user_from_user(
+        &user,
+        Roles::OrgMember,
+        Some(&organization),
+        &database,
+    );
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = fan_segment.id;
+    let query_parameters = Query::<PagingParameters>::extract(&test_request.request).unwrap();
+    let response = fan_segments::members((
+        database.connection.clone().into(),
+        path,
+        query_parameters,
+        auth_user,
+    ))
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let payload = response.payload();
+    assert_eq!(payload.data.len(), 1);
+    assert_eq!(payload.data[0].user_id, fan.id);
+}
+
+#[test]
+fn export_for_other_organization() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let fan_segment = FanSegment::create(
+        organization.id,
+        "Everyone",
+        &FanSegmentCriteria::default(),
+        database.create_user().finish().id,
+    )
+    .commit(connection)
+    .unwrap();
+    let auth_user: AuthUser = support::create_auth_user(
+        Roles::OrgOwner,
+        Some(&database.create_organization().finish()),
+        &database,
+    );
+
+    let test_request = TestRequest::create_with_uri("/fan_segments/export?format=csv");
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = fan_segment.id;
+    let query = Query::<ExportParameters>::extract(&test_request.request).unwrap();
+    let response: HttpResponse =
+        fan_segments::export((database.connection.clone().into(), path, query, auth_user)).into();
+    support::expects_unauthorized(&response);
+}
+
+#[test]
+fn export() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let fan = database.create_user().finish();
+    database
+        .create_order()
+        .for_event(&event)
+        .for_user(&fan)
+        .is_paid()
+        .finish();
+    let fan_segment = FanSegment::create(
+        organization.id,
+        "Everyone",
+        &FanSegmentCriteria::default(),
+        fan.id,
+    )
+    .commit(connection)
+    .unwrap();
+    let auth_user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request = TestRequest::create_with_uri("/fan_segments/export?format=csv");
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = fan_segment.id;
+    let query = Query::<ExportParameters>::extract(&test_request.request).unwrap();
+    assert_eq!(query.format, ExportFormats::Csv);
+    let response: HttpResponse =
+        fan_segments::export((database.connection.clone().into(), path, query, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+}
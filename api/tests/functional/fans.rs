@@ -0,0 +1,128 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::fans::{self, AddFanTagRequest, CreateFanNoteRequest};
+use bigneon_api::extractors::*;
+use bigneon_api::models::{OrganizationFanNotePathParameters, OrganizationFanPathParameters};
+use bigneon_db::models::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+#[test]
+fn add_tag() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let fan = database.create_user().finish();
+    database
+        .create_order()
+        .for_event(&event)
+        .for_user(&fan)
+        .is_paid()
+        .finish();
+    let auth_user = support::create_auth_user(Roles::OrgMember, Some(&organization), &database);
+
+    let test_request = TestRequest::create_with_uri_custom_params("/", vec!["id", "user_id"]);
+    let mut path = Path::<OrganizationFanPathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    path.user_id = fan.id;
+    let json = Json(AddFanTagRequest {
+        tag: "VIP".to_string(),
+    });
+    let response: HttpResponse =
+        fans::add_tag((database.connection.clone().into(), path, json, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let fan_tag: FanTag = serde_json::from_str(&body).unwrap();
+    assert_eq!(fan_tag.user_id, fan.id);
+    assert_eq!(fan_tag.tag, "vip".to_string());
+}
+
+#[test]
+fn add_tag_for_non_fan() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let user = database.create_user().finish();
+    let auth_user = support::create_auth_user(Roles::OrgMember, Some(&organization), &database);
+
+    let test_request = TestRequest::create_with_uri_custom_params("/", vec!["id", "user_id"]);
+    let mut path = Path::<OrganizationFanPathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    path.user_id = user.id;
+    let json = Json(AddFanTagRequest {
+        tag: "VIP".to_string(),
+    });
+    let response: HttpResponse =
+        fans::add_tag((database.connection.clone().into(), path, json, auth_user)).into();
+    support::expects_forbidden(&response, Some("Fan does not belong to this organization"));
+}
+
+#[test]
+fn create_note() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let fan = database.create_user().finish();
+    database
+        .create_order()
+        .for_event(&event)
+        .for_user(&fan)
+        .is_paid()
+        .finish();
+    let user = database.create_user().finish();
+    let auth_user = support::create_auth_user_from_user(
+        &user,
+        Roles::OrgMember,
+        Some(&organization),
+        &database,
+    );
+
+    let test_request = TestRequest::create_with_uri_custom_params("/", vec!["id", "user_id"]);
+    let mut path = Path::<OrganizationFanPathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    path.user_id = fan.id;
+    let json = Json(CreateFanNoteRequest {
+        note: "Prefers aisle seats".to_string(),
+    });
+    let response: HttpResponse =
+        fans::create_note((database.connection.clone().into(), path, json, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let fan_note: FanNote = serde_json::from_str(&body).unwrap();
+    assert_eq!(fan_note.user_id, fan.id);
+    assert_eq!(fan_note.author_id, user.id);
+    assert_eq!(fan_note.note, "Prefers aisle seats".to_string());
+}
+
+#[test]
+fn destroy_note_for_other_organization() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let other_organization = database.create_organization().finish();
+    let fan = database.create_user().finish();
+    let fan_note = FanNote::create(other_organization.id, fan.id, fan.id, "Note")
+        .commit(connection)
+        .unwrap();
+    let auth_user = support::create_auth_user(Roles::OrgMember, Some(&organization), &database);
+
+    let test_request =
+        TestRequest::create_with_uri_custom_params("/", vec!["id", "user_id", "note_id"]);
+    let mut path =
+        Path::<OrganizationFanNotePathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    path.user_id = fan.id;
+    path.note_id = fan_note.id;
+    let response: HttpResponse =
+        fans::destroy_note((database.connection.clone().into(), path, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert!(FanNote::find(fan_note.id, connection).is_ok());
+}
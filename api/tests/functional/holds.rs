@@ -75,6 +75,39 @@ mod update_tests {
     }
 }
 
+#[cfg(test)]
+mod show_tests {
+    use super::*;
+    #[test]
+    fn show_org_member() {
+        base::holds::show(Roles::OrgMember, true);
+    }
+    #[test]
+    fn show_admin() {
+        base::holds::show(Roles::Admin, true);
+    }
+    #[test]
+    fn show_user() {
+        base::holds::show(Roles::User, false);
+    }
+    #[test]
+    fn show_org_owner() {
+        base::holds::show(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn show_door_person() {
+        base::holds::show(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn show_org_admin() {
+        base::holds::show(Roles::OrgAdmin, true);
+    }
+    #[test]
+    fn show_box_office() {
+        base::holds::show(Roles::OrgBoxOffice, false);
+    }
+}
+
 #[test]
 fn create_with_validation_errors() {
     let database = TestDatabase::new();
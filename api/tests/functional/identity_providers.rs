@@ -0,0 +1,291 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::config::IdentityProviderConfig;
+use bigneon_api::controllers::external::identity_providers;
+use bigneon_api::extractors::*;
+use bigneon_api::models::{ExternalLoginRequest, IdentityProviderPathParameters};
+use bigneon_api::utils::identity_providers::{JsonWebKey, JsonWebKeySet};
+use bigneon_db::models::*;
+use chrono::prelude::*;
+use chrono::Duration;
+use jwt::{encode, Algorithm, Header};
+use serde_json::Value;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+const PROVIDER: &str = "example";
+const SITE: &str = "example.com";
+const ISSUER: &str = "https://id.example.com";
+const CLIENT_ID: &str = "bigneon-test";
+const KEY_ID: &str = "test-key";
+const PRIVATE_KEY: &[u8] = include_bytes!("../support/keys/oidc_test_private_key.der");
+const MODULUS: &str = "yawIB6FK62MYV7bm4cHIsn_W3vtp4Lz5KwBMVzL1aZAi3dmZPVi85ZEQOuML8NpVe4kGDO9ZJto5dq6BY7xd-h0Foat0TXvxsXqpxmPiOy2iaGC3cQ7W4UbN9YNRnAKucq3bhn0iJBrbhtAs7LZq-9qjlGoatL5SZIeSstMNAfM_CGVyC4Z55E65A1MS__oslAC_xzCb9U3vtUf1x1_IC4MntT5tB4zLS3GBUqi63iCRWi5z5h94wuZUn52b55lzbk81Q7aLvN1-Ng0-Fk_nBsUEF-4PsmYBBXSttKaxXAmNRJdUdvcIcVPkmqArhSGHDh7E46mM6_0r0Itzd_n_HQ";
+
+fn test_request() -> TestRequest {
+    let mut config = TestRequest::test_config();
+    config.identity_providers = vec![IdentityProviderConfig {
+        name: PROVIDER.to_string(),
+        site: SITE.to_string(),
+        issuer: ISSUER.to_string(),
+        client_id: CLIENT_ID.to_string(),
+        jwks_url: None,
+        jwks: Some(JsonWebKeySet {
+            keys: vec![JsonWebKey {
+                kid: Some(KEY_ID.to_string()),
+                kty: "RSA".to_string(),
+                alg: Some("RS256".to_string()),
+                n: Some(MODULUS.to_string()),
+                e: Some("AQAB".to_string()),
+            }],
+        }),
+    }];
+    TestRequest::create_with_config("/", vec!["provider"], config)
+}
+
+fn id_token(subject: &str, email: &str, email_verified: Value, audience: &str) -> String {
+    let mut header = Header::new(Algorithm::RS256);
+    header.kid = Some(KEY_ID.to_string());
+    let claims = json!({
+        "iss": ISSUER,
+        "aud": audience,
+        "sub": subject,
+        "email": email,
+        "email_verified": email_verified,
+        "given_name": "Jane",
+        "family_name": "Doe",
+        "exp": (Utc::now().naive_utc() + Duration::minutes(5)).timestamp(),
+    });
+    encode(&header, &claims, PRIVATE_KEY).unwrap()
+}
+
+fn login(database: &TestDatabase, token: String) -> HttpResponse {
+    let test_request = test_request();
+    let mut path = Path::<IdentityProviderPathParameters>::extract(&test_request.request).unwrap();
+    path.provider = PROVIDER.to_string();
+    identity_providers::login((
+        test_request.request,
+        database.connection.clone().into(),
+        path,
+        Json(ExternalLoginRequest { token }),
+    ))
+    .into()
+}
+
+#[test]
+fn login_creates_user() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+
+    let token = id_token("subject-1", "Jane@Example.com", json!(true), CLIENT_ID);
+    let response = login(&database, token);
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let external_login = ExternalLogin::find_user("subject-1", SITE, connection)
+        .unwrap()
+        .unwrap();
+    let user = User::find(external_login.user_id, connection).unwrap();
+    assert_eq!(user.email, Some("jane@example.com".to_string()));
+    assert_eq!(user.first_name, Some("Jane".to_string()));
+
+    // Signing in again finds the same user
+    let token = id_token("subject-1", "jane@example.com", json!(true), CLIENT_ID);
+    let response = login(&database, token);
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        ExternalLogin::find_for_user(user.id, connection)
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn login_links_existing_user_by_verified_email() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database
+        .create_user()
+        .with_email("jane@example.com".to_string())
+        .finish();
+    let user = user.verify_email("jane@example.com", connection).unwrap();
+
+    // Apple sends the flag as a string
+    let token = id_token("subject-2", "jane@example.com", json!("true"), CLIENT_ID);
+    let response = login(&database, token);
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let external_login = ExternalLogin::find_user("subject-2", SITE, connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(external_login.user_id, user.id);
+}
+
+#[test]
+fn login_does_not_link_unverified_account() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database
+        .create_user()
+        .with_email("jane@example.com".to_string())
+        .finish();
+
+    let token = id_token("subject-8", "jane@example.com", json!(true), CLIENT_ID);
+    let response = login(&database, token);
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(ExternalLogin::find_for_user(user.id, connection)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn login_with_locked_user() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database
+        .create_user()
+        .with_email("jane@example.com".to_string())
+        .finish();
+    let user = user.verify_email("jane@example.com", connection).unwrap();
+    user.add_external_login(
+        "subject-9".to_string(),
+        SITE.to_string(),
+        "token".to_string(),
+        connection,
+    )
+    .unwrap();
+    user.record_failed_login(1, Duration::minutes(5), connection)
+        .unwrap();
+
+    let token = id_token("subject-9", "jane@example.com", json!(true), CLIENT_ID);
+    let response = login(&database, token);
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+}
+
+#[test]
+fn login_with_two_factor_authentication_enabled() {
+    let database = TestDatabase::new();
+    let user = database
+        .create_user()
+        .with_email("jane@example.com".to_string())
+        .finish();
+    let user = user
+        .verify_email("jane@example.com", database.connection.get())
+        .unwrap();
+    support::enable_two_factor_authentication(&user, &database);
+
+    let token = id_token("subject-7", "jane@example.com", json!(true), CLIENT_ID);
+    let response = login(&database, token);
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[test]
+fn login_with_unverified_email() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database
+        .create_user()
+        .with_email("jane@example.com".to_string())
+        .finish();
+
+    let token = id_token("subject-3", "jane@example.com", json!(false), CLIENT_ID);
+    let response = login(&database, token);
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(ExternalLogin::find_for_user(user.id, connection)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn login_with_token_for_another_client() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+
+    let token = id_token(
+        "subject-4",
+        "jane@example.com",
+        json!(true),
+        "another-client",
+    );
+    let response = login(&database, token);
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert!(ExternalLogin::find_user("subject-4", SITE, connection)
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn login_with_unknown_provider() {
+    let database = TestDatabase::new();
+    let test_request = test_request();
+    let mut path = Path::<IdentityProviderPathParameters>::extract(&test_request.request).unwrap();
+    path.provider = "unknown".to_string();
+    let response: HttpResponse = identity_providers::login((
+        test_request.request,
+        database.connection.clone().into(),
+        path,
+        Json(ExternalLoginRequest {
+            token: id_token("subject-5", "jane@example.com", json!(true), CLIENT_ID),
+        }),
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[test]
+fn link_and_unlink() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let other_user = database.create_user().finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    let other_auth_user =
+        support::create_auth_user_from_user(&other_user, Roles::User, None, &database);
+
+    // The provider's email does not need to match when a signed in user links an account
+    let test_request = test_request();
+    let mut path = Path::<IdentityProviderPathParameters>::extract(&test_request.request).unwrap();
+    path.provider = PROVIDER.to_string();
+    let response: HttpResponse = identity_providers::link((
+        test_request.extract_state(),
+        database.connection.clone().into(),
+        path,
+        Json(ExternalLoginRequest {
+            token: id_token("subject-6", "jane@example.com", json!(false), CLIENT_ID),
+        }),
+        auth_user.clone(),
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let external_logins = ExternalLogin::find_for_user(user.id, connection).unwrap();
+    assert_eq!(external_logins.len(), 1);
+    assert_eq!(external_logins[0].external_user_id, "subject-6".to_string());
+
+    // The same account can not be linked to a second user
+    let mut path = Path::<IdentityProviderPathParameters>::extract(&test_request.request).unwrap();
+    path.provider = PROVIDER.to_string();
+    let response: HttpResponse = identity_providers::link((
+        test_request.extract_state(),
+        database.connection.clone().into(),
+        path,
+        Json(ExternalLoginRequest {
+            token: id_token("subject-6", "jane@example.com", json!(true), CLIENT_ID),
+        }),
+        other_auth_user,
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let mut path = Path::<IdentityProviderPathParameters>::extract(&test_request.request).unwrap();
+    path.provider = PROVIDER.to_string();
+    let response: HttpResponse = identity_providers::unlink((
+        test_request.extract_state(),
+        database.connection.clone().into(),
+        path,
+        auth_user,
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert!(ExternalLogin::find_for_user(user.id, connection)
+        .unwrap()
+        .is_empty());
+}
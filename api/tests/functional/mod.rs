@@ -1,21 +1,42 @@
+mod affiliates;
 mod artists;
 mod auth;
 mod base;
+mod campaigns;
 mod cart;
 mod codes;
+mod communication_preferences;
 mod comps;
+mod domain_action_monitor;
+mod email_verifications;
+mod event_split_partners;
+mod event_users;
 mod events;
+mod exchange_rates;
+mod fan_segments;
+mod fans;
 mod holds;
+mod identity_providers;
 mod orders;
+mod organization_api_keys;
 mod organization_invites;
+mod organization_roles;
 mod organizations;
 mod password_resets;
+mod passwordless_logins;
 mod payment_methods;
+mod phone_verifications;
+mod rate_limiter;
 mod redemption_codes;
 mod regions;
+mod report_subscriptions;
+mod settlements;
 mod stages;
+mod tax_rules;
 mod ticket_types;
 mod tickets;
+mod two_factor_authentications;
 mod user_invites;
+mod user_sessions;
 mod users;
 mod venues;
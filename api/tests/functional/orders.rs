@@ -23,7 +23,7 @@ pub fn show() {
     let conn = database.connection.get();
     let total = order.calculate_total(conn).unwrap();
     order
-        .add_external_payment(Some("test".to_string()), user.id, total, conn)
+        .add_external_payment(Some("test".to_string()), user.id, total, "USD", conn)
         .unwrap();
     assert_eq!(order.status, OrderStatus::Paid);
 
@@ -142,7 +142,7 @@ pub fn index() {
     let conn = database.connection.get();
     let total = order1.calculate_total(conn).unwrap();
     order1
-        .add_external_payment(Some("test".to_string()), user.id, total, conn)
+        .add_external_payment(Some("test".to_string()), user.id, total, "USD", conn)
         .unwrap();
     order1 = diesel::update(&order1)
         .set(schema::orders::order_date.eq(date1))
@@ -151,7 +151,7 @@ pub fn index() {
     let mut order2 = database.create_order().for_user(&user).finish();
     let total = order2.calculate_total(conn).unwrap();
     order2
-        .add_external_payment(Some("test".to_string()), user.id, total - 100, conn)
+        .add_external_payment(Some("test".to_string()), user.id, total - 100, "USD", conn)
         .unwrap();
     order2 = diesel::update(&order2)
         .set(schema::orders::order_date.eq(date2))
@@ -291,7 +291,7 @@ pub fn details_with_tickets_user_has_no_access_to() {
     .unwrap();
 
     let total = cart.calculate_total(connection).unwrap();
-    cart.add_external_payment(Some("Test".to_string()), user.id, total, connection)
+    cart.add_external_payment(Some("Test".to_string()), user.id, total, "USD", connection)
         .unwrap();
 
     let items = cart.items(connection).unwrap();
@@ -418,7 +418,7 @@ pub fn refund_for_non_refundable_tickets() {
     .unwrap();
 
     let total = cart.calculate_total(connection).unwrap();
-    cart.add_external_payment(Some("Test".to_string()), user.id, total, connection)
+    cart.add_external_payment(Some("Test".to_string()), user.id, total, "USD", connection)
         .unwrap();
 
     let items = cart.items(&connection).unwrap();
@@ -531,7 +531,7 @@ pub fn refund_hold_ticket() {
     .unwrap();
 
     let total = cart.calculate_total(connection).unwrap();
-    cart.add_external_payment(Some("Test".to_string()), user.id, total, connection)
+    cart.add_external_payment(Some("Test".to_string()), user.id, total, "USD", connection)
         .unwrap();
 
     let items = cart.items(&connection).unwrap();
@@ -639,7 +639,7 @@ pub fn refund_removes_event_fee_if_all_event_tickets_refunded() {
     .unwrap();
 
     let total = cart.calculate_total(connection).unwrap();
-    cart.add_external_payment(Some("Test".to_string()), user.id, total, connection)
+    cart.add_external_payment(Some("Test".to_string()), user.id, total, "USD", connection)
         .unwrap();
 
     let items = cart.items(&connection).unwrap();
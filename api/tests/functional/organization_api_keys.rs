@@ -0,0 +1,195 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path, Query};
+use bigneon_api::auth::user::User as AuthUser;
+use bigneon_api::controllers::organization_api_keys::{self, CreateOrganizationApiKeyRequest};
+use bigneon_api::controllers::ticket_types;
+use bigneon_api::extractors::*;
+use bigneon_api::models::{
+    AdminDisplayTicketType, OrganizationApiKeyPathParameters, PathParameters,
+};
+use bigneon_db::models::*;
+use functional::base;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+    #[test]
+    fn create_org_member() {
+        base::organization_api_keys::create(Roles::OrgMember, false);
+    }
+    #[test]
+    fn create_admin() {
+        base::organization_api_keys::create(Roles::Admin, true);
+    }
+    #[test]
+    fn create_user() {
+        base::organization_api_keys::create(Roles::User, false);
+    }
+    #[test]
+    fn create_org_owner() {
+        base::organization_api_keys::create(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn create_door_person() {
+        base::organization_api_keys::create(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn create_org_admin() {
+        base::organization_api_keys::create(Roles::OrgAdmin, true);
+    }
+    #[test]
+    fn create_box_office() {
+        base::organization_api_keys::create(Roles::OrgBoxOffice, false);
+    }
+}
+
+#[test]
+fn create_with_scope_the_user_does_not_have() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let user = support::create_auth_user(Roles::OrgAdmin, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let json = Json(CreateOrganizationApiKeyRequest {
+        name: "Accounting".to_string(),
+        scopes: vec!["settlement:read".to_string()],
+        event_ids: Vec::new(),
+        expires_at: None,
+    });
+
+    let response: HttpResponse =
+        organization_api_keys::create((database.connection.clone().into(), path, json, user))
+            .into();
+    support::expects_unauthorized(&response);
+}
+
+#[test]
+fn index() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let user = database.create_user().finish();
+    let (api_key, _) = OrganizationApiKey::create(
+        organization.id,
+        "Integration",
+        vec![Scopes::EventWrite],
+        Vec::new(),
+        None,
+        user.id,
+        connection,
+    )
+    .unwrap();
+    let auth_user =
+        support::create_auth_user_from_user(&user, Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request = TestRequest::create_with_uri("/api_keys?");
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let query_parameters = Query::<PagingParameters>::extract(&test_request.request).unwrap();
+    let response = organization_api_keys::index((
+        database.connection.clone().into(),
+        path,
+        query_parameters,
+        auth_user,
+    ))
+    .unwrap();
+    assert_eq!(response.payload().data, vec![api_key]);
+}
+
+#[test]
+fn revoke() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let user = database.create_user().finish();
+    let (api_key, key) = OrganizationApiKey::create(
+        organization.id,
+        "Integration",
+        vec![Scopes::EventWrite],
+        Vec::new(),
+        None,
+        user.id,
+        connection,
+    )
+    .unwrap();
+    let auth_user =
+        support::create_auth_user_from_user(&user, Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request = TestRequest::create_with_uri_custom_params("/", vec!["id", "api_key_id"]);
+    let mut path =
+        Path::<OrganizationApiKeyPathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    path.api_key_id = api_key.id;
+    let response: HttpResponse =
+        organization_api_keys::revoke((database.connection.clone().into(), path, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(OrganizationApiKey::find_active_by_key(&key, connection).is_err());
+}
+
+#[test]
+fn api_key_is_limited_to_its_events() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let user = database.create_user().finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let other_event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let (api_key, _) = OrganizationApiKey::create(
+        organization.id,
+        "Integration",
+        vec![Scopes::EventWrite],
+        vec![event.id],
+        None,
+        user.id,
+        connection,
+    )
+    .unwrap();
+    let service_user = User::find(api_key.user_id, connection).unwrap();
+    let test_request = TestRequest::create_with_uri("/ticket_types?");
+    let auth_user = AuthUser::for_api_key(service_user, api_key, &test_request.request);
+
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = event.id;
+    let query_parameters = Query::<PagingParameters>::extract(&test_request.request).unwrap();
+    let response: HttpResponse = ticket_types::index((
+        database.connection.clone().into(),
+        path,
+        query_parameters,
+        auth_user.clone(),
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let ticket_types: Payload<AdminDisplayTicketType> = serde_json::from_str(&body).unwrap();
+    assert_eq!(ticket_types.data.len(), 1);
+
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = other_event.id;
+    let query_parameters = Query::<PagingParameters>::extract(&test_request.request).unwrap();
+    let response: HttpResponse = ticket_types::index((
+        database.connection.clone().into(),
+        path,
+        query_parameters,
+        auth_user.clone(),
+    ))
+    .into();
+    support::expects_unauthorized(&response);
+
+    // Organization wide access is not available to a key limited to events
+    assert!(!auth_user
+        .has_scope_for_organization(Scopes::EventWrite, &organization, connection)
+        .unwrap());
+}
@@ -0,0 +1,240 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::organization_roles::{self, OrganizationRoleRequest};
+use bigneon_api::controllers::organizations::{self, AddUserRequest};
+use bigneon_api::extractors::*;
+use bigneon_api::models::{OrganizationRolePathParameters, PathParameters};
+use bigneon_db::models::*;
+use functional::base;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+    #[test]
+    fn create_org_member() {
+        base::organization_roles::create(Roles::OrgMember, false);
+    }
+    #[test]
+    fn create_admin() {
+        base::organization_roles::create(Roles::Admin, true);
+    }
+    #[test]
+    fn create_user() {
+        base::organization_roles::create(Roles::User, false);
+    }
+    #[test]
+    fn create_org_owner() {
+        base::organization_roles::create(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn create_door_person() {
+        base::organization_roles::create(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn create_org_admin() {
+        base::organization_roles::create(Roles::OrgAdmin, false);
+    }
+    #[test]
+    fn create_box_office() {
+        base::organization_roles::create(Roles::OrgBoxOffice, false);
+    }
+}
+
+#[test]
+fn create_with_unknown_scope() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let json = Json(OrganizationRoleRequest {
+        name: "Marketing".to_string(),
+        scopes: vec!["fans:everything".to_string()],
+    });
+
+    let response: HttpResponse =
+        organization_roles::create((database.connection.clone().into(), path, json, user)).into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[test]
+fn update() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let organization_role = OrganizationRole::create(
+        organization.id,
+        "Marketing",
+        vec![Scopes::OrgFans],
+        connection,
+    )
+    .unwrap();
+    let user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request =
+        TestRequest::create_with_uri_custom_params("/", vec!["id", "organization_role_id"]);
+    let mut path = Path::<OrganizationRolePathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    path.organization_role_id = organization_role.id;
+    let json = Json(OrganizationRoleRequest {
+        name: "Finance".to_string(),
+        scopes: vec!["settlement:read".to_string()],
+    });
+
+    let response: HttpResponse =
+        organization_roles::update((database.connection.clone().into(), path, json, user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let updated_organization_role: OrganizationRole = serde_json::from_str(&body).unwrap();
+    assert_eq!(updated_organization_role.name, "Finance".to_string());
+    assert_eq!(
+        updated_organization_role.get_scopes(),
+        vec![Scopes::SettlementRead]
+    );
+}
+
+#[test]
+fn destroy() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let organization_role = OrganizationRole::create(
+        organization.id,
+        "Marketing",
+        vec![Scopes::OrgFans],
+        connection,
+    )
+    .unwrap();
+    let user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request =
+        TestRequest::create_with_uri_custom_params("/", vec!["id", "organization_role_id"]);
+    let mut path = Path::<OrganizationRolePathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    path.organization_role_id = organization_role.id;
+
+    let response: HttpResponse =
+        organization_roles::destroy((database.connection.clone().into(), path, user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(OrganizationRole::find(organization_role.id, connection).is_err());
+}
+
+#[test]
+fn add_user_with_organization_role() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let organization_role = OrganizationRole::create(
+        organization.id,
+        "Marketing",
+        vec![Scopes::OrgFans, Scopes::OrgReports],
+        connection,
+    )
+    .unwrap();
+    let member = database.create_user().finish();
+
+    // An organization admin can not give a role with scopes they do not have
+    let finance_role = OrganizationRole::create(
+        organization.id,
+        "Finance",
+        vec![Scopes::SettlementRead],
+        connection,
+    )
+    .unwrap();
+    let org_admin = support::create_auth_user(Roles::OrgAdmin, Some(&organization), &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let json = Json(AddUserRequest {
+        user_id: member.id,
+        roles: vec![],
+        organization_role_ids: Some(vec![finance_role.id]),
+    });
+    let response: HttpResponse = organizations::add_or_replace_user((
+        database.connection.clone().into(),
+        path,
+        json,
+        org_admin.clone(),
+    ))
+    .into();
+    support::expects_unauthorized(&response);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let json = Json(AddUserRequest {
+        user_id: member.id,
+        roles: vec![Roles::OrgBoxOffice],
+        organization_role_ids: Some(vec![organization_role.id]),
+    });
+    let response: HttpResponse = organizations::add_or_replace_user((
+        database.connection.clone().into(),
+        path,
+        json,
+        org_admin,
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let scopes = organization
+        .get_scopes_for_user(&member, connection)
+        .unwrap();
+    assert!(scopes.contains(&Scopes::OrgFans));
+    assert!(scopes.contains(&Scopes::OrgReports));
+    assert!(scopes.contains(&Scopes::BoxOfficeTicketRead));
+    assert!(!scopes.contains(&Scopes::OrderRefund));
+}
+
+#[test]
+fn add_user_without_organization_roles_keeps_existing_roles() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let organization_role = OrganizationRole::create(
+        organization.id,
+        "Marketing",
+        vec![Scopes::OrgFans],
+        connection,
+    )
+    .unwrap();
+    let member = database.create_user().finish();
+    organization
+        .add_user(
+            member.id,
+            vec![Roles::OrgMember],
+            vec![organization_role.id],
+            connection,
+        )
+        .unwrap();
+
+    let org_admin = support::create_auth_user(Roles::OrgAdmin, Some(&organization), &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let json = Json(AddUserRequest {
+        user_id: member.id,
+        roles: vec![Roles::OrgBoxOffice],
+        organization_role_ids: None,
+    });
+    let response: HttpResponse = organizations::add_or_replace_user((
+        database.connection.clone().into(),
+        path,
+        json,
+        org_admin,
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let organization_user =
+        OrganizationUser::find_by_user_id(member.id, organization.id, connection).unwrap();
+    assert_eq!(organization_user.role, vec![Roles::OrgBoxOffice]);
+    assert_eq!(
+        organization_user.organization_role_ids,
+        vec![organization_role.id]
+    );
+}
@@ -1,5 +1,13 @@
-use bigneon_db::models::Roles;
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path, Query};
+use bigneon_api::controllers::organizations as organizations_controller;
+use bigneon_api::extractors::*;
+use bigneon_api::models::{AuditLogQueryParameters, PathParameters};
+use bigneon_db::models::*;
+use chrono::prelude::*;
 use functional::base::organizations;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
 
 #[cfg(test)]
 mod index_tests {
@@ -396,3 +404,102 @@ mod add_fee_schedule_tests {
         organizations::add_fee_schedule(Roles::OrgBoxOffice, false);
     }
 }
+
+#[test]
+fn calendar() {
+    let database = TestDatabase::new();
+    let organization = database
+        .create_organization()
+        .with_name("Organization".to_string())
+        .finish();
+    let venue = database
+        .create_venue()
+        .with_timezone("Europe/London".to_string())
+        .finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_venue(&venue)
+        .with_event_start(NaiveDate::from_ymd(2019, 7, 1).and_hms(19, 0, 0))
+        .finish();
+    let event2 = database
+        .create_event()
+        .with_organization(&organization)
+        .with_event_start(NaiveDate::from_ymd(2019, 7, 2).and_hms(19, 0, 0))
+        .finish();
+    let other_event = database
+        .create_event()
+        .with_event_start(NaiveDate::from_ymd(2019, 7, 3).and_hms(19, 0, 0))
+        .finish();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+
+    let response: HttpResponse = organizations_controller::calendar((
+        test_request.extract_state(),
+        database.connection.into(),
+        path,
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    assert!(body.contains("X-WR-CALNAME:Organization\r\n"));
+    assert!(body.contains(&format!("UID:{}@bigneon.com\r\n", event.id)));
+    assert!(body.contains("DTSTART;TZID=Europe/London:20190701T200000\r\n"));
+    assert!(body.contains("BEGIN:VTIMEZONE\r\nTZID:Europe/London\r\nBEGIN:DAYLIGHT\r\n"));
+    assert!(body.contains("TZOFFSETTO:+0100\r\n"));
+    assert!(body.contains(&format!("UID:{}@bigneon.com\r\n", event2.id)));
+    assert!(body.contains("DTSTART:20190702T190000Z\r\n"));
+    assert!(!body.contains(&other_event.id.to_string()));
+}
+
+#[test]
+fn audit() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let user2 = database.create_user().finish();
+    let organization = database.create_organization().finish();
+    let auth_user =
+        support::create_auth_user_from_user(&user, Roles::OrgOwner, Some(&organization), &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let json = Json(organizations_controller::AddUserRequest {
+        user_id: user2.id,
+        roles: vec![Roles::OrgMember],
+        organization_role_ids: None,
+    });
+    let response: HttpResponse = organizations_controller::add_or_replace_user((
+        database.connection.clone().into(),
+        path,
+        json,
+        auth_user.clone(),
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let test_request = TestRequest::create_with_uri(&format!(
+        "/organizations/{}/audit?main_table=OrganizationUsers",
+        organization.id
+    ));
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let query = Query::<AuditLogQueryParameters>::extract(&test_request.request).unwrap();
+    let response = organizations_controller::audit((
+        database.connection.clone().into(),
+        path,
+        query,
+        auth_user,
+    ))
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let audit_logs = response.payload().data.clone();
+    assert_eq!(audit_logs.len(), 1);
+    assert_eq!(audit_logs[0].user_id, Some(user.id));
+    assert_eq!(audit_logs[0].scope, Some("org:users".to_string()));
+    assert_eq!(audit_logs[0].action, AuditActions::Created);
+    assert_eq!(audit_logs[0].changes["role"]["after"], json!(["OrgMember"]));
+}
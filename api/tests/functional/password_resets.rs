@@ -6,7 +6,7 @@ use bigneon_api::controllers::password_resets::{
 use bigneon_api::db::Connection as BigNeonConnection;
 use bigneon_api::extractors::*;
 use bigneon_db::models::concerns::users::password_resetable::*;
-use bigneon_db::models::User;
+use bigneon_db::models::{User, UserSession};
 use chrono::{Duration, Utc};
 use diesel;
 use diesel::prelude::*;
@@ -32,12 +32,11 @@ fn create() {
     .to_string();
 
     let test_request = TestRequest::create();
-    let state = test_request.extract_state();
     let json = Json(CreatePasswordResetParameters {
         email: email.to_string(),
     });
     let response: HttpResponse =
-        password_resets::create((state, database.connection.clone(), json)).into();
+        password_resets::create((test_request.request, database.connection.clone(), json)).into();
 
     assert_eq!(response.status(), StatusCode::CREATED);
     let body = support::unwrap_body_to_string(&response).unwrap();
@@ -55,11 +54,11 @@ fn create_fake_email() {
     .to_string();
 
     let test_request = TestRequest::create();
-    let state = test_request.extract_state();
     let json = Json(CreatePasswordResetParameters {
         email: email.to_string(),
     });
-    let response: HttpResponse = password_resets::create((state, database.connection, json)).into();
+    let response: HttpResponse =
+        password_resets::create((test_request.request, database.connection, json)).into();
 
     assert_eq!(response.status(), StatusCode::CREATED);
     let body = support::unwrap_body_to_string(&response).unwrap();
@@ -77,15 +76,17 @@ fn update() {
         .unwrap();
     let new_password = "newPassword";
     assert!(!user.check_password(&new_password));
+    let existing_session =
+        UserSession::create(user.id, None, None, database.connection.get()).unwrap();
 
     let test_request = TestRequest::create();
-    let state = test_request.extract_state();
-    let token_secret = &state.config.token_secret.clone();
+    let token_secret = &test_request.config.token_secret.clone();
     let json = Json(UpdatePasswordResetParameters {
         password_reset_token: user.password_reset_token.unwrap(),
         password: new_password.to_string(),
     });
-    let response: HttpResponse = password_resets::update((state, connection_object, json)).into();
+    let response: HttpResponse =
+        password_resets::update((test_request.request, connection_object, json)).into();
 
     let user = User::find(user.id, database.connection.get()).unwrap();
     assert!(user.password_reset_token.is_none());
@@ -113,6 +114,16 @@ fn update() {
     )
     .unwrap();
     assert_eq!(refresh_token.claims.get_id().unwrap(), user.id);
+
+    // Other devices are signed out
+    assert!(
+        UserSession::find(existing_session.id, database.connection.get())
+            .unwrap()
+            .is_revoked()
+    );
+    let sessions = UserSession::find_active_for_user(user.id, database.connection.get()).unwrap();
+    assert_eq!(sessions.len(), 1);
+    assert_eq!(refresh_token.claims.sid, Some(sessions[0].id));
 }
 
 #[test]
@@ -134,12 +145,12 @@ fn update_expired_token() {
     assert!(!user.check_password(&new_password));
 
     let test_request = TestRequest::create();
-    let state = test_request.extract_state();
     let json = Json(UpdatePasswordResetParameters {
         password_reset_token: token,
         password: new_password.to_string(),
     });
-    let response: HttpResponse = password_resets::update((state, connection_object, json)).into();
+    let response: HttpResponse =
+        password_resets::update((test_request.request, connection_object, json)).into();
 
     let user = User::find(user.id, database.connection.get()).unwrap();
     assert_eq!(user.password_reset_token.unwrap(), token);
@@ -162,12 +173,12 @@ fn update_incorrect_token() {
     assert!(!user.check_password(&new_password));
 
     let test_request = TestRequest::create();
-    let state = test_request.extract_state();
     let json = Json(UpdatePasswordResetParameters {
         password_reset_token: Uuid::new_v4(),
         password: new_password.to_string(),
     });
-    let response: HttpResponse = password_resets::update((state, connection_object, json)).into();
+    let response: HttpResponse =
+        password_resets::update((test_request.request, connection_object, json)).into();
 
     let user = User::find(user.id, database.connection.get()).unwrap();
     assert_eq!(user.password_reset_token.unwrap(), token);
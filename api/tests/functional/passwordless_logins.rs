@@ -0,0 +1,273 @@
+use actix_web::{http::StatusCode, HttpResponse};
+use bigneon_api::auth::{claims::AccessToken, TokenResponse};
+use bigneon_api::controllers::passwordless_logins::{
+    self, CreatePasswordlessLoginParameters, UpdatePasswordlessLoginParameters,
+};
+use bigneon_api::db::Connection as BigNeonConnection;
+use bigneon_api::extractors::*;
+use bigneon_db::models::concerns::users::passwordless_loginable::*;
+use bigneon_db::models::User;
+use chrono::prelude::*;
+use chrono::Duration;
+use diesel;
+use diesel::prelude::*;
+use jwt::{decode, Validation};
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+use uuid::Uuid;
+
+#[test]
+fn create_with_email() {
+    let database = TestDatabase::new();
+    let email = "joe@tari.com";
+    let user = database
+        .create_user()
+        .with_email(email.to_string())
+        .finish();
+    let expected_json = json!({
+        "message": format!("Your request has been received; {} will receive an email shortly with a link to sign in if it is an account on file.", email)
+    })
+    .to_string();
+
+    let test_request = TestRequest::create();
+    let json = Json(CreatePasswordlessLoginParameters {
+        email: Some(email.to_string()),
+        phone: None,
+    });
+    let response: HttpResponse =
+        passwordless_logins::create((test_request.request, database.connection.clone(), json))
+            .into();
+
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    assert_eq!(body, expected_json);
+    let user = User::find(user.id, database.connection.get()).unwrap();
+    assert!(user.has_valid_login_token());
+    assert!(user.login_code_hash.is_none());
+}
+
+#[test]
+fn create_with_phone() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let phone = user.phone.clone().unwrap();
+    let user = user
+        .verify_phone(&phone, database.connection.get())
+        .unwrap();
+
+    let test_request = TestRequest::create();
+    let json = Json(CreatePasswordlessLoginParameters {
+        email: None,
+        phone: Some(phone.clone()),
+    });
+    let response: HttpResponse =
+        passwordless_logins::create((test_request.request, database.connection.clone(), json))
+            .into();
+
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let user = User::find(user.id, database.connection.get()).unwrap();
+    assert!(user.has_valid_login_token());
+    assert!(user.login_code_hash.is_some());
+}
+
+#[test]
+fn create_with_unverified_phone() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+
+    let test_request = TestRequest::create();
+    let json = Json(CreatePasswordlessLoginParameters {
+        email: None,
+        phone: user.phone.clone(),
+    });
+    let response: HttpResponse =
+        passwordless_logins::create((test_request.request, database.connection.clone(), json))
+            .into();
+
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let user = User::find(user.id, database.connection.get()).unwrap();
+    assert!(!user.has_valid_login_token());
+}
+
+#[test]
+fn create_fake_email() {
+    let database = TestDatabase::new();
+    let email = "joe@tari.com";
+    let expected_json = json!({
+        "message": format!("Your request has been received; {} will receive an email shortly with a link to sign in if it is an account on file.", email)
+    })
+    .to_string();
+
+    let test_request = TestRequest::create();
+    let json = Json(CreatePasswordlessLoginParameters {
+        email: Some(email.to_string()),
+        phone: None,
+    });
+    let response: HttpResponse =
+        passwordless_logins::create((test_request.request, database.connection, json)).into();
+
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    assert_eq!(body, expected_json);
+}
+
+#[test]
+fn update_with_login_token() {
+    let database = TestDatabase::new();
+    let connection_object: BigNeonConnection = database.connection.clone().into();
+    let user = database.create_user().finish();
+    let user = user.create_login_token(database.connection.get()).unwrap();
+
+    let test_request = TestRequest::create();
+    let token_secret = &test_request.config.token_secret.clone();
+    let json = Json(UpdatePasswordlessLoginParameters {
+        login_token: user.login_token,
+        phone: None,
+        code: None,
+    });
+    let response: HttpResponse =
+        passwordless_logins::update((test_request.request, connection_object, json)).into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let token_response: TokenResponse = serde_json::from_str(&body).unwrap();
+    let access_token = decode::<AccessToken>(
+        &token_response.access_token,
+        token_secret.as_bytes(),
+        &Validation::default(),
+    )
+    .unwrap();
+    assert_eq!(access_token.claims.get_id().unwrap(), user.id);
+
+    let user = User::find(user.id, database.connection.get()).unwrap();
+    assert!(user.login_token.is_none());
+    assert!(user.email_verified_at.is_some());
+}
+
+#[test]
+fn update_with_login_token_for_locked_user() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let connection_object: BigNeonConnection = database.connection.clone().into();
+    let user = database.create_user().finish();
+    let user = user.create_login_token(connection).unwrap();
+    user.record_failed_login(1, Duration::minutes(5), connection)
+        .unwrap();
+
+    let test_request = TestRequest::create();
+    let json = Json(UpdatePasswordlessLoginParameters {
+        login_token: user.login_token,
+        phone: None,
+        code: None,
+    });
+    let response: HttpResponse =
+        passwordless_logins::update((test_request.request, connection_object, json)).into();
+
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    // The link can still be used once the lock has passed
+    let found_user = User::find(user.id, connection).unwrap();
+    assert_eq!(found_user.login_token, user.login_token);
+    assert!(found_user.email_verified_at.is_none());
+}
+
+#[test]
+fn update_with_expired_login_token() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let connection_object: BigNeonConnection = database.connection.clone().into();
+    let user = database.create_user().finish();
+    let token = Uuid::new_v4();
+    diesel::update(&user)
+        .set(PasswordlessLogin {
+            login_token: Some(token),
+            login_code_hash: None,
+            login_token_requested_at: Some(Utc::now().naive_utc() - Duration::minutes(20)),
+            login_code_failed_attempts: 0,
+        })
+        .execute(connection)
+        .unwrap();
+
+    let test_request = TestRequest::create();
+    let json = Json(UpdatePasswordlessLoginParameters {
+        login_token: Some(token),
+        phone: None,
+        code: None,
+    });
+    let response: HttpResponse =
+        passwordless_logins::update((test_request.request, connection_object, json)).into();
+
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[test]
+fn update_incorrect_login_token() {
+    let database = TestDatabase::new();
+    let connection_object: BigNeonConnection = database.connection.clone().into();
+    let user = database.create_user().finish();
+    let user = user.create_login_token(database.connection.get()).unwrap();
+
+    let test_request = TestRequest::create();
+    let json = Json(UpdatePasswordlessLoginParameters {
+        login_token: Some(Uuid::new_v4()),
+        phone: None,
+        code: None,
+    });
+    let response: HttpResponse =
+        passwordless_logins::update((test_request.request, connection_object, json)).into();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let found_user = User::find(user.id, database.connection.get()).unwrap();
+    assert_eq!(found_user.login_token, user.login_token);
+}
+
+#[test]
+fn update_with_code() {
+    let database = TestDatabase::new();
+    let connection_object: BigNeonConnection = database.connection.clone().into();
+    let user = database.create_user().finish();
+    let phone = user.phone.clone().unwrap();
+    let user = user
+        .verify_phone(&phone, database.connection.get())
+        .unwrap();
+    let (user, code) = user.create_login_code(database.connection.get()).unwrap();
+
+    let test_request = TestRequest::create();
+    let json = Json(UpdatePasswordlessLoginParameters {
+        login_token: None,
+        phone: user.phone.clone(),
+        code: Some(code),
+    });
+    let response: HttpResponse =
+        passwordless_logins::update((test_request.request, connection_object, json)).into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let user = User::find(user.id, database.connection.get()).unwrap();
+    assert!(user.login_code_hash.is_none());
+}
+
+#[test]
+fn update_incorrect_code() {
+    let database = TestDatabase::new();
+    let connection_object: BigNeonConnection = database.connection.clone().into();
+    let user = database.create_user().finish();
+    let phone = user.phone.clone().unwrap();
+    let user = user
+        .verify_phone(&phone, database.connection.get())
+        .unwrap();
+    let (user, _) = user.create_login_code(database.connection.get()).unwrap();
+
+    let test_request = TestRequest::create();
+    let json = Json(UpdatePasswordlessLoginParameters {
+        login_token: None,
+        phone: user.phone.clone(),
+        code: Some("not the code".to_string()),
+    });
+    let response: HttpResponse =
+        passwordless_logins::update((test_request.request, connection_object, json)).into();
+
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let user = User::find(user.id, database.connection.get()).unwrap();
+    assert_eq!(user.login_code_failed_attempts, 1);
+}
@@ -0,0 +1,82 @@
+use actix_web::{http::StatusCode, HttpResponse};
+use bigneon_api::controllers::phone_verifications::{self, UpdatePhoneVerificationParameters};
+use bigneon_api::extractors::*;
+use bigneon_db::models::*;
+use serde_json;
+use serde_json::Value;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+#[test]
+fn create() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let test_request = TestRequest::create();
+    let response: HttpResponse = phone_verifications::create((
+        test_request.extract_state(),
+        database.connection.clone().into(),
+        auth_user,
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let phone_verification: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(
+        phone_verification["phone"].as_str(),
+        user.phone.as_ref().map(|p| p.as_str())
+    );
+    assert!(phone_verification.get("code_hash").is_none());
+}
+
+#[test]
+fn update() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let (_, code) = PhoneVerification::create(&user, database.connection.get()).unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let test_request = TestRequest::create();
+    let response: HttpResponse = phone_verifications::update((
+        test_request.extract_state(),
+        database.connection.clone().into(),
+        Json(UpdatePhoneVerificationParameters {
+            code: "abc".to_string(),
+        }),
+        auth_user.clone(),
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let response: HttpResponse = phone_verifications::update((
+        test_request.extract_state(),
+        database.connection.clone().into(),
+        Json(UpdatePhoneVerificationParameters { code }),
+        auth_user,
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let user = User::find(user.id, database.connection.get()).unwrap();
+    assert!(user.is_phone_verified());
+}
+
+#[test]
+fn update_without_verification() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let test_request = TestRequest::create();
+    let response: HttpResponse = phone_verifications::update((
+        test_request.extract_state(),
+        database.connection.clone().into(),
+        Json(UpdatePhoneVerificationParameters {
+            code: "123456".to_string(),
+        }),
+        auth_user,
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
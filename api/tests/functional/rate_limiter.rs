@@ -0,0 +1,171 @@
+use actix_web::middleware::{Middleware, Started};
+use actix_web::{http::Method, http::StatusCode, test, HttpRequest};
+use bigneon_api::config::{Config, RateLimitConfig, RateLimitKey, RateLimitStoreType};
+use bigneon_api::db::Database;
+use bigneon_api::middleware::{client_ip, MemoryRateLimitStore, RateLimitStore, RateLimiter};
+use bigneon_api::server::AppState;
+use std::net::{IpAddr, SocketAddr};
+use support::test_request::TestRequest;
+use uuid::Uuid;
+
+fn config_with_rate_limit(key: RateLimitKey) -> Config {
+    let mut config = TestRequest::test_config();
+    // Counters are committed, a unique name keeps them apart from other test runs
+    config.rate_limits = vec![RateLimitConfig {
+        name: Uuid::new_v4().to_string(),
+        path: "/redemption_codes/{code}".to_string(),
+        method: Some("GET".to_string()),
+        key,
+        limit: 2,
+        period_in_seconds: 60,
+    }];
+    config
+}
+
+fn request(config: &Config, method: Method, path: &str) -> HttpRequest<AppState> {
+    test::TestRequest::with_state(AppState::new(config.clone(), Database::from_config(config)))
+        .method(method)
+        .uri(path)
+        .finish()
+}
+
+fn is_allowed(request: &HttpRequest<AppState>) -> bool {
+    match RateLimiter::new().start(request).unwrap() {
+        Started::Done => true,
+        Started::Response(response) => {
+            assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+            assert!(response.headers().contains_key("Retry-After"));
+            false
+        }
+        _ => panic!("Unexpected middleware result"),
+    }
+}
+
+fn is_allowed_for_ip(config: &Config, method: Method, path: &str, ip: &str) -> bool {
+    RateLimiter::check(&request(config, method, path), RateLimitKey::Ip, ip).is_ok()
+}
+
+#[test]
+fn limits_by_ip() {
+    assert_limits_by_ip(config_with_rate_limit(RateLimitKey::Ip));
+}
+
+#[test]
+fn limits_by_ip_in_memory() {
+    let mut config = config_with_rate_limit(RateLimitKey::Ip);
+    config.rate_limit_store = RateLimitStoreType::Memory;
+    assert_limits_by_ip(config);
+}
+
+#[test]
+fn memory_rate_limit_store() {
+    let key = Uuid::new_v4().to_string();
+    let store = MemoryRateLimitStore::new();
+    assert_eq!(store.hit(&key, 2, 60).unwrap(), None);
+    assert_eq!(store.hit(&key, 2, 60).unwrap(), None);
+    let retry_after = store.hit(&key, 2, 60).unwrap().unwrap();
+    assert!(retry_after > 0 && retry_after <= 60);
+
+    // Counters are shared by all stores of this instance
+    assert!(MemoryRateLimitStore::new()
+        .hit(&key, 2, 60)
+        .unwrap()
+        .is_some());
+
+    // A new window starts once the last one has ended
+    let key = Uuid::new_v4().to_string();
+    assert_eq!(store.hit(&key, 1, 0).unwrap(), None);
+    assert_eq!(store.hit(&key, 1, 0).unwrap(), None);
+}
+
+fn assert_limits_by_ip(config: Config) {
+    for _ in 0..2 {
+        assert!(is_allowed_for_ip(
+            &config,
+            Method::GET,
+            "/redemption_codes/ABC123",
+            "10.0.0.1"
+        ));
+    }
+    assert!(!is_allowed_for_ip(
+        &config,
+        Method::GET,
+        "/redemption_codes/DEF456",
+        "10.0.0.1"
+    ));
+
+    // Other addresses, methods and routes are not affected
+    assert!(is_allowed_for_ip(
+        &config,
+        Method::GET,
+        "/redemption_codes/ABC123",
+        "10.0.0.2"
+    ));
+    assert!(is_allowed_for_ip(
+        &config,
+        Method::POST,
+        "/redemption_codes/ABC123",
+        "10.0.0.1"
+    ));
+    assert!(is_allowed_for_ip(
+        &config,
+        Method::GET,
+        "/redemption_codes",
+        "10.0.0.1"
+    ));
+}
+
+#[test]
+fn client_ip_ignores_forwarded_for_from_untrusted_peers() {
+    let peer: SocketAddr = "203.0.113.7:51234".parse().unwrap();
+    let proxy: SocketAddr = "10.0.0.2:443".parse().unwrap();
+    let trusted_proxies: Vec<IpAddr> = vec!["10.0.0.2".parse().unwrap()];
+
+    // A client can not pick its own address
+    assert_eq!(
+        client_ip(Some(peer), Some("198.51.100.1"), &trusted_proxies),
+        Some(peer.ip())
+    );
+    assert_eq!(
+        client_ip(None, Some("198.51.100.1"), &trusted_proxies),
+        None
+    );
+
+    // Behind a trusted proxy the address it appended is used, not those the client sent
+    assert_eq!(
+        client_ip(
+            Some(proxy),
+            Some("198.51.100.1, 203.0.113.7"),
+            &trusted_proxies
+        ),
+        Some(peer.ip())
+    );
+    assert_eq!(
+        client_ip(Some(proxy), Some("garbage, 203.0.113.7"), &trusted_proxies),
+        Some(peer.ip())
+    );
+    assert_eq!(
+        client_ip(Some(proxy), None, &trusted_proxies),
+        Some(proxy.ip())
+    );
+}
+
+#[test]
+fn check_target() {
+    let config = config_with_rate_limit(RateLimitKey::Target);
+    let request = request(&config, Method::GET, "/redemption_codes/ABC123");
+
+    // Target limits are left to the controller
+    for _ in 0..3 {
+        assert!(is_allowed(&request));
+    }
+
+    assert!(RateLimiter::check_target(&request, "fake@localhost").is_ok());
+    assert!(RateLimiter::check_target(&request, "Fake@localhost").is_ok());
+    let error = RateLimiter::check_target(&request, "fake@localhost").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Too many requests, please try again later"
+    );
+    assert!(RateLimiter::check_target(&request, "other@localhost").is_ok());
+}
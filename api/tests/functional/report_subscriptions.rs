@@ -0,0 +1,186 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path, Query};
+use bigneon_api::controllers::report_subscriptions::{self, UnsubscribeRequest};
+use bigneon_api::domain_events::executors::send_scheduled_report::SendScheduledReportExecutor;
+use bigneon_api::extractors::*;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use bigneon_db::schema::{domain_actions, report_subscriptions as report_subscriptions_table};
+use chrono::Duration;
+use diesel;
+use diesel::prelude::*;
+use functional::base;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+    #[test]
+    fn create_org_member() {
+        base::report_subscriptions::create(Roles::OrgMember, false);
+    }
+    #[test]
+    fn create_admin() {
+        base::report_subscriptions::create(Roles::Admin, true);
+    }
+    #[test]
+    fn create_user() {
+        base::report_subscriptions::create(Roles::User, false);
+    }
+    #[test]
+    fn create_org_owner() {
+        base::report_subscriptions::create(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn create_door_person() {
+        base::report_subscriptions::create(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn create_org_admin() {
+        base::report_subscriptions::create(Roles::OrgAdmin, true);
+    }
+    #[test]
+    fn create_box_office() {
+        base::report_subscriptions::create(Roles::OrgBoxOffice, false);
+    }
+}
+
+fn new_subscription(organization: &Organization, user: &User) -> NewReportSubscription {
+    NewReportSubscription {
+        organization_id: organization.id,
+        user_id: user.id,
+        report_type: ReportTypes::TicketCount,
+        event_id: None,
+        frequency: ReportFrequencies::Daily,
+        day_of_week: None,
+        hour_of_day: 7,
+        timezone: "UTC".to_string(),
+        format: ExportFormats::Csv,
+    }
+}
+
+#[test]
+fn index_and_destroy() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let user = database.create_user().finish();
+    let auth_user =
+        support::create_auth_user_from_user(&user, Roles::OrgOwner, Some(&organization), &database);
+    let subscription = new_subscription(&organization, &user)
+        .commit(connection)
+        .unwrap();
+    let other_user = database.create_user().finish();
+    let other_subscription = new_subscription(&organization, &other_user)
+        .commit(connection)
+        .unwrap();
+
+    let test_request = TestRequest::create_with_uri("/limits?");
+    let query_parameters = Query::<PagingParameters>::extract(&test_request.request).unwrap();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let response = report_subscriptions::index((
+        database.connection.clone().into(),
+        path,
+        query_parameters,
+        auth_user.clone(),
+    ))
+    .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.payload().data, vec![subscription.clone()]);
+
+    // Other users' subscriptions cannot be removed
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = other_subscription.id;
+    let response: HttpResponse = report_subscriptions::destroy((
+        database.connection.clone().into(),
+        path,
+        auth_user.clone(),
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = subscription.id;
+    let response: HttpResponse =
+        report_subscriptions::destroy((database.connection.clone().into(), path, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let subscription = ReportSubscription::find(subscription.id, connection).unwrap();
+    assert!(subscription.unsubscribed_at.is_some());
+}
+
+#[test]
+fn unsubscribe() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let user = database.create_user().finish();
+    let subscription = new_subscription(&organization, &user)
+        .commit(connection)
+        .unwrap();
+
+    let json = Json(UnsubscribeRequest {
+        token: subscription.unsubscribe_token,
+    });
+    let response: HttpResponse =
+        report_subscriptions::unsubscribe((database.connection.clone().into(), json)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let subscription = ReportSubscription::find(subscription.id, connection).unwrap();
+    assert!(subscription.unsubscribed_at.is_some());
+}
+
+#[test]
+fn send_scheduled_report() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let user = database.create_user().finish();
+    support::create_auth_user_from_user(&user, Roles::OrgOwner, Some(&organization), &database);
+    let subscription = new_subscription(&organization, &user)
+        .commit(connection)
+        .unwrap();
+    let delivery_at = subscription.next_delivery_at - Duration::days(1);
+    diesel::update(&subscription)
+        .set(report_subscriptions_table::next_delivery_at.eq(delivery_at))
+        .execute(connection)
+        .unwrap();
+    let domain_action: DomainAction = domain_actions::table
+        .filter(domain_actions::main_table_id.eq(subscription.id))
+        .first(connection)
+        .unwrap();
+
+    let test_request = TestRequest::create();
+    let executor = SendScheduledReportExecutor::new(test_request.config.clone());
+    executor
+        .perform_job(&domain_action, &database.connection.clone())
+        .unwrap();
+
+    let deliveries = subscription.deliveries(connection).unwrap();
+    assert_eq!(deliveries.len(), 1);
+    assert_eq!(deliveries[0].status, ReportDeliveryStatus::Sent);
+    assert_eq!(deliveries[0].period_end, delivery_at);
+    let subscription = ReportSubscription::find(subscription.id, connection).unwrap();
+    assert_eq!(
+        subscription.next_delivery_at,
+        delivery_at + Duration::days(1)
+    );
+
+    let communications =
+        DomainAction::find_pending(Some(DomainActionTypes::Communication), connection).unwrap();
+    assert_eq!(communications.len(), 1);
+    let attachments = &communications[0].payload["attachments"];
+    assert_eq!(
+        attachments[0]["filename"],
+        json!(format!(
+            "ticket_count-{}.csv",
+            delivery_at.format("%Y-%m-%d")
+        ))
+    );
+
+    // Running the same action again does not send the report twice
+    executor
+        .perform_job(&domain_action, &database.connection.clone())
+        .unwrap();
+    assert_eq!(subscription.deliveries(connection).unwrap().len(), 1);
+}
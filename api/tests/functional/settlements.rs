@@ -0,0 +1,205 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path, Query};
+use bigneon_api::controllers::settlements::{
+    self, CreateSettlementAdjustmentRequest, ExportParameters,
+};
+use bigneon_api::extractors::*;
+use bigneon_api::models::{PathParameters, SettlementAdjustmentPathParameters};
+use bigneon_db::models::*;
+use functional::base;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+#[cfg(test)]
+mod index_tests {
+    use super::*;
+    #[test]
+    fn index_org_member() {
+        base::settlements::index(Roles::OrgMember, false);
+    }
+    #[test]
+    fn index_admin() {
+        base::settlements::index(Roles::Admin, true);
+    }
+    #[test]
+    fn index_user() {
+        base::settlements::index(Roles::User, false);
+    }
+    #[test]
+    fn index_org_owner() {
+        base::settlements::index(Roles::OrgOwner, true);
+    }
+    #[test]
+    fn index_door_person() {
+        base::settlements::index(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn index_org_admin() {
+        base::settlements::index(Roles::OrgAdmin, false);
+    }
+    #[test]
+    fn index_box_office() {
+        base::settlements::index(Roles::OrgBoxOffice, false);
+    }
+}
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+    #[test]
+    fn create_org_member() {
+        base::settlements::create(Roles::OrgMember, false);
+    }
+    #[test]
+    fn create_admin() {
+        base::settlements::create(Roles::Admin, true);
+    }
+    #[test]
+    fn create_user() {
+        base::settlements::create(Roles::User, false);
+    }
+    #[test]
+    fn create_org_owner() {
+        base::settlements::create(Roles::OrgOwner, false);
+    }
+    #[test]
+    fn create_door_person() {
+        base::settlements::create(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn create_org_admin() {
+        base::settlements::create(Roles::OrgAdmin, false);
+    }
+    #[test]
+    fn create_box_office() {
+        base::settlements::create(Roles::OrgBoxOffice, false);
+    }
+}
+
+#[cfg(test)]
+mod approve_tests {
+    use super::*;
+    #[test]
+    fn approve_org_member() {
+        base::settlements::approve(Roles::OrgMember, false);
+    }
+    #[test]
+    fn approve_admin() {
+        base::settlements::approve(Roles::Admin, true);
+    }
+    #[test]
+    fn approve_user() {
+        base::settlements::approve(Roles::User, false);
+    }
+    #[test]
+    fn approve_org_owner() {
+        base::settlements::approve(Roles::OrgOwner, false);
+    }
+    #[test]
+    fn approve_door_person() {
+        base::settlements::approve(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn approve_org_admin() {
+        base::settlements::approve(Roles::OrgAdmin, false);
+    }
+    #[test]
+    fn approve_box_office() {
+        base::settlements::approve(Roles::OrgBoxOffice, false);
+    }
+}
+
+#[test]
+fn adjustments() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database.create_organization().finish();
+    let user = support::create_auth_user(Roles::Admin, None, &database);
+    let settlement = Settlement::create(organization.id, None, None, None, user.id())
+        .commit(connection)
+        .unwrap();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = settlement.id;
+    let json = Json(CreateSettlementAdjustmentRequest {
+        adjustment_type: SettlementAdjustmentTypes::Chargeback,
+        amount_in_cents: -2500,
+        note: Some("Disputed order".to_string()),
+    });
+    let response: HttpResponse =
+        settlements::add_adjustment((database.connection.clone().into(), path, json, user.clone()))
+            .into();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let adjustment: SettlementAdjustment = serde_json::from_str(&body).unwrap();
+    assert_eq!(adjustment.amount_in_cents, -2500);
+    assert_eq!(
+        settlement.adjustments(connection).unwrap(),
+        vec![adjustment.clone()]
+    );
+
+    let test_request = TestRequest::create_with_uri_custom_params("/", vec!["id", "adjustment_id"]);
+    let mut path =
+        Path::<SettlementAdjustmentPathParameters>::extract(&test_request.request).unwrap();
+    path.id = settlement.id;
+    path.adjustment_id = adjustment.id;
+    let response: HttpResponse =
+        settlements::destroy_adjustment((database.connection.clone().into(), path, user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(settlement.adjustments(connection).unwrap().is_empty());
+}
+
+#[test]
+fn export() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let organization = database
+        .create_organization()
+        .with_name("Organization".to_string())
+        .finish();
+    let user = support::create_auth_user(Roles::OrgOwner, Some(&organization), &database);
+    let settlement = Settlement::create(organization.id, None, None, None, user.id())
+        .commit(connection)
+        .unwrap();
+    SettlementAdjustment::create(
+        settlement.id,
+        SettlementAdjustmentTypes::Expense,
+        -1050,
+        Some("Security, staff".to_string()),
+        user.id(),
+    )
+    .commit(connection)
+    .unwrap();
+
+    let test_request = TestRequest::create_with_uri("/settlements/export?format=csv");
+    let query = Query::<ExportParameters>::extract(&test_request.request).unwrap();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = settlement.id;
+
+    let response: HttpResponse = settlements::export((
+        database.connection.clone().into(),
+        path,
+        query,
+        user.clone(),
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    assert!(body.contains("Organization,Organization\r\n"));
+    assert!(body.contains("\"Expense: Security, staff\",-10.50\r\n"));
+    assert!(body.contains("Total,-10.50\r\n"));
+
+    let test_request = TestRequest::create_with_uri("/settlements/export?format=pdf");
+    let query = Query::<ExportParameters>::extract(&test_request.request).unwrap();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = settlement.id;
+
+    let response: HttpResponse =
+        settlements::export((database.connection.clone().into(), path, query, user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    assert!(body.starts_with("%PDF-1.4"));
+    assert!(body.contains("(Expense: Security, staff) Tj"));
+}
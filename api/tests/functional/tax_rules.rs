@@ -0,0 +1,103 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::tax_rules;
+use bigneon_api::extractors::*;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use functional::base;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+#[test]
+fn update() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let region = database.create_region().finish();
+    let tax_rule = TaxRule::create("Sales Tax", Some(region.id), None, 825, false)
+        .commit(connection)
+        .unwrap();
+
+    let user = support::create_auth_user(Roles::Admin, None, &database);
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = tax_rule.id;
+
+    let json = Json(TaxRuleEditableAttributes {
+        rate_in_basis_points: Some(900),
+        ..Default::default()
+    });
+    let response: HttpResponse =
+        tax_rules::update((database.connection.clone().into(), path, json, user)).into();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let updated_tax_rule: TaxRule = serde_json::from_str(&body).unwrap();
+    assert_eq!(updated_tax_rule.rate_in_basis_points, 900);
+}
+
+#[cfg(test)]
+mod create_tests {
+    use super::*;
+    #[test]
+    fn create_org_member() {
+        base::tax_rules::create(Roles::OrgMember, false);
+    }
+    #[test]
+    fn create_admin() {
+        base::tax_rules::create(Roles::Admin, true);
+    }
+    #[test]
+    fn create_user() {
+        base::tax_rules::create(Roles::User, false);
+    }
+    #[test]
+    fn create_org_owner() {
+        base::tax_rules::create(Roles::OrgOwner, false);
+    }
+    #[test]
+    fn create_door_person() {
+        base::tax_rules::create(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn create_org_admin() {
+        base::tax_rules::create(Roles::OrgAdmin, false);
+    }
+    #[test]
+    fn create_box_office() {
+        base::tax_rules::create(Roles::OrgBoxOffice, false);
+    }
+}
+
+#[cfg(test)]
+mod destroy_tests {
+    use super::*;
+    #[test]
+    fn destroy_org_member() {
+        base::tax_rules::destroy(Roles::OrgMember, false);
+    }
+    #[test]
+    fn destroy_admin() {
+        base::tax_rules::destroy(Roles::Admin, true);
+    }
+    #[test]
+    fn destroy_user() {
+        base::tax_rules::destroy(Roles::User, false);
+    }
+    #[test]
+    fn destroy_org_owner() {
+        base::tax_rules::destroy(Roles::OrgOwner, false);
+    }
+    #[test]
+    fn destroy_door_person() {
+        base::tax_rules::destroy(Roles::DoorPerson, false);
+    }
+    #[test]
+    fn destroy_org_admin() {
+        base::tax_rules::destroy(Roles::OrgAdmin, false);
+    }
+    #[test]
+    fn destroy_box_office() {
+        base::tax_rules::destroy(Roles::OrgBoxOffice, false);
+    }
+}
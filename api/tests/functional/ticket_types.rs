@@ -181,6 +181,7 @@ pub fn create_with_validation_errors() {
         increment: None,
         limit_per_person: 0,
         price_in_cents: 10000,
+        fee_mode: None,
     };
     let response: HttpResponse = ticket_types::create((
         database.connection.into(),
@@ -243,6 +244,7 @@ pub fn create_with_validation_errors_on_ticket_pricing() {
         increment: None,
         limit_per_person: 0,
         price_in_cents: 10000,
+        fee_mode: None,
     };
     let response: HttpResponse = ticket_types::create((
         database.connection.into(),
@@ -314,6 +316,7 @@ pub fn create_with_overlapping_periods() {
         increment: None,
         limit_per_person: 0,
         price_in_cents: 20000,
+        fee_mode: None,
     };
     let response: HttpResponse = ticket_types::create((
         database.connection.into(),
@@ -403,6 +406,7 @@ pub fn update_with_invalid_id() {
         increment: None,
         limit_per_person: Some(0),
         price_in_cents: Some(20000),
+        fee_mode: None,
     };
 
     //Send update request
@@ -469,6 +473,7 @@ pub fn update_with_validation_errors() {
         increment: None,
         limit_per_person: Some(0),
         price_in_cents: Some(20000),
+        fee_mode: None,
     };
 
     //Send update request
@@ -546,6 +551,7 @@ pub fn update_with_validation_errors_on_ticket_pricing() {
         increment: None,
         limit_per_person: Some(0),
         price_in_cents: Some(20000),
+        fee_mode: None,
     };
 
     //Send update request
@@ -635,6 +641,7 @@ pub fn update_with_overlapping_periods() {
         increment: None,
         limit_per_person: Some(0),
         price_in_cents: Some(20000),
+        fee_mode: None,
     };
 
     //Send update request
@@ -709,7 +716,7 @@ pub fn cancel_with_sold_tickets_and_hold() {
     )
     .unwrap();
     let total = cart.calculate_total(conn).unwrap();
-    cart.add_external_payment(Some("test".to_string()), user.id, total, conn)
+    cart.add_external_payment(Some("test".to_string()), user.id, total, "USD", conn)
         .unwrap();
 
     let valid_unsold_ticket_count = created_ticket_type.valid_unsold_ticket_count(conn).unwrap();
@@ -75,7 +75,7 @@ pub fn index() {
     .unwrap();
 
     let total = cart.calculate_total(conn).unwrap();
-    cart.add_external_payment(Some("test".to_string()), user.id, total, conn)
+    cart.add_external_payment(Some("test".to_string()), user.id, total, "USD", conn)
         .unwrap();
     let ticket =
         TicketInstance::find_for_user_for_display(user.id, Some(event.id), None, None, conn)
@@ -215,7 +215,7 @@ pub fn show() {
     )
     .unwrap();
     let total = cart.calculate_total(conn).unwrap();
-    cart.add_external_payment(Some("test".to_string()), user.id, total, conn)
+    cart.add_external_payment(Some("test".to_string()), user.id, total, "USD", conn)
         .unwrap();
     let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
     let mut path = Path::<PathParameters>::extract(&request.request).unwrap();
@@ -396,7 +396,7 @@ fn ticket_transfer_authorization() {
 
     //Try after paying for the tickets
     let total = cart.calculate_total(conn).unwrap();
-    cart.add_external_payment(Some("test".to_string()), user.id, total, conn)
+    cart.add_external_payment(Some("test".to_string()), user.id, total, "USD", conn)
         .unwrap();
 
     let response = tickets::transfer_authorization((
@@ -504,7 +504,7 @@ fn receive_ticket_transfer() {
     .unwrap();
 
     let total = cart.calculate_total(conn).unwrap();
-    cart.add_external_payment(Some("test".to_string()), user.id, total, conn)
+    cart.add_external_payment(Some("test".to_string()), user.id, total, "USD", conn)
         .unwrap();
     let tickets = TicketInstance::find_for_user(user.id, conn).unwrap();
 
@@ -0,0 +1,218 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::organizations;
+use bigneon_api::controllers::two_factor_authentications::{
+    self, EnableTwoFactorAuthenticationRequest,
+};
+use bigneon_api::extractors::*;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use serde_json;
+use serde_json::Value;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+#[test]
+fn create_and_enable() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let test_request = TestRequest::create();
+    let response: HttpResponse = two_factor_authentications::create((
+        test_request.extract_state(),
+        database.connection.clone().into(),
+        auth_user.clone(),
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let enrolment: Value = serde_json::from_str(&body).unwrap();
+    let secret = enrolment["secret"].as_str().unwrap().to_string();
+    assert!(enrolment["provisioning_uri"]
+        .as_str()
+        .unwrap()
+        .starts_with("otpauth://totp/"));
+    assert!(enrolment["enabled_at"].is_null());
+
+    let response: HttpResponse = two_factor_authentications::enable((
+        test_request.extract_state(),
+        database.connection.clone().into(),
+        Json(EnableTwoFactorAuthenticationRequest {
+            code: support::two_factor_code(&secret),
+        }),
+        auth_user.clone(),
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let recovery_codes: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(
+        recovery_codes["recovery_codes"].as_array().unwrap().len(),
+        RECOVERY_CODE_COUNT
+    );
+
+    let response: HttpResponse =
+        two_factor_authentications::show((database.connection.clone().into(), auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let two_factor_authentication: Value = serde_json::from_str(&body).unwrap();
+    assert!(!two_factor_authentication["enabled_at"].is_null());
+    assert!(two_factor_authentication.get("secret").is_none());
+}
+
+#[test]
+fn destroy_requires_code() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let secret = support::enable_two_factor_authentication(&user, &database);
+    let mut auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let test_request = TestRequest::create();
+    let response: HttpResponse = two_factor_authentications::destroy((
+        test_request.extract_state(),
+        database.connection.clone().into(),
+        auth_user.clone(),
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    auth_user.two_factor_code = Some(support::two_factor_code(&secret));
+    let response: HttpResponse = two_factor_authentications::destroy((
+        test_request.extract_state(),
+        database.connection.clone().into(),
+        auth_user,
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert!(
+        TwoFactorAuthentication::find_for_user(user.id, database.connection.get())
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn wrong_codes_lock_user() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let secret = support::enable_two_factor_authentication(&user, &database);
+    let mut config = TestRequest::test_config();
+    config.max_failed_login_attempts = 2;
+
+    for _ in 0..2 {
+        let mut auth_user =
+            support::create_auth_user_from_user(&user, Roles::User, None, &database);
+        auth_user.two_factor_code = Some("000000".to_string());
+        let test_request = TestRequest::create_with_config("/", vec![], config.clone());
+        let response: HttpResponse = two_factor_authentications::destroy((
+            test_request.extract_state(),
+            database.connection.clone().into(),
+            auth_user,
+        ))
+        .into();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+    let user = User::find(user.id, database.connection.get()).unwrap();
+    assert!(user.is_locked());
+
+    // Even the correct code is refused while locked
+    let mut auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+    auth_user.two_factor_code = Some(support::two_factor_code(&secret));
+    let test_request = TestRequest::create_with_config("/", vec![], config);
+    let response: HttpResponse = two_factor_authentications::destroy((
+        test_request.extract_state(),
+        database.connection.clone().into(),
+        auth_user,
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert!(
+        TwoFactorAuthentication::find_for_user(user.id, database.connection.get())
+            .unwrap()
+            .is_some()
+    );
+}
+
+#[test]
+fn add_fee_schedule_requires_code() {
+    let database = TestDatabase::new();
+    let organization = database.create_organization().finish();
+    let user = database.create_user().finish();
+    let secret = support::enable_two_factor_authentication(&user, &database);
+    let mut auth_user = support::create_auth_user_from_user(&user, Roles::Admin, None, &database);
+
+    let test_request = TestRequest::create();
+    let new_fee_schedule = || NewFeeSchedule {
+        name: "Fees".to_string(),
+        ranges: vec![NewFeeScheduleRange {
+            min_price_in_cents: 20,
+            company_fee_in_cents: 4,
+            client_fee_in_cents: 6,
+            ..Default::default()
+        }],
+    };
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let response: HttpResponse = organizations::add_fee_schedule((
+        test_request.extract_state(),
+        database.connection.clone().into(),
+        path,
+        Json(new_fee_schedule()),
+        auth_user.clone(),
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    assert_eq!(
+        body,
+        json!({"error": "Two factor code required"}).to_string()
+    );
+
+    auth_user.two_factor_code = Some(support::two_factor_code(&secret));
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = organization.id;
+    let response: HttpResponse = organizations::add_fee_schedule((
+        test_request.extract_state(),
+        database.connection.clone().into(),
+        path,
+        Json(new_fee_schedule()),
+        auth_user,
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::CREATED);
+}
+
+#[test]
+fn organization_requiring_two_factor() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let organization = database
+        .create_organization()
+        .finish()
+        .update(
+            OrganizationEditableAttributes {
+                require_two_factor: Some(true),
+                ..Default::default()
+            },
+            &"encryption_key".to_string(),
+            connection,
+        )
+        .unwrap();
+    let auth_user =
+        support::create_auth_user_from_user(&user, Roles::OrgOwner, Some(&organization), &database);
+    let admin = support::create_auth_user(Roles::Admin, None, &database);
+
+    assert!(!auth_user
+        .has_scope_for_organization(Scopes::OrgRead, &organization, connection)
+        .unwrap());
+    assert!(admin
+        .has_scope_for_organization(Scopes::OrgRead, &organization, connection)
+        .unwrap());
+
+    support::enable_two_factor_authentication(&user, &database);
+    assert!(auth_user
+        .has_scope_for_organization(Scopes::OrgRead, &organization, connection)
+        .unwrap());
+}
@@ -0,0 +1,79 @@
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
+use bigneon_api::controllers::user_sessions;
+use bigneon_api::models::PathParameters;
+use bigneon_db::models::*;
+use serde_json;
+use support;
+use support::database::TestDatabase;
+use support::test_request::TestRequest;
+
+#[test]
+fn index() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let session = UserSession::create(user.id, None, None, connection).unwrap();
+    let revoked_session = UserSession::create(user.id, None, None, connection).unwrap();
+    revoked_session.revoke(connection).unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let response: HttpResponse =
+        user_sessions::index((database.connection.clone().into(), auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    assert_eq!(body, serde_json::to_string(&vec![session]).unwrap());
+}
+
+#[test]
+fn destroy() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let other_user = database.create_user().finish();
+    let session = UserSession::create(user.id, None, None, connection).unwrap();
+    let other_session = UserSession::create(other_user.id, None, None, connection).unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    // Sessions of other users are not found
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = other_session.id;
+    let response: HttpResponse =
+        user_sessions::destroy((database.connection.clone().into(), path, auth_user.clone()))
+            .into();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert!(!UserSession::find(other_session.id, connection)
+        .unwrap()
+        .is_revoked());
+
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = session.id;
+    let response: HttpResponse =
+        user_sessions::destroy((database.connection.clone().into(), path, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert!(UserSession::find(session.id, connection)
+        .unwrap()
+        .is_revoked());
+}
+
+#[test]
+fn destroy_all() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let other_user = database.create_user().finish();
+    UserSession::create(user.id, None, None, connection).unwrap();
+    UserSession::create(user.id, None, None, connection).unwrap();
+    let other_session = UserSession::create(other_user.id, None, None, connection).unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let response: HttpResponse =
+        user_sessions::destroy_all((database.connection.clone().into(), auth_user)).into();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert!(UserSession::find_active_for_user(user.id, connection)
+        .unwrap()
+        .is_empty());
+    assert!(!UserSession::find(other_session.id, connection)
+        .unwrap()
+        .is_revoked());
+}
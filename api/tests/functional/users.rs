@@ -1,8 +1,8 @@
-use actix_web::{http::StatusCode, HttpResponse};
+use actix_web::{http::StatusCode, FromRequest, HttpResponse, Path};
 use bigneon_api::auth::TokenResponse;
 use bigneon_api::controllers::users;
 use bigneon_api::extractors::*;
-use bigneon_api::models::{RegisterRequest, UserProfileAttributes};
+use bigneon_api::models::{PathParameters, RegisterRequest, UserProfileAttributes};
 use bigneon_db::prelude::*;
 use functional::base;
 use serde_json;
@@ -333,6 +333,86 @@ mod users_show_tests {
     }
 }
 
+#[cfg(test)]
+mod export_data_tests {
+    use super::*;
+
+    #[test]
+    fn export_data_org_member() {
+        base::users::export_data(Roles::OrgMember, false);
+    }
+
+    #[test]
+    fn export_data_admin() {
+        base::users::export_data(Roles::Admin, true);
+    }
+
+    #[test]
+    fn export_data_user() {
+        base::users::export_data(Roles::User, false);
+    }
+
+    #[test]
+    fn export_data_org_owner() {
+        base::users::export_data(Roles::OrgOwner, false);
+    }
+
+    #[test]
+    fn export_data_door_person() {
+        base::users::export_data(Roles::DoorPerson, false);
+    }
+
+    #[test]
+    fn export_data_org_admin() {
+        base::users::export_data(Roles::OrgAdmin, false);
+    }
+
+    #[test]
+    fn export_data_box_office() {
+        base::users::export_data(Roles::OrgBoxOffice, false);
+    }
+}
+
+#[cfg(test)]
+mod erase_tests {
+    use super::*;
+
+    #[test]
+    fn erase_org_member() {
+        base::users::erase(Roles::OrgMember, false);
+    }
+
+    #[test]
+    fn erase_admin() {
+        base::users::erase(Roles::Admin, true);
+    }
+
+    #[test]
+    fn erase_user() {
+        base::users::erase(Roles::User, false);
+    }
+
+    #[test]
+    fn erase_org_owner() {
+        base::users::erase(Roles::OrgOwner, false);
+    }
+
+    #[test]
+    fn erase_door_person() {
+        base::users::erase(Roles::DoorPerson, false);
+    }
+
+    #[test]
+    fn erase_org_admin() {
+        base::users::erase(Roles::OrgAdmin, false);
+    }
+
+    #[test]
+    fn erase_box_office() {
+        base::users::erase(Roles::OrgBoxOffice, false);
+    }
+}
+
 #[test]
 fn register_address_exists() {
     let database = TestDatabase::new();
@@ -459,6 +539,31 @@ fn current_user() {
         response.scopes
     );
     assert!(response.organization_scopes.is_empty());
+    assert!(!response.email_verified);
+    assert!(!response.phone_verified);
+}
+
+#[test]
+fn current_user_event_promoter() {
+    let database = TestDatabase::new();
+    let connection = database.connection.get();
+    let user = database.create_user().finish();
+    let event = database.create_event().finish();
+    database.create_event().finish();
+    EventUser::create(event.id, user.id, Roles::Promoter, connection).unwrap();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let current_user =
+        users::current_user((database.connection.clone().into(), auth_user)).unwrap();
+    assert!(current_user.organization_roles.is_empty());
+    let mut expected_roles = HashMap::new();
+    expected_roles.insert(event.id, vec![Roles::Promoter]);
+    assert_eq!(expected_roles, current_user.event_roles);
+    assert_eq!(1, current_user.event_scopes.len());
+    let event_scopes = &current_user.event_scopes[&event.id];
+    assert!(event_scopes.contains(&Scopes::HoldRead));
+    assert!(event_scopes.contains(&Scopes::EventViewGuests));
+    assert!(!event_scopes.contains(&Scopes::OrgRead));
 }
 
 #[test]
@@ -504,6 +609,7 @@ fn current_user_organization_owner() {
             "org:users",
             "org:write",
             "redeem:ticket",
+            "settlement:read",
             "ticket:admin",
             "ticket:read",
             "ticket:transfer",
@@ -643,3 +749,24 @@ fn update_current_user_address_exists() {
     let response: HttpResponse = result.into();
     assert_eq!(response.status(), StatusCode::CONFLICT);
 }
+
+#[test]
+fn export_data_for_current_user() {
+    let database = TestDatabase::new();
+    let user = database.create_user().finish();
+    let auth_user = support::create_auth_user_from_user(&user, Roles::User, None, &database);
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = user.id;
+    let response: HttpResponse =
+        users::export_data((database.connection.into(), path, auth_user)).into();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("Content-Disposition").unwrap(),
+        &format!("attachment; filename=\"user-data-{}.json\"", user.id)
+    );
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    let data: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(data["profile"]["email"], json!(user.email));
+}
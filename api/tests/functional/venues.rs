@@ -3,6 +3,7 @@ use bigneon_api::controllers::venues;
 use bigneon_api::extractors::*;
 use bigneon_api::models::{AddVenueToOrganizationRequest, PathParameters};
 use bigneon_db::models::*;
+use chrono::prelude::*;
 use functional::base;
 use serde_json;
 use std::collections::HashMap;
@@ -82,6 +83,7 @@ fn index_with_org_linked_and_private_venues() {
     let _ = organization.add_user(
         auth_user.id(),
         vec![Roles::OrgMember],
+        Vec::new(),
         database.connection.get(),
     );
     expected_venues.push(venue4);
@@ -489,3 +491,55 @@ pub fn add_to_organization_where_link_already_exists() {
         venues::add_to_organization((database.connection.into(), path, json, auth_user)).into();
     assert_eq!(response.status(), StatusCode::CONFLICT);
 }
+
+#[test]
+pub fn calendar() {
+    let database = TestDatabase::new();
+    let venue = database
+        .create_venue()
+        .with_name("Venue, Main Stage".to_string())
+        .with_timezone("America/New_York".to_string())
+        .finish();
+    let event = database
+        .create_event()
+        .with_name("Event Name".to_string())
+        .with_venue(&venue)
+        .with_event_start(NaiveDate::from_ymd(2019, 3, 1).and_hms(1, 0, 0))
+        .finish();
+    let draft_event = database
+        .create_event()
+        .with_status(EventStatus::Draft)
+        .with_venue(&venue)
+        .with_event_start(NaiveDate::from_ymd(2019, 3, 2).and_hms(1, 0, 0))
+        .finish();
+    let unpublished_event = database
+        .create_event()
+        .with_venue(&venue)
+        .with_publish_date(NaiveDate::from_ymd(2099, 1, 1).and_hms(12, 0, 0))
+        .with_event_start(NaiveDate::from_ymd(2019, 3, 3).and_hms(1, 0, 0))
+        .finish();
+
+    let test_request = TestRequest::create();
+    let mut path = Path::<PathParameters>::extract(&test_request.request).unwrap();
+    path.id = venue.id;
+
+    let response: HttpResponse = venues::calendar((
+        test_request.extract_state(),
+        database.connection.into(),
+        path,
+    ))
+    .into();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = support::unwrap_body_to_string(&response).unwrap();
+    assert!(body.starts_with("BEGIN:VCALENDAR\r\n"));
+    assert!(body.contains("X-WR-CALNAME:Venue\\, Main Stage\r\n"));
+    assert!(body.contains(&format!("UID:{}@bigneon.com\r\n", event.id)));
+    assert!(body.contains("DTSTART;TZID=America/New_York:20190228T200000\r\n"));
+    assert!(body.contains("BEGIN:VTIMEZONE\r\nTZID:America/New_York\r\nBEGIN:STANDARD\r\n"));
+    assert!(body.contains("TZOFFSETFROM:-0500\r\n"));
+    assert_eq!(body.matches("BEGIN:VTIMEZONE").count(), 1);
+    assert!(body.contains("SUMMARY:Event Name\r\n"));
+    assert!(!body.contains(&draft_event.id.to_string()));
+    assert!(!body.contains(&unpublished_event.id.to_string()));
+    assert!(body.ends_with("END:VCALENDAR\r\n"));
+}
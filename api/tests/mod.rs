@@ -18,6 +18,7 @@ extern crate globee;
 extern crate jsonwebtoken as jwt;
 extern crate uuid;
 extern crate validator;
+extern crate zip;
 
 mod functional;
 mod support;
@@ -127,6 +127,7 @@ impl TestDatabase {
             Some("test".to_string()),
             user.id,
             total,
+            "USD",
             self.connection.get(),
         )
         .unwrap();
@@ -3,7 +3,9 @@ pub mod test_request;
 
 use actix_web::{http::StatusCode, Body::Binary, HttpResponse};
 use bigneon_api::auth::user::User as AuthUser;
-use bigneon_db::models::{Organization, Roles, User};
+use bigneon_db::models::{Organization, Roles, TwoFactorAuthentication, User};
+use bigneon_db::utils::totp;
+use chrono::prelude::*;
 use serde_json;
 use std::collections::HashMap;
 use std::str;
@@ -56,7 +58,7 @@ pub fn create_auth_user_from_user(
         };
 
         organization
-            .add_user(user.id, vec![role], database.connection.get())
+            .add_user(user.id, vec![role], Vec::new(), database.connection.get())
             .unwrap();
 
         AuthUser::new(user.clone(), &test_request.request).unwrap()
@@ -83,3 +85,32 @@ pub fn expects_forbidden(response: &HttpResponse, message: Option<&str>) {
     let body = unwrap_body_to_string(&response).unwrap();
     assert_eq!(body, expected_text);
 }
+
+/// Enables two factor authentication for the user, returning the authenticator app secret
+pub fn enable_two_factor_authentication(user: &User, database: &TestDatabase) -> String {
+    let encryption_key = TestRequest::test_config().api_keys_encryption_key;
+    let connection = database.connection.get();
+    let (two_factor_authentication, secret) =
+        TwoFactorAuthentication::create(user.id, &encryption_key, connection).unwrap();
+    // Enabled with the previous code so the current one is still unused
+    two_factor_authentication
+        .enable(
+            &two_factor_code_at(&secret, -1),
+            &encryption_key,
+            connection,
+        )
+        .unwrap();
+    secret
+}
+
+/// The code the authenticator app currently shows for the secret
+pub fn two_factor_code(secret: &str) -> String {
+    two_factor_code_at(secret, 0)
+}
+
+fn two_factor_code_at(secret: &str, step_offset: i64) -> String {
+    totp::code_at(
+        &totp::base32_decode(secret).unwrap(),
+        totp::time_step(Utc::now().naive_utc().timestamp()) + step_offset,
+    )
+}
@@ -19,6 +19,10 @@ impl TestRequest {
     }
 
     pub fn create_with_uri_custom_params(path: &str, params: Vec<&'static str>) -> TestRequest {
+        TestRequest::create_with_config(path, params, TestRequest::test_config())
+    }
+
+    pub fn test_config() -> Config {
         let mut config = Config::new(Environment::Test);
         config.token_secret = "test_secret".into();
         config.token_issuer = "bn-api-test".into();
@@ -27,7 +31,14 @@ impl TestRequest {
         if config.spotify_auth_token.is_some() {
             spotify::SINGLETON.set_auth_token(&config.spotify_auth_token.clone().unwrap());
         }
+        config
+    }
 
+    pub fn create_with_config(
+        path: &str,
+        params: Vec<&'static str>,
+        config: Config,
+    ) -> TestRequest {
         let test_request = test::TestRequest::with_state(AppState::new(
             config.clone(),
             Database::from_config(&config),
@@ -274,3 +274,58 @@ fn from_ticket_type() {
     println!("{:?}", display_ticket_type);
     assert_eq!(display_ticket_type.ticket_pricing.unwrap().fee_in_cents, 0);
 }
+
+#[test]
+fn from_ticket_type_with_fee_modes() {
+    let database = TestDatabase::new();
+
+    let admin = database.create_user().finish();
+    let fee_schedule = database.create_fee_schedule().finish(admin.id);
+    let organization = database
+        .create_organization()
+        .with_fee_schedule(&fee_schedule)
+        .finish();
+    let event = database
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let conn = database.connection.get();
+
+    let ticket_type = event.ticket_types(true, None, conn).unwrap().remove(0);
+    let ticket_pricing = ticket_type.current_ticket_pricing(false, conn).unwrap();
+    let fee_in_cents = fee_schedule
+        .get_range(ticket_pricing.price_in_cents, conn)
+        .unwrap()
+        .fee_in_cents;
+
+    // All-in pricing includes the fee in the price
+    let ticket_type = ticket_type
+        .update_fee_mode(Some(FeeModes::AllIn), conn)
+        .unwrap();
+    let display_ticket_type =
+        UserDisplayTicketType::from_ticket_type(&ticket_type, &fee_schedule, false, None, conn)
+            .unwrap();
+    assert_eq!(display_ticket_type.fee_mode, FeeModes::AllIn);
+    let display_ticket_pricing = display_ticket_type.ticket_pricing.unwrap();
+    assert_eq!(
+        display_ticket_pricing.price_in_cents,
+        ticket_pricing.price_in_cents + fee_in_cents
+    );
+    assert_eq!(display_ticket_pricing.fee_in_cents, 0);
+
+    // Absorbed fees are hidden from the buyer
+    let ticket_type = ticket_type
+        .update_fee_mode(Some(FeeModes::Absorbed), conn)
+        .unwrap();
+    let display_ticket_type =
+        UserDisplayTicketType::from_ticket_type(&ticket_type, &fee_schedule, false, None, conn)
+            .unwrap();
+    assert_eq!(display_ticket_type.fee_mode, FeeModes::Absorbed);
+    let display_ticket_pricing = display_ticket_type.ticket_pricing.unwrap();
+    assert_eq!(
+        display_ticket_pricing.price_in_cents,
+        ticket_pricing.price_in_cents
+    );
+    assert_eq!(display_ticket_pricing.fee_in_cents, 0);
+}
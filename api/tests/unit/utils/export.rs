@@ -0,0 +1,80 @@
+use bigneon_api::errors::BigNeonError;
+use bigneon_api::utils::csv::CsvWriter;
+use bigneon_api::utils::export::{escape_formula, ExportCell, ExportWriter, Pages};
+
+#[test]
+fn csv_writer() {
+    let mut writer = CsvWriter;
+    let mut output = writer.header(&["Ticket", "Quantity", "Price"]).unwrap();
+    output.append(
+        &mut writer
+            .rows(&[
+                vec![
+                    "General, \"GA\"".into(),
+                    ExportCell::Integer(2),
+                    ExportCell::Money(1050),
+                ],
+                vec![
+                    None::<String>.into(),
+                    ExportCell::Integer(1),
+                    ExportCell::Money(-5),
+                ],
+            ])
+            .unwrap(),
+    );
+    output.append(&mut writer.finish().unwrap());
+
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "Ticket,Quantity,Price\r\n\"General, \"\"GA\"\"\",2,10.50\r\n,1,-0.05\r\n"
+    );
+}
+
+#[test]
+fn csv_writer_escapes_formulas() {
+    let mut writer = CsvWriter;
+    let output = writer
+        .rows(&[vec![
+            "=HYPERLINK(\"http://example.com\")".into(),
+            "@SUM(A1:A2)".into(),
+            ExportCell::Money(-5),
+        ]])
+        .unwrap();
+
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "\"'=HYPERLINK(\"\"http://example.com\"\")\",'@SUM(A1:A2),-0.05\r\n"
+    );
+}
+
+#[test]
+fn escape_formula_prefixes_formula_characters() {
+    assert_eq!(escape_formula("=1+1"), "'=1+1");
+    assert_eq!(escape_formula("+1 555"), "'+1 555");
+    assert_eq!(escape_formula("-cmd"), "'-cmd");
+    assert_eq!(escape_formula("@user"), "'@user");
+    assert_eq!(escape_formula("\tvalue"), "'\tvalue");
+    assert_eq!(escape_formula("\rvalue"), "'\rvalue");
+    assert_eq!(escape_formula("-10.50"), "-10.50");
+    assert_eq!(escape_formula("Jane Doe"), "Jane Doe");
+    assert_eq!(escape_formula(""), "");
+}
+
+#[test]
+fn pages() {
+    let pages: Vec<Result<Vec<Vec<ExportCell>>, BigNeonError>> = Pages::new(|page| {
+        if page < 2 {
+            Ok(vec![vec![ExportCell::Integer(page as i64)]])
+        } else {
+            Ok(Vec::new())
+        }
+    })
+    .collect();
+
+    assert_eq!(pages.len(), 2);
+    let values: Vec<String> = pages
+        .into_iter()
+        .map(|page| page.unwrap()[0][0].to_string())
+        .collect();
+    assert_eq!(values, vec!["0", "1"]);
+}
@@ -0,0 +1,72 @@
+use bigneon_api::errors::BigNeonError;
+use bigneon_api::utils::identity_providers::{JsonWebKey, JsonWebKeySet, KeySetCache};
+use std::cell::Cell;
+use std::time::Duration;
+
+const URL: &str = "https://id.example.com/jwks";
+
+fn key_set(kids: &[&str]) -> JsonWebKeySet {
+    JsonWebKeySet {
+        keys: kids
+            .iter()
+            .map(|kid| JsonWebKey {
+                kid: Some(kid.to_string()),
+                kty: "RSA".to_string(),
+                alg: Some("RS256".to_string()),
+                n: Some("AQAB".to_string()),
+                e: Some("AQAB".to_string()),
+            })
+            .collect(),
+    }
+}
+
+fn find(
+    cache: &KeySetCache,
+    kid: &str,
+    published: &[&str],
+    fetches: &Cell<u32>,
+) -> Option<JsonWebKey> {
+    cache
+        .find(URL, Some(kid), |_| -> Result<JsonWebKeySet, BigNeonError> {
+            fetches.set(fetches.get() + 1);
+            Ok(key_set(published))
+        })
+        .unwrap()
+}
+
+#[test]
+fn key_set_cache_reuses_known_keys() {
+    let cache = KeySetCache::new(Duration::from_secs(3600), Duration::from_secs(60));
+    let fetches = Cell::new(0);
+
+    assert!(find(&cache, "key-1", &["key-1"], &fetches).is_some());
+    assert!(find(&cache, "key-1", &["key-1"], &fetches).is_some());
+    assert_eq!(fetches.get(), 1);
+
+    // A key that was just looked for is not fetched again right away
+    assert!(find(&cache, "key-2", &["key-1", "key-2"], &fetches).is_none());
+    assert_eq!(fetches.get(), 1);
+}
+
+#[test]
+fn key_set_cache_refetches_for_unknown_keys() {
+    let cache = KeySetCache::new(Duration::from_secs(3600), Duration::from_secs(0));
+    let fetches = Cell::new(0);
+
+    assert!(find(&cache, "key-1", &["key-1"], &fetches).is_some());
+    // The provider rotated its keys
+    assert!(find(&cache, "key-2", &["key-1", "key-2"], &fetches).is_some());
+    assert_eq!(fetches.get(), 2);
+    assert!(find(&cache, "key-2", &["key-1", "key-2"], &fetches).is_some());
+    assert_eq!(fetches.get(), 2);
+}
+
+#[test]
+fn key_set_cache_expires() {
+    let cache = KeySetCache::new(Duration::from_secs(0), Duration::from_secs(60));
+    let fetches = Cell::new(0);
+
+    assert!(find(&cache, "key-1", &["key-1"], &fetches).is_some());
+    assert!(find(&cache, "key-1", &["key-1"], &fetches).is_some());
+    assert_eq!(fetches.get(), 2);
+}
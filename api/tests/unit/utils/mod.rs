@@ -0,0 +1,5 @@
+pub mod export;
+pub mod jwks;
+pub mod money;
+pub mod pdf;
+pub mod xlsx;
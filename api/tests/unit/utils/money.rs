@@ -0,0 +1,10 @@
+use bigneon_api::utils::money::format_cents;
+
+#[test]
+fn format_cents_test() {
+    assert_eq!(format_cents(0), "0.00");
+    assert_eq!(format_cents(5), "0.05");
+    assert_eq!(format_cents(1050), "10.50");
+    assert_eq!(format_cents(-1050), "-10.50");
+    assert_eq!(format_cents(123456789), "1234567.89");
+}
@@ -0,0 +1,29 @@
+use bigneon_api::utils::pdf;
+
+#[test]
+fn document() {
+    let rows = vec![vec!["Ticket sales".to_string(), "10.50".to_string()]];
+    let content = String::from_utf8(pdf::document("Statement", &[0, 350], &rows)).unwrap();
+
+    assert!(content.starts_with("%PDF-1.4\n"));
+    assert!(content.contains("/Count 1 >>"));
+    assert!(content.contains("(Statement) Tj"));
+    assert!(content.contains("BT /F1 10 Tf 400 714 Td (10.50) Tj ET"));
+    assert!(content.ends_with("%%EOF\n"));
+}
+
+#[test]
+fn document_continues_rows_on_new_pages() {
+    let mut rows: Vec<Vec<String>> = (0..120)
+        .map(|i| vec![format!("Row {}", i), "1.00".to_string()])
+        .collect();
+    rows.push(vec!["Total".to_string(), "120.00".to_string()]);
+    let content = String::from_utf8(pdf::document("Statement", &[0, 350], &rows)).unwrap();
+
+    assert!(content.contains("/Kids [4 0 R 6 0 R 8 0 R] /Count 3 >>"));
+    assert_eq!(content.matches("/Type /Page /Parent").count(), 3);
+    assert!(content.contains("(Row 0) Tj"));
+    assert!(content.contains("(Row 119) Tj"));
+    assert!(content.contains("(Total) Tj"));
+    assert!(content.contains("(120.00) Tj"));
+}
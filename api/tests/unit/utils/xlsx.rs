@@ -0,0 +1,51 @@
+use bigneon_api::utils::export::{ExportCell, ExportWriter};
+use bigneon_api::utils::xlsx::XlsxWriter;
+use std::io::{Cursor, Read};
+use zip::ZipArchive;
+
+#[test]
+fn xlsx_writer() {
+    let mut writer = XlsxWriter::new();
+    let mut output = writer.header(&["Ticket", "Price"]).unwrap();
+    output.append(
+        &mut writer
+            .rows(&[vec!["VIP & Friends".into(), ExportCell::Money(2500)]])
+            .unwrap(),
+    );
+    output.append(
+        &mut writer
+            .rows(&[vec!["General".into(), ExportCell::Money(1050)]])
+            .unwrap(),
+    );
+    output.append(
+        &mut writer
+            .rows(&[vec!["=1+1".into(), ExportCell::Money(-500)]])
+            .unwrap(),
+    );
+    output.append(&mut writer.finish().unwrap());
+
+    let mut archive = ZipArchive::new(Cursor::new(output)).unwrap();
+    assert_eq!(archive.len(), 6);
+    for name in &[
+        "[Content_Types].xml",
+        "_rels/.rels",
+        "xl/workbook.xml",
+        "xl/_rels/workbook.xml.rels",
+        "xl/styles.xml",
+    ] {
+        assert!(archive.by_name(name).is_ok());
+    }
+
+    let mut content = String::new();
+    archive
+        .by_name("xl/worksheets/sheet1.xml")
+        .unwrap()
+        .read_to_string(&mut content)
+        .unwrap();
+    assert!(content.contains(r#"<t xml:space="preserve">VIP &amp; Friends</t>"#));
+    assert!(content.contains(r#"<c s="1"><v>25.00</v></c>"#));
+    assert!(content.contains(r#"<c s="1"><v>10.50</v></c>"#));
+    assert!(content.contains(r#"<t xml:space="preserve">'=1+1</t>"#));
+    assert!(content.contains(r#"<c s="1"><v>-5.00</v></c>"#));
+    assert!(content.ends_with("</sheetData></worksheet>"));
+}
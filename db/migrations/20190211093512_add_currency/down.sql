@@ -0,0 +1,17 @@
+DROP INDEX IF EXISTS index_exchange_rates_base_currency_target_currency_effective_at;
+DROP TABLE IF EXISTS exchange_rates;
+
+ALTER TABLE payments
+    DROP COLUMN currency;
+
+ALTER TABLE order_items
+    DROP COLUMN currency;
+
+ALTER TABLE orders
+    DROP COLUMN currency;
+
+ALTER TABLE events
+    DROP COLUMN currency;
+
+ALTER TABLE organizations
+    DROP COLUMN currency;
@@ -0,0 +1,35 @@
+ALTER TABLE organizations
+    ADD currency TEXT NOT NULL DEFAULT 'USD';
+
+ALTER TABLE events
+    ADD currency TEXT NOT NULL DEFAULT 'USD';
+
+ALTER TABLE orders
+    ADD currency TEXT NULL;
+
+UPDATE orders
+SET currency = 'USD'
+WHERE EXISTS(SELECT 1 FROM order_items oi WHERE oi.order_id = orders.id);
+
+ALTER TABLE order_items
+    ADD currency TEXT NOT NULL DEFAULT 'USD';
+
+ALTER TABLE payments
+    ADD currency TEXT NOT NULL DEFAULT 'USD';
+
+CREATE TABLE exchange_rates
+(
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    base_currency TEXT NOT NULL,
+    target_currency TEXT NOT NULL,
+    rate DOUBLE PRECISION NOT NULL CHECK (rate > 0),
+    effective_at TIMESTAMP NOT NULL DEFAULT now(),
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_exchange_rates_base_currency_target_currency_effective_at ON exchange_rates (
+    base_currency,
+    target_currency,
+    effective_at
+);
@@ -0,0 +1,11 @@
+DROP INDEX IF EXISTS index_order_items_tax_rule_id;
+
+ALTER TABLE order_items
+    DROP tax_in_cents;
+
+ALTER TABLE order_items
+    DROP tax_rule_id;
+
+DROP INDEX IF EXISTS index_tax_rules_venue_id;
+DROP INDEX IF EXISTS index_tax_rules_region_id;
+DROP TABLE IF EXISTS tax_rules;
@@ -0,0 +1,26 @@
+CREATE TABLE tax_rules
+(
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    name TEXT NOT NULL,
+    region_id UUID NULL REFERENCES regions (id),
+    venue_id UUID NULL REFERENCES venues (id),
+    rate_in_basis_points BIGINT NOT NULL CHECK (rate_in_basis_points >= 0),
+    inclusive BOOLEAN NOT NULL DEFAULT 'F',
+    applies_to_tickets BOOLEAN NOT NULL DEFAULT 'T',
+    applies_to_per_unit_fees BOOLEAN NOT NULL DEFAULT 'F',
+    applies_to_event_fees BOOLEAN NOT NULL DEFAULT 'F',
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP NOT NULL DEFAULT now(),
+    CONSTRAINT constraint_tax_rules_region_id_or_venue_id CHECK (region_id IS NOT NULL OR venue_id IS NOT NULL)
+);
+
+CREATE INDEX index_tax_rules_region_id ON tax_rules (region_id);
+CREATE INDEX index_tax_rules_venue_id ON tax_rules (venue_id);
+
+ALTER TABLE order_items
+    ADD tax_rule_id UUID NULL REFERENCES tax_rules (id);
+
+ALTER TABLE order_items
+    ADD tax_in_cents BIGINT NOT NULL DEFAULT 0;
+
+CREATE INDEX index_order_items_tax_rule_id ON order_items (tax_rule_id);
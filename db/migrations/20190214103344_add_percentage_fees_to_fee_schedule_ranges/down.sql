@@ -0,0 +1,14 @@
+ALTER TABLE fee_schedule_ranges
+    DROP CONSTRAINT constraint_fee_schedule_ranges_min_fee_less_than_max_fee;
+
+ALTER TABLE fee_schedule_ranges
+    DROP max_fee_in_cents;
+
+ALTER TABLE fee_schedule_ranges
+    DROP min_fee_in_cents;
+
+ALTER TABLE fee_schedule_ranges
+    DROP client_fee_in_basis_points;
+
+ALTER TABLE fee_schedule_ranges
+    DROP company_fee_in_basis_points;
@@ -0,0 +1,14 @@
+ALTER TABLE fee_schedule_ranges
+    ADD company_fee_in_basis_points BIGINT NOT NULL DEFAULT 0 CHECK (company_fee_in_basis_points >= 0);
+
+ALTER TABLE fee_schedule_ranges
+    ADD client_fee_in_basis_points BIGINT NOT NULL DEFAULT 0 CHECK (client_fee_in_basis_points >= 0);
+
+ALTER TABLE fee_schedule_ranges
+    ADD min_fee_in_cents BIGINT NULL CHECK (min_fee_in_cents >= 0);
+
+ALTER TABLE fee_schedule_ranges
+    ADD max_fee_in_cents BIGINT NULL CHECK (max_fee_in_cents >= 0);
+
+ALTER TABLE fee_schedule_ranges
+    ADD CONSTRAINT constraint_fee_schedule_ranges_min_fee_less_than_max_fee CHECK (min_fee_in_cents IS NULL OR max_fee_in_cents IS NULL OR min_fee_in_cents <= max_fee_in_cents);
@@ -0,0 +1,8 @@
+ALTER TABLE order_items
+    DROP fee_mode;
+
+ALTER TABLE ticket_types
+    DROP fee_mode;
+
+ALTER TABLE events
+    DROP fee_mode;
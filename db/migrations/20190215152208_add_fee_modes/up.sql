@@ -0,0 +1,8 @@
+ALTER TABLE events
+    ADD fee_mode TEXT NOT NULL DEFAULT 'Standard';
+
+ALTER TABLE ticket_types
+    ADD fee_mode TEXT NULL;
+
+ALTER TABLE order_items
+    ADD fee_mode TEXT NOT NULL DEFAULT 'Standard';
@@ -0,0 +1,6 @@
+DROP INDEX IF EXISTS index_settlement_adjustments_settlement_id;
+DROP TABLE IF EXISTS settlement_adjustments;
+
+DROP INDEX IF EXISTS index_settlements_event_id;
+DROP INDEX IF EXISTS index_settlements_organization_id;
+DROP TABLE IF EXISTS settlements;
@@ -0,0 +1,39 @@
+CREATE TABLE settlements
+(
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    organization_id UUID NOT NULL REFERENCES organizations (id),
+    event_id UUID NULL REFERENCES events (id),
+    start_time TIMESTAMP NULL,
+    end_time TIMESTAMP NULL,
+    status TEXT NOT NULL DEFAULT 'Draft',
+    currency TEXT NOT NULL,
+    ticket_sales_in_cents BIGINT NOT NULL DEFAULT 0,
+    client_fees_in_cents BIGINT NOT NULL DEFAULT 0,
+    company_fees_in_cents BIGINT NOT NULL DEFAULT 0,
+    absorbed_fees_in_cents BIGINT NOT NULL DEFAULT 0,
+    user_id UUID NOT NULL REFERENCES users (id),
+    approved_by_user_id UUID NULL REFERENCES users (id),
+    approved_at TIMESTAMP NULL,
+    paid_by_user_id UUID NULL REFERENCES users (id),
+    paid_at TIMESTAMP NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP NOT NULL DEFAULT now(),
+    CONSTRAINT constraint_settlements_start_time_before_end_time CHECK (start_time IS NULL OR end_time IS NULL OR start_time <= end_time)
+);
+
+CREATE INDEX index_settlements_organization_id ON settlements (organization_id);
+CREATE INDEX index_settlements_event_id ON settlements (event_id);
+
+CREATE TABLE settlement_adjustments
+(
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    settlement_id UUID NOT NULL REFERENCES settlements (id),
+    adjustment_type TEXT NOT NULL,
+    amount_in_cents BIGINT NOT NULL,
+    note TEXT NULL,
+    user_id UUID NOT NULL REFERENCES users (id),
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_settlement_adjustments_settlement_id ON settlement_adjustments (settlement_id);
@@ -0,0 +1,6 @@
+DROP INDEX IF EXISTS index_report_deliveries_report_subscription_id;
+DROP TABLE IF EXISTS report_deliveries;
+
+DROP INDEX IF EXISTS index_report_subscriptions_unsubscribe_token;
+DROP INDEX IF EXISTS index_report_subscriptions_organization_id_user_id;
+DROP TABLE IF EXISTS report_subscriptions;
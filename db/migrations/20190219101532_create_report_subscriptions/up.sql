@@ -0,0 +1,37 @@
+CREATE TABLE report_subscriptions
+(
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    organization_id UUID NOT NULL REFERENCES organizations (id),
+    user_id UUID NOT NULL REFERENCES users (id),
+    report_type TEXT NOT NULL,
+    event_id UUID NULL REFERENCES events (id),
+    frequency TEXT NOT NULL,
+    day_of_week INT NULL,
+    hour_of_day INT NOT NULL,
+    timezone TEXT NOT NULL,
+    format TEXT NOT NULL DEFAULT 'Csv',
+    unsubscribe_token UUID NOT NULL DEFAULT gen_random_uuid(),
+    next_delivery_at TIMESTAMP NOT NULL,
+    unsubscribed_at TIMESTAMP NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP NOT NULL DEFAULT now(),
+    CONSTRAINT constraint_report_subscriptions_day_of_week CHECK (day_of_week IS NULL OR day_of_week BETWEEN 0 AND 6),
+    CONSTRAINT constraint_report_subscriptions_hour_of_day CHECK (hour_of_day BETWEEN 0 AND 23)
+);
+
+CREATE INDEX index_report_subscriptions_organization_id_user_id ON report_subscriptions (organization_id, user_id);
+CREATE UNIQUE INDEX index_report_subscriptions_unsubscribe_token ON report_subscriptions (unsubscribe_token);
+
+CREATE TABLE report_deliveries
+(
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    report_subscription_id UUID NOT NULL REFERENCES report_subscriptions (id),
+    period_start TIMESTAMP NOT NULL,
+    period_end TIMESTAMP NOT NULL,
+    status TEXT NOT NULL,
+    error TEXT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_report_deliveries_report_subscription_id ON report_deliveries (report_subscription_id);
@@ -0,0 +1,10 @@
+DROP INDEX IF EXISTS index_orders_utm_source_utm_medium_utm_campaign;
+
+ALTER TABLE orders
+    DROP referrer,
+    DROP utm_source,
+    DROP utm_medium,
+    DROP utm_campaign,
+    DROP utm_term,
+    DROP utm_content,
+    DROP source_id;
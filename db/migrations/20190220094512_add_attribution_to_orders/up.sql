@@ -0,0 +1,10 @@
+ALTER TABLE orders
+    ADD referrer TEXT NULL,
+    ADD utm_source TEXT NULL,
+    ADD utm_medium TEXT NULL,
+    ADD utm_campaign TEXT NULL,
+    ADD utm_term TEXT NULL,
+    ADD utm_content TEXT NULL,
+    ADD source_id TEXT NULL;
+
+CREATE INDEX index_orders_utm_source_utm_medium_utm_campaign ON orders (utm_source, utm_medium, utm_campaign);
@@ -0,0 +1,8 @@
+DROP INDEX IF EXISTS index_orders_affiliate_click_id;
+
+ALTER TABLE orders
+    DROP affiliate_click_id;
+
+DROP TABLE IF EXISTS affiliate_clicks;
+DROP TABLE IF EXISTS affiliate_commission_rules;
+DROP TABLE IF EXISTS affiliates;
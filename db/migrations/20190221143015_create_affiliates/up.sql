@@ -0,0 +1,47 @@
+CREATE TABLE affiliates
+(
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    organization_id UUID NOT NULL REFERENCES organizations (id),
+    user_id UUID NULL REFERENCES users (id),
+    name TEXT NOT NULL,
+    code TEXT NOT NULL,
+    attribution_window_in_days BIGINT NOT NULL DEFAULT 30,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP NOT NULL DEFAULT now(),
+    CONSTRAINT constraint_affiliates_attribution_window_positive CHECK (attribution_window_in_days > 0)
+);
+
+CREATE INDEX index_affiliates_organization_id ON affiliates (organization_id);
+CREATE INDEX index_affiliates_user_id ON affiliates (user_id);
+CREATE UNIQUE INDEX index_affiliates_code ON affiliates (UPPER(code));
+
+CREATE TABLE affiliate_commission_rules
+(
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    affiliate_id UUID NOT NULL REFERENCES affiliates (id) ON DELETE CASCADE,
+    ticket_type_id UUID NULL REFERENCES ticket_types (id),
+    commission_type TEXT NOT NULL,
+    flat_in_cents BIGINT NOT NULL DEFAULT 0,
+    rate_in_basis_points BIGINT NOT NULL DEFAULT 0,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+-- One rule per ticket type, plus a default rule for ticket types without their own rule
+CREATE UNIQUE INDEX index_affiliate_commission_rules_affiliate_id_ticket_type_id ON affiliate_commission_rules (affiliate_id, ticket_type_id);
+CREATE UNIQUE INDEX index_affiliate_commission_rules_affiliate_id_default ON affiliate_commission_rules (affiliate_id) WHERE ticket_type_id IS NULL;
+
+CREATE TABLE affiliate_clicks
+(
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    affiliate_id UUID NOT NULL REFERENCES affiliates (id) ON DELETE CASCADE,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_affiliate_clicks_affiliate_id ON affiliate_clicks (affiliate_id);
+
+ALTER TABLE orders
+    ADD affiliate_click_id UUID NULL REFERENCES affiliate_clicks (id);
+
+CREATE INDEX index_orders_affiliate_click_id ON orders (affiliate_click_id);
@@ -0,0 +1,11 @@
+DROP INDEX IF EXISTS index_codes_fan_segment_id;
+
+ALTER TABLE codes
+    DROP fan_segment_id;
+
+DROP TABLE IF EXISTS fan_segments;
+DROP TABLE IF EXISTS fan_notes;
+DROP TABLE IF EXISTS fan_tags;
+
+ALTER TABLE events
+    DROP genres;
@@ -0,0 +1,46 @@
+ALTER TABLE events
+    ADD genres TEXT[] NOT NULL DEFAULT '{}';
+
+CREATE TABLE fan_tags
+(
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    organization_id UUID NOT NULL REFERENCES organizations (id),
+    user_id UUID NOT NULL REFERENCES users (id),
+    tag TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_fan_tags_organization_id_user_id_tag ON fan_tags (organization_id, user_id, tag);
+CREATE INDEX index_fan_tags_organization_id_tag ON fan_tags (organization_id, tag);
+
+CREATE TABLE fan_notes
+(
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    organization_id UUID NOT NULL REFERENCES organizations (id),
+    user_id UUID NOT NULL REFERENCES users (id),
+    author_id UUID NOT NULL REFERENCES users (id),
+    note TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_fan_notes_organization_id_user_id ON fan_notes (organization_id, user_id);
+
+CREATE TABLE fan_segments
+(
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    organization_id UUID NOT NULL REFERENCES organizations (id),
+    name TEXT NOT NULL,
+    criteria JSONB NOT NULL,
+    created_by_user_id UUID NOT NULL REFERENCES users (id),
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_fan_segments_organization_id ON fan_segments (organization_id);
+
+ALTER TABLE codes
+    ADD fan_segment_id UUID NULL REFERENCES fan_segments (id);
+
+CREATE INDEX index_codes_fan_segment_id ON codes (fan_segment_id);
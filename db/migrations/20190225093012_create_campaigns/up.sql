@@ -0,0 +1,54 @@
+CREATE TABLE campaigns
+(
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    organization_id UUID NOT NULL REFERENCES organizations (id),
+    name TEXT NOT NULL,
+    channel TEXT NOT NULL,
+    audience TEXT NOT NULL,
+    event_id UUID NULL REFERENCES events (id),
+    ticket_type_id UUID NULL REFERENCES ticket_types (id),
+    fan_segment_id UUID NULL REFERENCES fan_segments (id),
+    subject TEXT NULL,
+    body TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'Draft',
+    batch_size INT NOT NULL DEFAULT 100,
+    batch_interval_in_seconds INT NOT NULL DEFAULT 60,
+    created_by_user_id UUID NOT NULL REFERENCES users (id),
+    sent_at TIMESTAMP NULL,
+    completed_at TIMESTAMP NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_campaigns_organization_id ON campaigns (organization_id);
+
+CREATE TABLE campaign_recipients
+(
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    campaign_id UUID NOT NULL REFERENCES campaigns (id),
+    user_id UUID NOT NULL REFERENCES users (id),
+    address TEXT NULL,
+    status TEXT NOT NULL,
+    status_reason TEXT NULL,
+    domain_action_id UUID NULL REFERENCES domain_actions (id),
+    unsubscribe_token UUID NOT NULL DEFAULT gen_random_uuid(),
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_campaign_recipients_campaign_id_user_id ON campaign_recipients (campaign_id, user_id);
+CREATE UNIQUE INDEX index_campaign_recipients_unsubscribe_token ON campaign_recipients (unsubscribe_token);
+CREATE INDEX index_campaign_recipients_domain_action_id ON campaign_recipients (domain_action_id);
+
+CREATE TABLE campaign_opt_outs
+(
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    organization_id UUID NOT NULL REFERENCES organizations (id),
+    user_id UUID NOT NULL REFERENCES users (id),
+    channel TEXT NOT NULL,
+    campaign_id UUID NULL REFERENCES campaigns (id),
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_campaign_opt_outs_organization_id_user_id_channel ON campaign_opt_outs (organization_id, user_id, channel);
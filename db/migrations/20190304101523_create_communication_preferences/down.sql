@@ -0,0 +1,22 @@
+CREATE TABLE campaign_opt_outs
+(
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    organization_id UUID NOT NULL REFERENCES organizations (id),
+    user_id UUID NOT NULL REFERENCES users (id),
+    channel TEXT NOT NULL,
+    campaign_id UUID NULL REFERENCES campaigns (id),
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_campaign_opt_outs_organization_id_user_id_channel ON campaign_opt_outs (organization_id, user_id, channel);
+
+INSERT INTO campaign_opt_outs (organization_id, user_id, channel, created_at, updated_at)
+SELECT organization_id, user_id, channel, created_at, updated_at
+FROM communication_preferences
+WHERE organization_id IS NOT NULL
+  AND category = 'Marketing'
+  AND subscribed = false;
+
+DROP TABLE IF EXISTS communication_consents;
+DROP TABLE IF EXISTS communication_preferences;
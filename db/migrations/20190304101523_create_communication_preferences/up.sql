@@ -0,0 +1,42 @@
+CREATE TABLE communication_preferences
+(
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    user_id UUID NOT NULL REFERENCES users (id),
+    organization_id UUID NULL REFERENCES organizations (id),
+    category TEXT NOT NULL,
+    channel TEXT NOT NULL,
+    subscribed BOOLEAN NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+-- Preferences without an organization apply to every organization
+CREATE UNIQUE INDEX index_communication_preferences_user_id_organization_id_category_channel ON communication_preferences (user_id, COALESCE(organization_id, '00000000-0000-0000-0000-000000000000'), category, channel);
+CREATE INDEX index_communication_preferences_organization_id ON communication_preferences (organization_id);
+
+CREATE TABLE communication_consents
+(
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    user_id UUID NOT NULL REFERENCES users (id),
+    organization_id UUID NULL REFERENCES organizations (id),
+    category TEXT NOT NULL,
+    channel TEXT NOT NULL,
+    subscribed BOOLEAN NOT NULL,
+    source TEXT NOT NULL,
+    ip_address TEXT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_communication_consents_user_id ON communication_consents (user_id);
+
+-- Campaign opt outs become organization marketing preferences
+INSERT INTO communication_preferences (user_id, organization_id, category, channel, subscribed, created_at, updated_at)
+SELECT user_id, organization_id, 'Marketing', channel, false, created_at, updated_at
+FROM campaign_opt_outs;
+
+INSERT INTO communication_consents (user_id, organization_id, category, channel, subscribed, source, created_at, updated_at)
+SELECT user_id, organization_id, 'Marketing', channel, false, 'Campaign unsubscribe', created_at, updated_at
+FROM campaign_opt_outs;
+
+DROP TABLE campaign_opt_outs;
@@ -0,0 +1,19 @@
+CREATE TABLE audit_logs
+(
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    organization_id UUID NULL REFERENCES organizations (id),
+    event_id UUID NULL REFERENCES events (id),
+    user_id UUID NULL REFERENCES users (id),
+    scope TEXT NULL,
+    action TEXT NOT NULL,
+    main_table TEXT NOT NULL,
+    main_id UUID NOT NULL,
+    changes JSON NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_audit_logs_organization_id_created_at ON audit_logs (organization_id, created_at);
+CREATE INDEX index_audit_logs_event_id ON audit_logs (event_id);
+CREATE INDEX index_audit_logs_main_table_main_id ON audit_logs (main_table, main_id);
+CREATE INDEX index_audit_logs_user_id ON audit_logs (user_id);
@@ -0,0 +1,21 @@
+CREATE TABLE organization_api_keys
+(
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    organization_id UUID NOT NULL REFERENCES organizations (id),
+    user_id UUID NOT NULL REFERENCES users (id),
+    name TEXT NOT NULL,
+    key_prefix TEXT NOT NULL,
+    key_hash TEXT NOT NULL,
+    scopes TEXT[] NOT NULL,
+    event_ids UUID[] NOT NULL DEFAULT '{}',
+    expires_at TIMESTAMP NULL,
+    last_used_at TIMESTAMP NULL,
+    revoked_at TIMESTAMP NULL,
+    created_by_user_id UUID NOT NULL REFERENCES users (id),
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_organization_api_keys_key_hash ON organization_api_keys (key_hash);
+CREATE UNIQUE INDEX index_organization_api_keys_user_id ON organization_api_keys (user_id);
+CREATE INDEX index_organization_api_keys_organization_id ON organization_api_keys (organization_id);
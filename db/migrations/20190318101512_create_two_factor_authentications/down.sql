@@ -0,0 +1,5 @@
+ALTER TABLE organizations
+    DROP COLUMN require_two_factor;
+
+DROP INDEX IF EXISTS index_two_factor_authentications_user_id;
+DROP TABLE IF EXISTS two_factor_authentications;
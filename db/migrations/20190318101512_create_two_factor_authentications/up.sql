@@ -0,0 +1,16 @@
+CREATE TABLE two_factor_authentications
+(
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    user_id UUID NOT NULL REFERENCES users (id),
+    secret TEXT NOT NULL,
+    recovery_code_hashes TEXT[] NOT NULL DEFAULT '{}',
+    last_used_step BIGINT NULL,
+    enabled_at TIMESTAMP NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_two_factor_authentications_user_id ON two_factor_authentications (user_id);
+
+ALTER TABLE organizations
+    ADD require_two_factor BOOLEAN NOT NULL DEFAULT false;
@@ -0,0 +1,6 @@
+ALTER TABLE users
+    DROP COLUMN locked_until,
+    DROP COLUMN failed_login_attempts;
+
+DROP INDEX IF EXISTS index_rate_limit_counters_key;
+DROP TABLE IF EXISTS rate_limit_counters;
@@ -0,0 +1,15 @@
+CREATE TABLE rate_limit_counters
+(
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    key TEXT NOT NULL,
+    hits INTEGER NOT NULL DEFAULT 1,
+    window_ends_at TIMESTAMP NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_rate_limit_counters_key ON rate_limit_counters (key);
+
+ALTER TABLE users
+    ADD failed_login_attempts INTEGER NOT NULL DEFAULT 0,
+    ADD locked_until TIMESTAMP NULL;
@@ -0,0 +1,9 @@
+DROP INDEX IF EXISTS index_phone_verifications_user_id;
+DROP TABLE IF EXISTS phone_verifications;
+
+ALTER TABLE organizations
+    DROP COLUMN require_verified_contact_details;
+
+ALTER TABLE users
+    DROP COLUMN phone_verified_at,
+    DROP COLUMN email_verified_at;
@@ -0,0 +1,20 @@
+ALTER TABLE users
+    ADD email_verified_at TIMESTAMP NULL,
+    ADD phone_verified_at TIMESTAMP NULL;
+
+ALTER TABLE organizations
+    ADD require_verified_contact_details BOOLEAN NOT NULL DEFAULT false;
+
+CREATE TABLE phone_verifications
+(
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    user_id UUID NOT NULL REFERENCES users (id),
+    phone TEXT NOT NULL,
+    code_hash TEXT NOT NULL,
+    failed_attempts INTEGER NOT NULL DEFAULT 0,
+    expires_at TIMESTAMP NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_phone_verifications_user_id ON phone_verifications (user_id);
@@ -0,0 +1,7 @@
+DROP INDEX IF EXISTS index_users_login_token;
+
+ALTER TABLE users
+    DROP COLUMN login_code_failed_attempts,
+    DROP COLUMN login_token_requested_at,
+    DROP COLUMN login_code_hash,
+    DROP COLUMN login_token;
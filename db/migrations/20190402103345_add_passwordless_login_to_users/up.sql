@@ -0,0 +1,7 @@
+ALTER TABLE users
+    ADD login_token UUID NULL,
+    ADD login_code_hash TEXT NULL,
+    ADD login_token_requested_at TIMESTAMP NULL,
+    ADD login_code_failed_attempts INTEGER NOT NULL DEFAULT 0;
+
+CREATE UNIQUE INDEX index_users_login_token ON users (login_token);
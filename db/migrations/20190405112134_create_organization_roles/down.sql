@@ -0,0 +1,7 @@
+ALTER TABLE organization_invites
+    DROP COLUMN organization_role_ids;
+ALTER TABLE organization_users
+    DROP COLUMN organization_role_ids;
+
+DROP INDEX IF EXISTS index_organization_roles_organization_id_name;
+DROP TABLE IF EXISTS organization_roles;
@@ -0,0 +1,16 @@
+CREATE TABLE organization_roles
+(
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    organization_id UUID NOT NULL REFERENCES organizations (id),
+    name TEXT NOT NULL,
+    scopes TEXT[] NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_organization_roles_organization_id_name ON organization_roles (organization_id, name);
+
+ALTER TABLE organization_users
+    ADD organization_role_ids UUID[] NOT NULL DEFAULT '{}';
+ALTER TABLE organization_invites
+    ADD organization_role_ids UUID[] NOT NULL DEFAULT '{}';
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS index_event_users_user_id;
+DROP INDEX IF EXISTS index_event_users_event_id_user_id;
+DROP TABLE IF EXISTS event_users;
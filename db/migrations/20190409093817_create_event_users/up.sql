@@ -0,0 +1,12 @@
+CREATE TABLE event_users
+(
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    event_id UUID NOT NULL REFERENCES events (id),
+    user_id UUID NOT NULL REFERENCES users (id),
+    role TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_event_users_event_id_user_id ON event_users (event_id, user_id);
+CREATE INDEX index_event_users_user_id ON event_users (user_id);
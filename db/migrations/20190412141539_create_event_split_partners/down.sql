@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS index_settlement_partner_shares_settlement_id;
+DROP TABLE IF EXISTS settlement_partner_shares;
+DROP INDEX IF EXISTS index_event_split_partners_organization_id;
+DROP INDEX IF EXISTS index_event_split_partners_event_id_organization_id;
+DROP TABLE IF EXISTS event_split_partners;
@@ -0,0 +1,31 @@
+CREATE TABLE event_split_partners
+(
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    event_id UUID NOT NULL REFERENCES events (id),
+    organization_id UUID NOT NULL REFERENCES organizations (id),
+    split_type TEXT NOT NULL,
+    ticket_sales_flat_in_cents BIGINT NOT NULL DEFAULT 0,
+    ticket_sales_rate_in_basis_points BIGINT NOT NULL DEFAULT 0,
+    fees_flat_in_cents BIGINT NOT NULL DEFAULT 0,
+    fees_rate_in_basis_points BIGINT NOT NULL DEFAULT 0,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX index_event_split_partners_event_id_organization_id ON event_split_partners (event_id, organization_id);
+CREATE INDEX index_event_split_partners_organization_id ON event_split_partners (organization_id);
+
+-- Partner shares are fixed when the settlement is generated
+CREATE TABLE settlement_partner_shares
+(
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+    settlement_id UUID NOT NULL REFERENCES settlements (id) ON DELETE CASCADE,
+    event_id UUID NOT NULL REFERENCES events (id),
+    organization_id UUID NOT NULL REFERENCES organizations (id),
+    ticket_sales_in_cents BIGINT NOT NULL,
+    fees_in_cents BIGINT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT now(),
+    updated_at TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX index_settlement_partner_shares_settlement_id ON settlement_partner_shares (settlement_id);
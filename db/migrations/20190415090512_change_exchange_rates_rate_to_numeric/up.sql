@@ -0,0 +1,2 @@
+ALTER TABLE exchange_rates
+    ALTER COLUMN rate TYPE NUMERIC;
@@ -0,0 +1,2 @@
+ALTER TABLE tax_rules
+    DROP COLUMN deleted_at;
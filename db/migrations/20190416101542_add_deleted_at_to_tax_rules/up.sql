@@ -0,0 +1,2 @@
+ALTER TABLE tax_rules
+    ADD deleted_at TIMESTAMP NULL;
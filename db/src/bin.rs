@@ -15,6 +15,8 @@ extern crate bigneon_db;
 extern crate chrono;
 extern crate clap;
 extern crate diesel;
+extern crate serde_json;
+extern crate uuid;
 
 #[allow(unused_imports)]
 embed_migrations!("./migrations");
@@ -30,6 +32,7 @@ use std::error::Error;
 use std::fs::{create_dir_all, File};
 use std::io::Write;
 use std::path::Path;
+use uuid::Uuid;
 
 pub fn main() {
     let matches = App::new("Big Neon DB CLI")
@@ -105,6 +108,34 @@ pub fn main() {
                      .takes_value(true)
                      .help("Connection string to the database")
             )
+    ).subcommand(
+        SubCommand::with_name("export-user")
+            .about("Exports everything held about a user as JSON, for data subject access requests")
+            .arg(Arg::with_name("connection")
+                     .short("c")
+                     .takes_value(true)
+                     .help("Connection string to the database")
+            ).arg(Arg::with_name("user")
+                     .short("u")
+                     .takes_value(true)
+                     .help("Id of the user")
+            ).arg(Arg::with_name("output")
+                     .short("o")
+                     .takes_value(true)
+                     .help("File to write the export to, defaults to stdout")
+            )
+    ).subcommand(
+        SubCommand::with_name("erase-user")
+            .about("Anonymizes a user and removes their personal data, financial records are kept. WARNING! This is NOT REVERSIBLE")
+            .arg(Arg::with_name("connection")
+                     .short("c")
+                     .takes_value(true)
+                     .help("Connection string to the database")
+            ).arg(Arg::with_name("user")
+                     .short("u")
+                     .takes_value(true)
+                     .help("Id of the user")
+            )
     ).get_matches();
 
     match matches.subcommand() {
@@ -114,6 +145,8 @@ pub fn main() {
         ("rollback", Some(matches)) => rollback_db(matches),
         ("new-migration", Some(matches)) => create_new_migration(matches),
         ("seed", Some(matches)) => seed_db(matches),
+        ("export-user", Some(matches)) => export_user(matches),
+        ("erase-user", Some(matches)) => erase_user(matches),
         _ => {
             eprintln!("Invalid subcommand '{}'", matches.subcommand().0);
         }
@@ -239,6 +272,54 @@ fn seed_db(matches: &ArgMatches) {
         .expect("Seeding database failed");
 }
 
+fn find_user(matches: &ArgMatches, connection: &PgConnection) -> User {
+    let user_id = matches.value_of("user").expect("User id was not provided");
+    let user_id = Uuid::parse_str(user_id).expect("User id is not a valid id");
+    User::find(user_id, connection).expect("User not found")
+}
+
+fn export_user(matches: &ArgMatches) {
+    let conn_string = matches
+        .value_of("connection")
+        .expect("Connection string was not provided");
+    let db_connection = get_connection(conn_string);
+    let user = find_user(matches, &db_connection);
+
+    let data = user
+        .export_data(&db_connection)
+        .expect("Could not export user data");
+    let data = serde_json::to_string_pretty(&data).expect("Could not serialize user data");
+    match matches.value_of("output") {
+        Some(output) => {
+            let mut file = File::create(output).expect("Error creating export file");
+            file.write_all(data.as_bytes())
+                .expect("Error writing export file");
+            println!("Exported user {} to {}", user.id, output);
+        }
+        None => println!("{}", data),
+    }
+}
+
+fn erase_user(matches: &ArgMatches) {
+    let conn_string = matches
+        .value_of("connection")
+        .expect("Connection string was not provided");
+    let db_connection = get_connection(conn_string);
+    let user = find_user(matches, &db_connection);
+    let user_id = user.id;
+
+    println!("Erasing user {}", user_id);
+    db_connection
+        .transaction(|| {
+            user.erase(None, &db_connection).map_err(|e| {
+                eprintln!("Could not erase user: {}", e);
+                diesel::result::Error::RollbackTransaction
+            })
+        })
+        .expect("Erasure failed, no changes were made");
+    println!("Erased user {}", user_id);
+}
+
 fn get_connection(connection_string: &str) -> PgConnection {
     PgConnection::establish(&connection_string).expect("Error connecting to DB")
 }
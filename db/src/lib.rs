@@ -16,6 +16,7 @@ extern crate diesel_migrations;
 
 extern crate argon2rs;
 extern crate backtrace;
+extern crate bigdecimal;
 extern crate chrono;
 extern crate chrono_tz;
 extern crate hex;
@@ -0,0 +1,47 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::prelude::*;
+use models::*;
+use schema::affiliate_clicks;
+use utils::errors::*;
+use uuid::Uuid;
+
+#[derive(
+    Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize,
+)]
+#[belongs_to(Affiliate)]
+#[table_name = "affiliate_clicks"]
+pub struct AffiliateClick {
+    pub id: Uuid,
+    pub affiliate_id: Uuid,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "affiliate_clicks"]
+pub struct NewAffiliateClick {
+    pub affiliate_id: Uuid,
+}
+
+impl NewAffiliateClick {
+    pub fn commit(self, conn: &PgConnection) -> Result<AffiliateClick, DatabaseError> {
+        diesel::insert_into(affiliate_clicks::table)
+            .values(&self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not record affiliate click")
+    }
+}
+
+impl AffiliateClick {
+    pub fn create(affiliate_id: Uuid) -> NewAffiliateClick {
+        NewAffiliateClick { affiliate_id }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<AffiliateClick, DatabaseError> {
+        affiliate_clicks::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find affiliate click")
+    }
+}
@@ -0,0 +1,172 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::prelude::*;
+use models::*;
+use schema::{affiliate_commission_rules, events, ticket_types};
+use utils::errors::*;
+use uuid::Uuid;
+use validators;
+use validators::*;
+
+#[derive(
+    Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize,
+)]
+#[belongs_to(Affiliate)]
+#[table_name = "affiliate_commission_rules"]
+pub struct AffiliateCommissionRule {
+    pub id: Uuid,
+    pub affiliate_id: Uuid,
+    pub ticket_type_id: Option<Uuid>,
+    pub commission_type: CommissionTypes,
+    pub flat_in_cents: i64,
+    pub rate_in_basis_points: i64,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+/// The commission paid to an affiliate per ticket sold. A rule without a ticket type applies
+/// to every ticket type that does not have its own rule. Flat commissions are paid per ticket,
+/// percentage commissions are a share of the ticket price in basis points.
+#[derive(Clone, Debug, Deserialize, Insertable, Serialize)]
+#[table_name = "affiliate_commission_rules"]
+pub struct NewAffiliateCommissionRule {
+    pub affiliate_id: Uuid,
+    pub ticket_type_id: Option<Uuid>,
+    pub commission_type: CommissionTypes,
+    #[serde(default)]
+    pub flat_in_cents: i64,
+    #[serde(default)]
+    pub rate_in_basis_points: i64,
+}
+
+impl NewAffiliateCommissionRule {
+    pub fn commit(self, conn: &PgConnection) -> Result<AffiliateCommissionRule, DatabaseError> {
+        self.validate_record(conn)?;
+
+        diesel::insert_into(affiliate_commission_rules::table)
+            .values(&self)
+            .get_result(conn)
+            .to_db_error(
+                ErrorCode::InsertError,
+                "Could not create affiliate commission rule",
+            )
+    }
+
+    fn validate_record(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        let mut validation_errors = Ok(());
+        match self.commission_type {
+            CommissionTypes::Flat => {
+                if self.flat_in_cents <= 0 || self.rate_in_basis_points != 0 {
+                    validation_errors = validators::append_validation_error(
+                        validation_errors,
+                        "flat_in_cents",
+                        Err(create_validation_error(
+                            "flat_commission_invalid",
+                            "Flat commissions require a positive amount and no rate",
+                        )),
+                    );
+                }
+            }
+            CommissionTypes::Percentage => {
+                if self.rate_in_basis_points <= 0
+                    || self.rate_in_basis_points > 10_000
+                    || self.flat_in_cents != 0
+                {
+                    validation_errors = validators::append_validation_error(
+                        validation_errors,
+                        "rate_in_basis_points",
+                        Err(create_validation_error(
+                            "percentage_commission_invalid",
+                            "Percentage commissions require a rate between 1 and 10000 basis points and no flat amount",
+                        )),
+                    );
+                }
+            }
+        }
+
+        if let Some(ticket_type_id) = self.ticket_type_id {
+            let affiliate = Affiliate::find(self.affiliate_id, conn)?;
+            let organization_id: Uuid = ticket_types::table
+                .inner_join(events::table)
+                .filter(ticket_types::id.eq(ticket_type_id))
+                .select(events::organization_id)
+                .first(conn)
+                .to_db_error(ErrorCode::QueryError, "Could not find ticket type")?;
+            if organization_id != affiliate.organization_id {
+                validation_errors = validators::append_validation_error(
+                    validation_errors,
+                    "ticket_type_id",
+                    Err(create_validation_error(
+                        "ticket_type_not_in_organization",
+                        "Ticket type does not belong to this organization",
+                    )),
+                );
+            }
+        }
+
+        let existing = AffiliateCommissionRule::find_for_affiliate(self.affiliate_id, conn)?;
+        if existing
+            .iter()
+            .any(|r| r.ticket_type_id == self.ticket_type_id)
+        {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "ticket_type_id",
+                Err(create_validation_error(
+                    "commission_rule_exists",
+                    "A commission rule already exists for this ticket type",
+                )),
+            );
+        }
+        Ok(validation_errors?)
+    }
+}
+
+impl AffiliateCommissionRule {
+    pub fn create(
+        affiliate_id: Uuid,
+        ticket_type_id: Option<Uuid>,
+        commission_type: CommissionTypes,
+        flat_in_cents: i64,
+        rate_in_basis_points: i64,
+    ) -> NewAffiliateCommissionRule {
+        NewAffiliateCommissionRule {
+            affiliate_id,
+            ticket_type_id,
+            commission_type,
+            flat_in_cents,
+            rate_in_basis_points,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<AffiliateCommissionRule, DatabaseError> {
+        affiliate_commission_rules::table
+            .find(id)
+            .first(conn)
+            .to_db_error(
+                ErrorCode::QueryError,
+                "Could not find affiliate commission rule",
+            )
+    }
+
+    pub fn find_for_affiliate(
+        affiliate_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<AffiliateCommissionRule>, DatabaseError> {
+        affiliate_commission_rules::table
+            .filter(affiliate_commission_rules::affiliate_id.eq(affiliate_id))
+            .order_by(affiliate_commission_rules::created_at)
+            .load(conn)
+            .to_db_error(
+                ErrorCode::QueryError,
+                "Could not load affiliate commission rules",
+            )
+    }
+
+    pub fn destroy(self, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        diesel::delete(&self).execute(conn).to_db_error(
+            ErrorCode::DeleteError,
+            "Could not delete affiliate commission rule",
+        )
+    }
+}
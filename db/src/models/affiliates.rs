@@ -0,0 +1,193 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::affiliates;
+use utils::errors::*;
+use utils::rand::random_alpha_string;
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+use validators;
+use validators::*;
+
+const CODE_LENGTH: usize = 8;
+
+#[derive(
+    Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize,
+)]
+#[belongs_to(Organization)]
+#[table_name = "affiliates"]
+pub struct Affiliate {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub name: String,
+    pub code: String,
+    pub attribution_window_in_days: i64,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+/// A promoter paid a commission on the tickets sold through their tracking link. The user, if
+/// set, can view the affiliate's own sales.
+#[derive(Clone, Debug, Deserialize, Insertable, Serialize, Validate)]
+#[table_name = "affiliates"]
+pub struct NewAffiliate {
+    pub organization_id: Uuid,
+    pub user_id: Option<Uuid>,
+    #[validate(length(min = "1", message = "Name is required"))]
+    pub name: String,
+    pub code: String,
+    pub attribution_window_in_days: i64,
+}
+
+#[derive(AsChangeset, Default, Deserialize, Validate)]
+#[table_name = "affiliates"]
+pub struct AffiliateEditableAttributes {
+    #[validate(length(min = "1", message = "Name is required"))]
+    pub name: Option<String>,
+    #[serde(default, deserialize_with = "double_option_deserialize_unless_blank")]
+    pub user_id: Option<Option<Uuid>>,
+    pub attribution_window_in_days: Option<i64>,
+}
+
+impl NewAffiliate {
+    pub fn commit(mut self, conn: &PgConnection) -> Result<Affiliate, DatabaseError> {
+        self.code = self.code.trim().to_uppercase();
+        self.validate_record(conn)?;
+
+        diesel::insert_into(affiliates::table)
+            .values(&self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create affiliate")
+    }
+
+    fn validate_record(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        let mut validation_errors = validators::append_validation_error(
+            self.validate(),
+            "attribution_window_in_days",
+            attribution_window_valid(self.attribution_window_in_days),
+        );
+        if self.code.is_empty()
+            || !self
+                .code
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-')
+        {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "code",
+                Err(create_validation_error(
+                    "invalid_code",
+                    "Code may only contain letters, numbers and dashes",
+                )),
+            );
+        } else if Affiliate::find_by_code(&self.code, conn)
+            .optional()?
+            .is_some()
+        {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "code",
+                Err(create_validation_error(
+                    "code_taken",
+                    "Code is already in use",
+                )),
+            );
+        }
+        Ok(validation_errors?)
+    }
+}
+
+impl Affiliate {
+    /// A code is generated if one is not provided
+    pub fn create(
+        organization_id: Uuid,
+        name: &str,
+        code: Option<String>,
+        user_id: Option<Uuid>,
+    ) -> NewAffiliate {
+        NewAffiliate {
+            organization_id,
+            user_id,
+            name: name.to_string(),
+            code: code.unwrap_or_else(|| random_alpha_string(CODE_LENGTH)),
+            attribution_window_in_days: 30,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<Affiliate, DatabaseError> {
+        affiliates::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find affiliate")
+    }
+
+    /// Codes are not case sensitive
+    pub fn find_by_code(code: &str, conn: &PgConnection) -> Result<Affiliate, DatabaseError> {
+        affiliates::table
+            .filter(affiliates::code.eq(code.trim().to_uppercase()))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find affiliate")
+    }
+
+    pub fn find_for_organization(
+        organization_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<Affiliate>, DatabaseError> {
+        affiliates::table
+            .filter(affiliates::organization_id.eq(organization_id))
+            .order_by(affiliates::name)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load affiliates")
+    }
+
+    pub fn update(
+        &self,
+        attributes: AffiliateEditableAttributes,
+        conn: &PgConnection,
+    ) -> Result<Affiliate, DatabaseError> {
+        let mut validation_errors = attributes.validate();
+        if let Some(attribution_window_in_days) = attributes.attribution_window_in_days {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "attribution_window_in_days",
+                attribution_window_valid(attribution_window_in_days),
+            );
+        }
+        validation_errors?;
+
+        diesel::update(self)
+            .set((attributes, affiliates::updated_at.eq(dsl::now)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update affiliate")
+    }
+
+    pub fn organization(&self, conn: &PgConnection) -> Result<Organization, DatabaseError> {
+        Organization::find(self.organization_id, conn)
+    }
+
+    pub fn commission_rules(
+        &self,
+        conn: &PgConnection,
+    ) -> Result<Vec<AffiliateCommissionRule>, DatabaseError> {
+        AffiliateCommissionRule::find_for_affiliate(self.id, conn)
+    }
+
+    /// Records a visit to the affiliate's tracking link. The click id is stored on the cart
+    /// created by the visitor.
+    pub fn record_click(&self, conn: &PgConnection) -> Result<AffiliateClick, DatabaseError> {
+        AffiliateClick::create(self.id).commit(conn)
+    }
+}
+
+fn attribution_window_valid(attribution_window_in_days: i64) -> Result<(), ValidationError> {
+    if attribution_window_in_days <= 0 {
+        return Err(create_validation_error(
+            "attribution_window_must_be_positive",
+            "Attribution window must be at least one day",
+        ));
+    }
+    Ok(())
+}
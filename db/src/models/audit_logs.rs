@@ -0,0 +1,267 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use diesel::sql_types::Text;
+use models::*;
+use schema::audit_logs;
+use serde::Serialize;
+use serde_json::{self, Map, Value};
+use utils::errors::*;
+use utils::text::escape_regex;
+use uuid::Uuid;
+
+/// Fields that change on every update and would only add noise to the diff
+const IGNORED_FIELDS: &[&str] = &["created_at", "updated_at"];
+/// Fields whose values must not be copied into the log, only the fact that they changed
+const REDACTED_FIELDS: &[&str] = &["sendgrid_api_key"];
+const REDACTED_VALUE: &str = "[redacted]";
+
+/// A change made to an entity by a user, with the scope that allowed it and the before and
+/// after value of each field that changed
+#[derive(Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[table_name = "audit_logs"]
+pub struct AuditLog {
+    pub id: Uuid,
+    pub organization_id: Option<Uuid>,
+    pub event_id: Option<Uuid>,
+    pub user_id: Option<Uuid>,
+    pub scope: Option<String>,
+    pub action: AuditActions,
+    pub main_table: Tables,
+    pub main_id: Uuid,
+    pub changes: Value,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Deserialize, Insertable, Serialize)]
+#[table_name = "audit_logs"]
+pub struct NewAuditLog {
+    pub organization_id: Option<Uuid>,
+    pub event_id: Option<Uuid>,
+    pub user_id: Option<Uuid>,
+    pub scope: Option<String>,
+    pub action: AuditActions,
+    pub main_table: Tables,
+    pub main_id: Uuid,
+    pub changes: Value,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct AuditLogFilters {
+    pub main_table: Option<Tables>,
+    pub main_id: Option<Uuid>,
+    pub user_id: Option<Uuid>,
+    pub action: Option<AuditActions>,
+    pub start_date: Option<NaiveDateTime>,
+    pub end_date: Option<NaiveDateTime>,
+}
+
+impl NewAuditLog {
+    pub fn created<T: Serialize>(self, after: &T) -> Result<NewAuditLog, DatabaseError> {
+        self.with_changes(AuditActions::Created, None, Some(after))
+    }
+
+    pub fn updated<T: Serialize>(
+        self,
+        before: &T,
+        after: &T,
+    ) -> Result<NewAuditLog, DatabaseError> {
+        self.with_changes(AuditActions::Updated, Some(before), Some(after))
+    }
+
+    pub fn deleted<T: Serialize>(self, before: &T) -> Result<NewAuditLog, DatabaseError> {
+        self.with_changes(AuditActions::Deleted, Some(before), None)
+    }
+
+    /// Records the fields that differ between the two versions of the entity
+    fn with_changes<T: Serialize>(
+        mut self,
+        action: AuditActions,
+        before: Option<&T>,
+        after: Option<&T>,
+    ) -> Result<NewAuditLog, DatabaseError> {
+        let before = to_fields(before)?;
+        let after = to_fields(after)?;
+
+        let mut changes = Map::new();
+        for field in before.keys().chain(after.keys()) {
+            if IGNORED_FIELDS.contains(&field.as_str()) || changes.contains_key(field) {
+                continue;
+            }
+            let old_value = before.get(field).cloned().unwrap_or(Value::Null);
+            let new_value = after.get(field).cloned().unwrap_or(Value::Null);
+            if old_value == new_value {
+                continue;
+            }
+            let (old_value, new_value) = if REDACTED_FIELDS.contains(&field.as_str()) {
+                (redact(old_value), redact(new_value))
+            } else {
+                (old_value, new_value)
+            };
+            changes.insert(
+                field.clone(),
+                json!({"before": old_value, "after": new_value}),
+            );
+        }
+        self.action = action;
+        self.changes = Value::Object(changes);
+        Ok(self)
+    }
+
+    pub fn commit(self, conn: &PgConnection) -> Result<AuditLog, DatabaseError> {
+        diesel::insert_into(audit_logs::table)
+            .values(&self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create audit log")
+    }
+}
+
+impl AuditLog {
+    /// Replaces every occurrence of `value`, e.g. an erased user's email, in the recorded
+    /// changes
+    pub fn redact(value: &str, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        diesel::sql_query(
+            r#"
+            UPDATE audit_logs
+            SET changes = regexp_replace(changes::text, $1, $2, 'gi')::json,
+                updated_at = now()
+            WHERE changes::text ~* $1;
+        "#,
+        )
+        .bind::<Text, _>(escape_regex(value))
+        .bind::<Text, _>(REDACTED_VALUE)
+        .execute(conn)
+        .to_db_error(ErrorCode::UpdateError, "Could not redact audit logs")
+    }
+
+    /// Starts a log entry for a change made by `user_id` using `scope`, the change itself is
+    /// added with `created`, `updated` or `deleted`
+    pub fn create(
+        user_id: Option<Uuid>,
+        scope: Option<Scopes>,
+        main_table: Tables,
+        main_id: Uuid,
+        organization_id: Option<Uuid>,
+        event_id: Option<Uuid>,
+    ) -> NewAuditLog {
+        NewAuditLog {
+            organization_id,
+            event_id,
+            user_id,
+            scope: scope.map(|s| s.to_string()),
+            action: AuditActions::Updated,
+            main_table,
+            main_id,
+            changes: Value::Object(Map::new()),
+        }
+    }
+
+    /// Changes to the event and to everything belonging to it, newest first
+    pub fn find_for_event(
+        event_id: Uuid,
+        filters: AuditLogFilters,
+        page: u32,
+        limit: u32,
+        conn: &PgConnection,
+    ) -> Result<Payload<AuditLog>, DatabaseError> {
+        AuditLog::find(None, Some(event_id), filters, page, limit, conn)
+    }
+
+    /// Changes to the organization and to everything belonging to it, newest first
+    pub fn find_for_organization(
+        organization_id: Uuid,
+        filters: AuditLogFilters,
+        page: u32,
+        limit: u32,
+        conn: &PgConnection,
+    ) -> Result<Payload<AuditLog>, DatabaseError> {
+        AuditLog::find(Some(organization_id), None, filters, page, limit, conn)
+    }
+
+    fn find(
+        organization_id: Option<Uuid>,
+        event_id: Option<Uuid>,
+        filters: AuditLogFilters,
+        page: u32,
+        limit: u32,
+        conn: &PgConnection,
+    ) -> Result<Payload<AuditLog>, DatabaseError> {
+        let mut query = audit_logs::table.into_boxed();
+        let mut count_query = audit_logs::table.select(dsl::count_star()).into_boxed();
+        if let Some(organization_id) = organization_id {
+            query = query.filter(audit_logs::organization_id.eq(organization_id));
+            count_query = count_query.filter(audit_logs::organization_id.eq(organization_id));
+        }
+        if let Some(event_id) = event_id {
+            query = query.filter(audit_logs::event_id.eq(event_id));
+            count_query = count_query.filter(audit_logs::event_id.eq(event_id));
+        }
+        if let Some(main_table) = filters.main_table {
+            query = query.filter(audit_logs::main_table.eq(main_table));
+            count_query = count_query.filter(audit_logs::main_table.eq(main_table));
+        }
+        if let Some(main_id) = filters.main_id {
+            query = query.filter(audit_logs::main_id.eq(main_id));
+            count_query = count_query.filter(audit_logs::main_id.eq(main_id));
+        }
+        if let Some(user_id) = filters.user_id {
+            query = query.filter(audit_logs::user_id.eq(user_id));
+            count_query = count_query.filter(audit_logs::user_id.eq(user_id));
+        }
+        if let Some(action) = filters.action {
+            query = query.filter(audit_logs::action.eq(action));
+            count_query = count_query.filter(audit_logs::action.eq(action));
+        }
+        if let Some(start_date) = filters.start_date {
+            query = query.filter(audit_logs::created_at.ge(start_date));
+            count_query = count_query.filter(audit_logs::created_at.ge(start_date));
+        }
+        if let Some(end_date) = filters.end_date {
+            query = query.filter(audit_logs::created_at.le(end_date));
+            count_query = count_query.filter(audit_logs::created_at.le(end_date));
+        }
+
+        let logs = query
+            .order_by(audit_logs::created_at.desc())
+            .then_order_by(audit_logs::id)
+            .limit(limit as i64)
+            .offset((page * limit) as i64)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load audit logs")?;
+        let total: i64 = count_query
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load audit logs")?;
+
+        let mut payload = Payload::new(logs, Paging::new(page, limit));
+        payload.paging.total = total as u64;
+        Ok(payload)
+    }
+}
+
+fn to_fields<T: Serialize>(value: Option<&T>) -> Result<Map<String, Value>, DatabaseError> {
+    let value = match value {
+        Some(value) => serde_json::to_value(value).map_err(|e| {
+            DatabaseError::new(
+                ErrorCode::InternalError,
+                Some(format!("Could not serialize audited entity: {}", e)),
+            )
+        })?,
+        None => return Ok(Map::new()),
+    };
+    match value {
+        Value::Object(fields) => Ok(fields),
+        _ => Err(DatabaseError::new(
+            ErrorCode::InternalError,
+            Some("Audited entities must serialize to an object".to_string()),
+        )),
+    }
+}
+
+fn redact(value: Value) -> Value {
+    match value {
+        Value::Null => Value::Null,
+        _ => Value::String(REDACTED_VALUE.to_string()),
+    }
+}
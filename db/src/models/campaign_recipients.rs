@@ -0,0 +1,121 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::campaign_recipients;
+use utils::errors::*;
+use uuid::Uuid;
+
+#[derive(
+    Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize,
+)]
+#[belongs_to(Campaign)]
+#[table_name = "campaign_recipients"]
+pub struct CampaignRecipient {
+    pub id: Uuid,
+    pub campaign_id: Uuid,
+    pub user_id: Uuid,
+    pub address: Option<String>,
+    pub status: CampaignRecipientStatus,
+    pub status_reason: Option<String>,
+    pub domain_action_id: Option<Uuid>,
+    #[serde(skip_serializing)]
+    pub unsubscribe_token: Uuid,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Deserialize, Insertable, Serialize)]
+#[table_name = "campaign_recipients"]
+pub struct NewCampaignRecipient {
+    pub campaign_id: Uuid,
+    pub user_id: Uuid,
+    pub address: Option<String>,
+    pub status: CampaignRecipientStatus,
+    pub status_reason: Option<String>,
+}
+
+impl CampaignRecipient {
+    pub fn find_by_unsubscribe_token(
+        unsubscribe_token: Uuid,
+        conn: &PgConnection,
+    ) -> Result<CampaignRecipient, DatabaseError> {
+        campaign_recipients::table
+            .filter(campaign_recipients::unsubscribe_token.eq(unsubscribe_token))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find campaign recipient")
+    }
+
+    pub fn find_for_campaign(
+        campaign_id: Uuid,
+        status: Option<CampaignRecipientStatus>,
+        page: u32,
+        limit: u32,
+        conn: &PgConnection,
+    ) -> Result<Payload<CampaignRecipient>, DatabaseError> {
+        let mut query = campaign_recipients::table
+            .filter(campaign_recipients::campaign_id.eq(campaign_id))
+            .into_boxed();
+        let mut count_query = campaign_recipients::table
+            .filter(campaign_recipients::campaign_id.eq(campaign_id))
+            .select(dsl::count_star())
+            .into_boxed();
+        if let Some(status) = status {
+            query = query.filter(campaign_recipients::status.eq(status));
+            count_query = count_query.filter(campaign_recipients::status.eq(status));
+        }
+
+        let recipients = query
+            .order_by(campaign_recipients::created_at)
+            .then_order_by(campaign_recipients::id)
+            .limit(limit as i64)
+            .offset((page * limit) as i64)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load campaign recipients")?;
+        let total: i64 = count_query
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load campaign recipients")?;
+
+        let mut payload = Payload::new(recipients, Paging::new(page, limit));
+        payload.paging.total = total as u64;
+        Ok(payload)
+    }
+
+    pub fn mark_queued(
+        &self,
+        domain_action_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<CampaignRecipient, DatabaseError> {
+        diesel::update(self)
+            .set((
+                campaign_recipients::status.eq(CampaignRecipientStatus::Queued),
+                campaign_recipients::domain_action_id.eq(domain_action_id),
+                campaign_recipients::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(
+                ErrorCode::UpdateError,
+                "Could not update campaign recipient",
+            )
+    }
+
+    /// Turns off the organization's marketing on the campaign's channel for the recipient
+    pub fn unsubscribe(
+        &self,
+        ip_address: Option<String>,
+        conn: &PgConnection,
+    ) -> Result<CommunicationPreference, DatabaseError> {
+        let campaign = Campaign::find(self.campaign_id, conn)?;
+        CommunicationPreference::set(
+            self.user_id,
+            Some(campaign.organization_id),
+            CommunicationCategory::Marketing,
+            campaign.channel,
+            false,
+            "Campaign unsubscribe",
+            ip_address,
+            conn,
+        )
+    }
+}
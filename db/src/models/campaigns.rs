@@ -0,0 +1,711 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use diesel::sql_types::{Nullable, Text, Uuid as dUuid};
+use models::*;
+use schema::{campaign_recipients, campaigns, domain_actions, events, ticket_types};
+use std::collections::HashSet;
+use utils::errors::*;
+use uuid::Uuid;
+use validator::{Validate, ValidationErrors};
+use validators;
+use validators::*;
+
+const FAN_SEGMENT_PAGE_SIZE: u32 = 1000;
+const RECIPIENT_INSERT_BATCH_SIZE: usize = 1000;
+
+#[derive(
+    Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize,
+)]
+#[belongs_to(Organization)]
+#[table_name = "campaigns"]
+pub struct Campaign {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub name: String,
+    pub channel: CommunicationChannelType,
+    pub audience: CampaignAudiences,
+    pub event_id: Option<Uuid>,
+    pub ticket_type_id: Option<Uuid>,
+    pub fan_segment_id: Option<Uuid>,
+    pub subject: Option<String>,
+    pub body: String,
+    pub status: CampaignStatus,
+    pub batch_size: i32,
+    pub batch_interval_in_seconds: i32,
+    pub created_by_user_id: Uuid,
+    pub sent_at: Option<NaiveDateTime>,
+    pub completed_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+/// A message to an event's ticket holders, the holders of a ticket type or the members of a
+/// fan segment. Messages are sent in batches of `batch_size` recipients, waiting
+/// `batch_interval_in_seconds` between batches.
+#[derive(Clone, Debug, Deserialize, Insertable, Serialize, Validate)]
+#[table_name = "campaigns"]
+pub struct NewCampaign {
+    pub organization_id: Uuid,
+    #[validate(length(min = "1", message = "Name is required"))]
+    pub name: String,
+    pub channel: CommunicationChannelType,
+    pub audience: CampaignAudiences,
+    pub event_id: Option<Uuid>,
+    pub ticket_type_id: Option<Uuid>,
+    pub fan_segment_id: Option<Uuid>,
+    pub subject: Option<String>,
+    #[validate(length(min = "1", message = "Body is required"))]
+    pub body: String,
+    pub batch_size: i32,
+    pub batch_interval_in_seconds: i32,
+    pub created_by_user_id: Uuid,
+}
+
+#[derive(AsChangeset, Default, Deserialize, Validate)]
+#[table_name = "campaigns"]
+pub struct CampaignEditableAttributes {
+    #[validate(length(min = "1", message = "Name is required"))]
+    pub name: Option<String>,
+    pub subject: Option<String>,
+    #[validate(length(min = "1", message = "Body is required"))]
+    pub body: Option<String>,
+    pub batch_size: Option<i32>,
+    pub batch_interval_in_seconds: Option<i32>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, QueryableByName, Serialize)]
+pub struct CampaignAudienceMember {
+    #[sql_type = "dUuid"]
+    pub user_id: Uuid,
+    #[sql_type = "Nullable<Text>"]
+    pub first_name: Option<String>,
+    #[sql_type = "Nullable<Text>"]
+    pub last_name: Option<String>,
+    #[sql_type = "Nullable<Text>"]
+    pub email: Option<String>,
+    #[sql_type = "Nullable<Text>"]
+    pub phone: Option<String>,
+}
+
+/// The size of a campaign's audience before it is sent, `reachable` fans have an address for
+/// the campaign's channel and have not opted out
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct CampaignAudienceSummary {
+    pub total: i64,
+    pub opted_out: i64,
+    pub missing_address: i64,
+    pub reachable: i64,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct CampaignRecipientCounts {
+    pub pending: i64,
+    pub queued: i64,
+    pub sent: i64,
+    pub failed: i64,
+    pub skipped: i64,
+    pub cancelled: i64,
+}
+
+impl NewCampaign {
+    pub fn commit(self, conn: &PgConnection) -> Result<Campaign, DatabaseError> {
+        self.validate_record(conn)?;
+
+        diesel::insert_into(campaigns::table)
+            .values(&self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create campaign")
+    }
+
+    fn validate_record(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        let mut validation_errors = delivery_settings_valid(
+            self.validate(),
+            self.channel,
+            self.subject.as_ref(),
+            self.batch_size,
+            self.batch_interval_in_seconds,
+        );
+
+        match self.audience {
+            CampaignAudiences::EventTicketHolders => match self.event_id {
+                Some(event_id) => {
+                    if Event::find(event_id, conn)?.organization_id != self.organization_id {
+                        validation_errors = validators::append_validation_error(
+                            validation_errors,
+                            "event_id",
+                            Err(create_validation_error(
+                                "event_not_in_organization",
+                                "Event does not belong to this organization",
+                            )),
+                        );
+                    }
+                }
+                None => {
+                    validation_errors = validators::append_validation_error(
+                        validation_errors,
+                        "event_id",
+                        Err(create_validation_error(
+                            "event_required",
+                            "An event is required to message its ticket holders",
+                        )),
+                    );
+                }
+            },
+            CampaignAudiences::TicketTypeHolders => match self.ticket_type_id {
+                Some(ticket_type_id) => {
+                    let organization_id: Uuid = ticket_types::table
+                        .inner_join(events::table)
+                        .filter(ticket_types::id.eq(ticket_type_id))
+                        .select(events::organization_id)
+                        .first(conn)
+                        .to_db_error(ErrorCode::QueryError, "Could not find ticket type")?;
+                    if organization_id != self.organization_id {
+                        validation_errors = validators::append_validation_error(
+                            validation_errors,
+                            "ticket_type_id",
+                            Err(create_validation_error(
+                                "ticket_type_not_in_organization",
+                                "Ticket type does not belong to this organization",
+                            )),
+                        );
+                    }
+                }
+                None => {
+                    validation_errors = validators::append_validation_error(
+                        validation_errors,
+                        "ticket_type_id",
+                        Err(create_validation_error(
+                            "ticket_type_required",
+                            "A ticket type is required to message its ticket holders",
+                        )),
+                    );
+                }
+            },
+            CampaignAudiences::FanSegment => match self.fan_segment_id {
+                Some(fan_segment_id) => {
+                    if FanSegment::find(fan_segment_id, conn)?.organization_id
+                        != self.organization_id
+                    {
+                        validation_errors = validators::append_validation_error(
+                            validation_errors,
+                            "fan_segment_id",
+                            Err(create_validation_error(
+                                "fan_segment_not_in_organization",
+                                "Fan segment does not belong to this organization",
+                            )),
+                        );
+                    }
+                }
+                None => {
+                    validation_errors = validators::append_validation_error(
+                        validation_errors,
+                        "fan_segment_id",
+                        Err(create_validation_error(
+                            "fan_segment_required",
+                            "A fan segment is required to message its members",
+                        )),
+                    );
+                }
+            },
+        }
+        Ok(validation_errors?)
+    }
+}
+
+impl Campaign {
+    pub fn create(
+        organization_id: Uuid,
+        name: &str,
+        channel: CommunicationChannelType,
+        audience: CampaignAudiences,
+        subject: Option<String>,
+        body: &str,
+        created_by_user_id: Uuid,
+    ) -> NewCampaign {
+        NewCampaign {
+            organization_id,
+            name: name.to_string(),
+            channel,
+            audience,
+            event_id: None,
+            ticket_type_id: None,
+            fan_segment_id: None,
+            subject,
+            body: body.to_string(),
+            batch_size: 100,
+            batch_interval_in_seconds: 60,
+            created_by_user_id,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<Campaign, DatabaseError> {
+        campaigns::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find campaign")
+    }
+
+    pub fn find_for_organization(
+        organization_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<Campaign>, DatabaseError> {
+        campaigns::table
+            .filter(campaigns::organization_id.eq(organization_id))
+            .order_by(campaigns::created_at.desc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load campaigns")
+    }
+
+    pub fn organization(&self, conn: &PgConnection) -> Result<Organization, DatabaseError> {
+        Organization::find(self.organization_id, conn)
+    }
+
+    /// Only draft campaigns can be changed
+    pub fn update(
+        &self,
+        attributes: CampaignEditableAttributes,
+        conn: &PgConnection,
+    ) -> Result<Campaign, DatabaseError> {
+        if self.status != CampaignStatus::Draft {
+            return DatabaseError::business_process_error(
+                "Campaign cannot be changed once it has been sent",
+            );
+        }
+        let validation_errors = delivery_settings_valid(
+            attributes.validate(),
+            self.channel,
+            attributes.subject.as_ref().or(self.subject.as_ref()),
+            attributes.batch_size.unwrap_or(self.batch_size),
+            attributes
+                .batch_interval_in_seconds
+                .unwrap_or(self.batch_interval_in_seconds),
+        );
+        validation_errors?;
+
+        diesel::update(self)
+            .set((attributes, campaigns::updated_at.eq(dsl::now)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update campaign")
+    }
+
+    /// Everyone the campaign is addressed to, including fans who will be skipped when it is sent
+    pub fn audience(
+        &self,
+        conn: &PgConnection,
+    ) -> Result<Vec<CampaignAudienceMember>, DatabaseError> {
+        match self.audience {
+            CampaignAudiences::EventTicketHolders | CampaignAudiences::TicketTypeHolders => {
+                let query = include_str!("../queries/campaign_ticket_holders.sql");
+                diesel::sql_query(query)
+                    .bind::<Nullable<dUuid>, _>(self.event_id)
+                    .bind::<Nullable<dUuid>, _>(self.ticket_type_id)
+                    .load(conn)
+                    .to_db_error(ErrorCode::QueryError, "Could not load campaign audience")
+            }
+            CampaignAudiences::FanSegment => {
+                let fan_segment = FanSegment::find(
+                    self.fan_segment_id.ok_or_else(|| {
+                        DatabaseError::new(
+                            ErrorCode::BusinessProcessError,
+                            Some("Campaign does not have a fan segment".to_string()),
+                        )
+                    })?,
+                    conn,
+                )?;
+                let mut audience = Vec::new();
+                let mut page = 0;
+                loop {
+                    let fans = fan_segment.members(page, FAN_SEGMENT_PAGE_SIZE, conn)?.data;
+                    let fan_count = fans.len();
+                    audience.extend(fans.into_iter().map(|fan| CampaignAudienceMember {
+                        user_id: fan.user_id,
+                        first_name: fan.first_name,
+                        last_name: fan.last_name,
+                        email: fan.email,
+                        phone: fan.phone,
+                    }));
+                    if fan_count < FAN_SEGMENT_PAGE_SIZE as usize {
+                        break;
+                    }
+                    page += 1;
+                }
+                Ok(audience)
+            }
+        }
+    }
+
+    pub fn audience_summary(
+        &self,
+        conn: &PgConnection,
+    ) -> Result<CampaignAudienceSummary, DatabaseError> {
+        let mut summary = CampaignAudienceSummary::default();
+        for recipient in self.new_recipients(conn)? {
+            summary.total += 1;
+            match recipient.status {
+                CampaignRecipientStatus::Pending => summary.reachable += 1,
+                _ if recipient.address.is_none() => summary.missing_address += 1,
+                _ => summary.opted_out += 1,
+            }
+        }
+        Ok(summary)
+    }
+
+    /// Marks the campaign as sending and schedules its first batch. The recipients are
+    /// recorded and their messages queued in the background, see `record_recipients` and
+    /// `pending_recipients`.
+    pub fn start_sending(&self, conn: &PgConnection) -> Result<Campaign, DatabaseError> {
+        if self.status != CampaignStatus::Draft {
+            return DatabaseError::business_process_error("Campaign has already been sent");
+        }
+
+        let campaign: Campaign = diesel::update(
+            campaigns::table
+                .filter(campaigns::id.eq(self.id))
+                .filter(campaigns::status.eq(CampaignStatus::Draft)),
+        )
+        .set((
+            campaigns::status.eq(CampaignStatus::Sending),
+            campaigns::sent_at.eq(dsl::now.nullable()),
+            campaigns::updated_at.eq(dsl::now),
+        ))
+        .get_result(conn)
+        .to_db_error(ErrorCode::UpdateError, "Could not update campaign")?;
+
+        campaign.schedule_batch(Utc::now().naive_utc(), conn)?;
+        Ok(campaign)
+    }
+
+    /// Schedules the next batch of the campaign's messages to be queued at `scheduled_at`
+    pub fn schedule_batch(
+        &self,
+        scheduled_at: NaiveDateTime,
+        conn: &PgConnection,
+    ) -> Result<DomainAction, DatabaseError> {
+        DomainAction::create(
+            None,
+            DomainActionTypes::SendCampaign,
+            None,
+            json!({ "campaign_id": self.id }),
+            Some(Tables::Campaigns.to_string()),
+            Some(self.id),
+            scheduled_at,
+            scheduled_at + Duration::days(1),
+            3,
+        )
+        .commit(conn)
+    }
+
+    /// Records the recipients of a sending campaign the first time it is called, later calls
+    /// leave the recipients as they are. Fans who opted out or have no address for the
+    /// channel are recorded as skipped.
+    pub fn record_recipients(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        if self.status != CampaignStatus::Sending {
+            return Ok(());
+        }
+        let recorded: i64 = campaign_recipients::table
+            .filter(campaign_recipients::campaign_id.eq(self.id))
+            .select(dsl::count_star())
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load campaign recipients")?;
+        if recorded > 0 {
+            return Ok(());
+        }
+
+        let new_recipients = self.new_recipients(conn)?;
+        for chunk in new_recipients.chunks(RECIPIENT_INSERT_BATCH_SIZE) {
+            diesel::insert_into(campaign_recipients::table)
+                .values(chunk)
+                .execute(conn)
+                .to_db_error(
+                    ErrorCode::InsertError,
+                    "Could not create campaign recipients",
+                )?;
+        }
+        Ok(())
+    }
+
+    /// Recipients whose messages have not been queued yet, up to `limit`
+    pub fn pending_recipients(
+        &self,
+        limit: i64,
+        conn: &PgConnection,
+    ) -> Result<Vec<CampaignRecipient>, DatabaseError> {
+        campaign_recipients::table
+            .filter(campaign_recipients::campaign_id.eq(self.id))
+            .filter(campaign_recipients::status.eq(CampaignRecipientStatus::Pending))
+            .order_by(campaign_recipients::created_at)
+            .then_order_by(campaign_recipients::id)
+            .limit(limit)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load campaign recipients")
+    }
+
+    /// Stops a campaign that has not finished sending, messages that have not been sent yet
+    /// are cancelled
+    pub fn cancel(&self, conn: &PgConnection) -> Result<Campaign, DatabaseError> {
+        // Messages that were delivered before the campaign was cancelled keep their status
+        let campaign = self.update_recipient_statuses(conn)?;
+        if campaign.status != CampaignStatus::Draft && campaign.status != CampaignStatus::Sending {
+            return DatabaseError::business_process_error(
+                "Only draft or sending campaigns can be cancelled",
+            );
+        }
+
+        let queued_action_ids: Vec<Option<Uuid>> = campaign_recipients::table
+            .filter(campaign_recipients::campaign_id.eq(self.id))
+            .filter(campaign_recipients::status.eq(CampaignRecipientStatus::Queued))
+            .select(campaign_recipients::domain_action_id)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load campaign recipients")?;
+        let queued_action_ids: Vec<Uuid> =
+            queued_action_ids.into_iter().filter_map(|id| id).collect();
+        diesel::update(
+            domain_actions::table
+                .filter(domain_actions::id.eq_any(queued_action_ids))
+                .filter(domain_actions::status.eq(DomainActionStatus::Pending)),
+        )
+        .set((
+            domain_actions::status.eq(DomainActionStatus::Cancelled),
+            domain_actions::updated_at.eq(dsl::now),
+        ))
+        .execute(conn)
+        .to_db_error(
+            ErrorCode::UpdateError,
+            "Could not cancel campaign communications",
+        )?;
+        // Batches whose messages have not been queued yet
+        diesel::update(
+            domain_actions::table
+                .filter(domain_actions::main_table.eq(Tables::Campaigns.to_string()))
+                .filter(domain_actions::main_table_id.eq(self.id))
+                .filter(domain_actions::status.eq(DomainActionStatus::Pending)),
+        )
+        .set((
+            domain_actions::status.eq(DomainActionStatus::Cancelled),
+            domain_actions::updated_at.eq(dsl::now),
+        ))
+        .execute(conn)
+        .to_db_error(ErrorCode::UpdateError, "Could not cancel campaign batches")?;
+
+        diesel::update(
+            campaign_recipients::table
+                .filter(campaign_recipients::campaign_id.eq(self.id))
+                .filter(campaign_recipients::status.eq_any(vec![
+                    CampaignRecipientStatus::Pending,
+                    CampaignRecipientStatus::Queued,
+                ])),
+        )
+        .set((
+            campaign_recipients::status.eq(CampaignRecipientStatus::Cancelled),
+            campaign_recipients::updated_at.eq(dsl::now),
+        ))
+        .execute(conn)
+        .to_db_error(
+            ErrorCode::UpdateError,
+            "Could not cancel campaign recipients",
+        )?;
+
+        diesel::update(&campaign)
+            .set((
+                campaigns::status.eq(CampaignStatus::Cancelled),
+                campaigns::completed_at.eq(dsl::now.nullable()),
+                campaigns::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not cancel campaign")
+    }
+
+    /// Updates the delivery status of queued recipients from their communication actions and
+    /// marks the campaign as sent once every message has been delivered or has failed
+    pub fn update_recipient_statuses(
+        &self,
+        conn: &PgConnection,
+    ) -> Result<Campaign, DatabaseError> {
+        if self.status != CampaignStatus::Sending {
+            return Ok(self.clone());
+        }
+
+        let query = include_str!("../queries/update_campaign_recipient_statuses.sql");
+        diesel::sql_query(query)
+            .bind::<dUuid, _>(self.id)
+            .execute(conn)
+            .to_db_error(
+                ErrorCode::UpdateError,
+                "Could not update campaign recipient statuses",
+            )?;
+        self.complete_if_finished(conn)
+    }
+
+    pub fn recipient_counts(
+        &self,
+        conn: &PgConnection,
+    ) -> Result<CampaignRecipientCounts, DatabaseError> {
+        let rows: Vec<(CampaignRecipientStatus, i64)> = campaign_recipients::table
+            .filter(campaign_recipients::campaign_id.eq(self.id))
+            .group_by(campaign_recipients::status)
+            .select((campaign_recipients::status, dsl::count_star()))
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load campaign recipients")?;
+
+        let mut counts = CampaignRecipientCounts::default();
+        for (status, count) in rows {
+            match status {
+                CampaignRecipientStatus::Pending => counts.pending = count,
+                CampaignRecipientStatus::Queued => counts.queued = count,
+                CampaignRecipientStatus::Sent => counts.sent = count,
+                CampaignRecipientStatus::Failed => counts.failed = count,
+                CampaignRecipientStatus::Skipped => counts.skipped = count,
+                CampaignRecipientStatus::Cancelled => counts.cancelled = count,
+            }
+        }
+        Ok(counts)
+    }
+
+    pub fn recipients(
+        &self,
+        status: Option<CampaignRecipientStatus>,
+        page: u32,
+        limit: u32,
+        conn: &PgConnection,
+    ) -> Result<Payload<CampaignRecipient>, DatabaseError> {
+        CampaignRecipient::find_for_campaign(self.id, status, page, limit, conn)
+    }
+
+    /// The recipient's address for the campaign's channel, blank addresses are ignored
+    pub fn address_for(&self, member: &CampaignAudienceMember) -> Option<String> {
+        let address = match self.channel {
+            CommunicationChannelType::Email => member.email.clone(),
+            CommunicationChannelType::Sms => member.phone.clone(),
+            CommunicationChannelType::Push => None,
+        };
+        address.filter(|a| !a.trim().is_empty())
+    }
+
+    fn new_recipients(
+        &self,
+        conn: &PgConnection,
+    ) -> Result<Vec<NewCampaignRecipient>, DatabaseError> {
+        let opted_out: HashSet<Uuid> = CommunicationPreference::unsubscribed_user_ids(
+            self.organization_id,
+            CommunicationCategory::Marketing,
+            self.channel,
+            conn,
+        )?
+        .into_iter()
+        .collect();
+
+        Ok(self
+            .audience(conn)?
+            .into_iter()
+            .map(|member| {
+                let address = self.address_for(&member);
+                let (status, status_reason) = if address.is_none() {
+                    (
+                        CampaignRecipientStatus::Skipped,
+                        Some(format!(
+                            "No {} address",
+                            self.channel.to_string().to_lowercase()
+                        )),
+                    )
+                } else if opted_out.contains(&member.user_id) {
+                    (
+                        CampaignRecipientStatus::Skipped,
+                        Some("Opted out".to_string()),
+                    )
+                } else {
+                    (CampaignRecipientStatus::Pending, None)
+                };
+                NewCampaignRecipient {
+                    campaign_id: self.id,
+                    user_id: member.user_id,
+                    address,
+                    status,
+                    status_reason,
+                }
+            })
+            .collect())
+    }
+
+    fn complete_if_finished(&self, conn: &PgConnection) -> Result<Campaign, DatabaseError> {
+        let counts = self.recipient_counts(conn)?;
+        if self.status != CampaignStatus::Sending || counts.pending > 0 || counts.queued > 0 {
+            return Ok(self.clone());
+        }
+        // Recipients are only recorded once the first batch runs
+        if DomainAction::has_pending_action(
+            DomainActionTypes::SendCampaign,
+            Tables::Campaigns.to_string(),
+            self.id,
+            conn,
+        )? {
+            return Ok(self.clone());
+        }
+        diesel::update(self)
+            .set((
+                campaigns::status.eq(CampaignStatus::Sent),
+                campaigns::completed_at.eq(dsl::now.nullable()),
+                campaigns::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update campaign")
+    }
+}
+
+fn delivery_settings_valid(
+    validation_errors: Result<(), ValidationErrors>,
+    channel: CommunicationChannelType,
+    subject: Option<&String>,
+    batch_size: i32,
+    batch_interval_in_seconds: i32,
+) -> Result<(), ValidationErrors> {
+    let mut validation_errors = validation_errors;
+    match channel {
+        CommunicationChannelType::Email => {
+            if subject.map(|s| s.trim().is_empty()).unwrap_or(true) {
+                validation_errors = validators::append_validation_error(
+                    validation_errors,
+                    "subject",
+                    Err(create_validation_error(
+                        "subject_required",
+                        "Email campaigns require a subject",
+                    )),
+                );
+            }
+        }
+        CommunicationChannelType::Sms => (),
+        CommunicationChannelType::Push => {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "channel",
+                Err(create_validation_error(
+                    "channel_not_supported",
+                    "Campaigns can only be sent by email or SMS",
+                )),
+            );
+        }
+    }
+    if batch_size < 1 {
+        validation_errors = validators::append_validation_error(
+            validation_errors,
+            "batch_size",
+            Err(create_validation_error(
+                "batch_size_must_be_positive",
+                "Batch size must be at least 1",
+            )),
+        );
+    }
+    if batch_interval_in_seconds < 0 {
+        validation_errors = validators::append_validation_error(
+            validation_errors,
+            "batch_interval_in_seconds",
+            Err(create_validation_error(
+                "batch_interval_cannot_be_negative",
+                "Batch interval cannot be negative",
+            )),
+        );
+    }
+    validation_errors
+}
@@ -25,6 +25,7 @@ pub struct Code {
     pub max_tickets_per_user: Option<i64>,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub fan_segment_id: Option<Uuid>,
 }
 
 #[derive(Debug, Deserialize, PartialEq, Queryable, Serialize, QueryableByName)]
@@ -55,6 +56,8 @@ pub struct DisplayCode {
     pub updated_at: NaiveDateTime,
     #[sql_type = "Array<dUuid>"]
     pub ticket_type_ids: Vec<Uuid>,
+    #[sql_type = "Nullable<dUuid>"]
+    pub fan_segment_id: Option<Uuid>,
 }
 
 #[derive(AsChangeset, Default, Deserialize, Validate)]
@@ -71,6 +74,7 @@ pub struct UpdateCodeAttributes {
     pub start_date: Option<NaiveDateTime>,
     pub end_date: Option<NaiveDateTime>,
     pub max_tickets_per_user: Option<Option<i64>>,
+    pub fan_segment_id: Option<Option<Uuid>>,
 }
 
 impl Code {
@@ -133,6 +137,7 @@ impl Code {
             created_at: self.created_at,
             updated_at: self.updated_at,
             ticket_type_ids: ticket_type_ids,
+            fan_segment_id: self.fan_segment_id,
         })
     }
 
@@ -157,6 +162,7 @@ impl Code {
             start_date,
             end_date,
             max_tickets_per_user: max_tickets_per_user.map(|max| max as i64),
+            fan_segment_id: None,
         }
     }
 
@@ -171,6 +177,23 @@ impl Code {
         Ok(())
     }
 
+    /// Codes restricted to a fan segment can only be redeemed by the segment's members
+    pub fn confirm_user_eligible(
+        &self,
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        if let Some(fan_segment_id) = self.fan_segment_id {
+            if !FanSegment::find(fan_segment_id, conn)?.has_member(user_id, conn)? {
+                return DatabaseError::validation_error(
+                    "redemption_code",
+                    "Redemption code is not valid",
+                );
+            }
+        }
+        Ok(())
+    }
+
     pub fn organization(&self, conn: &PgConnection) -> Result<Organization, DatabaseError> {
         use schema::*;
         events::table
@@ -203,6 +226,7 @@ impl Code {
                     codes.max_tickets_per_user,
                     codes.created_at,
                     codes.updated_at,
+                    codes.fan_segment_id,
                     array(select ticket_type_id from ticket_type_codes where ticket_type_codes.code_id = codes.id) as ticket_type_ids
                 FROM codes
                 WHERE
@@ -271,6 +295,13 @@ impl Code {
                 conn,
             )?,
         );
+        if let Some(Some(fan_segment_id)) = update_attrs.fan_segment_id {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "fan_segment_id",
+                fan_segment_valid_for_event(fan_segment_id, self.event_id, conn)?,
+            );
+        }
 
         Ok(validation_errors?)
     }
@@ -323,6 +354,7 @@ pub struct NewCode {
     pub start_date: NaiveDateTime,
     pub end_date: NaiveDateTime,
     pub max_tickets_per_user: Option<i64>,
+    pub fan_segment_id: Option<Uuid>,
 }
 
 impl NewCode {
@@ -361,7 +393,29 @@ impl NewCode {
                 conn,
             )?,
         );
+        if let Some(fan_segment_id) = self.fan_segment_id {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "fan_segment_id",
+                fan_segment_valid_for_event(fan_segment_id, self.event_id, conn)?,
+            );
+        }
 
         Ok(validation_errors?)
     }
 }
+
+fn fan_segment_valid_for_event(
+    fan_segment_id: Uuid,
+    event_id: Uuid,
+    conn: &PgConnection,
+) -> Result<Result<(), ValidationError>, DatabaseError> {
+    let fan_segment = FanSegment::find(fan_segment_id, conn)?;
+    if fan_segment.organization_id != Event::find(event_id, conn)?.organization_id {
+        return Ok(Err(create_validation_error(
+            "fan_segment_not_in_organization",
+            "Fan segment does not belong to this organization",
+        )));
+    }
+    Ok(Ok(()))
+}
@@ -0,0 +1,86 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::prelude::*;
+use models::*;
+use schema::communication_consents;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// A record of a user agreeing to or declining a kind of communication, kept for compliance.
+/// Consents are never updated, each change of preference adds a new one.
+#[derive(Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[table_name = "communication_consents"]
+pub struct CommunicationConsent {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub organization_id: Option<Uuid>,
+    pub category: CommunicationCategory,
+    pub channel: CommunicationChannelType,
+    pub subscribed: bool,
+    pub source: String,
+    pub ip_address: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Deserialize, Insertable, Serialize)]
+#[table_name = "communication_consents"]
+pub struct NewCommunicationConsent {
+    pub user_id: Uuid,
+    pub organization_id: Option<Uuid>,
+    pub category: CommunicationCategory,
+    pub channel: CommunicationChannelType,
+    pub subscribed: bool,
+    pub source: String,
+    pub ip_address: Option<String>,
+}
+
+impl NewCommunicationConsent {
+    pub fn commit(self, conn: &PgConnection) -> Result<CommunicationConsent, DatabaseError> {
+        diesel::insert_into(communication_consents::table)
+            .values(&self)
+            .get_result(conn)
+            .to_db_error(
+                ErrorCode::InsertError,
+                "Could not create communication consent",
+            )
+    }
+}
+
+impl CommunicationConsent {
+    pub fn create(
+        user_id: Uuid,
+        organization_id: Option<Uuid>,
+        category: CommunicationCategory,
+        channel: CommunicationChannelType,
+        subscribed: bool,
+        source: &str,
+        ip_address: Option<String>,
+    ) -> NewCommunicationConsent {
+        NewCommunicationConsent {
+            user_id,
+            organization_id,
+            category,
+            channel,
+            subscribed,
+            source: source.to_string(),
+            ip_address,
+        }
+    }
+
+    /// Most recent first
+    pub fn find_for_user(
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<CommunicationConsent>, DatabaseError> {
+        communication_consents::table
+            .filter(communication_consents::user_id.eq(user_id))
+            .order_by(communication_consents::created_at.desc())
+            .then_order_by(communication_consents::id)
+            .load(conn)
+            .to_db_error(
+                ErrorCode::QueryError,
+                "Could not load communication consents",
+            )
+    }
+}
@@ -0,0 +1,193 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::communication_preferences;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// Whether a user receives a category of communication on a channel. A preference without an
+/// organization applies to every organization. Users are subscribed unless they have a
+/// preference saying otherwise.
+#[derive(Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[table_name = "communication_preferences"]
+pub struct CommunicationPreference {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub organization_id: Option<Uuid>,
+    pub category: CommunicationCategory,
+    pub channel: CommunicationChannelType,
+    pub subscribed: bool,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "communication_preferences"]
+struct NewCommunicationPreference {
+    user_id: Uuid,
+    organization_id: Option<Uuid>,
+    category: CommunicationCategory,
+    channel: CommunicationChannelType,
+    subscribed: bool,
+}
+
+impl CommunicationPreference {
+    /// Saves the user's preference and records their consent. `source` describes where the
+    /// change was made, e.g. "Preferences" or "Campaign unsubscribe". Transactional emails
+    /// such as receipts and tickets cannot be turned off.
+    pub fn set(
+        user_id: Uuid,
+        organization_id: Option<Uuid>,
+        category: CommunicationCategory,
+        channel: CommunicationChannelType,
+        subscribed: bool,
+        source: &str,
+        ip_address: Option<String>,
+        conn: &PgConnection,
+    ) -> Result<CommunicationPreference, DatabaseError> {
+        if !subscribed
+            && category == CommunicationCategory::Transactional
+            && channel == CommunicationChannelType::Email
+        {
+            return DatabaseError::validation_error(
+                "subscribed",
+                "Transactional emails cannot be turned off",
+            );
+        }
+
+        let preference =
+            match CommunicationPreference::find(user_id, organization_id, category, channel, conn)?
+            {
+                Some(preference) => diesel::update(&preference)
+                    .set((
+                        communication_preferences::subscribed.eq(subscribed),
+                        communication_preferences::updated_at.eq(dsl::now),
+                    ))
+                    .get_result(conn)
+                    .to_db_error(
+                        ErrorCode::UpdateError,
+                        "Could not update communication preference",
+                    )?,
+                None => diesel::insert_into(communication_preferences::table)
+                    .values(&NewCommunicationPreference {
+                        user_id,
+                        organization_id,
+                        category,
+                        channel,
+                        subscribed,
+                    })
+                    .get_result(conn)
+                    .to_db_error(
+                        ErrorCode::InsertError,
+                        "Could not create communication preference",
+                    )?,
+            };
+
+        CommunicationConsent::create(
+            user_id,
+            organization_id,
+            category,
+            channel,
+            subscribed,
+            source,
+            ip_address,
+        )
+        .commit(conn)?;
+        Ok(preference)
+    }
+
+    pub fn find(
+        user_id: Uuid,
+        organization_id: Option<Uuid>,
+        category: CommunicationCategory,
+        channel: CommunicationChannelType,
+        conn: &PgConnection,
+    ) -> Result<Option<CommunicationPreference>, DatabaseError> {
+        let mut query = communication_preferences::table
+            .filter(communication_preferences::user_id.eq(user_id))
+            .filter(communication_preferences::category.eq(category))
+            .filter(communication_preferences::channel.eq(channel))
+            .into_boxed();
+        query = match organization_id {
+            Some(organization_id) => {
+                query.filter(communication_preferences::organization_id.eq(organization_id))
+            }
+            None => query.filter(communication_preferences::organization_id.is_null()),
+        };
+
+        query.first(conn).optional().to_db_error(
+            ErrorCode::QueryError,
+            "Could not load communication preference",
+        )
+    }
+
+    pub fn find_for_user(
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<CommunicationPreference>, DatabaseError> {
+        communication_preferences::table
+            .filter(communication_preferences::user_id.eq(user_id))
+            .order_by(communication_preferences::created_at)
+            .then_order_by(communication_preferences::id)
+            .load(conn)
+            .to_db_error(
+                ErrorCode::QueryError,
+                "Could not load communication preferences",
+            )
+    }
+
+    /// Whether the user receives this kind of communication from the organization. Turning
+    /// it off for every organization wins over subscribing to a single organization.
+    pub fn allows(
+        user_id: Uuid,
+        organization_id: Option<Uuid>,
+        category: CommunicationCategory,
+        channel: CommunicationChannelType,
+        conn: &PgConnection,
+    ) -> Result<bool, DatabaseError> {
+        let unsubscribed: i64 = communication_preferences::table
+            .filter(communication_preferences::user_id.eq(user_id))
+            .filter(communication_preferences::category.eq(category))
+            .filter(communication_preferences::channel.eq(channel))
+            .filter(communication_preferences::subscribed.eq(false))
+            .filter(
+                communication_preferences::organization_id
+                    .is_null()
+                    .or(communication_preferences::organization_id.eq(organization_id)),
+            )
+            .select(dsl::count_star())
+            .first(conn)
+            .to_db_error(
+                ErrorCode::QueryError,
+                "Could not load communication preferences",
+            )?;
+        Ok(unsubscribed == 0)
+    }
+
+    /// Users who turned this kind of communication off for every organization or for this one
+    pub fn unsubscribed_user_ids(
+        organization_id: Uuid,
+        category: CommunicationCategory,
+        channel: CommunicationChannelType,
+        conn: &PgConnection,
+    ) -> Result<Vec<Uuid>, DatabaseError> {
+        communication_preferences::table
+            .filter(communication_preferences::category.eq(category))
+            .filter(communication_preferences::channel.eq(channel))
+            .filter(communication_preferences::subscribed.eq(false))
+            .filter(
+                communication_preferences::organization_id
+                    .is_null()
+                    .or(communication_preferences::organization_id.eq(organization_id)),
+            )
+            .select(communication_preferences::user_id)
+            .distinct()
+            .load(conn)
+            .to_db_error(
+                ErrorCode::QueryError,
+                "Could not load communication preferences",
+            )
+    }
+}
@@ -0,0 +1,27 @@
+use chrono::{Duration, NaiveDateTime, Utc};
+use uuid::Uuid;
+
+/// A single use token sent to a user, such as a password reset or login link, which is only
+/// accepted for a limited time after it was requested
+pub struct ExpiringToken {
+    pub token: Uuid,
+    pub requested_at: NaiveDateTime,
+}
+
+impl ExpiringToken {
+    pub fn generate() -> ExpiringToken {
+        ExpiringToken {
+            token: Uuid::new_v4(),
+            requested_at: Utc::now().naive_utc(),
+        }
+    }
+
+    pub fn is_valid(requested_at: Option<NaiveDateTime>, valid_for: Duration) -> bool {
+        match requested_at {
+            Some(requested_at) => {
+                Utc::now().naive_utc().signed_duration_since(requested_at) < valid_for
+            }
+            None => false,
+        }
+    }
+}
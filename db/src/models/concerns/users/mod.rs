@@ -1 +1,3 @@
+mod expiring_token;
 pub mod password_resetable;
+pub mod passwordless_loginable;
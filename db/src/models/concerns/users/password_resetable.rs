@@ -1,8 +1,9 @@
-use chrono::{NaiveDateTime, Utc};
+use super::expiring_token::ExpiringToken;
+use chrono::{Duration, NaiveDateTime, Utc};
 use diesel;
 use diesel::expression::dsl;
 use diesel::prelude::*;
-use models::User;
+use models::{User, UserSession};
 use schema::users;
 use utils::errors::{DatabaseError, ErrorCode};
 use utils::passwords::PasswordHash;
@@ -47,13 +48,15 @@ impl PasswordResetable for User {
                     let hash = PasswordHash::generate(password, None);
                     let now = Utc::now().naive_utc();
 
-                    DatabaseError::wrap(
+                    let user: User = DatabaseError::wrap(
                         ErrorCode::UpdateError,
                         "Could not save new password for user",
                         diesel::update(users.filter(id.eq(user.id)))
                             .set((
                                 hashed_pw.eq(&hash.to_string()),
                                 password_modified_at.eq(now),
+                                failed_login_attempts.eq(0),
+                                locked_until.eq(None::<NaiveDateTime>),
                                 updated_at.eq(dsl::now),
                                 PasswordReset {
                                     password_reset_token: None,
@@ -61,7 +64,10 @@ impl PasswordResetable for User {
                                 },
                             ))
                             .get_result(conn),
-                    )
+                    )?;
+                    // Whoever prompted the reset may be signed in elsewhere
+                    UserSession::revoke_all_for_user(user.id, conn)?;
+                    Ok(user)
                 } else {
                     Err(DatabaseError::new(
                         ErrorCode::InternalError,
@@ -87,21 +93,17 @@ impl PasswordResetable for User {
     }
 
     fn has_valid_password_reset_token(&self) -> bool {
-        match self.password_reset_requested_at {
-            Some(password_reset_requested_at) => {
-                let now = Utc::now().naive_utc();
-                now.signed_duration_since(password_reset_requested_at)
-                    .num_days()
-                    < PASSWORD_RESET_EXPIRATION_PERIOD_IN_DAYS
-            }
-            None => false,
-        }
+        ExpiringToken::is_valid(
+            self.password_reset_requested_at,
+            Duration::days(PASSWORD_RESET_EXPIRATION_PERIOD_IN_DAYS),
+        )
     }
 
     fn create_password_reset_token(&self, conn: &PgConnection) -> Result<User, DatabaseError> {
+        let token = ExpiringToken::generate();
         let data = PasswordReset {
-            password_reset_token: Some(Uuid::new_v4()),
-            password_reset_requested_at: Some(Utc::now().naive_utc()),
+            password_reset_token: Some(token.token),
+            password_reset_requested_at: Some(token.requested_at),
         };
 
         DatabaseError::wrap(
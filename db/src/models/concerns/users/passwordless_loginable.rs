@@ -0,0 +1,202 @@
+use super::expiring_token::ExpiringToken;
+use chrono::{Duration, NaiveDateTime, Utc};
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::User;
+use schema::users;
+use utils::encryption;
+use utils::errors::{ConvertToDatabaseError, DatabaseError, ErrorCode};
+use utils::rand::random_numeric_string;
+use uuid::Uuid;
+
+const LOGIN_TOKEN_EXPIRATION_PERIOD_IN_MINUTES: i64 = 15;
+pub const LOGIN_CODE_LENGTH: usize = 6;
+/// The code is discarded after this many incorrect guesses
+const LOGIN_CODE_MAX_FAILED_ATTEMPTS: i32 = 5;
+
+#[derive(AsChangeset)]
+#[changeset_options(treat_none_as_null = "true")]
+#[table_name = "users"]
+pub struct PasswordlessLogin {
+    pub login_token: Option<Uuid>,
+    pub login_code_hash: Option<String>,
+    pub login_token_requested_at: Option<NaiveDateTime>,
+    pub login_code_failed_attempts: i32,
+}
+
+impl PasswordlessLogin {
+    fn cleared() -> PasswordlessLogin {
+        PasswordlessLogin {
+            login_token: None,
+            login_code_hash: None,
+            login_token_requested_at: None,
+            login_code_failed_attempts: 0,
+        }
+    }
+}
+
+/// Signing in without a password, using a link (`login_token`) emailed to the user or a code
+/// texted to them. Both are single use and short lived.
+pub trait PasswordlessLoginable {
+    fn has_valid_login_token(&self) -> bool;
+    /// Replaces any earlier link and code with a new link
+    fn create_login_token(&self, conn: &PgConnection) -> Result<User, DatabaseError>;
+    /// Replaces any earlier link and code with a new code, which is returned here only as it
+    /// is stored hashed
+    fn create_login_code(&self, conn: &PgConnection) -> Result<(User, String), DatabaseError>;
+    fn find_by_login_token(token: &Uuid, conn: &PgConnection) -> Result<User, DatabaseError>;
+    fn consume_login_token(token: &Uuid, conn: &PgConnection) -> Result<User, DatabaseError>;
+    fn find_by_login_phone(phone: &str, conn: &PgConnection)
+        -> Result<Option<User>, DatabaseError>;
+    fn consume_login_code(
+        phone: &str,
+        code: &str,
+        conn: &PgConnection,
+    ) -> Result<Option<User>, DatabaseError>;
+}
+
+impl PasswordlessLoginable for User {
+    fn has_valid_login_token(&self) -> bool {
+        ExpiringToken::is_valid(
+            self.login_token_requested_at,
+            Duration::minutes(LOGIN_TOKEN_EXPIRATION_PERIOD_IN_MINUTES),
+        )
+    }
+
+    fn create_login_token(&self, conn: &PgConnection) -> Result<User, DatabaseError> {
+        let token = ExpiringToken::generate();
+        replace_passwordless_login(
+            self,
+            PasswordlessLogin {
+                login_token: Some(token.token),
+                login_code_hash: None,
+                login_token_requested_at: Some(token.requested_at),
+                login_code_failed_attempts: 0,
+            },
+            conn,
+        )
+    }
+
+    fn create_login_code(&self, conn: &PgConnection) -> Result<(User, String), DatabaseError> {
+        let code = random_numeric_string(LOGIN_CODE_LENGTH);
+        let user = replace_passwordless_login(
+            self,
+            PasswordlessLogin {
+                login_token: None,
+                login_code_hash: Some(encryption::hash_secret(&code)),
+                login_token_requested_at: Some(Utc::now().naive_utc()),
+                login_code_failed_attempts: 0,
+            },
+            conn,
+        )?;
+        Ok((user, code))
+    }
+
+    fn find_by_login_token(token: &Uuid, conn: &PgConnection) -> Result<User, DatabaseError> {
+        let user: User = DatabaseError::wrap(
+            ErrorCode::QueryError,
+            "Error loading user",
+            users::table
+                .filter(users::login_token.eq(token))
+                .first(conn),
+        )?;
+        if !user.has_valid_login_token() {
+            return DatabaseError::business_process_error("Login token is expired");
+        }
+        Ok(user)
+    }
+
+    /// Following the emailed link also shows the user can be reached on their email
+    fn consume_login_token(token: &Uuid, conn: &PgConnection) -> Result<User, DatabaseError> {
+        User::find_by_login_token(token, conn)?;
+
+        // Conditional on the token so it can only be used once
+        diesel::update(users::table.filter(users::login_token.eq(token)))
+            .set((
+                PasswordlessLogin::cleared(),
+                users::email_verified_at.eq(dsl::now.nullable()),
+                users::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading user")
+    }
+
+    /// Phone numbers are not unique, so codes are only sent to and accepted for a verified
+    /// number belonging to exactly one user
+    fn find_by_login_phone(
+        phone: &str,
+        conn: &PgConnection,
+    ) -> Result<Option<User>, DatabaseError> {
+        let mut users: Vec<User> = users::table
+            .filter(users::phone.eq(phone))
+            .filter(users::phone_verified_at.is_not_null())
+            .limit(2)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading user")?;
+        if users.len() != 1 {
+            return Ok(None);
+        }
+        Ok(users.pop())
+    }
+
+    /// Returns `None` for an incorrect or expired code, or a phone not found by
+    /// `find_by_login_phone`
+    fn consume_login_code(
+        phone: &str,
+        code: &str,
+        conn: &PgConnection,
+    ) -> Result<Option<User>, DatabaseError> {
+        let user = match User::find_by_login_phone(phone, conn)? {
+            Some(user) => user,
+            None => return Ok(None),
+        };
+        let login_code_hash = match user.login_code_hash {
+            Some(ref login_code_hash) if user.has_valid_login_token() => login_code_hash.clone(),
+            _ => return Ok(None),
+        };
+
+        let code: String = code.chars().filter(|c| !c.is_whitespace()).collect();
+        if encryption::hash_secret(&code) != login_code_hash {
+            if user.login_code_failed_attempts + 1 >= LOGIN_CODE_MAX_FAILED_ATTEMPTS {
+                diesel::update(&user)
+                    .set(PasswordlessLogin::cleared())
+                    .execute(conn)
+                    .to_db_error(ErrorCode::UpdateError, "Could not update user")?;
+            } else {
+                diesel::update(&user)
+                    .set(
+                        users::login_code_failed_attempts.eq(users::login_code_failed_attempts + 1),
+                    )
+                    .execute(conn)
+                    .to_db_error(ErrorCode::UpdateError, "Could not update user")?;
+            }
+            return Ok(None);
+        }
+
+        // Conditional on the code so it can only be used once
+        diesel::update(
+            users::table
+                .filter(users::id.eq(user.id))
+                .filter(users::login_code_hash.eq(login_code_hash)),
+        )
+        .set((PasswordlessLogin::cleared(), users::updated_at.eq(dsl::now)))
+        .get_result(conn)
+        .optional()
+        .to_db_error(ErrorCode::UpdateError, "Could not update user")
+    }
+}
+
+fn replace_passwordless_login(
+    user: &User,
+    data: PasswordlessLogin,
+    conn: &PgConnection,
+) -> Result<User, DatabaseError> {
+    DatabaseError::wrap(
+        ErrorCode::UpdateError,
+        "Could not create token for passwordless login",
+        diesel::update(user)
+            .set((data, users::updated_at.eq(dsl::now)))
+            .get_result(conn),
+    )
+}
@@ -2,10 +2,12 @@ use chrono::{Duration, NaiveDateTime, Utc};
 use diesel;
 use diesel::expression::dsl;
 use diesel::prelude::*;
+use diesel::sql_types::Text;
 use models::enums::*;
 use schema::*;
 use serde_json;
 use utils::errors::*;
+use utils::text::escape_regex;
 use uuid::Uuid;
 
 #[derive(Clone, Debug, PartialEq, Identifiable, Queryable)]
@@ -39,6 +41,21 @@ pub struct DomainActionEditableAttributes {
 }
 
 impl DomainAction {
+    /// Replaces every occurrence of `value`, e.g. an erased user's email, in action payloads
+    pub fn redact(value: &str, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        diesel::sql_query(
+            r#"
+            UPDATE domain_actions
+            SET payload = regexp_replace(payload::text, $1, '[redacted]', 'gi')::json,
+                updated_at = now()
+            WHERE payload::text ~* $1;
+        "#,
+        )
+        .bind::<Text, _>(escape_regex(value))
+        .execute(conn)
+        .to_db_error(ErrorCode::UpdateError, "Could not redact domain actions")
+    }
+
     pub fn create(
         domain_event_id: Option<Uuid>,
         domain_action_type: DomainActionTypes,
@@ -9,10 +9,11 @@ use std::str::FromStr;
 use utils::errors::EnumParseError;
 
 macro_rules! string_enum {
-    ($name:ident [$($value:ident),+]) => {
+    ($(#[$attr:meta])* $name:ident [$($value:ident),+]) => {
 
         #[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug, Eq, Hash, FromSqlRow, AsExpression)]
         #[sql_type = "Text"]
+        $(#[$attr])*
         pub enum $name {
             $(
                 $value,
@@ -74,8 +75,14 @@ macro_rules! string_enum {
 }
 
 string_enum! { AssetStatus [Unsynced] }
+string_enum! { AuditActions [Created, Updated, Deleted] }
+string_enum! { CampaignAudiences [EventTicketHolders, TicketTypeHolders, FanSegment] }
+string_enum! { CampaignRecipientStatus [Pending, Queued, Sent, Failed, Skipped, Cancelled] }
+string_enum! { CampaignStatus [Draft, Sending, Sent, Cancelled] }
 string_enum! { CartItemStatus [CodeExpired, HoldExpired, TicketNullified, TicketNotReserved, Valid] }
 string_enum! { CodeTypes [Access, Discount] }
+string_enum! { CommissionTypes [Flat, Percentage] }
+string_enum! { CommunicationCategory [Transactional, Marketing] }
 string_enum! { CommunicationChannelType [Email, Sms, Push]}
 string_enum! { DomainEventTypes [
     FeeScheduleCreated,
@@ -93,6 +100,10 @@ string_enum! { DomainEventTypes [
     PaymentMethodCreated,
     PaymentMethodUpdated,
     PaymentUpdated,
+    SettlementApproved,
+    SettlementCreated,
+    SettlementPaid,
+    UserErased,
     UserRegistration,
     LostPassword,
     PurchaseCompleted,
@@ -109,7 +120,11 @@ string_enum! { DomainActionTypes [
     MarketingContactsCreateEventList,
     MarketingContactsBulkEventFanListImport,
     PaymentProviderIPN,
-    SendPurchaseCompletedCommunication
+    SendPurchaseCompletedCommunication,
+    // Queues a batch of campaign messages
+    SendCampaign,
+    // Scheduled report emails
+    SendScheduledReport
 
 ]}
 string_enum! { DomainActionStatus [Pending, RetriesExceeded, Errored, Success, Cancelled]}
@@ -117,18 +132,30 @@ string_enum! { EventStatus [Draft,Closed,Published,Offline]}
 string_enum! { EventSearchSortField [ Name, EventStart]}
 string_enum! { EventOverrideStatus [PurchaseTickets,SoldOut,OnSaleSoon,TicketsAtTheDoor,Free,Rescheduled,Cancelled,OffSale,Ended]}
 string_enum! { EventTypes [ Music, Conference]}
+string_enum! {
+    // Lowercase in requests to match the file extension
+    #[serde(rename_all = "lowercase")]
+    ExportFormats [Csv, Pdf, Xlsx]
+}
+string_enum! { FeeModes [Standard, Absorbed, AllIn] }
 string_enum! { FanSortField [FirstName, LastName, Email, Phone, Orders, FirstOrder, LastOrder, Revenue] }
 string_enum! { HistoryType [Purchase]}
 string_enum! { HoldTypes [Discount, Comp] }
 string_enum! { OrderStatus [Cancelled, Draft, Paid, PendingPayment] }
-string_enum! { OrderItemTypes [Tickets, PerUnitFees, EventFees]}
+string_enum! { OrderItemTypes [Tickets, PerUnitFees, EventFees, Tax]}
 string_enum! { OrderTypes [Cart, BackOffice] }
 string_enum! { PaymentMethods [External, CreditCard, Provider] }
 string_enum! { PaymentStatus [Authorized, Completed, Requested, Refunded, Unpaid, PendingConfirmation, Cancelled, Draft, Unknown, PendingIpn] }
 string_enum! { PastOrUpcoming [Past,Upcoming]}
-string_enum! { Roles [Admin, OrgMember, OrgOwner, OrgAdmin, OrgBoxOffice, DoorPerson, User] }
+string_enum! { ReportDeliveryStatus [Sent, Failed] }
+string_enum! { ReportFrequencies [Daily, Weekly] }
+string_enum! { ReportTypes [TransactionDetails, EventSummary, WeeklySettlement, TicketCount, AuditReport] }
+string_enum! { Roles [Admin, OrgMember, OrgOwner, OrgAdmin, OrgBoxOffice, DoorPerson, Promoter, User] }
+string_enum! { SettlementAdjustmentTypes [Chargeback, Expense, Deposit, Other] }
+string_enum! { SettlementStatus [Draft, Approved, Paid] }
 string_enum! { SortingDir[ Asc, Desc ] }
-string_enum! { Tables [Events, FeeSchedules, Orders, Organizations, Payments, PaymentMethods, TicketInstances] }
+string_enum! { SplitTypes [Flat, Percentage] }
+string_enum! { Tables [CampaignRecipients, Campaigns, Codes, EventSplitPartners, EventUsers, Events, FeeSchedules, Holds, Orders, OrganizationRoles, OrganizationUsers, Organizations, Payments, PaymentMethods, ReportSubscriptions, Settlements, TicketInstances, TicketPricing, TicketTypes, Users] }
 string_enum! { TicketInstanceStatus [Available, Reserved, Purchased, Redeemed, Nullified]}
 string_enum! { TicketPricingStatus [Published, Deleted, Default] }
 string_enum! { TicketTypeStatus [NoActivePricing, Published, SoldOut, Cancelled] }
@@ -145,6 +172,28 @@ impl Default for EventTypes {
     }
 }
 
+impl Default for FeeModes {
+    fn default() -> FeeModes {
+        FeeModes::Standard
+    }
+}
+
+impl Default for ExportFormats {
+    fn default() -> ExportFormats {
+        ExportFormats::Csv
+    }
+}
+
+impl ExportFormats {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormats::Csv => "csv",
+            ExportFormats::Pdf => "pdf",
+            ExportFormats::Xlsx => "xlsx",
+        }
+    }
+}
+
 impl Tables {
     pub fn table_name(&self) -> String {
         self.to_string().to_ascii_lowercase()
@@ -159,6 +208,7 @@ fn display() {
     assert_eq!(Roles::OrgOwner.to_string(), "OrgOwner");
     assert_eq!(Roles::OrgBoxOffice.to_string(), "OrgBoxOffice");
     assert_eq!(Roles::DoorPerson.to_string(), "DoorPerson");
+    assert_eq!(Roles::Promoter.to_string(), "Promoter");
     assert_eq!(Roles::User.to_string(), "User");
 }
 
@@ -0,0 +1,260 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::prelude::*;
+use models::*;
+use schema::{event_split_partners, events};
+use utils::errors::*;
+use uuid::Uuid;
+use validators;
+use validators::*;
+
+#[derive(
+    Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize,
+)]
+#[belongs_to(Event)]
+#[belongs_to(Organization)]
+#[table_name = "event_split_partners"]
+pub struct EventSplitPartner {
+    pub id: Uuid,
+    pub event_id: Uuid,
+    pub organization_id: Uuid,
+    pub split_type: SplitTypes,
+    pub ticket_sales_flat_in_cents: i64,
+    pub ticket_sales_rate_in_basis_points: i64,
+    pub fees_flat_in_cents: i64,
+    pub fees_rate_in_basis_points: i64,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+/// An organization co-promoting an event it does not own, and its share of the event's ticket
+/// sales and fees. Flat shares are fixed amounts, percentage shares are in basis points. Fees
+/// are those collected by the event's organization, less the fees it absorbed.
+#[derive(Clone, Debug, Deserialize, Insertable, Serialize)]
+#[table_name = "event_split_partners"]
+pub struct NewEventSplitPartner {
+    pub event_id: Uuid,
+    pub organization_id: Uuid,
+    pub split_type: SplitTypes,
+    #[serde(default)]
+    pub ticket_sales_flat_in_cents: i64,
+    #[serde(default)]
+    pub ticket_sales_rate_in_basis_points: i64,
+    #[serde(default)]
+    pub fees_flat_in_cents: i64,
+    #[serde(default)]
+    pub fees_rate_in_basis_points: i64,
+}
+
+/// A split partner's share of an event's ticket sales and fees
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SplitPartnerShare {
+    pub event_id: Uuid,
+    pub organization_id: Uuid,
+    pub split_type: SplitTypes,
+    pub ticket_sales_in_cents: i64,
+    pub fees_in_cents: i64,
+}
+
+impl NewEventSplitPartner {
+    pub fn commit(self, conn: &PgConnection) -> Result<EventSplitPartner, DatabaseError> {
+        self.validate_record(conn)?;
+
+        diesel::insert_into(event_split_partners::table)
+            .values(&self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create split partner")
+    }
+
+    fn validate_record(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        let mut validation_errors = Ok(());
+        match self.split_type {
+            SplitTypes::Flat => {
+                if self.ticket_sales_flat_in_cents < 0
+                    || self.fees_flat_in_cents < 0
+                    || self.ticket_sales_flat_in_cents + self.fees_flat_in_cents == 0
+                    || self.ticket_sales_rate_in_basis_points != 0
+                    || self.fees_rate_in_basis_points != 0
+                {
+                    validation_errors = validators::append_validation_error(
+                        validation_errors,
+                        "split_type",
+                        Err(create_validation_error(
+                            "flat_split_invalid",
+                            "Flat splits require a positive amount and no rates",
+                        )),
+                    );
+                }
+            }
+            SplitTypes::Percentage => {
+                if self.ticket_sales_rate_in_basis_points < 0
+                    || self.ticket_sales_rate_in_basis_points > 10_000
+                    || self.fees_rate_in_basis_points < 0
+                    || self.fees_rate_in_basis_points > 10_000
+                    || self.ticket_sales_rate_in_basis_points + self.fees_rate_in_basis_points == 0
+                    || self.ticket_sales_flat_in_cents != 0
+                    || self.fees_flat_in_cents != 0
+                {
+                    validation_errors = validators::append_validation_error(
+                        validation_errors,
+                        "split_type",
+                        Err(create_validation_error(
+                            "percentage_split_invalid",
+                            "Percentage splits require rates between 0 and 10000 basis points and no flat amounts",
+                        )),
+                    );
+                }
+            }
+        }
+
+        let organization_id: Uuid = events::table
+            .filter(events::id.eq(self.event_id))
+            .select(events::organization_id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find event")?;
+        if organization_id == self.organization_id {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "organization_id",
+                Err(create_validation_error(
+                    "split_partner_is_event_organization",
+                    "The event's organization can not be its own split partner",
+                )),
+            );
+        }
+
+        let existing = EventSplitPartner::find_for_event(self.event_id, conn)?;
+        if existing
+            .iter()
+            .any(|p| p.organization_id == self.organization_id)
+        {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "organization_id",
+                Err(create_validation_error(
+                    "split_partner_exists",
+                    "The organization is already a split partner for this event",
+                )),
+            );
+        }
+        if existing
+            .iter()
+            .map(|p| p.ticket_sales_rate_in_basis_points)
+            .sum::<i64>()
+            + self.ticket_sales_rate_in_basis_points
+            > 10_000
+            || existing
+                .iter()
+                .map(|p| p.fees_rate_in_basis_points)
+                .sum::<i64>()
+                + self.fees_rate_in_basis_points
+                > 10_000
+        {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "split_type",
+                Err(create_validation_error(
+                    "split_rates_exceed_total",
+                    "Split partners can not be given more than 10000 basis points in total",
+                )),
+            );
+        }
+        Ok(validation_errors?)
+    }
+}
+
+impl EventSplitPartner {
+    /// Amounts are in cents for flat splits and in basis points for percentage splits
+    pub fn create(
+        event_id: Uuid,
+        organization_id: Uuid,
+        split_type: SplitTypes,
+        ticket_sales_amount: i64,
+        fees_amount: i64,
+    ) -> NewEventSplitPartner {
+        let (ticket_sales_flat_in_cents, ticket_sales_rate_in_basis_points) = match split_type {
+            SplitTypes::Flat => (ticket_sales_amount, 0),
+            SplitTypes::Percentage => (0, ticket_sales_amount),
+        };
+        let (fees_flat_in_cents, fees_rate_in_basis_points) = match split_type {
+            SplitTypes::Flat => (fees_amount, 0),
+            SplitTypes::Percentage => (0, fees_amount),
+        };
+        NewEventSplitPartner {
+            event_id,
+            organization_id,
+            split_type,
+            ticket_sales_flat_in_cents,
+            ticket_sales_rate_in_basis_points,
+            fees_flat_in_cents,
+            fees_rate_in_basis_points,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<EventSplitPartner, DatabaseError> {
+        event_split_partners::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find split partner")
+    }
+
+    pub fn find_for_event(
+        event_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<EventSplitPartner>, DatabaseError> {
+        event_split_partners::table
+            .filter(event_split_partners::event_id.eq(event_id))
+            .order_by(event_split_partners::created_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load split partners")
+    }
+
+    pub fn find_by_event_id_organization_id(
+        event_id: Uuid,
+        organization_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<EventSplitPartner, DatabaseError> {
+        event_split_partners::table
+            .filter(event_split_partners::event_id.eq(event_id))
+            .filter(event_split_partners::organization_id.eq(organization_id))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find split partner")
+    }
+
+    /// The share of each of the event's split partners, given its ticket sales and fees
+    pub fn shares_for_event(
+        event_id: Uuid,
+        ticket_sales_in_cents: i64,
+        fees_in_cents: i64,
+        conn: &PgConnection,
+    ) -> Result<Vec<SplitPartnerShare>, DatabaseError> {
+        Ok(EventSplitPartner::find_for_event(event_id, conn)?
+            .iter()
+            .map(|p| p.share(ticket_sales_in_cents, fees_in_cents))
+            .collect())
+    }
+
+    /// Percentage shares are rounded down to the cent
+    pub fn share(&self, ticket_sales_in_cents: i64, fees_in_cents: i64) -> SplitPartnerShare {
+        let (ticket_sales_share_in_cents, fees_share_in_cents) = match self.split_type {
+            SplitTypes::Flat => (self.ticket_sales_flat_in_cents, self.fees_flat_in_cents),
+            SplitTypes::Percentage => (
+                ticket_sales_in_cents * self.ticket_sales_rate_in_basis_points / 10_000,
+                fees_in_cents * self.fees_rate_in_basis_points / 10_000,
+            ),
+        };
+        SplitPartnerShare {
+            event_id: self.event_id,
+            organization_id: self.organization_id,
+            split_type: self.split_type,
+            ticket_sales_in_cents: ticket_sales_share_in_cents,
+            fees_in_cents: fees_share_in_cents,
+        }
+    }
+
+    pub fn destroy(self, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        diesel::delete(&self)
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not delete split partner")
+    }
+}
@@ -0,0 +1,129 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::{event_users, users};
+use utils::errors::*;
+use uuid::Uuid;
+
+/// A role given to a user on a single event, e.g. an outside promoter co-promoting a show,
+/// without making them a member of the event's organization
+#[derive(
+    Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize,
+)]
+#[belongs_to(User)]
+#[belongs_to(Event)]
+#[table_name = "event_users"]
+pub struct EventUser {
+    pub id: Uuid,
+    pub event_id: Uuid,
+    pub user_id: Uuid,
+    pub role: Roles,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "event_users"]
+struct NewEventUser {
+    event_id: Uuid,
+    user_id: Uuid,
+    role: Roles,
+}
+
+impl EventUser {
+    /// Gives the user `role` on the event, replacing any role they already had on it
+    pub fn create(
+        event_id: Uuid,
+        user_id: Uuid,
+        role: Roles,
+        conn: &PgConnection,
+    ) -> Result<EventUser, DatabaseError> {
+        match role {
+            Roles::Promoter | Roles::OrgMember | Roles::OrgBoxOffice | Roles::DoorPerson => (),
+            _ => {
+                return DatabaseError::validation_error(
+                    "role",
+                    "Role can not be given for a single event",
+                );
+            }
+        }
+
+        match EventUser::find_by_event_id_user_id(event_id, user_id, conn).optional()? {
+            Some(event_user) => diesel::update(&event_user)
+                .set((
+                    event_users::role.eq(role),
+                    event_users::updated_at.eq(dsl::now),
+                ))
+                .get_result(conn)
+                .to_db_error(ErrorCode::UpdateError, "Could not update event user"),
+            None => diesel::insert_into(event_users::table)
+                .values(NewEventUser {
+                    event_id,
+                    user_id,
+                    role,
+                })
+                .get_result(conn)
+                .to_db_error(ErrorCode::InsertError, "Could not create event user"),
+        }
+    }
+
+    pub fn destroy(&self, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        diesel::delete(self)
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not remove event user")
+    }
+
+    pub fn find_by_event_id_user_id(
+        event_id: Uuid,
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<EventUser, DatabaseError> {
+        event_users::table
+            .filter(event_users::event_id.eq(event_id))
+            .filter(event_users::user_id.eq(user_id))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find event user")
+    }
+
+    pub fn find_for_event(
+        event_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<(EventUser, User)>, DatabaseError> {
+        event_users::table
+            .inner_join(users::table)
+            .filter(event_users::event_id.eq(event_id))
+            .order_by(users::last_name.asc())
+            .then_order_by(users::first_name.asc())
+            .select((event_users::all_columns, users::all_columns))
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load event users")
+    }
+
+    /// The events the user has been given a role on, so they can find them without being a
+    /// member of the events' organizations
+    pub fn find_for_user(
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<EventUser>, DatabaseError> {
+        event_users::table
+            .filter(event_users::user_id.eq(user_id))
+            .order_by(event_users::created_at.asc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load events for user")
+    }
+
+    /// The scopes the user has on the event from a role given on it, in addition to any they
+    /// have as a member of the event's organization
+    pub fn get_scopes_for_user(
+        event_id: Uuid,
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<Scopes>, DatabaseError> {
+        match EventUser::find_by_event_id_user_id(event_id, user_id, conn).optional()? {
+            Some(event_user) => Ok(scopes::get_scopes(vec![event_user.role])),
+            None => Ok(vec![]),
+        }
+    }
+}
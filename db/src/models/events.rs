@@ -8,7 +8,9 @@ use diesel::prelude::*;
 use diesel::sql_types;
 use log::Level;
 use models::*;
-use schema::{artists, event_artists, events, organization_users, organizations, venues};
+use schema::{
+    artists, event_artists, events, order_items, organization_users, organizations, venues,
+};
 use serde_with::rust::double_option;
 use std::borrow::Cow;
 use std::collections::HashMap;
@@ -53,6 +55,9 @@ pub struct Event {
     pub event_end: Option<NaiveDateTime>,
     pub sendgrid_list_id: Option<i64>,
     pub event_type: EventTypes,
+    pub currency: String,
+    pub fee_mode: FeeModes,
+    pub genres: Vec<String>,
 }
 
 #[derive(Default, Insertable, Serialize, Deserialize, Validate, Clone)]
@@ -91,6 +96,13 @@ pub struct NewEvent {
     pub override_status: Option<EventOverrideStatus>,
     pub event_end: Option<NaiveDateTime>,
     pub event_type: EventTypes,
+    #[validate(custom = "validate_currency_code")]
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub currency: Option<String>,
+    #[serde(default)]
+    pub fee_mode: FeeModes,
+    #[serde(default)]
+    pub genres: Vec<String>,
 }
 
 impl NewEvent {
@@ -98,9 +110,15 @@ impl NewEvent {
         self.validate()?;
         let organization = Organization::find(self.organization_id, conn)?;
 
+        // Events are priced in the organization's currency unless specified otherwise
+        let mut new_event = self.clone();
+        if new_event.currency.is_none() {
+            new_event.currency = Some(organization.currency.clone());
+        }
+
         diesel::insert_into(events::table)
             .values((
-                self,
+                &new_event,
                 events::fee_in_cents.eq(organization.client_event_fee_in_cents
                     + organization.company_event_fee_in_cents),
                 events::client_fee_in_cents.eq(organization.client_event_fee_in_cents),
@@ -154,6 +172,11 @@ pub struct EventEditableAttributes {
     pub event_end: Option<NaiveDateTime>,
     pub sendgrid_list_id: Option<i64>,
     pub event_type: Option<EventTypes>,
+    #[validate(custom = "validate_currency_code")]
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub currency: Option<String>,
+    pub fee_mode: Option<FeeModes>,
+    pub genres: Option<Vec<String>>,
 }
 
 #[derive(Debug, Default, PartialEq, Serialize)]
@@ -234,6 +257,19 @@ impl Event {
             ),
         )?;
 
+        if let Some(ref currency) = event.currency {
+            if currency != &self.currency && self.has_order_items(conn)? {
+                validators::append_validation_error(
+                    Ok(()),
+                    "currency",
+                    Err(create_validation_error(
+                        "currency_locked",
+                        "Currency cannot be changed once tickets have been added to orders",
+                    )),
+                )?;
+            }
+        }
+
         DatabaseError::wrap(
             ErrorCode::UpdateError,
             "Could not update event",
@@ -388,6 +424,17 @@ impl Event {
         Event::find(self.id, conn)
     }
 
+    fn has_order_items(&self, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        diesel::select(dsl::exists(
+            order_items::table.filter(order_items::event_id.eq(self.id)),
+        ))
+        .get_result(conn)
+        .to_db_error(
+            ErrorCode::QueryError,
+            "Could not check if order items exist for event",
+        )
+    }
+
     pub fn find(id: Uuid, conn: &PgConnection) -> Result<Event, DatabaseError> {
         DatabaseError::wrap(
             ErrorCode::QueryError,
@@ -479,12 +526,30 @@ impl Event {
             events::table
                 .filter(events::venue_id.eq(venue_id))
                 .filter(events::status.eq(EventStatus::Published))
+                .filter(events::publish_date.le(dsl::now.nullable()))
                 .filter(events::cancelled_at.is_null())
                 .order_by(events::name)
                 .load(conn),
         )
     }
 
+    pub fn find_all_active_events_for_organization(
+        organization_id: &Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<Event>, DatabaseError> {
+        DatabaseError::wrap(
+            ErrorCode::QueryError,
+            "Error loading event via organization",
+            events::table
+                .filter(events::organization_id.eq(organization_id))
+                .filter(events::status.eq(EventStatus::Published))
+                .filter(events::publish_date.le(dsl::now.nullable()))
+                .filter(events::cancelled_at.is_null())
+                .order_by(events::event_start.asc())
+                .load(conn),
+        )
+    }
+
     pub fn find_all_events_for_organization(
         organization_id: Uuid,
         past_or_upcoming: PastOrUpcoming,
@@ -0,0 +1,199 @@
+pub use bigdecimal::BigDecimal;
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::prelude::*;
+use schema::exchange_rates;
+use serde::de::Error as DeserializeError;
+use serde::{Deserialize, Deserializer, Serializer};
+use serde_json::Value;
+use std::str::FromStr;
+use utils::errors::*;
+use uuid::Uuid;
+use validator::Validate;
+use validators;
+use validators::*;
+
+#[derive(Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[table_name = "exchange_rates"]
+pub struct ExchangeRate {
+    pub id: Uuid,
+    pub base_currency: String,
+    pub target_currency: String,
+    #[serde(
+        serialize_with = "serialize_rate",
+        deserialize_with = "deserialize_rate"
+    )]
+    pub rate: BigDecimal,
+    pub effective_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Deserialize, Insertable, Serialize, Validate)]
+#[table_name = "exchange_rates"]
+pub struct NewExchangeRate {
+    #[validate(custom = "validate_currency_code")]
+    pub base_currency: String,
+    #[validate(custom = "validate_currency_code")]
+    pub target_currency: String,
+    #[serde(
+        serialize_with = "serialize_rate",
+        deserialize_with = "deserialize_rate"
+    )]
+    pub rate: BigDecimal,
+    pub effective_at: Option<NaiveDateTime>,
+}
+
+impl NewExchangeRate {
+    pub fn commit(self, conn: &PgConnection) -> Result<ExchangeRate, DatabaseError> {
+        let mut validation_errors = self.validate();
+        if self.rate <= decimal("0") {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "rate",
+                Err(create_validation_error(
+                    "rate_must_be_positive",
+                    "Rate must be greater than zero",
+                )),
+            );
+        }
+        validation_errors?;
+
+        diesel::insert_into(exchange_rates::table)
+            .values(&self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create exchange rate")
+    }
+}
+
+impl ExchangeRate {
+    pub fn create(
+        base_currency: &str,
+        target_currency: &str,
+        rate: BigDecimal,
+        effective_at: Option<NaiveDateTime>,
+    ) -> NewExchangeRate {
+        NewExchangeRate {
+            base_currency: base_currency.to_string(),
+            target_currency: target_currency.to_string(),
+            rate,
+            effective_at,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<ExchangeRate, DatabaseError> {
+        exchange_rates::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find exchange rate")
+    }
+
+    pub fn all(conn: &PgConnection) -> Result<Vec<ExchangeRate>, DatabaseError> {
+        exchange_rates::table
+            .order_by((
+                exchange_rates::base_currency,
+                exchange_rates::target_currency,
+                exchange_rates::effective_at.desc(),
+            ))
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load exchange rates")
+    }
+
+    /// Finds the most recent rate for converting `base_currency` into `target_currency` that was
+    /// effective at the given time. An inverse rate is used if only the opposite pair is stored.
+    pub fn find_effective_rate(
+        base_currency: &str,
+        target_currency: &str,
+        at: NaiveDateTime,
+        conn: &PgConnection,
+    ) -> Result<BigDecimal, DatabaseError> {
+        if base_currency == target_currency {
+            return Ok(decimal("1"));
+        }
+
+        let rate: Option<ExchangeRate> = exchange_rates::table
+            .filter(exchange_rates::base_currency.eq(base_currency))
+            .filter(exchange_rates::target_currency.eq(target_currency))
+            .filter(exchange_rates::effective_at.le(at))
+            .order_by(exchange_rates::effective_at.desc())
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Could not load exchange rate")?;
+        if let Some(rate) = rate {
+            return Ok(rate.rate);
+        }
+
+        let inverse_rate: Option<ExchangeRate> = exchange_rates::table
+            .filter(exchange_rates::base_currency.eq(target_currency))
+            .filter(exchange_rates::target_currency.eq(base_currency))
+            .filter(exchange_rates::effective_at.le(at))
+            .order_by(exchange_rates::effective_at.desc())
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Could not load exchange rate")?;
+
+        match inverse_rate {
+            Some(rate) => Ok(decimal("1") / rate.rate),
+            None => DatabaseError::no_results(&format!(
+                "No exchange rate found for {} to {}",
+                base_currency, target_currency
+            )),
+        }
+    }
+
+    /// Converted amounts are rounded half away from zero to the nearest cent
+    pub fn convert(
+        amount_in_cents: i64,
+        from_currency: &str,
+        to_currency: &str,
+        at: NaiveDateTime,
+        conn: &PgConnection,
+    ) -> Result<i64, DatabaseError> {
+        let rate = ExchangeRate::find_effective_rate(from_currency, to_currency, at, conn)?;
+        let converted = decimal(&amount_in_cents.to_string()) * rate;
+        let rounded = if converted < decimal("0") {
+            converted - decimal("0.5")
+        } else {
+            converted + decimal("0.5")
+        }
+        .with_scale(0);
+        rounded.to_string().parse::<i64>().map_err(|_| {
+            DatabaseError::new(
+                ErrorCode::BusinessProcessError,
+                Some("Converted amount is too large".to_string()),
+            )
+        })
+    }
+
+    pub fn destroy(self, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        diesel::delete(&self)
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not delete exchange rate")
+    }
+}
+
+fn decimal(value: &str) -> BigDecimal {
+    BigDecimal::from_str(value).unwrap()
+}
+
+/// Rates are serialized as strings so no precision is lost
+fn serialize_rate<S>(rate: &BigDecimal, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(&rate.to_string())
+}
+
+/// Accepts rates as either strings or numbers
+fn deserialize_rate<'de, D>(deserializer: D) -> Result<BigDecimal, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value: Value = Deserialize::deserialize(deserializer)?;
+    let rate = match value {
+        Value::String(ref rate) => BigDecimal::from_str(rate).ok(),
+        Value::Number(ref rate) => BigDecimal::from_str(&rate.to_string()).ok(),
+        _ => None,
+    };
+    rate.ok_or_else(|| D::Error::custom("Rate must be a decimal number"))
+}
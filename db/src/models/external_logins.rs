@@ -3,11 +3,14 @@ use diesel;
 use diesel::prelude::*;
 use models::User;
 use schema::external_logins;
+use utils::errors::ConvertToDatabaseError;
 use utils::errors::DatabaseError;
 use utils::errors::ErrorCode;
 use uuid::Uuid;
 
 pub const FACEBOOK_SITE: &str = "facebook.com";
+pub const GOOGLE_SITE: &str = "google.com";
+pub const APPLE_SITE: &str = "apple.com";
 
 #[derive(Identifiable, Associations, Queryable)]
 #[belongs_to(User, foreign_key = "user_id")]
@@ -18,6 +21,7 @@ pub struct ExternalLogin {
     pub user_id: Uuid,
     pub created_at: NaiveDateTime,
     pub site: String,
+    #[serde(default, skip_serializing)]
     pub access_token: String,
     pub external_user_id: String,
     pub updated_at: NaiveDateTime,
@@ -91,4 +95,21 @@ impl ExternalLogin {
                 .optional(),
         )
     }
+
+    pub fn find_for_user(
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<ExternalLogin>, DatabaseError> {
+        external_logins::table
+            .filter(external_logins::user_id.eq(user_id))
+            .order_by(external_logins::site)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading external logins")
+    }
+
+    pub fn destroy(self, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        diesel::delete(&self)
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not delete external login")
+    }
 }
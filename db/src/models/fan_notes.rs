@@ -0,0 +1,83 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::prelude::*;
+use models::*;
+use schema::fan_notes;
+use utils::errors::*;
+use uuid::Uuid;
+use validator::Validate;
+
+#[derive(
+    Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize,
+)]
+#[belongs_to(Organization)]
+#[table_name = "fan_notes"]
+pub struct FanNote {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub user_id: Uuid,
+    pub author_id: Uuid,
+    pub note: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+/// A note about a fan, only visible to the organization that wrote it
+#[derive(Clone, Debug, Deserialize, Insertable, Serialize, Validate)]
+#[table_name = "fan_notes"]
+pub struct NewFanNote {
+    pub organization_id: Uuid,
+    pub user_id: Uuid,
+    pub author_id: Uuid,
+    #[validate(length(min = "1", message = "Note is required"))]
+    pub note: String,
+}
+
+impl NewFanNote {
+    pub fn commit(self, conn: &PgConnection) -> Result<FanNote, DatabaseError> {
+        self.validate()?;
+
+        diesel::insert_into(fan_notes::table)
+            .values(&self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create fan note")
+    }
+}
+
+impl FanNote {
+    pub fn create(organization_id: Uuid, user_id: Uuid, author_id: Uuid, note: &str) -> NewFanNote {
+        NewFanNote {
+            organization_id,
+            user_id,
+            author_id,
+            note: note.to_string(),
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<FanNote, DatabaseError> {
+        fan_notes::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find fan note")
+    }
+
+    /// Notes about the fan, newest first
+    pub fn find_for_fan(
+        organization_id: Uuid,
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<FanNote>, DatabaseError> {
+        fan_notes::table
+            .filter(fan_notes::organization_id.eq(organization_id))
+            .filter(fan_notes::user_id.eq(user_id))
+            .order_by(fan_notes::created_at.desc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load fan notes")
+    }
+
+    pub fn destroy(self, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        diesel::delete(&self)
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not delete fan note")
+    }
+}
@@ -0,0 +1,281 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use diesel::sql_types::{Array, BigInt, Nullable, Text, Timestamp, Uuid as dUuid};
+use models::*;
+use schema::fan_segments;
+use serde_json;
+use serde_json::Value;
+use utils::errors::*;
+use uuid::Uuid;
+use validator::Validate;
+use validators;
+use validators::*;
+
+#[derive(
+    Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize,
+)]
+#[belongs_to(Organization)]
+#[table_name = "fan_segments"]
+pub struct FanSegment {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub name: String,
+    pub criteria: Value,
+    pub created_by_user_id: Uuid,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+/// The conditions a fan must meet to belong to a segment, all conditions that are set must be
+/// met. Purchases are limited to the last `purchased_within_days` days when set, the genre
+/// matches events the fan bought tickets for and the fan must have every tag.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct FanSegmentCriteria {
+    #[serde(default)]
+    pub purchased_within_days: Option<i64>,
+    #[serde(default)]
+    pub min_event_count: Option<i64>,
+    #[serde(default)]
+    pub min_revenue_in_cents: Option<i64>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub genre: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Insertable, Serialize, Validate)]
+#[table_name = "fan_segments"]
+pub struct NewFanSegment {
+    pub organization_id: Uuid,
+    #[validate(length(min = "1", message = "Name is required"))]
+    pub name: String,
+    pub criteria: Value,
+    pub created_by_user_id: Uuid,
+}
+
+#[derive(AsChangeset, Default, Deserialize, Validate)]
+#[table_name = "fan_segments"]
+pub struct FanSegmentEditableAttributes {
+    #[validate(length(min = "1", message = "Name is required"))]
+    pub name: Option<String>,
+    pub criteria: Option<Value>,
+}
+
+impl NewFanSegment {
+    pub fn commit(self, conn: &PgConnection) -> Result<FanSegment, DatabaseError> {
+        let validation_errors = validators::append_validation_error(
+            self.validate(),
+            "criteria",
+            criteria_valid(&self.criteria),
+        );
+        validation_errors?;
+
+        diesel::insert_into(fan_segments::table)
+            .values(&self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create fan segment")
+    }
+}
+
+impl FanSegment {
+    pub fn create(
+        organization_id: Uuid,
+        name: &str,
+        criteria: &FanSegmentCriteria,
+        created_by_user_id: Uuid,
+    ) -> NewFanSegment {
+        NewFanSegment {
+            organization_id,
+            name: name.to_string(),
+            criteria: json!(criteria),
+            created_by_user_id,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<FanSegment, DatabaseError> {
+        fan_segments::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find fan segment")
+    }
+
+    pub fn find_for_organization(
+        organization_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<FanSegment>, DatabaseError> {
+        fan_segments::table
+            .filter(fan_segments::organization_id.eq(organization_id))
+            .order_by(fan_segments::name)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load fan segments")
+    }
+
+    pub fn update(
+        &self,
+        attributes: FanSegmentEditableAttributes,
+        conn: &PgConnection,
+    ) -> Result<FanSegment, DatabaseError> {
+        let mut validation_errors = attributes.validate();
+        if let Some(ref criteria) = attributes.criteria {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "criteria",
+                criteria_valid(criteria),
+            );
+        }
+        validation_errors?;
+
+        diesel::update(self)
+            .set((attributes, fan_segments::updated_at.eq(dsl::now)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update fan segment")
+    }
+
+    pub fn destroy(self, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        diesel::delete(&self)
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not delete fan segment")
+    }
+
+    pub fn organization(&self, conn: &PgConnection) -> Result<Organization, DatabaseError> {
+        Organization::find(self.organization_id, conn)
+    }
+
+    pub fn criteria(&self) -> Result<FanSegmentCriteria, DatabaseError> {
+        serde_json::from_value(self.criteria.clone()).map_err(|e| {
+            DatabaseError::new(
+                ErrorCode::InvalidInput,
+                Some(format!("Invalid fan segment criteria: {}", e)),
+            )
+        })
+    }
+
+    /// Fans currently matching the segment's criteria, most recent purchasers first
+    pub fn members(
+        &self,
+        page: u32,
+        limit: u32,
+        conn: &PgConnection,
+    ) -> Result<Payload<DisplayFan>, DatabaseError> {
+        let (fans, total) = self.load_members(None, page, limit, conn)?;
+        let mut payload = Payload::new(fans, Paging::new(page, limit));
+        payload.paging.total = total as u64;
+        Ok(payload)
+    }
+
+    pub fn member_count(&self, conn: &PgConnection) -> Result<i64, DatabaseError> {
+        Ok(self.load_members(None, 0, 1, conn)?.1)
+    }
+
+    pub fn has_member(&self, user_id: Uuid, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        Ok(self.load_members(Some(user_id), 0, 1, conn)?.1 > 0)
+    }
+
+    fn load_members(
+        &self,
+        user_id: Option<Uuid>,
+        page: u32,
+        limit: u32,
+        conn: &PgConnection,
+    ) -> Result<(Vec<DisplayFan>, i64), DatabaseError> {
+        let criteria = self.criteria()?;
+        let purchased_since = criteria
+            .purchased_within_days
+            .map(|days| Utc::now().naive_utc() - Duration::days(days));
+        let tags: Vec<String> = criteria
+            .tags
+            .iter()
+            .map(|t| t.trim().to_lowercase())
+            .collect();
+
+        #[derive(QueryableByName)]
+        struct R {
+            #[sql_type = "dUuid"]
+            organization_id: Uuid,
+            #[sql_type = "dUuid"]
+            user_id: Uuid,
+            #[sql_type = "Nullable<Text>"]
+            first_name: Option<String>,
+            #[sql_type = "Nullable<Text>"]
+            last_name: Option<String>,
+            #[sql_type = "Nullable<Text>"]
+            email: Option<String>,
+            #[sql_type = "Nullable<Text>"]
+            phone: Option<String>,
+            #[sql_type = "Nullable<Text>"]
+            thumb_profile_pic_url: Option<String>,
+            #[sql_type = "Timestamp"]
+            created_at: NaiveDateTime,
+            #[sql_type = "BigInt"]
+            order_count: i64,
+            #[sql_type = "Timestamp"]
+            first_order_time: NaiveDateTime,
+            #[sql_type = "Timestamp"]
+            last_order_time: NaiveDateTime,
+            #[sql_type = "BigInt"]
+            revenue_in_cents: i64,
+            #[sql_type = "BigInt"]
+            total: i64,
+        }
+
+        let query = include_str!("../queries/fan_segment_members.sql");
+        let results: Vec<R> = diesel::sql_query(query)
+            .bind::<dUuid, _>(self.organization_id)
+            .bind::<Nullable<Timestamp>, _>(purchased_since)
+            .bind::<Nullable<BigInt>, _>(criteria.min_event_count)
+            .bind::<Nullable<BigInt>, _>(criteria.min_revenue_in_cents)
+            .bind::<Nullable<Text>, _>(criteria.genre)
+            .bind::<Array<Text>, _>(tags)
+            .bind::<BigInt, _>(limit as i64)
+            .bind::<BigInt, _>((page * limit) as i64)
+            .bind::<Nullable<dUuid>, _>(user_id)
+            .get_results(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load fan segment members")?;
+
+        let total = results.first().map(|r| r.total).unwrap_or(0);
+        let fans = results
+            .into_iter()
+            .map(|r| DisplayFan {
+                user_id: r.user_id,
+                first_name: r.first_name,
+                last_name: r.last_name,
+                email: r.email,
+                phone: r.phone,
+                thumb_profile_pic_url: r.thumb_profile_pic_url,
+                organization_id: r.organization_id,
+                order_count: Some(r.order_count as u32),
+                created_at: r.created_at,
+                first_order_time: Some(r.first_order_time),
+                last_order_time: Some(r.last_order_time),
+                revenue_in_cents: Some(r.revenue_in_cents),
+            })
+            .collect();
+        Ok((fans, total))
+    }
+}
+
+fn criteria_valid(criteria: &Value) -> Result<(), ::validator::ValidationError> {
+    let criteria: FanSegmentCriteria = match serde_json::from_value(criteria.clone()) {
+        Ok(criteria) => criteria,
+        Err(_) => {
+            return Err(create_validation_error(
+                "invalid_criteria",
+                "Segment criteria are not valid",
+            ));
+        }
+    };
+    let negative = |value: Option<i64>| value.map(|v| v < 0).unwrap_or(false);
+    if negative(criteria.purchased_within_days)
+        || negative(criteria.min_event_count)
+        || negative(criteria.min_revenue_in_cents)
+    {
+        return Err(create_validation_error(
+            "invalid_criteria",
+            "Segment criteria cannot be negative",
+        ));
+    }
+    Ok(())
+}
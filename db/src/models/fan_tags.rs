@@ -0,0 +1,112 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::prelude::*;
+use models::*;
+use schema::fan_tags;
+use utils::errors::*;
+use uuid::Uuid;
+
+#[derive(
+    Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize,
+)]
+#[belongs_to(Organization)]
+#[belongs_to(User)]
+#[table_name = "fan_tags"]
+pub struct FanTag {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub user_id: Uuid,
+    pub tag: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+/// A label an organization gives to one of its fans. Tags are stored in lowercase so that
+/// segments can match them regardless of how they were entered.
+#[derive(Clone, Debug, Deserialize, Insertable, Serialize)]
+#[table_name = "fan_tags"]
+pub struct NewFanTag {
+    pub organization_id: Uuid,
+    pub user_id: Uuid,
+    pub tag: String,
+}
+
+impl NewFanTag {
+    /// Adding a tag the fan already has returns the existing tag
+    pub fn commit(mut self, conn: &PgConnection) -> Result<FanTag, DatabaseError> {
+        self.tag = self.tag.trim().to_lowercase();
+        if self.tag.is_empty() {
+            return DatabaseError::validation_error("tag", "Tag is required");
+        }
+
+        if let Some(fan_tag) = fan_tags::table
+            .filter(fan_tags::organization_id.eq(self.organization_id))
+            .filter(fan_tags::user_id.eq(self.user_id))
+            .filter(fan_tags::tag.eq(&self.tag))
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Could not load fan tag")?
+        {
+            return Ok(fan_tag);
+        }
+
+        diesel::insert_into(fan_tags::table)
+            .values(&self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create fan tag")
+    }
+}
+
+impl FanTag {
+    pub fn create(organization_id: Uuid, user_id: Uuid, tag: &str) -> NewFanTag {
+        NewFanTag {
+            organization_id,
+            user_id,
+            tag: tag.to_string(),
+        }
+    }
+
+    pub fn find_for_fan(
+        organization_id: Uuid,
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<FanTag>, DatabaseError> {
+        fan_tags::table
+            .filter(fan_tags::organization_id.eq(organization_id))
+            .filter(fan_tags::user_id.eq(user_id))
+            .order_by(fan_tags::tag)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load fan tags")
+    }
+
+    /// Every tag in use by the organization
+    pub fn find_tags_for_organization(
+        organization_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<String>, DatabaseError> {
+        fan_tags::table
+            .filter(fan_tags::organization_id.eq(organization_id))
+            .select(fan_tags::tag)
+            .distinct()
+            .order_by(fan_tags::tag)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load fan tags")
+    }
+
+    /// Removes the tag from the fan, returning the number of tags removed
+    pub fn remove(
+        organization_id: Uuid,
+        user_id: Uuid,
+        tag: &str,
+        conn: &PgConnection,
+    ) -> Result<usize, DatabaseError> {
+        diesel::delete(
+            fan_tags::table
+                .filter(fan_tags::organization_id.eq(organization_id))
+                .filter(fan_tags::user_id.eq(user_id))
+                .filter(fan_tags::tag.eq(tag.trim().to_lowercase())),
+        )
+        .execute(conn)
+        .to_db_error(ErrorCode::DeleteError, "Could not remove fan tag")
+    }
+}
@@ -1,6 +1,7 @@
 use chrono::NaiveDateTime;
 use diesel::prelude::*;
 use schema::fee_schedule_ranges;
+use std::cmp;
 use utils::errors::ConvertToDatabaseError;
 use utils::errors::DatabaseError;
 use utils::errors::ErrorCode;
@@ -18,13 +19,33 @@ pub struct FeeScheduleRange {
     pub updated_at: NaiveDateTime,
     pub company_fee_in_cents: i64,
     pub client_fee_in_cents: i64,
+    pub company_fee_in_basis_points: i64,
+    pub client_fee_in_basis_points: i64,
+    pub min_fee_in_cents: Option<i64>,
+    pub max_fee_in_cents: Option<i64>,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Default, Serialize, Deserialize)]
 pub struct NewFeeScheduleRange {
     pub min_price_in_cents: i64,
     pub company_fee_in_cents: i64,
     pub client_fee_in_cents: i64,
+    #[serde(default)]
+    pub company_fee_in_basis_points: i64,
+    #[serde(default)]
+    pub client_fee_in_basis_points: i64,
+    #[serde(default)]
+    pub min_fee_in_cents: Option<i64>,
+    #[serde(default)]
+    pub max_fee_in_cents: Option<i64>,
+}
+
+/// Fees due on a single unit with the company and client portions
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct CalculatedFees {
+    pub fee_in_cents: i64,
+    pub company_fee_in_cents: i64,
+    pub client_fee_in_cents: i64,
 }
 
 impl FeeScheduleRange {
@@ -34,6 +55,51 @@ impl FeeScheduleRange {
             .first::<FeeScheduleRange>(conn)
             .to_db_error(ErrorCode::QueryError, "Error loading fee schedule range")
     }
+
+    /// Calculates the fee for a unit price. Each portion is a fixed amount plus a percentage
+    /// of the price. The minimum and maximum apply to the total fee, with the company and
+    /// client portions scaled so that the split between them is preserved.
+    pub fn calculate_fees(&self, price_in_cents: i64) -> CalculatedFees {
+        let company_fee_in_cents = self.company_fee_in_cents
+            + percentage_of(price_in_cents, self.company_fee_in_basis_points);
+        let client_fee_in_cents = self.client_fee_in_cents
+            + percentage_of(price_in_cents, self.client_fee_in_basis_points);
+        let fee_in_cents = company_fee_in_cents + client_fee_in_cents;
+
+        let mut limited_fee_in_cents = fee_in_cents;
+        if let Some(min_fee_in_cents) = self.min_fee_in_cents {
+            limited_fee_in_cents = cmp::max(limited_fee_in_cents, min_fee_in_cents);
+        }
+        if let Some(max_fee_in_cents) = self.max_fee_in_cents {
+            limited_fee_in_cents = cmp::min(limited_fee_in_cents, max_fee_in_cents);
+        }
+
+        if limited_fee_in_cents == fee_in_cents {
+            return CalculatedFees {
+                fee_in_cents,
+                company_fee_in_cents,
+                client_fee_in_cents,
+            };
+        }
+
+        // If there is no fee to split the minimum is taken by the company
+        let company_fee_in_cents = if fee_in_cents == 0 {
+            limited_fee_in_cents
+        } else {
+            (company_fee_in_cents as f64 * limited_fee_in_cents as f64 / fee_in_cents as f64)
+                .round() as i64
+        };
+
+        CalculatedFees {
+            fee_in_cents: limited_fee_in_cents,
+            company_fee_in_cents,
+            client_fee_in_cents: limited_fee_in_cents - company_fee_in_cents,
+        }
+    }
+}
+
+fn percentage_of(price_in_cents: i64, basis_points: i64) -> i64 {
+    (price_in_cents as f64 * basis_points as f64 / 10_000f64).round() as i64
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
@@ -42,6 +108,13 @@ pub struct DisplayFeeScheduleRange {
     pub fee_schedule_id: Uuid,
     pub min_price_in_cents: i64,
     pub fee_in_cents: i64,
+    pub fee_in_basis_points: i64,
+    pub company_fee_in_cents: i64,
+    pub client_fee_in_cents: i64,
+    pub company_fee_in_basis_points: i64,
+    pub client_fee_in_basis_points: i64,
+    pub min_fee_in_cents: Option<i64>,
+    pub max_fee_in_cents: Option<i64>,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
 }
@@ -53,6 +126,14 @@ impl From<FeeScheduleRange> for DisplayFeeScheduleRange {
             fee_schedule_id: fee_schedule_range.fee_schedule_id,
             min_price_in_cents: fee_schedule_range.min_price_in_cents,
             fee_in_cents: fee_schedule_range.fee_in_cents,
+            fee_in_basis_points: fee_schedule_range.company_fee_in_basis_points
+                + fee_schedule_range.client_fee_in_basis_points,
+            company_fee_in_cents: fee_schedule_range.company_fee_in_cents,
+            client_fee_in_cents: fee_schedule_range.client_fee_in_cents,
+            company_fee_in_basis_points: fee_schedule_range.company_fee_in_basis_points,
+            client_fee_in_basis_points: fee_schedule_range.client_fee_in_basis_points,
+            min_fee_in_cents: fee_schedule_range.min_fee_in_cents,
+            max_fee_in_cents: fee_schedule_range.max_fee_in_cents,
             created_at: fee_schedule_range.created_at,
             updated_at: fee_schedule_range.updated_at,
         }
@@ -8,6 +8,7 @@ use utils::errors::ConvertToDatabaseError;
 use utils::errors::DatabaseError;
 use utils::errors::ErrorCode;
 use uuid::Uuid;
+use validators::{self, *};
 
 #[derive(Queryable, Identifiable, Clone, Debug, Serialize)]
 pub struct FeeSchedule {
@@ -74,11 +75,44 @@ pub struct NewFeeSchedule {
 }
 
 impl NewFeeSchedule {
+    fn validate_record(&self) -> Result<(), DatabaseError> {
+        let mut validation_errors = Ok(());
+        for range in &self.ranges {
+            if range.company_fee_in_basis_points < 0 || range.client_fee_in_basis_points < 0 {
+                validation_errors = validators::append_validation_error(
+                    validation_errors,
+                    "ranges",
+                    Err(create_validation_error(
+                        "fee_percentage_must_not_be_negative",
+                        "Fee percentage cannot be negative",
+                    )),
+                );
+            }
+            if let (Some(min_fee_in_cents), Some(max_fee_in_cents)) =
+                (range.min_fee_in_cents, range.max_fee_in_cents)
+            {
+                if min_fee_in_cents > max_fee_in_cents {
+                    validation_errors = validators::append_validation_error(
+                        validation_errors,
+                        "ranges",
+                        Err(create_validation_error(
+                            "min_fee_greater_than_max_fee",
+                            "Minimum fee cannot be greater than maximum fee",
+                        )),
+                    );
+                }
+            }
+        }
+        Ok(validation_errors?)
+    }
+
     pub fn commit(
         self,
         created_by_user_id: Uuid,
         conn: &PgConnection,
     ) -> Result<FeeSchedule, DatabaseError> {
+        self.validate_record()?;
+
         let previous_version = fee_schedules::table
             .filter(fee_schedules::name.eq(&self.name))
             .order_by(fee_schedules::version.desc())
@@ -108,6 +142,10 @@ impl NewFeeSchedule {
             fee_in_cents: i64,
             company_fee_in_cents: i64,
             client_fee_in_cents: i64,
+            company_fee_in_basis_points: i64,
+            client_fee_in_basis_points: i64,
+            min_fee_in_cents: Option<i64>,
+            max_fee_in_cents: Option<i64>,
         }
         let mut ranges = Vec::<I>::new();
         for range in &self.ranges {
@@ -117,6 +155,10 @@ impl NewFeeSchedule {
                 fee_in_cents: range.company_fee_in_cents + range.client_fee_in_cents,
                 company_fee_in_cents: range.company_fee_in_cents,
                 client_fee_in_cents: range.client_fee_in_cents,
+                company_fee_in_basis_points: range.company_fee_in_basis_points,
+                client_fee_in_basis_points: range.client_fee_in_basis_points,
+                min_fee_in_cents: range.min_fee_in_cents,
+                max_fee_in_cents: range.max_fee_in_cents,
             })
         }
         diesel::insert_into(fee_schedule_ranges::table)
@@ -1,14 +1,28 @@
+pub use self::affiliate_clicks::*;
+pub use self::affiliate_commission_rules::*;
+pub use self::affiliates::*;
 pub use self::artists::*;
 pub use self::assets::*;
+pub use self::audit_logs::*;
+pub use self::campaign_recipients::*;
+pub use self::campaigns::*;
 pub use self::codes::*;
+pub use self::communication_consents::*;
+pub use self::communication_preferences::*;
 pub use self::domain_actions::*;
 pub use self::domain_events::*;
 pub use self::enums::*;
 pub use self::event_artists::*;
 pub use self::event_interest::*;
+pub use self::event_split_partners::*;
+pub use self::event_users::*;
 pub use self::events::*;
+pub use self::exchange_rates::*;
 pub use self::external_logins::FACEBOOK_SITE;
 pub use self::external_logins::*;
+pub use self::fan_notes::*;
+pub use self::fan_segments::*;
+pub use self::fan_tags::*;
 pub use self::fans::*;
 pub use self::fee_schedule_ranges::*;
 pub use self::fee_schedules::*;
@@ -17,24 +31,36 @@ pub use self::history_item::*;
 pub use self::holds::*;
 pub use self::order_items::*;
 pub use self::orders::*;
+pub use self::organization_api_keys::*;
 pub use self::organization_invites::*;
+pub use self::organization_roles::*;
 pub use self::organization_users::*;
 pub use self::organizations::*;
 pub use self::paging::*;
 pub use self::payment_methods::*;
 pub use self::payments::*;
+pub use self::phone_verifications::*;
 pub use self::push_notification_tokens::*;
+pub use self::rate_limit_counters::*;
 pub use self::redeemable_ticket::*;
 pub use self::refunded_tickets::*;
 pub use self::regions::*;
+pub use self::report_deliveries::*;
+pub use self::report_subscriptions::*;
 pub use self::reports::*;
 pub use self::scopes::*;
+pub use self::settlement_adjustments::*;
+pub use self::settlement_partner_shares::*;
+pub use self::settlements::*;
 pub use self::stages::*;
+pub use self::tax_rules::*;
 pub use self::ticket_instances::RedeemResults;
 pub use self::ticket_instances::*;
 pub use self::ticket_pricing::*;
 pub use self::ticket_type_codes::*;
 pub use self::ticket_types::*;
+pub use self::two_factor_authentications::*;
+pub use self::user_sessions::*;
 pub use self::users::*;
 pub use self::venues::*;
 pub use self::wallets::*;
@@ -44,16 +70,30 @@ use serde_json::Value;
 
 pub mod concerns;
 
+mod affiliate_clicks;
+mod affiliate_commission_rules;
+mod affiliates;
 mod artists;
 mod assets;
+mod audit_logs;
+mod campaign_recipients;
+mod campaigns;
 mod codes;
+mod communication_consents;
+mod communication_preferences;
 mod domain_actions;
 mod domain_events;
 pub mod enums;
 mod event_artists;
 mod event_interest;
+mod event_split_partners;
+mod event_users;
 mod events;
+mod exchange_rates;
 mod external_logins;
+mod fan_notes;
+mod fan_segments;
+mod fan_tags;
 mod fans;
 mod fee_schedule_ranges;
 mod fee_schedules;
@@ -62,23 +102,35 @@ mod history_item;
 mod holds;
 mod order_items;
 mod orders;
+mod organization_api_keys;
 mod organization_invites;
+mod organization_roles;
 mod organization_users;
 mod organizations;
 mod paging;
 mod payment_methods;
 mod payments;
+mod phone_verifications;
 mod push_notification_tokens;
+mod rate_limit_counters;
 mod redeemable_ticket;
 mod refunded_tickets;
 mod regions;
+mod report_deliveries;
+mod report_subscriptions;
 mod reports;
 pub mod scopes;
+mod settlement_adjustments;
+mod settlement_partner_shares;
+mod settlements;
 mod stages;
+mod tax_rules;
 mod ticket_instances;
 mod ticket_pricing;
 mod ticket_type_codes;
 mod ticket_types;
+mod two_factor_authentications;
+mod user_sessions;
 mod users;
 mod venues;
 mod wallets;
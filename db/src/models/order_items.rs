@@ -7,6 +7,7 @@ use diesel::sql_types::{BigInt, Bool, Nullable, Text, Uuid as dUuid};
 use models::*;
 use schema::{codes, order_items, ticket_instances, ticket_types};
 use std::borrow::Cow;
+use std::collections::HashMap;
 use utils::errors::*;
 use uuid::Uuid;
 use validator::*;
@@ -39,6 +40,10 @@ pub struct OrderItem {
     pub(crate) company_fee_in_cents: i64,
     pub(crate) client_fee_in_cents: i64,
     pub refunded_quantity: i64,
+    pub currency: String,
+    pub tax_rule_id: Option<Uuid>,
+    pub tax_in_cents: i64,
+    pub fee_mode: FeeModes,
 }
 
 impl OrderItem {
@@ -51,6 +56,14 @@ impl OrderItem {
             .to_db_error(ErrorCode::QueryError, "Could not retrieve order item fees")
     }
 
+    pub fn find_tax_items(&self, conn: &PgConnection) -> Result<Vec<OrderItem>, DatabaseError> {
+        order_items::table
+            .filter(order_items::parent_id.eq(self.id))
+            .filter(order_items::item_type.eq(OrderItemTypes::Tax))
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not retrieve order item taxes")
+    }
+
     pub(crate) fn refund_one_unit(
         &mut self,
         refund_fees: bool,
@@ -76,6 +89,14 @@ impl OrderItem {
                 refund_amount_in_cents += fee_item.refund_one_unit(true, conn)? as i64;
             }
         }
+        // Tax is refunded in proportion to the units refunded
+        if self.item_type != OrderItemTypes::Tax {
+            for mut tax_item in self.find_tax_items(conn)? {
+                if tax_item.refunded_quantity < tax_item.quantity {
+                    refund_amount_in_cents += tax_item.refund_one_unit(false, conn)? as i64;
+                }
+            }
+        }
 
         diesel::update(order_items::table.filter(order_items::id.eq(self.id)))
             .set((
@@ -111,9 +132,7 @@ impl OrderItem {
         order: &Order,
         conn: &PgConnection,
     ) -> Result<(), DatabaseError> {
-        if self.item_type == OrderItemTypes::PerUnitFees
-            || self.item_type == OrderItemTypes::EventFees
-        {
+        if self.item_type != OrderItemTypes::Tickets {
             return Ok(());
         }
 
@@ -127,6 +146,7 @@ impl OrderItem {
         };
 
         let fee_schedule_ranges = ticket_type.fee_schedule(conn)?.ranges(conn)?;
+        let fee_mode = ticket_type.effective_fee_mode(conn)?;
 
         if fee_schedule_ranges.len() > 0
             && self.unit_price_in_cents >= fee_schedule_ranges[0].min_price_in_cents
@@ -134,6 +154,15 @@ impl OrderItem {
             let fee_schedule_range = ticket_type
                 .fee_schedule(conn)?
                 .get_range(self.unit_price_in_cents, conn)?;
+            let mut fees = fee_schedule_range.calculate_fees(self.unit_price_in_cents);
+            let mut unit_price_in_cents = fees.fee_in_cents;
+
+            // Absorbed fees are not charged to the buyer, the company fee is deducted from
+            // the organizer's settlement instead
+            if fee_mode == FeeModes::Absorbed {
+                unit_price_in_cents = 0;
+                fees.client_fee_in_cents = 0;
+            }
 
             // If the hold is a comp, then there are no fees.
             if let Some(hold_id) = self.hold_id {
@@ -149,9 +178,11 @@ impl OrderItem {
             match fee_item {
                 Some(mut fee_item) => {
                     fee_item.quantity = self.quantity;
-                    fee_item.unit_price_in_cents = fee_schedule_range.fee_in_cents;
-                    fee_item.company_fee_in_cents = fee_schedule_range.company_fee_in_cents;
-                    fee_item.client_fee_in_cents = fee_schedule_range.client_fee_in_cents;
+                    fee_item.unit_price_in_cents = unit_price_in_cents;
+                    fee_item.company_fee_in_cents = fees.company_fee_in_cents;
+                    fee_item.client_fee_in_cents = fees.client_fee_in_cents;
+                    fee_item.fee_schedule_range_id = Some(fee_schedule_range.id);
+                    fee_item.fee_mode = fee_mode;
                     fee_item.update(conn)
                 }
                 None => {
@@ -159,12 +190,14 @@ impl OrderItem {
                         order_id: self.order_id,
                         item_type: OrderItemTypes::PerUnitFees,
                         event_id: self.event_id,
-                        unit_price_in_cents: fee_schedule_range.fee_in_cents,
+                        unit_price_in_cents,
                         fee_schedule_range_id: Some(fee_schedule_range.id),
-                        company_fee_in_cents: fee_schedule_range.company_fee_in_cents,
-                        client_fee_in_cents: fee_schedule_range.client_fee_in_cents,
+                        company_fee_in_cents: fees.company_fee_in_cents,
+                        client_fee_in_cents: fees.client_fee_in_cents,
                         quantity: self.quantity,
                         parent_id: Some(self.id),
+                        currency: self.currency.clone(),
+                        fee_mode,
                     }
                     .commit(conn)?;
 
@@ -185,6 +218,10 @@ impl OrderItem {
             .set((
                 order_items::quantity.eq(self.quantity),
                 order_items::unit_price_in_cents.eq(self.unit_price_in_cents),
+                order_items::fee_schedule_range_id.eq(self.fee_schedule_range_id),
+                order_items::company_fee_in_cents.eq(self.company_fee_in_cents),
+                order_items::client_fee_in_cents.eq(self.client_fee_in_cents),
+                order_items::fee_mode.eq(self.fee_mode),
                 order_items::updated_at.eq(dsl::now),
             ))
             .execute(conn)
@@ -388,9 +425,11 @@ impl OrderItem {
            oi.refunded_quantity,
            oi.unit_price_in_cents,
            oi.item_type,
+           oi.fee_mode,
            CASE
              WHEN item_type = 'PerUnitFees' THEN 'Ticket Fees'
              WHEN item_type = 'EventFees' THEN 'Event Fees - ' || e.name
+             WHEN item_type = 'Tax' THEN 'Tax - ' || tr.name
              ELSE e.name || ' - ' || tt.name
            END AS description,
            COALESCE(h.redemption_code, c.redemption_code) as redemption_code,
@@ -422,6 +461,7 @@ impl OrderItem {
                LIMIT 1
            )
            LEFT JOIN codes c ON oi.code_id = c.id
+           LEFT JOIN tax_rules tr ON oi.tax_rule_id = tr.id
            LEFT JOIN (
                SELECT count(ti.id) as count, oi.id
                FROM order_items oi
@@ -443,6 +483,7 @@ impl OrderItem {
         .bind::<Nullable<Array<dUuid>>, _>(organization_ids)
         .load(conn)
         .to_db_error(ErrorCode::QueryError, "Could not load order items")
+        .map(DisplayOrderItem::apply_fee_modes)
     }
 
     pub fn find_for_order(
@@ -490,6 +531,7 @@ pub(crate) struct NewTicketsOrderItem {
     pub ticket_pricing_id: Uuid,
     pub hold_id: Option<Uuid>,
     pub code_id: Option<Uuid>,
+    pub currency: String,
 }
 
 impl NewTicketsOrderItem {
@@ -554,6 +596,8 @@ pub(crate) struct NewFeesOrderItem {
     pub company_fee_in_cents: i64,
     pub client_fee_in_cents: i64,
     pub parent_id: Option<Uuid>,
+    pub currency: String,
+    pub fee_mode: FeeModes,
 }
 
 impl NewFeesOrderItem {
@@ -565,6 +609,29 @@ impl NewFeesOrderItem {
     }
 }
 
+#[derive(Insertable, Serialize, Deserialize, PartialEq, Debug)]
+#[table_name = "order_items"]
+pub(crate) struct NewTaxOrderItem {
+    pub order_id: Uuid,
+    pub item_type: OrderItemTypes,
+    pub event_id: Option<Uuid>,
+    pub quantity: i64,
+    pub unit_price_in_cents: i64,
+    pub parent_id: Option<Uuid>,
+    pub currency: String,
+    pub tax_rule_id: Option<Uuid>,
+    pub tax_in_cents: i64,
+}
+
+impl NewTaxOrderItem {
+    pub(crate) fn commit(self, conn: &PgConnection) -> Result<OrderItem, DatabaseError> {
+        diesel::insert_into(order_items::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create order item")
+    }
+}
+
 #[derive(Deserialize, Queryable, QueryableByName, Serialize)]
 pub struct DisplayOrderItem {
     #[sql_type = "dUuid"]
@@ -583,6 +650,9 @@ pub struct DisplayOrderItem {
     pub unit_price_in_cents: i64,
     #[sql_type = "Text"]
     pub item_type: OrderItemTypes,
+    #[serde(skip)]
+    #[sql_type = "Text"]
+    pub fee_mode: FeeModes,
     #[sql_type = "Text"]
     pub description: String,
     #[sql_type = "Nullable<Text>"]
@@ -592,3 +662,38 @@ pub struct DisplayOrderItem {
     #[sql_type = "Nullable<Text>"]
     pub cart_item_status: Option<CartItemStatus>,
 }
+
+impl DisplayOrderItem {
+    /// Folds all-in fees into the unit price of the item they are charged on and hides absorbed
+    /// fees so that buyers only see the amounts they pay
+    fn apply_fee_modes(items: Vec<DisplayOrderItem>) -> Vec<DisplayOrderItem> {
+        let mut folded_fee_parents: HashMap<Uuid, Uuid> = HashMap::new();
+        let mut folded_fees_in_cents: HashMap<Uuid, i64> = HashMap::new();
+        for item in items
+            .iter()
+            .filter(|i| i.item_type == OrderItemTypes::PerUnitFees && i.fee_mode == FeeModes::AllIn)
+        {
+            if let Some(parent_id) = item.parent_id {
+                folded_fee_parents.insert(item.id, parent_id);
+                *folded_fees_in_cents.entry(parent_id).or_insert(0) += item.unit_price_in_cents;
+            }
+        }
+
+        items
+            .into_iter()
+            .filter(|i| i.fee_mode != FeeModes::Absorbed && !folded_fee_parents.contains_key(&i.id))
+            .map(|mut i| {
+                if let Some(fee_in_cents) = folded_fees_in_cents.get(&i.id) {
+                    i.unit_price_in_cents += fee_in_cents;
+                }
+                // Tax charged on a folded fee is shown against the ticket instead
+                if let Some(parent_id) = i.parent_id {
+                    if let Some(ticket_item_id) = folded_fee_parents.get(&parent_id) {
+                        i.parent_id = Some(*ticket_item_id);
+                    }
+                }
+                i
+            })
+            .collect()
+    }
+}
@@ -44,6 +44,15 @@ pub struct Order {
     pub box_office_pricing: bool,
     pub checkout_url: Option<String>,
     pub checkout_url_expires: Option<NaiveDateTime>,
+    pub currency: Option<String>,
+    pub referrer: Option<String>,
+    pub utm_source: Option<String>,
+    pub utm_medium: Option<String>,
+    pub utm_campaign: Option<String>,
+    pub utm_term: Option<String>,
+    pub utm_content: Option<String>,
+    pub source_id: Option<String>,
+    pub affiliate_click_id: Option<Uuid>,
 }
 
 #[derive(Insertable)]
@@ -75,6 +84,36 @@ pub struct UpdateOrderAttributes {
     pub note: Option<Option<String>>,
 }
 
+/// Marketing attribution for an order, captured from the UTM parameters and referrer of the
+/// page where the cart was created. The source id identifies a campaign or partner link and the
+/// affiliate click is recorded when the visitor arrived through an affiliate's tracking link.
+#[derive(AsChangeset, Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[table_name = "orders"]
+pub struct OrderAttribution {
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub referrer: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub utm_source: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub utm_medium: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub utm_campaign: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub utm_term: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub utm_content: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub source_id: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub affiliate_click_id: Option<Uuid>,
+}
+
+impl OrderAttribution {
+    pub fn is_empty(&self) -> bool {
+        *self == OrderAttribution::default()
+    }
+}
+
 #[derive(Deserialize, Serialize, Clone)]
 pub struct RefundItem {
     pub order_item_id: Uuid,
@@ -134,7 +173,17 @@ impl Order {
             );
         }
 
-        // delete children order items
+        // delete children order items, including taxes charged on child fees
+        let child_ids: Vec<Option<Uuid>> = order_items::table
+            .filter(order_items::parent_id.eq(item_id))
+            .select(order_items::id.nullable())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load child order items")?;
+        diesel::delete(order_items::table.filter(order_items::parent_id.eq_any(child_ids)))
+            .execute(conn)
+            .map(|_| ())
+            .to_db_error(ErrorCode::DeleteError, "Could not delete child order item")?;
+
         diesel::delete(order_items::table.filter(order_items::parent_id.eq(item_id)))
             .execute(conn)
             .map(|_| ())
@@ -244,7 +293,7 @@ impl Order {
                 select id from order_items oi2
                 where oi2.order_id = order_items.order_id
                 and oi2.event_id = order_items.event_id
-                and item_type <> 'EventFees'
+                and item_type NOT IN ('EventFees', 'Tax')
                 and oi2.refunded_quantity <> oi2.quantity
             )"))
             .select(order_items::all_columns)
@@ -280,6 +329,17 @@ impl Order {
             .to_db_error(ErrorCode::QueryError, "Error loading organizations")
     }
 
+    pub fn events(&self, conn: &PgConnection) -> Result<Vec<Event>, DatabaseError> {
+        events::table
+            .inner_join(order_items::table.on(order_items::event_id.eq(events::id.nullable())))
+            .filter(order_items::order_id.eq(self.id))
+            .select(events::all_columns)
+            .order_by(events::event_start.asc())
+            .distinct()
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Error loading events")
+    }
+
     pub fn payments(&self, conn: &PgConnection) -> Result<Vec<Payment>, DatabaseError> {
         payments::table
             .filter(payments::order_id.eq(self.id))
@@ -505,6 +565,44 @@ impl Order {
         id_string[id_string.len() - ORDER_NUMBER_LENGTH..].to_string()
     }
 
+    pub fn attribution(&self) -> OrderAttribution {
+        OrderAttribution {
+            referrer: self.referrer.clone(),
+            utm_source: self.utm_source.clone(),
+            utm_medium: self.utm_medium.clone(),
+            utm_campaign: self.utm_campaign.clone(),
+            utm_term: self.utm_term.clone(),
+            utm_content: self.utm_content.clone(),
+            source_id: self.source_id.clone(),
+            affiliate_click_id: self.affiliate_click_id,
+        }
+    }
+
+    /// Attribution is first touch, it is only recorded while the order has none. Unknown
+    /// affiliate clicks are ignored.
+    pub fn set_attribution(
+        self,
+        mut attribution: OrderAttribution,
+        conn: &PgConnection,
+    ) -> Result<Order, DatabaseError> {
+        if let Some(affiliate_click_id) = attribution.affiliate_click_id {
+            if AffiliateClick::find(affiliate_click_id, conn)
+                .optional()?
+                .is_none()
+            {
+                attribution.affiliate_click_id = None;
+            }
+        }
+        if attribution.is_empty() || !self.attribution().is_empty() {
+            return Ok(self);
+        }
+
+        diesel::update(&self)
+            .set((attribution, orders::updated_at.eq(dsl::now)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update order attribution")
+    }
+
     pub fn update(
         self,
         attrs: UpdateOrderAttributes,
@@ -596,6 +694,7 @@ impl Order {
                     None => match Code::find_by_redemption_code(r, conn).optional()? {
                         Some(code) => {
                             code.confirm_code_valid()?;
+                            code.confirm_user_eligible(self.user_id, conn)?;
                             MatchData {
                                 index: Some(index),
                                 hold_id: None,
@@ -671,6 +770,7 @@ impl Order {
                             conn,
                         )?;
                         let ticket_type = TicketType::find(ticket_type_id, conn)?;
+                        let event = Event::find(ticket_type.event_id, conn)?;
 
                         check_ticket_limits.push(LimitCheck {
                             limit_per_person: ticket_type.limit_per_person.clone(),
@@ -705,6 +805,7 @@ impl Order {
                                 unit_price_in_cents: price_in_cents,
                                 hold_id: match_data.hold_id,
                                 code_id: match_data.code_id,
+                                currency: event.currency.clone(),
                             }
                             .commit(conn)?;
                             TicketInstance::reserve_tickets(
@@ -765,6 +866,7 @@ impl Order {
                 conn,
             )?;
             let ticket_type = TicketType::find(match_data.update_order_item.ticket_type_id, conn)?;
+            let event = Event::find(ticket_type.event_id, conn)?;
 
             check_ticket_limits.push(LimitCheck {
                 limit_per_person: ticket_type.limit_per_person.clone(),
@@ -795,6 +897,7 @@ impl Order {
                 unit_price_in_cents: price_in_cents,
                 hold_id: match_data.hold_id,
                 code_id: match_data.code_id,
+                currency: event.currency.clone(),
             }
             .commit(conn)?;
 
@@ -845,11 +948,52 @@ impl Order {
             }
         }
 
+        self.update_currency(conn)?;
         self.update_fees(conn)?;
 
         Ok(())
     }
 
+    /// Sets the order currency from its items. Orders can only be paid in a single currency
+    /// so carts containing tickets priced in different currencies are rejected.
+    fn update_currency(&mut self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        let currencies: Vec<String> = order_items::table
+            .filter(order_items::order_id.eq(self.id))
+            .filter(order_items::item_type.eq(OrderItemTypes::Tickets))
+            .select(order_items::currency)
+            .distinct()
+            .load(conn)
+            .to_db_error(
+                ErrorCode::QueryError,
+                "Could not load order item currencies",
+            )?;
+
+        if currencies.len() > 1 {
+            let mut error = create_validation_error(
+                "multiple_currencies",
+                "Cart cannot contain tickets priced in different currencies",
+            );
+            error.add_param(Cow::from("currencies"), &currencies);
+            let mut errors = ValidationErrors::new();
+            errors.add("currency", error);
+            return Err(errors.into());
+        }
+
+        let currency = currencies.into_iter().next();
+        if currency != self.currency {
+            self.currency = diesel::update(&*self)
+                .set((
+                    orders::currency.eq(&currency),
+                    orders::updated_at.eq(dsl::now),
+                ))
+                .returning(orders::currency)
+                .get_result(conn)
+                .to_db_error(ErrorCode::UpdateError, "Could not update order currency")?;
+        }
+
+        Ok(())
+    }
+
     pub fn has_items(&self, conn: &PgConnection) -> Result<bool, DatabaseError> {
         select(exists(
             order_items::table.filter(order_items::order_id.eq(self.id)),
@@ -866,14 +1010,14 @@ impl Order {
 
         for o in items {
             match o.item_type {
-                OrderItemTypes::EventFees => self.destroy_item(o.id, conn)?,
+                OrderItemTypes::EventFees | OrderItemTypes::Tax => self.destroy_item(o.id, conn)?,
                 _ => {}
             }
         }
 
         // Box office purchased tickets do not have fees at this time
         if self.box_office_pricing {
-            return Ok(());
+            return self.update_taxes(conn);
         }
 
         for ((event_id, hold_id), items) in self
@@ -920,6 +1064,8 @@ impl Order {
                     client_fee_in_cents: 0,
                     quantity: 1,
                     parent_id: None,
+                    currency: event.currency.clone(),
+                    fee_mode: event.fee_mode,
                 };
                 if event.fee_in_cents > 0 {
                     //we dont want to create 0 fee order item
@@ -927,11 +1073,62 @@ impl Order {
                     new_event_fee.client_fee_in_cents = event.client_fee_in_cents;
                     new_event_fee.unit_price_in_cents =
                         event.client_fee_in_cents + event.company_fee_in_cents;
+                    if event.fee_mode == FeeModes::Absorbed {
+                        new_event_fee.client_fee_in_cents = 0;
+                        new_event_fee.unit_price_in_cents = 0;
+                    }
                     new_event_fee.commit(conn)?;
                 }
             }
         }
 
+        self.update_taxes(conn)
+    }
+
+    /// Adds a tax item for each taxable item in the order. Tax items are children of the
+    /// item they are charged on so that they are refunded along with it.
+    fn update_taxes(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        let mut tax_rules: HashMap<Uuid, Vec<TaxRule>> = HashMap::new();
+
+        for item in self.items(conn)? {
+            let event_id = match item.event_id {
+                Some(event_id) => event_id,
+                None => continue,
+            };
+            if item.item_type == OrderItemTypes::Tax {
+                continue;
+            }
+
+            if !tax_rules.contains_key(&event_id) {
+                let event = Event::find(event_id, conn)?;
+                tax_rules.insert(event_id, TaxRule::find_for_event(&event, conn)?);
+            }
+
+            for tax_rule in &tax_rules[&event_id] {
+                if !tax_rule.applies_to(item.item_type) {
+                    continue;
+                }
+                let tax_in_cents = tax_rule.calculate_tax(item.unit_price_in_cents);
+                if tax_in_cents == 0 {
+                    continue;
+                }
+
+                NewTaxOrderItem {
+                    order_id: self.id,
+                    item_type: OrderItemTypes::Tax,
+                    event_id: item.event_id,
+                    quantity: item.quantity,
+                    // Inclusive tax is already part of the item price
+                    unit_price_in_cents: if tax_rule.inclusive { 0 } else { tax_in_cents },
+                    parent_id: Some(item.id),
+                    currency: item.currency.clone(),
+                    tax_rule_id: Some(tax_rule.id),
+                    tax_in_cents,
+                }
+                .commit(conn)?;
+            }
+        }
+
         Ok(())
     }
 
@@ -1155,11 +1352,19 @@ impl Order {
         }
     }
 
+    /// Orders without items (e.g. free carts) fall back to the configured default currency
+    pub fn payment_currency(&self, default_currency: &str) -> String {
+        self.currency
+            .clone()
+            .unwrap_or_else(|| default_currency.to_uppercase())
+    }
+
     pub fn add_external_payment(
         &mut self,
         external_reference: Option<String>,
         current_user_id: Uuid,
         amount: i64,
+        default_currency: &str,
         conn: &PgConnection,
     ) -> Result<Payment, DatabaseError> {
         let payment = Payment::create(
@@ -1170,6 +1375,7 @@ impl Order {
             "External".to_string(),
             external_reference,
             amount,
+            self.payment_currency(default_currency),
             None,
             None,
         );
@@ -1185,6 +1391,7 @@ impl Order {
         status: PaymentStatus,
         url_nonce: Option<String>,
         data: Value,
+        default_currency: &str,
         conn: &PgConnection,
     ) -> Result<Payment, DatabaseError> {
         let payment = Payment::create(
@@ -1195,6 +1402,7 @@ impl Order {
             provider,
             external_reference,
             amount,
+            self.payment_currency(default_currency),
             Some(data),
             url_nonce,
         );
@@ -1210,6 +1418,7 @@ impl Order {
         external_reference: String,
         status: PaymentStatus,
         provider_data: serde_json::Value,
+        default_currency: &str,
         conn: &PgConnection,
     ) -> Result<Payment, DatabaseError> {
         let payment = Payment::create(
@@ -1220,6 +1429,7 @@ impl Order {
             provider,
             Some(external_reference),
             amount,
+            self.payment_currency(default_currency),
             Some(provider_data),
             None,
         );
@@ -0,0 +1,198 @@
+use chrono::{Duration, NaiveDateTime, Utc};
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::{events, organization_api_keys};
+use utils::encryption;
+use utils::errors::*;
+use utils::rand::random_alpha_string;
+use uuid::Uuid;
+
+/// Prefix of every key, so keys can be told apart from access tokens and found if leaked
+pub const API_KEY_PREFIX: &str = "bnk_";
+const API_KEY_LENGTH: usize = 40;
+/// Characters of the key kept in plain text so the owner can recognise it
+const KEY_PREFIX_LENGTH: usize = 12;
+/// `last_used_at` is only written when it is older than this, rather than on every request
+const LAST_USED_RESOLUTION_IN_MINUTES: i64 = 1;
+
+/// A key an integration uses in place of a person's login. It acts as its own service account
+/// user and only has the listed scopes, for the listed events when there are any.
+#[derive(Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[table_name = "organization_api_keys"]
+pub struct OrganizationApiKey {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub key_prefix: String,
+    #[serde(skip_serializing)]
+    pub key_hash: String,
+    pub scopes: Vec<String>,
+    pub event_ids: Vec<Uuid>,
+    pub expires_at: Option<NaiveDateTime>,
+    pub last_used_at: Option<NaiveDateTime>,
+    pub revoked_at: Option<NaiveDateTime>,
+    pub created_by_user_id: Uuid,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "organization_api_keys"]
+struct NewOrganizationApiKey {
+    organization_id: Uuid,
+    user_id: Uuid,
+    name: String,
+    key_prefix: String,
+    key_hash: String,
+    scopes: Vec<String>,
+    event_ids: Vec<Uuid>,
+    expires_at: Option<NaiveDateTime>,
+    created_by_user_id: Uuid,
+}
+
+impl OrganizationApiKey {
+    /// Creates the key and its service account user. The key itself is returned here only,
+    /// just its hash is stored.
+    pub fn create(
+        organization_id: Uuid,
+        name: &str,
+        scopes: Vec<Scopes>,
+        event_ids: Vec<Uuid>,
+        expires_at: Option<NaiveDateTime>,
+        created_by_user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<(OrganizationApiKey, String), DatabaseError> {
+        if name.trim().is_empty() {
+            return DatabaseError::validation_error("name", "API key name is required");
+        }
+        if scopes.is_empty() {
+            return DatabaseError::validation_error("scopes", "At least one scope is required");
+        }
+        if let Some(expires_at) = expires_at {
+            if expires_at <= Utc::now().naive_utc() {
+                return DatabaseError::validation_error(
+                    "expires_at",
+                    "Expiry must be in the future",
+                );
+            }
+        }
+        if !event_ids.is_empty() {
+            let organization_event_count: i64 = events::table
+                .filter(events::id.eq_any(&event_ids))
+                .filter(events::organization_id.eq(organization_id))
+                .select(dsl::count_star())
+                .first(conn)
+                .to_db_error(ErrorCode::QueryError, "Could not check API key events")?;
+            if organization_event_count != event_ids.len() as i64 {
+                return DatabaseError::validation_error(
+                    "event_ids",
+                    "Events must belong to the organization",
+                );
+            }
+        }
+
+        let service_user =
+            User::create_stub(name.to_string(), "API key".to_string(), None, None, conn)?;
+        let key = format!("{}{}", API_KEY_PREFIX, random_alpha_string(API_KEY_LENGTH));
+        let mut scopes: Vec<String> = scopes.iter().map(|s| s.to_string()).collect();
+        scopes.sort();
+        scopes.dedup();
+
+        let api_key: OrganizationApiKey = diesel::insert_into(organization_api_keys::table)
+            .values(NewOrganizationApiKey {
+                organization_id,
+                user_id: service_user.id,
+                name: name.to_string(),
+                key_prefix: key.chars().take(KEY_PREFIX_LENGTH).collect(),
+                key_hash: encryption::hash_secret(&key),
+                scopes,
+                event_ids,
+                expires_at,
+                created_by_user_id,
+            })
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create API key")?;
+        Ok((api_key, key))
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<OrganizationApiKey, DatabaseError> {
+        organization_api_keys::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find API key")
+    }
+
+    /// Keys that have not been revoked, newest first
+    pub fn find_for_organization(
+        organization_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<OrganizationApiKey>, DatabaseError> {
+        organization_api_keys::table
+            .filter(organization_api_keys::organization_id.eq(organization_id))
+            .filter(organization_api_keys::revoked_at.is_null())
+            .order_by(organization_api_keys::created_at.desc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load API keys")
+    }
+
+    /// Finds the key presented by an integration, revoked and expired keys are not found
+    pub fn find_active_by_key(
+        key: &str,
+        conn: &PgConnection,
+    ) -> Result<OrganizationApiKey, DatabaseError> {
+        organization_api_keys::table
+            .filter(organization_api_keys::key_hash.eq(encryption::hash_secret(key)))
+            .filter(organization_api_keys::revoked_at.is_null())
+            .filter(
+                organization_api_keys::expires_at
+                    .is_null()
+                    .or(organization_api_keys::expires_at.gt(dsl::now.nullable())),
+            )
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find API key")
+    }
+
+    pub fn organization(&self, conn: &PgConnection) -> Result<Organization, DatabaseError> {
+        Organization::find(self.organization_id, conn)
+    }
+
+    /// Whether the key allows `scope`, within `event_id` if the key is limited to some events
+    pub fn allows(&self, scope: Scopes, organization_id: Uuid, event_id: Option<Uuid>) -> bool {
+        if organization_id != self.organization_id || !self.scopes.contains(&scope.to_string()) {
+            return false;
+        }
+        if self.event_ids.is_empty() {
+            return true;
+        }
+        match event_id {
+            Some(event_id) => self.event_ids.contains(&event_id),
+            None => false,
+        }
+    }
+
+    pub fn mark_used(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        let resolution =
+            Utc::now().naive_utc() - Duration::minutes(LAST_USED_RESOLUTION_IN_MINUTES);
+        if self.last_used_at.map(|l| l > resolution).unwrap_or(false) {
+            return Ok(());
+        }
+        diesel::update(self)
+            .set(organization_api_keys::last_used_at.eq(dsl::now.nullable()))
+            .execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update API key")?;
+        Ok(())
+    }
+
+    pub fn revoke(&self, conn: &PgConnection) -> Result<OrganizationApiKey, DatabaseError> {
+        diesel::update(self)
+            .set((
+                organization_api_keys::revoked_at.eq(dsl::now.nullable()),
+                organization_api_keys::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not revoke API key")
+    }
+}
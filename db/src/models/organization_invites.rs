@@ -40,6 +40,7 @@ pub struct OrganizationInvite {
     pub updated_at: NaiveDateTime,
     pub sent_invite: bool,
     pub roles: Vec<Roles>,
+    pub organization_role_ids: Vec<Uuid>,
 }
 
 #[derive(Insertable, PartialEq, Debug, Deserialize, Validate)]
@@ -52,6 +53,8 @@ pub struct NewOrganizationInvite {
     pub security_token: Option<Uuid>,
     pub user_id: Option<Uuid>,
     pub roles: Vec<Roles>,
+    #[serde(default)]
+    pub organization_role_ids: Vec<Uuid>,
 }
 
 #[derive(Debug, PartialEq, Queryable, Serialize, QueryableByName)]
@@ -84,6 +87,7 @@ impl OrganizationInvite {
         email: &str,
         user_id: Option<Uuid>,
         roles: Vec<Roles>,
+        organization_role_ids: Vec<Uuid>,
     ) -> NewOrganizationInvite {
         NewOrganizationInvite {
             organization_id: org_id,
@@ -92,6 +96,7 @@ impl OrganizationInvite {
             security_token: None,
             user_id,
             roles,
+            organization_role_ids,
         }
     }
 
@@ -0,0 +1,165 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use diesel::sql_types::Uuid as dUuid;
+use models::*;
+use schema::organization_roles;
+use std::str::FromStr;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// A role an organization composes from scopes, e.g. "Marketing", given to members alongside
+/// the built-in `Roles`
+#[derive(Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+#[table_name = "organization_roles"]
+pub struct OrganizationRole {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "organization_roles"]
+struct NewOrganizationRole {
+    organization_id: Uuid,
+    name: String,
+    scopes: Vec<String>,
+}
+
+impl OrganizationRole {
+    pub fn create(
+        organization_id: Uuid,
+        name: &str,
+        scopes: Vec<Scopes>,
+        conn: &PgConnection,
+    ) -> Result<OrganizationRole, DatabaseError> {
+        let scopes = OrganizationRole::validate(name, scopes)?;
+        diesel::insert_into(organization_roles::table)
+            .values(NewOrganizationRole {
+                organization_id,
+                name: name.trim().to_string(),
+                scopes,
+            })
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create organization role")
+    }
+
+    pub fn update(
+        &self,
+        name: &str,
+        scopes: Vec<Scopes>,
+        conn: &PgConnection,
+    ) -> Result<OrganizationRole, DatabaseError> {
+        let scopes = OrganizationRole::validate(name, scopes)?;
+        diesel::update(self)
+            .set((
+                organization_roles::name.eq(name.trim()),
+                organization_roles::scopes.eq(scopes),
+                organization_roles::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update organization role")
+    }
+
+    /// Removes the role, taking it away from members and pending invites
+    pub fn destroy(&self, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        for table in &["organization_users", "organization_invites"] {
+            diesel::sql_query(format!(
+                "UPDATE {0} SET organization_role_ids = array_remove(organization_role_ids, $1) WHERE $1 = ANY({0}.organization_role_ids);",
+                table
+            ))
+            .bind::<dUuid, _>(self.id)
+            .execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not remove organization role")?;
+        }
+        diesel::delete(self)
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not remove organization role")
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<OrganizationRole, DatabaseError> {
+        organization_roles::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find organization role")
+    }
+
+    pub fn find_for_organization(
+        organization_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<OrganizationRole>, DatabaseError> {
+        organization_roles::table
+            .filter(organization_roles::organization_id.eq(organization_id))
+            .order_by(organization_roles::name.asc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load organization roles")
+    }
+
+    /// The organization's roles among `ids`, e.g. those given to a member
+    pub fn find_by_ids_for_organization(
+        ids: &[Uuid],
+        organization_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<OrganizationRole>, DatabaseError> {
+        if ids.is_empty() {
+            return Ok(vec![]);
+        }
+        organization_roles::table
+            .filter(organization_roles::id.eq_any(ids))
+            .filter(organization_roles::organization_id.eq(organization_id))
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load organization roles")
+    }
+
+    /// Checks roles being given to a member or invitee all belong to the organization
+    pub fn validate_ids_for_organization(
+        ids: &[Uuid],
+        organization_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<(), DatabaseError> {
+        let mut ids = ids.to_vec();
+        ids.sort();
+        ids.dedup();
+        let organization_roles =
+            OrganizationRole::find_by_ids_for_organization(&ids, organization_id, conn)?;
+        if organization_roles.len() != ids.len() {
+            return DatabaseError::validation_error(
+                "organization_role_ids",
+                "Roles must belong to the organization",
+            );
+        }
+        Ok(())
+    }
+
+    pub fn get_scopes(&self) -> Vec<Scopes> {
+        self.scopes
+            .iter()
+            .filter_map(|s| Scopes::from_str(s).ok())
+            .collect()
+    }
+
+    /// Custom roles are limited to the scopes an organization owner has
+    fn validate(name: &str, scopes: Vec<Scopes>) -> Result<Vec<String>, DatabaseError> {
+        if name.trim().is_empty() {
+            return DatabaseError::validation_error("name", "Role name is required");
+        }
+        if scopes.is_empty() {
+            return DatabaseError::validation_error("scopes", "At least one scope is required");
+        }
+        let organization_scopes = scopes::get_scopes(vec![Roles::OrgOwner]);
+        if scopes.iter().any(|s| !organization_scopes.contains(s)) {
+            return DatabaseError::validation_error(
+                "scopes",
+                "Scopes must be available to organization owners",
+            );
+        }
+        let mut scopes: Vec<String> = scopes.iter().map(|s| s.to_string()).collect();
+        scopes.sort();
+        scopes.dedup();
+        Ok(scopes)
+    }
+}
@@ -21,6 +21,7 @@ pub struct OrganizationUser {
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
     pub role: Vec<Roles>,
+    pub organization_role_ids: Vec<Uuid>,
 }
 
 #[derive(Insertable)]
@@ -29,6 +30,7 @@ pub struct NewOrganizationUser {
     pub organization_id: Uuid,
     pub user_id: Uuid,
     role: Vec<Roles>,
+    organization_role_ids: Vec<Uuid>,
 }
 
 impl NewOrganizationUser {
@@ -37,10 +39,12 @@ impl NewOrganizationUser {
             OrganizationUser::find_by_user_id(self.user_id, self.organization_id, conn)
                 .optional()?;
         match existing_user {
-            Some(mut user) => {
-                user.role = self.role;
+            Some(user) => {
                 diesel::update(organization_users::table.filter(organization_users::id.eq(user.id)))
-                    .set(organization_users::role.eq(user.role))
+                    .set((
+                        organization_users::role.eq(self.role),
+                        organization_users::organization_role_ids.eq(self.organization_role_ids),
+                    ))
                     .get_result(conn)
                     .to_db_error(
                         ErrorCode::UpdateError,
@@ -59,11 +63,17 @@ impl NewOrganizationUser {
 }
 
 impl OrganizationUser {
-    pub fn create(organization_id: Uuid, user_id: Uuid, role: Vec<Roles>) -> NewOrganizationUser {
+    pub fn create(
+        organization_id: Uuid,
+        user_id: Uuid,
+        role: Vec<Roles>,
+        organization_role_ids: Vec<Uuid>,
+    ) -> NewOrganizationUser {
         NewOrganizationUser {
             organization_id,
             user_id,
             role,
+            organization_role_ids,
         }
     }
 
@@ -15,6 +15,7 @@ use utils::encryption::*;
 use utils::errors::*;
 use utils::text;
 use uuid::Uuid;
+use validators;
 
 #[derive(
     Identifiable,
@@ -48,6 +49,9 @@ pub struct Organization {
     pub client_event_fee_in_cents: i64,
     pub company_event_fee_in_cents: i64,
     pub allowed_payment_providers: Vec<String>,
+    pub currency: String,
+    pub require_two_factor: bool,
+    pub require_verified_contact_details: bool,
 }
 
 #[derive(Serialize)]
@@ -76,6 +80,8 @@ pub struct NewOrganization {
     pub facebook_pixel_key: Option<String>,
     pub client_event_fee_in_cents: Option<i64>,
     pub company_event_fee_in_cents: Option<i64>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub currency: Option<String>,
 }
 
 #[derive(Default, Serialize, Clone)]
@@ -91,6 +97,14 @@ impl NewOrganization {
         current_user_id: Uuid,
         conn: &PgConnection,
     ) -> Result<Organization, DatabaseError> {
+        if let Some(ref currency) = self.currency {
+            validators::append_validation_error(
+                Ok(()),
+                "currency",
+                validators::validate_currency_code(currency),
+            )?;
+        }
+
         let mut updated_organisation = self;
         if encryption_key.len() > 0 {
             if let Some(key) = updated_organisation.sendgrid_api_key.clone() {
@@ -152,6 +166,10 @@ pub struct OrganizationEditableAttributes {
     pub facebook_pixel_key: Option<Option<String>>,
     pub client_event_fee_in_cents: Option<i64>,
     pub company_event_fee_in_cents: Option<i64>,
+    #[serde(default, deserialize_with = "deserialize_unless_blank")]
+    pub currency: Option<String>,
+    pub require_two_factor: Option<bool>,
+    pub require_verified_contact_details: Option<bool>,
 }
 
 impl Organization {
@@ -169,6 +187,14 @@ impl Organization {
         encryption_key: &String,
         conn: &PgConnection,
     ) -> Result<Organization, DatabaseError> {
+        if let Some(ref currency) = attributes.currency {
+            validators::append_validation_error(
+                Ok(()),
+                "currency",
+                validators::validate_currency_code(currency),
+            )?;
+        }
+
         if encryption_key.len() > 0 {
             if let Some(Some(key)) = attributes.sendgrid_api_key {
                 attributes.sendgrid_api_key = Some(Some(encrypt(&key, encryption_key)?));
@@ -334,7 +360,30 @@ impl Organization {
         user: &User,
         conn: &PgConnection,
     ) -> Result<Vec<Scopes>, DatabaseError> {
-        Ok(scopes::get_scopes(self.get_roles_for_user(user, conn)?))
+        let mut scopes = scopes::get_scopes(self.get_roles_for_user(user, conn)?);
+        for organization_role in self.get_organization_roles_for_user(user, conn)? {
+            scopes.extend(organization_role.get_scopes());
+        }
+        scopes.sort();
+        scopes.dedup();
+        Ok(scopes)
+    }
+
+    /// The custom roles the organization has given the user, in addition to their `Roles`
+    pub fn get_organization_roles_for_user(
+        &self,
+        user: &User,
+        conn: &PgConnection,
+    ) -> Result<Vec<OrganizationRole>, DatabaseError> {
+        let org_member = OrganizationUser::find_by_user_id(user.id, self.id, conn).optional()?;
+        match org_member {
+            Some(member) => OrganizationRole::find_by_ids_for_organization(
+                &member.organization_role_ids,
+                self.id,
+                conn,
+            ),
+            None => Ok(vec![]),
+        }
     }
 
     pub fn get_roles_for_user(
@@ -493,9 +542,12 @@ impl Organization {
         &self,
         user_id: Uuid,
         role: Vec<Roles>,
+        organization_role_ids: Vec<Uuid>,
         conn: &PgConnection,
     ) -> Result<OrganizationUser, DatabaseError> {
-        let org_user = OrganizationUser::create(self.id, user_id, role).commit(conn)?;
+        OrganizationRole::validate_ids_for_organization(&organization_role_ids, self.id, conn)?;
+        let org_user =
+            OrganizationUser::create(self.id, user_id, role, organization_role_ids).commit(conn)?;
         Ok(org_user)
     }
 
@@ -27,6 +27,7 @@ pub struct Payment {
     created_at: NaiveDateTime,
     updated_at: NaiveDateTime,
     pub url_nonce: Option<String>,
+    pub currency: String,
 }
 
 impl Payment {
@@ -38,6 +39,7 @@ impl Payment {
         provider: String,
         external_reference: Option<String>,
         amount: i64,
+        currency: String,
         raw_data: Option<serde_json::Value>,
         url_nonce: Option<String>,
     ) -> NewPayment {
@@ -49,6 +51,7 @@ impl Payment {
             provider,
             external_reference,
             amount,
+            currency,
             raw_data,
             url_nonce,
         }
@@ -92,6 +95,7 @@ impl Payment {
             self.provider.clone(),
             self.external_reference.clone(),
             -(refund_amount as i64),
+            self.currency.clone(),
             refund_data.clone(),
             None,
         )
@@ -281,6 +285,7 @@ pub struct NewPayment {
     provider: String,
     raw_data: Option<serde_json::Value>,
     url_nonce: Option<String>,
+    currency: String,
 }
 
 impl NewPayment {
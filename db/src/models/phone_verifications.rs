@@ -0,0 +1,122 @@
+use chrono::{NaiveDateTime, Utc};
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::User;
+use schema::phone_verifications;
+use time::Duration;
+use utils::encryption;
+use utils::errors::*;
+use utils::rand::random_numeric_string;
+use uuid::Uuid;
+
+pub const PHONE_VERIFICATION_CODE_LENGTH: usize = 6;
+const PHONE_VERIFICATION_EXPIRY_MINUTES: i64 = 10;
+/// A code stops working after this many incorrect guesses
+const PHONE_VERIFICATION_MAX_FAILED_ATTEMPTS: i32 = 5;
+
+/// A code sent by SMS to confirm the user can be reached on `phone`
+#[derive(Clone, Debug, Identifiable, PartialEq, Queryable, Serialize)]
+#[table_name = "phone_verifications"]
+pub struct PhoneVerification {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub phone: String,
+    #[serde(skip_serializing)]
+    pub code_hash: String,
+    pub failed_attempts: i32,
+    pub expires_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "phone_verifications"]
+struct NewPhoneVerification {
+    user_id: Uuid,
+    phone: String,
+    code_hash: String,
+    expires_at: NaiveDateTime,
+}
+
+impl PhoneVerification {
+    /// Starts verification of the user's current phone, replacing any earlier code. The code
+    /// to send is returned here only, it is stored hashed.
+    pub fn create(
+        user: &User,
+        conn: &PgConnection,
+    ) -> Result<(PhoneVerification, String), DatabaseError> {
+        let phone = match user.phone {
+            Some(ref phone) => phone.clone(),
+            None => return DatabaseError::validation_error("phone", "Phone is required"),
+        };
+        diesel::delete(phone_verifications::table.filter(phone_verifications::user_id.eq(user.id)))
+            .execute(conn)
+            .to_db_error(
+                ErrorCode::DeleteError,
+                "Could not remove previous phone verification",
+            )?;
+
+        let code = random_numeric_string(PHONE_VERIFICATION_CODE_LENGTH);
+        let phone_verification = diesel::insert_into(phone_verifications::table)
+            .values(NewPhoneVerification {
+                user_id: user.id,
+                phone,
+                code_hash: encryption::hash_secret(&code),
+                expires_at: Utc::now().naive_utc()
+                    + Duration::minutes(PHONE_VERIFICATION_EXPIRY_MINUTES),
+            })
+            .get_result(conn)
+            .to_db_error(
+                ErrorCode::InsertError,
+                "Could not create phone verification",
+            )?;
+        Ok((phone_verification, code))
+    }
+
+    pub fn find_for_user(
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Option<PhoneVerification>, DatabaseError> {
+        phone_verifications::table
+            .filter(phone_verifications::user_id.eq(user_id))
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Could not load phone verification")
+    }
+
+    pub fn is_usable(&self) -> bool {
+        self.expires_at > Utc::now().naive_utc()
+            && self.failed_attempts < PHONE_VERIFICATION_MAX_FAILED_ATTEMPTS
+    }
+
+    /// Checks the code and verifies the phone it was sent to if it matches. Incorrect guesses
+    /// are counted, the code can not be used once it has expired or had too many of them.
+    pub fn verify(&self, code: &str, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        if !self.is_usable() {
+            return Ok(false);
+        }
+        let code: String = code.chars().filter(|c| !c.is_whitespace()).collect();
+        if encryption::hash_secret(&code) != self.code_hash {
+            diesel::update(self)
+                .set((
+                    phone_verifications::failed_attempts
+                        .eq(phone_verifications::failed_attempts + 1),
+                    phone_verifications::updated_at.eq(dsl::now),
+                ))
+                .execute(conn)
+                .to_db_error(
+                    ErrorCode::UpdateError,
+                    "Could not update phone verification",
+                )?;
+            return Ok(false);
+        }
+
+        User::find(self.user_id, conn)?.verify_phone(&self.phone, conn)?;
+        diesel::delete(self).execute(conn).to_db_error(
+            ErrorCode::DeleteError,
+            "Could not remove phone verification",
+        )?;
+        Ok(true)
+    }
+}
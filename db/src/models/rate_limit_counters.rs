@@ -0,0 +1,79 @@
+use chrono::{Duration, NaiveDateTime, Utc};
+use diesel;
+use diesel::prelude::*;
+use diesel::sql_types::{Text, Timestamp};
+use schema::rate_limit_counters;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// Requests counted against a rate limit key, e.g. `auth_token:ip:127.0.0.1`, within a fixed
+/// window. The window starts again with the first hit after `window_ends_at`.
+#[derive(Clone, Debug, Identifiable, PartialEq, Queryable, QueryableByName)]
+#[table_name = "rate_limit_counters"]
+pub struct RateLimitCounter {
+    pub id: Uuid,
+    pub key: String,
+    pub hits: i32,
+    pub window_ends_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl RateLimitCounter {
+    /// Counts a hit against `key`, starting a new window of `period_in_seconds` if the last
+    /// one has ended
+    pub fn hit(
+        key: &str,
+        period_in_seconds: i64,
+        conn: &PgConnection,
+    ) -> Result<RateLimitCounter, DatabaseError> {
+        let now = Utc::now().naive_utc();
+        let query = r#"
+            INSERT INTO rate_limit_counters (key, hits, window_ends_at)
+            VALUES ($1, 1, $2)
+            ON CONFLICT (key) DO UPDATE SET
+                hits = CASE WHEN rate_limit_counters.window_ends_at <= $3 THEN 1
+                    ELSE rate_limit_counters.hits + 1 END,
+                window_ends_at = CASE WHEN rate_limit_counters.window_ends_at <= $3
+                    THEN EXCLUDED.window_ends_at ELSE rate_limit_counters.window_ends_at END,
+                updated_at = now()
+            RETURNING *;
+        "#;
+        diesel::sql_query(query)
+            .bind::<Text, _>(key)
+            .bind::<Timestamp, _>(now + Duration::seconds(period_in_seconds))
+            .bind::<Timestamp, _>(now)
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update rate limit")
+    }
+
+    pub fn find_by_key(
+        key: &str,
+        conn: &PgConnection,
+    ) -> Result<Option<RateLimitCounter>, DatabaseError> {
+        rate_limit_counters::table
+            .filter(rate_limit_counters::key.eq(key))
+            .first(conn)
+            .optional()
+            .to_db_error(ErrorCode::QueryError, "Could not load rate limit")
+    }
+
+    /// Seconds until the limit allows another request, `None` if it has not been exceeded
+    pub fn retry_after(&self, limit: i32) -> Option<i64> {
+        if self.hits <= limit {
+            return None;
+        }
+        let remaining = (self.window_ends_at - Utc::now().naive_utc()).num_seconds();
+        Some(remaining.max(1))
+    }
+
+    /// Removes counters whose window has ended, returning the number removed
+    pub fn delete_expired(conn: &PgConnection) -> Result<usize, DatabaseError> {
+        diesel::delete(
+            rate_limit_counters::table
+                .filter(rate_limit_counters::window_ends_at.le(Utc::now().naive_utc())),
+        )
+        .execute(conn)
+        .to_db_error(ErrorCode::DeleteError, "Could not remove rate limits")
+    }
+}
@@ -0,0 +1,71 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::prelude::*;
+use models::*;
+use schema::report_deliveries;
+use utils::errors::*;
+use uuid::Uuid;
+
+#[derive(
+    Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize,
+)]
+#[belongs_to(ReportSubscription)]
+#[table_name = "report_deliveries"]
+pub struct ReportDelivery {
+    pub id: Uuid,
+    pub report_subscription_id: Uuid,
+    pub period_start: NaiveDateTime,
+    pub period_end: NaiveDateTime,
+    pub status: ReportDeliveryStatus,
+    pub error: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Deserialize, Insertable, Serialize)]
+#[table_name = "report_deliveries"]
+pub struct NewReportDelivery {
+    pub report_subscription_id: Uuid,
+    pub period_start: NaiveDateTime,
+    pub period_end: NaiveDateTime,
+    pub status: ReportDeliveryStatus,
+    pub error: Option<String>,
+}
+
+impl NewReportDelivery {
+    pub fn commit(self, conn: &PgConnection) -> Result<ReportDelivery, DatabaseError> {
+        diesel::insert_into(report_deliveries::table)
+            .values(&self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create report delivery")
+    }
+}
+
+impl ReportDelivery {
+    pub fn create(
+        report_subscription_id: Uuid,
+        period_start: NaiveDateTime,
+        period_end: NaiveDateTime,
+        status: ReportDeliveryStatus,
+        error: Option<String>,
+    ) -> NewReportDelivery {
+        NewReportDelivery {
+            report_subscription_id,
+            period_start,
+            period_end,
+            status,
+            error,
+        }
+    }
+
+    pub fn find_for_subscription(
+        report_subscription_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<ReportDelivery>, DatabaseError> {
+        report_deliveries::table
+            .filter(report_deliveries::report_subscription_id.eq(report_subscription_id))
+            .order_by(report_deliveries::created_at.desc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load report deliveries")
+    }
+}
@@ -0,0 +1,360 @@
+use chrono::prelude::*;
+use chrono::Duration;
+use chrono_tz::Tz;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::{events, report_subscriptions};
+use utils::errors::*;
+use uuid::Uuid;
+use validators;
+use validators::*;
+
+#[derive(
+    Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize,
+)]
+#[belongs_to(Organization)]
+#[belongs_to(User)]
+#[table_name = "report_subscriptions"]
+pub struct ReportSubscription {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub user_id: Uuid,
+    pub report_type: ReportTypes,
+    pub event_id: Option<Uuid>,
+    pub frequency: ReportFrequencies,
+    pub day_of_week: Option<i32>,
+    pub hour_of_day: i32,
+    pub timezone: String,
+    pub format: ExportFormats,
+    #[serde(skip_serializing)]
+    pub unsubscribe_token: Uuid,
+    pub next_delivery_at: NaiveDateTime,
+    pub unsubscribed_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+/// A subscription to receive a report by email. Day of week is only used for weekly
+/// subscriptions, starting with 0 for Monday, and the hour of day is in the given timezone.
+#[derive(Clone, Debug, Deserialize, Insertable, Serialize)]
+#[table_name = "report_subscriptions"]
+pub struct NewReportSubscription {
+    pub organization_id: Uuid,
+    pub user_id: Uuid,
+    pub report_type: ReportTypes,
+    pub event_id: Option<Uuid>,
+    pub frequency: ReportFrequencies,
+    pub day_of_week: Option<i32>,
+    pub hour_of_day: i32,
+    pub timezone: String,
+    #[serde(default)]
+    pub format: ExportFormats,
+}
+
+impl NewReportSubscription {
+    pub fn commit(mut self, conn: &PgConnection) -> Result<ReportSubscription, DatabaseError> {
+        self.validate_record(conn)?;
+        if self.frequency == ReportFrequencies::Daily {
+            self.day_of_week = None;
+        }
+
+        let next_delivery_at = next_delivery(
+            self.frequency,
+            self.day_of_week,
+            self.hour_of_day,
+            &self.timezone,
+            Utc::now().naive_utc(),
+        )?;
+        let subscription: ReportSubscription = diesel::insert_into(report_subscriptions::table)
+            .values((
+                &self,
+                report_subscriptions::next_delivery_at.eq(next_delivery_at),
+            ))
+            .get_result(conn)
+            .to_db_error(
+                ErrorCode::InsertError,
+                "Could not create report subscription",
+            )?;
+
+        subscription.schedule_delivery(conn)?;
+        Ok(subscription)
+    }
+
+    fn validate_record(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        let mut validation_errors = Ok(());
+        if self.hour_of_day < 0 || self.hour_of_day > 23 {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "hour_of_day",
+                Err(create_validation_error(
+                    "hour_of_day_out_of_range",
+                    "Hour of day must be between 0 and 23",
+                )),
+            );
+        }
+        if self.frequency == ReportFrequencies::Weekly
+            && self.day_of_week.map(|d| d < 0 || d > 6).unwrap_or(true)
+        {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "day_of_week",
+                Err(create_validation_error(
+                    "day_of_week_required",
+                    "Weekly reports require a day of week between 0 (Monday) and 6 (Sunday)",
+                )),
+            );
+        }
+        if self.format == ExportFormats::Pdf {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "format",
+                Err(create_validation_error(
+                    "format_not_supported",
+                    "Scheduled reports can be delivered as CSV or XLSX",
+                )),
+            );
+        }
+        if self.timezone.parse::<Tz>().is_err() {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "timezone",
+                Err(create_validation_error(
+                    "invalid_timezone",
+                    "Timezone is not valid",
+                )),
+            );
+        }
+        match self.event_id {
+            Some(event_id) => {
+                let organization_id: Uuid = events::table
+                    .filter(events::id.eq(event_id))
+                    .select(events::organization_id)
+                    .first(conn)
+                    .to_db_error(ErrorCode::QueryError, "Could not find event")?;
+                if organization_id != self.organization_id {
+                    validation_errors = validators::append_validation_error(
+                        validation_errors,
+                        "event_id",
+                        Err(create_validation_error(
+                            "event_not_in_organization",
+                            "Event does not belong to this organization",
+                        )),
+                    );
+                }
+            }
+            None => {
+                if self.report_type.requires_event() {
+                    validation_errors = validators::append_validation_error(
+                        validation_errors,
+                        "event_id",
+                        Err(create_validation_error(
+                            "event_required",
+                            "This report requires an event",
+                        )),
+                    );
+                }
+            }
+        }
+        Ok(validation_errors?)
+    }
+}
+
+impl ReportTypes {
+    pub fn requires_event(&self) -> bool {
+        match self {
+            ReportTypes::EventSummary | ReportTypes::AuditReport => true,
+            _ => false,
+        }
+    }
+
+    /// The scope needed to view this report, matching the permissions of the report endpoints
+    pub fn required_scope(&self, event_id: Option<Uuid>) -> Scopes {
+        match self {
+            ReportTypes::WeeklySettlement => Scopes::OrgFinancialReports,
+            _ if event_id.is_some() => Scopes::EventFinancialReports,
+            _ => Scopes::OrgReports,
+        }
+    }
+}
+
+impl ReportSubscription {
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<ReportSubscription, DatabaseError> {
+        report_subscriptions::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find report subscription")
+    }
+
+    pub fn find_by_unsubscribe_token(
+        unsubscribe_token: Uuid,
+        conn: &PgConnection,
+    ) -> Result<ReportSubscription, DatabaseError> {
+        report_subscriptions::table
+            .filter(report_subscriptions::unsubscribe_token.eq(unsubscribe_token))
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find report subscription")
+    }
+
+    /// Active subscriptions for the user within the organization
+    pub fn find_for_user(
+        organization_id: Uuid,
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<ReportSubscription>, DatabaseError> {
+        report_subscriptions::table
+            .filter(report_subscriptions::organization_id.eq(organization_id))
+            .filter(report_subscriptions::user_id.eq(user_id))
+            .filter(report_subscriptions::unsubscribed_at.is_null())
+            .order_by(report_subscriptions::created_at)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load report subscriptions")
+    }
+
+    /// Schedules a delivery for active subscriptions whose delivery time has passed with no
+    /// pending delivery action, e.g. when the action expired or failed before the delivery
+    /// could be completed. Returns the subscriptions that were scheduled.
+    pub fn schedule_overdue_deliveries(
+        conn: &PgConnection,
+    ) -> Result<Vec<ReportSubscription>, DatabaseError> {
+        let overdue_subscriptions: Vec<ReportSubscription> = report_subscriptions::table
+            .filter(report_subscriptions::unsubscribed_at.is_null())
+            .filter(report_subscriptions::next_delivery_at.le(dsl::now))
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load report subscriptions")?;
+
+        let mut scheduled_subscriptions = Vec::new();
+        for subscription in overdue_subscriptions {
+            if !DomainAction::has_pending_action(
+                DomainActionTypes::SendScheduledReport,
+                Tables::ReportSubscriptions.to_string(),
+                subscription.id,
+                conn,
+            )? {
+                subscription.schedule_delivery(conn)?;
+                scheduled_subscriptions.push(subscription);
+            }
+        }
+        Ok(scheduled_subscriptions)
+    }
+
+    pub fn deliveries(&self, conn: &PgConnection) -> Result<Vec<ReportDelivery>, DatabaseError> {
+        ReportDelivery::find_for_subscription(self.id, conn)
+    }
+
+    pub fn unsubscribe(self, conn: &PgConnection) -> Result<ReportSubscription, DatabaseError> {
+        diesel::update(&self)
+            .set((
+                report_subscriptions::unsubscribed_at.eq(dsl::now.nullable()),
+                report_subscriptions::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not unsubscribe from report")
+    }
+
+    /// The period covered by the next delivery, ending at the time of delivery
+    pub fn reporting_period(&self) -> (NaiveDateTime, NaiveDateTime) {
+        let length = match self.frequency {
+            ReportFrequencies::Daily => Duration::days(1),
+            ReportFrequencies::Weekly => Duration::days(7),
+        };
+        (self.next_delivery_at - length, self.next_delivery_at)
+    }
+
+    /// Records the outcome of the current delivery and schedules the next one. Deliveries
+    /// missed while the subscription could not be processed are skipped. Should the next
+    /// delivery not be scheduled here it is picked up by `schedule_overdue_deliveries`.
+    pub fn complete_delivery(
+        self,
+        status: ReportDeliveryStatus,
+        error: Option<String>,
+        conn: &PgConnection,
+    ) -> Result<ReportDelivery, DatabaseError> {
+        let (period_start, period_end) = self.reporting_period();
+        let delivery = ReportDelivery::create(self.id, period_start, period_end, status, error)
+            .commit(conn)?;
+
+        let after = Utc::now().naive_utc().max(self.next_delivery_at);
+        let next_delivery_at = next_delivery(
+            self.frequency,
+            self.day_of_week,
+            self.hour_of_day,
+            &self.timezone,
+            after,
+        )?;
+        let subscription: ReportSubscription = diesel::update(&self)
+            .set((
+                report_subscriptions::next_delivery_at.eq(next_delivery_at),
+                report_subscriptions::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(
+                ErrorCode::UpdateError,
+                "Could not update report subscription",
+            )?;
+        subscription.schedule_delivery(conn)?;
+
+        Ok(delivery)
+    }
+
+    fn schedule_delivery(&self, conn: &PgConnection) -> Result<DomainAction, DatabaseError> {
+        // Overdue deliveries are scheduled immediately
+        let scheduled_at = self.next_delivery_at.max(Utc::now().naive_utc());
+        DomainAction::create(
+            None,
+            DomainActionTypes::SendScheduledReport,
+            None,
+            json!({ "report_subscription_id": self.id }),
+            Some(Tables::ReportSubscriptions.to_string()),
+            Some(self.id),
+            scheduled_at,
+            scheduled_at.checked_add_signed(Duration::days(1)).unwrap(),
+            3,
+        )
+        .commit(conn)
+    }
+}
+
+/// Finds the first delivery time after the given time, delivery times are on the hour in
+/// the subscription's timezone
+fn next_delivery(
+    frequency: ReportFrequencies,
+    day_of_week: Option<i32>,
+    hour_of_day: i32,
+    timezone: &str,
+    after: NaiveDateTime,
+) -> Result<NaiveDateTime, DatabaseError> {
+    let tz: Tz = timezone.parse().map_err(|_| {
+        DatabaseError::new(
+            ErrorCode::BusinessProcessError,
+            Some(format!("Invalid timezone {}", timezone)),
+        )
+    })?;
+
+    let mut date = tz.from_utc_datetime(&after).naive_local().date();
+    // A weekly delivery is always found within eight days, a day in a DST gap is skipped
+    for _ in 0..15 {
+        let is_delivery_day = match frequency {
+            ReportFrequencies::Daily => true,
+            ReportFrequencies::Weekly => {
+                day_of_week == Some(date.weekday().num_days_from_monday() as i32)
+            }
+        };
+        if is_delivery_day {
+            let local_time = date.and_hms(hour_of_day as u32, 0, 0);
+            if let Some(delivery_time) = tz.from_local_datetime(&local_time).earliest() {
+                let delivery_time = delivery_time.naive_utc();
+                if delivery_time > after {
+                    return Ok(delivery_time);
+                }
+            }
+        }
+        date = date.succ();
+    }
+
+    Err(DatabaseError::new(
+        ErrorCode::BusinessProcessError,
+        Some("Could not find next report delivery time".to_string()),
+    ))
+}
@@ -116,8 +116,8 @@ pub struct TicketCountRow {
 
 #[derive(Default, Debug, Serialize, Deserialize)]
 pub struct TicketSalesAndCounts {
-    counts: Vec<TicketCountRow>,
-    sales: Vec<TicketSalesRow>,
+    pub counts: Vec<TicketCountRow>,
+    pub sales: Vec<TicketSalesRow>,
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Queryable, QueryableByName)]
@@ -170,32 +170,220 @@ pub struct TransactionReportRow {
     pub last_name: String,
     #[sql_type = "Text"]
     pub email: String,
+    #[sql_type = "Text"]
+    pub currency: String,
+    #[sql_type = "Text"]
+    pub fee_mode: FeeModes,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Queryable, QueryableByName)]
+pub struct CurrencySummaryRow {
+    #[sql_type = "Text"]
+    pub currency: String,
+    #[sql_type = "BigInt"]
+    pub order_count: i64,
+    #[sql_type = "BigInt"]
+    pub ticket_count: i64,
+    #[sql_type = "BigInt"]
+    pub ticket_sales_in_cents: i64,
+    #[sql_type = "BigInt"]
+    pub fees_in_cents: i64,
+    #[sql_type = "BigInt"]
+    pub absorbed_fees_in_cents: i64,
+    #[sql_type = "BigInt"]
+    pub tax_in_cents: i64,
+    #[sql_type = "BigInt"]
+    pub gross_in_cents: i64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Queryable, QueryableByName)]
+pub struct TaxLiabilityRow {
+    #[sql_type = "dUuid"]
+    pub tax_rule_id: Uuid,
+    #[sql_type = "Text"]
+    pub tax_rule_name: String,
+    #[sql_type = "BigInt"]
+    pub rate_in_basis_points: i64,
+    #[sql_type = "Bool"]
+    pub inclusive: bool,
+    #[sql_type = "Text"]
+    pub currency: String,
+    #[sql_type = "BigInt"]
+    pub taxable_amount_in_cents: i64,
+    #[sql_type = "BigInt"]
+    pub tax_charged_in_cents: i64,
+    #[sql_type = "BigInt"]
+    pub tax_refunded_in_cents: i64,
+    #[sql_type = "BigInt"]
+    pub tax_due_in_cents: i64,
+}
+
+/// Sales grouped by marketing attribution, orders without attribution are grouped together
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Queryable, QueryableByName)]
+pub struct SalesBySourceRow {
+    #[sql_type = "Nullable<Text>"]
+    pub utm_source: Option<String>,
+    #[sql_type = "Nullable<Text>"]
+    pub utm_medium: Option<String>,
+    #[sql_type = "Nullable<Text>"]
+    pub utm_campaign: Option<String>,
+    #[sql_type = "Nullable<Text>"]
+    pub source_id: Option<String>,
+    #[sql_type = "BigInt"]
+    pub cart_count: i64,
+    #[sql_type = "BigInt"]
+    pub order_count: i64,
+    #[sql_type = "BigInt"]
+    pub conversion_rate_in_basis_points: i64,
+    #[sql_type = "BigInt"]
+    pub ticket_count: i64,
+    #[sql_type = "BigInt"]
+    pub revenue_in_cents: i64,
+}
+
+/// Commission owed to affiliates, one row per affiliate and ticket type
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Queryable, QueryableByName)]
+pub struct AffiliateCommissionRow {
+    #[sql_type = "dUuid"]
+    pub affiliate_id: Uuid,
+    #[sql_type = "Text"]
+    pub affiliate_name: String,
+    #[sql_type = "Text"]
+    pub affiliate_code: String,
+    #[sql_type = "dUuid"]
+    pub event_id: Uuid,
+    #[sql_type = "Text"]
+    pub event_name: String,
+    #[sql_type = "dUuid"]
+    pub ticket_type_id: Uuid,
+    #[sql_type = "Text"]
+    pub ticket_type_name: String,
+    #[sql_type = "BigInt"]
+    pub order_count: i64,
+    #[sql_type = "BigInt"]
+    pub ticket_count: i64,
+    #[sql_type = "BigInt"]
+    pub revenue_in_cents: i64,
+    #[sql_type = "BigInt"]
+    pub commission_in_cents: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct CurrencySummaryResult {
+    pub rows: Vec<CurrencySummaryRow>,
+    pub target_currency: Option<String>,
+    pub converted_gross_in_cents: Option<i64>,
 }
 
+/// Sales for a single event in a single currency, amounts in different currencies are never summed
 #[derive(Serialize, Deserialize)]
 pub struct EventSummarySalesResult {
     pub event_id: Uuid,
+    pub currency: String,
     pub sales: Vec<EventSummarySalesRow>,
     pub ticket_fees: Vec<EventSummaryFeesRow>,
     pub other_fees: Vec<EventSummaryOtherFees>,
+    pub split_partner_shares: Vec<SplitPartnerShare>,
 }
 
 impl Default for EventSummarySalesResult {
     fn default() -> Self {
         EventSummarySalesResult {
             event_id: Uuid::nil(),
+            currency: String::new(),
             sales: vec![],
             ticket_fees: vec![],
             other_fees: vec![],
+            split_partner_shares: vec![],
         }
     }
 }
 
+impl EventSummarySalesResult {
+    pub fn totals(&self) -> EventSummaryTotals {
+        let mut totals = EventSummaryTotals::default();
+        for row in &self.sales {
+            // Gross income includes the fees paid by the buyer
+            totals.ticket_sales_in_cents += row.total_gross_income_in_cents
+                - row.total_company_fee_in_cents
+                - row.total_client_fee_in_cents
+                + row.total_absorbed_fee_in_cents;
+            totals.client_fees_in_cents += row.total_client_fee_in_cents;
+            totals.company_fees_in_cents += row.total_company_fee_in_cents;
+            totals.absorbed_fees_in_cents += row.total_absorbed_fee_in_cents;
+        }
+        for row in &self.other_fees {
+            totals.client_fees_in_cents += row.total_client_fee_in_cents;
+            totals.company_fees_in_cents += row.total_company_fee_in_cents;
+            totals.absorbed_fees_in_cents += row.total_absorbed_fee_in_cents;
+        }
+        totals
+    }
+
+    fn with_split_partner_shares(
+        mut self,
+        conn: &PgConnection,
+    ) -> Result<EventSummarySalesResult, DatabaseError> {
+        let totals = self.totals();
+        self.split_partner_shares = EventSplitPartner::shares_for_event(
+            self.event_id,
+            totals.ticket_sales_in_cents,
+            totals.organization_fees_in_cents(),
+            conn,
+        )?;
+        Ok(self)
+    }
+
+    /// The summary as shown to the split partner `organization_id`, `None` if it has no share
+    pub fn for_split_partner(&self, organization_id: Uuid) -> Option<SplitPartnerEventSummary> {
+        self.split_partner_shares
+            .iter()
+            .find(|share| share.organization_id == organization_id)
+            .map(|share| SplitPartnerEventSummary {
+                event_id: self.event_id,
+                currency: self.currency.clone(),
+                organization_id: share.organization_id,
+                split_type: share.split_type,
+                ticket_sales_in_cents: share.ticket_sales_in_cents,
+                fees_in_cents: share.fees_in_cents,
+            })
+    }
+}
+
+/// A split partner's own share of an event, without the sales behind it or the shares of the
+/// event's organization and other partners
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct SplitPartnerEventSummary {
+    pub event_id: Uuid,
+    pub currency: String,
+    pub organization_id: Uuid,
+    pub split_type: SplitTypes,
+    pub ticket_sales_in_cents: i64,
+    pub fees_in_cents: i64,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct EventSummaryTotals {
+    pub ticket_sales_in_cents: i64,
+    pub client_fees_in_cents: i64,
+    pub company_fees_in_cents: i64,
+    pub absorbed_fees_in_cents: i64,
+}
+
+impl EventSummaryTotals {
+    /// The fees kept by the event's organization, which its split partners share in
+    pub fn organization_fees_in_cents(&self) -> i64 {
+        self.client_fees_in_cents - self.absorbed_fees_in_cents
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Queryable, QueryableByName)]
 pub struct EventSummarySalesRow {
     #[sql_type = "dUuid"]
     pub event_id: Uuid,
     #[sql_type = "Text"]
+    pub currency: String,
+    #[sql_type = "Text"]
     pub ticket_name: String,
     #[sql_type = "Text"]
     pub pricing_name: String,
@@ -204,6 +392,8 @@ pub struct EventSummarySalesRow {
     #[sql_type = "BigInt"]
     pub total_company_fee_in_cents: i64,
     #[sql_type = "BigInt"]
+    pub total_absorbed_fee_in_cents: i64,
+    #[sql_type = "BigInt"]
     pub price_in_cents: i64,
     #[sql_type = "BigInt"]
     pub online_count: i64,
@@ -225,6 +415,8 @@ pub struct EventSummarySalesRow {
 pub struct EventSummaryFeesRow {
     #[sql_type = "dUuid"]
     pub event_id: Uuid,
+    #[sql_type = "Text"]
+    pub currency: String,
 
     #[sql_type = "dUuid"]
     pub ticket_type_id: Uuid,
@@ -250,12 +442,16 @@ pub struct EventSummaryFeesRow {
     pub total_client_fee_in_cents: i64,
     #[sql_type = "BigInt"]
     pub client_fee_in_cents: i64,
+    #[sql_type = "BigInt"]
+    pub total_absorbed_fee_in_cents: i64,
 }
 
 #[derive(Clone, Serialize, Deserialize, PartialEq, Queryable, QueryableByName)]
 pub struct EventSummaryOtherFees {
     #[sql_type = "dUuid"]
     pub event_id: Uuid,
+    #[sql_type = "Text"]
+    pub currency: String,
 
     #[sql_type = "BigInt"]
     pub unit_price_in_cents: i64,
@@ -267,6 +463,8 @@ pub struct EventSummaryOtherFees {
     pub total_client_fee_in_cents: i64,
     #[sql_type = "BigInt"]
     pub client_fee_in_cents: i64,
+    #[sql_type = "BigInt"]
+    pub total_absorbed_fee_in_cents: i64,
 }
 
 impl TicketSalesRow {
@@ -325,19 +523,157 @@ impl Report {
         start: Option<NaiveDateTime>,
         end: Option<NaiveDateTime>,
         conn: &PgConnection,
+    ) -> Result<Vec<TransactionReportRow>, DatabaseError> {
+        Report::transaction_detail_report_query(
+            event_id,
+            organization_id,
+            start,
+            end,
+            None,
+            0,
+            conn,
+        )
+    }
+
+    /// Fetches a single page of the transaction detail report, allowing large reports to be
+    /// exported without loading every row at once.
+    pub fn transaction_detail_report_page(
+        event_id: Option<Uuid>,
+        organization_id: Option<Uuid>,
+        start: Option<NaiveDateTime>,
+        end: Option<NaiveDateTime>,
+        page: u32,
+        limit: u32,
+        conn: &PgConnection,
+    ) -> Result<Vec<TransactionReportRow>, DatabaseError> {
+        Report::transaction_detail_report_query(
+            event_id,
+            organization_id,
+            start,
+            end,
+            Some(limit as i64),
+            (page * limit) as i64,
+            conn,
+        )
+    }
+
+    fn transaction_detail_report_query(
+        event_id: Option<Uuid>,
+        organization_id: Option<Uuid>,
+        start: Option<NaiveDateTime>,
+        end: Option<NaiveDateTime>,
+        limit: Option<i64>,
+        offset: i64,
+        conn: &PgConnection,
     ) -> Result<Vec<TransactionReportRow>, DatabaseError> {
         let query = include_str!("../queries/reports/reports_transaction_details.sql");
         let q = diesel::sql_query(query)
             .bind::<Nullable<dUuid>, _>(event_id)
             .bind::<Nullable<dUuid>, _>(organization_id)
             .bind::<Nullable<Timestamp>, _>(start)
-            .bind::<Nullable<Timestamp>, _>(end);
+            .bind::<Nullable<Timestamp>, _>(end)
+            .bind::<Nullable<BigInt>, _>(limit)
+            .bind::<BigInt, _>(offset);
         let transaction_rows: Vec<TransactionReportRow> = q
             .get_results(conn)
             .to_db_error(ErrorCode::QueryError, "Could not fetch report results")?;
         Ok(transaction_rows)
     }
 
+    pub fn tax_liability_report(
+        organization_id: Uuid,
+        start: Option<NaiveDateTime>,
+        end: Option<NaiveDateTime>,
+        conn: &PgConnection,
+    ) -> Result<Vec<TaxLiabilityRow>, DatabaseError> {
+        let query = include_str!("../queries/reports/reports_tax_liability.sql");
+        diesel::sql_query(query)
+            .bind::<Nullable<dUuid>, _>(Some(organization_id))
+            .bind::<Nullable<Timestamp>, _>(start)
+            .bind::<Nullable<Timestamp>, _>(end)
+            .get_results(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not fetch report results")
+    }
+
+    pub fn sales_by_source_report(
+        organization_id: Uuid,
+        event_id: Option<Uuid>,
+        start: Option<NaiveDateTime>,
+        end: Option<NaiveDateTime>,
+        conn: &PgConnection,
+    ) -> Result<Vec<SalesBySourceRow>, DatabaseError> {
+        let query = include_str!("../queries/reports/reports_sales_by_source.sql");
+        diesel::sql_query(query)
+            .bind::<dUuid, _>(organization_id)
+            .bind::<Nullable<dUuid>, _>(event_id)
+            .bind::<Nullable<Timestamp>, _>(start)
+            .bind::<Nullable<Timestamp>, _>(end)
+            .get_results(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not fetch report results")
+    }
+
+    /// Commission earned by the organization's affiliates on tickets paid for within the
+    /// period. The affiliate id limits the report to a single affiliate.
+    pub fn affiliate_commission_report(
+        organization_id: Uuid,
+        affiliate_id: Option<Uuid>,
+        start: Option<NaiveDateTime>,
+        end: Option<NaiveDateTime>,
+        conn: &PgConnection,
+    ) -> Result<Vec<AffiliateCommissionRow>, DatabaseError> {
+        let query = include_str!("../queries/reports/reports_affiliate_commissions.sql");
+        diesel::sql_query(query)
+            .bind::<dUuid, _>(organization_id)
+            .bind::<Nullable<dUuid>, _>(affiliate_id)
+            .bind::<Nullable<Timestamp>, _>(start)
+            .bind::<Nullable<Timestamp>, _>(end)
+            .get_results(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not fetch report results")
+    }
+
+    /// Sales grouped by currency. If a target currency is provided the gross totals are
+    /// also converted using the exchange rates effective at the end of the period.
+    pub fn currency_summary_report(
+        organization_id: Uuid,
+        start: Option<NaiveDateTime>,
+        end: Option<NaiveDateTime>,
+        target_currency: Option<String>,
+        conn: &PgConnection,
+    ) -> Result<CurrencySummaryResult, DatabaseError> {
+        let query = include_str!("../queries/reports/reports_currency_summary.sql");
+        let q = diesel::sql_query(query)
+            .bind::<Nullable<dUuid>, _>(Some(organization_id))
+            .bind::<Nullable<Timestamp>, _>(start)
+            .bind::<Nullable<Timestamp>, _>(end);
+        let rows: Vec<CurrencySummaryRow> = q
+            .get_results(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not fetch report results")?;
+
+        let converted_gross_in_cents = match target_currency {
+            Some(ref target_currency) => {
+                let at = end.unwrap_or(Utc::now().naive_utc());
+                let mut total = 0;
+                for row in &rows {
+                    total += ExchangeRate::convert(
+                        row.gross_in_cents,
+                        &row.currency,
+                        target_currency,
+                        at,
+                        conn,
+                    )?;
+                }
+                Some(total)
+            }
+            None => None,
+        };
+
+        Ok(CurrencySummaryResult {
+            rows,
+            target_currency,
+            converted_gross_in_cents,
+        })
+    }
+
     pub fn summary_event_report(
         event_id: Uuid,
         start: Option<NaiveDateTime>,
@@ -353,7 +689,8 @@ impl Report {
                     ..Default::default()
                 };
                 event_summary.event_id = event_id;
-                event_summary
+                event_summary.currency = Event::find(event_id, conn)?.currency;
+                event_summary.with_split_partner_shares(conn)?
             }
             false => results.pop().unwrap(),
         };
@@ -361,6 +698,7 @@ impl Report {
         Ok(result)
     }
 
+    /// One result per event and currency
     pub fn organization_summary_report(
         organization_id: Uuid,
         start: Option<NaiveDateTime>,
@@ -396,7 +734,9 @@ impl Report {
             return Ok(empty_result);
         }
 
-        let sales_rows = sales_rows.into_iter().group_by(|row| row.event_id);
+        let sales_rows = sales_rows
+            .into_iter()
+            .group_by(|row| (row.event_id, row.currency.clone()));
 
         //Now get the transaction fees results
         let query_fees = include_str!("../queries/reports/reports_event_summary_fees.sql");
@@ -410,10 +750,10 @@ impl Report {
             .get_results(conn)
             .to_db_error(ErrorCode::QueryError, "Could not fetch report fee results")?;
 
-        let mut fees_hash: HashMap<Uuid, Vec<EventSummaryFeesRow>> = HashMap::new();
+        let mut fees_hash: HashMap<(Uuid, String), Vec<EventSummaryFeesRow>> = HashMap::new();
         for row in fees_rows {
             fees_hash
-                .entry(row.event_id)
+                .entry((row.event_id, row.currency.clone()))
                 .or_insert(Vec::<EventSummaryFeesRow>::new())
                 .push(row);
         }
@@ -431,10 +771,11 @@ impl Report {
             .get_results(conn)
             .to_db_error(ErrorCode::QueryError, "Could not fetch report fee results")?;
 
-        let mut other_fees_hash: HashMap<Uuid, Vec<EventSummaryOtherFees>> = HashMap::new();
+        let mut other_fees_hash: HashMap<(Uuid, String), Vec<EventSummaryOtherFees>> =
+            HashMap::new();
         for row in other_fees_rows {
             other_fees_hash
-                .entry(row.event_id)
+                .entry((row.event_id, row.currency.clone()))
                 .or_insert(Vec::<EventSummaryOtherFees>::new())
                 .push(row);
         }
@@ -442,13 +783,19 @@ impl Report {
         let mut result = Vec::<EventSummarySalesResult>::new();
 
         // assume that an event must have sales in order to have other fees
-        for (event_id, sales) in sales_rows.into_iter() {
-            result.push(EventSummarySalesResult {
-                event_id,
-                sales: sales.into_iter().collect_vec(),
-                ticket_fees: fees_hash.get(&event_id).unwrap_or(&vec![]).to_vec(),
-                other_fees: other_fees_hash.get(&event_id).unwrap_or(&vec![]).to_vec(),
-            })
+        for (key, sales) in sales_rows.into_iter() {
+            let (event_id, currency) = key.clone();
+            result.push(
+                EventSummarySalesResult {
+                    event_id,
+                    currency,
+                    sales: sales.into_iter().collect_vec(),
+                    ticket_fees: fees_hash.get(&key).unwrap_or(&vec![]).to_vec(),
+                    other_fees: other_fees_hash.get(&key).unwrap_or(&vec![]).to_vec(),
+                    split_partner_shares: vec![],
+                }
+                .with_split_partner_shares(conn)?,
+            )
         }
         //Then get the fees summary
         Ok(result)
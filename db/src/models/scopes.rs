@@ -21,6 +21,7 @@ pub enum Scopes {
     EventScan,
     EventViewGuests,
     EventWrite,
+    ExchangeRateWrite,
     HoldRead,
     HoldWrite,
     OrderMakeExternalPayment,
@@ -37,6 +38,10 @@ pub enum Scopes {
     OrgWrite,
     RedeemTicket,
     RegionWrite,
+    SettlementApprove,
+    SettlementRead,
+    SettlementWrite,
+    TaxRuleWrite,
     TicketAdmin,
     TicketRead,
     TicketTransfer,
@@ -70,6 +75,7 @@ impl fmt::Display for Scopes {
             Scopes::EventReports => "event:reports",
             Scopes::EventScan => "event:scan",
             Scopes::EventViewGuests => "event:view-guests",
+            Scopes::ExchangeRateWrite => "exchange-rate:write",
             Scopes::HoldRead => "hold:read",
             Scopes::HoldWrite => "hold:write",
             Scopes::OrderRead => "order:read",
@@ -86,6 +92,10 @@ impl fmt::Display for Scopes {
             Scopes::OrgUsers => "org:users",
             Scopes::RedeemTicket => "redeem:ticket",
             Scopes::RegionWrite => "region:write",
+            Scopes::SettlementApprove => "settlement:approve",
+            Scopes::SettlementRead => "settlement:read",
+            Scopes::SettlementWrite => "settlement:write",
+            Scopes::TaxRuleWrite => "tax-rule:write",
             Scopes::UserRead => "user:read",
             Scopes::VenueWrite => "venue:write",
             Scopes::TicketAdmin => "ticket:admin",
@@ -115,6 +125,7 @@ impl FromStr for Scopes {
             "event:reports" => Scopes::EventReports,
             "event:scan" => Scopes::EventScan,
             "event:view-guests" => Scopes::EventViewGuests,
+            "exchange-rate:write" => Scopes::ExchangeRateWrite,
             "hold:read" => Scopes::HoldRead,
             "hold:write" => Scopes::HoldWrite,
             "order:read" => Scopes::OrderRead,
@@ -131,6 +142,10 @@ impl FromStr for Scopes {
             "org:users" => Scopes::OrgUsers,
             "redeem:ticket" => Scopes::RedeemTicket,
             "region:write" => Scopes::RegionWrite,
+            "settlement:approve" => Scopes::SettlementApprove,
+            "settlement:read" => Scopes::SettlementRead,
+            "settlement:write" => Scopes::SettlementWrite,
+            "tax-rule:write" => Scopes::TaxRuleWrite,
             "user:read" => Scopes::UserRead,
             "venue:write" => Scopes::VenueWrite,
             "ticket:admin" => Scopes::TicketAdmin,
@@ -188,6 +203,21 @@ fn get_scopes_for_role(role: Roles) -> Vec<Scopes> {
             roles.extend(get_scopes_for_role(Roles::DoorPerson));
             roles
         }
+        Promoter => {
+            let mut roles = vec![
+                Scopes::CodeRead,
+                Scopes::CompRead,
+                Scopes::CompWrite,
+                Scopes::DashboardRead,
+                Scopes::EventFinancialReports,
+                Scopes::EventReports,
+                Scopes::EventViewGuests,
+                Scopes::HoldRead,
+                Scopes::HoldWrite,
+            ];
+            roles.extend(get_scopes_for_role(Roles::User));
+            roles
+        }
         OrgMember => {
             let mut roles = vec![
                 Scopes::ArtistWrite,
@@ -229,15 +259,19 @@ fn get_scopes_for_role(role: Roles) -> Vec<Scopes> {
             roles
         }
         OrgOwner => {
-            let mut roles = vec![Scopes::OrgAdminUsers];
+            let mut roles = vec![Scopes::OrgAdminUsers, Scopes::SettlementRead];
             roles.extend(get_scopes_for_role(Roles::OrgAdmin));
             roles
         }
         Admin => {
             let mut roles = vec![
+                Scopes::ExchangeRateWrite,
                 Scopes::OrgAdmin,
                 Scopes::RegionWrite,
                 Scopes::OrgFinancialReports,
+                Scopes::SettlementApprove,
+                Scopes::SettlementWrite,
+                Scopes::TaxRuleWrite,
             ];
             roles.extend(get_scopes_for_role(OrgOwner));
             roles
@@ -281,6 +315,7 @@ fn get_scopes_for_role_test() {
             Scopes::OrgUsers,
             Scopes::OrgWrite,
             Scopes::RedeemTicket,
+            Scopes::SettlementRead,
             Scopes::TicketAdmin,
             Scopes::TicketRead,
             Scopes::TicketTransfer,
@@ -332,6 +367,7 @@ fn get_scopes_test() {
             "org:users",
             "org:write",
             "redeem:ticket",
+            "settlement:read",
             "ticket:admin",
             "ticket:read",
             "ticket:transfer",
@@ -361,6 +397,7 @@ fn get_scopes_test() {
             "event:scan",
             "event:view-guests",
             "event:write",
+            "exchange-rate:write",
             "hold:read",
             "hold:write",
             "order:make-external-payment",
@@ -377,6 +414,10 @@ fn get_scopes_test() {
             "org:write",
             "redeem:ticket",
             "region:write",
+            "settlement:approve",
+            "settlement:read",
+            "settlement:write",
+            "tax-rule:write",
             "ticket:admin",
             "ticket:read",
             "ticket:transfer",
@@ -406,6 +447,7 @@ fn get_scopes_test() {
             "event:scan",
             "event:view-guests",
             "event:write",
+            "exchange-rate:write",
             "hold:read",
             "hold:write",
             "order:make-external-payment",
@@ -422,6 +464,10 @@ fn get_scopes_test() {
             "org:write",
             "redeem:ticket",
             "region:write",
+            "settlement:approve",
+            "settlement:read",
+            "settlement:write",
+            "tax-rule:write",
             "ticket:admin",
             "ticket:read",
             "ticket:transfer",
@@ -0,0 +1,115 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::prelude::*;
+use models::*;
+use schema::settlement_adjustments;
+use utils::errors::*;
+use uuid::Uuid;
+use validators;
+use validators::*;
+
+#[derive(
+    Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize,
+)]
+#[belongs_to(Settlement)]
+#[table_name = "settlement_adjustments"]
+pub struct SettlementAdjustment {
+    pub id: Uuid,
+    pub settlement_id: Uuid,
+    pub adjustment_type: SettlementAdjustmentTypes,
+    pub amount_in_cents: i64,
+    pub note: Option<String>,
+    pub user_id: Uuid,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+/// A manual line on a settlement statement. Negative amounts are deducted from the amount
+/// paid to the organization.
+#[derive(Clone, Debug, Deserialize, Insertable, Serialize)]
+#[table_name = "settlement_adjustments"]
+pub struct NewSettlementAdjustment {
+    pub settlement_id: Uuid,
+    pub adjustment_type: SettlementAdjustmentTypes,
+    pub amount_in_cents: i64,
+    pub note: Option<String>,
+    pub user_id: Uuid,
+}
+
+impl NewSettlementAdjustment {
+    pub fn commit(self, conn: &PgConnection) -> Result<SettlementAdjustment, DatabaseError> {
+        let mut validation_errors = Ok(());
+        if self.amount_in_cents == 0 {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "amount_in_cents",
+                Err(create_validation_error(
+                    "amount_must_not_be_zero",
+                    "Adjustment amount cannot be zero",
+                )),
+            );
+        }
+        validation_errors?;
+
+        Settlement::find(self.settlement_id, conn)?.requires_draft("adjusted")?;
+
+        diesel::insert_into(settlement_adjustments::table)
+            .values(&self)
+            .get_result(conn)
+            .to_db_error(
+                ErrorCode::InsertError,
+                "Could not create settlement adjustment",
+            )
+    }
+}
+
+impl SettlementAdjustment {
+    pub fn create(
+        settlement_id: Uuid,
+        adjustment_type: SettlementAdjustmentTypes,
+        amount_in_cents: i64,
+        note: Option<String>,
+        user_id: Uuid,
+    ) -> NewSettlementAdjustment {
+        NewSettlementAdjustment {
+            settlement_id,
+            adjustment_type,
+            amount_in_cents,
+            note,
+            user_id,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<SettlementAdjustment, DatabaseError> {
+        settlement_adjustments::table
+            .find(id)
+            .first(conn)
+            .to_db_error(
+                ErrorCode::QueryError,
+                "Could not find settlement adjustment",
+            )
+    }
+
+    pub fn find_for_settlement(
+        settlement_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<SettlementAdjustment>, DatabaseError> {
+        settlement_adjustments::table
+            .filter(settlement_adjustments::settlement_id.eq(settlement_id))
+            .order_by(settlement_adjustments::created_at)
+            .load(conn)
+            .to_db_error(
+                ErrorCode::QueryError,
+                "Could not load settlement adjustments",
+            )
+    }
+
+    pub fn destroy(self, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        Settlement::find(self.settlement_id, conn)?.requires_draft("adjusted")?;
+
+        diesel::delete(&self).execute(conn).to_db_error(
+            ErrorCode::DeleteError,
+            "Could not delete settlement adjustment",
+        )
+    }
+}
@@ -0,0 +1,94 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::prelude::*;
+use models::*;
+use schema::settlement_partner_shares;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// A split partner's share of a settled event, deducted from the amount paid to the event's
+/// organization
+#[derive(
+    Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize,
+)]
+#[belongs_to(Settlement)]
+#[table_name = "settlement_partner_shares"]
+pub struct SettlementPartnerShare {
+    pub id: Uuid,
+    pub settlement_id: Uuid,
+    pub event_id: Uuid,
+    pub organization_id: Uuid,
+    pub ticket_sales_in_cents: i64,
+    pub fees_in_cents: i64,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "settlement_partner_shares"]
+struct NewSettlementPartnerShare {
+    settlement_id: Uuid,
+    event_id: Uuid,
+    organization_id: Uuid,
+    ticket_sales_in_cents: i64,
+    fees_in_cents: i64,
+}
+
+impl SettlementPartnerShare {
+    pub(crate) fn create(
+        settlement_id: Uuid,
+        share: &SplitPartnerShare,
+        conn: &PgConnection,
+    ) -> Result<SettlementPartnerShare, DatabaseError> {
+        diesel::insert_into(settlement_partner_shares::table)
+            .values(NewSettlementPartnerShare {
+                settlement_id,
+                event_id: share.event_id,
+                organization_id: share.organization_id,
+                ticket_sales_in_cents: share.ticket_sales_in_cents,
+                fees_in_cents: share.fees_in_cents,
+            })
+            .get_result(conn)
+            .to_db_error(
+                ErrorCode::InsertError,
+                "Could not create settlement partner share",
+            )
+    }
+
+    /// Whether the partner's share of the event is on an earlier settlement
+    pub(crate) fn is_settled(
+        event_id: Uuid,
+        organization_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<bool, DatabaseError> {
+        let settlement_partner_share: Option<Uuid> = settlement_partner_shares::table
+            .filter(settlement_partner_shares::event_id.eq(event_id))
+            .filter(settlement_partner_shares::organization_id.eq(organization_id))
+            .select(settlement_partner_shares::id)
+            .first(conn)
+            .optional()
+            .to_db_error(
+                ErrorCode::QueryError,
+                "Could not load settlement partner shares",
+            )?;
+        Ok(settlement_partner_share.is_some())
+    }
+
+    pub fn find_for_settlement(
+        settlement_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<SettlementPartnerShare>, DatabaseError> {
+        settlement_partner_shares::table
+            .filter(settlement_partner_shares::settlement_id.eq(settlement_id))
+            .order_by(settlement_partner_shares::created_at)
+            .load(conn)
+            .to_db_error(
+                ErrorCode::QueryError,
+                "Could not load settlement partner shares",
+            )
+    }
+
+    pub fn amount_in_cents(&self) -> i64 {
+        self.ticket_sales_in_cents + self.fees_in_cents
+    }
+}
@@ -0,0 +1,352 @@
+use chrono::prelude::*;
+use diesel;
+use diesel::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::{events, settlements};
+use utils::errors::*;
+use uuid::Uuid;
+use validators;
+use validators::*;
+
+#[derive(
+    Associations, Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize,
+)]
+#[belongs_to(Organization)]
+#[table_name = "settlements"]
+pub struct Settlement {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub event_id: Option<Uuid>,
+    pub start_time: Option<NaiveDateTime>,
+    pub end_time: Option<NaiveDateTime>,
+    pub status: SettlementStatus,
+    pub currency: String,
+    pub ticket_sales_in_cents: i64,
+    pub client_fees_in_cents: i64,
+    pub company_fees_in_cents: i64,
+    pub absorbed_fees_in_cents: i64,
+    pub user_id: Uuid,
+    pub approved_by_user_id: Option<Uuid>,
+    pub approved_at: Option<NaiveDateTime>,
+    pub paid_by_user_id: Option<Uuid>,
+    pub paid_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Deserialize, Insertable, Serialize)]
+#[table_name = "settlements"]
+pub struct NewSettlement {
+    pub organization_id: Uuid,
+    pub event_id: Option<Uuid>,
+    pub start_time: Option<NaiveDateTime>,
+    pub end_time: Option<NaiveDateTime>,
+    pub user_id: Uuid,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct DisplaySettlement {
+    #[serde(flatten)]
+    pub settlement: Settlement,
+    pub adjustments: Vec<SettlementAdjustment>,
+    pub adjustments_in_cents: i64,
+    pub partner_shares: Vec<SettlementPartnerShare>,
+    pub partner_shares_in_cents: i64,
+    pub total_in_cents: i64,
+}
+
+impl NewSettlement {
+    /// Generates the settlement totals from the event summary sales and fees for the event
+    /// or period being settled.
+    pub fn commit(self, conn: &PgConnection) -> Result<Settlement, DatabaseError> {
+        self.validate_record(conn)?;
+
+        let summaries = match self.event_id {
+            Some(event_id) => vec![Report::summary_event_report(
+                event_id,
+                self.start_time,
+                self.end_time,
+                conn,
+            )?],
+            None => Report::organization_summary_report(
+                self.organization_id,
+                self.start_time,
+                self.end_time,
+                conn,
+            )?,
+        };
+
+        // Totals are only meaningful in a single currency so periods with sales for events
+        // priced in different currencies must be settled per event
+        let event_ids: Vec<Uuid> = summaries.iter().map(|s| s.event_id).collect();
+        let mut currencies: Vec<String> = events::table
+            .filter(events::id.eq_any(&event_ids))
+            .select(events::currency)
+            .distinct()
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load event currencies")?;
+        if currencies.len() > 1 {
+            return DatabaseError::business_process_error(
+                "Settlement period includes sales in more than one currency, settle each event separately",
+            );
+        }
+        let currency = match (currencies.pop(), self.event_id) {
+            (Some(currency), _) => currency,
+            (None, Some(event_id)) => Event::find(event_id, conn)?.currency,
+            (None, None) => Organization::find(self.organization_id, conn)?.currency,
+        };
+
+        let mut ticket_sales_in_cents = 0;
+        let mut client_fees_in_cents = 0;
+        let mut company_fees_in_cents = 0;
+        let mut absorbed_fees_in_cents = 0;
+        let mut split_partner_shares = Vec::new();
+        for summary in summaries {
+            let totals = summary.totals();
+            ticket_sales_in_cents += totals.ticket_sales_in_cents;
+            client_fees_in_cents += totals.client_fees_in_cents;
+            company_fees_in_cents += totals.company_fees_in_cents;
+            absorbed_fees_in_cents += totals.absorbed_fees_in_cents;
+            for share in summary.split_partner_shares {
+                // Flat shares are settled once per event
+                if share.split_type == SplitTypes::Flat
+                    && SettlementPartnerShare::is_settled(
+                        share.event_id,
+                        share.organization_id,
+                        conn,
+                    )?
+                {
+                    continue;
+                }
+                split_partner_shares.push(share);
+            }
+        }
+
+        let settlement: Settlement = diesel::insert_into(settlements::table)
+            .values((
+                &self,
+                settlements::currency.eq(currency),
+                settlements::ticket_sales_in_cents.eq(ticket_sales_in_cents),
+                settlements::client_fees_in_cents.eq(client_fees_in_cents),
+                settlements::company_fees_in_cents.eq(company_fees_in_cents),
+                settlements::absorbed_fees_in_cents.eq(absorbed_fees_in_cents),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create settlement")?;
+        for share in &split_partner_shares {
+            SettlementPartnerShare::create(settlement.id, share, conn)?;
+        }
+
+        DomainEvent::create(
+            DomainEventTypes::SettlementCreated,
+            "Settlement created".to_string(),
+            Tables::Settlements,
+            Some(settlement.id),
+            Some(self.user_id),
+            None,
+        )
+        .commit(conn)?;
+
+        Ok(settlement)
+    }
+
+    fn validate_record(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        let mut validation_errors = Ok(());
+        if let (Some(start_time), Some(end_time)) = (self.start_time, self.end_time) {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "start_time",
+                validators::start_date_valid(start_time, end_time),
+            );
+        }
+        if let Some(event_id) = self.event_id {
+            let organization_id: Uuid = events::table
+                .filter(events::id.eq(event_id))
+                .select(events::organization_id)
+                .first(conn)
+                .to_db_error(ErrorCode::QueryError, "Could not find event")?;
+            if organization_id != self.organization_id {
+                validation_errors = validators::append_validation_error(
+                    validation_errors,
+                    "event_id",
+                    Err(create_validation_error(
+                        "event_not_in_organization",
+                        "Event does not belong to this organization",
+                    )),
+                );
+            }
+        }
+        Ok(validation_errors?)
+    }
+}
+
+impl Settlement {
+    pub fn create(
+        organization_id: Uuid,
+        event_id: Option<Uuid>,
+        start_time: Option<NaiveDateTime>,
+        end_time: Option<NaiveDateTime>,
+        user_id: Uuid,
+    ) -> NewSettlement {
+        NewSettlement {
+            organization_id,
+            event_id,
+            start_time,
+            end_time,
+            user_id,
+        }
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<Settlement, DatabaseError> {
+        settlements::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find settlement")
+    }
+
+    pub fn find_for_organization(
+        organization_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<Settlement>, DatabaseError> {
+        settlements::table
+            .filter(settlements::organization_id.eq(organization_id))
+            .order_by(settlements::created_at.desc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load settlements")
+    }
+
+    pub fn organization(&self, conn: &PgConnection) -> Result<Organization, DatabaseError> {
+        Organization::find(self.organization_id, conn)
+    }
+
+    pub fn adjustments(
+        &self,
+        conn: &PgConnection,
+    ) -> Result<Vec<SettlementAdjustment>, DatabaseError> {
+        SettlementAdjustment::find_for_settlement(self.id, conn)
+    }
+
+    pub fn partner_shares(
+        &self,
+        conn: &PgConnection,
+    ) -> Result<Vec<SettlementPartnerShare>, DatabaseError> {
+        SettlementPartnerShare::find_for_settlement(self.id, conn)
+    }
+
+    /// Amount due to the organization before adjustments and split partner shares
+    pub fn subtotal_in_cents(&self) -> i64 {
+        self.ticket_sales_in_cents + self.client_fees_in_cents - self.absorbed_fees_in_cents
+    }
+
+    pub fn total_in_cents(&self, conn: &PgConnection) -> Result<i64, DatabaseError> {
+        Ok(self.for_display(conn)?.total_in_cents)
+    }
+
+    pub fn for_display(&self, conn: &PgConnection) -> Result<DisplaySettlement, DatabaseError> {
+        let adjustments = self.adjustments(conn)?;
+        let adjustments_in_cents = adjustments.iter().map(|a| a.amount_in_cents).sum::<i64>();
+        let partner_shares = self.partner_shares(conn)?;
+        let partner_shares_in_cents = partner_shares
+            .iter()
+            .map(|s| s.amount_in_cents())
+            .sum::<i64>();
+
+        Ok(DisplaySettlement {
+            settlement: self.clone(),
+            adjustments,
+            adjustments_in_cents,
+            partner_shares,
+            partner_shares_in_cents,
+            total_in_cents: self.subtotal_in_cents() + adjustments_in_cents
+                - partner_shares_in_cents,
+        })
+    }
+
+    pub fn approve(self, user_id: Uuid, conn: &PgConnection) -> Result<Settlement, DatabaseError> {
+        self.requires_draft("approved")?;
+
+        let settlement: Settlement = diesel::update(&self)
+            .set((
+                settlements::status.eq(SettlementStatus::Approved),
+                settlements::approved_by_user_id.eq(user_id),
+                settlements::approved_at.eq(dsl::now.nullable()),
+                settlements::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not approve settlement")?;
+
+        DomainEvent::create(
+            DomainEventTypes::SettlementApproved,
+            "Settlement approved".to_string(),
+            Tables::Settlements,
+            Some(settlement.id),
+            Some(user_id),
+            None,
+        )
+        .commit(conn)?;
+
+        Ok(settlement)
+    }
+
+    /// Marks an approved settlement as paid. Event settlements record the amount paid on
+    /// the event.
+    pub fn mark_paid(
+        self,
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Settlement, DatabaseError> {
+        if self.status != SettlementStatus::Approved {
+            return Err(DatabaseError::new(
+                ErrorCode::BusinessProcessError,
+                Some("Only approved settlements can be marked as paid".to_string()),
+            ));
+        }
+
+        let total_in_cents = self.total_in_cents(conn)?;
+        let settlement: Settlement = diesel::update(&self)
+            .set((
+                settlements::status.eq(SettlementStatus::Paid),
+                settlements::paid_by_user_id.eq(user_id),
+                settlements::paid_at.eq(dsl::now.nullable()),
+                settlements::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not mark settlement as paid")?;
+
+        if let Some(event_id) = settlement.event_id {
+            diesel::update(events::table.filter(events::id.eq(event_id)))
+                .set((
+                    events::settlement_amount_in_cents.eq(total_in_cents),
+                    events::updated_at.eq(dsl::now),
+                ))
+                .execute(conn)
+                .to_db_error(
+                    ErrorCode::UpdateError,
+                    "Could not update event settlement amount",
+                )?;
+        }
+
+        DomainEvent::create(
+            DomainEventTypes::SettlementPaid,
+            "Settlement paid".to_string(),
+            Tables::Settlements,
+            Some(settlement.id),
+            Some(user_id),
+            Some(json!({ "total_in_cents": total_in_cents })),
+        )
+        .commit(conn)?;
+
+        Ok(settlement)
+    }
+
+    pub(crate) fn requires_draft(&self, action: &str) -> Result<(), DatabaseError> {
+        if self.status != SettlementStatus::Draft {
+            return Err(DatabaseError::new(
+                ErrorCode::BusinessProcessError,
+                Some(format!("Only draft settlements can be {}", action)),
+            ));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,225 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use models::*;
+use schema::tax_rules;
+use utils::errors::*;
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+use validators;
+use validators::*;
+
+const BASIS_POINTS: i64 = 10_000;
+
+#[derive(Clone, Debug, Deserialize, Identifiable, PartialEq, Queryable, Serialize)]
+pub struct TaxRule {
+    pub id: Uuid,
+    pub name: String,
+    pub region_id: Option<Uuid>,
+    pub venue_id: Option<Uuid>,
+    pub rate_in_basis_points: i64,
+    pub inclusive: bool,
+    pub applies_to_tickets: bool,
+    pub applies_to_per_unit_fees: bool,
+    pub applies_to_event_fees: bool,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    /// Deleted rules are kept for the orders they were applied to, but no longer apply
+    pub deleted_at: Option<NaiveDateTime>,
+}
+
+#[derive(AsChangeset, Default, Deserialize, Validate)]
+#[table_name = "tax_rules"]
+pub struct TaxRuleEditableAttributes {
+    pub name: Option<String>,
+    pub rate_in_basis_points: Option<i64>,
+    pub inclusive: Option<bool>,
+    pub applies_to_tickets: Option<bool>,
+    pub applies_to_per_unit_fees: Option<bool>,
+    pub applies_to_event_fees: Option<bool>,
+}
+
+#[derive(Clone, Debug, Deserialize, Insertable, Serialize, Validate)]
+#[table_name = "tax_rules"]
+pub struct NewTaxRule {
+    pub name: String,
+    pub region_id: Option<Uuid>,
+    pub venue_id: Option<Uuid>,
+    pub rate_in_basis_points: i64,
+    #[serde(default)]
+    pub inclusive: bool,
+    #[serde(default = "default_applies_to_tickets")]
+    pub applies_to_tickets: bool,
+    #[serde(default)]
+    pub applies_to_per_unit_fees: bool,
+    #[serde(default)]
+    pub applies_to_event_fees: bool,
+}
+
+fn default_applies_to_tickets() -> bool {
+    true
+}
+
+impl NewTaxRule {
+    pub fn commit(self, conn: &PgConnection) -> Result<TaxRule, DatabaseError> {
+        let mut validation_errors = self.validate();
+        validation_errors = validators::append_validation_error(
+            validation_errors,
+            "rate_in_basis_points",
+            TaxRule::rate_valid(self.rate_in_basis_points),
+        );
+        if self.region_id.is_none() && self.venue_id.is_none() {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "region_id",
+                Err(create_validation_error(
+                    "region_or_venue_required",
+                    "Tax rule must belong to a region or venue",
+                )),
+            );
+        }
+        validation_errors?;
+
+        diesel::insert_into(tax_rules::table)
+            .values(self)
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create tax rule")
+    }
+}
+
+impl TaxRule {
+    pub fn create(
+        name: &str,
+        region_id: Option<Uuid>,
+        venue_id: Option<Uuid>,
+        rate_in_basis_points: i64,
+        inclusive: bool,
+    ) -> NewTaxRule {
+        NewTaxRule {
+            name: name.to_string(),
+            region_id,
+            venue_id,
+            rate_in_basis_points,
+            inclusive,
+            applies_to_tickets: true,
+            applies_to_per_unit_fees: false,
+            applies_to_event_fees: false,
+        }
+    }
+
+    pub fn update(
+        &self,
+        attributes: TaxRuleEditableAttributes,
+        conn: &PgConnection,
+    ) -> Result<TaxRule, DatabaseError> {
+        if self.is_deleted() {
+            return DatabaseError::business_process_error("Tax rule has been deleted");
+        }
+        let mut validation_errors = attributes.validate();
+        if let Some(rate_in_basis_points) = attributes.rate_in_basis_points {
+            validation_errors = validators::append_validation_error(
+                validation_errors,
+                "rate_in_basis_points",
+                TaxRule::rate_valid(rate_in_basis_points),
+            );
+        }
+        validation_errors?;
+
+        diesel::update(self)
+            .set((attributes, tax_rules::updated_at.eq(dsl::now)))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update tax rule")
+    }
+
+    /// Stops the rule from applying to new orders, orders it was applied to still refer to it
+    pub fn destroy(&self, conn: &PgConnection) -> Result<TaxRule, DatabaseError> {
+        diesel::update(self)
+            .set((
+                tax_rules::deleted_at.eq(dsl::now.nullable()),
+                tax_rules::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not delete tax rule")
+    }
+
+    pub fn is_deleted(&self) -> bool {
+        self.deleted_at.is_some()
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<TaxRule, DatabaseError> {
+        tax_rules::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find tax rule")
+    }
+
+    pub fn all(conn: &PgConnection) -> Result<Vec<TaxRule>, DatabaseError> {
+        tax_rules::table
+            .filter(tax_rules::deleted_at.is_null())
+            .order_by(tax_rules::name)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load tax rules")
+    }
+
+    /// Rules attached to the event's venue take precedence over those of the venue's region.
+    /// Events without a venue are not taxed, deleted rules are ignored.
+    pub fn find_for_event(
+        event: &Event,
+        conn: &PgConnection,
+    ) -> Result<Vec<TaxRule>, DatabaseError> {
+        let venue = match event.venue_id {
+            Some(venue_id) => Venue::find(venue_id, conn)?,
+            None => return Ok(Vec::new()),
+        };
+
+        let venue_rules: Vec<TaxRule> = tax_rules::table
+            .filter(tax_rules::venue_id.eq(venue.id))
+            .filter(tax_rules::deleted_at.is_null())
+            .order_by(tax_rules::name)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load tax rules for venue")?;
+        if !venue_rules.is_empty() {
+            return Ok(venue_rules);
+        }
+
+        tax_rules::table
+            .filter(tax_rules::region_id.eq(venue.region_id))
+            .filter(tax_rules::venue_id.is_null())
+            .filter(tax_rules::deleted_at.is_null())
+            .order_by(tax_rules::name)
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load tax rules for region")
+    }
+
+    pub fn applies_to(&self, item_type: OrderItemTypes) -> bool {
+        match item_type {
+            OrderItemTypes::Tickets => self.applies_to_tickets,
+            OrderItemTypes::PerUnitFees => self.applies_to_per_unit_fees,
+            OrderItemTypes::EventFees => self.applies_to_event_fees,
+            OrderItemTypes::Tax => false,
+        }
+    }
+
+    /// Tax due on a single unit. For inclusive rules the tax is the portion of the price
+    /// that is attributable to tax, otherwise it is charged on top of the price.
+    pub fn calculate_tax(&self, unit_price_in_cents: i64) -> i64 {
+        let tax = if self.inclusive {
+            unit_price_in_cents as f64 * self.rate_in_basis_points as f64
+                / (BASIS_POINTS + self.rate_in_basis_points) as f64
+        } else {
+            unit_price_in_cents as f64 * self.rate_in_basis_points as f64 / BASIS_POINTS as f64
+        };
+        tax.round() as i64
+    }
+
+    fn rate_valid(rate_in_basis_points: i64) -> Result<(), ValidationError> {
+        if rate_in_basis_points < 0 {
+            return Err(create_validation_error(
+                "rate_must_not_be_negative",
+                "Rate cannot be negative",
+            ));
+        }
+        Ok(())
+    }
+}
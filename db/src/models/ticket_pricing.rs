@@ -13,7 +13,7 @@ use validators::{self, *};
 
 sql_function!(fn ticket_pricing_no_overlapping_periods(id: dUuid, ticket_type_id: dUuid, start_date: Timestamp, end_date: Timestamp, is_box_office_only: Bool, is_default_status: Bool) -> Bool);
 
-#[derive(Clone, Identifiable, Associations, Queryable, PartialEq, Debug)]
+#[derive(Clone, Identifiable, Associations, Queryable, PartialEq, Debug, Serialize)]
 #[belongs_to(TicketType)]
 #[table_name = "ticket_pricing"]
 pub struct TicketPricing {
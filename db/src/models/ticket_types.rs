@@ -13,7 +13,9 @@ use uuid::Uuid;
 use validator::*;
 use validators;
 
-#[derive(Associations, Clone, Debug, Identifiable, PartialEq, Queryable, QueryableByName)]
+#[derive(
+    Associations, Clone, Debug, Identifiable, PartialEq, Queryable, QueryableByName, Serialize,
+)]
 #[table_name = "ticket_types"]
 #[belongs_to(Event)]
 pub struct TicketType {
@@ -30,6 +32,7 @@ pub struct TicketType {
     updated_at: NaiveDateTime,
     pub price_in_cents: i64,
     pub cancelled_at: Option<NaiveDateTime>,
+    pub fee_mode: Option<FeeModes>,
 }
 
 #[derive(AsChangeset, Default, Deserialize)]
@@ -42,6 +45,7 @@ pub struct TicketTypeEditableAttributes {
     pub increment: Option<i32>,
     pub limit_per_person: Option<i32>,
     pub price_in_cents: Option<i64>,
+    pub fee_mode: Option<Option<FeeModes>>,
 }
 
 impl TicketType {
@@ -61,6 +65,23 @@ impl TicketType {
             )
     }
 
+    /// The fee mode used when selling this ticket type, falling back to the event's fee mode
+    /// when it has not been overridden
+    pub fn effective_fee_mode(&self, conn: &PgConnection) -> Result<FeeModes, DatabaseError> {
+        if let Some(fee_mode) = self.fee_mode {
+            return Ok(fee_mode);
+        }
+
+        events::table
+            .filter(events::id.eq(self.event_id))
+            .select(events::fee_mode)
+            .first(conn)
+            .to_db_error(
+                ErrorCode::QueryError,
+                "Could not retrieve fee mode for ticket type",
+            )
+    }
+
     /// Creates a ticket type. `Event::add_ticket_type` should be used in most scenarios
     pub(crate) fn create(
         event_id: Uuid,
@@ -121,6 +142,20 @@ impl TicketType {
         Ok(result)
     }
 
+    pub fn update_fee_mode(
+        &self,
+        fee_mode: Option<FeeModes>,
+        conn: &PgConnection,
+    ) -> Result<TicketType, DatabaseError> {
+        diesel::update(self)
+            .set((
+                ticket_types::fee_mode.eq(fee_mode),
+                ticket_types::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not update ticket_types")
+    }
+
     pub fn cancel(&self, conn: &PgConnection) -> Result<TicketType, DatabaseError> {
         let result: TicketType = diesel::update(self)
             .set((
@@ -0,0 +1,236 @@
+use chrono::{NaiveDateTime, Utc};
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use diesel::sql_types::{Array, Text};
+use schema::two_factor_authentications;
+use utils::encryption;
+use utils::errors::*;
+use utils::rand::random_alpha_string;
+use utils::totp;
+use uuid::Uuid;
+
+sql_function!(fn array_remove(array: Array<Text>, element: Text) -> Array<Text>);
+
+pub const RECOVERY_CODE_COUNT: usize = 10;
+const RECOVERY_CODE_LENGTH: usize = 10;
+/// Codes from the steps either side of the current one are accepted to allow for clock drift
+const ALLOWED_STEP_DRIFT: i64 = 1;
+
+/// A user's authenticator app (TOTP) enrolment. It only protects the account once the user
+/// has confirmed it with a code, `enabled_at` is set from then on.
+#[derive(Clone, Debug, Identifiable, PartialEq, Queryable, Serialize)]
+#[table_name = "two_factor_authentications"]
+pub struct TwoFactorAuthentication {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    #[serde(skip_serializing)]
+    pub secret: String,
+    #[serde(skip_serializing)]
+    pub recovery_code_hashes: Vec<String>,
+    #[serde(skip_serializing)]
+    pub last_used_step: Option<i64>,
+    pub enabled_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "two_factor_authentications"]
+struct NewTwoFactorAuthentication {
+    user_id: Uuid,
+    secret: String,
+}
+
+impl TwoFactorAuthentication {
+    /// Starts enrolment with a new secret, replacing an enrolment that was never confirmed.
+    /// The base32 secret for the authenticator app is returned here only, it is stored
+    /// encrypted.
+    pub fn create(
+        user_id: Uuid,
+        encryption_key: &str,
+        conn: &PgConnection,
+    ) -> Result<(TwoFactorAuthentication, String), DatabaseError> {
+        if let Some(existing) = TwoFactorAuthentication::find_for_user(user_id, conn)? {
+            if existing.is_enabled() {
+                return DatabaseError::business_process_error(
+                    "Two factor authentication is already enabled",
+                );
+            }
+            existing.destroy(conn)?;
+        }
+
+        let secret = totp::base32_encode(&totp::generate_secret());
+        let two_factor_authentication = diesel::insert_into(two_factor_authentications::table)
+            .values(NewTwoFactorAuthentication {
+                user_id,
+                secret: encryption::encrypt(&secret, encryption_key)?,
+            })
+            .get_result(conn)
+            .to_db_error(
+                ErrorCode::InsertError,
+                "Could not create two factor authentication",
+            )?;
+        Ok((two_factor_authentication, secret))
+    }
+
+    pub fn find_for_user(
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Option<TwoFactorAuthentication>, DatabaseError> {
+        two_factor_authentications::table
+            .filter(two_factor_authentications::user_id.eq(user_id))
+            .first(conn)
+            .optional()
+            .to_db_error(
+                ErrorCode::QueryError,
+                "Could not load two factor authentication",
+            )
+    }
+
+    pub fn find_enabled_for_user(
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Option<TwoFactorAuthentication>, DatabaseError> {
+        Ok(TwoFactorAuthentication::find_for_user(user_id, conn)?.filter(|t| t.is_enabled()))
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled_at.is_some()
+    }
+
+    /// Confirms enrolment with a code from the authenticator app and returns the recovery
+    /// codes, which like the secret are only ever shown this once
+    pub fn enable(
+        &self,
+        code: &str,
+        encryption_key: &str,
+        conn: &PgConnection,
+    ) -> Result<Vec<String>, DatabaseError> {
+        if self.is_enabled() {
+            return DatabaseError::business_process_error(
+                "Two factor authentication is already enabled",
+            );
+        }
+        if !self.verify_code(code, encryption_key, conn)? {
+            return DatabaseError::validation_error("code", "Code is incorrect");
+        }
+
+        let recovery_codes: Vec<String> = (0..RECOVERY_CODE_COUNT)
+            .map(|_| random_alpha_string(RECOVERY_CODE_LENGTH).to_lowercase())
+            .collect();
+        diesel::update(self)
+            .set((
+                two_factor_authentications::enabled_at.eq(dsl::now.nullable()),
+                two_factor_authentications::recovery_code_hashes.eq(recovery_codes
+                    .iter()
+                    .map(|c| encryption::hash_secret(c))
+                    .collect::<Vec<String>>()),
+                two_factor_authentications::updated_at.eq(dsl::now),
+            ))
+            .execute(conn)
+            .to_db_error(
+                ErrorCode::UpdateError,
+                "Could not enable two factor authentication",
+            )?;
+        Ok(recovery_codes)
+    }
+
+    /// Checks a code from the authenticator app, or failing that a recovery code. Each code
+    /// can only be used once.
+    pub fn verify(
+        &self,
+        code: &str,
+        encryption_key: &str,
+        conn: &PgConnection,
+    ) -> Result<bool, DatabaseError> {
+        Ok(self.verify_code(code, encryption_key, conn)? || self.use_recovery_code(code, conn)?)
+    }
+
+    fn verify_code(
+        &self,
+        code: &str,
+        encryption_key: &str,
+        conn: &PgConnection,
+    ) -> Result<bool, DatabaseError> {
+        let code: String = code.chars().filter(|c| !c.is_whitespace()).collect();
+        let secret = encryption::decrypt(&self.secret, &encryption_key.to_string())?;
+        let secret = totp::base32_decode(&secret).ok_or_else(|| {
+            DatabaseError::new(
+                ErrorCode::InternalError,
+                Some("Two factor secret is invalid".to_string()),
+            )
+        })?;
+
+        let current_step = totp::time_step(Utc::now().naive_utc().timestamp());
+        let step = ((current_step - ALLOWED_STEP_DRIFT)..(current_step + ALLOWED_STEP_DRIFT + 1))
+            .filter(|step| self.last_used_step.map(|l| *step > l).unwrap_or(true))
+            .find(|step| totp::code_at(&secret, *step) == code);
+        let step = match step {
+            Some(step) => step,
+            None => return Ok(false),
+        };
+
+        // Conditional on the last used step so concurrent requests can't both use the code
+        let updated = diesel::update(
+            two_factor_authentications::table
+                .filter(two_factor_authentications::id.eq(self.id))
+                .filter(
+                    two_factor_authentications::last_used_step
+                        .is_null()
+                        .or(two_factor_authentications::last_used_step.lt(step)),
+                ),
+        )
+        .set((
+            two_factor_authentications::last_used_step.eq(step),
+            two_factor_authentications::updated_at.eq(dsl::now),
+        ))
+        .execute(conn)
+        .to_db_error(
+            ErrorCode::UpdateError,
+            "Could not update two factor authentication",
+        )?;
+        Ok(updated == 1)
+    }
+
+    fn use_recovery_code(&self, code: &str, conn: &PgConnection) -> Result<bool, DatabaseError> {
+        let code: String = code
+            .chars()
+            .filter(|c| c.is_alphanumeric())
+            .collect::<String>()
+            .to_lowercase();
+        let code_hash = encryption::hash_secret(&code);
+        if !self.recovery_code_hashes.contains(&code_hash) {
+            return Ok(false);
+        }
+
+        let updated = diesel::update(
+            two_factor_authentications::table
+                .filter(two_factor_authentications::id.eq(self.id))
+                .filter(
+                    two_factor_authentications::recovery_code_hashes
+                        .contains(vec![code_hash.clone()]),
+                ),
+        )
+        .set((
+            two_factor_authentications::recovery_code_hashes.eq(array_remove(
+                two_factor_authentications::recovery_code_hashes,
+                code_hash,
+            )),
+            two_factor_authentications::updated_at.eq(dsl::now),
+        ))
+        .execute(conn)
+        .to_db_error(
+            ErrorCode::UpdateError,
+            "Could not update two factor authentication",
+        )?;
+        Ok(updated == 1)
+    }
+
+    pub fn destroy(self, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        diesel::delete(&self).execute(conn).to_db_error(
+            ErrorCode::DeleteError,
+            "Could not remove two factor authentication",
+        )
+    }
+}
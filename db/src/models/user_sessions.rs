@@ -0,0 +1,179 @@
+use chrono::NaiveDateTime;
+use diesel;
+use diesel::expression::dsl;
+use diesel::prelude::*;
+use ring::digest;
+use schema::user_sessions;
+use utils::errors::*;
+use uuid::Uuid;
+
+/// A signed in device. Each refresh of the session's access token rotates its refresh token,
+/// only the latest refresh token (`refresh_token_id`) can be used.
+#[derive(Clone, Debug, Identifiable, PartialEq, Queryable, Serialize)]
+#[table_name = "user_sessions"]
+pub struct UserSession {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    #[serde(skip_serializing)]
+    pub refresh_token_id: Uuid,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub last_used_at: NaiveDateTime,
+    pub revoked_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "user_sessions"]
+struct NewUserSession {
+    id: Option<Uuid>,
+    user_id: Uuid,
+    refresh_token_id: Uuid,
+    user_agent: Option<String>,
+    ip_address: Option<String>,
+}
+
+impl UserSession {
+    pub fn create(
+        user_id: Uuid,
+        user_agent: Option<String>,
+        ip_address: Option<String>,
+        conn: &PgConnection,
+    ) -> Result<UserSession, DatabaseError> {
+        diesel::insert_into(user_sessions::table)
+            .values(NewUserSession {
+                id: None,
+                user_id,
+                refresh_token_id: Uuid::new_v4(),
+                user_agent,
+                ip_address,
+            })
+            .get_result(conn)
+            .to_db_error(ErrorCode::InsertError, "Could not create session")
+    }
+
+    /// Starts a session for a refresh token issued before sessions were recorded. The session
+    /// id is derived from the token so each token can only start one session, a token that has
+    /// already been used has been copied so its session is revoked and `None` returned.
+    pub fn create_from_legacy_token(
+        user_id: Uuid,
+        legacy_token: &str,
+        user_agent: Option<String>,
+        ip_address: Option<String>,
+        conn: &PgConnection,
+    ) -> Result<Option<UserSession>, DatabaseError> {
+        let hash = digest::digest(&digest::SHA256, legacy_token.as_bytes());
+        let id = Uuid::from_bytes(&hash.as_ref()[..16]).map_err(|_| {
+            DatabaseError::new(
+                ErrorCode::InternalError,
+                Some("Could not derive session id".to_string()),
+            )
+        })?;
+
+        let session = diesel::insert_into(user_sessions::table)
+            .values(NewUserSession {
+                id: Some(id),
+                user_id,
+                refresh_token_id: Uuid::new_v4(),
+                user_agent,
+                ip_address,
+            })
+            .on_conflict_do_nothing()
+            .get_result(conn)
+            .optional()
+            .to_db_error(ErrorCode::InsertError, "Could not create session")?;
+        if session.is_none() {
+            let session = UserSession::find(id, conn)?;
+            if !session.is_revoked() {
+                session.revoke(conn)?;
+            }
+        }
+        Ok(session)
+    }
+
+    pub fn find(id: Uuid, conn: &PgConnection) -> Result<UserSession, DatabaseError> {
+        user_sessions::table
+            .find(id)
+            .first(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not find session")
+    }
+
+    /// Sessions that have not been revoked, most recently used first
+    pub fn find_active_for_user(
+        user_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Vec<UserSession>, DatabaseError> {
+        user_sessions::table
+            .filter(user_sessions::user_id.eq(user_id))
+            .filter(user_sessions::revoked_at.is_null())
+            .order_by(user_sessions::last_used_at.desc())
+            .load(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not load sessions")
+    }
+
+    pub fn is_revoked(&self) -> bool {
+        self.revoked_at.is_some()
+    }
+
+    /// Exchanges the presented refresh token for a new one. A token that has already been
+    /// exchanged means it has been copied, so the whole session is revoked and `None` returned.
+    pub fn rotate(
+        &self,
+        refresh_token_id: Uuid,
+        conn: &PgConnection,
+    ) -> Result<Option<UserSession>, DatabaseError> {
+        if self.is_revoked() {
+            return Ok(None);
+        }
+        if refresh_token_id != self.refresh_token_id {
+            self.revoke(conn)?;
+            return Ok(None);
+        }
+
+        // Conditional on the presented token so only one of two concurrent refreshes succeeds
+        let session = diesel::update(
+            user_sessions::table
+                .filter(user_sessions::id.eq(self.id))
+                .filter(user_sessions::refresh_token_id.eq(refresh_token_id))
+                .filter(user_sessions::revoked_at.is_null()),
+        )
+        .set((
+            user_sessions::refresh_token_id.eq(Uuid::new_v4()),
+            user_sessions::last_used_at.eq(dsl::now),
+            user_sessions::updated_at.eq(dsl::now),
+        ))
+        .get_result(conn)
+        .optional()
+        .to_db_error(ErrorCode::UpdateError, "Could not refresh session")?;
+        if session.is_none() {
+            self.revoke(conn)?;
+        }
+        Ok(session)
+    }
+
+    pub fn revoke(&self, conn: &PgConnection) -> Result<UserSession, DatabaseError> {
+        diesel::update(self)
+            .set((
+                user_sessions::revoked_at.eq(dsl::now.nullable()),
+                user_sessions::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not revoke session")
+    }
+
+    /// Signs the user out everywhere, returning the number of sessions revoked
+    pub fn revoke_all_for_user(user_id: Uuid, conn: &PgConnection) -> Result<usize, DatabaseError> {
+        diesel::update(
+            user_sessions::table
+                .filter(user_sessions::user_id.eq(user_id))
+                .filter(user_sessions::revoked_at.is_null()),
+        )
+        .set((
+            user_sessions::revoked_at.eq(dsl::now.nullable()),
+            user_sessions::updated_at.eq(dsl::now),
+        ))
+        .execute(conn)
+        .to_db_error(ErrorCode::UpdateError, "Could not revoke sessions")
+    }
+}
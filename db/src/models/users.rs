@@ -4,9 +4,15 @@ use diesel;
 use diesel::expression::dsl;
 use diesel::expression::sql_literal::sql;
 use diesel::prelude::*;
-use diesel::sql_types::BigInt;
+use diesel::sql_types::{BigInt, Json, Uuid as dUuid};
 use models::*;
-use schema::{events, organization_users, organizations, users};
+use schema::{
+    affiliates, audit_logs, campaign_recipients, communication_consents, communication_preferences,
+    event_interest, event_users, events, external_logins, fan_notes, fan_tags, organization_users,
+    organizations, payment_methods, phone_verifications, push_notification_tokens,
+    report_subscriptions, two_factor_authentications, user_sessions, users,
+};
+use serde_json;
 use std::collections::HashMap;
 use time::Duration;
 use utils::errors::{ConvertToDatabaseError, DatabaseError, ErrorCode};
@@ -50,6 +56,14 @@ pub struct User {
     pub last_cart_id: Option<Uuid>,
     pub accepted_terms_date: Option<NaiveDateTime>,
     pub invited_at: Option<NaiveDateTime>,
+    pub failed_login_attempts: i32,
+    pub locked_until: Option<NaiveDateTime>,
+    pub email_verified_at: Option<NaiveDateTime>,
+    pub phone_verified_at: Option<NaiveDateTime>,
+    pub login_token: Option<Uuid>,
+    pub login_code_hash: Option<String>,
+    pub login_token_requested_at: Option<NaiveDateTime>,
+    pub login_code_failed_attempts: i32,
 }
 
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
@@ -360,11 +374,75 @@ impl User {
         let query =
             diesel::update(self).set((lower_cased_attributes, users::updated_at.eq(dsl::now)));
 
-        DatabaseError::wrap(
+        let user: User = DatabaseError::wrap(
             ErrorCode::UpdateError,
             "Error updating user",
             query.get_result(conn),
-        )
+        )?;
+
+        // A changed email or phone has to be verified again
+        if user.email == self.email && user.phone == self.phone {
+            return Ok(user);
+        }
+        diesel::update(&user)
+            .set((
+                users::email_verified_at.eq(if user.email == self.email {
+                    user.email_verified_at
+                } else {
+                    None
+                }),
+                users::phone_verified_at.eq(if user.phone == self.phone {
+                    user.phone_verified_at
+                } else {
+                    None
+                }),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Error updating user")
+    }
+
+    pub fn is_email_verified(&self) -> bool {
+        self.email.is_some() && self.email_verified_at.is_some()
+    }
+
+    pub fn is_phone_verified(&self) -> bool {
+        self.phone.is_some() && self.phone_verified_at.is_some()
+    }
+
+    /// Whether the user can be reached on every email and phone they have given, which must be
+    /// at least one
+    pub fn has_verified_contact_details(&self) -> bool {
+        (self.email.is_some() || self.phone.is_some())
+            && (self.email.is_none() || self.is_email_verified())
+            && (self.phone.is_none() || self.is_phone_verified())
+    }
+
+    /// Marks `email` as verified, provided it is still the user's email
+    pub fn verify_email(&self, email: &str, conn: &PgConnection) -> Result<User, DatabaseError> {
+        if self.email.as_ref().map(|e| e.as_str()) != Some(&email.to_lowercase()) {
+            return DatabaseError::validation_error("email", "Email has changed since it was sent");
+        }
+        diesel::update(self)
+            .set((
+                users::email_verified_at.eq(dsl::now.nullable()),
+                users::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not verify email")
+    }
+
+    /// Marks `phone` as verified, provided it is still the user's phone
+    pub fn verify_phone(&self, phone: &str, conn: &PgConnection) -> Result<User, DatabaseError> {
+        if self.phone.as_ref().map(|p| p.as_str()) != Some(phone) {
+            return DatabaseError::validation_error("phone", "Phone has changed since it was sent");
+        }
+        diesel::update(self)
+            .set((
+                users::phone_verified_at.eq(dsl::now.nullable()),
+                users::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not verify phone")
     }
 
     pub fn check_password(&self, password: &str) -> bool {
@@ -375,6 +453,55 @@ impl User {
         hash.verify(password)
     }
 
+    /// Sign in with a password is refused until `locked_until` has passed
+    pub fn is_locked(&self) -> bool {
+        self.locked_until
+            .map(|locked_until| locked_until > Utc::now().naive_utc())
+            .unwrap_or(false)
+    }
+
+    /// Counts a failed password sign in. Once `max_attempts` is reached the account is locked
+    /// for `lockout_period` and the count starts over. Returns true if this attempt locked it.
+    pub fn record_failed_login(
+        &self,
+        max_attempts: i32,
+        lockout_period: Duration,
+        conn: &PgConnection,
+    ) -> Result<bool, DatabaseError> {
+        // updated_at is left alone, it guards the user's cart against concurrent changes
+        let failed_login_attempts: i32 = diesel::update(self)
+            .set(users::failed_login_attempts.eq(users::failed_login_attempts + 1))
+            .returning(users::failed_login_attempts)
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not record failed login")?;
+        if failed_login_attempts < max_attempts {
+            return Ok(false);
+        }
+
+        diesel::update(self)
+            .set((
+                users::failed_login_attempts.eq(0),
+                users::locked_until.eq(Utc::now().naive_utc() + lockout_period),
+            ))
+            .execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not lock user")?;
+        Ok(true)
+    }
+
+    pub fn clear_failed_logins(&self, conn: &PgConnection) -> Result<(), DatabaseError> {
+        if self.failed_login_attempts == 0 && self.locked_until.is_none() {
+            return Ok(());
+        }
+        diesel::update(self)
+            .set((
+                users::failed_login_attempts.eq(0),
+                users::locked_until.eq(None::<NaiveDateTime>),
+            ))
+            .execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not clear failed logins")?;
+        Ok(())
+    }
+
     pub fn add_role(&self, r: Roles, conn: &PgConnection) -> Result<User, DatabaseError> {
         let mut new_roles = self.role.clone();
         if !new_roles.contains(&r) {
@@ -560,6 +687,183 @@ impl User {
         Wallet::find_for_user(self.id, conn)
     }
 
+    /// Everything held about the user as a single JSON document, for data subject access
+    /// requests
+    pub fn export_data(&self, conn: &PgConnection) -> Result<serde_json::Value, DatabaseError> {
+        #[derive(QueryableByName)]
+        struct R {
+            #[sql_type = "Json"]
+            data: serde_json::Value,
+        }
+
+        let result: R = diesel::sql_query(include_str!("../queries/user_data_export.sql"))
+            .bind::<dUuid, _>(self.id)
+            .get_result(conn)
+            .to_db_error(ErrorCode::QueryError, "Could not export user data")?;
+        Ok(result.data)
+    }
+
+    /// Anonymizes the user and removes their personal data. Orders, payments and tickets are
+    /// kept so financial records and report totals are unchanged, they just no longer
+    /// identify anyone. Consent records are kept without the IP address they were given from,
+    /// sessions are revoked and kept without their device details, and audit logs are kept
+    /// without saying who made the change. Their email and phone number are redacted wherever
+    /// they appear in audit logs and communications.
+    pub fn erase(
+        self,
+        erased_by_user_id: Option<Uuid>,
+        conn: &PgConnection,
+    ) -> Result<User, DatabaseError> {
+        if self
+            .get_roles_by_organization(conn)?
+            .values()
+            .any(|roles| roles.contains(&Roles::OrgOwner))
+        {
+            return DatabaseError::business_process_error(
+                "User owns an organization, ownership must be transferred before the user can be erased",
+            );
+        }
+
+        diesel::delete(external_logins::table.filter(external_logins::user_id.eq(self.id)))
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not remove external logins")?;
+        diesel::delete(
+            push_notification_tokens::table.filter(push_notification_tokens::user_id.eq(self.id)),
+        )
+        .execute(conn)
+        .to_db_error(
+            ErrorCode::DeleteError,
+            "Could not remove push notification tokens",
+        )?;
+        diesel::delete(payment_methods::table.filter(payment_methods::user_id.eq(self.id)))
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not remove payment methods")?;
+        diesel::delete(event_interest::table.filter(event_interest::user_id.eq(self.id)))
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not remove event interest")?;
+        diesel::delete(fan_notes::table.filter(fan_notes::user_id.eq(self.id)))
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not remove fan notes")?;
+        diesel::delete(fan_tags::table.filter(fan_tags::user_id.eq(self.id)))
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not remove fan tags")?;
+        diesel::delete(organization_users::table.filter(organization_users::user_id.eq(self.id)))
+            .execute(conn)
+            .to_db_error(
+                ErrorCode::DeleteError,
+                "Could not remove organization memberships",
+            )?;
+        diesel::delete(
+            report_subscriptions::table.filter(report_subscriptions::user_id.eq(self.id)),
+        )
+        .execute(conn)
+        .to_db_error(
+            ErrorCode::DeleteError,
+            "Could not remove report subscriptions",
+        )?;
+        diesel::delete(
+            communication_preferences::table.filter(communication_preferences::user_id.eq(self.id)),
+        )
+        .execute(conn)
+        .to_db_error(
+            ErrorCode::DeleteError,
+            "Could not remove communication preferences",
+        )?;
+        diesel::update(
+            communication_consents::table.filter(communication_consents::user_id.eq(self.id)),
+        )
+        .set(communication_consents::ip_address.eq(None::<String>))
+        .execute(conn)
+        .to_db_error(
+            ErrorCode::UpdateError,
+            "Could not anonymize communication consents",
+        )?;
+        diesel::update(campaign_recipients::table.filter(campaign_recipients::user_id.eq(self.id)))
+            .set(campaign_recipients::address.eq(None::<String>))
+            .execute(conn)
+            .to_db_error(
+                ErrorCode::UpdateError,
+                "Could not anonymize campaign recipients",
+            )?;
+        diesel::update(affiliates::table.filter(affiliates::user_id.eq(self.id)))
+            .set(affiliates::user_id.eq(None::<Uuid>))
+            .execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not unlink affiliates")?;
+        diesel::delete(event_users::table.filter(event_users::user_id.eq(self.id)))
+            .execute(conn)
+            .to_db_error(ErrorCode::DeleteError, "Could not remove event roles")?;
+        diesel::delete(
+            two_factor_authentications::table
+                .filter(two_factor_authentications::user_id.eq(self.id)),
+        )
+        .execute(conn)
+        .to_db_error(
+            ErrorCode::DeleteError,
+            "Could not remove two factor authentication",
+        )?;
+        diesel::delete(phone_verifications::table.filter(phone_verifications::user_id.eq(self.id)))
+            .execute(conn)
+            .to_db_error(
+                ErrorCode::DeleteError,
+                "Could not remove phone verifications",
+            )?;
+        UserSession::revoke_all_for_user(self.id, conn)?;
+        diesel::update(user_sessions::table.filter(user_sessions::user_id.eq(self.id)))
+            .set((
+                user_sessions::user_agent.eq(None::<String>),
+                user_sessions::ip_address.eq(None::<String>),
+            ))
+            .execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not anonymize sessions")?;
+        diesel::update(audit_logs::table.filter(audit_logs::user_id.eq(self.id)))
+            .set(audit_logs::user_id.eq(None::<Uuid>))
+            .execute(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not anonymize audit logs")?;
+        for personal_data in self.email.iter().chain(self.phone.iter()) {
+            if personal_data.trim().is_empty() {
+                continue;
+            }
+            AuditLog::redact(personal_data, conn)?;
+            DomainAction::redact(personal_data, conn)?;
+        }
+
+        let user: User = diesel::update(&self)
+            .set((
+                users::first_name.eq(None::<String>),
+                users::last_name.eq(None::<String>),
+                users::email.eq(None::<String>),
+                users::phone.eq(None::<String>),
+                users::profile_pic_url.eq(None::<String>),
+                users::thumb_profile_pic_url.eq(None::<String>),
+                users::cover_photo_url.eq(None::<String>),
+                users::hashed_pw
+                    .eq(PasswordHash::generate(&random_alpha_string(32), None).to_string()),
+                users::active.eq(false),
+                users::role.eq(vec![Roles::User]),
+                users::password_reset_token.eq(None::<Uuid>),
+                users::password_reset_requested_at.eq(None::<NaiveDateTime>),
+                users::email_verified_at.eq(None::<NaiveDateTime>),
+                users::phone_verified_at.eq(None::<NaiveDateTime>),
+                users::login_token.eq(None::<Uuid>),
+                users::login_code_hash.eq(None::<String>),
+                users::login_token_requested_at.eq(None::<NaiveDateTime>),
+                users::updated_at.eq(dsl::now),
+            ))
+            .get_result(conn)
+            .to_db_error(ErrorCode::UpdateError, "Could not erase user")?;
+
+        DomainEvent::create(
+            DomainEventTypes::UserErased,
+            "User erased".to_string(),
+            Tables::Users,
+            Some(user.id),
+            erased_by_user_id,
+            None,
+        )
+        .commit(conn)?;
+        Ok(user)
+    }
+
     pub fn update_last_cart(
         &self,
         new_cart_id: Option<Uuid>,
@@ -0,0 +1,15 @@
+-- Owners of purchased or redeemed tickets for an event ($1) or a ticket type ($2)
+SELECT DISTINCT u.id AS user_id,
+                u.first_name,
+                u.last_name,
+                u.email,
+                u.phone
+FROM ticket_instances ti
+       INNER JOIN assets a ON ti.asset_id = a.id
+       INNER JOIN ticket_types tt ON a.ticket_type_id = tt.id
+       INNER JOIN wallets w ON ti.wallet_id = w.id
+       INNER JOIN users u ON w.user_id = u.id
+WHERE ti.status IN ('Purchased', 'Redeemed')
+  AND ($1 IS NULL OR tt.event_id = $1)
+  AND ($2 IS NULL OR tt.id = $2)
+ORDER BY u.id;
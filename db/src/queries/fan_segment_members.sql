@@ -0,0 +1,45 @@
+-- Fans of the organization matching a segment's criteria. A fan's purchases are limited to those
+-- made since $2 before the event count, revenue and genre criteria are applied, and the fan must
+-- have every tag in $6. $9 limits the results to a single user.
+WITH fans AS (
+  SELECT o.user_id,
+         COUNT(DISTINCT oi.event_id)                                                      AS event_count,
+         COUNT(DISTINCT o.id)                                                             AS order_count,
+         MIN(o.order_date)                                                                AS first_order_time,
+         MAX(o.order_date)                                                                AS last_order_time,
+         CAST(SUM(oi.unit_price_in_cents * (oi.quantity - oi.refunded_quantity)) AS BIGINT) AS revenue_in_cents,
+         BOOL_OR(EXISTS(SELECT 1 FROM UNNEST(e.genres) g WHERE LOWER(g) = LOWER($5)))     AS in_genre
+  FROM orders o
+         INNER JOIN order_items oi ON o.id = oi.order_id
+         INNER JOIN events e ON oi.event_id = e.id
+  WHERE o.status = 'Paid'
+    AND e.organization_id = $1
+    AND ($2 IS NULL OR o.order_date >= $2)
+    AND ($9 IS NULL OR o.user_id = $9)
+  GROUP BY o.user_id
+)
+SELECT $1                     AS organization_id,
+       u.id                   AS user_id,
+       u.first_name,
+       u.last_name,
+       u.email,
+       u.phone,
+       u.thumb_profile_pic_url,
+       u.created_at,
+       f.order_count,
+       f.first_order_time,
+       f.last_order_time,
+       f.revenue_in_cents,
+       COUNT(*) OVER ()       AS total
+FROM fans f
+       INNER JOIN users u ON f.user_id = u.id
+WHERE ($3 IS NULL OR f.event_count >= $3)
+  AND ($4 IS NULL OR f.revenue_in_cents >= $4)
+  AND ($5 IS NULL OR f.in_genre)
+  AND (SELECT COUNT(*)
+       FROM fan_tags ft
+       WHERE ft.organization_id = $1
+         AND ft.user_id = u.id
+         AND ft.tag = ANY ($6)) = CARDINALITY($6)
+ORDER BY f.last_order_time DESC, u.id
+LIMIT $7 OFFSET $8;
@@ -0,0 +1,41 @@
+-- Paid orders are attributed to the affiliate whose tracking link created the cart, as long as
+-- the order was paid within the affiliate's attribution window. A commission rule for the
+-- ticket type takes precedence over the affiliate's default rule.
+SELECT a.id                                                                         AS affiliate_id,
+       a.name                                                                       AS affiliate_name,
+       a.code                                                                       AS affiliate_code,
+       e.id                                                                         AS event_id,
+       e.name                                                                       AS event_name,
+       tt.id                                                                        AS ticket_type_id,
+       tt.name                                                                      AS ticket_type_name,
+       CAST(COUNT(DISTINCT o.id) AS BIGINT)                                         AS order_count,
+       CAST(SUM(oi.quantity - oi.refunded_quantity) AS BIGINT)                      AS ticket_count,
+       CAST(SUM(oi.unit_price_in_cents * (oi.quantity - oi.refunded_quantity)) AS BIGINT) AS revenue_in_cents,
+       CAST(COALESCE(SUM(CASE
+                           WHEN r.commission_type = 'Flat' THEN r.flat_in_cents
+                           ELSE ROUND(oi.unit_price_in_cents * r.rate_in_basis_points / 10000.0)
+                           END * (oi.quantity - oi.refunded_quantity)), 0) AS BIGINT) AS commission_in_cents
+FROM orders o
+       INNER JOIN affiliate_clicks ac ON o.affiliate_click_id = ac.id
+       INNER JOIN affiliates a ON ac.affiliate_id = a.id
+       INNER JOIN order_items oi ON o.id = oi.order_id
+       INNER JOIN events e ON oi.event_id = e.id
+       INNER JOIN ticket_types tt ON oi.ticket_type_id = tt.id
+       LEFT JOIN LATERAL (
+  SELECT acr.commission_type, acr.flat_in_cents, acr.rate_in_basis_points
+  FROM affiliate_commission_rules acr
+  WHERE acr.affiliate_id = a.id
+    AND (acr.ticket_type_id = tt.id OR acr.ticket_type_id IS NULL)
+  ORDER BY acr.ticket_type_id IS NULL
+  LIMIT 1
+  ) r ON TRUE
+WHERE a.organization_id = $1
+  AND e.organization_id = $1
+  AND ($2 IS NULL OR a.id = $2)
+  AND o.status = 'Paid'
+  AND oi.item_type = 'Tickets'
+  AND o.paid_at <= ac.created_at + a.attribution_window_in_days * INTERVAL '1 day'
+  AND ($3 IS NULL OR o.paid_at >= $3)
+  AND ($4 IS NULL OR o.paid_at <= $4)
+GROUP BY a.id, a.name, a.code, e.id, e.name, tt.id, tt.name
+ORDER BY a.name, e.name, tt.name;
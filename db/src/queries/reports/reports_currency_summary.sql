@@ -0,0 +1,17 @@
+SELECT oi.currency,
+       CAST(COUNT(DISTINCT o.id) AS BIGINT)                                                                      AS order_count,
+       CAST(COALESCE(SUM(CASE WHEN oi.item_type = 'Tickets' THEN oi.quantity - oi.refunded_quantity ELSE 0 END), 0) AS BIGINT) AS ticket_count,
+       CAST(COALESCE(SUM(CASE WHEN oi.item_type = 'Tickets' THEN (oi.quantity - oi.refunded_quantity) * oi.unit_price_in_cents ELSE 0 END), 0) AS BIGINT) AS ticket_sales_in_cents,
+       CAST(COALESCE(SUM(CASE WHEN oi.item_type IN ('PerUnitFees', 'EventFees') THEN (oi.quantity - oi.refunded_quantity) * oi.unit_price_in_cents ELSE 0 END), 0) AS BIGINT) AS fees_in_cents,
+       CAST(COALESCE(SUM(CASE WHEN oi.item_type IN ('PerUnitFees', 'EventFees') AND oi.fee_mode = 'Absorbed' THEN (oi.quantity - oi.refunded_quantity) * oi.company_fee_in_cents ELSE 0 END), 0) AS BIGINT) AS absorbed_fees_in_cents,
+       CAST(COALESCE(SUM(CASE WHEN oi.item_type = 'Tax' THEN (oi.quantity - oi.refunded_quantity) * oi.tax_in_cents ELSE 0 END), 0) AS BIGINT) AS tax_in_cents,
+       CAST(COALESCE(SUM((oi.quantity - oi.refunded_quantity) * oi.unit_price_in_cents), 0) AS BIGINT)           AS gross_in_cents
+FROM orders o
+       INNER JOIN order_items oi ON o.id = oi.order_id
+       INNER JOIN events e ON oi.event_id = e.id
+WHERE o.status = 'Paid'
+  AND ($1 IS NULL OR e.organization_id = $1)
+  AND ($2 IS NULL OR o.paid_at >= $2)
+  AND ($3 IS NULL OR o.paid_at <= $3)
+GROUP BY oi.currency
+ORDER BY oi.currency;
@@ -1,4 +1,5 @@
 SELECT oi.event_id,
+       oi.currency,
        oi.ticket_type_id                                                                AS ticket_type_id,
        oi.ticket_pricing_id                                                             AS ticket_pricing_id,
        tp.name                                                                          AS pricing_name,
@@ -12,10 +13,11 @@ SELECT oi.event_id,
        CAST(COALESCE(SUM(oi_fees.company_fee_in_cents * (oi_fees.quantity - oi.refunded_quantity)), 0) AS BIGINT) AS total_company_fee_in_cents,
        CAST(COALESCE(AVG(oi_fees.company_fee_in_cents * (oi_fees.quantity - oi.refunded_quantity)), 0) AS BIGINT) AS company_fee_in_cents,
        CAST(COALESCE(SUM(oi_fees.client_fee_in_cents * (oi_fees.quantity - oi.refunded_quantity)), 0) AS BIGINT)  AS total_client_fee_in_cents,
-       CAST(COALESCE(AVG(oi_fees.client_fee_in_cents * (oi_fees.quantity - oi.refunded_quantity)), 0) AS BIGINT)  AS client_fee_in_cents
+       CAST(COALESCE(AVG(oi_fees.client_fee_in_cents * (oi_fees.quantity - oi.refunded_quantity)), 0) AS BIGINT)  AS client_fee_in_cents,
+       CAST(COALESCE(SUM(oi_fees.company_fee_in_cents * (oi_fees.quantity - oi.refunded_quantity)) FILTER (WHERE oi_fees.fee_mode = 'Absorbed'), 0) AS BIGINT) AS total_absorbed_fee_in_cents
 FROM orders
        LEFT JOIN order_items oi on orders.id = oi.order_id
-       LEFT JOIN order_items oi_fees on oi.id = oi_fees.parent_id
+       LEFT JOIN order_items oi_fees on (oi.id = oi_fees.parent_id AND oi_fees.item_type = 'PerUnitFees')
        LEFT JOIN ticket_types tt ON (oi.ticket_type_id = tt.id)
        LEFT JOIN ticket_pricing tp ON (oi.ticket_pricing_id = tp.id)
        LEFT JOIN (SELECT order_id, ARRAY_TO_STRING(ARRAY_AGG(DISTINCT p.payment_method), ', ') AS payment_method FROM payments p GROUP BY p.payment_method, p.order_id) AS p on orders.id = p.order_id
@@ -27,4 +29,4 @@ WHERE orders.status = 'Paid'
   AND oi.item_type = 'Tickets'
   AND ($3 IS NULL OR orders.paid_at >= $3)
   AND ($4 IS NULL OR orders.paid_at <= $4)
-GROUP BY oi.event_id, oi.ticket_type_id, oi.ticket_pricing_id, tt.name, tp.name;
+GROUP BY oi.event_id, oi.currency, oi.ticket_type_id, oi.ticket_pricing_id, tt.name, tp.name;
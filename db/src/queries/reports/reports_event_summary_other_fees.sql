@@ -1,9 +1,11 @@
 SELECT oi.event_id,
+       oi.currency,
        CAST(AVG(oi.unit_price_in_cents) AS BIGINT)               AS unit_price_in_cents,
        CAST(COALESCE(SUM(oi.company_fee_in_cents), 0) AS BIGINT) AS total_company_fee_in_cents,
        CAST(COALESCE(AVG(oi.company_fee_in_cents), 0) AS BIGINT) AS company_fee_in_cents,
        CAST(COALESCE(SUM(oi.client_fee_in_cents), 0) AS BIGINT)  AS total_client_fee_in_cents,
-       CAST(COALESCE(AVG(oi.client_fee_in_cents), 0) AS BIGINT)  AS client_fee_in_cents
+       CAST(COALESCE(AVG(oi.client_fee_in_cents), 0) AS BIGINT)  AS client_fee_in_cents,
+       CAST(COALESCE(SUM(oi.company_fee_in_cents) FILTER (WHERE oi.fee_mode = 'Absorbed'), 0) AS BIGINT) AS total_absorbed_fee_in_cents
 FROM orders
        LEFT JOIN order_items oi on orders.id = oi.order_id
        LEFT JOIN events e on oi.event_id = e.id
@@ -14,4 +16,4 @@ WHERE orders.status = 'Paid'
   AND oi.refunded_quantity = 0
   AND ($3 IS NULL OR orders.paid_at >= $3)
   AND ($4 IS NULL OR orders.paid_at <= $4)
-GROUP BY oi.event_id;
+GROUP BY oi.event_id, oi.currency;
@@ -1,4 +1,5 @@
 SELECT event_id,
+       currency,
        ticket_type_id,
        ticket_pricing_id,
        total_sold,
@@ -8,12 +9,15 @@ SELECT event_id,
        price_in_cents,
        total_company_fee_in_cents,
        total_client_fee_in_cents,
+       total_absorbed_fee_in_cents,
        pricing_name,
        ticket_name,
+       -- Absorbed fees are not paid by the buyer so are not part of the gross income
        CAST(total_net_income + total_company_fee_in_cents +
-            total_client_fee_in_cents AS BIGINT) AS total_gross_income_in_cents
+            total_client_fee_in_cents - total_absorbed_fee_in_cents AS BIGINT) AS total_gross_income_in_cents
 FROM (
          SELECT oi.event_id,
+                oi.currency,
                 oi.ticket_type_id,
                 oi.ticket_pricing_id,
                 CAST(COALESCE(SUM(oi.quantity - oi.refunded_quantity) FILTER (WHERE h.hold_type is null or h.hold_type != 'Comp'),0) AS BIGINT)        AS total_sold,
@@ -27,13 +31,14 @@ FROM (
                 CAST(AVG(tp.price_in_cents) AS BIGINT)                         AS price_in_cents, -- face price
                 CAST(COALESCE(SUM((oi.quantity - oi.refunded_quantity) * oi_fees.company_fee_in_cents), 0) AS BIGINT) AS total_company_fee_in_cents,
                 CAST(COALESCE(SUM((oi.quantity - oi.refunded_quantity) * oi_fees.client_fee_in_cents), 0) AS BIGINT)  AS total_client_fee_in_cents,
+                CAST(COALESCE(SUM((oi.quantity - oi.refunded_quantity) * oi_fees.company_fee_in_cents) FILTER (WHERE oi_fees.fee_mode = 'Absorbed'), 0) AS BIGINT) AS total_absorbed_fee_in_cents,
 
                 CAST(COALESCE(SUM((oi.quantity - oi.refunded_quantity) * oi.unit_price_in_cents), 0) AS BIGINT)       AS total_net_income,
                 tp.name                                                        AS pricing_name,
                 tt.name                                                        AS ticket_name
          FROM orders
                   LEFT JOIN order_items oi ON orders.id = oi.order_id
-                  LEFT JOIN order_items oi_fees ON oi.id = oi_fees.parent_id
+                  LEFT JOIN order_items oi_fees ON (oi.id = oi_fees.parent_id AND oi_fees.item_type = 'PerUnitFees')
                   LEFT JOIN ticket_types tt ON (oi.ticket_type_id = tt.id)
                   LEFT JOIN ticket_pricing tp ON (oi.ticket_pricing_id = tp.id)
                   LEFT JOIN holds h ON oi.hold_id = h.id
@@ -44,5 +49,6 @@ FROM (
            AND oi.item_type = 'Tickets'
            AND ($3 IS NULL OR orders.paid_at >= $3)
            AND ($4 IS NULL OR orders.paid_at <= $4)
-         GROUP BY oi.event_id, oi.ticket_type_id, oi.ticket_pricing_id, tt.name, tp.name
-     ) AS report_data;
+         GROUP BY oi.event_id, oi.currency, oi.ticket_type_id, oi.ticket_pricing_id, tt.name, tp.name
+     ) AS report_data
+ORDER BY event_id, currency;
@@ -0,0 +1,23 @@
+-- Orders are grouped by the attribution captured when the cart was created. Carts are counted
+-- by creation date so that conversion compares orders to the carts they started from.
+SELECT o.utm_source,
+       o.utm_medium,
+       o.utm_campaign,
+       o.source_id,
+       CAST(COUNT(DISTINCT o.id) AS BIGINT)                                          AS cart_count,
+       CAST(COUNT(DISTINCT o.id) FILTER (WHERE o.status = 'Paid') AS BIGINT)          AS order_count,
+       CAST(10000 * COUNT(DISTINCT o.id) FILTER (WHERE o.status = 'Paid')
+              / COUNT(DISTINCT o.id) AS BIGINT)                                       AS conversion_rate_in_basis_points,
+       CAST(COALESCE(SUM(oi.quantity - oi.refunded_quantity)
+                     FILTER (WHERE o.status = 'Paid' AND oi.item_type = 'Tickets'), 0) AS BIGINT) AS ticket_count,
+       CAST(COALESCE(SUM(oi.unit_price_in_cents * (oi.quantity - oi.refunded_quantity))
+                     FILTER (WHERE o.status = 'Paid'), 0) AS BIGINT)                  AS revenue_in_cents
+FROM orders o
+       INNER JOIN order_items oi ON o.id = oi.order_id
+       INNER JOIN events e ON oi.event_id = e.id
+WHERE e.organization_id = $1
+  AND ($2 IS NULL OR oi.event_id = $2)
+  AND ($3 IS NULL OR o.created_at >= $3)
+  AND ($4 IS NULL OR o.created_at <= $4)
+GROUP BY o.utm_source, o.utm_medium, o.utm_campaign, o.source_id
+ORDER BY revenue_in_cents DESC, cart_count DESC;
@@ -0,0 +1,20 @@
+SELECT tr.id                                                                                  AS tax_rule_id,
+       tr.name                                                                                AS tax_rule_name,
+       tr.rate_in_basis_points,
+       tr.inclusive,
+       tax.currency,
+       CAST(SUM(parent.unit_price_in_cents * (tax.quantity - tax.refunded_quantity)) AS BIGINT) AS taxable_amount_in_cents,
+       CAST(SUM(tax.tax_in_cents * tax.quantity) AS BIGINT)                                    AS tax_charged_in_cents,
+       CAST(SUM(tax.tax_in_cents * tax.refunded_quantity) AS BIGINT)                           AS tax_refunded_in_cents,
+       CAST(SUM(tax.tax_in_cents * (tax.quantity - tax.refunded_quantity)) AS BIGINT)          AS tax_due_in_cents
+FROM orders o
+       INNER JOIN order_items tax ON o.id = tax.order_id AND tax.item_type = 'Tax'
+       INNER JOIN order_items parent ON tax.parent_id = parent.id
+       INNER JOIN tax_rules tr ON tax.tax_rule_id = tr.id
+       INNER JOIN events e ON tax.event_id = e.id
+WHERE o.status = 'Paid'
+  AND ($1 IS NULL OR e.organization_id = $1)
+  AND ($2 IS NULL OR o.paid_at >= $2)
+  AND ($3 IS NULL OR o.paid_at <= $3)
+GROUP BY tr.id, tr.name, tr.rate_in_basis_points, tr.inclusive, tax.currency
+ORDER BY tr.name, tax.currency;
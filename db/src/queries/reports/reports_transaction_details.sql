@@ -36,10 +36,12 @@ SELECT e.name
        COALESCE(u.first_name, '')                                                                                       AS first_name,
        COALESCE(u.last_name, '')                                                                                        AS last_name,
        COALESCE(u.phone, '')                                                                                            AS phone,
-       COALESCE(u.email, '')                                                                                            AS email
+       COALESCE(u.email, '')                                                                                            AS email,
+       oi.currency,
+       COALESCE(oi_fees.fee_mode, 'Standard')                                                                           AS fee_mode
 FROM orders
        LEFT JOIN order_items oi on (orders.id = oi.order_id AND oi.item_type = 'Tickets')
-       LEFT JOIN order_items oi_fees on oi.id = oi_fees.parent_id
+       LEFT JOIN order_items oi_fees on (oi.id = oi_fees.parent_id AND oi_fees.item_type = 'PerUnitFees')
        LEFT JOIN order_items oi_event_fees ON (oi_event_fees.item_type = 'EventFees' AND orders.id = oi_event_fees.order_id)
        LEFT JOIN ticket_types tt ON (oi.ticket_type_id = tt.id)
        LEFT JOIN (SELECT order_id, ARRAY_TO_STRING(ARRAY_AGG(DISTINCT p.payment_method), ', ') AS payment_method FROM payments p GROUP BY p.payment_method, p.order_id) AS p on orders.id = p.order_id
@@ -51,4 +53,6 @@ WHERE orders.status = 'Paid'
   AND ($2 IS NULL OR e.organization_id = $2)
   AND ($3 IS NULL OR orders.paid_at >= $3)
   AND ($4 IS NULL OR orders.paid_at <= $4)
-  AND (oi.item_type = 'Tickets');
\ No newline at end of file
+  AND (oi.item_type = 'Tickets')
+ORDER BY orders.paid_at, oi.id, oi_event_fees.id
+LIMIT $5 OFFSET $6;
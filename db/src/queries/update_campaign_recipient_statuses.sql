@@ -0,0 +1,13 @@
+-- Copies the outcome of each queued recipient's communication action onto the recipient, actions
+-- that expired before being sent are treated as failed. Cancelled actions are left to the
+-- campaign's cancellation.
+UPDATE campaign_recipients cr
+SET status        = CASE WHEN da.status = 'Success' THEN 'Sent' ELSE 'Failed' END,
+    status_reason = CASE WHEN da.status = 'Success' THEN NULL ELSE COALESCE(da.last_failure_reason, 'Not sent') END,
+    updated_at    = now()
+FROM domain_actions da
+WHERE cr.domain_action_id = da.id
+  AND cr.campaign_id = $1
+  AND cr.status = 'Queued'
+  AND (da.status IN ('Success', 'Errored', 'RetriesExceeded')
+  OR (da.status = 'Pending' AND (da.expires_at <= now() OR da.attempt_count >= da.max_attempt_count)));
@@ -0,0 +1,135 @@
+-- Everything held about a user. Passwords, reset and sign in tokens, two factor secrets, third
+-- party access tokens and payment provider data are credentials rather than personal data and
+-- are left out.
+SELECT json_build_object(
+    'profile', (
+        SELECT row_to_json(p)
+        FROM (SELECT u.id, u.first_name, u.last_name, u.email, u.phone, u.profile_pic_url, u.thumb_profile_pic_url,
+                     u.cover_photo_url, u.role, u.active, u.accepted_terms_date, u.invited_at, u.last_used,
+                     u.email_verified_at, u.phone_verified_at, u.login_token_requested_at, u.created_at,
+                     u.updated_at
+              FROM users u
+              WHERE u.id = $1) p
+    ),
+    'organizations', COALESCE((
+        SELECT json_agg(x ORDER BY x.created_at)
+        FROM (SELECT ou.organization_id, o.name AS organization_name, ou.role, ou.created_at
+              FROM organization_users ou
+                       INNER JOIN organizations o ON o.id = ou.organization_id
+              WHERE ou.user_id = $1) x
+    ), '[]'),
+    'orders', COALESCE((
+        SELECT json_agg(x ORDER BY x.created_at)
+        FROM (SELECT o.*,
+                     (SELECT json_agg(oi ORDER BY oi.created_at) FROM order_items oi WHERE oi.order_id = o.id) AS items
+              FROM orders o
+              WHERE o.user_id = $1
+                 OR o.on_behalf_of_user_id = $1) x
+    ), '[]'),
+    'payments', COALESCE((
+        SELECT json_agg(x ORDER BY x.created_at)
+        FROM (SELECT p.id, p.order_id, p.status, p.payment_method, p.amount, p.currency, p.provider,
+                     p.external_reference, p.created_at, p.updated_at
+              FROM payments p
+                       INNER JOIN orders o ON o.id = p.order_id
+              WHERE o.user_id = $1
+                 OR o.on_behalf_of_user_id = $1) x
+    ), '[]'),
+    'event_roles', COALESCE((
+        SELECT json_agg(x ORDER BY x.created_at)
+        FROM (SELECT eu.event_id, e.name AS event_name, eu.role, eu.created_at
+              FROM event_users eu
+                       INNER JOIN events e ON e.id = eu.event_id
+              WHERE eu.user_id = $1) x
+    ), '[]'),
+    'payment_methods', COALESCE((
+        SELECT json_agg(x ORDER BY x.created_at)
+        FROM (SELECT pm.id, pm.name, pm.is_default, pm.provider, pm.created_at, pm.updated_at
+              FROM payment_methods pm
+              WHERE pm.user_id = $1) x
+    ), '[]'),
+    'tickets', COALESCE((
+        SELECT json_agg(x ORDER BY x.created_at)
+        FROM (SELECT ti.id, ti.status, e.id AS event_id, e.name AS event_name, tt.id AS ticket_type_id,
+                     tt.name AS ticket_type_name, ti.order_item_id, ti.created_at, ti.updated_at
+              FROM ticket_instances ti
+                       INNER JOIN wallets w ON w.id = ti.wallet_id
+                       INNER JOIN assets a ON a.id = ti.asset_id
+                       INNER JOIN ticket_types tt ON tt.id = a.ticket_type_id
+                       INNER JOIN events e ON e.id = tt.event_id
+              WHERE w.user_id = $1) x
+    ), '[]'),
+    'external_logins', COALESCE((
+        SELECT json_agg(x ORDER BY x.created_at)
+        FROM (SELECT el.id, el.site, el.external_user_id, el.created_at, el.updated_at
+              FROM external_logins el
+              WHERE el.user_id = $1) x
+    ), '[]'),
+    'sessions', COALESCE((
+        SELECT json_agg(x ORDER BY x.created_at)
+        FROM (SELECT us.id, us.user_agent, us.ip_address, us.last_used_at, us.revoked_at, us.created_at
+              FROM user_sessions us
+              WHERE us.user_id = $1) x
+    ), '[]'),
+    'two_factor_authentication', (
+        SELECT row_to_json(x)
+        FROM (SELECT tfa.enabled_at, tfa.created_at, tfa.updated_at
+              FROM two_factor_authentications tfa
+              WHERE tfa.user_id = $1) x
+    ),
+    'phone_verifications', COALESCE((
+        SELECT json_agg(x ORDER BY x.created_at)
+        FROM (SELECT pv.phone, pv.expires_at, pv.created_at
+              FROM phone_verifications pv
+              WHERE pv.user_id = $1) x
+    ), '[]'),
+    'push_notification_tokens', COALESCE((
+        SELECT json_agg(x ORDER BY x.created_at)
+        FROM (SELECT pnt.id, pnt.token_source, pnt.token, pnt.last_notification_at, pnt.created_at
+              FROM push_notification_tokens pnt
+              WHERE pnt.user_id = $1) x
+    ), '[]'),
+    'event_interest', COALESCE((
+        SELECT json_agg(x ORDER BY x.created_at)
+        FROM (SELECT ei.event_id, e.name AS event_name, ei.created_at
+              FROM event_interest ei
+                       INNER JOIN events e ON e.id = ei.event_id
+              WHERE ei.user_id = $1) x
+    ), '[]'),
+    'communication_preferences', COALESCE((
+        SELECT json_agg(x ORDER BY x.created_at)
+        FROM (SELECT * FROM communication_preferences cp WHERE cp.user_id = $1) x
+    ), '[]'),
+    'communication_consents', COALESCE((
+        SELECT json_agg(x ORDER BY x.created_at)
+        FROM (SELECT * FROM communication_consents cc WHERE cc.user_id = $1) x
+    ), '[]'),
+    'campaign_messages', COALESCE((
+        SELECT json_agg(x ORDER BY x.created_at)
+        FROM (SELECT cr.campaign_id, c.name AS campaign_name, c.channel, cr.address, cr.status, cr.created_at
+              FROM campaign_recipients cr
+                       INNER JOIN campaigns c ON c.id = cr.campaign_id
+              WHERE cr.user_id = $1) x
+    ), '[]'),
+    'fan_notes', COALESCE((
+        SELECT json_agg(x ORDER BY x.created_at)
+        FROM (SELECT fn.organization_id, fn.note, fn.created_at FROM fan_notes fn WHERE fn.user_id = $1) x
+    ), '[]'),
+    'fan_tags', COALESCE((
+        SELECT json_agg(x ORDER BY x.created_at)
+        FROM (SELECT ft.organization_id, ft.tag, ft.created_at FROM fan_tags ft WHERE ft.user_id = $1) x
+    ), '[]'),
+    'domain_events', COALESCE((
+        SELECT json_agg(x ORDER BY x.created_at)
+        FROM (SELECT de.id, de.event_type, de.display_text, de.event_data, de.main_table, de.main_id, de.created_at
+              FROM domain_events de
+              WHERE de.user_id = $1) x
+    ), '[]'),
+    'audit_logs', COALESCE((
+        SELECT json_agg(x ORDER BY x.created_at)
+        FROM (SELECT al.id, al.organization_id, al.event_id, al.scope, al.action, al.main_table, al.main_id,
+                     al.created_at
+              FROM audit_logs al
+              WHERE al.user_id = $1) x
+    ), '[]')
+) AS data;
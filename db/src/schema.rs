@@ -1,3 +1,38 @@
+table! {
+    affiliate_clicks (id) {
+        id -> Uuid,
+        affiliate_id -> Uuid,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    affiliate_commission_rules (id) {
+        id -> Uuid,
+        affiliate_id -> Uuid,
+        ticket_type_id -> Nullable<Uuid>,
+        commission_type -> Text,
+        flat_in_cents -> Int8,
+        rate_in_basis_points -> Int8,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    affiliates (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        user_id -> Nullable<Uuid>,
+        name -> Text,
+        code -> Text,
+        attribution_window_in_days -> Int8,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     artists (id) {
         id -> Uuid,
@@ -33,6 +68,60 @@ table! {
     }
 }
 
+table! {
+    audit_logs (id) {
+        id -> Uuid,
+        organization_id -> Nullable<Uuid>,
+        event_id -> Nullable<Uuid>,
+        user_id -> Nullable<Uuid>,
+        scope -> Nullable<Text>,
+        action -> Text,
+        main_table -> Text,
+        main_id -> Uuid,
+        changes -> Json,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    campaign_recipients (id) {
+        id -> Uuid,
+        campaign_id -> Uuid,
+        user_id -> Uuid,
+        address -> Nullable<Text>,
+        status -> Text,
+        status_reason -> Nullable<Text>,
+        domain_action_id -> Nullable<Uuid>,
+        unsubscribe_token -> Uuid,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    campaigns (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        name -> Text,
+        channel -> Text,
+        audience -> Text,
+        event_id -> Nullable<Uuid>,
+        ticket_type_id -> Nullable<Uuid>,
+        fan_segment_id -> Nullable<Uuid>,
+        subject -> Nullable<Text>,
+        body -> Text,
+        status -> Text,
+        batch_size -> Int4,
+        batch_interval_in_seconds -> Int4,
+        created_by_user_id -> Uuid,
+        sent_at -> Nullable<Timestamp>,
+        completed_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     codes (id) {
         id -> Uuid,
@@ -47,6 +136,35 @@ table! {
         max_tickets_per_user -> Nullable<Int8>,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        fan_segment_id -> Nullable<Uuid>,
+    }
+}
+
+table! {
+    communication_consents (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        organization_id -> Nullable<Uuid>,
+        category -> Text,
+        channel -> Text,
+        subscribed -> Bool,
+        source -> Text,
+        ip_address -> Nullable<Text>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    communication_preferences (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        organization_id -> Nullable<Uuid>,
+        category -> Text,
+        channel -> Text,
+        subscribed -> Bool,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
     }
 }
 
@@ -111,6 +229,32 @@ table! {
     }
 }
 
+table! {
+    event_split_partners (id) {
+        id -> Uuid,
+        event_id -> Uuid,
+        organization_id -> Uuid,
+        split_type -> Text,
+        ticket_sales_flat_in_cents -> Int8,
+        ticket_sales_rate_in_basis_points -> Int8,
+        fees_flat_in_cents -> Int8,
+        fees_rate_in_basis_points -> Int8,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    event_users (id) {
+        id -> Uuid,
+        event_id -> Uuid,
+        user_id -> Uuid,
+        role -> Text,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     events (id) {
         id -> Uuid,
@@ -140,6 +284,21 @@ table! {
         event_end -> Nullable<Timestamp>,
         sendgrid_list_id -> Nullable<Int8>,
         event_type -> Text,
+        currency -> Text,
+        fee_mode -> Text,
+        genres -> Array<Text>,
+    }
+}
+
+table! {
+    exchange_rates (id) {
+        id -> Uuid,
+        base_currency -> Text,
+        target_currency -> Text,
+        rate -> Numeric,
+        effective_at -> Timestamp,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
     }
 }
 
@@ -155,6 +314,41 @@ table! {
     }
 }
 
+table! {
+    fan_notes (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        user_id -> Uuid,
+        author_id -> Uuid,
+        note -> Text,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    fan_segments (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        name -> Text,
+        criteria -> Jsonb,
+        created_by_user_id -> Uuid,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    fan_tags (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        user_id -> Uuid,
+        tag -> Text,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     fee_schedule_ranges (id) {
         id -> Uuid,
@@ -165,6 +359,10 @@ table! {
         updated_at -> Timestamp,
         company_fee_in_cents -> Int8,
         client_fee_in_cents -> Int8,
+        company_fee_in_basis_points -> Int8,
+        client_fee_in_basis_points -> Int8,
+        min_fee_in_cents -> Nullable<Int8>,
+        max_fee_in_cents -> Nullable<Int8>,
     }
 }
 
@@ -216,6 +414,10 @@ table! {
         company_fee_in_cents -> Int8,
         client_fee_in_cents -> Int8,
         refunded_quantity -> Int8,
+        currency -> Text,
+        tax_rule_id -> Nullable<Uuid>,
+        tax_in_cents -> Int8,
+        fee_mode -> Text,
     }
 }
 
@@ -236,6 +438,34 @@ table! {
         box_office_pricing -> Bool,
         checkout_url -> Nullable<Text>,
         checkout_url_expires -> Nullable<Timestamp>,
+        currency -> Nullable<Text>,
+        referrer -> Nullable<Text>,
+        utm_source -> Nullable<Text>,
+        utm_medium -> Nullable<Text>,
+        utm_campaign -> Nullable<Text>,
+        utm_term -> Nullable<Text>,
+        utm_content -> Nullable<Text>,
+        source_id -> Nullable<Text>,
+        affiliate_click_id -> Nullable<Uuid>,
+    }
+}
+
+table! {
+    organization_api_keys (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        user_id -> Uuid,
+        name -> Text,
+        key_prefix -> Text,
+        key_hash -> Text,
+        scopes -> Array<Text>,
+        event_ids -> Array<Uuid>,
+        expires_at -> Nullable<Timestamp>,
+        last_used_at -> Nullable<Timestamp>,
+        revoked_at -> Nullable<Timestamp>,
+        created_by_user_id -> Uuid,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
     }
 }
 
@@ -252,6 +482,18 @@ table! {
         updated_at -> Timestamp,
         sent_invite -> Bool,
         roles -> Array<Text>,
+        organization_role_ids -> Array<Uuid>,
+    }
+}
+
+table! {
+    organization_roles (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        name -> Text,
+        scopes -> Array<Text>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
     }
 }
 
@@ -275,6 +517,9 @@ table! {
         client_event_fee_in_cents -> Int8,
         company_event_fee_in_cents -> Int8,
         allowed_payment_providers -> Array<Text>,
+        currency -> Text,
+        require_two_factor -> Bool,
+        require_verified_contact_details -> Bool,
     }
 }
 
@@ -286,6 +531,7 @@ table! {
         created_at -> Timestamp,
         updated_at -> Timestamp,
         role -> Array<Text>,
+        organization_role_ids -> Array<Uuid>,
     }
 }
 
@@ -316,6 +562,20 @@ table! {
         created_at -> Timestamp,
         updated_at -> Timestamp,
         url_nonce -> Nullable<Text>,
+        currency -> Text,
+    }
+}
+
+table! {
+    phone_verifications (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        phone -> Text,
+        code_hash -> Text,
+        failed_attempts -> Int4,
+        expires_at -> Timestamp,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
     }
 }
 
@@ -330,6 +590,17 @@ table! {
     }
 }
 
+table! {
+    rate_limit_counters (id) {
+        id -> Uuid,
+        key -> Text,
+        hits -> Int4,
+        window_ends_at -> Timestamp,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     refunded_tickets (id) {
         id -> Uuid,
@@ -351,6 +622,88 @@ table! {
     }
 }
 
+table! {
+    report_deliveries (id) {
+        id -> Uuid,
+        report_subscription_id -> Uuid,
+        period_start -> Timestamp,
+        period_end -> Timestamp,
+        status -> Text,
+        error -> Nullable<Text>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    report_subscriptions (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        user_id -> Uuid,
+        report_type -> Text,
+        event_id -> Nullable<Uuid>,
+        frequency -> Text,
+        day_of_week -> Nullable<Int4>,
+        hour_of_day -> Int4,
+        timezone -> Text,
+        format -> Text,
+        unsubscribe_token -> Uuid,
+        next_delivery_at -> Timestamp,
+        unsubscribed_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    settlement_adjustments (id) {
+        id -> Uuid,
+        settlement_id -> Uuid,
+        adjustment_type -> Text,
+        amount_in_cents -> Int8,
+        note -> Nullable<Text>,
+        user_id -> Uuid,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    settlement_partner_shares (id) {
+        id -> Uuid,
+        settlement_id -> Uuid,
+        event_id -> Uuid,
+        organization_id -> Uuid,
+        ticket_sales_in_cents -> Int8,
+        fees_in_cents -> Int8,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    settlements (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        event_id -> Nullable<Uuid>,
+        start_time -> Nullable<Timestamp>,
+        end_time -> Nullable<Timestamp>,
+        status -> Text,
+        currency -> Text,
+        ticket_sales_in_cents -> Int8,
+        client_fees_in_cents -> Int8,
+        company_fees_in_cents -> Int8,
+        absorbed_fees_in_cents -> Int8,
+        user_id -> Uuid,
+        approved_by_user_id -> Nullable<Uuid>,
+        approved_at -> Nullable<Timestamp>,
+        paid_by_user_id -> Nullable<Uuid>,
+        paid_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 table! {
     stages (id) {
         id -> Uuid,
@@ -363,6 +716,23 @@ table! {
     }
 }
 
+table! {
+    tax_rules (id) {
+        id -> Uuid,
+        name -> Text,
+        region_id -> Nullable<Uuid>,
+        venue_id -> Nullable<Uuid>,
+        rate_in_basis_points -> Int8,
+        inclusive -> Bool,
+        applies_to_tickets -> Bool,
+        applies_to_per_unit_fees -> Bool,
+        applies_to_event_fees -> Bool,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        deleted_at -> Nullable<Timestamp>,
+    }
+}
+
 table! {
     ticket_instances (id) {
         id -> Uuid,
@@ -421,6 +791,34 @@ table! {
         updated_at -> Timestamp,
         price_in_cents -> Int8,
         cancelled_at -> Nullable<Timestamp>,
+        fee_mode -> Nullable<Text>,
+    }
+}
+
+table! {
+    two_factor_authentications (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        secret -> Text,
+        recovery_code_hashes -> Array<Text>,
+        last_used_step -> Nullable<Int8>,
+        enabled_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+table! {
+    user_sessions (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        refresh_token_id -> Uuid,
+        user_agent -> Nullable<Text>,
+        ip_address -> Nullable<Text>,
+        last_used_at -> Timestamp,
+        revoked_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
     }
 }
 
@@ -446,6 +844,14 @@ table! {
         last_cart_id -> Nullable<Uuid>,
         accepted_terms_date -> Nullable<Timestamp>,
         invited_at -> Nullable<Timestamp>,
+        failed_login_attempts -> Int4,
+        locked_until -> Nullable<Timestamp>,
+        email_verified_at -> Nullable<Timestamp>,
+        phone_verified_at -> Nullable<Timestamp>,
+        login_token -> Nullable<Uuid>,
+        login_code_hash -> Nullable<Text>,
+        login_token_requested_at -> Nullable<Timestamp>,
+        login_code_failed_attempts -> Int4,
     }
 }
 
@@ -486,9 +892,30 @@ table! {
     }
 }
 
+joinable!(affiliate_clicks -> affiliates (affiliate_id));
+joinable!(affiliate_commission_rules -> affiliates (affiliate_id));
+joinable!(affiliate_commission_rules -> ticket_types (ticket_type_id));
+joinable!(affiliates -> organizations (organization_id));
+joinable!(affiliates -> users (user_id));
 joinable!(artists -> organizations (organization_id));
 joinable!(assets -> ticket_types (ticket_type_id));
+joinable!(audit_logs -> events (event_id));
+joinable!(audit_logs -> organizations (organization_id));
+joinable!(audit_logs -> users (user_id));
+joinable!(campaign_recipients -> campaigns (campaign_id));
+joinable!(campaign_recipients -> domain_actions (domain_action_id));
+joinable!(campaign_recipients -> users (user_id));
+joinable!(campaigns -> events (event_id));
+joinable!(campaigns -> fan_segments (fan_segment_id));
+joinable!(campaigns -> organizations (organization_id));
+joinable!(campaigns -> ticket_types (ticket_type_id));
+joinable!(campaigns -> users (created_by_user_id));
 joinable!(codes -> events (event_id));
+joinable!(codes -> fan_segments (fan_segment_id));
+joinable!(communication_consents -> organizations (organization_id));
+joinable!(communication_consents -> users (user_id));
+joinable!(communication_preferences -> organizations (organization_id));
+joinable!(communication_preferences -> users (user_id));
 joinable!(domain_actions -> domain_events (domain_event_id));
 joinable!(domain_events -> users (user_id));
 joinable!(event_artists -> artists (artist_id));
@@ -496,9 +923,18 @@ joinable!(event_artists -> events (event_id));
 joinable!(event_artists -> stages (stage_id));
 joinable!(event_interest -> events (event_id));
 joinable!(event_interest -> users (user_id));
+joinable!(event_split_partners -> events (event_id));
+joinable!(event_split_partners -> organizations (organization_id));
+joinable!(event_users -> events (event_id));
+joinable!(event_users -> users (user_id));
 joinable!(events -> organizations (organization_id));
 joinable!(events -> venues (venue_id));
 joinable!(external_logins -> users (user_id));
+joinable!(fan_notes -> organizations (organization_id));
+joinable!(fan_segments -> organizations (organization_id));
+joinable!(fan_segments -> users (created_by_user_id));
+joinable!(fan_tags -> organizations (organization_id));
+joinable!(fan_tags -> users (user_id));
 joinable!(fee_schedule_ranges -> fee_schedules (fee_schedule_id));
 joinable!(holds -> events (event_id));
 joinable!(holds -> ticket_types (ticket_type_id));
@@ -507,18 +943,36 @@ joinable!(order_items -> events (event_id));
 joinable!(order_items -> fee_schedule_ranges (fee_schedule_range_id));
 joinable!(order_items -> holds (hold_id));
 joinable!(order_items -> orders (order_id));
+joinable!(order_items -> tax_rules (tax_rule_id));
 joinable!(order_items -> ticket_pricing (ticket_pricing_id));
 joinable!(order_items -> ticket_types (ticket_type_id));
+joinable!(orders -> affiliate_clicks (affiliate_click_id));
+joinable!(organization_api_keys -> organizations (organization_id));
 joinable!(organization_invites -> organizations (organization_id));
+joinable!(organization_roles -> organizations (organization_id));
 joinable!(organization_users -> organizations (organization_id));
 joinable!(organization_users -> users (user_id));
 joinable!(organizations -> fee_schedules (fee_schedule_id));
 joinable!(payment_methods -> users (user_id));
 joinable!(payments -> orders (order_id));
 joinable!(payments -> users (created_by));
+joinable!(phone_verifications -> users (user_id));
 joinable!(push_notification_tokens -> users (user_id));
 joinable!(refunded_tickets -> order_items (order_item_id));
 joinable!(refunded_tickets -> ticket_instances (ticket_instance_id));
+joinable!(report_deliveries -> report_subscriptions (report_subscription_id));
+joinable!(report_subscriptions -> events (event_id));
+joinable!(report_subscriptions -> organizations (organization_id));
+joinable!(report_subscriptions -> users (user_id));
+joinable!(settlement_adjustments -> settlements (settlement_id));
+joinable!(settlement_adjustments -> users (user_id));
+joinable!(settlement_partner_shares -> events (event_id));
+joinable!(settlement_partner_shares -> organizations (organization_id));
+joinable!(settlement_partner_shares -> settlements (settlement_id));
+joinable!(settlements -> events (event_id));
+joinable!(settlements -> organizations (organization_id));
+joinable!(tax_rules -> regions (region_id));
+joinable!(tax_rules -> venues (venue_id));
 joinable!(ticket_instances -> assets (asset_id));
 joinable!(ticket_instances -> holds (hold_id));
 joinable!(ticket_instances -> order_items (order_item_id));
@@ -527,39 +981,67 @@ joinable!(ticket_pricing -> ticket_types (ticket_type_id));
 joinable!(ticket_type_codes -> codes (code_id));
 joinable!(ticket_type_codes -> ticket_types (ticket_type_id));
 joinable!(ticket_types -> events (event_id));
+joinable!(two_factor_authentications -> users (user_id));
+joinable!(user_sessions -> users (user_id));
 joinable!(venues -> organizations (organization_id));
 joinable!(venues -> regions (region_id));
 joinable!(wallets -> organizations (organization_id));
 joinable!(wallets -> users (user_id));
 
 allow_tables_to_appear_in_same_query!(
+    affiliate_clicks,
+    affiliate_commission_rules,
+    affiliates,
     artists,
     assets,
+    audit_logs,
+    campaign_recipients,
+    campaigns,
     codes,
+    communication_consents,
+    communication_preferences,
     domain_actions,
     domain_events,
     event_artists,
     event_interest,
+    event_split_partners,
+    event_users,
     events,
+    exchange_rates,
     external_logins,
+    fan_notes,
+    fan_segments,
+    fan_tags,
     fee_schedule_ranges,
     fee_schedules,
     holds,
     order_items,
     orders,
+    organization_api_keys,
     organization_invites,
+    organization_roles,
     organizations,
     organization_users,
     payment_methods,
     payments,
+    phone_verifications,
     push_notification_tokens,
+    rate_limit_counters,
     refunded_tickets,
     regions,
+    report_deliveries,
+    report_subscriptions,
+    settlement_adjustments,
+    settlement_partner_shares,
+    settlements,
     stages,
+    tax_rules,
     ticket_instances,
     ticket_pricing,
     ticket_type_codes,
     ticket_types,
+    two_factor_authentications,
+    user_sessions,
     users,
     venues,
     wallets,
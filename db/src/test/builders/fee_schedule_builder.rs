@@ -23,11 +23,13 @@ impl<'a> FeeScheduleBuilder<'a> {
                     min_price_in_cents: 50,
                     company_fee_in_cents: 4,
                     client_fee_in_cents: 6,
+                    ..Default::default()
                 },
                 NewFeeScheduleRange {
                     min_price_in_cents: 100,
                     company_fee_in_cents: 8,
                     client_fee_in_cents: 12,
+                    ..Default::default()
                 },
             ],
         )
@@ -106,8 +106,14 @@ impl<'a> OrderBuilder<'a> {
 
         let mut cart = cart;
         if self.is_paid {
-            cart.add_external_payment(Some("blah".to_string()), user.id, total, self.connection)
-                .unwrap();
+            cart.add_external_payment(
+                Some("blah".to_string()),
+                user.id,
+                total,
+                "USD",
+                self.connection,
+            )
+            .unwrap();
         }
 
         cart
@@ -14,6 +14,7 @@ pub struct OrganizationBuilder<'a> {
     company_fee_in_cents: Option<i64>,
     client_fee_in_cents: Option<i64>,
     use_address: bool,
+    currency: Option<String>,
 }
 
 impl<'a> OrganizationBuilder<'a> {
@@ -28,6 +29,7 @@ impl<'a> OrganizationBuilder<'a> {
             event_fee_in_cents: None,
             company_fee_in_cents: None,
             client_fee_in_cents: None,
+            currency: None,
         }
     }
 
@@ -51,6 +53,11 @@ impl<'a> OrganizationBuilder<'a> {
         self
     }
 
+    pub fn with_currency(mut self, currency: &str) -> Self {
+        self.currency = Some(currency.to_string());
+        self
+    }
+
     pub fn with_event_fee(mut self) -> Self {
         self.event_fee_in_cents = Some(250);
         self.company_fee_in_cents = Some(100);
@@ -73,13 +80,16 @@ impl<'a> OrganizationBuilder<'a> {
                     min_price_in_cents: 1,
                     company_fee_in_cents: 20,
                     client_fee_in_cents: 30,
+                    ..Default::default()
                 }],
             )
             .commit(current_user_id, self.connection);
             self.fee_schedule = Some(fee_schedule.unwrap());
         }
 
-        let mut organization = Organization::create(&self.name, self.fee_schedule.unwrap().id)
+        let mut new_organization = Organization::create(&self.name, self.fee_schedule.unwrap().id);
+        new_organization.currency = self.currency.clone();
+        let mut organization = new_organization
             .commit("encryption_key", current_user_id, self.connection)
             .unwrap();
 
@@ -98,7 +108,7 @@ impl<'a> OrganizationBuilder<'a> {
             .unwrap();
 
         for (user_id, role) in self.members {
-            OrganizationUser::create(organization.id, user_id, vec![role])
+            OrganizationUser::create(organization.id, user_id, vec![role], Vec::new())
                 .commit(self.connection)
                 .unwrap();
         }
@@ -90,6 +90,7 @@ impl<'a> OrgInviteBuilder<'a> {
             &self.user_email,
             self.user_id,
             vec![self.role],
+            Vec::new(),
         )
         .commit(self.connection)
         .unwrap();
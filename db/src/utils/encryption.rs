@@ -1,5 +1,6 @@
 use hex;
 use ring::aead::*;
+use ring::digest::{digest, SHA256};
 use ring::rand::{SecureRandom, SystemRandom};
 use utils::errors::*;
 
@@ -82,3 +83,8 @@ pub fn decrypt(ciphertext: &String, encryption_key: &String) -> Result<String, D
 
     Ok(plaintext.unwrap())
 }
+
+/// One way hash for secrets that only need to be compared, such as API keys
+pub fn hash_secret(secret: &str) -> String {
+    hex::encode(digest(&SHA256, secret.as_bytes()).as_ref())
+}
@@ -6,5 +6,6 @@ pub mod migration;
 pub mod passwords;
 pub mod rand;
 pub mod text;
+pub mod totp;
 
 pub use self::math::*;
@@ -4,3 +4,11 @@ use rand::{thread_rng, Rng};
 pub fn random_alpha_string(len: usize) -> String {
     thread_rng().sample_iter(&Alphanumeric).take(len).collect()
 }
+
+/// A code made of `len` digits, e.g. for sending by SMS
+pub fn random_numeric_string(len: usize) -> String {
+    let mut rng = thread_rng();
+    (0..len)
+        .map(|_| char::from(b'0' + rng.gen_range(0, 10)))
+        .collect()
+}
@@ -0,0 +1,23 @@
+use std::borrow::Cow;
+use validator::ValidationError;
+use validators::*;
+
+/// Currencies are stored as upper case ISO 4217 codes e.g. USD, ZAR, EUR
+pub fn validate_currency_code(currency: &String) -> Result<(), ValidationError> {
+    if currency.len() != 3 || !currency.chars().all(|c| c.is_ascii_uppercase()) {
+        let mut validation_error =
+            create_validation_error("currency", "Currency must be a three letter ISO 4217 code");
+        validation_error.add_param(Cow::from("currency"), &currency);
+        return Err(validation_error);
+    }
+    Ok(())
+}
+
+#[test]
+fn validate_currency_code_test() {
+    assert!(validate_currency_code(&"USD".to_string()).is_ok());
+    assert!(validate_currency_code(&"ZAR".to_string()).is_ok());
+    assert!(validate_currency_code(&"usd".to_string()).is_err());
+    assert!(validate_currency_code(&"US".to_string()).is_err());
+    assert!(validate_currency_code(&"USD1".to_string()).is_err());
+}
@@ -1,9 +1,11 @@
+mod currency_validator;
 mod n_date_before_m_date_validator;
 mod number_validators;
 mod redemption_code_uniqueness_validator;
 mod start_date_before_end_date_validator;
 mod url_array_validator;
 
+pub use self::currency_validator::validate_currency_code;
 pub use self::n_date_before_m_date_validator::n_date_valid;
 pub use self::number_validators::validate_greater_than;
 pub use self::redemption_code_uniqueness_validator::redemption_code_unique_per_event_validation;
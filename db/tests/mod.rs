@@ -11,6 +11,7 @@
 #![deny(dead_code)]
 extern crate bigneon_db;
 extern crate chrono;
+extern crate chrono_tz;
 extern crate diesel;
 extern crate rand;
 #[macro_use]
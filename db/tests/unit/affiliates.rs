@@ -0,0 +1,258 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::utils::errors::ErrorCode;
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+
+    let affiliate = Affiliate::create(
+        organization.id,
+        "Promoter",
+        Some(" summer-19 ".to_string()),
+        Some(user.id),
+    )
+    .commit(connection)
+    .unwrap();
+    assert_eq!(affiliate.organization_id, organization.id);
+    assert_eq!(affiliate.user_id, Some(user.id));
+    assert_eq!(affiliate.name, "Promoter".to_string());
+    assert_eq!(affiliate.code, "SUMMER-19".to_string());
+    assert_eq!(affiliate.attribution_window_in_days, 30);
+
+    // A code is generated when none is given
+    let affiliate = Affiliate::create(organization.id, "Blogger", None, None)
+        .commit(connection)
+        .unwrap();
+    assert_eq!(affiliate.code.len(), 8);
+    assert_eq!(affiliate.code, affiliate.code.to_uppercase());
+}
+
+#[test]
+fn create_with_validation_errors() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    Affiliate::create(
+        organization.id,
+        "Promoter",
+        Some("SUMMER".to_string()),
+        None,
+    )
+    .commit(connection)
+    .unwrap();
+
+    let mut affiliate = Affiliate::create(organization.id, "", Some("summer".to_string()), None);
+    affiliate.attribution_window_in_days = 0;
+    match affiliate.commit(connection) {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ErrorCode::ValidationError { errors } => {
+                assert_eq!(errors["name"][0].code, "length");
+                assert_eq!(errors["code"][0].code, "code_taken");
+                assert_eq!(
+                    errors["attribution_window_in_days"][0].code,
+                    "attribution_window_must_be_positive"
+                );
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    let result = Affiliate::create(organization.id, "Promoter", Some("a b".to_string()), None)
+        .commit(connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ErrorCode::ValidationError { errors } => {
+                assert_eq!(errors["code"][0].code, "invalid_code");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn find_by_code() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let affiliate = Affiliate::create(
+        organization.id,
+        "Promoter",
+        Some("SUMMER".to_string()),
+        None,
+    )
+    .commit(connection)
+    .unwrap();
+
+    assert_eq!(
+        Affiliate::find_by_code("summer", connection).unwrap(),
+        affiliate
+    );
+    assert!(Affiliate::find_by_code("winter", connection).is_err());
+}
+
+#[test]
+fn find_for_organization() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let organization2 = project.create_organization().finish();
+    let affiliate = Affiliate::create(organization.id, "Promoter", None, None)
+        .commit(connection)
+        .unwrap();
+    let affiliate2 = Affiliate::create(organization.id, "Blogger", None, None)
+        .commit(connection)
+        .unwrap();
+    Affiliate::create(organization2.id, "Other", None, None)
+        .commit(connection)
+        .unwrap();
+
+    assert_eq!(
+        Affiliate::find_for_organization(organization.id, connection).unwrap(),
+        vec![affiliate2, affiliate]
+    );
+}
+
+#[test]
+fn update() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let affiliate = Affiliate::create(organization.id, "Promoter", None, None)
+        .commit(connection)
+        .unwrap();
+
+    let affiliate = affiliate
+        .update(
+            AffiliateEditableAttributes {
+                name: Some("New name".to_string()),
+                user_id: Some(Some(user.id)),
+                attribution_window_in_days: Some(7),
+            },
+            connection,
+        )
+        .unwrap();
+    assert_eq!(affiliate.name, "New name".to_string());
+    assert_eq!(affiliate.user_id, Some(user.id));
+    assert_eq!(affiliate.attribution_window_in_days, 7);
+
+    let result = affiliate.update(
+        AffiliateEditableAttributes {
+            attribution_window_in_days: Some(-1),
+            ..Default::default()
+        },
+        connection,
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn record_click() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let affiliate = Affiliate::create(organization.id, "Promoter", None, None)
+        .commit(connection)
+        .unwrap();
+
+    let click = affiliate.record_click(connection).unwrap();
+    assert_eq!(click.affiliate_id, affiliate.id);
+    assert_eq!(AffiliateClick::find(click.id, connection).unwrap(), click);
+}
+
+#[test]
+fn commission_rules() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let affiliate = Affiliate::create(organization.id, "Promoter", None, None)
+        .commit(connection)
+        .unwrap();
+
+    let default_rule =
+        AffiliateCommissionRule::create(affiliate.id, None, CommissionTypes::Flat, 100, 0)
+            .commit(connection)
+            .unwrap();
+    let ticket_type_rule = AffiliateCommissionRule::create(
+        affiliate.id,
+        Some(ticket_type.id),
+        CommissionTypes::Percentage,
+        0,
+        1000,
+    )
+    .commit(connection)
+    .unwrap();
+    assert_eq!(
+        affiliate.commission_rules(connection).unwrap(),
+        vec![default_rule, ticket_type_rule.clone()]
+    );
+
+    ticket_type_rule.destroy(connection).unwrap();
+    assert_eq!(affiliate.commission_rules(connection).unwrap().len(), 1);
+}
+
+#[test]
+fn create_commission_rule_with_validation_errors() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let other_event = project.create_event().with_tickets().finish();
+    let other_ticket_type = &other_event.ticket_types(true, None, connection).unwrap()[0];
+    let affiliate = Affiliate::create(organization.id, "Promoter", None, None)
+        .commit(connection)
+        .unwrap();
+    AffiliateCommissionRule::create(affiliate.id, None, CommissionTypes::Flat, 100, 0)
+        .commit(connection)
+        .unwrap();
+
+    let result =
+        AffiliateCommissionRule::create(affiliate.id, None, CommissionTypes::Percentage, 0, 10001)
+            .commit(connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ErrorCode::ValidationError { errors } => {
+                assert_eq!(
+                    errors["rate_in_basis_points"][0].code,
+                    "percentage_commission_invalid"
+                );
+                assert_eq!(errors["ticket_type_id"][0].code, "commission_rule_exists");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    let result = AffiliateCommissionRule::create(
+        affiliate.id,
+        Some(other_ticket_type.id),
+        CommissionTypes::Flat,
+        0,
+        0,
+    )
+    .commit(connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ErrorCode::ValidationError { errors } => {
+                assert_eq!(errors["flat_in_cents"][0].code, "flat_commission_invalid");
+                assert_eq!(
+                    errors["ticket_type_id"][0].code,
+                    "ticket_type_not_in_organization"
+                );
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
@@ -0,0 +1,235 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use chrono::prelude::*;
+use chrono::Duration;
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project
+        .create_event()
+        .with_name("Old name".to_string())
+        .finish();
+    let updated_event = event
+        .update(
+            EventEditableAttributes {
+                name: Some("New name".to_string()),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+
+    let audit_log = AuditLog::create(
+        Some(user.id),
+        Some(Scopes::EventWrite),
+        Tables::Events,
+        event.id,
+        Some(event.organization_id),
+        Some(event.id),
+    )
+    .updated(&event, &updated_event)
+    .unwrap()
+    .commit(connection)
+    .unwrap();
+
+    assert_eq!(audit_log.user_id, Some(user.id));
+    assert_eq!(audit_log.scope, Some("event:write".to_string()));
+    assert_eq!(audit_log.action, AuditActions::Updated);
+    assert_eq!(audit_log.main_table, Tables::Events);
+    assert_eq!(audit_log.main_id, event.id);
+    assert_eq!(
+        audit_log.changes,
+        json!({"name": {"before": "Old name", "after": "New name"}})
+    );
+}
+
+#[test]
+fn created_and_deleted() {
+    let project = TestProject::new();
+    let code = project.create_code().finish();
+
+    let created = AuditLog::create(
+        None,
+        None,
+        Tables::Codes,
+        code.id,
+        None,
+        Some(code.event_id),
+    )
+    .created(&code)
+    .unwrap();
+    assert_eq!(created.action, AuditActions::Created);
+    assert_eq!(
+        created.changes["name"],
+        json!({"before": null, "after": code.name})
+    );
+    assert!(created.changes.get("created_at").is_none());
+
+    let deleted = AuditLog::create(
+        None,
+        None,
+        Tables::Codes,
+        code.id,
+        None,
+        Some(code.event_id),
+    )
+    .deleted(&code)
+    .unwrap();
+    assert_eq!(deleted.action, AuditActions::Deleted);
+    assert_eq!(
+        deleted.changes["name"],
+        json!({"before": code.name, "after": null})
+    );
+}
+
+#[test]
+fn updated_redacts_secrets() {
+    let project = TestProject::new();
+    let organization = project.create_organization().finish();
+    let mut updated_organization = organization.clone();
+    updated_organization.sendgrid_api_key = Some("new-key".to_string());
+
+    let audit_log = AuditLog::create(
+        None,
+        Some(Scopes::OrgWrite),
+        Tables::Organizations,
+        organization.id,
+        Some(organization.id),
+        None,
+    )
+    .updated(&organization, &updated_organization)
+    .unwrap();
+    assert_eq!(
+        audit_log.changes,
+        json!({"sendgrid_api_key": {"before": null, "after": "[redacted]"}})
+    );
+}
+
+#[test]
+fn find_for_event() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let other_event = project
+        .create_event()
+        .with_organization(&event.organization(connection).unwrap())
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+
+    let event_log = AuditLog::create(
+        Some(user.id),
+        Some(Scopes::EventWrite),
+        Tables::Events,
+        event.id,
+        Some(event.organization_id),
+        Some(event.id),
+    )
+    .created(&event)
+    .unwrap()
+    .commit(connection)
+    .unwrap();
+    let ticket_type_log = AuditLog::create(
+        Some(user2.id),
+        Some(Scopes::EventWrite),
+        Tables::TicketTypes,
+        ticket_type.id,
+        Some(event.organization_id),
+        Some(event.id),
+    )
+    .created(ticket_type)
+    .unwrap()
+    .commit(connection)
+    .unwrap();
+    let other_event_log = AuditLog::create(
+        Some(user.id),
+        Some(Scopes::EventWrite),
+        Tables::Events,
+        other_event.id,
+        Some(other_event.organization_id),
+        Some(other_event.id),
+    )
+    .created(&other_event)
+    .unwrap()
+    .commit(connection)
+    .unwrap();
+
+    let payload =
+        AuditLog::find_for_event(event.id, AuditLogFilters::default(), 0, 100, connection).unwrap();
+    assert_eq!(payload.paging.total, 2);
+    assert!(payload.data.contains(&event_log));
+    assert!(payload.data.contains(&ticket_type_log));
+
+    // Filtered by table
+    let payload = AuditLog::find_for_event(
+        event.id,
+        AuditLogFilters {
+            main_table: Some(Tables::TicketTypes),
+            ..Default::default()
+        },
+        0,
+        100,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(payload.data, vec![ticket_type_log.clone()]);
+
+    // Filtered by user
+    let payload = AuditLog::find_for_event(
+        event.id,
+        AuditLogFilters {
+            user_id: Some(user.id),
+            ..Default::default()
+        },
+        0,
+        100,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(payload.data, vec![event_log.clone()]);
+
+    // Filtered by date
+    let payload = AuditLog::find_for_event(
+        event.id,
+        AuditLogFilters {
+            start_date: Some(Utc::now().naive_utc() + Duration::days(1)),
+            ..Default::default()
+        },
+        0,
+        100,
+        connection,
+    )
+    .unwrap();
+    assert!(payload.data.is_empty());
+
+    // The organization sees the changes to all of its events
+    let payload = AuditLog::find_for_organization(
+        event.organization_id,
+        AuditLogFilters {
+            action: Some(AuditActions::Created),
+            ..Default::default()
+        },
+        0,
+        100,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(payload.paging.total, 3);
+    assert!(payload.data.contains(&other_event_log));
+
+    // Paging
+    let payload = AuditLog::find_for_organization(
+        event.organization_id,
+        AuditLogFilters::default(),
+        1,
+        2,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(payload.paging.total, 3);
+    assert_eq!(payload.data.len(), 1);
+}
@@ -0,0 +1,98 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+
+fn sent_campaign(project: &TestProject, buyer_count: usize) -> (Campaign, Vec<CampaignRecipient>) {
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let user = project.create_user().finish();
+    for _ in 0..buyer_count {
+        let buyer = project.create_user().finish();
+        project
+            .create_order()
+            .for_event(&event)
+            .for_user(&buyer)
+            .is_paid()
+            .finish();
+    }
+    let mut new_campaign = Campaign::create(
+        organization.id,
+        "Doors update",
+        CommunicationChannelType::Sms,
+        CampaignAudiences::EventTicketHolders,
+        None,
+        "Doors now open at 8pm",
+        user.id,
+    );
+    new_campaign.event_id = Some(event.id);
+    let campaign = new_campaign
+        .commit(connection)
+        .unwrap()
+        .start_sending(connection)
+        .unwrap();
+    campaign.record_recipients(connection).unwrap();
+    let recipients = campaign.pending_recipients(100, connection).unwrap();
+    (campaign, recipients)
+}
+
+#[test]
+fn find_for_campaign() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (campaign, _) = sent_campaign(&project, 3);
+
+    let recipients =
+        CampaignRecipient::find_for_campaign(campaign.id, None, 0, 2, connection).unwrap();
+    assert_eq!(recipients.data.len(), 2);
+    assert_eq!(recipients.paging.total, 3);
+
+    let recipients = CampaignRecipient::find_for_campaign(
+        campaign.id,
+        Some(CampaignRecipientStatus::Sent),
+        0,
+        100,
+        connection,
+    )
+    .unwrap();
+    assert!(recipients.data.is_empty());
+    assert_eq!(recipients.paging.total, 0);
+}
+
+#[test]
+fn unsubscribe() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let (campaign, recipients) = sent_campaign(&project, 1);
+    let recipient =
+        CampaignRecipient::find_by_unsubscribe_token(recipients[0].unsubscribe_token, connection)
+            .unwrap();
+    assert_eq!(recipient, recipients[0]);
+
+    let preference = recipient
+        .unsubscribe(Some("127.0.0.1".to_string()), connection)
+        .unwrap();
+    assert_eq!(preference.user_id, recipient.user_id);
+    assert_eq!(preference.organization_id, Some(campaign.organization_id));
+    assert_eq!(preference.category, CommunicationCategory::Marketing);
+    assert_eq!(preference.channel, CommunicationChannelType::Sms);
+    assert!(!preference.subscribed);
+
+    let consents = CommunicationConsent::find_for_user(recipient.user_id, connection).unwrap();
+    assert_eq!(consents.len(), 1);
+    assert_eq!(consents[0].source, "Campaign unsubscribe".to_string());
+    assert_eq!(consents[0].ip_address, Some("127.0.0.1".to_string()));
+    assert_eq!(
+        CommunicationPreference::unsubscribed_user_ids(
+            campaign.organization_id,
+            CommunicationCategory::Marketing,
+            CommunicationChannelType::Sms,
+            connection
+        )
+        .unwrap(),
+        vec![recipient.user_id]
+    );
+}
@@ -0,0 +1,467 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::schema::users;
+use bigneon_db::utils::errors::ErrorCode;
+use chrono::prelude::*;
+use diesel;
+use diesel::prelude::*;
+use time::Duration;
+
+fn create_domain_action(recipient: &CampaignRecipient, connection: &PgConnection) -> DomainAction {
+    DomainAction::create(
+        None,
+        DomainActionTypes::Communication,
+        Some(CommunicationChannelType::Email),
+        json!({}),
+        Some(Tables::CampaignRecipients.to_string()),
+        Some(recipient.id),
+        Utc::now().naive_utc(),
+        Utc::now().naive_utc() + Duration::days(1),
+        3,
+    )
+    .commit(connection)
+    .unwrap()
+}
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .finish();
+
+    let mut new_campaign = Campaign::create(
+        organization.id,
+        "Doors update",
+        CommunicationChannelType::Email,
+        CampaignAudiences::EventTicketHolders,
+        Some("Doors moved to 8pm".to_string()),
+        "Doors now open at 8pm",
+        user.id,
+    );
+    new_campaign.event_id = Some(event.id);
+    let campaign = new_campaign.commit(connection).unwrap();
+    assert_eq!(campaign.organization_id, organization.id);
+    assert_eq!(campaign.event_id, Some(event.id));
+    assert_eq!(campaign.status, CampaignStatus::Draft);
+    assert_eq!(campaign.batch_size, 100);
+    assert_eq!(campaign.created_by_user_id, user.id);
+}
+
+#[test]
+fn create_with_validation_errors() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let other_event = project.create_event().finish();
+
+    let mut new_campaign = Campaign::create(
+        organization.id,
+        "Doors update",
+        CommunicationChannelType::Email,
+        CampaignAudiences::EventTicketHolders,
+        None,
+        "",
+        user.id,
+    );
+    new_campaign.event_id = Some(other_event.id);
+    new_campaign.batch_size = 0;
+    match new_campaign.commit(connection) {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ErrorCode::ValidationError { errors } => {
+                assert_eq!(errors["body"][0].code, "length");
+                assert_eq!(errors["subject"][0].code, "subject_required");
+                assert_eq!(errors["batch_size"][0].code, "batch_size_must_be_positive");
+                assert_eq!(errors["event_id"][0].code, "event_not_in_organization");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    let result = Campaign::create(
+        organization.id,
+        "Members",
+        CommunicationChannelType::Push,
+        CampaignAudiences::FanSegment,
+        None,
+        "Hello",
+        user.id,
+    )
+    .commit(connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ErrorCode::ValidationError { errors } => {
+                assert_eq!(errors["channel"][0].code, "channel_not_supported");
+                assert_eq!(errors["fan_segment_id"][0].code, "fan_segment_required");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn update() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .finish();
+    let mut new_campaign = Campaign::create(
+        organization.id,
+        "Doors update",
+        CommunicationChannelType::Sms,
+        CampaignAudiences::EventTicketHolders,
+        None,
+        "Doors now open at 8pm",
+        user.id,
+    );
+    new_campaign.event_id = Some(event.id);
+    let campaign = new_campaign.commit(connection).unwrap();
+
+    let campaign = campaign
+        .update(
+            CampaignEditableAttributes {
+                body: Some("Doors now open at 9pm".to_string()),
+                batch_size: Some(10),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+    assert_eq!(campaign.body, "Doors now open at 9pm".to_string());
+    assert_eq!(campaign.batch_size, 10);
+
+    // Sent campaigns cannot be changed
+    let campaign = campaign.start_sending(connection).unwrap();
+    let result = campaign.update(
+        CampaignEditableAttributes {
+            body: Some("Doors now open at 10pm".to_string()),
+            ..Default::default()
+        },
+        connection,
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn audience() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_type_count(2)
+        .with_ticket_pricing()
+        .finish();
+    let ticket_types = event.ticket_types(true, None, connection).unwrap();
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .is_paid()
+        .finish();
+    // Unpaid orders do not make the user a ticket holder
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user2)
+        .finish();
+
+    let mut new_campaign = Campaign::create(
+        organization.id,
+        "Doors update",
+        CommunicationChannelType::Sms,
+        CampaignAudiences::EventTicketHolders,
+        None,
+        "Doors now open at 8pm",
+        user.id,
+    );
+    new_campaign.event_id = Some(event.id);
+    let campaign = new_campaign.commit(connection).unwrap();
+    let audience = campaign.audience(connection).unwrap();
+    assert_eq!(audience.len(), 1);
+    assert_eq!(audience[0].user_id, user.id);
+    assert_eq!(audience[0].email, user.email);
+
+    // The order builder buys the first ticket type
+    let mut new_campaign = Campaign::create(
+        organization.id,
+        "VIP update",
+        CommunicationChannelType::Sms,
+        CampaignAudiences::TicketTypeHolders,
+        None,
+        "VIP entrance moved",
+        user.id,
+    );
+    new_campaign.ticket_type_id = Some(ticket_types[1].id);
+    let campaign = new_campaign.commit(connection).unwrap();
+    assert!(campaign.audience(connection).unwrap().is_empty());
+
+    let fan_segment = FanSegment::create(
+        organization.id,
+        "Everyone",
+        &FanSegmentCriteria::default(),
+        user.id,
+    )
+    .commit(connection)
+    .unwrap();
+    let mut new_campaign = Campaign::create(
+        organization.id,
+        "Presale",
+        CommunicationChannelType::Sms,
+        CampaignAudiences::FanSegment,
+        None,
+        "Presale starts now",
+        user.id,
+    );
+    new_campaign.fan_segment_id = Some(fan_segment.id);
+    let campaign = new_campaign.commit(connection).unwrap();
+    let audience = campaign.audience(connection).unwrap();
+    assert_eq!(audience.len(), 1);
+    assert_eq!(audience[0].user_id, user.id);
+}
+
+#[test]
+fn start_sending() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let user = project.create_user().finish();
+    let opted_out_user = project.create_user().finish();
+    let user_without_phone = project.create_user().finish();
+    for buyer in &[&user, &opted_out_user, &user_without_phone] {
+        project
+            .create_order()
+            .for_event(&event)
+            .for_user(buyer)
+            .is_paid()
+            .finish();
+    }
+    diesel::update(users::table.filter(users::id.eq(user_without_phone.id)))
+        .set(users::phone.eq(None::<String>))
+        .execute(connection)
+        .unwrap();
+    // Turned off for every organization
+    CommunicationPreference::set(
+        opted_out_user.id,
+        None,
+        CommunicationCategory::Marketing,
+        CommunicationChannelType::Sms,
+        false,
+        "Preferences",
+        None,
+        connection,
+    )
+    .unwrap();
+
+    let mut new_campaign = Campaign::create(
+        organization.id,
+        "Doors update",
+        CommunicationChannelType::Sms,
+        CampaignAudiences::EventTicketHolders,
+        None,
+        "Doors now open at 8pm",
+        user.id,
+    );
+    new_campaign.event_id = Some(event.id);
+    let campaign = new_campaign.commit(connection).unwrap();
+    assert_eq!(
+        campaign.audience_summary(connection).unwrap(),
+        CampaignAudienceSummary {
+            total: 3,
+            opted_out: 1,
+            missing_address: 1,
+            reachable: 1,
+        }
+    );
+
+    let campaign = campaign.start_sending(connection).unwrap();
+    assert_eq!(campaign.status, CampaignStatus::Sending);
+    assert!(campaign.sent_at.is_some());
+    let batches =
+        DomainAction::find_pending(Some(DomainActionTypes::SendCampaign), connection).unwrap();
+    assert_eq!(batches.len(), 1);
+    assert_eq!(batches[0].main_table_id, Some(campaign.id));
+    // Nothing is recorded until the first batch runs
+    assert_eq!(
+        campaign.recipient_counts(connection).unwrap(),
+        CampaignRecipientCounts::default()
+    );
+
+    campaign.record_recipients(connection).unwrap();
+    let recipients = campaign.pending_recipients(100, connection).unwrap();
+    assert_eq!(recipients.len(), 1);
+    assert_eq!(recipients[0].user_id, user.id);
+    assert_eq!(recipients[0].address, user.phone);
+    assert_eq!(
+        campaign.recipient_counts(connection).unwrap(),
+        CampaignRecipientCounts {
+            pending: 1,
+            skipped: 2,
+            ..Default::default()
+        }
+    );
+    // Recipients are only recorded once
+    campaign.record_recipients(connection).unwrap();
+    assert_eq!(campaign.recipient_counts(connection).unwrap().skipped, 2);
+
+    // Campaigns can only be sent once
+    assert!(campaign.start_sending(connection).is_err());
+}
+
+#[test]
+fn update_recipient_statuses() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    for buyer in &[&user, &user2] {
+        project
+            .create_order()
+            .for_event(&event)
+            .for_user(buyer)
+            .is_paid()
+            .finish();
+    }
+    let mut new_campaign = Campaign::create(
+        organization.id,
+        "Doors update",
+        CommunicationChannelType::Email,
+        CampaignAudiences::EventTicketHolders,
+        Some("Doors update".to_string()),
+        "Doors now open at 8pm",
+        user.id,
+    );
+    new_campaign.event_id = Some(event.id);
+    let campaign = new_campaign
+        .commit(connection)
+        .unwrap()
+        .start_sending(connection)
+        .unwrap();
+    campaign.record_recipients(connection).unwrap();
+    let recipients = campaign.pending_recipients(100, connection).unwrap();
+    // The batch that queues the messages has run
+    DomainAction::find_pending(Some(DomainActionTypes::SendCampaign), connection).unwrap()[0]
+        .set_done(connection)
+        .unwrap();
+    let actions: Vec<DomainAction> = recipients
+        .iter()
+        .map(|recipient| {
+            let action = create_domain_action(recipient, connection);
+            recipient.mark_queued(action.id, connection).unwrap();
+            action
+        })
+        .collect();
+
+    actions[0].set_done(connection).unwrap();
+    let campaign = campaign.update_recipient_statuses(connection).unwrap();
+    assert_eq!(campaign.status, CampaignStatus::Sending);
+    assert_eq!(
+        campaign.recipient_counts(connection).unwrap(),
+        CampaignRecipientCounts {
+            queued: 1,
+            sent: 1,
+            ..Default::default()
+        }
+    );
+
+    actions[1]
+        .set_errored("Invalid address", connection)
+        .unwrap();
+    let campaign = campaign.update_recipient_statuses(connection).unwrap();
+    assert_eq!(campaign.status, CampaignStatus::Sent);
+    assert!(campaign.completed_at.is_some());
+    let failed = campaign
+        .recipients(Some(CampaignRecipientStatus::Failed), 0, 100, connection)
+        .unwrap();
+    assert_eq!(failed.data.len(), 1);
+    assert_eq!(
+        failed.data[0].status_reason,
+        Some("Invalid address".to_string())
+    );
+}
+
+#[test]
+fn cancel() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let user = project.create_user().finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .is_paid()
+        .finish();
+    let mut new_campaign = Campaign::create(
+        organization.id,
+        "Doors update",
+        CommunicationChannelType::Sms,
+        CampaignAudiences::EventTicketHolders,
+        None,
+        "Doors now open at 8pm",
+        user.id,
+    );
+    new_campaign.event_id = Some(event.id);
+    let campaign = new_campaign
+        .commit(connection)
+        .unwrap()
+        .start_sending(connection)
+        .unwrap();
+    campaign.record_recipients(connection).unwrap();
+    let recipients = campaign.pending_recipients(100, connection).unwrap();
+    let action = create_domain_action(&recipients[0], connection);
+    recipients[0].mark_queued(action.id, connection).unwrap();
+
+    let batches =
+        DomainAction::find_pending(Some(DomainActionTypes::SendCampaign), connection).unwrap();
+    let campaign = campaign.cancel(connection).unwrap();
+    assert_eq!(campaign.status, CampaignStatus::Cancelled);
+    assert_eq!(
+        DomainAction::find(batches[0].id, connection)
+            .unwrap()
+            .status,
+        DomainActionStatus::Cancelled
+    );
+    assert_eq!(
+        DomainAction::find(action.id, connection).unwrap().status,
+        DomainActionStatus::Cancelled
+    );
+    assert_eq!(
+        campaign.recipient_counts(connection).unwrap(),
+        CampaignRecipientCounts {
+            cancelled: 1,
+            ..Default::default()
+        }
+    );
+    assert!(campaign.cancel(connection).is_err());
+}
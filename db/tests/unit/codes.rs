@@ -217,6 +217,118 @@ fn update() {
     assert_eq!(new_code.name, "New name".to_string());
 }
 
+#[test]
+fn update_with_fan_segment() {
+    let db = TestProject::new();
+    let connection = db.get_connection();
+    let user = db.create_user().finish();
+    let organization = db.create_organization().finish();
+    let event = db
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .finish();
+    let code = db.create_code().with_event(&event).finish();
+    let fan_segment = FanSegment::create(
+        organization.id,
+        "Regulars",
+        &FanSegmentCriteria::default(),
+        user.id,
+    )
+    .commit(connection)
+    .unwrap();
+    let other_fan_segment = FanSegment::create(
+        db.create_organization().finish().id,
+        "Regulars",
+        &FanSegmentCriteria::default(),
+        user.id,
+    )
+    .commit(connection)
+    .unwrap();
+
+    let update_patch = UpdateCodeAttributes {
+        fan_segment_id: Some(Some(other_fan_segment.id)),
+        ..Default::default()
+    };
+    match code.update(update_patch, connection) {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert_eq!(
+                    errors["fan_segment_id"][0].code,
+                    "fan_segment_not_in_organization"
+                );
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    let update_patch = UpdateCodeAttributes {
+        fan_segment_id: Some(Some(fan_segment.id)),
+        ..Default::default()
+    };
+    let code = code.update(update_patch, connection).unwrap();
+    assert_eq!(code.fan_segment_id, Some(fan_segment.id));
+
+    let update_patch = UpdateCodeAttributes {
+        fan_segment_id: Some(None),
+        ..Default::default()
+    };
+    let code = code.update(update_patch, connection).unwrap();
+    assert_eq!(code.fan_segment_id, None);
+}
+
+#[test]
+fn confirm_user_eligible() {
+    let db = TestProject::new();
+    let connection = db.get_connection();
+    let organization = db.create_organization().finish();
+    let event = db
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let fan = db.create_user().finish();
+    let user = db.create_user().finish();
+    db.create_order()
+        .for_event(&event)
+        .for_user(&fan)
+        .is_paid()
+        .finish();
+    let fan_segment = FanSegment::create(
+        organization.id,
+        "Regulars",
+        &FanSegmentCriteria::default(),
+        fan.id,
+    )
+    .commit(connection)
+    .unwrap();
+    let code = db.create_code().with_event(&event).finish();
+
+    // Codes without a segment are available to everyone
+    assert!(code.confirm_user_eligible(user.id, connection).is_ok());
+
+    let code = code
+        .update(
+            UpdateCodeAttributes {
+                fan_segment_id: Some(Some(fan_segment.id)),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+    assert!(code.confirm_user_eligible(fan.id, connection).is_ok());
+    match code.confirm_user_eligible(user.id, connection) {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("redemption_code"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
 #[test]
 pub fn update_with_validation_errors() {
     let db = TestProject::new();
@@ -0,0 +1,195 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::utils::errors::ErrorCode;
+
+#[test]
+fn set() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+
+    let preference = CommunicationPreference::set(
+        user.id,
+        Some(organization.id),
+        CommunicationCategory::Marketing,
+        CommunicationChannelType::Email,
+        false,
+        "Preferences",
+        Some("127.0.0.1".to_string()),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(preference.user_id, user.id);
+    assert_eq!(preference.organization_id, Some(organization.id));
+    assert!(!preference.subscribed);
+
+    // Changing the preference updates it and keeps the earlier consent
+    let updated_preference = CommunicationPreference::set(
+        user.id,
+        Some(organization.id),
+        CommunicationCategory::Marketing,
+        CommunicationChannelType::Email,
+        true,
+        "Preferences",
+        None,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(updated_preference.id, preference.id);
+    assert!(updated_preference.subscribed);
+    assert_eq!(
+        CommunicationPreference::find_for_user(user.id, connection).unwrap(),
+        vec![updated_preference]
+    );
+
+    let consents = CommunicationConsent::find_for_user(user.id, connection).unwrap();
+    assert_eq!(consents.len(), 2);
+    assert!(consents
+        .iter()
+        .any(|c| c.subscribed && c.ip_address.is_none()));
+    assert!(consents
+        .iter()
+        .any(|c| !c.subscribed && c.ip_address == Some("127.0.0.1".to_string())));
+}
+
+#[test]
+fn set_transactional_email() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+
+    let result = CommunicationPreference::set(
+        user.id,
+        None,
+        CommunicationCategory::Transactional,
+        CommunicationChannelType::Email,
+        false,
+        "Preferences",
+        None,
+        connection,
+    );
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ErrorCode::ValidationError { errors } => {
+                assert_eq!(
+                    errors["subscribed"][0].code,
+                    "Transactional emails cannot be turned off"
+                );
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+    assert!(CommunicationConsent::find_for_user(user.id, connection)
+        .unwrap()
+        .is_empty());
+
+    // Transactional text messages can be turned off
+    assert!(CommunicationPreference::set(
+        user.id,
+        None,
+        CommunicationCategory::Transactional,
+        CommunicationChannelType::Sms,
+        false,
+        "Preferences",
+        None,
+        connection,
+    )
+    .is_ok());
+}
+
+#[test]
+fn allows() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let other_organization = project.create_organization().finish();
+    let allows = |organization_id, category, channel| {
+        CommunicationPreference::allows(user.id, organization_id, category, channel, connection)
+            .unwrap()
+    };
+
+    assert!(allows(
+        Some(organization.id),
+        CommunicationCategory::Marketing,
+        CommunicationChannelType::Email
+    ));
+
+    CommunicationPreference::set(
+        user.id,
+        Some(organization.id),
+        CommunicationCategory::Marketing,
+        CommunicationChannelType::Email,
+        false,
+        "Preferences",
+        None,
+        connection,
+    )
+    .unwrap();
+    assert!(!allows(
+        Some(organization.id),
+        CommunicationCategory::Marketing,
+        CommunicationChannelType::Email
+    ));
+    assert!(allows(
+        Some(other_organization.id),
+        CommunicationCategory::Marketing,
+        CommunicationChannelType::Email
+    ));
+    assert!(allows(
+        Some(organization.id),
+        CommunicationCategory::Marketing,
+        CommunicationChannelType::Sms
+    ));
+    assert!(allows(
+        Some(organization.id),
+        CommunicationCategory::Transactional,
+        CommunicationChannelType::Email
+    ));
+
+    // Turning it off everywhere wins over subscribing to one organization
+    CommunicationPreference::set(
+        user.id,
+        Some(other_organization.id),
+        CommunicationCategory::Marketing,
+        CommunicationChannelType::Email,
+        true,
+        "Preferences",
+        None,
+        connection,
+    )
+    .unwrap();
+    CommunicationPreference::set(
+        user.id,
+        None,
+        CommunicationCategory::Marketing,
+        CommunicationChannelType::Email,
+        false,
+        "Preferences",
+        None,
+        connection,
+    )
+    .unwrap();
+    assert!(!allows(
+        Some(other_organization.id),
+        CommunicationCategory::Marketing,
+        CommunicationChannelType::Email
+    ));
+    assert!(!allows(
+        None,
+        CommunicationCategory::Marketing,
+        CommunicationChannelType::Email
+    ));
+    assert_eq!(
+        CommunicationPreference::unsubscribed_user_ids(
+            other_organization.id,
+            CommunicationCategory::Marketing,
+            CommunicationChannelType::Email,
+            connection
+        )
+        .unwrap(),
+        vec![user.id]
+    );
+}
@@ -1 +1,2 @@
 pub mod password_resetable;
+pub mod passwordless_loginable;
@@ -0,0 +1,168 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::concerns::users::passwordless_loginable::{
+    PasswordlessLogin, PasswordlessLoginable,
+};
+use bigneon_db::models::User;
+use bigneon_db::utils::errors::ErrorCode;
+use chrono::{Duration, Utc};
+use diesel;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+#[test]
+fn create_login_token() {
+    let project = TestProject::new();
+    let user = project.create_user().finish();
+    assert!(!user.has_valid_login_token());
+
+    let user = user
+        .create_login_token(project.get_connection())
+        .expect("Failed to create login token");
+    assert!(user.has_valid_login_token());
+    assert!(user.login_token.is_some());
+    assert!(user.login_code_hash.is_none());
+
+    // Requesting again replaces the earlier token
+    let token = user.login_token;
+    let user = user
+        .create_login_token(project.get_connection())
+        .expect("Failed to create login token");
+    assert_ne!(user.login_token, token);
+}
+
+#[test]
+fn create_login_code() {
+    let project = TestProject::new();
+    let user = project.create_user().finish();
+    let user = user.create_login_token(project.get_connection()).unwrap();
+
+    let (user, code) = user
+        .create_login_code(project.get_connection())
+        .expect("Failed to create login code");
+    assert!(user.has_valid_login_token());
+    assert!(user.login_token.is_none());
+    assert_eq!(code.len(), 6);
+    assert!(user.login_code_hash.is_some());
+    assert_ne!(user.login_code_hash, Some(code));
+}
+
+#[test]
+fn consume_login_token() {
+    use bigneon_db::schema::users::dsl::*;
+    let project = TestProject::new();
+    let user = project.create_user().finish();
+    assert!(user.email_verified_at.is_none());
+    let user = user.create_login_token(project.get_connection()).unwrap();
+    let token = user.login_token.unwrap();
+
+    let consumed_user = User::consume_login_token(&token, project.get_connection()).unwrap();
+    assert_eq!(consumed_user.id, user.id);
+    assert!(consumed_user.login_token.is_none());
+    assert!(consumed_user.login_code_hash.is_none());
+    assert!(consumed_user.email_verified_at.is_some());
+
+    // Can only be used once
+    assert!(User::consume_login_token(&token, project.get_connection()).is_err());
+
+    // Expired
+    let token = Uuid::new_v4();
+    diesel::update(users.filter(id.eq(user.id)))
+        .set(PasswordlessLogin {
+            login_token: Some(token),
+            login_code_hash: None,
+            login_token_requested_at: Some(Utc::now().naive_utc() - Duration::minutes(20)),
+            login_code_failed_attempts: 0,
+        })
+        .execute(project.get_connection())
+        .unwrap();
+    match User::consume_login_token(&token, project.get_connection()) {
+        Ok(_) => panic!("Expected failure to consume expired login token"),
+        Err(e) => assert_eq!(e.error_code, ErrorCode::BusinessProcessError),
+    }
+}
+
+#[test]
+fn consume_login_code() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let phone = user.phone.clone().unwrap();
+    let user = user.verify_phone(&phone, connection).unwrap();
+    let (user, code) = user.create_login_code(connection).unwrap();
+
+    assert!(User::consume_login_code(&phone, "not the code", connection)
+        .unwrap()
+        .is_none());
+    let user = User::find(user.id, connection).unwrap();
+    assert_eq!(user.login_code_failed_attempts, 1);
+
+    let consumed_user = User::consume_login_code(&phone, &code, connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(consumed_user.id, user.id);
+    assert!(consumed_user.login_code_hash.is_none());
+    assert!(consumed_user.login_token.is_none());
+    assert_eq!(consumed_user.login_code_failed_attempts, 0);
+
+    // Can only be used once
+    assert!(User::consume_login_code(&phone, &code, connection)
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn consume_login_code_discarded_after_failed_attempts() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let phone = user.phone.clone().unwrap();
+    let user = user.verify_phone(&phone, connection).unwrap();
+    let (user, code) = user.create_login_code(connection).unwrap();
+
+    for _ in 0..5 {
+        assert!(User::consume_login_code(&phone, "wrong", connection)
+            .unwrap()
+            .is_none());
+    }
+    let user = User::find(user.id, connection).unwrap();
+    assert!(user.login_code_hash.is_none());
+    assert!(User::consume_login_code(&phone, &code, connection)
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn consume_login_code_requires_single_verified_phone() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let phone = user.phone.clone().unwrap();
+    let (_, code) = user.create_login_code(connection).unwrap();
+
+    // Unverified
+    assert!(User::find_by_login_phone(&phone, connection)
+        .unwrap()
+        .is_none());
+    assert!(User::consume_login_code(&phone, &code, connection)
+        .unwrap()
+        .is_none());
+
+    let user = User::find(user.id, connection).unwrap();
+    let user = user.verify_phone(&phone, connection).unwrap();
+    assert_eq!(
+        User::find_by_login_phone(&phone, connection).unwrap(),
+        Some(user.clone())
+    );
+
+    // Shared with another verified user
+    let user2 = project.create_user().finish();
+    user2.verify_phone(&phone, connection).unwrap();
+    assert!(User::find_by_login_phone(&phone, connection)
+        .unwrap()
+        .is_none());
+    assert!(User::consume_login_code(&phone, &code, connection)
+        .unwrap()
+        .is_none());
+    let user = User::find(user.id, connection).unwrap();
+    assert!(user.has_valid_login_token());
+}
@@ -0,0 +1,176 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::utils::errors::ErrorCode;
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().finish();
+    let partner = project.create_organization().finish();
+
+    let split_partner =
+        EventSplitPartner::create(event.id, partner.id, SplitTypes::Percentage, 2_500, 1_000)
+            .commit(connection)
+            .unwrap();
+    assert_eq!(split_partner.event_id, event.id);
+    assert_eq!(split_partner.organization_id, partner.id);
+    assert_eq!(split_partner.split_type, SplitTypes::Percentage);
+    assert_eq!(split_partner.ticket_sales_rate_in_basis_points, 2_500);
+    assert_eq!(split_partner.fees_rate_in_basis_points, 1_000);
+    assert_eq!(split_partner.ticket_sales_flat_in_cents, 0);
+    assert_eq!(split_partner.fees_flat_in_cents, 0);
+    assert_eq!(
+        EventSplitPartner::find(split_partner.id, connection).unwrap(),
+        split_partner
+    );
+}
+
+#[test]
+fn create_with_validation_errors() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .finish();
+    let partner = project.create_organization().finish();
+    let partner2 = project.create_organization().finish();
+
+    let assert_validation_error =
+        |new_split_partner: NewEventSplitPartner, field: &str| match new_split_partner
+            .commit(connection)
+        {
+            Ok(_) => panic!("Expected validation error"),
+            Err(error) => match &error.error_code {
+                ErrorCode::ValidationError { errors } => {
+                    assert!(errors.contains_key(field));
+                }
+                _ => panic!("Expected validation error"),
+            },
+        };
+
+    // Flat split with no amount
+    assert_validation_error(
+        EventSplitPartner::create(event.id, partner.id, SplitTypes::Flat, 0, 0),
+        "split_type",
+    );
+    // Percentage split over 100%
+    assert_validation_error(
+        EventSplitPartner::create(event.id, partner.id, SplitTypes::Percentage, 10_001, 0),
+        "split_type",
+    );
+    // Percentage split with a flat amount
+    let mut new_split_partner =
+        EventSplitPartner::create(event.id, partner.id, SplitTypes::Percentage, 1_000, 0);
+    new_split_partner.fees_flat_in_cents = 100;
+    assert_validation_error(new_split_partner, "split_type");
+    // The event's own organization
+    assert_validation_error(
+        EventSplitPartner::create(event.id, organization.id, SplitTypes::Flat, 100, 0),
+        "organization_id",
+    );
+
+    EventSplitPartner::create(event.id, partner.id, SplitTypes::Percentage, 6_000, 0)
+        .commit(connection)
+        .unwrap();
+    // Already a partner
+    assert_validation_error(
+        EventSplitPartner::create(event.id, partner.id, SplitTypes::Flat, 100, 0),
+        "organization_id",
+    );
+    // Rates over 100% in total
+    assert_validation_error(
+        EventSplitPartner::create(event.id, partner2.id, SplitTypes::Percentage, 5_000, 0),
+        "split_type",
+    );
+}
+
+#[test]
+fn destroy() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().finish();
+    let partner = project.create_organization().finish();
+    let split_partner = EventSplitPartner::create(event.id, partner.id, SplitTypes::Flat, 100, 0)
+        .commit(connection)
+        .unwrap();
+
+    split_partner.destroy(connection).unwrap();
+    assert!(
+        EventSplitPartner::find_by_event_id_organization_id(event.id, partner.id, connection)
+            .is_err()
+    );
+}
+
+#[test]
+fn find_for_event() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().finish();
+    let event2 = project.create_event().finish();
+    let partner = project.create_organization().finish();
+    let split_partner = EventSplitPartner::create(event.id, partner.id, SplitTypes::Flat, 100, 0)
+        .commit(connection)
+        .unwrap();
+    EventSplitPartner::create(event2.id, partner.id, SplitTypes::Flat, 100, 0)
+        .commit(connection)
+        .unwrap();
+
+    assert_eq!(
+        EventSplitPartner::find_for_event(event.id, connection).unwrap(),
+        vec![split_partner.clone()]
+    );
+    assert_eq!(
+        EventSplitPartner::find_by_event_id_organization_id(event.id, partner.id, connection)
+            .unwrap(),
+        split_partner
+    );
+}
+
+#[test]
+fn share() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().finish();
+    let partner = project.create_organization().finish();
+    let partner2 = project.create_organization().finish();
+    let flat_split_partner =
+        EventSplitPartner::create(event.id, partner.id, SplitTypes::Flat, 5_000, 150)
+            .commit(connection)
+            .unwrap();
+    let percentage_split_partner =
+        EventSplitPartner::create(event.id, partner2.id, SplitTypes::Percentage, 2_500, 1_000)
+            .commit(connection)
+            .unwrap();
+
+    // Flat shares do not depend on sales
+    assert_eq!(
+        flat_split_partner.share(100_000, 999),
+        SplitPartnerShare {
+            event_id: event.id,
+            organization_id: partner.id,
+            split_type: SplitTypes::Flat,
+            ticket_sales_in_cents: 5_000,
+            fees_in_cents: 150,
+        }
+    );
+    // Percentage shares are rounded down
+    let percentage_share = SplitPartnerShare {
+        event_id: event.id,
+        organization_id: partner2.id,
+        split_type: SplitTypes::Percentage,
+        ticket_sales_in_cents: 25_000,
+        fees_in_cents: 99,
+    };
+    assert_eq!(
+        percentage_split_partner.share(100_000, 999),
+        percentage_share
+    );
+
+    let shares = EventSplitPartner::shares_for_event(event.id, 100_000, 999, connection).unwrap();
+    assert_eq!(shares.len(), 2);
+    assert!(shares.contains(&flat_split_partner.share(100_000, 999)));
+    assert!(shares.contains(&percentage_share));
+}
@@ -0,0 +1,112 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::utils::errors::ErrorCode;
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().finish();
+    let user = project.create_user().finish();
+
+    let event_user = EventUser::create(event.id, user.id, Roles::Promoter, connection).unwrap();
+    assert_eq!(event_user.event_id, event.id);
+    assert_eq!(event_user.user_id, user.id);
+    assert_eq!(event_user.role, Roles::Promoter);
+
+    // Giving the user another role on the event replaces the first
+    let updated_event_user =
+        EventUser::create(event.id, user.id, Roles::DoorPerson, connection).unwrap();
+    assert_eq!(updated_event_user.id, event_user.id);
+    assert_eq!(updated_event_user.role, Roles::DoorPerson);
+}
+
+#[test]
+fn create_with_organization_role() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().finish();
+    let user = project.create_user().finish();
+
+    let result = EventUser::create(event.id, user.id, Roles::OrgOwner, connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ErrorCode::ValidationError { errors } => {
+                assert!(errors.contains_key("role"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn destroy() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().finish();
+    let user = project.create_user().finish();
+    let event_user = EventUser::create(event.id, user.id, Roles::Promoter, connection).unwrap();
+
+    event_user.destroy(connection).unwrap();
+    assert!(EventUser::find_by_event_id_user_id(event.id, user.id, connection).is_err());
+}
+
+#[test]
+fn find_for_event() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().finish();
+    let event2 = project.create_event().finish();
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    let event_user = EventUser::create(event.id, user.id, Roles::Promoter, connection).unwrap();
+    EventUser::create(event2.id, user2.id, Roles::Promoter, connection).unwrap();
+
+    assert_eq!(
+        EventUser::find_for_event(event.id, connection).unwrap(),
+        vec![(event_user, user)]
+    );
+}
+
+#[test]
+fn find_for_user() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().finish();
+    let event2 = project.create_event().finish();
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    let event_user = EventUser::create(event.id, user.id, Roles::Promoter, connection).unwrap();
+    EventUser::create(event2.id, user2.id, Roles::Promoter, connection).unwrap();
+
+    assert_eq!(
+        EventUser::find_for_user(user.id, connection).unwrap(),
+        vec![event_user]
+    );
+    assert!(
+        EventUser::find_for_user(project.create_user().finish().id, connection)
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn get_scopes_for_user() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project.create_event().finish();
+    let event2 = project.create_event().finish();
+    let user = project.create_user().finish();
+    EventUser::create(event.id, user.id, Roles::Promoter, connection).unwrap();
+
+    let event_scopes = EventUser::get_scopes_for_user(event.id, user.id, connection).unwrap();
+    assert!(event_scopes.contains(&Scopes::EventViewGuests));
+    assert!(event_scopes.contains(&Scopes::HoldWrite));
+    assert!(!event_scopes.contains(&Scopes::OrgRead));
+    assert!(
+        EventUser::get_scopes_for_user(event2.id, user.id, connection)
+            .unwrap()
+            .is_empty()
+    );
+}
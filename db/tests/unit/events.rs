@@ -1,5 +1,6 @@
 use bigneon_db::dev::TestProject;
 use bigneon_db::models::*;
+use bigneon_db::utils::errors::ErrorCode;
 use chrono::prelude::*;
 use chrono::Duration;
 use uuid::Uuid;
@@ -25,6 +26,76 @@ fn create() {
     assert_eq!(event.id.to_string().is_empty(), false);
 }
 
+#[test]
+fn create_uses_organization_currency() {
+    let project = TestProject::new();
+    let organization = project.create_organization().with_currency("ZAR").finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .finish();
+    assert_eq!(event.currency, "ZAR");
+
+    let event = Event::create(
+        "Euro event",
+        EventStatus::Draft,
+        organization.id,
+        None,
+        None,
+        None,
+        None,
+        None,
+    );
+    let event = NewEvent {
+        currency: Some("EUR".to_string()),
+        ..event
+    }
+    .commit(project.get_connection())
+    .unwrap();
+    assert_eq!(event.currency, "EUR");
+}
+
+#[test]
+fn update_currency() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project
+        .create_event()
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    assert_eq!(event.currency, "USD");
+
+    let parameters = EventEditableAttributes {
+        currency: Some("ZAR".to_string()),
+        ..Default::default()
+    };
+    let event = event.update(parameters, connection).unwrap();
+    assert_eq!(event.currency, "ZAR");
+
+    // Currency is locked once tickets have been added to a cart
+    project
+        .create_order()
+        .for_event(&event)
+        .quantity(1)
+        .finish();
+    let parameters = EventEditableAttributes {
+        currency: Some("EUR".to_string()),
+        ..Default::default()
+    };
+    let result = event.update(parameters, connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ErrorCode::ValidationError { errors } => {
+                assert!(errors.contains_key("currency"));
+                assert_eq!(errors["currency"][0].code, "currency_locked");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
 #[test]
 fn update() {
     //create event
@@ -362,7 +433,7 @@ fn get_sales_by_date_range() {
     )
     .unwrap();
     assert_eq!(cart.calculate_total(connection).unwrap(), 1700);
-    cart.add_external_payment(Some("test".to_string()), user.id, 1700, connection)
+    cart.add_external_payment(Some("test".to_string()), user.id, 1700, "USD", connection)
         .unwrap();
     assert_eq!(cart.status, OrderStatus::Paid);
 
@@ -486,6 +557,43 @@ fn find_individuals() {
     assert_eq!(found_event_via_venue[0], event);
 }
 
+#[test]
+fn find_all_active_events_for_organization() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_event_start(NaiveDate::from_ymd(2019, 3, 5).and_hms(12, 0, 0))
+        .with_organization(&organization)
+        .finish();
+    let event2 = project
+        .create_event()
+        .with_event_start(NaiveDate::from_ymd(2019, 3, 4).and_hms(12, 0, 0))
+        .with_organization(&organization)
+        .finish();
+    let cancelled_event = project
+        .create_event()
+        .with_organization(&organization)
+        .finish();
+    cancelled_event.cancel(connection).unwrap();
+    project
+        .create_event()
+        .with_status(EventStatus::Draft)
+        .with_organization(&organization)
+        .finish();
+    project
+        .create_event()
+        .with_publish_date(NaiveDate::from_ymd(2099, 1, 1).and_hms(12, 0, 0))
+        .with_organization(&organization)
+        .finish();
+    project.create_event().finish();
+
+    let found_events =
+        Event::find_all_active_events_for_organization(&organization.id, connection).unwrap();
+    assert_eq!(found_events, vec![event2, event]);
+}
+
 #[test]
 fn find_all_events_for_organization() {
     let project = TestProject::new();
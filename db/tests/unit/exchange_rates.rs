@@ -0,0 +1,149 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::utils::errors::ErrorCode;
+use chrono::prelude::*;
+use time::Duration;
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let exchange_rate = ExchangeRate::create("USD", "ZAR", "14.5".parse().unwrap(), None)
+        .commit(connection)
+        .unwrap();
+
+    assert_eq!(exchange_rate.base_currency, "USD");
+    assert_eq!(exchange_rate.target_currency, "ZAR");
+    assert_eq!(exchange_rate.rate, "14.5".parse::<BigDecimal>().unwrap());
+}
+
+#[test]
+fn create_with_validation_errors() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let result =
+        ExchangeRate::create("usd", "ZAR", "0.0".parse().unwrap(), None).commit(connection);
+
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ErrorCode::ValidationError { errors } => {
+                assert!(errors.contains_key("base_currency"));
+                assert_eq!(errors["base_currency"][0].code, "currency");
+                assert!(errors.contains_key("rate"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn find_effective_rate() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let now = Utc::now().naive_utc();
+    ExchangeRate::create(
+        "USD",
+        "ZAR",
+        "13.0".parse().unwrap(),
+        Some(now - Duration::days(10)),
+    )
+    .commit(connection)
+    .unwrap();
+    ExchangeRate::create(
+        "USD",
+        "ZAR",
+        "14.0".parse().unwrap(),
+        Some(now - Duration::days(1)),
+    )
+    .commit(connection)
+    .unwrap();
+
+    // Latest rate
+    assert_eq!(
+        ExchangeRate::find_effective_rate("USD", "ZAR", now, connection),
+        Ok("14".parse::<BigDecimal>().unwrap())
+    );
+    // Historic rate
+    assert_eq!(
+        ExchangeRate::find_effective_rate("USD", "ZAR", now - Duration::days(5), connection),
+        Ok("13".parse::<BigDecimal>().unwrap())
+    );
+    // Inverse rate
+    assert_eq!(
+        ExchangeRate::find_effective_rate("ZAR", "USD", now, connection),
+        Ok("1".parse::<BigDecimal>().unwrap() / "14".parse::<BigDecimal>().unwrap())
+    );
+    // Same currency
+    assert_eq!(
+        ExchangeRate::find_effective_rate("EUR", "EUR", now, connection),
+        Ok("1".parse::<BigDecimal>().unwrap())
+    );
+    // No rate available
+    assert!(ExchangeRate::find_effective_rate("USD", "EUR", now, connection).is_err());
+    assert!(
+        ExchangeRate::find_effective_rate("USD", "ZAR", now - Duration::days(20), connection)
+            .is_err()
+    );
+}
+
+#[test]
+fn convert() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let now = Utc::now().naive_utc();
+    ExchangeRate::create(
+        "USD",
+        "ZAR",
+        "14.5".parse().unwrap(),
+        Some(now - Duration::days(1)),
+    )
+    .commit(connection)
+    .unwrap();
+
+    assert_eq!(
+        ExchangeRate::convert(1000, "USD", "ZAR", now, connection),
+        Ok(14500)
+    );
+    assert_eq!(
+        ExchangeRate::convert(14500, "ZAR", "USD", now, connection),
+        Ok(1000)
+    );
+}
+
+#[test]
+fn convert_with_precise_rate() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let now = Utc::now().naive_utc();
+    let exchange_rate = ExchangeRate::create(
+        "USD",
+        "JPY",
+        "110.123456789".parse().unwrap(),
+        Some(now - Duration::days(1)),
+    )
+    .commit(connection)
+    .unwrap();
+    // Rates are stored without losing precision
+    assert_eq!(
+        exchange_rate.rate,
+        "110.123456789".parse::<BigDecimal>().unwrap()
+    );
+    assert_eq!(
+        ExchangeRate::find(exchange_rate.id, connection)
+            .unwrap()
+            .rate,
+        "110.123456789".parse::<BigDecimal>().unwrap()
+    );
+
+    // 1000000 * 110.123456789 = 110123456.789
+    assert_eq!(
+        ExchangeRate::convert(1_000_000, "USD", "JPY", now, connection),
+        Ok(110_123_457)
+    );
+    // 1000 * 110.123456789 = 110123.456789
+    assert_eq!(
+        ExchangeRate::convert(1000, "USD", "JPY", now, connection),
+        Ok(110_123)
+    );
+}
@@ -0,0 +1,74 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::utils::errors::ErrorCode;
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let author = project.create_user().finish();
+    let organization = project.create_organization().finish();
+
+    let fan_note = FanNote::create(
+        organization.id,
+        user.id,
+        author.id,
+        "Asked about accessible seating",
+    )
+    .commit(connection)
+    .unwrap();
+    assert_eq!(fan_note.organization_id, organization.id);
+    assert_eq!(fan_note.user_id, user.id);
+    assert_eq!(fan_note.author_id, author.id);
+    assert_eq!(fan_note.note, "Asked about accessible seating".to_string());
+
+    match FanNote::create(organization.id, user.id, author.id, "").commit(connection) {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ErrorCode::ValidationError { errors } => {
+                assert_eq!(errors["note"][0].code, "length");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn find_for_fan() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    let author = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let organization2 = project.create_organization().finish();
+    let fan_note = FanNote::create(organization.id, user.id, author.id, "First note")
+        .commit(connection)
+        .unwrap();
+    FanNote::create(organization.id, user2.id, author.id, "Other fan")
+        .commit(connection)
+        .unwrap();
+    FanNote::create(organization2.id, user.id, author.id, "Other organization")
+        .commit(connection)
+        .unwrap();
+
+    assert_eq!(
+        FanNote::find_for_fan(organization.id, user.id, connection).unwrap(),
+        vec![fan_note]
+    );
+}
+
+#[test]
+fn destroy() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let fan_note = FanNote::create(organization.id, user.id, user.id, "Note")
+        .commit(connection)
+        .unwrap();
+
+    assert_eq!(fan_note.clone().destroy(connection).unwrap(), 1);
+    assert!(FanNote::find(fan_note.id, connection).is_err());
+}
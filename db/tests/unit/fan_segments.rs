@@ -0,0 +1,292 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::utils::errors::ErrorCode;
+use serde_json;
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let criteria = FanSegmentCriteria {
+        min_event_count: Some(2),
+        tags: vec!["vip".to_string()],
+        ..Default::default()
+    };
+
+    let fan_segment = FanSegment::create(organization.id, "Regulars", &criteria, user.id)
+        .commit(connection)
+        .unwrap();
+    assert_eq!(fan_segment.organization_id, organization.id);
+    assert_eq!(fan_segment.name, "Regulars".to_string());
+    assert_eq!(fan_segment.created_by_user_id, user.id);
+    assert_eq!(fan_segment.criteria().unwrap(), criteria);
+}
+
+#[test]
+fn create_with_validation_errors() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let criteria = FanSegmentCriteria {
+        min_revenue_in_cents: Some(-100),
+        ..Default::default()
+    };
+
+    match FanSegment::create(organization.id, "", &criteria, user.id).commit(connection) {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ErrorCode::ValidationError { errors } => {
+                assert_eq!(errors["name"][0].code, "length");
+                assert_eq!(errors["criteria"][0].code, "invalid_criteria");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn update() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let fan_segment = FanSegment::create(
+        organization.id,
+        "Regulars",
+        &FanSegmentCriteria::default(),
+        user.id,
+    )
+    .commit(connection)
+    .unwrap();
+
+    let criteria = FanSegmentCriteria {
+        genre: Some("Jazz".to_string()),
+        ..Default::default()
+    };
+    let fan_segment = fan_segment
+        .update(
+            FanSegmentEditableAttributes {
+                name: Some("Jazz fans".to_string()),
+                criteria: Some(serde_json::to_value(&criteria).unwrap()),
+            },
+            connection,
+        )
+        .unwrap();
+    assert_eq!(fan_segment.name, "Jazz fans".to_string());
+    assert_eq!(fan_segment.criteria().unwrap(), criteria);
+
+    let result = fan_segment.update(
+        FanSegmentEditableAttributes {
+            criteria: Some(json!({"min_event_count": "many"})),
+            ..Default::default()
+        },
+        connection,
+    );
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ErrorCode::ValidationError { errors } => {
+                assert_eq!(errors["criteria"][0].code, "invalid_criteria");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn find_for_organization() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let organization2 = project.create_organization().finish();
+    let criteria = FanSegmentCriteria::default();
+    let fan_segment = FanSegment::create(organization.id, "Regulars", &criteria, user.id)
+        .commit(connection)
+        .unwrap();
+    let fan_segment2 = FanSegment::create(organization.id, "Big spenders", &criteria, user.id)
+        .commit(connection)
+        .unwrap();
+    FanSegment::create(organization2.id, "Regulars", &criteria, user.id)
+        .commit(connection)
+        .unwrap();
+
+    assert_eq!(
+        FanSegment::find_for_organization(organization.id, connection).unwrap(),
+        vec![fan_segment2, fan_segment]
+    );
+}
+
+#[test]
+fn destroy() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let fan_segment = FanSegment::create(
+        organization.id,
+        "Regulars",
+        &FanSegmentCriteria::default(),
+        user.id,
+    )
+    .commit(connection)
+    .unwrap();
+
+    assert_eq!(fan_segment.clone().destroy(connection).unwrap(), 1);
+    assert!(FanSegment::find(fan_segment.id, connection).is_err());
+}
+
+#[test]
+fn members() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let jazz_event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let jazz_event = jazz_event
+        .update(
+            EventEditableAttributes {
+                genres: Some(vec!["Jazz".to_string()]),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+    let rock_event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let other_event = project.create_event().with_ticket_pricing().finish();
+
+    let regular = project.create_user().finish();
+    let jazz_fan = project.create_user().finish();
+    let other_fan = project.create_user().finish();
+    let unpaid_fan = project.create_user().finish();
+    project
+        .create_order()
+        .for_event(&jazz_event)
+        .for_user(&regular)
+        .is_paid()
+        .finish();
+    project
+        .create_order()
+        .for_event(&rock_event)
+        .for_user(&regular)
+        .is_paid()
+        .finish();
+    project
+        .create_order()
+        .for_event(&jazz_event)
+        .for_user(&jazz_fan)
+        .quantity(10)
+        .is_paid()
+        .finish();
+    project
+        .create_order()
+        .for_event(&other_event)
+        .for_user(&other_fan)
+        .is_paid()
+        .finish();
+    project
+        .create_order()
+        .for_event(&rock_event)
+        .for_user(&unpaid_fan)
+        .finish();
+    FanTag::create(organization.id, regular.id, "vip")
+        .commit(connection)
+        .unwrap();
+
+    let member_ids = |criteria: FanSegmentCriteria| {
+        let fan_segment = FanSegment::create(organization.id, "Segment", &criteria, regular.id)
+            .commit(connection)
+            .unwrap();
+        let mut ids: Vec<_> = fan_segment
+            .members(0, 100, connection)
+            .unwrap()
+            .data
+            .into_iter()
+            .map(|f| f.user_id)
+            .collect();
+        ids.sort();
+        ids
+    };
+    let mut expected = vec![regular.id, jazz_fan.id];
+    expected.sort();
+
+    // Only paid purchases from the organization's events count
+    assert_eq!(member_ids(FanSegmentCriteria::default()), expected);
+    assert_eq!(
+        member_ids(FanSegmentCriteria {
+            min_event_count: Some(2),
+            ..Default::default()
+        }),
+        vec![regular.id]
+    );
+    assert_eq!(
+        member_ids(FanSegmentCriteria {
+            genre: Some("jazz".to_string()),
+            ..Default::default()
+        }),
+        expected
+    );
+    assert_eq!(
+        member_ids(FanSegmentCriteria {
+            genre: Some("Country".to_string()),
+            ..Default::default()
+        }),
+        vec![]
+    );
+    assert_eq!(
+        member_ids(FanSegmentCriteria {
+            tags: vec!["VIP".to_string()],
+            ..Default::default()
+        }),
+        vec![regular.id]
+    );
+    assert_eq!(
+        member_ids(FanSegmentCriteria {
+            purchased_within_days: Some(30),
+            ..Default::default()
+        }),
+        expected
+    );
+
+    let fan_segment = FanSegment::create(
+        organization.id,
+        "Everyone",
+        &FanSegmentCriteria::default(),
+        regular.id,
+    )
+    .commit(connection)
+    .unwrap();
+    let jazz_fan_revenue = fan_segment
+        .members(0, 100, connection)
+        .unwrap()
+        .data
+        .into_iter()
+        .find(|f| f.user_id == jazz_fan.id)
+        .unwrap()
+        .revenue_in_cents;
+    assert_eq!(
+        member_ids(FanSegmentCriteria {
+            min_revenue_in_cents: jazz_fan_revenue,
+            ..Default::default()
+        }),
+        vec![jazz_fan.id]
+    );
+
+    let members = fan_segment.members(0, 1, connection).unwrap();
+    assert_eq!(members.data.len(), 1);
+    assert_eq!(members.paging.total, 2);
+    assert_eq!(fan_segment.member_count(connection).unwrap(), 2);
+    assert!(fan_segment.has_member(regular.id, connection).unwrap());
+    assert!(!fan_segment.has_member(other_fan.id, connection).unwrap());
+    assert!(!fan_segment.has_member(unpaid_fan.id, connection).unwrap());
+}
@@ -0,0 +1,98 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::utils::errors::ErrorCode;
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+
+    let fan_tag = FanTag::create(organization.id, user.id, " VIP ")
+        .commit(connection)
+        .unwrap();
+    assert_eq!(fan_tag.organization_id, organization.id);
+    assert_eq!(fan_tag.user_id, user.id);
+    assert_eq!(fan_tag.tag, "vip".to_string());
+
+    // Adding the same tag again returns the existing tag
+    let duplicate_tag = FanTag::create(organization.id, user.id, "vip")
+        .commit(connection)
+        .unwrap();
+    assert_eq!(duplicate_tag, fan_tag);
+    assert_eq!(
+        FanTag::find_for_fan(organization.id, user.id, connection)
+            .unwrap()
+            .len(),
+        1
+    );
+
+    match FanTag::create(organization.id, user.id, "  ").commit(connection) {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ErrorCode::ValidationError { errors } => {
+                assert_eq!(errors["tag"][0].code, "Tag is required");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn find_tags_for_organization() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let organization2 = project.create_organization().finish();
+    FanTag::create(organization.id, user.id, "vip")
+        .commit(connection)
+        .unwrap();
+    FanTag::create(organization.id, user.id, "local")
+        .commit(connection)
+        .unwrap();
+    FanTag::create(organization.id, user2.id, "vip")
+        .commit(connection)
+        .unwrap();
+    FanTag::create(organization2.id, user.id, "press")
+        .commit(connection)
+        .unwrap();
+
+    assert_eq!(
+        FanTag::find_tags_for_organization(organization.id, connection).unwrap(),
+        vec!["local".to_string(), "vip".to_string()]
+    );
+    assert_eq!(
+        FanTag::find_tags_for_organization(organization2.id, connection).unwrap(),
+        vec!["press".to_string()]
+    );
+}
+
+#[test]
+fn remove() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    FanTag::create(organization.id, user.id, "vip")
+        .commit(connection)
+        .unwrap();
+    let local_tag = FanTag::create(organization.id, user.id, "local")
+        .commit(connection)
+        .unwrap();
+
+    assert_eq!(
+        FanTag::remove(organization.id, user.id, "VIP", connection).unwrap(),
+        1
+    );
+    assert_eq!(
+        FanTag::remove(organization.id, user.id, "vip", connection).unwrap(),
+        0
+    );
+    assert_eq!(
+        FanTag::find_for_fan(organization.id, user.id, connection).unwrap(),
+        vec![local_tag]
+    );
+}
@@ -1,5 +1,7 @@
 use bigneon_db::dev::TestProject;
-use bigneon_db::models::{FeeSchedule, FeeScheduleRange, NewFeeScheduleRange};
+use bigneon_db::models::{
+    CalculatedFees, DisplayFeeScheduleRange, FeeSchedule, FeeScheduleRange, NewFeeScheduleRange,
+};
 
 #[test]
 fn find() {
@@ -13,11 +15,13 @@ fn find() {
                 min_price_in_cents: 20,
                 company_fee_in_cents: 4,
                 client_fee_in_cents: 6,
+                ..Default::default()
             },
             NewFeeScheduleRange {
                 min_price_in_cents: 100,
                 company_fee_in_cents: 8,
                 client_fee_in_cents: 12,
+                ..Default::default()
             },
         ],
     )
@@ -32,3 +36,106 @@ fn find() {
         FeeScheduleRange::find(fee_schedule_range.id, project.get_connection()).unwrap();
     assert_eq!(found_fee_schedule_range, fee_schedule_range);
 }
+
+#[test]
+fn calculate_fees() {
+    let project = TestProject::new();
+    let creator = project.create_user().finish();
+
+    // 3% + $0.99, capped at $9, split evenly between company and client
+    let fee_schedule = FeeSchedule::create(
+        "percentage".to_string(),
+        vec![NewFeeScheduleRange {
+            min_price_in_cents: 0,
+            company_fee_in_cents: 49,
+            client_fee_in_cents: 50,
+            company_fee_in_basis_points: 150,
+            client_fee_in_basis_points: 150,
+            min_fee_in_cents: Some(150),
+            max_fee_in_cents: Some(900),
+        }],
+    )
+    .commit(creator.id, project.get_connection())
+    .unwrap();
+    let fee_schedule_range = &fee_schedule.ranges(project.get_connection()).unwrap()[0];
+
+    // Percentage component
+    assert_eq!(
+        fee_schedule_range.calculate_fees(10000),
+        CalculatedFees {
+            fee_in_cents: 399,
+            company_fee_in_cents: 199,
+            client_fee_in_cents: 200,
+        }
+    );
+
+    // Capped
+    assert_eq!(
+        fee_schedule_range.calculate_fees(100000),
+        CalculatedFees {
+            fee_in_cents: 900,
+            company_fee_in_cents: 450,
+            client_fee_in_cents: 450,
+        }
+    );
+
+    // Minimum
+    let fees = fee_schedule_range.calculate_fees(100);
+    assert_eq!(fees.fee_in_cents, 150);
+    assert_eq!(
+        fees.company_fee_in_cents + fees.client_fee_in_cents,
+        fees.fee_in_cents
+    );
+}
+
+#[test]
+fn calculate_fees_fixed() {
+    let project = TestProject::new();
+    let creator = project.create_user().finish();
+    let fee_schedule = project.create_fee_schedule().finish(creator.id);
+    let fee_schedule_range = fee_schedule
+        .get_range(30, project.get_connection())
+        .unwrap();
+
+    assert_eq!(
+        fee_schedule_range.calculate_fees(30),
+        CalculatedFees {
+            fee_in_cents: fee_schedule_range.fee_in_cents,
+            company_fee_in_cents: fee_schedule_range.company_fee_in_cents,
+            client_fee_in_cents: fee_schedule_range.client_fee_in_cents,
+        }
+    );
+}
+
+#[test]
+fn display_fee_schedule_range() {
+    let project = TestProject::new();
+    let creator = project.create_user().finish();
+
+    let fee_schedule = FeeSchedule::create(
+        "percentage".to_string(),
+        vec![NewFeeScheduleRange {
+            min_price_in_cents: 0,
+            company_fee_in_cents: 49,
+            client_fee_in_cents: 50,
+            company_fee_in_basis_points: 100,
+            client_fee_in_basis_points: 200,
+            min_fee_in_cents: Some(150),
+            max_fee_in_cents: None,
+        }],
+    )
+    .commit(creator.id, project.get_connection())
+    .unwrap();
+    let fee_schedule_range = fee_schedule.ranges(project.get_connection()).unwrap()[0].clone();
+
+    let display_range: DisplayFeeScheduleRange = fee_schedule_range.clone().into();
+    assert_eq!(display_range.id, fee_schedule_range.id);
+    assert_eq!(display_range.fee_in_cents, 99);
+    assert_eq!(display_range.fee_in_basis_points, 300);
+    assert_eq!(display_range.company_fee_in_cents, 49);
+    assert_eq!(display_range.client_fee_in_cents, 50);
+    assert_eq!(display_range.company_fee_in_basis_points, 100);
+    assert_eq!(display_range.client_fee_in_basis_points, 200);
+    assert_eq!(display_range.min_fee_in_cents, Some(150));
+    assert_eq!(display_range.max_fee_in_cents, None);
+}
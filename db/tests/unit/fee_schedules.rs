@@ -1,5 +1,6 @@
 use bigneon_db::dev::TestProject;
 use bigneon_db::models::{FeeSchedule, NewFeeScheduleRange};
+use bigneon_db::utils::errors::ErrorCode::ValidationError;
 
 #[test]
 fn fee_schedule_create() {
@@ -13,11 +14,13 @@ fn fee_schedule_create() {
                 min_price_in_cents: 20,
                 company_fee_in_cents: 4,
                 client_fee_in_cents: 6,
+                ..Default::default()
             },
             NewFeeScheduleRange {
                 min_price_in_cents: 1000,
                 company_fee_in_cents: 40,
                 client_fee_in_cents: 60,
+                ..Default::default()
             },
         ],
     )
@@ -41,11 +44,13 @@ fn fee_schedule_create() {
                 min_price_in_cents: 20,
                 company_fee_in_cents: 4,
                 client_fee_in_cents: 6,
+                ..Default::default()
             },
             NewFeeScheduleRange {
                 min_price_in_cents: 1000,
                 company_fee_in_cents: 40,
                 client_fee_in_cents: 60,
+                ..Default::default()
             },
         ],
     )
@@ -67,11 +72,13 @@ fn get_fee_schedule_range() {
                 min_price_in_cents: 20,
                 company_fee_in_cents: 4,
                 client_fee_in_cents: 6,
+                ..Default::default()
             },
             NewFeeScheduleRange {
                 min_price_in_cents: 100,
                 company_fee_in_cents: 8,
                 client_fee_in_cents: 12,
+                ..Default::default()
             },
         ],
     )
@@ -90,3 +97,37 @@ fn get_fee_schedule_range() {
     assert_eq!(fee_schedule_range2.fee_in_cents, 20);
     assert!(fee_schedule_range3.is_err());
 }
+
+#[test]
+fn fee_schedule_create_with_invalid_fee_limits() {
+    let project = TestProject::new();
+    let creator = project.create_user().finish();
+
+    let result = FeeSchedule::create(
+        "default".to_string(),
+        vec![NewFeeScheduleRange {
+            min_price_in_cents: 20,
+            company_fee_in_cents: 4,
+            client_fee_in_cents: 6,
+            client_fee_in_basis_points: 300,
+            min_fee_in_cents: Some(500),
+            max_fee_in_cents: Some(100),
+            ..Default::default()
+        }],
+    )
+    .commit(creator.id, project.get_connection());
+
+    match result {
+        Ok(_) => {
+            panic!("Expected validation error");
+        }
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("ranges"));
+                assert_eq!(errors["ranges"].len(), 1);
+                assert_eq!(errors["ranges"][0].code, "min_fee_greater_than_max_fee");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
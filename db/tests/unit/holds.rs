@@ -288,7 +288,7 @@ fn remove_available_quantity() {
     )
     .unwrap();
     let total = cart.calculate_total(connection).unwrap();
-    cart.add_external_payment(Some("test".to_string()), user.id, total, connection)
+    cart.add_external_payment(Some("test".to_string()), user.id, total, "USD", connection)
         .unwrap();
 
     // Add additional cart item from existing unsold quantity (is removed from hold)
@@ -1,29 +1,50 @@
+pub mod affiliates;
 pub mod artists;
 pub mod assets;
+pub mod audit_logs;
+pub mod campaign_recipients;
+pub mod campaigns;
 pub mod codes;
+pub mod communication_preferences;
 pub mod comps;
 pub mod concerns;
 pub mod domain_actions;
 pub mod domain_events;
 pub mod event_artists;
 pub mod event_interest;
+pub mod event_split_partners;
+pub mod event_users;
 pub mod events;
+pub mod exchange_rates;
+pub mod fan_notes;
+pub mod fan_segments;
+pub mod fan_tags;
 pub mod fee_schedule_ranges;
 pub mod fee_schedules;
 pub mod holds;
 pub mod order_items;
 pub mod orders;
+pub mod organization_api_keys;
 pub mod organization_invites;
+pub mod organization_roles;
 pub mod organization_users;
 pub mod organizations;
 pub mod payment_methods;
+pub mod phone_verifications;
 pub mod push_notification_tokens;
+pub mod rate_limit_counters;
 pub mod refunded_tickets;
 pub mod regions;
+pub mod report_subscriptions;
+pub mod reports;
+pub mod settlements;
 pub mod stages;
+pub mod tax_rules;
 pub mod ticket_instances;
 pub mod ticket_pricing;
 pub mod ticket_type_codes;
 pub mod ticket_types;
+pub mod two_factor_authentications;
+pub mod user_sessions;
 pub mod users;
 pub mod venues;
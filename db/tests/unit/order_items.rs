@@ -282,7 +282,7 @@ fn update_with_validation_errors() {
     )
     .unwrap();
     let total = cart.calculate_total(connection).unwrap();
-    cart.add_external_payment(Some("Test".to_string()), user.id, total, connection)
+    cart.add_external_payment(Some("Test".to_string()), user.id, total, "USD", connection)
         .unwrap();
 
     // Max uses is 1 so second order for user should trigger validation error
@@ -350,7 +350,7 @@ fn details() {
     .unwrap();
 
     let total = cart.calculate_total(connection).unwrap();
-    cart.add_external_payment(Some("Test".to_string()), user.id, total, connection)
+    cart.add_external_payment(Some("Test".to_string()), user.id, total, "USD", connection)
         .unwrap();
 
     let items = cart.items(connection).unwrap();
@@ -527,7 +527,7 @@ fn refund() {
     .unwrap();
 
     let total = cart.calculate_total(connection).unwrap();
-    cart.add_external_payment(Some("Test".to_string()), user.id, total, connection)
+    cart.add_external_payment(Some("Test".to_string()), user.id, total, "USD", connection)
         .unwrap();
 
     let items = cart.items(&connection).unwrap();
@@ -634,6 +634,54 @@ fn organizations() {
     );
 }
 
+#[test]
+fn events() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let event = project
+        .create_event()
+        .with_event_start(NaiveDate::from_ymd(2019, 2, 1).and_hms(20, 0, 0))
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let event2 = project
+        .create_event()
+        .with_event_start(NaiveDate::from_ymd(2019, 1, 1).and_hms(20, 0, 0))
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    project
+        .create_event()
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let ticket_type2 = &event2.ticket_types(true, None, connection).unwrap()[0];
+    cart.update_quantities(
+        user.id,
+        &[
+            UpdateOrderItem {
+                ticket_type_id: ticket_type.id,
+                quantity: 2,
+                redemption_code: None,
+            },
+            UpdateOrderItem {
+                ticket_type_id: ticket_type2.id,
+                quantity: 2,
+                redemption_code: None,
+            },
+        ],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+
+    assert_eq!(cart.events(connection).unwrap(), vec![event2, event]);
+}
+
 #[test]
 fn payments() {
     let project = TestProject::new();
@@ -660,9 +708,9 @@ fn payments() {
     .unwrap();
     assert_eq!(cart.calculate_total(connection).unwrap(), 2000);
 
-    cart.add_external_payment(Some("Test".to_string()), user.id, 500, connection)
+    cart.add_external_payment(Some("Test".to_string()), user.id, 500, "USD", connection)
         .unwrap();
-    cart.add_external_payment(Some("Test2".to_string()), user.id, 1500, connection)
+    cart.add_external_payment(Some("Test2".to_string()), user.id, 1500, "USD", connection)
         .unwrap();
 
     let payments = cart.payments(connection).unwrap();
@@ -1068,6 +1116,98 @@ fn replace_tickets_with_code_pricing() {
     assert_eq!(order_item.calculate_quantity(connection), Ok(15));
 }
 
+#[test]
+fn replace_tickets_with_fan_segment_code() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let past_event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let fan = project.create_user().finish();
+    let user = project.create_user().finish();
+    project
+        .create_order()
+        .for_event(&past_event)
+        .for_user(&fan)
+        .is_paid()
+        .finish();
+    let fan_segment = FanSegment::create(
+        organization.id,
+        "Regulars",
+        &FanSegmentCriteria::default(),
+        fan.id,
+    )
+    .commit(connection)
+    .unwrap();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let code = project
+        .create_code()
+        .with_code_type(CodeTypes::Access)
+        .with_event(&event)
+        .for_ticket_type(&ticket_type)
+        .finish()
+        .update(
+            UpdateCodeAttributes {
+                fan_segment_id: Some(Some(fan_segment.id)),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+
+    // Only members of the segment can use the code
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    let result = cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 1,
+            redemption_code: Some(code.redemption_code.clone()),
+        }],
+        false,
+        false,
+        connection,
+    );
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("redemption_code"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    let mut cart = Order::find_or_create_cart(&fan, connection).unwrap();
+    cart.update_quantities(
+        fan.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 1,
+            redemption_code: Some(code.redemption_code.clone()),
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    let items = cart.items(connection).unwrap();
+    let order_item = items
+        .iter()
+        .find(|i| i.ticket_type_id == Some(ticket_type.id))
+        .unwrap();
+    assert_eq!(order_item.code_id, Some(code.id));
+}
+
 #[test]
 fn remove_tickets() {
     let project = TestProject::new();
@@ -1508,13 +1648,13 @@ fn add_external_payment() {
     assert!(cart.paid_at.is_none());
 
     // Partially paid
-    cart.add_external_payment(Some("test".to_string()), user.id, 1500, conn)
+    cart.add_external_payment(Some("test".to_string()), user.id, 1500, "USD", conn)
         .unwrap();
     assert_eq!(cart.status, OrderStatus::Draft);
     assert!(cart.paid_at.is_none());
 
     // Fully paid
-    cart.add_external_payment(Some("test2".to_string()), user.id, 500, conn)
+    cart.add_external_payment(Some("test2".to_string()), user.id, 500, "USD", conn)
         .unwrap();
     assert_eq!(cart.status, OrderStatus::Paid);
     assert!(cart.paid_at.is_some());
@@ -1577,7 +1717,7 @@ fn add_external_payment_for_expired_code() {
     .unwrap();
 
     // Attempting to pay triggers error
-    let result = cart.add_external_payment(Some("test".to_string()), user.id, 1000, conn);
+    let result = cart.add_external_payment(Some("test".to_string()), user.id, 1000, "USD", conn);
     match result {
         Ok(_) => {
             panic!("Expected validation error");
@@ -1606,6 +1746,7 @@ fn find_for_user_for_display() {
             Some("test".to_string()),
             user.id,
             2000,
+            "USD",
             project.get_connection(),
         )
         .unwrap();
@@ -1615,6 +1756,7 @@ fn find_for_user_for_display() {
             Some("test".to_string()),
             user.id,
             500,
+            "USD",
             project.get_connection(),
         )
         .unwrap();
@@ -1974,7 +2116,7 @@ fn for_display_with_invalid_items() {
 
     // Pay off cart
     let total = cart.calculate_total(connection).unwrap();
-    cart.add_external_payment(Some("Test".to_string()), user.id, total, connection)
+    cart.add_external_payment(Some("Test".to_string()), user.id, total, "USD", connection)
         .unwrap();
 
     // Paid order does not include valid_for_purchase
@@ -2246,3 +2388,487 @@ pub fn update() {
     // 1 order update event should be recorded from the update call
     assert_eq!(domain_event_count + 1, new_domain_event_count);
 }
+
+#[test]
+fn update_quantities_with_multiple_currencies() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_currency("ZAR").finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let event2 = project
+        .create_event()
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let ticket_type2 = &event2.ticket_types(true, None, connection).unwrap()[0];
+    cart.update_quantities(
+        user.id,
+        &vec![UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 2,
+            redemption_code: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(cart.currency, Some("ZAR".to_string()));
+    let items = cart.items(connection).unwrap();
+    assert!(items.iter().all(|i| i.currency == "ZAR"));
+
+    let result = cart.update_quantities(
+        user.id,
+        &vec![
+            UpdateOrderItem {
+                ticket_type_id: ticket_type.id,
+                quantity: 2,
+                redemption_code: None,
+            },
+            UpdateOrderItem {
+                ticket_type_id: ticket_type2.id,
+                quantity: 1,
+                redemption_code: None,
+            },
+        ],
+        false,
+        false,
+        connection,
+    );
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ValidationError { errors } => {
+                assert!(errors.contains_key("currency"));
+                assert_eq!(errors["currency"][0].code, "multiple_currencies");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn payment_currency() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().with_currency("ZAR").finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+
+    // Empty carts use the configured default currency
+    assert_eq!(cart.payment_currency("eur"), "EUR".to_string());
+
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    cart.update_quantities(
+        user.id,
+        &vec![UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 1,
+            redemption_code: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(cart.payment_currency("eur"), "ZAR".to_string());
+}
+
+#[test]
+fn update_fees_with_tax() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let creator = project.create_user().finish();
+    let region = project.create_region().finish();
+    let venue = project.create_venue().with_region(&region).finish();
+    let organization = project
+        .create_organization()
+        .with_event_fee()
+        .with_fee_schedule(&project.create_fee_schedule().finish(creator.id))
+        .finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_venue(&venue)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let tax_rule = TaxRule::create("Sales Tax", Some(region.id), None, 1000, false)
+        .commit(connection)
+        .unwrap();
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 2,
+            redemption_code: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+
+    let items = cart.items(connection).unwrap();
+    let order_item = items
+        .iter()
+        .find(|i| i.item_type == OrderItemTypes::Tickets)
+        .unwrap();
+    let tax_items = order_item.find_tax_items(connection).unwrap();
+    assert_eq!(tax_items.len(), 1);
+    let tax_item = &tax_items[0];
+    let expected_tax = tax_rule.calculate_tax(order_item.unit_price_in_cents);
+    assert_eq!(tax_item.tax_rule_id, Some(tax_rule.id));
+    assert_eq!(tax_item.quantity, 2);
+    assert_eq!(tax_item.tax_in_cents, expected_tax);
+    assert_eq!(tax_item.unit_price_in_cents, expected_tax);
+
+    // Fees are not taxed by default
+    let fee_item = order_item.find_fee_item(connection).unwrap().unwrap();
+    assert!(fee_item.find_tax_items(connection).unwrap().is_empty());
+    assert_eq!(
+        items
+            .iter()
+            .filter(|i| i.item_type == OrderItemTypes::Tax)
+            .count(),
+        1
+    );
+
+    // Changing quantities recalculates tax
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 3,
+            redemption_code: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+    let tax_items = order_item.find_tax_items(connection).unwrap();
+    assert_eq!(tax_items.len(), 1);
+    assert_eq!(tax_items[0].quantity, 3);
+
+    // Inclusive tax does not change the total
+    let total_with_exclusive_tax = cart.calculate_total(connection).unwrap();
+    tax_rule
+        .update(
+            TaxRuleEditableAttributes {
+                inclusive: Some(true),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+    cart.update_fees(connection).unwrap();
+    let tax_items = order_item.find_tax_items(connection).unwrap();
+    assert_eq!(tax_items[0].unit_price_in_cents, 0);
+    assert!(tax_items[0].tax_in_cents > 0);
+    assert_eq!(
+        cart.calculate_total(connection).unwrap(),
+        total_with_exclusive_tax - expected_tax * 3
+    );
+
+    // Removing the tickets removes the tax
+    cart.update_quantities(user.id, &[], false, true, connection)
+        .unwrap();
+    assert!(cart.items(connection).unwrap().is_empty());
+}
+
+#[test]
+fn refund_with_tax() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let creator = project.create_user().finish();
+    let region = project.create_region().finish();
+    let venue = project.create_venue().with_region(&region).finish();
+    let organization = project
+        .create_organization()
+        .with_fee_schedule(&project.create_fee_schedule().finish(creator.id))
+        .finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_venue(&venue)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    NewTaxRule {
+        applies_to_per_unit_fees: true,
+        ..TaxRule::create("Sales Tax", Some(region.id), None, 1000, false)
+    }
+    .commit(connection)
+    .unwrap();
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 2,
+            redemption_code: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+
+    let total = cart.calculate_total(connection).unwrap();
+    cart.add_external_payment(Some("Test".to_string()), user.id, total, "USD", connection)
+        .unwrap();
+
+    let items = cart.items(connection).unwrap();
+    let order_item = items
+        .iter()
+        .find(|i| i.item_type == OrderItemTypes::Tickets)
+        .unwrap();
+    let fee_item = order_item.find_fee_item(connection).unwrap().unwrap();
+    let ticket_tax_item = &order_item.find_tax_items(connection).unwrap()[0];
+    let fee_tax_item = &fee_item.find_tax_items(connection).unwrap()[0];
+    let tickets = TicketInstance::find_for_order_item(order_item.id, connection).unwrap();
+
+    let refund_items = vec![RefundItem {
+        order_item_id: order_item.id,
+        ticket_instance_id: Some(tickets[0].id),
+    }];
+    let refund_amount = order_item.unit_price_in_cents
+        + fee_item.unit_price_in_cents
+        + ticket_tax_item.unit_price_in_cents
+        + fee_tax_item.unit_price_in_cents;
+    assert_eq!(
+        cart.refund(refund_items, user.id, connection).unwrap(),
+        refund_amount as u32
+    );
+
+    // Tax is refunded for the refunded unit only
+    let ticket_tax_item = &order_item.find_tax_items(connection).unwrap()[0];
+    assert_eq!(ticket_tax_item.refunded_quantity, 1);
+    assert_eq!(ticket_tax_item.quantity, 2);
+    let fee_tax_item = &fee_item.find_tax_items(connection).unwrap()[0];
+    assert_eq!(fee_tax_item.refunded_quantity, 1);
+}
+
+#[test]
+fn update_fees_with_absorbed_fees() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let creator = project.create_user().finish();
+    let organization = project
+        .create_organization()
+        .with_event_fee()
+        .with_fee_schedule(&project.create_fee_schedule().finish(creator.id))
+        .finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let event = event
+        .update(
+            EventEditableAttributes {
+                fee_mode: Some(FeeModes::Absorbed),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 2,
+            redemption_code: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+
+    let items = cart.items(connection).unwrap();
+    let order_item = items
+        .iter()
+        .find(|i| i.item_type == OrderItemTypes::Tickets)
+        .unwrap();
+    let fee_item = order_item.find_fee_item(connection).unwrap().unwrap();
+    assert_eq!(fee_item.fee_mode, FeeModes::Absorbed);
+    assert_eq!(fee_item.unit_price_in_cents, 0);
+    let event_fee_item = items
+        .iter()
+        .find(|i| i.item_type == OrderItemTypes::EventFees)
+        .unwrap();
+    assert_eq!(event_fee_item.fee_mode, FeeModes::Absorbed);
+    assert_eq!(event_fee_item.unit_price_in_cents, 0);
+
+    // Buyer only pays for the tickets
+    assert_eq!(
+        cart.calculate_total(connection).unwrap(),
+        order_item.unit_price_in_cents * 2
+    );
+    let display_order = cart.for_display(None, connection).unwrap();
+    assert_eq!(display_order.items.len(), 1);
+    assert_eq!(display_order.items[0].item_type, OrderItemTypes::Tickets);
+}
+
+#[test]
+fn for_display_with_all_in_fees() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let creator = project.create_user().finish();
+    let organization = project
+        .create_organization()
+        .with_fee_schedule(&project.create_fee_schedule().finish(creator.id))
+        .finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let ticket_type = ticket_type
+        .update_fee_mode(Some(FeeModes::AllIn), connection)
+        .unwrap();
+    let user = project.create_user().finish();
+    let mut cart = Order::find_or_create_cart(&user, connection).unwrap();
+    cart.update_quantities(
+        user.id,
+        &[UpdateOrderItem {
+            ticket_type_id: ticket_type.id,
+            quantity: 2,
+            redemption_code: None,
+        }],
+        false,
+        false,
+        connection,
+    )
+    .unwrap();
+
+    let items = cart.items(connection).unwrap();
+    let order_item = items
+        .iter()
+        .find(|i| i.item_type == OrderItemTypes::Tickets)
+        .unwrap();
+    let fee_item = order_item.find_fee_item(connection).unwrap().unwrap();
+    assert_eq!(fee_item.fee_mode, FeeModes::AllIn);
+    assert!(fee_item.unit_price_in_cents > 0);
+
+    // Fees are folded into the ticket price
+    let display_order = cart.for_display(None, connection).unwrap();
+    assert_eq!(display_order.items.len(), 1);
+    let display_item = &display_order.items[0];
+    assert_eq!(display_item.id, order_item.id);
+    assert_eq!(
+        display_item.unit_price_in_cents,
+        order_item.unit_price_in_cents + fee_item.unit_price_in_cents
+    );
+    assert_eq!(
+        display_order.total_in_cents,
+        display_item.unit_price_in_cents * display_item.quantity
+    );
+}
+
+#[test]
+fn set_attribution() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let cart = Order::find_or_create_cart(&user, connection).unwrap();
+    assert!(cart.attribution().is_empty());
+
+    // Empty attribution is ignored
+    let cart = cart
+        .set_attribution(OrderAttribution::default(), connection)
+        .unwrap();
+    assert!(cart.attribution().is_empty());
+
+    let attribution = OrderAttribution {
+        referrer: Some("https://www.facebook.com/".to_string()),
+        utm_source: Some("facebook".to_string()),
+        utm_medium: Some("social".to_string()),
+        utm_campaign: Some("summer".to_string()),
+        source_id: Some("fb-summer".to_string()),
+        ..Default::default()
+    };
+    let cart = cart
+        .set_attribution(attribution.clone(), connection)
+        .unwrap();
+    assert_eq!(cart.attribution(), attribution);
+
+    // The first attribution is kept
+    let cart = cart
+        .set_attribution(
+            OrderAttribution {
+                utm_source: Some("newsletter".to_string()),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+    assert_eq!(cart.attribution(), attribution);
+    assert_eq!(
+        Order::find(cart.id, connection).unwrap().attribution(),
+        attribution
+    );
+}
+
+#[test]
+fn set_attribution_with_affiliate_click() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let affiliate = Affiliate::create(organization.id, "Promoter", None, None)
+        .commit(connection)
+        .unwrap();
+    let click = affiliate.record_click(connection).unwrap();
+
+    // Unknown clicks are ignored
+    let cart = Order::find_or_create_cart(&user, connection).unwrap();
+    let cart = cart
+        .set_attribution(
+            OrderAttribution {
+                affiliate_click_id: Some(Uuid::new_v4()),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+    assert!(cart.attribution().is_empty());
+
+    let cart = cart
+        .set_attribution(
+            OrderAttribution {
+                affiliate_click_id: Some(click.id),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+    assert_eq!(cart.affiliate_click_id, Some(click.id));
+}
@@ -0,0 +1,221 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::utils::errors::ErrorCode;
+use chrono::prelude::*;
+use chrono::Duration;
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .finish();
+
+    let (api_key, key) = OrganizationApiKey::create(
+        organization.id,
+        "Scanner",
+        vec![
+            Scopes::RedeemTicket,
+            Scopes::EventScan,
+            Scopes::RedeemTicket,
+        ],
+        vec![event.id],
+        None,
+        user.id,
+        connection,
+    )
+    .unwrap();
+
+    assert!(key.starts_with(API_KEY_PREFIX));
+    assert!(key.starts_with(&api_key.key_prefix));
+    assert_ne!(api_key.key_hash, key);
+    assert_eq!(
+        api_key.scopes,
+        vec!["event:scan".to_string(), "redeem:ticket".to_string()]
+    );
+    assert_eq!(api_key.event_ids, vec![event.id]);
+    assert_eq!(api_key.created_by_user_id, user.id);
+
+    // The key acts as its own service account
+    let service_user = User::find(api_key.user_id, connection).unwrap();
+    assert_eq!(service_user.first_name, Some("Scanner".to_string()));
+    assert_ne!(service_user.id, user.id);
+
+    assert_eq!(
+        OrganizationApiKey::find_for_organization(organization.id, connection).unwrap(),
+        vec![api_key]
+    );
+}
+
+#[test]
+fn create_with_invalid_data() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let other_event = project.create_event().finish();
+
+    let result = OrganizationApiKey::create(
+        organization.id,
+        "",
+        vec![Scopes::EventWrite],
+        vec![],
+        None,
+        user.id,
+        connection,
+    );
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ErrorCode::ValidationError { errors } => {
+                assert!(errors.contains_key("name"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    let result = OrganizationApiKey::create(
+        organization.id,
+        "Integration",
+        vec![Scopes::EventWrite],
+        vec![other_event.id],
+        Some(Utc::now().naive_utc() - Duration::days(1)),
+        user.id,
+        connection,
+    );
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ErrorCode::ValidationError { errors } => {
+                assert!(errors.contains_key("expires_at"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    let result = OrganizationApiKey::create(
+        organization.id,
+        "Integration",
+        vec![Scopes::EventWrite],
+        vec![other_event.id],
+        None,
+        user.id,
+        connection,
+    );
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ErrorCode::ValidationError { errors } => {
+                assert!(errors.contains_key("event_ids"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn find_active_by_key() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let (api_key, key) = OrganizationApiKey::create(
+        organization.id,
+        "Integration",
+        vec![Scopes::EventWrite],
+        vec![],
+        Some(Utc::now().naive_utc() + Duration::days(1)),
+        user.id,
+        connection,
+    )
+    .unwrap();
+
+    assert_eq!(
+        OrganizationApiKey::find_active_by_key(&key, connection).unwrap(),
+        api_key
+    );
+    assert!(OrganizationApiKey::find_active_by_key("bnk_unknown", connection).is_err());
+
+    // Revoked keys can no longer be used
+    let api_key = api_key.revoke(connection).unwrap();
+    assert!(api_key.revoked_at.is_some());
+    assert!(OrganizationApiKey::find_active_by_key(&key, connection).is_err());
+    assert!(
+        OrganizationApiKey::find_for_organization(organization.id, connection)
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn mark_used() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let (api_key, _) = OrganizationApiKey::create(
+        organization.id,
+        "Integration",
+        vec![Scopes::EventWrite],
+        vec![],
+        None,
+        user.id,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(api_key.last_used_at, None);
+
+    api_key.mark_used(connection).unwrap();
+    let api_key = OrganizationApiKey::find(api_key.id, connection).unwrap();
+    assert!(api_key.last_used_at.is_some());
+}
+
+#[test]
+fn allows() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let other_organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .finish();
+    let other_event = project
+        .create_event()
+        .with_organization(&organization)
+        .finish();
+
+    let (api_key, _) = OrganizationApiKey::create(
+        organization.id,
+        "Integration",
+        vec![Scopes::EventWrite],
+        vec![],
+        None,
+        user.id,
+        connection,
+    )
+    .unwrap();
+    assert!(api_key.allows(Scopes::EventWrite, organization.id, None));
+    assert!(api_key.allows(Scopes::EventWrite, organization.id, Some(event.id)));
+    assert!(!api_key.allows(Scopes::OrgWrite, organization.id, None));
+    assert!(!api_key.allows(Scopes::EventWrite, other_organization.id, None));
+
+    let (event_api_key, _) = OrganizationApiKey::create(
+        organization.id,
+        "Scanner",
+        vec![Scopes::RedeemTicket],
+        vec![event.id],
+        None,
+        user.id,
+        connection,
+    )
+    .unwrap();
+    assert!(event_api_key.allows(Scopes::RedeemTicket, organization.id, Some(event.id)));
+    assert!(!event_api_key.allows(Scopes::RedeemTicket, organization.id, Some(other_event.id)));
+    assert!(!event_api_key.allows(Scopes::RedeemTicket, organization.id, None));
+}
@@ -38,6 +38,7 @@ fn create_with_validation_errors() {
         &"invalid-email".to_string(),
         Some(user.id),
         vec![Roles::OrgMember],
+        Vec::new(),
     )
     .commit(project.get_connection());
 
@@ -0,0 +1,154 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::utils::errors::ErrorCode;
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+
+    let organization_role = OrganizationRole::create(
+        organization.id,
+        " Marketing ",
+        vec![Scopes::OrgFans, Scopes::OrgReports, Scopes::OrgFans],
+        connection,
+    )
+    .unwrap();
+
+    assert_eq!(organization_role.name, "Marketing".to_string());
+    assert_eq!(
+        organization_role.scopes,
+        vec!["org:fans".to_string(), "org:reports".to_string()]
+    );
+    assert_eq!(
+        organization_role.get_scopes(),
+        vec![Scopes::OrgFans, Scopes::OrgReports]
+    );
+    assert_eq!(
+        OrganizationRole::find_for_organization(organization.id, connection).unwrap(),
+        vec![organization_role]
+    );
+}
+
+#[test]
+fn create_with_invalid_data() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+
+    let result = OrganizationRole::create(organization.id, "", vec![Scopes::OrgFans], connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ErrorCode::ValidationError { errors } => {
+                assert!(errors.contains_key("name"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    // Roles can not grant access beyond the organization
+    let result = OrganizationRole::create(
+        organization.id,
+        "Finance",
+        vec![Scopes::OrgFinancialReports],
+        connection,
+    );
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ErrorCode::ValidationError { errors } => {
+                assert!(errors.contains_key("scopes"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn update() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let organization_role = OrganizationRole::create(
+        organization.id,
+        "Marketing",
+        vec![Scopes::OrgFans],
+        connection,
+    )
+    .unwrap();
+
+    let organization_role = organization_role
+        .update(
+            "Finance",
+            vec![Scopes::OrgReports, Scopes::SettlementRead],
+            connection,
+        )
+        .unwrap();
+    assert_eq!(organization_role.name, "Finance".to_string());
+    assert_eq!(
+        organization_role.get_scopes(),
+        vec![Scopes::OrgReports, Scopes::SettlementRead]
+    );
+}
+
+#[test]
+fn destroy() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let organization_role = OrganizationRole::create(
+        organization.id,
+        "Marketing",
+        vec![Scopes::OrgFans],
+        connection,
+    )
+    .unwrap();
+    organization
+        .add_user(user.id, vec![], vec![organization_role.id], connection)
+        .unwrap();
+    assert!(organization
+        .get_scopes_for_user(&user, connection)
+        .unwrap()
+        .contains(&Scopes::OrgFans));
+
+    organization_role.destroy(connection).unwrap();
+    assert!(OrganizationRole::find(organization_role.id, connection).is_err());
+    let organization_user =
+        OrganizationUser::find_by_user_id(user.id, organization.id, connection).unwrap();
+    assert!(organization_user.organization_role_ids.is_empty());
+    assert!(organization
+        .get_scopes_for_user(&user, connection)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn validate_ids_for_organization() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let other_organization = project.create_organization().finish();
+    let organization_role = OrganizationRole::create(
+        organization.id,
+        "Marketing",
+        vec![Scopes::OrgFans],
+        connection,
+    )
+    .unwrap();
+
+    assert!(OrganizationRole::validate_ids_for_organization(
+        &[organization_role.id, organization_role.id],
+        organization.id,
+        connection
+    )
+    .is_ok());
+    assert!(OrganizationRole::validate_ids_for_organization(
+        &[organization_role.id],
+        other_organization.id,
+        connection
+    )
+    .is_err());
+}
@@ -10,10 +10,14 @@ fn create() {
         .create_organization()
         .with_member(&user, Roles::OrgOwner)
         .finish();
-    let organization_user =
-        OrganizationUser::create(organization.id, user2.id, vec![Roles::OrgMember])
-            .commit(project.get_connection())
-            .unwrap();
+    let organization_user = OrganizationUser::create(
+        organization.id,
+        user2.id,
+        vec![Roles::OrgMember],
+        Vec::new(),
+    )
+    .commit(project.get_connection())
+    .unwrap();
 
     assert_eq!(organization_user.user_id, user2.id);
     assert_eq!(organization_user.organization_id, organization.id);
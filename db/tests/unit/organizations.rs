@@ -14,6 +14,7 @@ fn create() {
             min_price_in_cents: 0,
             company_fee_in_cents: 0,
             client_fee_in_cents: 0,
+            ..Default::default()
         }],
     )
     .commit(creator.id, connection)
@@ -213,7 +214,7 @@ fn has_fan() {
 
     // User checks out so has a paid order so relationship exists
     assert_eq!(cart.calculate_total(connection).unwrap(), 1700);
-    cart.add_external_payment(Some("test".to_string()), user.id, 1700, connection)
+    cart.add_external_payment(Some("test".to_string()), user.id, 1700, "USD", connection)
         .unwrap();
     assert_eq!(cart.status, OrderStatus::Paid);
     assert!(organization.has_fan(&user, connection).unwrap());
@@ -309,9 +310,14 @@ fn users() {
         .create_organization()
         .with_member(&user3, Roles::OrgOwner)
         .finish();
-    OrganizationUser::create(organization2.id, user2.id, vec![Roles::OrgMember])
-        .commit(project.get_connection())
-        .unwrap();
+    OrganizationUser::create(
+        organization2.id,
+        user2.id,
+        vec![Roles::OrgMember],
+        Vec::new(),
+    )
+    .commit(project.get_connection())
+    .unwrap();
 
     // Owner is included in the user results for organization2 but not organization2
     let user_results = organization.users(project.get_connection()).unwrap();
@@ -325,7 +331,7 @@ fn users() {
     );
 
     // Explicitly make the organization user an org user
-    OrganizationUser::create(organization.id, user.id, vec![Roles::OrgMember])
+    OrganizationUser::create(organization.id, user.id, vec![Roles::OrgMember], Vec::new())
         .commit(project.get_connection())
         .unwrap();
     let user_results = organization.users(project.get_connection()).unwrap();
@@ -337,9 +343,14 @@ fn users() {
     assert_eq!(user3.id, user_results2[1].1.id);
 
     // Add a new user to the organization
-    OrganizationUser::create(organization.id, user2.id, vec![Roles::OrgMember])
-        .commit(project.get_connection())
-        .unwrap();
+    OrganizationUser::create(
+        organization.id,
+        user2.id,
+        vec![Roles::OrgMember],
+        Vec::new(),
+    )
+    .commit(project.get_connection())
+    .unwrap();
     let user_results = organization.users(project.get_connection()).unwrap();
     assert_eq!(user_results.len(), 2);
     assert_eq!(user.id, user_results[0].1.id);
@@ -478,12 +489,22 @@ fn remove_users() {
         .create_organization()
         .with_member(&user, Roles::OrgOwner)
         .finish();
-    OrganizationUser::create(organization.id, user2.id, vec![Roles::OrgMember])
-        .commit(project.get_connection())
-        .unwrap();
-    OrganizationUser::create(organization.id, user3.id, vec![Roles::OrgMember])
-        .commit(project.get_connection())
-        .unwrap();
+    OrganizationUser::create(
+        organization.id,
+        user2.id,
+        vec![Roles::OrgMember],
+        Vec::new(),
+    )
+    .commit(project.get_connection())
+    .unwrap();
+    OrganizationUser::create(
+        organization.id,
+        user3.id,
+        vec![Roles::OrgMember],
+        Vec::new(),
+    )
+    .commit(project.get_connection())
+    .unwrap();
     let user2_id = user2.id;
 
     let mut user_results = organization.users(project.get_connection()).unwrap();
@@ -602,6 +623,7 @@ pub fn get_scopes_for_user() {
             "org:users",
             "org:write",
             "redeem:ticket",
+            "settlement:read",
             "ticket:admin",
             "ticket:read",
             "ticket:transfer",
@@ -751,6 +773,7 @@ pub fn get_scopes_for_user() {
             "org:users",
             "org:write",
             "redeem:ticket",
+            "settlement:read",
             "ticket:admin",
             "ticket:read",
             "ticket:transfer",
@@ -776,7 +799,7 @@ fn add_user() {
         .with_member(&user, Roles::OrgOwner)
         .finish();
     let organization_user = organization
-        .add_user(user2.id, vec![Roles::OrgMember], connection)
+        .add_user(user2.id, vec![Roles::OrgMember], Vec::new(), connection)
         .unwrap();
 
     assert_eq!(organization_user.user_id, user2.id);
@@ -789,6 +812,57 @@ fn add_user() {
         .contains(&Roles::OrgMember));
 }
 
+#[test]
+fn add_user_with_organization_roles() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let other_organization = project.create_organization().finish();
+    let organization_role = OrganizationRole::create(
+        organization.id,
+        "Marketing",
+        vec![Scopes::OrgFans],
+        connection,
+    )
+    .unwrap();
+
+    assert!(other_organization
+        .add_user(
+            user.id,
+            vec![Roles::OrgBoxOffice],
+            vec![organization_role.id],
+            connection
+        )
+        .is_err());
+
+    organization
+        .add_user(
+            user.id,
+            vec![Roles::OrgBoxOffice],
+            vec![organization_role.id],
+            connection,
+        )
+        .unwrap();
+    assert_eq!(
+        organization
+            .get_organization_roles_for_user(&user, connection)
+            .unwrap(),
+        vec![organization_role]
+    );
+    let scopes = organization.get_scopes_for_user(&user, connection).unwrap();
+    assert!(scopes.contains(&Scopes::OrgFans));
+    assert!(scopes.contains(&Scopes::BoxOfficeTicketRead));
+    assert!(!scopes.contains(&Scopes::OrderRefund));
+
+    // Replacing the user's roles also replaces their organization roles
+    organization
+        .add_user(user.id, vec![Roles::OrgBoxOffice], vec![], connection)
+        .unwrap();
+    let scopes = organization.get_scopes_for_user(&user, connection).unwrap();
+    assert!(!scopes.contains(&Scopes::OrgFans));
+}
+
 #[test]
 fn add_fee_schedule() {
     let project = TestProject::new();
@@ -837,7 +911,7 @@ fn search_fans() {
     .unwrap();
     let total = cart.calculate_total(connection).unwrap();
 
-    cart.add_external_payment(Some("test".to_string()), user.id, total, connection)
+    cart.add_external_payment(Some("test".to_string()), user.id, total, "USD", connection)
         .unwrap();
 
     let search_results = organization
@@ -0,0 +1,80 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+
+    let (phone_verification, code) = PhoneVerification::create(&user, connection).unwrap();
+    assert_eq!(Some(phone_verification.phone.clone()), user.phone);
+    assert_eq!(code.len(), PHONE_VERIFICATION_CODE_LENGTH);
+    assert!(code.chars().all(|c| c.is_digit(10)));
+    assert_ne!(phone_verification.code_hash, code);
+    assert!(phone_verification.is_usable());
+
+    // Asking again replaces the earlier code
+    let (new_phone_verification, _) = PhoneVerification::create(&user, connection).unwrap();
+    assert_eq!(
+        PhoneVerification::find_for_user(user.id, connection).unwrap(),
+        Some(new_phone_verification)
+    );
+}
+
+#[test]
+fn create_without_phone() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project
+        .create_user()
+        .finish()
+        .update(
+            &UserEditableAttributes {
+                phone: Some(None),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+
+    assert!(PhoneVerification::create(&user, connection).is_err());
+}
+
+#[test]
+fn verify() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let (phone_verification, code) = PhoneVerification::create(&user, connection).unwrap();
+
+    assert!(!phone_verification.verify("abc", connection).unwrap());
+    let phone_verification = PhoneVerification::find_for_user(user.id, connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(phone_verification.failed_attempts, 1);
+    assert!(!User::find(user.id, connection).unwrap().is_phone_verified());
+
+    assert!(phone_verification.verify(&code, connection).unwrap());
+    assert!(User::find(user.id, connection).unwrap().is_phone_verified());
+    assert!(PhoneVerification::find_for_user(user.id, connection)
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn verify_after_too_many_attempts() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let (phone_verification, code) = PhoneVerification::create(&user, connection).unwrap();
+
+    for _ in 0..5 {
+        assert!(!phone_verification.verify("abc", connection).unwrap());
+    }
+    let phone_verification = PhoneVerification::find_for_user(user.id, connection)
+        .unwrap()
+        .unwrap();
+    assert!(!phone_verification.is_usable());
+    assert!(!phone_verification.verify(&code, connection).unwrap());
+}
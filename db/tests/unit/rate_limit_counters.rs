@@ -0,0 +1,52 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+
+#[test]
+fn hit() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+
+    let counter = RateLimitCounter::hit("auth_token:ip:127.0.0.1", 60, connection).unwrap();
+    assert_eq!(counter.hits, 1);
+    assert_eq!(counter.retry_after(1), None);
+
+    let counter = RateLimitCounter::hit("auth_token:ip:127.0.0.1", 60, connection).unwrap();
+    assert_eq!(counter.hits, 2);
+    let retry_after = counter.retry_after(1).unwrap();
+    assert!(retry_after > 0 && retry_after <= 60);
+    assert_eq!(counter.retry_after(2), None);
+
+    // Other keys are counted separately
+    let counter = RateLimitCounter::hit("auth_token:ip:127.0.0.2", 60, connection).unwrap();
+    assert_eq!(counter.hits, 1);
+}
+
+#[test]
+fn hit_after_window_ends() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+
+    RateLimitCounter::hit("cart_checkout:user:1", 0, connection).unwrap();
+    let counter = RateLimitCounter::hit("cart_checkout:user:1", 0, connection).unwrap();
+    assert_eq!(counter.hits, 1);
+}
+
+#[test]
+fn delete_expired() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    RateLimitCounter::hit("cart_checkout:user:1", 0, connection).unwrap();
+    RateLimitCounter::hit("cart_checkout:user:2", 60, connection).unwrap();
+
+    assert!(RateLimitCounter::delete_expired(connection).unwrap() >= 1);
+    assert!(
+        RateLimitCounter::find_by_key("cart_checkout:user:1", connection)
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        RateLimitCounter::find_by_key("cart_checkout:user:2", connection)
+            .unwrap()
+            .is_some()
+    );
+}
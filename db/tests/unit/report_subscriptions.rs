@@ -0,0 +1,233 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::schema::{domain_actions, report_subscriptions};
+use bigneon_db::utils::errors::ErrorCode;
+use chrono::prelude::*;
+use chrono::Duration;
+use chrono_tz::Tz;
+use diesel;
+use diesel::prelude::*;
+
+fn new_subscription(organization: &Organization, user: &User) -> NewReportSubscription {
+    NewReportSubscription {
+        organization_id: organization.id,
+        user_id: user.id,
+        report_type: ReportTypes::TicketCount,
+        event_id: None,
+        frequency: ReportFrequencies::Weekly,
+        day_of_week: Some(2),
+        hour_of_day: 8,
+        timezone: "America/New_York".to_string(),
+        format: ExportFormats::Csv,
+    }
+}
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+
+    let subscription = new_subscription(&organization, &user)
+        .commit(connection)
+        .unwrap();
+
+    // Next delivery is on the next Wednesday at 8am New York time
+    let now = Utc::now().naive_utc();
+    assert!(subscription.next_delivery_at > now);
+    assert!(subscription.next_delivery_at <= now + Duration::days(7));
+    let tz: Tz = "America/New_York".parse().unwrap();
+    let local_time = tz.from_utc_datetime(&subscription.next_delivery_at);
+    assert_eq!(local_time.weekday(), Weekday::Wed);
+    assert_eq!(local_time.hour(), 8);
+    assert_eq!(local_time.minute(), 0);
+
+    let actions: Vec<DomainAction> = domain_actions::table
+        .filter(domain_actions::main_table_id.eq(subscription.id))
+        .load(connection)
+        .unwrap();
+    assert_eq!(actions.len(), 1);
+    assert_eq!(
+        actions[0].domain_action_type,
+        DomainActionTypes::SendScheduledReport
+    );
+    assert_eq!(actions[0].scheduled_at, subscription.next_delivery_at);
+}
+
+#[test]
+fn create_daily_ignores_day_of_week() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let mut subscription = new_subscription(&organization, &user);
+    subscription.frequency = ReportFrequencies::Daily;
+    subscription.timezone = "UTC".to_string();
+
+    let subscription = subscription.commit(connection).unwrap();
+    assert_eq!(subscription.day_of_week, None);
+    assert!(subscription.next_delivery_at <= Utc::now().naive_utc() + Duration::days(1));
+    assert_eq!(subscription.next_delivery_at.hour(), 8);
+}
+
+#[test]
+fn create_with_validation_errors() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let mut subscription = new_subscription(&organization, &user);
+    subscription.report_type = ReportTypes::EventSummary;
+    subscription.day_of_week = None;
+    subscription.hour_of_day = 24;
+    subscription.timezone = "Mars/Olympus_Mons".to_string();
+    subscription.format = ExportFormats::Pdf;
+
+    match subscription.commit(connection) {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ErrorCode::ValidationError { errors } => {
+                assert_eq!(errors["hour_of_day"][0].code, "hour_of_day_out_of_range");
+                assert_eq!(errors["day_of_week"][0].code, "day_of_week_required");
+                assert_eq!(errors["format"][0].code, "format_not_supported");
+                assert_eq!(errors["timezone"][0].code, "invalid_timezone");
+                assert_eq!(errors["event_id"][0].code, "event_required");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    let event = project.create_event().finish();
+    let mut subscription = new_subscription(&organization, &user);
+    subscription.event_id = Some(event.id);
+    match subscription.commit(connection) {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ErrorCode::ValidationError { errors } => {
+                assert_eq!(errors["event_id"][0].code, "event_not_in_organization");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn find_for_user() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let user2 = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let subscription = new_subscription(&organization, &user)
+        .commit(connection)
+        .unwrap();
+    let subscription2 = new_subscription(&organization, &user)
+        .commit(connection)
+        .unwrap();
+    new_subscription(&organization, &user2)
+        .commit(connection)
+        .unwrap();
+
+    let subscriptions =
+        ReportSubscription::find_for_user(organization.id, user.id, connection).unwrap();
+    assert_eq!(subscriptions.len(), 2);
+    assert!(subscriptions.contains(&subscription));
+    assert!(subscriptions.contains(&subscription2));
+
+    let subscription2 =
+        ReportSubscription::find_by_unsubscribe_token(subscription2.unsubscribe_token, connection)
+            .unwrap()
+            .unsubscribe(connection)
+            .unwrap();
+    assert!(subscription2.unsubscribed_at.is_some());
+    assert_eq!(
+        ReportSubscription::find_for_user(organization.id, user.id, connection).unwrap(),
+        vec![subscription]
+    );
+}
+
+#[test]
+fn complete_delivery() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let subscription = new_subscription(&organization, &user)
+        .commit(connection)
+        .unwrap();
+    let next_delivery_at = subscription.next_delivery_at;
+    // Deliveries missed for several weeks are skipped
+    let missed_delivery_at = next_delivery_at - Duration::days(21);
+    let subscription: ReportSubscription = diesel::update(&subscription)
+        .set(report_subscriptions::next_delivery_at.eq(missed_delivery_at))
+        .get_result(connection)
+        .unwrap();
+    assert_eq!(
+        subscription.reporting_period(),
+        (missed_delivery_at - Duration::days(7), missed_delivery_at)
+    );
+
+    let delivery = subscription
+        .clone()
+        .complete_delivery(ReportDeliveryStatus::Sent, None, connection)
+        .unwrap();
+    assert_eq!(delivery.status, ReportDeliveryStatus::Sent);
+    assert_eq!(delivery.period_end, missed_delivery_at);
+    assert_eq!(subscription.deliveries(connection).unwrap(), vec![delivery]);
+
+    let subscription = ReportSubscription::find(subscription.id, connection).unwrap();
+    assert_eq!(subscription.next_delivery_at, next_delivery_at);
+    let actions: Vec<DomainAction> = domain_actions::table
+        .filter(domain_actions::main_table_id.eq(subscription.id))
+        .load(connection)
+        .unwrap();
+    assert_eq!(actions.len(), 2);
+}
+
+#[test]
+fn schedule_overdue_deliveries() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let subscription = new_subscription(&organization, &user)
+        .commit(connection)
+        .unwrap();
+    let unsubscribed_subscription = new_subscription(&organization, &user)
+        .commit(connection)
+        .unwrap();
+
+    // Nothing is overdue while deliveries are in the future
+    assert!(ReportSubscription::schedule_overdue_deliveries(connection)
+        .unwrap()
+        .is_empty());
+
+    // The pending actions expired without the delivery being completed
+    let overdue_delivery_at = Utc::now().naive_utc() - Duration::days(3);
+    diesel::update(report_subscriptions::table)
+        .set(report_subscriptions::next_delivery_at.eq(overdue_delivery_at))
+        .execute(connection)
+        .unwrap();
+    diesel::update(domain_actions::table)
+        .set(domain_actions::expires_at.eq(overdue_delivery_at))
+        .execute(connection)
+        .unwrap();
+    unsubscribed_subscription.unsubscribe(connection).unwrap();
+
+    let scheduled = ReportSubscription::schedule_overdue_deliveries(connection).unwrap();
+    assert_eq!(scheduled.len(), 1);
+    assert_eq!(scheduled[0].id, subscription.id);
+    assert!(DomainAction::has_pending_action(
+        DomainActionTypes::SendScheduledReport,
+        Tables::ReportSubscriptions.to_string(),
+        subscription.id,
+        connection,
+    )
+    .unwrap());
+
+    // A pending delivery is not scheduled again
+    assert!(ReportSubscription::schedule_overdue_deliveries(connection)
+        .unwrap()
+        .is_empty());
+}
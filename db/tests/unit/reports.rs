@@ -0,0 +1,450 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::schema::affiliate_clicks;
+use chrono::prelude::*;
+use chrono::Duration;
+use diesel;
+use diesel::prelude::*;
+
+#[test]
+fn tax_liability_report() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let region = project.create_region().finish();
+    let venue = project.create_venue().with_region(&region).finish();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_venue(&venue)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let tax_rule = TaxRule::create("Sales Tax", Some(region.id), None, 1000, false)
+        .commit(connection)
+        .unwrap();
+
+    let order = project
+        .create_order()
+        .for_event(&event)
+        .quantity(2)
+        .is_paid()
+        .finish();
+    let order_item = order
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .find(|i| i.item_type == OrderItemTypes::Tickets)
+        .unwrap();
+    let tax_in_cents = tax_rule.calculate_tax(order_item.unit_price_in_cents);
+
+    let result = Report::tax_liability_report(organization.id, None, None, connection).unwrap();
+    assert_eq!(
+        result,
+        vec![TaxLiabilityRow {
+            tax_rule_id: tax_rule.id,
+            tax_rule_name: "Sales Tax".to_string(),
+            rate_in_basis_points: 1000,
+            inclusive: false,
+            currency: "USD".to_string(),
+            taxable_amount_in_cents: order_item.unit_price_in_cents * 2,
+            tax_charged_in_cents: tax_in_cents * 2,
+            tax_refunded_in_cents: 0,
+            tax_due_in_cents: tax_in_cents * 2,
+        }]
+    );
+
+    // Other organizations are not included
+    let organization2 = project.create_organization().finish();
+    assert!(
+        Report::tax_liability_report(organization2.id, None, None, connection)
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn currency_summary_report() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let order = project
+        .create_order()
+        .for_event(&event)
+        .quantity(2)
+        .is_paid()
+        .finish();
+    let total = order.calculate_total(connection).unwrap();
+    ExchangeRate::create("USD", "ZAR", "14.0".parse().unwrap(), None)
+        .commit(connection)
+        .unwrap();
+
+    let result = Report::currency_summary_report(
+        organization.id,
+        None,
+        None,
+        Some("ZAR".to_string()),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(result.rows.len(), 1);
+    assert_eq!(result.rows[0].currency, "USD");
+    assert_eq!(result.rows[0].order_count, 1);
+    assert_eq!(result.rows[0].ticket_count, 2);
+    assert_eq!(result.rows[0].gross_in_cents, total);
+    assert_eq!(result.target_currency, Some("ZAR".to_string()));
+    assert_eq!(result.converted_gross_in_cents, Some(total * 14));
+}
+
+#[test]
+fn summary_event_report_with_absorbed_fees() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let creator = project.create_user().finish();
+    let organization = project
+        .create_organization()
+        .with_fee_schedule(&project.create_fee_schedule().finish(creator.id))
+        .finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    ticket_type
+        .update_fee_mode(Some(FeeModes::Absorbed), connection)
+        .unwrap();
+    let order = project
+        .create_order()
+        .for_event(&event)
+        .quantity(2)
+        .is_paid()
+        .finish();
+    let order_item = order
+        .items(connection)
+        .unwrap()
+        .into_iter()
+        .find(|i| i.item_type == OrderItemTypes::Tickets)
+        .unwrap();
+
+    let result = Report::summary_event_report(event.id, None, None, connection).unwrap();
+    assert_eq!(result.sales.len(), 1);
+    let sales = &result.sales[0];
+    assert!(sales.total_absorbed_fee_in_cents > 0);
+    assert_eq!(
+        sales.total_absorbed_fee_in_cents,
+        sales.total_company_fee_in_cents
+    );
+    assert_eq!(sales.total_client_fee_in_cents, 0);
+    // Buyer only paid the ticket price
+    assert_eq!(
+        sales.total_gross_income_in_cents,
+        order_item.unit_price_in_cents * 2
+    );
+    assert_eq!(
+        result.ticket_fees[0].total_absorbed_fee_in_cents,
+        sales.total_absorbed_fee_in_cents
+    );
+}
+
+#[test]
+fn organization_summary_report_by_currency() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let event2 = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let event2 = event2
+        .update(
+            EventEditableAttributes {
+                currency: Some("ZAR".to_string()),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+    project.create_order().for_event(&event).is_paid().finish();
+    project.create_order().for_event(&event2).is_paid().finish();
+
+    let result =
+        Report::organization_summary_report(organization.id, None, None, connection).unwrap();
+    assert_eq!(result.len(), 2);
+    let usd_result = result.iter().find(|r| r.event_id == event.id).unwrap();
+    let zar_result = result.iter().find(|r| r.event_id == event2.id).unwrap();
+    assert_eq!(usd_result.currency, "USD");
+    assert!(usd_result.sales.iter().all(|r| r.currency == "USD"));
+    assert_eq!(zar_result.currency, "ZAR");
+    assert!(zar_result.sales.iter().all(|r| r.currency == "ZAR"));
+    assert!(zar_result.ticket_fees.iter().all(|r| r.currency == "ZAR"));
+
+    let event_result = Report::summary_event_report(event2.id, None, None, connection).unwrap();
+    assert_eq!(event_result.currency, "ZAR");
+}
+
+#[test]
+fn transaction_detail_report_page() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    for _ in 0..3 {
+        project.create_order().for_event(&event).is_paid().finish();
+    }
+
+    let all_rows =
+        Report::transaction_detail_report(None, Some(organization.id), None, None, connection)
+            .unwrap();
+    assert_eq!(all_rows.len(), 3);
+
+    let first_page = Report::transaction_detail_report_page(
+        None,
+        Some(organization.id),
+        None,
+        None,
+        0,
+        2,
+        connection,
+    )
+    .unwrap();
+    let second_page = Report::transaction_detail_report_page(
+        None,
+        Some(organization.id),
+        None,
+        None,
+        1,
+        2,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(first_page.len(), 2);
+    assert_eq!(second_page.len(), 1);
+
+    let mut order_ids: Vec<_> = first_page
+        .iter()
+        .chain(second_page.iter())
+        .map(|r| r.order_id)
+        .collect();
+    order_ids.dedup();
+    assert_eq!(order_ids.len(), 3);
+}
+
+#[test]
+fn sales_by_source_report() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let attribution = OrderAttribution {
+        utm_source: Some("facebook".to_string()),
+        utm_medium: Some("social".to_string()),
+        utm_campaign: Some("summer".to_string()),
+        ..Default::default()
+    };
+
+    let order = project
+        .create_order()
+        .for_event(&event)
+        .quantity(2)
+        .is_paid()
+        .finish()
+        .set_attribution(attribution.clone(), connection)
+        .unwrap();
+    project
+        .create_order()
+        .for_event(&event)
+        .quantity(1)
+        .finish()
+        .set_attribution(attribution, connection)
+        .unwrap();
+    let direct_order = project
+        .create_order()
+        .for_event(&event)
+        .quantity(3)
+        .is_paid()
+        .finish();
+    let revenue_in_cents = |order: &Order| -> i64 {
+        order
+            .items(connection)
+            .unwrap()
+            .iter()
+            .filter(|i| i.event_id == Some(event.id))
+            .map(|i| i.unit_price_in_cents * (i.quantity - i.refunded_quantity))
+            .sum()
+    };
+
+    let result =
+        Report::sales_by_source_report(organization.id, Some(event.id), None, None, connection)
+            .unwrap();
+    assert_eq!(result.len(), 2);
+    let facebook = result
+        .iter()
+        .find(|r| r.utm_source == Some("facebook".to_string()))
+        .unwrap();
+    assert_eq!(facebook.utm_campaign, Some("summer".to_string()));
+    assert_eq!(facebook.cart_count, 2);
+    assert_eq!(facebook.order_count, 1);
+    assert_eq!(facebook.conversion_rate_in_basis_points, 5000);
+    assert_eq!(facebook.ticket_count, 2);
+    assert_eq!(facebook.revenue_in_cents, revenue_in_cents(&order));
+
+    let direct = result.iter().find(|r| r.utm_source.is_none()).unwrap();
+    assert_eq!(direct.cart_count, 1);
+    assert_eq!(direct.order_count, 1);
+    assert_eq!(direct.conversion_rate_in_basis_points, 10000);
+    assert_eq!(direct.ticket_count, 3);
+    assert_eq!(direct.revenue_in_cents, revenue_in_cents(&direct_order));
+}
+
+#[test]
+fn affiliate_commission_report() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    let promoter = Affiliate::create(organization.id, "Promoter", None, None)
+        .commit(connection)
+        .unwrap();
+    AffiliateCommissionRule::create(promoter.id, None, CommissionTypes::Flat, 100, 0)
+        .commit(connection)
+        .unwrap();
+    let blogger = Affiliate::create(organization.id, "Blogger", None, None)
+        .commit(connection)
+        .unwrap();
+    AffiliateCommissionRule::create(blogger.id, None, CommissionTypes::Flat, 100, 0)
+        .commit(connection)
+        .unwrap();
+    // The ticket type rule takes precedence over the default rule
+    AffiliateCommissionRule::create(
+        blogger.id,
+        Some(ticket_type.id),
+        CommissionTypes::Percentage,
+        0,
+        1000,
+    )
+    .commit(connection)
+    .unwrap();
+
+    let attribute = |order: Order, click: &AffiliateClick| -> Order {
+        order
+            .set_attribution(
+                OrderAttribution {
+                    affiliate_click_id: Some(click.id),
+                    ..Default::default()
+                },
+                connection,
+            )
+            .unwrap()
+    };
+    let promoter_click = promoter.record_click(connection).unwrap();
+    attribute(
+        project
+            .create_order()
+            .for_event(&event)
+            .quantity(2)
+            .is_paid()
+            .finish(),
+        &promoter_click,
+    );
+    // Unpaid orders earn no commission
+    attribute(
+        project
+            .create_order()
+            .for_event(&event)
+            .quantity(1)
+            .finish(),
+        &promoter_click,
+    );
+    let blogger_order = attribute(
+        project
+            .create_order()
+            .for_event(&event)
+            .quantity(3)
+            .is_paid()
+            .finish(),
+        &blogger.record_click(connection).unwrap(),
+    );
+    // Orders paid after the attribution window are not attributed
+    let expired_click = blogger.record_click(connection).unwrap();
+    diesel::update(affiliate_clicks::table.filter(affiliate_clicks::id.eq(expired_click.id)))
+        .set(affiliate_clicks::created_at.eq(Utc::now().naive_utc() - Duration::days(31)))
+        .execute(connection)
+        .unwrap();
+    attribute(
+        project
+            .create_order()
+            .for_event(&event)
+            .quantity(4)
+            .is_paid()
+            .finish(),
+        &expired_click,
+    );
+    let unit_price_in_cents = blogger_order
+        .items(connection)
+        .unwrap()
+        .iter()
+        .find(|i| i.ticket_type_id == Some(ticket_type.id))
+        .unwrap()
+        .unit_price_in_cents;
+
+    let result =
+        Report::affiliate_commission_report(organization.id, None, None, None, connection).unwrap();
+    assert_eq!(result.len(), 2);
+    assert_eq!(result[0].affiliate_id, blogger.id);
+    assert_eq!(result[0].ticket_type_id, ticket_type.id);
+    assert_eq!(result[0].order_count, 1);
+    assert_eq!(result[0].ticket_count, 3);
+    assert_eq!(result[0].revenue_in_cents, unit_price_in_cents * 3);
+    assert_eq!(
+        result[0].commission_in_cents,
+        (unit_price_in_cents as f64 * 0.1).round() as i64 * 3
+    );
+    assert_eq!(result[1].affiliate_id, promoter.id);
+    assert_eq!(result[1].order_count, 1);
+    assert_eq!(result[1].ticket_count, 2);
+    assert_eq!(result[1].revenue_in_cents, unit_price_in_cents * 2);
+    assert_eq!(result[1].commission_in_cents, 200);
+
+    let result = Report::affiliate_commission_report(
+        organization.id,
+        Some(promoter.id),
+        None,
+        None,
+        connection,
+    )
+    .unwrap();
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0].affiliate_id, promoter.id);
+}
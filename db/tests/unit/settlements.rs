@@ -0,0 +1,367 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::utils::errors::ErrorCode;
+use chrono::prelude::*;
+use chrono::Duration;
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project
+        .create_organization()
+        .with_fee_schedule(&project.create_fee_schedule().finish(user.id))
+        .finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let order = project
+        .create_order()
+        .for_event(&event)
+        .quantity(2)
+        .is_paid()
+        .finish();
+    let items = order.items(connection).unwrap();
+    let ticket_item = items
+        .iter()
+        .find(|i| i.item_type == OrderItemTypes::Tickets)
+        .unwrap();
+    let fees_in_cents: i64 = items
+        .iter()
+        .filter(|i| i.item_type != OrderItemTypes::Tickets)
+        .map(|i| i.unit_price_in_cents * i.quantity)
+        .sum();
+
+    let settlement = Settlement::create(organization.id, Some(event.id), None, None, user.id)
+        .commit(connection)
+        .unwrap();
+
+    assert_eq!(settlement.status, SettlementStatus::Draft);
+    assert_eq!(settlement.currency, event.currency);
+    assert_eq!(
+        settlement.ticket_sales_in_cents,
+        ticket_item.unit_price_in_cents * 2
+    );
+    assert!(settlement.company_fees_in_cents > 0);
+    assert_eq!(
+        settlement.client_fees_in_cents + settlement.company_fees_in_cents,
+        fees_in_cents
+    );
+    assert_eq!(settlement.absorbed_fees_in_cents, 0);
+    assert_eq!(
+        settlement.subtotal_in_cents(),
+        ticket_item.unit_price_in_cents * 2 + settlement.client_fees_in_cents
+    );
+
+    let domain_events = DomainEvent::find(
+        Tables::Settlements,
+        Some(settlement.id),
+        Some(DomainEventTypes::SettlementCreated),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+}
+
+#[test]
+fn create_with_validation_errors() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let event = project.create_event().finish();
+    let start_time = Utc::now().naive_utc();
+    let end_time = start_time - Duration::days(1);
+
+    let result = Settlement::create(
+        organization.id,
+        Some(event.id),
+        Some(start_time),
+        Some(end_time),
+        user.id,
+    )
+    .commit(connection);
+
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ErrorCode::ValidationError { errors } => {
+                assert_eq!(
+                    errors["start_time"][0].code,
+                    "start_date_must_be_before_end_date"
+                );
+                assert_eq!(errors["event_id"][0].code, "event_not_in_organization");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn create_for_period_with_mixed_currencies() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project
+        .create_organization()
+        .with_currency("USD")
+        .with_fee_schedule(&project.create_fee_schedule().finish(user.id))
+        .finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let event2 = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    let event2 = event2
+        .update(
+            EventEditableAttributes {
+                currency: Some("EUR".to_string()),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+    project
+        .create_order()
+        .for_event(&event)
+        .quantity(1)
+        .is_paid()
+        .finish();
+    project
+        .create_order()
+        .for_event(&event2)
+        .quantity(1)
+        .is_paid()
+        .finish();
+
+    let result = Settlement::create(organization.id, None, None, None, user.id).commit(connection);
+    match result {
+        Ok(_) => panic!("Expected business process error"),
+        Err(error) => assert_eq!(error.error_code, ErrorCode::BusinessProcessError),
+    }
+
+    // Each event can still be settled in its own currency
+    let settlement = Settlement::create(organization.id, Some(event2.id), None, None, user.id)
+        .commit(connection)
+        .unwrap();
+    assert_eq!(settlement.currency, "EUR");
+}
+
+#[test]
+fn find_for_organization() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let organization2 = project.create_organization().finish();
+    let settlement = Settlement::create(organization.id, None, None, None, user.id)
+        .commit(connection)
+        .unwrap();
+    Settlement::create(organization2.id, None, None, None, user.id)
+        .commit(connection)
+        .unwrap();
+
+    assert_eq!(
+        Settlement::find_for_organization(organization.id, connection).unwrap(),
+        vec![settlement]
+    );
+}
+
+#[test]
+fn for_display() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let settlement = Settlement::create(organization.id, None, None, None, user.id)
+        .commit(connection)
+        .unwrap();
+    let deposit = SettlementAdjustment::create(
+        settlement.id,
+        SettlementAdjustmentTypes::Deposit,
+        5000,
+        None,
+        user.id,
+    )
+    .commit(connection)
+    .unwrap();
+    let chargeback = SettlementAdjustment::create(
+        settlement.id,
+        SettlementAdjustmentTypes::Chargeback,
+        -1500,
+        Some("Disputed order".to_string()),
+        user.id,
+    )
+    .commit(connection)
+    .unwrap();
+
+    let display_settlement = settlement.for_display(connection).unwrap();
+    assert_eq!(display_settlement.adjustments, vec![deposit, chargeback]);
+    assert_eq!(display_settlement.adjustments_in_cents, 3500);
+    assert_eq!(
+        display_settlement.total_in_cents,
+        settlement.subtotal_in_cents() + 3500
+    );
+}
+
+#[test]
+fn approve_and_mark_paid() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project.create_organization().finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .finish();
+    let settlement = Settlement::create(organization.id, Some(event.id), None, None, user.id)
+        .commit(connection)
+        .unwrap();
+    SettlementAdjustment::create(
+        settlement.id,
+        SettlementAdjustmentTypes::Deposit,
+        5000,
+        None,
+        user.id,
+    )
+    .commit(connection)
+    .unwrap();
+
+    // Settlements must be approved before being paid
+    let result = settlement.clone().mark_paid(user.id, connection);
+    match result {
+        Ok(_) => panic!("Expected business process error"),
+        Err(error) => assert_eq!(error.error_code, ErrorCode::BusinessProcessError),
+    }
+
+    let settlement = settlement.approve(user.id, connection).unwrap();
+    assert_eq!(settlement.status, SettlementStatus::Approved);
+    assert_eq!(settlement.approved_by_user_id, Some(user.id));
+    assert!(settlement.approved_at.is_some());
+
+    // Approved settlements can no longer be adjusted
+    let result = SettlementAdjustment::create(
+        settlement.id,
+        SettlementAdjustmentTypes::Expense,
+        -100,
+        None,
+        user.id,
+    )
+    .commit(connection);
+    match result {
+        Ok(_) => panic!("Expected business process error"),
+        Err(error) => assert_eq!(error.error_code, ErrorCode::BusinessProcessError),
+    }
+
+    let total_in_cents = settlement.total_in_cents(connection).unwrap();
+    let settlement = settlement.mark_paid(user.id, connection).unwrap();
+    assert_eq!(settlement.status, SettlementStatus::Paid);
+    assert_eq!(settlement.paid_by_user_id, Some(user.id));
+    assert!(settlement.paid_at.is_some());
+
+    let event = Event::find(event.id, connection).unwrap();
+    assert_eq!(event.settlement_amount_in_cents, Some(total_in_cents));
+
+    for event_type in vec![
+        DomainEventTypes::SettlementApproved,
+        DomainEventTypes::SettlementPaid,
+    ] {
+        let domain_events = DomainEvent::find(
+            Tables::Settlements,
+            Some(settlement.id),
+            Some(event_type),
+            connection,
+        )
+        .unwrap();
+        assert_eq!(domain_events.len(), 1);
+    }
+}
+
+#[test]
+fn create_with_split_partners() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let organization = project
+        .create_organization()
+        .with_fee_schedule(&project.create_fee_schedule().finish(user.id))
+        .finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_tickets()
+        .with_ticket_pricing()
+        .finish();
+    project
+        .create_order()
+        .for_event(&event)
+        .quantity(2)
+        .is_paid()
+        .finish();
+    let flat_partner = project.create_organization().finish();
+    let percentage_partner = project.create_organization().finish();
+    EventSplitPartner::create(event.id, flat_partner.id, SplitTypes::Flat, 100, 0)
+        .commit(connection)
+        .unwrap();
+    EventSplitPartner::create(
+        event.id,
+        percentage_partner.id,
+        SplitTypes::Percentage,
+        5_000,
+        5_000,
+    )
+    .commit(connection)
+    .unwrap();
+
+    let settlement = Settlement::create(organization.id, Some(event.id), None, None, user.id)
+        .commit(connection)
+        .unwrap();
+    let partner_shares = settlement.partner_shares(connection).unwrap();
+    assert_eq!(partner_shares.len(), 2);
+    let flat_share = partner_shares
+        .iter()
+        .find(|s| s.organization_id == flat_partner.id)
+        .unwrap();
+    assert_eq!(flat_share.amount_in_cents(), 100);
+    let percentage_share = partner_shares
+        .iter()
+        .find(|s| s.organization_id == percentage_partner.id)
+        .unwrap();
+    assert_eq!(
+        percentage_share.ticket_sales_in_cents,
+        settlement.ticket_sales_in_cents / 2
+    );
+    assert_eq!(
+        percentage_share.fees_in_cents,
+        (settlement.client_fees_in_cents - settlement.absorbed_fees_in_cents) / 2
+    );
+
+    let display_settlement = settlement.for_display(connection).unwrap();
+    assert_eq!(
+        display_settlement.partner_shares_in_cents,
+        100 + percentage_share.amount_in_cents()
+    );
+    assert_eq!(
+        display_settlement.total_in_cents,
+        settlement.subtotal_in_cents() - display_settlement.partner_shares_in_cents
+    );
+
+    // Flat shares are only deducted from the first settlement of the event
+    let settlement2 = Settlement::create(organization.id, Some(event.id), None, None, user.id)
+        .commit(connection)
+        .unwrap();
+    let partner_shares = settlement2.partner_shares(connection).unwrap();
+    assert_eq!(partner_shares.len(), 1);
+    assert_eq!(partner_shares[0].organization_id, percentage_partner.id);
+}
@@ -0,0 +1,175 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::utils::errors::ErrorCode;
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let region = project.create_region().finish();
+    let tax_rule = TaxRule::create("Sales Tax", Some(region.id), None, 825, false)
+        .commit(connection)
+        .unwrap();
+
+    assert_eq!(tax_rule.name, "Sales Tax");
+    assert_eq!(tax_rule.region_id, Some(region.id));
+    assert_eq!(tax_rule.rate_in_basis_points, 825);
+    assert!(!tax_rule.inclusive);
+    assert!(tax_rule.applies_to_tickets);
+    assert!(!tax_rule.applies_to_per_unit_fees);
+    assert!(!tax_rule.applies_to_event_fees);
+}
+
+#[test]
+fn create_with_validation_errors() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let result = TaxRule::create("Sales Tax", None, None, -1, false).commit(connection);
+
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ErrorCode::ValidationError { errors } => {
+                assert_eq!(
+                    errors["rate_in_basis_points"][0].code,
+                    "rate_must_not_be_negative"
+                );
+                assert_eq!(errors["region_id"][0].code, "region_or_venue_required");
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+}
+
+#[test]
+fn update() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let region = project.create_region().finish();
+    let tax_rule = TaxRule::create("Sales Tax", Some(region.id), None, 825, false)
+        .commit(connection)
+        .unwrap();
+
+    let parameters = TaxRuleEditableAttributes {
+        rate_in_basis_points: Some(1500),
+        inclusive: Some(true),
+        applies_to_per_unit_fees: Some(true),
+        ..Default::default()
+    };
+    let tax_rule = tax_rule.update(parameters, connection).unwrap();
+    assert_eq!(tax_rule.rate_in_basis_points, 1500);
+    assert!(tax_rule.inclusive);
+    assert!(tax_rule.applies_to_per_unit_fees);
+}
+
+#[test]
+fn find_for_event() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let region = project.create_region().finish();
+    let venue = project.create_venue().with_region(&region).finish();
+    let venue2 = project.create_venue().with_region(&region).finish();
+    let event = project.create_event().with_venue(&venue).finish();
+    let event2 = project.create_event().with_venue(&venue2).finish();
+    let event3 = project.create_event().finish();
+
+    let region_tax_rule = TaxRule::create("State Tax", Some(region.id), None, 600, false)
+        .commit(connection)
+        .unwrap();
+    let venue_tax_rule = TaxRule::create("City Tax", None, Some(venue.id), 900, false)
+        .commit(connection)
+        .unwrap();
+
+    // Venue rules take precedence over region rules
+    assert_eq!(
+        TaxRule::find_for_event(&event, connection).unwrap(),
+        vec![venue_tax_rule]
+    );
+    assert_eq!(
+        TaxRule::find_for_event(&event2, connection).unwrap(),
+        vec![region_tax_rule]
+    );
+    assert!(TaxRule::find_for_event(&event3, connection)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn destroy() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let region = project.create_region().finish();
+    let venue = project.create_venue().with_region(&region).finish();
+    let event = project.create_event().with_venue(&venue).finish();
+    let region_tax_rule = TaxRule::create("State Tax", Some(region.id), None, 600, false)
+        .commit(connection)
+        .unwrap();
+    let venue_tax_rule = TaxRule::create("City Tax", None, Some(venue.id), 900, false)
+        .commit(connection)
+        .unwrap();
+
+    let deleted_tax_rule = venue_tax_rule.destroy(connection).unwrap();
+    assert!(deleted_tax_rule.is_deleted());
+    // Kept for the orders it was applied to
+    assert_eq!(
+        TaxRule::find(venue_tax_rule.id, connection).unwrap(),
+        deleted_tax_rule
+    );
+    assert_eq!(
+        TaxRule::all(connection).unwrap(),
+        vec![region_tax_rule.clone()]
+    );
+    // The region rules apply once the venue has no rules left
+    assert_eq!(
+        TaxRule::find_for_event(&event, connection).unwrap(),
+        vec![region_tax_rule]
+    );
+
+    let result = deleted_tax_rule.update(
+        TaxRuleEditableAttributes {
+            rate_in_basis_points: Some(1000),
+            ..Default::default()
+        },
+        connection,
+    );
+    assert_eq!(
+        result.unwrap_err().error_code,
+        ErrorCode::BusinessProcessError
+    );
+}
+
+#[test]
+fn applies_to() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let region = project.create_region().finish();
+    let tax_rule = NewTaxRule {
+        applies_to_event_fees: true,
+        ..TaxRule::create("VAT", Some(region.id), None, 1500, true)
+    }
+    .commit(connection)
+    .unwrap();
+
+    assert!(tax_rule.applies_to(OrderItemTypes::Tickets));
+    assert!(!tax_rule.applies_to(OrderItemTypes::PerUnitFees));
+    assert!(tax_rule.applies_to(OrderItemTypes::EventFees));
+    assert!(!tax_rule.applies_to(OrderItemTypes::Tax));
+}
+
+#[test]
+fn calculate_tax() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let region = project.create_region().finish();
+    let exclusive_tax_rule = TaxRule::create("Sales Tax", Some(region.id), None, 825, false)
+        .commit(connection)
+        .unwrap();
+    let inclusive_tax_rule = TaxRule::create("VAT", Some(region.id), None, 1500, true)
+        .commit(connection)
+        .unwrap();
+
+    assert_eq!(exclusive_tax_rule.calculate_tax(1000), 83);
+    assert_eq!(exclusive_tax_rule.calculate_tax(0), 0);
+    // 1150 includes 150 of tax at 15%
+    assert_eq!(inclusive_tax_rule.calculate_tax(1150), 150);
+}
@@ -64,7 +64,7 @@ fn find_for_user_for_display() {
 
     let total = cart2.calculate_total(connection).unwrap();
     cart2
-        .add_external_payment(Some("test".to_string()), user.id, total, connection)
+        .add_external_payment(Some("test".to_string()), user.id, total, "USD", connection)
         .unwrap();
 
     let found_tickets =
@@ -576,7 +576,7 @@ fn find_for_user() {
     .unwrap();
 
     let total = cart.calculate_total(connection).unwrap();
-    cart.add_external_payment(Some("test".to_string()), user.id, total, connection)
+    cart.add_external_payment(Some("test".to_string()), user.id, total, "USD", connection)
         .unwrap();
 
     let tickets = TicketInstance::find_for_user(user.id, connection).unwrap();
@@ -967,7 +967,7 @@ fn authorize_ticket_transfer() {
     .unwrap();
     let total = cart.calculate_total(connection).unwrap();
 
-    cart.add_external_payment(Some("test".to_string()), user.id, total, connection)
+    cart.add_external_payment(Some("test".to_string()), user.id, total, "USD", connection)
         .unwrap();
 
     let tickets = TicketInstance::find_for_user(user.id, connection).unwrap();
@@ -1029,7 +1029,7 @@ fn receive_ticket_transfer() {
     .unwrap();
     let total = cart.calculate_total(connection).unwrap();
 
-    cart.add_external_payment(Some("test".to_string()), user.id, total, connection)
+    cart.add_external_payment(Some("test".to_string()), user.id, total, "USD", connection)
         .unwrap();
     let tickets = TicketInstance::find_for_user(user.id, connection).unwrap();
     let ticket_ids: Vec<Uuid> = tickets.iter().map(|t| t.id).collect();
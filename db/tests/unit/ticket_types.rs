@@ -115,7 +115,7 @@ fn valid_unsold_ticket_count() {
     );
 
     let total = cart.calculate_total(connection).unwrap();
-    cart.add_external_payment(Some("test".to_string()), user.id, total, connection)
+    cart.add_external_payment(Some("test".to_string()), user.id, total, "USD", connection)
         .unwrap();
 
     // 50 paid
@@ -638,6 +638,44 @@ fn update() {
     assert_eq!(updated_ticket_type.end_date, update_end_date);
 }
 
+#[test]
+fn effective_fee_mode() {
+    let db = TestProject::new();
+    let connection = db.get_connection();
+    let event = db.create_event().with_tickets().finish();
+    let ticket_type = &event.ticket_types(true, None, connection).unwrap()[0];
+    assert_eq!(ticket_type.fee_mode, None);
+    assert_eq!(
+        ticket_type.effective_fee_mode(connection).unwrap(),
+        FeeModes::Standard
+    );
+
+    // Falls back to the event fee mode
+    event
+        .update(
+            EventEditableAttributes {
+                fee_mode: Some(FeeModes::AllIn),
+                ..Default::default()
+            },
+            connection,
+        )
+        .unwrap();
+    assert_eq!(
+        ticket_type.effective_fee_mode(connection).unwrap(),
+        FeeModes::AllIn
+    );
+
+    // Ticket type overrides the event fee mode
+    let ticket_type = ticket_type
+        .update_fee_mode(Some(FeeModes::Absorbed), connection)
+        .unwrap();
+    assert_eq!(ticket_type.fee_mode, Some(FeeModes::Absorbed));
+    assert_eq!(
+        ticket_type.effective_fee_mode(connection).unwrap(),
+        FeeModes::Absorbed
+    );
+}
+
 #[test]
 fn cancel() {
     let db = TestProject::new();
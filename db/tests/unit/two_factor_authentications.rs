@@ -0,0 +1,161 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::*;
+use bigneon_db::utils::errors::ErrorCode;
+use bigneon_db::utils::totp;
+use chrono::prelude::*;
+
+const ENCRYPTION_KEY: &str = "encryption_key";
+
+fn code(secret: &str, step_offset: i64) -> String {
+    let secret = totp::base32_decode(secret).unwrap();
+    totp::code_at(
+        &secret,
+        totp::time_step(Utc::now().naive_utc().timestamp()) + step_offset,
+    )
+}
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+
+    let (two_factor_authentication, secret) =
+        TwoFactorAuthentication::create(user.id, ENCRYPTION_KEY, connection).unwrap();
+    assert_eq!(two_factor_authentication.user_id, user.id);
+    assert_ne!(two_factor_authentication.secret, secret);
+    assert!(totp::base32_decode(&secret).is_some());
+    assert!(!two_factor_authentication.is_enabled());
+    assert!(
+        TwoFactorAuthentication::find_enabled_for_user(user.id, connection)
+            .unwrap()
+            .is_none()
+    );
+
+    // Starting again replaces an enrolment that was never confirmed
+    let (new_two_factor_authentication, new_secret) =
+        TwoFactorAuthentication::create(user.id, ENCRYPTION_KEY, connection).unwrap();
+    assert_ne!(new_secret, secret);
+    assert_eq!(
+        TwoFactorAuthentication::find_for_user(user.id, connection).unwrap(),
+        Some(new_two_factor_authentication)
+    );
+}
+
+#[test]
+fn enable() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let (two_factor_authentication, secret) =
+        TwoFactorAuthentication::create(user.id, ENCRYPTION_KEY, connection).unwrap();
+
+    let result = two_factor_authentication.enable("000000x", ENCRYPTION_KEY, connection);
+    match result {
+        Ok(_) => panic!("Expected validation error"),
+        Err(error) => match &error.error_code {
+            ErrorCode::ValidationError { errors } => {
+                assert!(errors.contains_key("code"));
+            }
+            _ => panic!("Expected validation error"),
+        },
+    }
+
+    let recovery_codes = two_factor_authentication
+        .enable(&code(&secret, 0), ENCRYPTION_KEY, connection)
+        .unwrap();
+    assert_eq!(recovery_codes.len(), RECOVERY_CODE_COUNT);
+    let two_factor_authentication =
+        TwoFactorAuthentication::find_enabled_for_user(user.id, connection)
+            .unwrap()
+            .unwrap();
+    assert!(two_factor_authentication.is_enabled());
+    assert_eq!(
+        two_factor_authentication.recovery_code_hashes.len(),
+        RECOVERY_CODE_COUNT
+    );
+    assert!(!two_factor_authentication
+        .recovery_code_hashes
+        .contains(&recovery_codes[0]));
+
+    // Enrolment can not be restarted once enabled
+    assert!(TwoFactorAuthentication::create(user.id, ENCRYPTION_KEY, connection).is_err());
+}
+
+#[test]
+fn verify() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let (two_factor_authentication, secret) =
+        TwoFactorAuthentication::create(user.id, ENCRYPTION_KEY, connection).unwrap();
+    two_factor_authentication
+        .enable(&code(&secret, -1), ENCRYPTION_KEY, connection)
+        .unwrap();
+
+    let two_factor_authentication = TwoFactorAuthentication::find_for_user(user.id, connection)
+        .unwrap()
+        .unwrap();
+    assert!(!two_factor_authentication
+        .verify("123", ENCRYPTION_KEY, connection)
+        .unwrap());
+    // A code can not be replayed
+    assert!(!two_factor_authentication
+        .verify(&code(&secret, -1), ENCRYPTION_KEY, connection)
+        .unwrap());
+    assert!(two_factor_authentication
+        .verify(&code(&secret, 0), ENCRYPTION_KEY, connection)
+        .unwrap());
+    assert!(!two_factor_authentication
+        .verify(&code(&secret, 0), ENCRYPTION_KEY, connection)
+        .unwrap());
+}
+
+#[test]
+fn verify_recovery_code() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let (two_factor_authentication, secret) =
+        TwoFactorAuthentication::create(user.id, ENCRYPTION_KEY, connection).unwrap();
+    let recovery_codes = two_factor_authentication
+        .enable(&code(&secret, 0), ENCRYPTION_KEY, connection)
+        .unwrap();
+
+    let two_factor_authentication = TwoFactorAuthentication::find_for_user(user.id, connection)
+        .unwrap()
+        .unwrap();
+    assert!(two_factor_authentication
+        .verify(
+            &recovery_codes[0].to_uppercase(),
+            ENCRYPTION_KEY,
+            connection
+        )
+        .unwrap());
+    // Each recovery code only works once
+    assert!(!two_factor_authentication
+        .verify(&recovery_codes[0], ENCRYPTION_KEY, connection)
+        .unwrap());
+
+    let two_factor_authentication = TwoFactorAuthentication::find_for_user(user.id, connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        two_factor_authentication.recovery_code_hashes.len(),
+        RECOVERY_CODE_COUNT - 1
+    );
+}
+
+#[test]
+fn destroy() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let (two_factor_authentication, _) =
+        TwoFactorAuthentication::create(user.id, ENCRYPTION_KEY, connection).unwrap();
+
+    assert_eq!(two_factor_authentication.destroy(connection).unwrap(), 1);
+    assert!(TwoFactorAuthentication::find_for_user(user.id, connection)
+        .unwrap()
+        .is_none());
+}
@@ -0,0 +1,186 @@
+use bigneon_db::dev::TestProject;
+use bigneon_db::models::concerns::users::password_resetable::*;
+use bigneon_db::models::*;
+
+#[test]
+fn create() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+
+    let session = UserSession::create(
+        user.id,
+        Some("Mozilla/5.0".to_string()),
+        Some("127.0.0.1".to_string()),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(session.user_id, user.id);
+    assert_eq!(session.user_agent, Some("Mozilla/5.0".to_string()));
+    assert!(!session.is_revoked());
+    assert_eq!(UserSession::find(session.id, connection).unwrap(), session);
+}
+
+#[test]
+fn find_active_for_user() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let other_user = project.create_user().finish();
+    let session = UserSession::create(user.id, None, None, connection).unwrap();
+    let session2 = UserSession::create(user.id, None, None, connection).unwrap();
+    UserSession::create(other_user.id, None, None, connection).unwrap();
+
+    session2.revoke(connection).unwrap();
+    assert_eq!(
+        UserSession::find_active_for_user(user.id, connection).unwrap(),
+        vec![session]
+    );
+}
+
+#[test]
+fn create_from_legacy_token() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+
+    let session = UserSession::create_from_legacy_token(
+        user.id,
+        "legacy.refresh.token",
+        None,
+        None,
+        connection,
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(session.user_id, user.id);
+    assert!(!session.is_revoked());
+    let other_session = UserSession::create_from_legacy_token(
+        user.id,
+        "other.refresh.token",
+        None,
+        None,
+        connection,
+    )
+    .unwrap()
+    .unwrap();
+    assert_ne!(other_session.id, session.id);
+
+    // Using the token again revokes the session it started
+    assert!(UserSession::create_from_legacy_token(
+        user.id,
+        "legacy.refresh.token",
+        None,
+        None,
+        connection
+    )
+    .unwrap()
+    .is_none());
+    assert!(UserSession::find(session.id, connection)
+        .unwrap()
+        .is_revoked());
+    assert!(!UserSession::find(other_session.id, connection)
+        .unwrap()
+        .is_revoked());
+}
+
+#[test]
+fn rotate() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let session = UserSession::create(user.id, None, None, connection).unwrap();
+
+    let rotated = session
+        .rotate(session.refresh_token_id, connection)
+        .unwrap()
+        .unwrap();
+    assert_eq!(rotated.id, session.id);
+    assert_ne!(rotated.refresh_token_id, session.refresh_token_id);
+    assert!(!rotated.is_revoked());
+
+    let rotated_again = rotated
+        .rotate(rotated.refresh_token_id, connection)
+        .unwrap()
+        .unwrap();
+    assert_ne!(rotated_again.refresh_token_id, rotated.refresh_token_id);
+}
+
+#[test]
+fn rotate_reused_token() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let session = UserSession::create(user.id, None, None, connection).unwrap();
+    let rotated = session
+        .rotate(session.refresh_token_id, connection)
+        .unwrap()
+        .unwrap();
+
+    // The first token has already been exchanged
+    assert!(rotated
+        .rotate(session.refresh_token_id, connection)
+        .unwrap()
+        .is_none());
+    let session = UserSession::find(session.id, connection).unwrap();
+    assert!(session.is_revoked());
+    assert!(session
+        .rotate(rotated.refresh_token_id, connection)
+        .unwrap()
+        .is_none());
+
+    // A stale copy of the session can not be rotated twice
+    let session = UserSession::create(user.id, None, None, connection).unwrap();
+    session
+        .rotate(session.refresh_token_id, connection)
+        .unwrap()
+        .unwrap();
+    assert!(session
+        .rotate(session.refresh_token_id, connection)
+        .unwrap()
+        .is_none());
+    assert!(UserSession::find(session.id, connection)
+        .unwrap()
+        .is_revoked());
+}
+
+#[test]
+fn revoke_all_for_user() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let other_user = project.create_user().finish();
+    UserSession::create(user.id, None, None, connection).unwrap();
+    UserSession::create(user.id, None, None, connection).unwrap();
+    let other_session = UserSession::create(other_user.id, None, None, connection).unwrap();
+
+    assert_eq!(
+        UserSession::revoke_all_for_user(user.id, connection).unwrap(),
+        2
+    );
+    assert!(UserSession::find_active_for_user(user.id, connection)
+        .unwrap()
+        .is_empty());
+    assert!(!UserSession::find(other_session.id, connection)
+        .unwrap()
+        .is_revoked());
+}
+
+#[test]
+fn password_reset_revokes_sessions() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let session = UserSession::create(user.id, None, None, connection).unwrap();
+
+    let user = user.create_password_reset_token(connection).unwrap();
+    User::consume_password_reset_token(
+        &user.password_reset_token.unwrap(),
+        "newPassword",
+        connection,
+    )
+    .unwrap();
+    assert!(UserSession::find(session.id, connection)
+        .unwrap()
+        .is_revoked());
+}
@@ -1,6 +1,6 @@
 use bigneon_db::dev::TestProject;
 use bigneon_db::prelude::*;
-use bigneon_db::schema::orders;
+use bigneon_db::schema::{audit_logs, domain_actions, orders};
 use bigneon_db::utils::errors;
 use bigneon_db::utils::errors::ErrorCode;
 use chrono::{Duration, Utc};
@@ -191,7 +191,7 @@ fn get_profile_for_organization() {
 
     // Checkout which changes sales data
     assert_eq!(cart.calculate_total(connection).unwrap(), 1700);
-    cart.add_external_payment(Some("test".to_string()), user.id, 1700, connection)
+    cart.add_external_payment(Some("test".to_string()), user.id, 1700, "USD", connection)
         .unwrap();
     assert_eq!(cart.status, OrderStatus::Paid);
     assert_eq!(
@@ -227,7 +227,7 @@ fn get_profile_for_organization() {
     )
     .unwrap();
     assert_eq!(cart.calculate_total(connection).unwrap(), 170);
-    cart.add_external_payment(Some("test".to_string()), user.id, 170, connection)
+    cart.add_external_payment(Some("test".to_string()), user.id, 170, "USD", connection)
         .unwrap();
     assert_eq!(cart.status, OrderStatus::Paid);
     assert_eq!(
@@ -263,7 +263,7 @@ fn get_profile_for_organization() {
     )
     .unwrap();
     assert_eq!(cart.calculate_total(connection).unwrap(), 170);
-    cart.add_external_payment(Some("test".to_string()), user.id, 170, connection)
+    cart.add_external_payment(Some("test".to_string()), user.id, 170, "USD", connection)
         .unwrap();
     assert_eq!(cart.status, OrderStatus::Paid);
     assert_eq!(
@@ -331,7 +331,7 @@ fn get_history_for_organization() {
 
     // User checks out so has a paid order so history exists
     assert_eq!(cart.calculate_total(connection).unwrap(), 1700);
-    cart.add_external_payment(Some("test".to_string()), user.id, 1700, connection)
+    cart.add_external_payment(Some("test".to_string()), user.id, 1700, "USD", connection)
         .unwrap();
     assert_eq!(cart.status, OrderStatus::Paid);
 
@@ -378,7 +378,7 @@ fn get_history_for_organization() {
 
     assert_eq!(cart2.calculate_total(connection).unwrap(), 170);
     cart2
-        .add_external_payment(Some("test".to_string()), user.id, 170, connection)
+        .add_external_payment(Some("test".to_string()), user.id, 170, "USD", connection)
         .unwrap();
     assert_eq!(cart2.status, OrderStatus::Paid);
 
@@ -550,6 +550,57 @@ fn update() {
     assert_eq!(updated_user.email, Some(email.into()));
 }
 
+#[test]
+fn verify_email() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let email = user.email.clone().unwrap();
+    assert!(!user.is_email_verified());
+
+    assert!(user.verify_email("other@tari.com", connection).is_err());
+    let user = user
+        .verify_email(&email.to_uppercase(), connection)
+        .unwrap();
+    assert!(user.is_email_verified());
+
+    // Changing the phone keeps the email verified
+    let mut attributes: UserEditableAttributes = Default::default();
+    attributes.phone = Some(Some("555-555-1234".to_string()));
+    let user = user.update(&attributes, connection).unwrap();
+    assert!(user.is_email_verified());
+
+    // A new email has to be verified again
+    let mut attributes: UserEditableAttributes = Default::default();
+    attributes.email = Some(Some("new_email@tari.com".to_string()));
+    let user = user.update(&attributes, connection).unwrap();
+    assert!(!user.is_email_verified());
+}
+
+#[test]
+fn has_verified_contact_details() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    assert!(!user.has_verified_contact_details());
+
+    let user = user
+        .verify_email(&user.email.clone().unwrap(), connection)
+        .unwrap();
+    assert!(!user.has_verified_contact_details());
+
+    let user = user
+        .verify_phone(&user.phone.clone().unwrap(), connection)
+        .unwrap();
+    assert!(user.has_verified_contact_details());
+
+    // Removing the phone leaves only the verified email
+    let mut attributes: UserEditableAttributes = Default::default();
+    attributes.phone = Some(None);
+    let user = user.update(&attributes, connection).unwrap();
+    assert!(user.has_verified_contact_details());
+}
+
 #[test]
 fn new_user_validate() {
     let email = "abc";
@@ -891,6 +942,7 @@ fn get_global_scopes() {
             "event:scan",
             "event:view-guests",
             "event:write",
+            "exchange-rate:write",
             "hold:read",
             "hold:write",
             "order:make-external-payment",
@@ -907,6 +959,10 @@ fn get_global_scopes() {
             "org:write",
             "redeem:ticket",
             "region:write",
+            "settlement:approve",
+            "settlement:read",
+            "settlement:write",
+            "tax-rule:write",
             "ticket:admin",
             "ticket:read",
             "ticket:transfer",
@@ -930,3 +986,284 @@ fn add_role() {
     let user2 = User::find(user.id, project.get_connection()).unwrap();
     assert_eq!(user2.role, vec![Roles::User, Roles::Admin]);
 }
+
+#[test]
+fn record_failed_login() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+
+    assert!(!user
+        .record_failed_login(2, Duration::minutes(15), connection)
+        .unwrap());
+    let user = User::find(user.id, connection).unwrap();
+    assert_eq!(user.failed_login_attempts, 1);
+    assert!(!user.is_locked());
+
+    assert!(user
+        .record_failed_login(2, Duration::minutes(15), connection)
+        .unwrap());
+    let user = User::find(user.id, connection).unwrap();
+    assert_eq!(user.failed_login_attempts, 0);
+    assert!(user.is_locked());
+
+    user.clear_failed_logins(connection).unwrap();
+    let user = User::find(user.id, connection).unwrap();
+    assert!(!user.is_locked());
+    assert!(user.locked_until.is_none());
+}
+
+#[test]
+fn export_data() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let event = project.create_event().with_ticket_pricing().finish();
+    let order = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .is_paid()
+        .finish();
+    user.add_external_login(
+        "external_id".to_string(),
+        FACEBOOK_SITE.to_string(),
+        "access_token".to_string(),
+        connection,
+    )
+    .unwrap();
+    EventInterest::create(event.id, user.id)
+        .commit(connection)
+        .unwrap();
+    EventUser::create(event.id, user.id, Roles::Promoter, connection).unwrap();
+    let session = UserSession::create(
+        user.id,
+        Some("Mozilla/5.0".to_string()),
+        Some("127.0.0.1".to_string()),
+        connection,
+    )
+    .unwrap();
+
+    let data = user.export_data(connection).unwrap();
+    assert_eq!(data["profile"]["id"], json!(user.id));
+    assert_eq!(data["profile"]["email"], json!(user.email));
+    assert!(data["profile"].get("hashed_pw").is_none());
+    assert_eq!(data["orders"].as_array().unwrap().len(), 1);
+    assert_eq!(data["orders"][0]["id"], json!(order.id));
+    assert!(!data["tickets"].as_array().unwrap().is_empty());
+    assert_eq!(data["tickets"][0]["event_id"], json!(event.id));
+    assert_eq!(data["external_logins"][0]["site"], json!(FACEBOOK_SITE));
+    assert!(data["external_logins"][0].get("access_token").is_none());
+    assert_eq!(data["event_interest"][0]["event_id"], json!(event.id));
+    assert!(data["push_notification_tokens"]
+        .as_array()
+        .unwrap()
+        .is_empty());
+    assert_eq!(data["event_roles"][0]["event_id"], json!(event.id));
+    assert_eq!(data["event_roles"][0]["role"], json!(Roles::Promoter));
+    assert_eq!(data["sessions"][0]["id"], json!(session.id));
+    assert_eq!(data["sessions"][0]["ip_address"], json!("127.0.0.1"));
+    assert!(data["sessions"][0].get("refresh_token_id").is_none());
+    assert!(data["two_factor_authentication"].is_null());
+    assert!(data["audit_logs"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn erase() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    let admin = project.create_user().finish();
+    let organization = project
+        .create_organization()
+        .with_member(&user, Roles::OrgMember)
+        .finish();
+    let event = project
+        .create_event()
+        .with_organization(&organization)
+        .with_ticket_pricing()
+        .finish();
+    let order = project
+        .create_order()
+        .for_event(&event)
+        .for_user(&user)
+        .is_paid()
+        .finish();
+    user.add_external_login(
+        "external_id".to_string(),
+        FACEBOOK_SITE.to_string(),
+        "access_token".to_string(),
+        connection,
+    )
+    .unwrap();
+    PushNotificationToken::create(user.id, "ios".to_string(), "token".to_string())
+        .commit(connection)
+        .unwrap();
+    CommunicationPreference::set(
+        user.id,
+        None,
+        CommunicationCategory::Marketing,
+        CommunicationChannelType::Email,
+        false,
+        "Preferences",
+        Some("127.0.0.1".to_string()),
+        connection,
+    )
+    .unwrap();
+    EventUser::create(event.id, user.id, Roles::Promoter, connection).unwrap();
+    TwoFactorAuthentication::create(user.id, "encryption_key", connection).unwrap();
+    PhoneVerification::create(&user, connection).unwrap();
+    let session = UserSession::create(
+        user.id,
+        Some("Mozilla/5.0".to_string()),
+        Some("127.0.0.1".to_string()),
+        connection,
+    )
+    .unwrap();
+    let audit_log = AuditLog::create(
+        Some(user.id),
+        Some(Scopes::HoldWrite),
+        Tables::Events,
+        event.id,
+        Some(organization.id),
+        Some(event.id),
+    )
+    .created(&event)
+    .unwrap()
+    .commit(connection)
+    .unwrap();
+    AuditLog::create(Some(admin.id), None, Tables::Users, user.id, None, None)
+        .created(&user)
+        .unwrap()
+        .commit(connection)
+        .unwrap();
+    let email = user.email.clone().unwrap();
+    let phone = user.phone.clone().unwrap();
+    DomainAction::create(
+        None,
+        DomainActionTypes::Communication,
+        Some(CommunicationChannelType::Sms),
+        json!({
+            "destinations": [email.to_uppercase(), phone],
+            "body": format!("Your tickets were sent to {}", email),
+        }),
+        Some(Tables::Users.table_name()),
+        Some(user.id),
+        Utc::now().naive_utc(),
+        Utc::now().naive_utc() + Duration::days(1),
+        3,
+    )
+    .commit(connection)
+    .unwrap();
+
+    let erased_user = user.clone().erase(Some(admin.id), connection).unwrap();
+    assert_eq!(erased_user.id, user.id);
+    assert_eq!(erased_user.first_name, None);
+    assert_eq!(erased_user.last_name, None);
+    assert_eq!(erased_user.email, None);
+    assert_eq!(erased_user.phone, None);
+    assert!(!erased_user.active);
+    assert_ne!(erased_user.hashed_pw, user.hashed_pw);
+    assert!(!erased_user.check_password("examplePassword"));
+
+    assert!(erased_user
+        .find_external_login(FACEBOOK_SITE, connection)
+        .unwrap()
+        .is_none());
+    assert!(PushNotificationToken::find_by_user_id(user.id, connection)
+        .unwrap()
+        .is_empty());
+    assert!(erased_user.organizations(connection).unwrap().is_empty());
+    assert!(CommunicationPreference::find_for_user(user.id, connection)
+        .unwrap()
+        .is_empty());
+    let consents = CommunicationConsent::find_for_user(user.id, connection).unwrap();
+    assert_eq!(consents.len(), 1);
+    assert_eq!(consents[0].ip_address, None);
+    assert!(
+        EventUser::find_by_event_id_user_id(event.id, user.id, connection)
+            .optional()
+            .unwrap()
+            .is_none()
+    );
+    assert!(TwoFactorAuthentication::find_for_user(user.id, connection)
+        .unwrap()
+        .is_none());
+    assert!(PhoneVerification::find_for_user(user.id, connection)
+        .unwrap()
+        .is_none());
+    assert_eq!(erased_user.login_token, None);
+    assert_eq!(erased_user.phone_verified_at, None);
+    let session = UserSession::find(session.id, connection).unwrap();
+    assert!(session.is_revoked());
+    assert_eq!(session.ip_address, None);
+    assert_eq!(session.user_agent, None);
+    let audit_log_user_id: Option<Uuid> = audit_logs::table
+        .find(audit_log.id)
+        .select(audit_logs::user_id)
+        .first(connection)
+        .unwrap();
+    assert_eq!(audit_log_user_id, None);
+
+    // No personal data is left in audit logs or communications
+    let changes: Vec<serde_json::Value> = audit_logs::table
+        .select(audit_logs::changes)
+        .load(connection)
+        .unwrap();
+    let payloads: Vec<serde_json::Value> = domain_actions::table
+        .select(domain_actions::payload)
+        .load(connection)
+        .unwrap();
+    for value in changes.iter().chain(payloads.iter()) {
+        let value = value.to_string().to_lowercase();
+        assert!(!value.contains(&email.to_lowercase()));
+        assert!(!value.contains(&phone));
+    }
+    let redacted_payloads: Vec<&serde_json::Value> = payloads
+        .iter()
+        .filter(|p| p.to_string().contains("[redacted]"))
+        .collect();
+    assert_eq!(redacted_payloads.len(), 1);
+    assert_eq!(
+        redacted_payloads[0]["body"],
+        json!("Your tickets were sent to [redacted]")
+    );
+
+    // Financial records are kept
+    let order = Order::find(order.id, connection).unwrap();
+    assert_eq!(order.user_id, user.id);
+    assert_eq!(order.status, OrderStatus::Paid);
+
+    let domain_events = DomainEvent::find(
+        Tables::Users,
+        Some(user.id),
+        Some(DomainEventTypes::UserErased),
+        connection,
+    )
+    .unwrap();
+    assert_eq!(domain_events.len(), 1);
+    assert_eq!(domain_events[0].user_id, Some(admin.id));
+}
+
+#[test]
+fn erase_organization_owner() {
+    let project = TestProject::new();
+    let connection = project.get_connection();
+    let user = project.create_user().finish();
+    project
+        .create_organization()
+        .with_member(&user, Roles::OrgOwner)
+        .finish();
+
+    let result = user.erase(None, connection);
+    match result {
+        Ok(_) => panic!("Expected business process error"),
+        Err(error) => assert_eq!(
+            error.cause,
+            Some(
+                "User owns an organization, ownership must be transferred before the user can be erased"
+                    .to_string()
+            )
+        ),
+    }
+}